@@ -4,6 +4,7 @@ use crate::bytes::default_controller::{self, NativeAllocationController};
 use alloc::boxed::Box;
 use alloc::vec::Vec;
 use core::alloc::LayoutError;
+use core::any::Any;
 use core::mem::MaybeUninit;
 use core::ops::{Deref, DerefMut};
 use core::ptr::NonNull;
@@ -125,6 +126,12 @@ pub trait AllocationController {
     fn try_detach(&mut self) -> Option<NonNull<u8>> {
         None
     }
+
+    /// Returns the controller as [`Any`], so the runtime that created the allocation can
+    /// recognize it and use the memory behind it directly.
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        None
+    }
 }
 
 /// Errors that may occur during memory allocation operations.
@@ -235,6 +242,13 @@ impl Bytes {
     pub fn property(&self) -> AllocationProperty {
         self.controller.property()
     }
+
+    /// Retrieves the allocation controller if it has the type `C`.
+    ///
+    /// Only controllers implementing [`AllocationController::as_any_mut`] can be retrieved.
+    pub fn controller_mut<C: AllocationController + 'static>(&mut self) -> Option<&mut C> {
+        self.controller.as_any_mut()?.downcast_mut()
+    }
     /// Creates the type from its raw parts.
     ///
     /// # Safety
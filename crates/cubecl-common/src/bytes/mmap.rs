@@ -0,0 +1,319 @@
+//! Memory-mapped file allocation controller.
+//!
+//! Mapping a file instead of reading it lets the OS page the data in on demand from the page
+//! cache, so large files (e.g. model weights) can be uploaded to a device without first being
+//! copied to the heap.
+
+use super::{
+    AllocationController, AllocationProperty, Bytes, SplitError,
+    default_controller::{MAX_ALIGN, NativeAllocationController},
+};
+use alloc::boxed::Box;
+use core::{mem::MaybeUninit, ptr::NonNull};
+use memmap2::{Mmap, MmapMut, MmapOptions};
+use std::{fs::File, io, path::Path, sync::Arc};
+
+/// How a file is mapped in memory, see [`Bytes::from_mmap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MmapMode {
+    /// The mapping is read-only. Mutable access copies the data to the heap first.
+    ReadOnly,
+    /// The mapping is private. Pages are copied by the OS when they're written, and changes are
+    /// never written back to the file.
+    CopyOnWrite,
+}
+
+/// The mapping shared by all views created by splitting the same allocation.
+struct Mapping {
+    file: Arc<File>,
+    /// The file offset of the start of the mapping.
+    offset: u64,
+    _map: MappingKind,
+}
+
+enum MappingKind {
+    ReadOnly(#[allow(unused)] Mmap),
+    CopyOnWrite(#[allow(unused)] MmapMut),
+}
+
+/// Allocation controller for a view into a memory-mapped file.
+///
+/// # Safety
+///
+/// Views created by [splitting](AllocationController::split) the same allocation point to
+/// disjoint ranges of the same mapping, so mutable access through one view never aliases another.
+/// A copy-on-write view is only [duplicated](AllocationController::duplicate) by mapping the file
+/// again, so the duplicate never sees the writes of the original.
+pub(crate) struct MmapAllocationController {
+    mapping: Arc<Mapping>,
+    mode: MmapMode,
+    /// Points to the start of this view in the mapping.
+    ptr: NonNull<u8>,
+    /// The offset of this view from the start of the mapping.
+    offset: usize,
+    len: usize,
+    /// The heap copy of a read-only view that was accessed mutably.
+    heap: Option<Box<dyn AllocationController>>,
+    /// Whether a copy-on-write view, or the view it was split from, was accessed mutably.
+    written: bool,
+}
+
+impl MmapAllocationController {
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while it's mapped, see [`Bytes::from_mmap`].
+    pub unsafe fn new(path: &Path, size: u64, offset: u64, mode: MmapMode) -> io::Result<Self> {
+        let file = Arc::new(File::open(path)?);
+        // SAFETY: Guaranteed by the caller.
+        unsafe { Self::map(file, size, offset, mode) }
+    }
+
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while it's mapped.
+    unsafe fn map(file: Arc<File>, size: u64, offset: u64, mode: MmapMode) -> io::Result<Self> {
+        let mut options = MmapOptions::new();
+        options.offset(offset).len(size as usize);
+
+        // SAFETY: Guaranteed by the caller.
+        let (map, ptr) = unsafe {
+            match mode {
+                MmapMode::ReadOnly => {
+                    let map = options.map(file.as_ref())?;
+                    let ptr = map.as_ptr() as *mut u8;
+                    (MappingKind::ReadOnly(map), ptr)
+                }
+                MmapMode::CopyOnWrite => {
+                    let mut map = options.map_copy(file.as_ref())?;
+                    let ptr = map.as_mut_ptr();
+                    (MappingKind::CopyOnWrite(map), ptr)
+                }
+            }
+        };
+
+        Ok(Self {
+            mapping: Arc::new(Mapping {
+                file,
+                offset,
+                _map: map,
+            }),
+            mode,
+            ptr: NonNull::new(ptr).unwrap_or(NonNull::dangling()),
+            offset: 0,
+            len: size as usize,
+            heap: None,
+            written: false,
+        })
+    }
+
+    /// A view into a range of the same mapping.
+    fn view(&self, offset: usize, len: usize) -> Self {
+        Self {
+            mapping: self.mapping.clone(),
+            mode: self.mode,
+            // SAFETY: The offset is in bounds of this view.
+            ptr: unsafe { self.ptr.add(offset) },
+            offset: self.offset + offset,
+            len,
+            heap: None,
+            written: self.written,
+        }
+    }
+}
+
+impl AllocationController for MmapAllocationController {
+    fn alloc_align(&self) -> usize {
+        if let Some(heap) = &self.heap {
+            return heap.alloc_align();
+        }
+        // Mappings are page-aligned, so the alignment of a view depends on where it was split.
+        let addr = self.ptr.as_ptr() as usize;
+        (1 << addr.trailing_zeros()).min(MAX_ALIGN)
+    }
+
+    fn property(&self) -> AllocationProperty {
+        AllocationProperty::File
+    }
+
+    fn memory(&self) -> &[MaybeUninit<u8>] {
+        if let Some(heap) = &self.heap {
+            return heap.memory();
+        }
+        // SAFETY: The view is in bounds of the mapping, which lives as long as `self`.
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr().cast(), self.len) }
+    }
+
+    unsafe fn memory_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        match self.mode {
+            MmapMode::ReadOnly => {
+                if self.heap.is_none() {
+                    let data = unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) };
+                    let controller = NativeAllocationController::alloc_with_data(data, MAX_ALIGN)
+                        .expect("Should allocate the copy of the mapped file");
+                    self.heap = Some(Box::new(controller));
+                }
+                unsafe { self.heap.as_mut().unwrap().memory_mut() }
+            }
+            MmapMode::CopyOnWrite => {
+                self.written = true;
+                // SAFETY: The mapping is private and writable, and no other view aliases this
+                // range.
+                unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr().cast(), self.len) }
+            }
+        }
+    }
+
+    fn split(
+        &mut self,
+        offset: usize,
+    ) -> Result<(Box<dyn AllocationController>, Box<dyn AllocationController>), SplitError> {
+        if self.heap.is_some() {
+            return Err(SplitError::Unsupported);
+        }
+        // Use `>` (not `>=`) to allow boundary splits where one side is empty.
+        if offset > self.len {
+            return Err(SplitError::InvalidOffset);
+        }
+
+        let left = self.view(0, offset);
+        let right = self.view(offset, self.len - offset);
+
+        Ok((Box::new(left), Box::new(right)))
+    }
+
+    fn duplicate(&self) -> Option<Box<dyn AllocationController>> {
+        match self.mode {
+            // Read-only views can share the mapping, since they never write to it.
+            MmapMode::ReadOnly if self.heap.is_none() => Some(Box::new(self.view(0, self.len))),
+            // A fresh private mapping doesn't see the writes of this one.
+            MmapMode::CopyOnWrite if !self.written => {
+                let offset = self.mapping.offset + self.offset as u64;
+                // SAFETY: The file is already mapped by this view, so the caller of
+                // `Bytes::from_mmap` guarantees it isn't modified.
+                let controller = unsafe {
+                    Self::map(
+                        self.mapping.file.clone(),
+                        self.len as u64,
+                        offset,
+                        self.mode,
+                    )
+                }
+                .ok()?;
+                Some(Box::new(controller))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Bytes {
+    /// Creates bytes by mapping the given range of a file in memory.
+    ///
+    /// Unlike [`Bytes::from_file`], the data isn't read eagerly to the heap. It's paged in from
+    /// the page cache when accessed, and [splitting](Bytes::split) the bytes doesn't copy it.
+    /// Splitting at page-aligned offsets keeps both sides page-aligned.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this process or any other, as long as the
+    /// returned bytes or any bytes split or cloned from them are alive. The mapped memory would
+    /// change or become inaccessible under the references handed out by [`Bytes`].
+    pub unsafe fn from_mmap<P: AsRef<Path>>(
+        file: P,
+        size: u64,
+        offset: u64,
+        mode: MmapMode,
+    ) -> io::Result<Self> {
+        // SAFETY: Guaranteed by the caller.
+        let controller =
+            unsafe { MmapAllocationController::new(file.as_ref(), size, offset, mode)? };
+
+        // SAFETY: The whole mapping is initialized.
+        Ok(unsafe { Bytes::from_controller(Box::new(controller), size as usize) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{io::Write, path::PathBuf, vec::Vec};
+    use tempfile::TempDir;
+
+    const PAGE_SIZE: usize = 4096;
+
+    #[test_log::test]
+    fn test_from_mmap() {
+        let (path, data, _dir) = with_data(PAGE_SIZE * 3);
+
+        for mode in [MmapMode::ReadOnly, MmapMode::CopyOnWrite] {
+            let bytes = mmap(&path, data.len(), 0, mode);
+            assert_eq!(&bytes[..], &data[..]);
+            assert_eq!(bytes.property(), AllocationProperty::File);
+
+            let offset = PAGE_SIZE + 10;
+            let bytes = mmap(&path, 100, offset, mode);
+            assert_eq!(&bytes[..], &data[offset..offset + 100]);
+        }
+    }
+
+    #[test_log::test]
+    fn test_split_mmap_page_aligned() {
+        let (path, data, _dir) = with_data(PAGE_SIZE * 3);
+
+        let bytes = mmap(&path, data.len(), 0, MmapMode::ReadOnly);
+        let (left, right) = bytes.split(PAGE_SIZE).unwrap();
+
+        assert_eq!(&left[..], &data[..PAGE_SIZE]);
+        assert_eq!(&right[..], &data[PAGE_SIZE..]);
+        assert_eq!(right.align(), MAX_ALIGN);
+        assert_eq!(right.property(), AllocationProperty::File);
+    }
+
+    #[test_log::test]
+    fn test_mmap_read_only_mutation() {
+        let (path, data, _dir) = with_data(PAGE_SIZE);
+
+        let bytes = mmap(&path, data.len(), 0, MmapMode::ReadOnly);
+        let mut bytes_mut = bytes.clone();
+        bytes_mut[0] = data[0].wrapping_add(1);
+
+        assert_eq!(&bytes[..], &data[..]);
+        assert_eq!(&bytes_mut[1..], &data[1..]);
+        assert_eq!(bytes_mut[0], data[0].wrapping_add(1));
+        assert_eq!(std::fs::read(&path).unwrap(), data);
+    }
+
+    #[test_log::test]
+    fn test_mmap_copy_on_write() {
+        let (path, data, _dir) = with_data(PAGE_SIZE * 2);
+
+        let bytes = mmap(&path, data.len(), 0, MmapMode::CopyOnWrite);
+        let (mut left, mut right) = bytes.split(PAGE_SIZE).unwrap();
+        let right_clone = right.clone();
+        left[0] = data[0].wrapping_add(1);
+        right[0] = data[PAGE_SIZE].wrapping_add(1);
+
+        assert_eq!(left[0], data[0].wrapping_add(1));
+        assert_eq!(right[0], data[PAGE_SIZE].wrapping_add(1));
+        assert_eq!(&right_clone[..], &data[PAGE_SIZE..]);
+        // A written view is copied when cloned.
+        assert_eq!(&right.clone()[..], &right[..]);
+        // Writes are never visible in the file.
+        assert_eq!(std::fs::read(&path).unwrap(), data);
+    }
+
+    fn mmap(path: &Path, size: usize, offset: usize, mode: MmapMode) -> Bytes {
+        // SAFETY: The test files aren't modified once written.
+        unsafe { Bytes::from_mmap(path, size as u64, offset as u64, mode) }.unwrap()
+    }
+
+    fn with_data(size: usize) -> (PathBuf, Vec<u8>, TempDir) {
+        let data = (0..size).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test");
+
+        let mut file = std::fs::File::create(&path).unwrap();
+        file.write_all(&data).unwrap();
+        (path, data, dir)
+    }
+}
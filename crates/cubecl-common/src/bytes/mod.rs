@@ -1,11 +1,15 @@
 pub(crate) mod default_controller;
 #[cfg(feature = "std")]
 pub(crate) mod file;
+#[cfg(feature = "mmap")]
+mod mmap;
 #[cfg(feature = "shared-bytes")]
 mod shared;
 
 mod base;
 
 pub use base::*;
+#[cfg(feature = "mmap")]
+pub use mmap::MmapMode;
 #[cfg(feature = "shared-bytes")]
 pub use shared::SharedBytesAllocationController;
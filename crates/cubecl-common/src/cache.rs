@@ -46,6 +46,8 @@ pub struct CacheOption {
     name: Option<String>,
     root: Option<PathBuf>,
     lock_max_duration: Option<Duration>,
+    pub(crate) max_size: Option<u64>,
+    pub(crate) max_age: Option<Duration>,
 }
 
 /// Error related to caching.
@@ -99,6 +101,24 @@ impl CacheOption {
         self
     }
 
+    /// The maximum size of the cache on disk, in bytes.
+    ///
+    /// Only used by the compilation cache, which evicts its oldest chunks when opened until it
+    /// fits.
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// The maximum age of the entries of the cache.
+    ///
+    /// Only used by the compilation cache, which evicts the chunks that weren't written to for
+    /// longer than that when opened.
+    pub fn max_age(mut self, age: Duration) -> Self {
+        self.max_age = Some(age);
+        self
+    }
+
     pub(crate) fn resolve(self) -> (Vec<u8>, String, String, PathBuf, Duration) {
         let separator = self.separator.unwrap_or_else(|| b"\n".to_vec());
         let version = self
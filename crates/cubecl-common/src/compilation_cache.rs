@@ -1,10 +1,13 @@
+use core::time::Duration;
 use std::{
     boxed::Box,
     cell::RefCell,
+    format,
     fs::{self, File},
     io::{Cursor, Write},
     path::{Path, PathBuf},
     string::{String, ToString},
+    time::SystemTime,
     vec::Vec,
 };
 
@@ -13,9 +16,14 @@ use hashbrown::HashMap;
 use serde::Serialize;
 
 use crate::cache::{
-    Cache, CacheError, CacheKey, CacheOption, CacheValue, Entry, sanitize_path_segment,
+    Cache, CacheError, CacheKey, CacheOption, CacheValue, Entry, get_persistent_cache_file_path,
+    sanitize_path_segment,
 };
 
+/// A new chunk is started once the current one reaches this size, which is also the granularity
+/// of the eviction.
+const CHUNK_SIZE_MAX: u64 = 8 * 1024 * 1024;
+
 /// The in-memory cache used by the chunked kernel cache.
 /// Box ensures values aren't moved when inserting new elements, so we don't need to keep it
 /// locked for reads
@@ -23,11 +31,18 @@ type InMemoryCache<K, V> = RefCell<HashMap<K, Box<V>>>;
 
 /// A chunked cache for compilation artifacts. Uses a human readable table of contents, with binary
 /// storage for the compiled kernel.
+///
+/// Entries are appended to the current chunk, and a new chunk is started once it gets too big.
+/// When a [maximum size](CacheOption::max_size) or a [maximum age](CacheOption::max_age) is set,
+/// the oldest chunks are evicted when the cache is opened. The limits aren't checked on insert, so
+/// the cache can grow past its maximum size until it is opened again.
 #[derive(Debug)]
 pub struct CompilationCache<K: CacheKey, V: CacheValue> {
     toc: Cache<K, String>,
     in_memory_cache: InMemoryCache<K, V>,
     current_chunk: File,
+    current_chunk_index: usize,
+    current_chunk_size: u64,
     current_chunk_path_normalized: String,
     cache_root: PathBuf,
 }
@@ -54,33 +69,54 @@ impl<K: CacheKey, V: CacheValue> CompilationCache<K, V> {
         skip(path),
         fields(path = ?path.as_ref())))]
     pub fn new<P: AsRef<Path>>(path: P, option: CacheOption) -> Self {
+        let (max_size, max_age) = (option.max_size, option.max_age);
         let (_, name, version, root, _) = option.clone().resolve();
         let path = path.as_ref();
         let toc_path = path.join("toc");
-        // `.cbor` suffix (was `.bin` with bincode) invalidates old on-disk chunks.
-        let chunk_path = Path::new("chunk0.cbor"); // Split later
 
-        let cache_root = get_persistent_cache_root(path, root, name, version);
-        let chunk_path = get_persistent_chunk_file_path(chunk_path, &cache_root);
+        let cache_root =
+            get_persistent_cache_root(path, root.clone(), name.clone(), version.clone());
+        let evicted = evict_chunks(&cache_root, max_size, max_age);
+
+        // The table of contents can't be edited, so it is rebuilt from the remaining chunks.
+        if evicted {
+            let toc_file = get_persistent_cache_file_path(&toc_path, root, name, version);
+            fs::remove_file(toc_file).ok();
+        }
 
         let in_memory_cache = InMemoryCache::default();
-        let toc = Cache::new(toc_path, option);
+        let mut toc = Cache::new(toc_path, option);
 
-        if fs::exists(&chunk_path).unwrap_or(false) {
-            Self::read_chunk(&chunk_path, &in_memory_cache);
+        if evicted {
+            for chunk in list_chunks(&cache_root) {
+                let chunk_path_normalized = chunk_path_normalized(&chunk.path, &cache_root);
+                for key in Self::read_chunk(&chunk.path, &in_memory_cache) {
+                    toc.insert(key, chunk_path_normalized.clone()).ok();
+                }
+            }
         }
 
-        let current_chunk = open_chunk_writable(&chunk_path);
+        let (current_chunk_index, current_chunk_size) = match list_chunks(&cache_root)
+            .into_iter()
+            .max_by_key(|chunk| chunk.index)
+        {
+            Some(chunk) if chunk.size < CHUNK_SIZE_MAX => (chunk.index, chunk.size),
+            Some(chunk) => (chunk.index + 1, 0),
+            None => (0, 0),
+        };
+        let chunk_path = chunk_path(current_chunk_index, &cache_root);
+
+        if current_chunk_size > 0 && !evicted {
+            Self::read_chunk(&chunk_path, &in_memory_cache);
+        }
 
         Self {
             toc,
             in_memory_cache,
-            current_chunk,
-            current_chunk_path_normalized: normalized_path(
-                chunk_path
-                    .strip_prefix(&cache_root)
-                    .expect("Should contain root"),
-            ),
+            current_chunk: open_chunk_writable(&chunk_path),
+            current_chunk_index,
+            current_chunk_size,
+            current_chunk_path_normalized: chunk_path_normalized(&chunk_path, &cache_root),
             cache_root,
         }
     }
@@ -110,9 +146,16 @@ impl<K: CacheKey, V: CacheValue> CompilationCache<K, V> {
         }
     }
 
-    fn read_chunk(chunk: &PathBuf, cache: &InMemoryCache<K, V>) {
-        let data =
-            fs::read(chunk).expect("Can't open chunk in table of contents, cache is corrupted!");
+    /// Loads the entries of a chunk in memory and returns their keys.
+    fn read_chunk(chunk: &Path, cache: &InMemoryCache<K, V>) -> Vec<K> {
+        // Another process might have evicted the chunk.
+        let data = match fs::read(chunk) {
+            Ok(data) => data,
+            Err(err) => {
+                log::warn!("Can't open chunk {chunk:?} in table of contents : {err}");
+                return Vec::new();
+            }
+        };
         let mut cursor = Cursor::new(data);
         // Collect new entries first so we only need to lock once everything is loaded
         let mut new_entries = Vec::new();
@@ -143,7 +186,9 @@ impl<K: CacheKey, V: CacheValue> CompilationCache<K, V> {
             idx += 1;
         }
 
+        let keys = new_entries.iter().map(|(key, _)| key.clone()).collect();
         cache.borrow_mut().extend(new_entries);
+        keys
     }
 
     /// Insert a new item to the cache.
@@ -173,6 +218,7 @@ impl<K: CacheKey, V: CacheValue> CompilationCache<K, V> {
             self.current_chunk
                 .write_all(&bytes)
                 .expect("Failed to write to chunk");
+            self.current_chunk_size += bytes.len() as u64;
 
             let mut cache = self.in_memory_cache.borrow_mut();
             cache.insert(entry.key, Box::new(entry.value));
@@ -181,8 +227,21 @@ impl<K: CacheKey, V: CacheValue> CompilationCache<K, V> {
             .insert(key, self.current_chunk_path_normalized.clone())
             .map_err(CompilationCacheError::TocError)?;
 
+        if self.current_chunk_size >= CHUNK_SIZE_MAX {
+            self.next_chunk();
+        }
+
         Ok(())
     }
+
+    fn next_chunk(&mut self) {
+        self.current_chunk_index += 1;
+        self.current_chunk_size = 0;
+
+        let chunk_path = chunk_path(self.current_chunk_index, &self.cache_root);
+        self.current_chunk = open_chunk_writable(&chunk_path);
+        self.current_chunk_path_normalized = chunk_path_normalized(&chunk_path, &self.cache_root);
+    }
 }
 
 fn get_persistent_cache_root(
@@ -207,20 +266,84 @@ fn get_persistent_cache_root(
     std::path::absolute(path).expect("Not empty, so can't fail")
 }
 
-fn get_persistent_chunk_file_path<P: AsRef<Path>>(path_partial: P, chunks_root: &Path) -> PathBuf {
-    let path_partial: &Path = path_partial.as_ref();
+/// A chunk of the cache on disk.
+struct Chunk {
+    index: usize,
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
 
-    let mut path = chunks_root.to_path_buf();
+// `.cbor` suffix (was `.bin` with bincode) invalidates old on-disk chunks.
+fn chunk_path(index: usize, cache_root: &Path) -> PathBuf {
+    cache_root.join(format!("chunk{index}.cbor"))
+}
 
-    for segment in path_partial.iter() {
-        // Skip the name directory since it resets the previous path segments.
-        if segment == "/" {
-            continue;
+fn chunk_path_normalized(chunk_path: &Path, cache_root: &Path) -> String {
+    normalized_path(
+        chunk_path
+            .strip_prefix(cache_root)
+            .expect("Should contain root"),
+    )
+}
+
+/// List the chunks in the cache root, ordered by index.
+fn list_chunks(cache_root: &Path) -> Vec<Chunk> {
+    let Ok(entries) = fs::read_dir(cache_root) else {
+        return Vec::new();
+    };
+
+    let mut chunks = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let file_name = entry.file_name();
+            let index = file_name
+                .to_str()?
+                .strip_prefix("chunk")?
+                .strip_suffix(".cbor")?
+                .parse()
+                .ok()?;
+            let metadata = entry.metadata().ok()?;
+
+            Some(Chunk {
+                index,
+                path: entry.path(),
+                size: metadata.len(),
+                modified: metadata.modified().ok()?,
+            })
+        })
+        .collect::<Vec<_>>();
+    chunks.sort_by_key(|chunk| chunk.index);
+    chunks
+}
+
+/// Removes the chunks older than `max_age`, then the oldest chunks until the cache is smaller than
+/// `max_size`. Returns whether any chunk was removed.
+fn evict_chunks(cache_root: &Path, max_size: Option<u64>, max_age: Option<Duration>) -> bool {
+    if max_size.is_none() && max_age.is_none() {
+        return false;
+    }
+
+    let mut chunks = list_chunks(cache_root);
+    chunks.sort_by_key(|chunk| chunk.modified);
+
+    let now = SystemTime::now();
+    let mut size = chunks.iter().map(|chunk| chunk.size).sum::<u64>();
+    let mut evicted = false;
+
+    for chunk in chunks {
+        let age = now.duration_since(chunk.modified).unwrap_or_default();
+        let expired = max_age.is_some_and(|max_age| age > max_age);
+        let oversized = max_size.is_some_and(|max_size| size > max_size);
+
+        if (expired || oversized) && fs::remove_file(&chunk.path).is_ok() {
+            log::info!("Evicted chunk {:?} from the compilation cache", chunk.path);
+            size -= chunk.size;
+            evicted = true;
         }
-        path = path.join(sanitize_path_segment(segment.to_str().unwrap()));
     }
 
-    std::path::absolute(path).expect("Not empty, so can't fail")
+    evicted
 }
 
 fn normalized_path(path: &Path) -> String {
@@ -235,3 +358,62 @@ fn open_chunk_writable(path: &Path) -> File {
     let file = File::options().append(true).create(true).open(path);
     file.expect("Failed to open write chunk")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec;
+
+    fn option(root: &Path) -> CacheOption {
+        CacheOption::default().name("test").root(root)
+    }
+
+    #[test_log::test]
+    #[cfg_attr(miri, ignore)]
+    fn test_compilation_cache_reload() {
+        let root = tempfile::tempdir().unwrap();
+
+        let mut cache = CompilationCache::<u64, Vec<u8>>::new("kernels", option(root.path()));
+        cache.insert(1, vec![1, 2, 3]).unwrap();
+        cache.insert(2, vec![4, 5]).unwrap();
+        core::mem::drop(cache);
+
+        let cache = CompilationCache::<u64, Vec<u8>>::new("kernels", option(root.path()));
+        assert_eq!(cache.get(&1), Some(&vec![1, 2, 3]));
+        assert_eq!(cache.get(&2), Some(&vec![4, 5]));
+    }
+
+    #[test_log::test]
+    #[cfg_attr(miri, ignore)]
+    fn test_compilation_cache_eviction() {
+        let root = tempfile::tempdir().unwrap();
+
+        let mut cache = CompilationCache::<u64, Vec<u8>>::new("kernels", option(root.path()));
+        cache.insert(1, vec![0; 64]).unwrap();
+        core::mem::drop(cache);
+
+        let cache = CompilationCache::<u64, Vec<u8>>::new(
+            "kernels",
+            option(root.path()).max_size(1024 * 1024),
+        );
+        assert_eq!(cache.get(&1), Some(&vec![0; 64]), "Fits in the cache.");
+        core::mem::drop(cache);
+
+        let mut cache =
+            CompilationCache::<u64, Vec<u8>>::new("kernels", option(root.path()).max_size(16));
+        assert_eq!(cache.get(&1), None, "Evicted since the cache is too big.");
+
+        cache.insert(1, vec![1; 64]).unwrap();
+        core::mem::drop(cache);
+
+        let cache = CompilationCache::<u64, Vec<u8>>::new(
+            "kernels",
+            option(root.path()).max_age(Duration::from_secs(3600)),
+        );
+        assert_eq!(
+            cache.get(&1),
+            Some(&vec![1; 64]),
+            "Reinserted after eviction."
+        );
+    }
+}
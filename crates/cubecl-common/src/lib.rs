@@ -73,6 +73,9 @@ pub mod quant;
 /// Format utilities.
 pub mod format;
 
+/// Formatting of the debug prints of kernels, following the C `printf` conventions.
+pub mod printf;
+
 /// Various utilities to create ID's.
 extern crate alloc;
 
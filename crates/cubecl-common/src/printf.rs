@@ -0,0 +1,338 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// An argument of a print.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrintValue {
+    /// A floating point value.
+    Float(f64),
+    /// A signed integer value.
+    Int(i64),
+    /// An unsigned integer value.
+    UInt(u64),
+}
+
+impl PrintValue {
+    fn as_f64(&self) -> f64 {
+        match self {
+            PrintValue::Float(value) => *value,
+            PrintValue::Int(value) => *value as f64,
+            PrintValue::UInt(value) => *value as f64,
+        }
+    }
+
+    fn as_i64(&self) -> i64 {
+        match self {
+            PrintValue::Float(value) => *value as i64,
+            PrintValue::Int(value) => *value,
+            PrintValue::UInt(value) => *value as i64,
+        }
+    }
+
+    fn as_u64(&self) -> u64 {
+        match self {
+            PrintValue::Float(value) => *value as u64,
+            PrintValue::Int(value) => *value as u64,
+            PrintValue::UInt(value) => *value,
+        }
+    }
+}
+
+/// A parsed conversion specification, i.e. `%-08.3f`.
+#[derive(Default)]
+struct Spec {
+    left: bool,
+    plus: bool,
+    space: bool,
+    zero: bool,
+    alternate: bool,
+    width: usize,
+    precision: Option<usize>,
+}
+
+/// Format values following the C++ `printf` conventions.
+///
+/// Vectors are printed component by component, separated by `, `, which also supports the
+/// `%v4f` syntax of Vulkan's debug printf.
+pub fn format_printf(format: &str, values: &[Vec<PrintValue>]) -> String {
+    let mut output = String::new();
+    let mut values = values.iter();
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            output.push(c);
+            continue;
+        }
+        if chars.peek() == Some(&'%') {
+            chars.next();
+            output.push('%');
+            continue;
+        }
+
+        let mut spec = Spec::default();
+        while let Some(flag) = chars.peek() {
+            match flag {
+                '-' => spec.left = true,
+                '+' => spec.plus = true,
+                ' ' => spec.space = true,
+                '0' => spec.zero = true,
+                '#' => spec.alternate = true,
+                _ => break,
+            }
+            chars.next();
+        }
+        spec.width = parse_number(&mut chars).unwrap_or_default();
+        if chars.peek() == Some(&'.') {
+            chars.next();
+            spec.precision = Some(parse_number(&mut chars).unwrap_or_default());
+        }
+        // Vector sizes and length modifiers don't change how values are printed.
+        if chars.peek() == Some(&'v') {
+            chars.next();
+            parse_number(&mut chars);
+        }
+        while chars
+            .peek()
+            .is_some_and(|c| matches!(c, 'h' | 'l' | 'L' | 'z' | 'j' | 't'))
+        {
+            chars.next();
+        }
+
+        let Some(conversion) = chars.next() else {
+            break;
+        };
+        let Some(value) = values.next() else {
+            // Missing arguments are left as is, like the rest of the string.
+            output.push('%');
+            output.push(conversion);
+            continue;
+        };
+
+        let components = value
+            .iter()
+            .map(|value| format_value(value, conversion, &spec))
+            .collect::<Vec<_>>();
+        output += &components.join(", ");
+    }
+
+    output
+}
+
+fn parse_number(chars: &mut core::iter::Peekable<core::str::Chars<'_>>) -> Option<usize> {
+    let mut number = None;
+    while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+        number = Some(number.unwrap_or(0) * 10 + digit as usize);
+        chars.next();
+    }
+    number
+}
+
+fn format_value(value: &PrintValue, conversion: char, spec: &Spec) -> String {
+    let (negative, body) = match conversion {
+        'd' | 'i' => {
+            let value = value.as_i64();
+            let body = value.unsigned_abs().to_string();
+            (value < 0, pad_digits(body, spec.precision))
+        }
+        'u' => (
+            false,
+            pad_digits(value.as_u64().to_string(), spec.precision),
+        ),
+        'x' | 'X' | 'o' => {
+            let value = value.as_u64();
+            let mut body = match conversion {
+                'x' => format!("{value:x}"),
+                'X' => format!("{value:X}"),
+                _ => format!("{value:o}"),
+            };
+            body = pad_digits(body, spec.precision);
+            if spec.alternate && value != 0 {
+                let prefix = match conversion {
+                    'x' => "0x",
+                    'X' => "0X",
+                    _ => "0",
+                };
+                body = format!("{prefix}{body}");
+            }
+            (false, body)
+        }
+        'f' | 'F' | 'e' | 'E' | 'g' | 'G' | 'a' | 'A' => {
+            let value = value.as_f64();
+            let body = format_float(value.abs(), conversion, spec);
+            (value.is_sign_negative() && !value.is_nan(), body)
+        }
+        'c' => {
+            let c = char::from_u32(value.as_u64() as u32).unwrap_or(char::REPLACEMENT_CHARACTER);
+            return pad(String::new(), c.to_string(), spec, false);
+        }
+        _ => {
+            // Unsupported conversions, i.e. strings, are printed with their default format.
+            let body = match value {
+                PrintValue::Float(value) => value.to_string(),
+                PrintValue::Int(value) => value.to_string(),
+                PrintValue::UInt(value) => value.to_string(),
+            };
+            return pad(String::new(), body, spec, false);
+        }
+    };
+
+    let sign = match (negative, spec.plus, spec.space) {
+        (true, _, _) => "-",
+        (false, true, _) => "+",
+        (false, false, true) => " ",
+        _ => "",
+    };
+    // Zero padding is ignored for integers with a precision, like in C.
+    let zero = spec.zero && !(spec.precision.is_some() && "diuxXo".contains(conversion));
+    pad(sign.to_string(), body, spec, zero)
+}
+
+fn pad_digits(digits: String, precision: Option<usize>) -> String {
+    match precision {
+        Some(precision) if digits.len() < precision => {
+            format!("{}{digits}", "0".repeat(precision - digits.len()))
+        }
+        _ => digits,
+    }
+}
+
+fn pad(sign: String, body: String, spec: &Spec, zero: bool) -> String {
+    let len = sign.chars().count() + body.chars().count();
+    if len >= spec.width {
+        return sign + &body;
+    }
+
+    let fill = spec.width - len;
+    if spec.left {
+        format!("{sign}{body}{}", " ".repeat(fill))
+    } else if zero {
+        format!("{sign}{}{body}", "0".repeat(fill))
+    } else {
+        format!("{}{sign}{body}", " ".repeat(fill))
+    }
+}
+
+/// Format a positive float.
+fn format_float(value: f64, conversion: char, spec: &Spec) -> String {
+    let upper = conversion.is_ascii_uppercase();
+    if !value.is_finite() {
+        let body = if value.is_nan() { "nan" } else { "inf" };
+        return match upper {
+            true => body.to_uppercase(),
+            false => body.to_string(),
+        };
+    }
+
+    let precision = spec.precision.unwrap_or(6);
+    let body = match conversion.to_ascii_lowercase() {
+        'f' => format!("{value:.precision$}"),
+        'e' => format_exponent(value, precision),
+        'g' => {
+            let precision = precision.max(1);
+            let exponent = match value == 0.0 {
+                true => 0,
+                false => exponent_of(value, precision - 1),
+            };
+            let body = if exponent < -4 || exponent >= precision as i32 {
+                format_exponent(value, precision - 1)
+            } else {
+                let decimals = (precision as i32 - 1 - exponent) as usize;
+                format!("{value:.decimals$}")
+            };
+            match spec.alternate {
+                true => body,
+                false => trim_zeros(body),
+            }
+        }
+        // Hexadecimal floats are rare enough to be printed in scientific notation.
+        _ => format_exponent(value, precision),
+    };
+
+    match upper {
+        true => body.to_uppercase(),
+        false => body,
+    }
+}
+
+/// The decimal exponent of a value once rounded to `precision` decimals in scientific notation.
+fn exponent_of(value: f64, precision: usize) -> i32 {
+    let formatted = format!("{value:.precision$e}");
+    formatted
+        .split_once('e')
+        .and_then(|(_, exponent)| exponent.parse().ok())
+        .unwrap_or_default()
+}
+
+/// Format a value in scientific notation with at least two exponent digits, like `1.5e+02`.
+fn format_exponent(value: f64, precision: usize) -> String {
+    let formatted = format!("{value:.precision$e}");
+    let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+    let exponent = exponent.parse::<i32>().unwrap_or_default();
+    let sign = if exponent < 0 { '-' } else { '+' };
+    format!("{mantissa}e{sign}{:02}", exponent.abs())
+}
+
+/// Remove the trailing zeros of the fractional part, including the point if it's left empty.
+fn trim_zeros(body: String) -> String {
+    let (mantissa, exponent) = match body.split_once('e') {
+        Some((mantissa, exponent)) => (mantissa.to_string(), format!("e{exponent}")),
+        None => (body, String::new()),
+    };
+    let mantissa = match mantissa.contains('.') {
+        true => mantissa
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string(),
+        false => mantissa,
+    };
+    mantissa + &exponent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn scalars(values: &[PrintValue]) -> Vec<Vec<PrintValue>> {
+        values.iter().map(|value| vec![value.clone()]).collect()
+    }
+
+    #[test]
+    fn formats_integers() {
+        let values = scalars(&[
+            PrintValue::Int(-42),
+            PrintValue::UInt(7),
+            PrintValue::UInt(255),
+            PrintValue::Int(5),
+        ]);
+        assert_eq!(
+            format_printf("%d|%03u|%#x|%-3d|%%", &values),
+            "-42|007|0xff|5  |%"
+        );
+    }
+
+    #[test]
+    fn formats_floats() {
+        let values = scalars(&[
+            PrintValue::Float(1.5),
+            PrintValue::Float(-150.0),
+            PrintValue::Float(0.0001),
+            PrintValue::Float(1234567.0),
+            PrintValue::Float(2.0),
+        ]);
+        assert_eq!(
+            format_printf("%f %.2e %g %g %08.3f", &values),
+            "1.500000 -1.50e+02 0.0001 1.23457e+06 0002.000"
+        );
+    }
+
+    #[test]
+    fn formats_vectors() {
+        let values = vec![vec![PrintValue::Float(1.0), PrintValue::Float(2.5)]];
+        assert_eq!(format_printf("(%.1v2f)", &values), "(1.0, 2.5)");
+    }
+}
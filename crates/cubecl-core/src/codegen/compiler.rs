@@ -1,14 +1,18 @@
 // We cannot put this struct in cubecl-wgpu crate due to circular dependencies.
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, Hash)]
 pub struct WgpuCompilationOptions {
     pub supports_u64: bool,
     /// Whether the Vulkan compiler is supported or we need to fall back to WGSL
     pub supports_vulkan: bool,
+    /// Whether WGSL kernels are compiled from the output of the full optimizer
+    pub optimize: bool,
+    /// Whether the optimizer partially unrolls the loops with a trip count known at compile time
+    pub unroll_loops: bool,
 
     pub vulkan: VulkanCompilationOptions,
 }
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, Hash)]
 pub struct VulkanCompilationOptions {
     pub supports_fp_fast_math: bool,
     pub supports_explicit_smem: bool,
@@ -8,7 +8,7 @@ use crate::{
     prelude::Assign,
 };
 
-use super::{CubeType, Int, NativeExpand, Numeric};
+use super::{CubePrimitive, CubeType, Int, NativeExpand, Numeric};
 
 /// Something that can be iterated on by a for loop. Currently only includes `Range`, `StepBy` and
 /// `Sequence`.
@@ -301,13 +301,18 @@ pub mod range_stepped {
     }
 }
 
+/// Expand a for loop over `range`, unrolling it if `unroll` is set.
+///
+/// Loops of a single iteration are unrolled too, unless their body `continues`, since an unrolled
+/// body has no loop to continue.
 pub fn for_expand<I: Numeric>(
     scope: &mut Scope,
     range: impl Iterable<I>,
     unroll: bool,
+    continues: bool,
     body: impl FnMut(&mut Scope, NativeExpand<I>),
 ) {
-    if unroll || range.const_len() == Some(1) {
+    if unroll || (!continues && range.const_len() == Some(1)) {
         range.expand_unroll(scope, body);
     } else {
         range.expand(scope, body);
@@ -855,10 +860,110 @@ pub fn break_expand(scope: &mut Scope) {
     scope.register(Branch::Break);
 }
 
+pub fn continue_expand(scope: &mut Scope) {
+    scope.register(Branch::Continue);
+}
+
 pub fn return_expand(scope: &mut Scope) {
     scope.register(Branch::Return);
 }
 
+/// The value returned by a `#[cube]` function containing `return` statements.
+///
+/// Functions are inlined in the scope of their caller, so a `return` can't exit the kernel.
+/// Instead, the body is expanded in a child scope wrapped in a range loop with a single iteration,
+/// and returning assigns the output before continuing past that iteration. Returns nested in loops
+/// of the function set a flag and break out of their loop instead, and the flag is checked after
+/// each of these loops to leave the enclosing ones.
+///
+/// Leaving through the continue target rather than breaking keeps it reachable, since the
+/// optimizer can't handle loops that never reach their continue target.
+pub struct ReturnExpand<C: Assign> {
+    out: Option<C>,
+    returned: Option<NativeExpand<bool>>,
+    index: Option<ManagedVariable>,
+}
+
+impl<C: Assign> ReturnExpand<C> {
+    /// Start the expansion of a function, `nested` if it returns inside one of its loops.
+    pub fn new(scope: &mut Scope, nested: bool) -> Self {
+        let returned = nested.then(|| {
+            let init = NativeExpand::<bool>::from_lit(scope, false);
+            let mut returned = init.init_mut(scope);
+            returned.expand_assign(scope, init);
+            returned
+        });
+
+        Self {
+            out: None,
+            returned,
+            index: None,
+        }
+    }
+
+    /// Create the scope the body of the function is expanded in.
+    pub fn body(&mut self, scope: &mut Scope) -> Scope {
+        let mut body = scope.child();
+        self.index = Some(body.create_local_restricted(u32::as_type(scope)));
+        body
+    }
+
+    /// Return `value` from the function, `nested` if the return is inside a loop of the function.
+    pub fn expand_return(&mut self, scope: &mut Scope, value: C, nested: bool) {
+        match &mut self.out {
+            Some(out) => out.expand_assign(scope, value),
+            None => {
+                let mut out = value.init_mut(scope);
+                out.expand_assign(scope, value);
+                self.out = Some(out);
+            }
+        }
+
+        if nested {
+            let returned = self
+                .returned
+                .as_mut()
+                .expect("Nested returns must be declared");
+            let value = NativeExpand::<bool>::from_lit(scope, true);
+            returned.expand_assign(scope, value);
+            break_expand(scope);
+        } else {
+            continue_expand(scope);
+        }
+    }
+
+    /// Leave the enclosing loop if the loop expanded just before returned, `nested` if the
+    /// enclosing loop is a loop of the function rather than the function body itself.
+    pub fn expand_propagate(&self, scope: &mut Scope, nested: bool) {
+        if let Some(returned) = &self.returned {
+            match nested {
+                true => if_expand(scope, returned.clone(), break_expand),
+                false => if_expand(scope, returned.clone(), continue_expand),
+            }
+        }
+    }
+
+    /// Finish the expansion of the function with the scope its `body` was expanded in, returning
+    /// its output.
+    pub fn finish(self, scope: &mut Scope, body: Scope) -> C {
+        let index = self
+            .index
+            .expect("The body must be created by `ReturnExpand::body`");
+        let start = NativeExpand::<u32>::from_lit(scope, 0u32);
+        let end = NativeExpand::<u32>::from_lit(scope, 1u32);
+        scope.register(Branch::RangeLoop(Box::new(RangeLoop {
+            i: *index,
+            start: *start.expand,
+            end: *end.expand,
+            step: None,
+            scope: body,
+            inclusive: false,
+        })));
+        self.out
+            .expect("A function with early returns must return on every path")
+    }
+}
+
 pub mod unreachable_unchecked {
     use super::*;
 
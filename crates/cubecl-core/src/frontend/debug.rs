@@ -72,7 +72,7 @@ pub fn printf_expand(scope: &mut Scope, format_string: impl Into<String>, args:
 }
 
 /// Print a formatted message using the target's debug print facilities. The format string is target
-/// specific, but Vulkan and CUDA both use the C++ conventions. WGSL isn't currently supported.
+/// specific, but Vulkan, CUDA and WGSL all use the C++ conventions.
 #[macro_export]
 macro_rules! debug_print {
     ($format:literal, $($args:expr),*) => {
@@ -87,7 +87,7 @@ macro_rules! debug_print {
 }
 
 /// Print a formatted message using the target's debug print facilities. The format string is target
-/// specific, but Vulkan and CUDA both use the C++ conventions. WGSL isn't currently supported.
+/// specific, but Vulkan, CUDA and WGSL all use the C++ conventions.
 #[macro_export]
 macro_rules! debug_print_expand {
     ($scope:expr, $format:expr, $($args:expr),*) => {
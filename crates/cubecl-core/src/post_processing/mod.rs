@@ -1,4 +1,5 @@
 pub mod checked_io;
+pub mod packed;
 pub mod predicate;
 pub mod saturating;
 pub mod unroll;
@@ -0,0 +1,83 @@
+use alloc::vec::Vec;
+use cubecl_ir::{
+    Allocator, ElemType, FloatKind, Instruction, Operation, OperationReflect, Operator, Processor,
+    ScopeProcessing, Type, UnaryOperator, Variable, VariableKind,
+};
+
+/// Replaces arithmetic on packed types with the same operation on unpacked `f32` values, for
+/// backends that represent packed types as plain integers and only know how to cast them.
+#[derive(new, Debug)]
+pub struct PackedArithmeticProcessor {}
+
+impl Processor for PackedArithmeticProcessor {
+    fn transform(&self, mut processing: ScopeProcessing, allocator: Allocator) -> ScopeProcessing {
+        let mut instructions = Vec::new();
+        core::mem::swap(&mut processing.instructions, &mut instructions);
+
+        for instruction in instructions {
+            if let Operation::Arithmetic(_) = &instruction.operation
+                && let Some(out) = instruction.out
+                && out.ty.packing_factor() > 1
+            {
+                self.unpack_arithmetic(&mut processing, &instruction, out, &allocator);
+                continue;
+            }
+
+            processing.instructions.push(instruction);
+        }
+        processing
+    }
+}
+
+impl PackedArithmeticProcessor {
+    fn unpack_arithmetic(
+        &self,
+        processing: &mut ScopeProcessing,
+        instruction: &Instruction,
+        out: Variable,
+        allocator: &Allocator,
+    ) {
+        let unpacked = |var: Variable| {
+            let vector_size = var.vector_size() * var.ty.packing_factor();
+            Type::new(ElemType::Float(FloatKind::F32).into()).with_vector_size(vector_size)
+        };
+
+        let args = instruction
+            .operation
+            .args()
+            .expect("Arithmetic should be reflectable")
+            .into_iter()
+            .map(|arg| match arg.kind {
+                // Constants have the same value in every lane, so they stay scalar
+                VariableKind::Constant(value) => {
+                    Variable::constant(value, ElemType::Float(FloatKind::F32))
+                }
+                _ if arg.ty.packing_factor() > 1 => {
+                    let unpacked_arg = *allocator.create_local(unpacked(arg));
+                    processing.instructions.push(Instruction::new(
+                        Operator::Cast(UnaryOperator { input: arg }),
+                        unpacked_arg,
+                    ));
+                    unpacked_arg
+                }
+                _ => arg,
+            })
+            .collect::<Vec<_>>();
+
+        let unpacked_out = *allocator.create_local(unpacked(out));
+        let operation = Operation::from_code_and_args(instruction.operation.op_code(), &args)
+            .expect("Failed to reconstruct operation");
+        processing.instructions.push(Instruction {
+            out: Some(unpacked_out),
+            source_loc: instruction.source_loc.clone(),
+            modes: instruction.modes,
+            operation,
+        });
+        processing.instructions.push(Instruction::new(
+            Operator::Cast(UnaryOperator {
+                input: unpacked_out,
+            }),
+            out,
+        ));
+    }
+}
@@ -2,8 +2,8 @@ use alloc::{vec, vec::Vec};
 use cubecl_ir::{
     Allocator, Arithmetic, BinaryOperator, Branch, CoopMma, CopyMemoryBulkOperator,
     IndexAssignOperator, IndexOperator, Instruction, ManagedVariable, MatrixLayout, Metadata,
-    Operation, OperationReflect, Operator, Processor, ScopeProcessing, Type, Variable,
-    VariableKind, VectorSize,
+    Operation, OperationReflect, Operator, Processor, ScopeProcessing, Type, UnaryOperator,
+    Variable, VariableKind, VectorInitOperator, VectorSize,
 };
 use hashbrown::HashMap;
 
@@ -106,6 +106,17 @@ impl UnrollProcessor {
             let unroll_factor = vector_size / self.max_vector_size;
 
             match &inst.operation {
+                Operation::Operator(Operator::Cast(op))
+                    if op.input.ty.packing_factor() != inst.ty().packing_factor() =>
+                {
+                    TransformAction::Replace(self.transform_packed_cast(
+                        alloc,
+                        inst,
+                        op,
+                        unroll_factor,
+                        mappings,
+                    ))
+                }
                 Operation::Operator(Operator::CopyMemoryBulk(op)) => TransformAction::Replace(
                     self.transform_memcpy(alloc, op, inst.out(), unroll_factor),
                 ),
@@ -520,6 +531,93 @@ impl UnrollProcessor {
             .collect()
     }
 
+    /// Transforms a cast between types with different packing factors. The input and output
+    /// have different vector sizes, so both are split into `unroll_factor` parts that each cover
+    /// the same logical elements. A side that isn't unrolled itself is split by extracting its
+    /// lanes, and reassembled after the cast if it's the output.
+    fn transform_packed_cast(
+        &self,
+        alloc: &Allocator,
+        inst: &Instruction,
+        op: &UnaryOperator,
+        unroll_factor: usize,
+        mappings: &mut Mappings,
+    ) -> Vec<Instruction> {
+        let out = inst.out();
+        let mut instructions = Vec::new();
+
+        let inputs = self.split_vector(alloc, op.input, unroll_factor, mappings, &mut instructions);
+        let outputs = if out.vector_size() > self.max_vector_size {
+            mappings.get(alloc, out, unroll_factor, self.max_vector_size)
+        } else {
+            let item = out.ty.with_vector_size(out.vector_size() / unroll_factor);
+            (0..unroll_factor)
+                .map(|_| *alloc.create_local(item))
+                .collect()
+        };
+
+        for (input, out) in inputs.into_iter().zip(outputs.iter()) {
+            instructions.push(Instruction {
+                out: Some(*out),
+                source_loc: inst.source_loc.clone(),
+                modes: inst.modes,
+                operation: Operator::Cast(UnaryOperator { input }).into(),
+            });
+        }
+
+        if out.vector_size() <= self.max_vector_size {
+            let inputs = outputs
+                .iter()
+                .flat_map(|part| extract_lanes(alloc, *part, &mut instructions))
+                .collect();
+            instructions.push(Instruction::new(
+                Operator::InitVector(VectorInitOperator { inputs }),
+                out,
+            ));
+        }
+
+        instructions
+    }
+
+    /// Splits `var` into `unroll_factor` parts. Variables that are already unrolled use their
+    /// existing mapping, others are split by extracting and regrouping their lanes.
+    fn split_vector(
+        &self,
+        alloc: &Allocator,
+        var: Variable,
+        unroll_factor: usize,
+        mappings: &mut Mappings,
+        instructions: &mut Vec<Instruction>,
+    ) -> Vec<Variable> {
+        if var.vector_size() > self.max_vector_size {
+            return mappings.get(alloc, var, unroll_factor, self.max_vector_size);
+        }
+
+        let part_size = var.vector_size() / unroll_factor;
+        let part_ty = var.ty.with_vector_size(part_size);
+
+        // Constants have the same value in every lane
+        if let VariableKind::Constant(_) = var.kind {
+            return vec![Variable::new(var.kind, part_ty); unroll_factor];
+        }
+
+        let mut lanes = extract_lanes(alloc, var, instructions).into_iter();
+        (0..unroll_factor)
+            .map(|_| {
+                let inputs = lanes.by_ref().take(part_size).collect::<Vec<_>>();
+                if part_size == 1 {
+                    return inputs[0];
+                }
+                let part = *alloc.create_local(part_ty);
+                instructions.push(Instruction::new(
+                    Operator::InitVector(VectorInitOperator { inputs }),
+                    part,
+                ));
+                part
+            })
+            .collect()
+    }
+
     fn transform_instructions(
         &self,
         allocator: &Allocator,
@@ -643,6 +741,33 @@ fn create_unrolled(
         .collect()
 }
 
+/// Extracts each lane of `var` into a separate scalar local.
+fn extract_lanes(
+    alloc: &Allocator,
+    var: Variable,
+    instructions: &mut Vec<Instruction>,
+) -> Vec<Variable> {
+    if var.vector_size() == 1 {
+        return vec![var];
+    }
+
+    (0..var.vector_size())
+        .map(|i| {
+            let lane = *alloc.create_local(Type::new(var.storage_type()));
+            instructions.push(Instruction::new(
+                Operator::Index(IndexOperator {
+                    list: var,
+                    index: i.into(),
+                    vector_size: 1,
+                    unroll_factor: 1,
+                }),
+                lane,
+            ));
+            lane
+        })
+        .collect()
+}
+
 fn add_index(alloc: &Allocator, idx: Variable, i: usize) -> (Instruction, ManagedVariable) {
     let add_idx = alloc.create_local(idx.ty);
     let add = Instruction::new(
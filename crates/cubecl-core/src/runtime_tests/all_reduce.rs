@@ -1,6 +1,8 @@
 use crate::{Runtime, prelude::*};
 use alloc::vec::Vec;
-use cubecl_common::device::Device;
+use cubecl_common::device::{Device, DeviceId};
+use cubecl_ir::{ElemType, FloatKind};
+use cubecl_runtime::server::ReduceOperation;
 
 pub fn test_all_reduce_sync_collective<R: Runtime>() {
     let type_id = 0;
@@ -61,6 +63,211 @@ pub fn test_all_reduce_sync_collective<R: Runtime>() {
     }
 }
 
+pub fn test_all_reduce_max<R: Runtime>() {
+    let Some((device_ids, clients)) = collective_clients::<R>() else {
+        return;
+    };
+    const SIZE: usize = 64;
+
+    let handles = clients
+        .iter()
+        .enumerate()
+        .map(|(i, client)| client.create_from_slice(f32::as_bytes(&[i as f32; SIZE])))
+        .collect::<Vec<_>>();
+
+    for (client, handle) in clients.iter().zip(handles.iter()) {
+        client.all_reduce(
+            handle.clone(),
+            handle.clone(),
+            F32,
+            device_ids.clone(),
+            ReduceOperation::Max,
+        );
+        client.sync_collective();
+    }
+
+    let expected = [(clients.len() - 1) as f32; SIZE];
+    for (client, handle) in clients.iter().zip(handles) {
+        let actual = client.read_one(handle).unwrap();
+        assert_eq!(f32::from_bytes(&actual), expected);
+    }
+}
+
+pub fn test_broadcast<R: Runtime>() {
+    let Some((device_ids, clients)) = collective_clients::<R>() else {
+        return;
+    };
+    const SIZE: usize = 64;
+    let root = device_ids[1];
+
+    let handles = clients
+        .iter()
+        .enumerate()
+        .map(|(i, client)| client.create_from_slice(f32::as_bytes(&[i as f32; SIZE])))
+        .collect::<Vec<_>>();
+
+    for (client, handle) in clients.iter().zip(handles.iter()) {
+        client.broadcast(
+            handle.clone(),
+            handle.clone(),
+            F32,
+            root,
+            device_ids.clone(),
+        );
+        client.sync_collective();
+    }
+
+    for (client, handle) in clients.iter().zip(handles) {
+        let actual = client.read_one(handle).unwrap();
+        assert_eq!(f32::from_bytes(&actual), [1.0; SIZE]);
+    }
+}
+
+pub fn test_reduce_to_root<R: Runtime>() {
+    let Some((device_ids, clients)) = collective_clients::<R>() else {
+        return;
+    };
+    const SIZE: usize = 64;
+    let root = 1;
+
+    let handles = clients
+        .iter()
+        .enumerate()
+        .map(|(i, client)| {
+            let src = client.create_from_slice(f32::as_bytes(&[i as f32 + 1.0; SIZE]));
+            let dst = client.create_from_slice(f32::as_bytes(&[-1.0; SIZE]));
+            (src, dst)
+        })
+        .collect::<Vec<_>>();
+
+    for (client, (src, dst)) in clients.iter().zip(handles.iter()) {
+        client.reduce(
+            src.clone(),
+            dst.clone(),
+            F32,
+            device_ids[root],
+            device_ids.clone(),
+            ReduceOperation::Sum,
+        );
+        client.sync_collective();
+    }
+
+    // Only the root receives the result, the other outputs are left untouched.
+    let sum = (1..=clients.len()).map(|i| i as f32).sum::<f32>();
+    for (i, (client, (_, dst))) in clients.iter().zip(handles).enumerate() {
+        let expected = if i == root { sum } else { -1.0 };
+        let actual = client.read_one(dst).unwrap();
+        assert_eq!(f32::from_bytes(&actual), [expected; SIZE]);
+    }
+}
+
+pub fn test_all_gather<R: Runtime>() {
+    let Some((device_ids, clients)) = collective_clients::<R>() else {
+        return;
+    };
+    const SIZE: usize = 16;
+    let count = clients.len();
+
+    let handles = clients
+        .iter()
+        .enumerate()
+        .map(|(i, client)| {
+            let src = client.create_from_slice(f32::as_bytes(&[i as f32; SIZE]));
+            let dst = client.empty(SIZE * count * size_of::<f32>());
+            (src, dst)
+        })
+        .collect::<Vec<_>>();
+
+    for (client, (src, dst)) in clients.iter().zip(handles.iter()) {
+        client.all_gather(src.clone(), dst.clone(), F32, device_ids.clone());
+        client.sync_collective();
+    }
+
+    let expected = (0..count)
+        .flat_map(|i| [i as f32; SIZE])
+        .collect::<Vec<_>>();
+    for (client, (_, dst)) in clients.iter().zip(handles) {
+        let actual = client.read_one(dst).unwrap();
+        assert_eq!(f32::from_bytes(&actual), expected.as_slice());
+    }
+}
+
+pub fn test_reduce_scatter<R: Runtime>() {
+    let Some((device_ids, clients)) = collective_clients::<R>() else {
+        return;
+    };
+    const SIZE: usize = 16;
+    let count = clients.len();
+
+    // Device `i` holds `i + j` in block `j`.
+    let handles = clients
+        .iter()
+        .enumerate()
+        .map(|(i, client)| {
+            let data = (0..count)
+                .flat_map(|j| [(i + j) as f32; SIZE])
+                .collect::<Vec<_>>();
+            let src = client.create_from_slice(f32::as_bytes(&data));
+            let dst = client.empty(SIZE * size_of::<f32>());
+            (src, dst)
+        })
+        .collect::<Vec<_>>();
+
+    for (client, (src, dst)) in clients.iter().zip(handles.iter()) {
+        client.reduce_scatter(
+            src.clone(),
+            dst.clone(),
+            F32,
+            device_ids.clone(),
+            ReduceOperation::Sum,
+        );
+        client.sync_collective();
+    }
+
+    for (j, (client, (_, dst))) in clients.iter().zip(handles).enumerate() {
+        let expected = (0..count).map(|i| (i + j) as f32).sum::<f32>();
+        let actual = client.read_one(dst).unwrap();
+        assert_eq!(f32::from_bytes(&actual), [expected; SIZE]);
+    }
+}
+
+pub fn test_send_recv<R: Runtime>() {
+    let Some((device_ids, clients)) = collective_clients::<R>() else {
+        return;
+    };
+    const SIZE: usize = 64;
+    let device_ids = device_ids[..2].to_vec();
+
+    let src = clients[0].create_from_slice(f32::as_bytes(&[42.0; SIZE]));
+    let dst = clients[1].empty(SIZE * size_of::<f32>());
+
+    clients[0].send(src, F32, device_ids[1], device_ids.clone());
+    clients[1].recv(dst.clone(), F32, device_ids[0], device_ids.clone());
+    clients[0].sync_collective();
+    clients[1].sync_collective();
+
+    let actual = clients[1].read_one(dst).unwrap();
+    assert_eq!(f32::from_bytes(&actual), [42.0; SIZE]);
+}
+
+const F32: ElemType = ElemType::Float(FloatKind::F32);
+
+/// A client for each device, if there are enough devices to test collective operations.
+fn collective_clients<R: Runtime>() -> Option<(Vec<DeviceId>, Vec<ComputeClient<R>>)> {
+    let client = R::client(&Default::default());
+    let device_ids = client.enumerate_devices(0);
+
+    if device_ids.len() < 2 {
+        return None;
+    }
+    let clients = device_ids
+        .iter()
+        .map(|id| R::client(&R::Device::from_id(*id)))
+        .collect();
+
+    Some((device_ids, clients))
+}
+
 #[allow(missing_docs)]
 #[macro_export]
 macro_rules! testgen_all_reduce {
@@ -72,5 +279,35 @@ macro_rules! testgen_all_reduce {
             cubecl_core::runtime_tests::all_reduce::test_all_reduce_sync_collective::<TestRuntime>(
             );
         }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_all_reduce_max() {
+            cubecl_core::runtime_tests::all_reduce::test_all_reduce_max::<TestRuntime>();
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_broadcast() {
+            cubecl_core::runtime_tests::all_reduce::test_broadcast::<TestRuntime>();
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_reduce_to_root() {
+            cubecl_core::runtime_tests::all_reduce::test_reduce_to_root::<TestRuntime>();
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_all_gather() {
+            cubecl_core::runtime_tests::all_reduce::test_all_gather::<TestRuntime>();
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_reduce_scatter() {
+            cubecl_core::runtime_tests::all_reduce::test_reduce_scatter::<TestRuntime>();
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_send_recv() {
+            cubecl_core::runtime_tests::all_reduce::test_send_recv::<TestRuntime>();
+        }
     };
 }
@@ -75,6 +75,87 @@ pub fn kernel_for_loop_with_break<F: Float>(output: &mut Array<F>) {
     }
 }
 
+#[cube(launch)]
+pub fn kernel_for_loop_with_continue<F: Float>(output: &mut Array<F>) {
+    for i in 0..20u32 {
+        if i % 2 == 1 {
+            continue;
+        }
+        output[i as usize] = F::new(1.0);
+    }
+}
+
+#[cube(launch)]
+pub fn kernel_single_iteration_loop_with_continue<F: Float>(output: &mut Array<F>) {
+    for i in 0..20u32 {
+        // A single iteration is normally unrolled, but the continue must only skip this loop
+        for _ in 0..1u32 {
+            if i % 2 == 1 {
+                continue;
+            }
+            output[i as usize] = F::new(1.0);
+        }
+        output[i as usize] += F::new(1.0);
+    }
+}
+
+#[cube(launch)]
+pub fn kernel_loop_with_continue<F: Float>(output: &mut Array<F>) {
+    let mut i: u32 = 0;
+    while i < 20 {
+        let index = i;
+        i += 1;
+        if index % 2 == 1 {
+            continue;
+        }
+        output[index as usize] = F::new(1.0);
+    }
+}
+
+#[cube]
+fn first_greater<F: Float>(input: &Array<F>, value: F) -> u32 {
+    for i in 0..input.len() {
+        if input[i] > value {
+            return i as u32;
+        }
+    }
+    input.len() as u32
+}
+
+#[cube]
+fn clamp_with_return<F: Float>(value: F) -> F {
+    if value < F::new(0.0) {
+        return F::new(0.0);
+    }
+    if value > F::new(1.0) {
+        return F::new(1.0);
+    }
+    value
+}
+
+#[cube]
+fn write_positive<F: Float>(output: &mut Array<F>, index: usize, value: F) {
+    if value <= F::new(0.0) {
+        return;
+    }
+    output[index] = value;
+}
+
+#[cube(launch)]
+pub fn kernel_early_return<F: Float>(input: &Array<F>, output: &mut Array<F>) {
+    if UNIT_POS == 0 {
+        let first = first_greater(input, F::new(2.5));
+        let none = first_greater(input, F::new(10.0));
+        output[0] = F::cast_from(first);
+        output[1] = F::cast_from(none);
+        output[2] = clamp_with_return::<F>(F::new(-1.0));
+        output[3] = clamp_with_return::<F>(F::new(0.5));
+        output[4] = clamp_with_return::<F>(F::new(3.0));
+        write_positive(output, 5, F::new(-2.0));
+        write_positive(output, 6, F::new(2.0));
+    }
+}
+
 pub fn test_switch_const<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
     let handle = client.create_from_slice(as_bytes![F: 0.0, 1.0]);
 
@@ -205,6 +286,87 @@ pub fn test_for_loop_with_break<R: Runtime, F: Float + CubeElement>(client: Comp
     assert_eq!(actual, expected.as_slice());
 }
 
+pub fn test_for_loop_with_continue<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let zeros = vec![F::new(0.0); 20];
+    let handle = client.create_from_slice(F::as_bytes(&zeros));
+
+    kernel_for_loop_with_continue::launch::<F, R>(
+        &client,
+        CubeCount::Static(1, 1, 1),
+        CubeDim::new_1d(1),
+        unsafe { ArrayArg::from_raw_parts(handle.clone(), 20) },
+    );
+
+    let actual = client.read_one_unchecked(handle);
+    let actual = F::from_bytes(&actual);
+
+    let expected: Vec<F> = (0..20)
+        .map(|i| if i % 2 == 0 { F::new(1.0) } else { F::new(0.0) })
+        .collect();
+    assert_eq!(actual, expected.as_slice());
+}
+
+pub fn test_single_iteration_loop_with_continue<R: Runtime, F: Float + CubeElement>(
+    client: ComputeClient<R>,
+) {
+    let zeros = vec![F::new(0.0); 20];
+    let handle = client.create_from_slice(F::as_bytes(&zeros));
+
+    kernel_single_iteration_loop_with_continue::launch::<F, R>(
+        &client,
+        CubeCount::Static(1, 1, 1),
+        CubeDim::new_1d(1),
+        unsafe { ArrayArg::from_raw_parts(handle.clone(), 20) },
+    );
+
+    let actual = client.read_one_unchecked(handle);
+    let actual = F::from_bytes(&actual);
+
+    let expected: Vec<F> = (0..20)
+        .map(|i| if i % 2 == 0 { F::new(2.0) } else { F::new(1.0) })
+        .collect();
+    assert_eq!(actual, expected.as_slice());
+}
+
+pub fn test_loop_with_continue<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let zeros = vec![F::new(0.0); 20];
+    let handle = client.create_from_slice(F::as_bytes(&zeros));
+
+    kernel_loop_with_continue::launch::<F, R>(
+        &client,
+        CubeCount::Static(1, 1, 1),
+        CubeDim::new_1d(1),
+        unsafe { ArrayArg::from_raw_parts(handle.clone(), 20) },
+    );
+
+    let actual = client.read_one_unchecked(handle);
+    let actual = F::from_bytes(&actual);
+
+    let expected: Vec<F> = (0..20)
+        .map(|i| if i % 2 == 0 { F::new(1.0) } else { F::new(0.0) })
+        .collect();
+    assert_eq!(actual, expected.as_slice());
+}
+
+pub fn test_early_return<R: Runtime, F: Float + CubeElement>(client: ComputeClient<R>) {
+    let input = client.create_from_slice(as_bytes![F: 1.0, 2.0, 3.0, 4.0]);
+    let output = client.create_from_slice(as_bytes![F: 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+
+    kernel_early_return::launch::<F, R>(
+        &client,
+        CubeCount::Static(1, 1, 1),
+        CubeDim::new_1d(1),
+        unsafe { ArrayArg::from_raw_parts(input, 4) },
+        unsafe { ArrayArg::from_raw_parts(output.clone(), 7) },
+    );
+
+    let actual = client.read_one_unchecked(output);
+    let actual = F::from_bytes(&actual);
+
+    let expected = [2.0, 4.0, 0.0, 0.5, 1.0, 0.0, 2.0].map(F::new);
+    assert_eq!(actual, expected.as_slice());
+}
+
 #[allow(missing_docs)]
 #[macro_export]
 macro_rules! testgen_branch {
@@ -270,5 +432,39 @@ macro_rules! testgen_branch {
                 client,
             );
         }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_for_loop_with_continue() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::branch::test_for_loop_with_continue::<
+                TestRuntime,
+                FloatType,
+            >(client);
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_single_iteration_loop_with_continue() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::branch::test_single_iteration_loop_with_continue::<
+                TestRuntime,
+                FloatType,
+            >(client);
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_loop_with_continue() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::branch::test_loop_with_continue::<TestRuntime, FloatType>(
+                client,
+            );
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_early_return() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::branch::test_early_return::<TestRuntime, FloatType>(
+                client,
+            );
+        }
     };
 }
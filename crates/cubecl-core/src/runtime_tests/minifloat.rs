@@ -47,6 +47,17 @@ pub fn kernel_fp4<F: Float, N: Size, N2: Size>(
     }
 }
 
+#[cube(launch_unchecked)]
+pub fn kernel_fp4_buffer<F: Float, N: Size, N2: Size>(
+    input: &mut Array<Vector<F, N>>,
+    out: &mut Array<Vector<e2m1x2, N2>>,
+) {
+    if ABSOLUTE_POS < input.len() {
+        out[ABSOLUTE_POS] = Vector::cast_from(input[ABSOLUTE_POS]);
+        input[ABSOLUTE_POS] = Vector::cast_from(out[ABSOLUTE_POS]);
+    }
+}
+
 #[cube(launch_unchecked)]
 pub fn kernel_scale<N: Size>(input: &mut Array<Vector<f32, N>>, out: &mut Array<Vector<ue8m0, N>>) {
     if ABSOLUTE_POS == 0 {
@@ -154,7 +165,9 @@ pub fn test_fp4<R: Runtime, F: Float + CubeElement>(
     client: ComputeClient<R>,
     vector_size: VectorSize,
 ) {
-    if !e2m1x2::supported_uses(&client).contains(TypeUsage::Conversion) {
+    if !e2m1x2::supported_uses(&client).contains(TypeUsage::Conversion)
+        || !u8::supported_uses(&client).contains(TypeUsage::Buffer)
+    {
         println!("Unsupported, skipping");
         return;
     }
@@ -193,6 +206,56 @@ pub fn test_fp4<R: Runtime, F: Float + CubeElement>(
     assert_eq!(&actual_2[..num_out], &expected_data[..num_out]);
 }
 
+pub fn test_fp4_buffer<R: Runtime, F: Float + CubeElement>(
+    client: ComputeClient<R>,
+    vector_size: VectorSize,
+) {
+    if !e2m1x2::supported_uses(&client).is_superset(TypeUsage::Conversion | TypeUsage::Buffer) {
+        println!("Unsupported, skipping");
+        return;
+    }
+
+    let data = as_type![F:
+        0.0, 0.4, 0.75, 1.2, 1.25, 1.75, 2.4, 2.6,
+        3.5, 5.0, 5.5, 100.0, -0.3, -1.8, -4.2, -7.0
+    ];
+    let num_units = data.len() / vector_size;
+    let handle1 = client.create_from_slice(F::as_bytes(data));
+    let handle2 = client.empty(data.len() / 2 * size_of::<u8>());
+
+    unsafe {
+        kernel_fp4_buffer::launch_unchecked::<F, R>(
+            &client,
+            CubeCount::Static(1, 1, 1),
+            CubeDim::new_1d(num_units as u32),
+            vector_size,
+            vector_size / 2,
+            ArrayArg::from_raw_parts(handle1.clone(), data.len()),
+            ArrayArg::from_raw_parts(handle2.clone(), data.len() / 2),
+        )
+    };
+
+    // Data rounded to the nearest e2m1 value, ties to even and saturating
+    let expected_data = as_type![F:
+        0.0, 0.5, 1.0, 1.0, 1.0, 2.0, 2.0, 3.0,
+        4.0, 4.0, 6.0, 6.0, -0.5, -2.0, -4.0, -6.0
+    ];
+    let expected = e2m1x2::from_f32_slice(&[
+        0.0, 0.5, 1.0, 1.0, 1.0, 2.0, 2.0, 3.0, 4.0, 4.0, 6.0, 6.0, -0.5, -2.0, -4.0, -6.0,
+    ])
+    .into_iter()
+    .map(e2m1x2::to_bits)
+    .collect::<Vec<_>>();
+
+    let actual = client.read_one_unchecked(handle2);
+    let actual = u8::from_bytes(&actual);
+    let actual_2 = client.read_one_unchecked(handle1);
+    let actual_2 = F::from_bytes(&actual_2);
+
+    assert_eq!(actual, &expected);
+    assert_eq!(actual_2, &expected_data[..]);
+}
+
 pub fn test_scale<R: Runtime>(client: ComputeClient<R>, vector_size: VectorSize) {
     if !ue8m0::supported_uses(&client).contains(TypeUsage::Conversion) {
         println!("Unsupported, skipping");
@@ -282,6 +345,23 @@ macro_rules! testgen_minifloat {
             );
         }
 
+        #[$crate::runtime_tests::test_log::test]
+        fn test_fp4_buffer() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::minifloat::test_fp4_buffer::<TestRuntime, FloatType>(
+                client.clone(),
+                2,
+            );
+            cubecl_core::runtime_tests::minifloat::test_fp4_buffer::<TestRuntime, FloatType>(
+                client.clone(),
+                4,
+            );
+            cubecl_core::runtime_tests::minifloat::test_fp4_buffer::<TestRuntime, FloatType>(
+                client.clone(),
+                8,
+            );
+        }
+
         #[$crate::runtime_tests::test_log::test]
         fn test_scale() {
             let client = TestRuntime::client(&Default::default());
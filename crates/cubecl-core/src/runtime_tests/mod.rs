@@ -26,7 +26,9 @@ pub mod properties;
 pub mod saturating;
 pub mod sequence;
 pub mod slice;
+pub mod staging;
 pub mod stream;
+pub mod strided;
 pub mod synchronization;
 pub mod tensor;
 pub mod tensormap;
@@ -140,6 +142,8 @@ macro_rules! testgen_untyped {
         cubecl_core::testgen_cmma!();
         cubecl_core::testgen_numeric!();
         cubecl_core::testgen_file!();
+        cubecl_core::testgen_staging!();
+        cubecl_core::testgen_strided!();
         cubecl_core::testgen_metadata!();
         cubecl_core::testgen_topology!();
         cubecl_core::testgen_properties!();
@@ -0,0 +1,65 @@
+use crate::{self as cubecl};
+use alloc::{vec, vec::Vec};
+use cubecl::prelude::*;
+use cubecl_common::bytes::Bytes;
+
+pub fn test_staging<R: Runtime>(client: ComputeClient<R>) {
+    let sizes = [1024 * 1024, 6, 28];
+    let data = sizes
+        .iter()
+        .enumerate()
+        .map(|(i, size)| (0..*size).map(|j| (j * 7 + i) as u8).collect::<Vec<u8>>())
+        .collect::<Vec<_>>();
+
+    let mut bytes = data
+        .iter()
+        .map(|data| Bytes::from_bytes_vec(data.clone()))
+        .collect::<Vec<_>>();
+    client.staging(bytes.iter_mut(), false);
+
+    let handles = bytes
+        .into_iter()
+        .map(|bytes| client.create(bytes))
+        .collect::<Vec<_>>();
+
+    for (handle, expected) in handles.into_iter().zip(data) {
+        let actual = client.read_one_unchecked(handle);
+        assert_eq!(&actual[..], &expected[..], "The staged data is uploaded.");
+    }
+}
+
+pub fn test_staging_write<R: Runtime>(client: ComputeClient<R>) {
+    let expected = (0u32..256).collect::<Vec<u32>>();
+
+    let mut bytes = [Bytes::from_elems(vec![0u32; expected.len()])];
+    client.staging(bytes.iter_mut(), false);
+    let [mut bytes] = bytes;
+    // The staging memory is written to directly.
+    bytes.copy_from_slice(u32::as_bytes(&expected));
+
+    let handle = client.create(bytes);
+    let actual = client.read_one_unchecked(handle);
+
+    assert_eq!(u32::from_bytes(&actual), &expected[..]);
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! testgen_staging {
+    () => {
+        use super::*;
+        use cubecl_core::prelude::*;
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_staging() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::staging::test_staging::<TestRuntime>(client);
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_staging_write() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::staging::test_staging_write::<TestRuntime>(client);
+        }
+    };
+}
@@ -0,0 +1,101 @@
+use crate::{self as cubecl};
+use alloc::{vec, vec::Vec};
+use cubecl::prelude::*;
+use cubecl_common::bytes::Bytes;
+use cubecl_runtime::server::{IoError, ServerError};
+
+/// A 3x4 tensor stored transposed, with `element (i, j)` at `i + 3 * j`.
+pub fn test_write_read_transposed<R: Runtime>(client: ComputeClient<R>) {
+    let expected = (0..12).map(|i| i as f32).collect::<Vec<_>>();
+    let handle = client.create_from_slice(f32::as_bytes(&[-1.0; 12]));
+
+    let descriptor = || {
+        handle
+            .clone()
+            .copy_descriptor([3, 4].into(), [1, 3].into(), 4)
+    };
+    client.write_tensor(vec![(descriptor(), Bytes::from_elems(expected.clone()))]);
+
+    let actual = client.read_one_unchecked_tensor(descriptor());
+    assert_eq!(f32::from_bytes(&actual), expected.as_slice());
+
+    let stored = client.read_one_unchecked(handle.clone());
+    let transposed = (0..12)
+        .map(|i| expected[(i % 3) * 4 + i / 3])
+        .collect::<Vec<_>>();
+    assert_eq!(f32::from_bytes(&stored), transposed.as_slice());
+}
+
+/// A 3x4 tensor with rows padded to 6 elements, where the padding is left unchanged.
+pub fn test_write_read_pitched<R: Runtime>(client: ComputeClient<R>) {
+    let expected = (0..12).map(|i| i as f32).collect::<Vec<_>>();
+    let handle = client.create_from_slice(f32::as_bytes(&[-1.0; 18]));
+
+    let descriptor = || {
+        handle
+            .clone()
+            .copy_descriptor([3, 4].into(), [6, 1].into(), 4)
+    };
+    client.write_tensor(vec![(descriptor(), Bytes::from_elems(expected.clone()))]);
+
+    let actual = client.read_one_unchecked_tensor(descriptor());
+    assert_eq!(f32::from_bytes(&actual), expected.as_slice());
+
+    let stored = client.read_one_unchecked(handle.clone());
+    let pitched = (0..18)
+        .map(|i| match i % 6 < 4 {
+            true => expected[(i / 6) * 4 + i % 6],
+            false => -1.0,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(f32::from_bytes(&stored), pitched.as_slice());
+}
+
+pub fn test_write_invalid_size<R: Runtime>(client: ComputeClient<R>) {
+    let handle = client.create_from_slice(f32::as_bytes(&[-1.0; 18]));
+
+    let descriptor = handle.copy_descriptor([3, 4].into(), [6, 1].into(), 4);
+    client.write_tensor(vec![(descriptor, Bytes::from_elems(vec![0.0f32; 11]))]);
+
+    let result = client.flush();
+
+    match result {
+        Err(ServerError::ServerUnhealthy { mut errors, .. }) => match errors.remove(0) {
+            ServerError::Io(IoError::InvalidSize {
+                expected, actual, ..
+            }) => {
+                assert_eq!(expected, 48, "Expected should be the size of the tensor");
+                assert_eq!(actual, 44, "Actual should be the size of the data");
+            }
+            error => panic!("Expected an invalid size error, got {error:?}"),
+        },
+        result => panic!("Expected the write to fail, got {result:?}"),
+    }
+}
+
+#[allow(missing_docs)]
+#[macro_export]
+macro_rules! testgen_strided {
+    () => {
+        use super::*;
+        use cubecl_core::prelude::*;
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_write_read_transposed() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::strided::test_write_read_transposed::<TestRuntime>(client);
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_write_read_pitched() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::strided::test_write_read_pitched::<TestRuntime>(client);
+        }
+
+        #[$crate::runtime_tests::test_log::test]
+        fn test_write_invalid_size() {
+            let client = TestRuntime::client(&Default::default());
+            cubecl_core::runtime_tests::strided::test_write_invalid_size::<TestRuntime>(client);
+        }
+    };
+}
@@ -0,0 +1,14 @@
+use cubecl::prelude::*;
+use cubecl_core as cubecl;
+
+#[cube]
+fn continue_comptime_loop(output: &mut Array<u32>, #[comptime] indices: Vec<usize>) {
+    for i in indices {
+        if output[i] == 0 {
+            continue;
+        }
+        output[i] += 1;
+    }
+}
+
+fn main() {}
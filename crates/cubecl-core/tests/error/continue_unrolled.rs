@@ -0,0 +1,15 @@
+use cubecl::prelude::*;
+use cubecl_core as cubecl;
+
+#[cube]
+fn continue_unrolled(output: &mut Array<u32>) {
+    #[unroll]
+    for i in 0..4usize {
+        if output[i] == 0 {
+            continue;
+        }
+        output[i] += 1;
+    }
+}
+
+fn main() {}
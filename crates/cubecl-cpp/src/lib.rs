@@ -16,6 +16,11 @@ pub mod cuda;
 pub mod hip;
 #[cfg(feature = "metal")]
 pub mod metal;
+#[cfg(feature = "opencl")]
+pub mod opencl;
 
 #[cfg(feature = "metal")]
 pub type MslCompiler = shared::CppCompiler<metal::MslDialect>;
+
+#[cfg(feature = "opencl")]
+pub type OpenClCompiler = shared::CppCompiler<opencl::OpenClDialect>;
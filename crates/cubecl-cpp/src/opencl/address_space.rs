@@ -0,0 +1,35 @@
+use crate::{Dialect, shared::Variable};
+
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AddressSpace {
+    Global,
+    Local,
+    Private,
+    /// Pointers without a qualifier use the generic address space.
+    Generic,
+}
+
+impl Display for AddressSpace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddressSpace::Global => f.write_str("__global"),
+            AddressSpace::Local => f.write_str("__local"),
+            AddressSpace::Private => f.write_str("__private"),
+            AddressSpace::Generic => Ok(()),
+        }
+    }
+}
+
+impl<D: Dialect> From<&Variable<D>> for AddressSpace {
+    fn from(value: &Variable<D>) -> Self {
+        match value {
+            Variable::GlobalInputArray(..) | Variable::GlobalOutputArray(..) => {
+                AddressSpace::Global
+            }
+            Variable::SharedArray(..) | Variable::Shared(..) => AddressSpace::Local,
+            _ => AddressSpace::Generic,
+        }
+    }
+}
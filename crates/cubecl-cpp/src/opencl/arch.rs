@@ -0,0 +1,73 @@
+use std::fmt::Display;
+
+use crate::shared::Architecture;
+
+// We support devices implementing OpenCL 2.0 or 3.0
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenClArchitecture {
+    OpenCl20,
+    OpenCl30,
+}
+
+impl Display for OpenClArchitecture {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::OpenCl20 => write!(f, "opencl2.0"),
+            Self::OpenCl30 => write!(f, "opencl3.0"),
+        }
+    }
+}
+
+impl OpenClArchitecture {
+    /// Parses the `CL_DEVICE_VERSION` of a device, formatted as `OpenCL <major>.<minor> <info>`.
+    pub fn parse(arg: &str) -> Result<Self, String> {
+        let version = arg
+            .trim()
+            .strip_prefix("OpenCL")
+            .unwrap_or(arg)
+            .split_whitespace()
+            .next()
+            .ok_or_else(|| format!("Invalid OpenCL version: {arg}"))?;
+        let (major, _minor) = version
+            .split_once('.')
+            .ok_or_else(|| format!("Invalid OpenCL version: {arg}"))?;
+        match major {
+            "2" => Ok(Self::OpenCl20),
+            "3" => Ok(Self::OpenCl30),
+            _ => Err(format!(
+                "Unsupported OpenCL version: {arg}, 2.0 or 3.0 is required"
+            )),
+        }
+    }
+
+    /// The options to pass to `clBuildProgram`, selecting the `OpenCL` C version of the device.
+    pub fn build_options(&self) -> &'static str {
+        match self {
+            Self::OpenCl20 => "-cl-std=CL2.0",
+            Self::OpenCl30 => "-cl-std=CL3.0",
+        }
+    }
+}
+
+impl Architecture for OpenClArchitecture {
+    fn warp_size(&self) -> u32 {
+        // The sub-group size depends on the device and the kernel, this is the most common one.
+        32
+    }
+
+    fn is_wmma_capable(&self) -> bool {
+        false
+    }
+
+    fn is_mfma_capable(&self) -> bool {
+        false
+    }
+
+    fn get_version(&self) -> u32 {
+        match self {
+            Self::OpenCl20 => 200,
+            Self::OpenCl30 => 300,
+        }
+    }
+}
@@ -0,0 +1,1090 @@
+use super::{
+    AddressSpace, Extension,
+    arch::OpenClArchitecture,
+    extension::{format_mulhi, format_reverse_bits},
+};
+use crate::{
+    Dialect,
+    shared::{
+        self, AtomicKind, Component, CubeIndexFlags, DialectBindings, DialectCubeBuiltins,
+        DialectIncludes, DialectInstructions, DialectProcessors, DialectTypes,
+        DialectWarpReduceCompiler, DialectWmmaCompiler, Elem, Flags, FmtLeft, Instruction, Item,
+        KernelArg, SharedMemory, SupportedMmaCombinations, Variable, WarpInstruction,
+        WmmaInstruction,
+    },
+};
+use cubecl_core::{
+    ir::{
+        self as gpu, DeviceProperties,
+        features::{AtomicUsage, TypeUsage},
+    },
+    prelude::Visibility,
+};
+use std::fmt::Display;
+
+/// `OpenCL` C 2.0 and 3.0 devices, including CPUs through `PoCL`.
+///
+/// The casts and aggregates of the shared compiler are overridden with their C forms, and kernels
+/// are built with the `-cl-std` matching the device (see [`OpenClArchitecture::build_options`]).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct OpenClDialect {}
+
+// Base dialect
+
+impl Dialect for OpenClDialect {
+    type Architecture = OpenClArchitecture;
+}
+
+/// Extensions providing the sub-group functions used for plane operations.
+const SUBGROUP_EXTENSIONS: [&str; 6] = [
+    "cl_khr_subgroups",
+    "cl_khr_subgroup_shuffle",
+    "cl_khr_subgroup_shuffle_relative",
+    "cl_khr_subgroup_ballot",
+    "cl_khr_subgroup_non_uniform_vote",
+    "cl_khr_subgroup_non_uniform_arithmetic",
+];
+
+impl OpenClDialect {
+    fn warp_op_vectorized(
+        f: &mut core::fmt::Formatter<'_>,
+        input: &Variable<Self>,
+        out: &Variable<Self>,
+        sub_group_op_prefix: &str,
+        sub_group_op_suffix: &str,
+    ) -> core::fmt::Result {
+        let out = out.fmt_left();
+        let vectorization = input.item().vectorization;
+
+        f.write_fmt(format_args!("{out} = ({}){{", input.item()))?;
+
+        for k in 0..vectorization {
+            let index = if vectorization > 1 {
+                format!(".i_{k}")
+            } else {
+                String::new()
+            };
+            let comma = if k + 1 < vectorization { "," } else { "" };
+
+            writeln!(
+                f,
+                "{sub_group_op_prefix}{input}{index}{sub_group_op_suffix}{comma}"
+            )?;
+        }
+
+        f.write_fmt(format_args!("}};\n"))
+    }
+}
+
+impl DialectWarpReduceCompiler<Self> for OpenClDialect {
+    fn warp_reduce_sum(
+        f: &mut core::fmt::Formatter<'_>,
+        input: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> core::fmt::Result {
+        Self::warp_op_vectorized(f, input, out, "sub_group_reduce_add(", ")")
+    }
+    fn warp_reduce_prod(
+        f: &mut core::fmt::Formatter<'_>,
+        input: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> core::fmt::Result {
+        Self::warp_op_vectorized(f, input, out, "sub_group_non_uniform_reduce_mul(", ")")
+    }
+    fn warp_reduce_max(
+        f: &mut core::fmt::Formatter<'_>,
+        input: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> core::fmt::Result {
+        Self::warp_op_vectorized(f, input, out, "sub_group_reduce_max(", ")")
+    }
+    fn warp_reduce_min(
+        f: &mut core::fmt::Formatter<'_>,
+        input: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> core::fmt::Result {
+        Self::warp_op_vectorized(f, input, out, "sub_group_reduce_min(", ")")
+    }
+    fn warp_reduce_all(
+        f: &mut core::fmt::Formatter<'_>,
+        input: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> core::fmt::Result {
+        Self::warp_op_vectorized(f, input, out, "sub_group_all(", ") != 0")
+    }
+    fn warp_reduce_any(
+        f: &mut core::fmt::Formatter<'_>,
+        input: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> core::fmt::Result {
+        Self::warp_op_vectorized(f, input, out, "sub_group_any(", ") != 0")
+    }
+    fn warp_reduce_sum_inclusive(
+        f: &mut core::fmt::Formatter<'_>,
+        input: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> core::fmt::Result {
+        Self::warp_op_vectorized(f, input, out, "sub_group_scan_inclusive_add(", ")")
+    }
+    fn warp_reduce_prod_inclusive(
+        f: &mut core::fmt::Formatter<'_>,
+        input: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> core::fmt::Result {
+        Self::warp_op_vectorized(
+            f,
+            input,
+            out,
+            "sub_group_non_uniform_scan_inclusive_mul(",
+            ")",
+        )
+    }
+    fn warp_reduce_sum_exclusive(
+        f: &mut core::fmt::Formatter<'_>,
+        input: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> core::fmt::Result {
+        Self::warp_op_vectorized(f, input, out, "sub_group_scan_exclusive_add(", ")")
+    }
+    fn warp_reduce_prod_exclusive(
+        f: &mut core::fmt::Formatter<'_>,
+        input: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> core::fmt::Result {
+        Self::warp_op_vectorized(
+            f,
+            input,
+            out,
+            "sub_group_non_uniform_scan_exclusive_mul(",
+            ")",
+        )
+    }
+}
+
+// Includes
+
+impl DialectIncludes<Self> for OpenClDialect {
+    type Extension = Extension<Self>;
+
+    fn compile_includes(f: &mut std::fmt::Formatter<'_>, flags: &Flags<Self>) -> std::fmt::Result {
+        if flags.elem_f16 {
+            writeln!(f, "#pragma OPENCL EXTENSION cl_khr_fp16 : enable")?;
+        }
+        if flags.elem_f64 {
+            writeln!(f, "#pragma OPENCL EXTENSION cl_khr_fp64 : enable")?;
+        }
+        let indexes = &flags.indexes;
+        // Every plane operation uses the checked plane dimension.
+        if indexes.plane_dim_checked || indexes.plane_pos || indexes.unit_pos_plane {
+            for extension in SUBGROUP_EXTENSIONS {
+                writeln!(f, "#pragma OPENCL EXTENSION {extension} : enable")?;
+            }
+        }
+        Ok(())
+    }
+
+    fn compile_extensions(
+        f: &mut std::fmt::Formatter<'_>,
+        extensions: &[Self::Extension],
+    ) -> std::fmt::Result {
+        for extension in extensions {
+            match extension {
+                Extension::MulHi(elem) => format_mulhi(f, elem)?,
+                Extension::ReverseBits(elem) => format_reverse_bits(f, elem)?,
+                Extension::NoExtension => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn register_instruction_extension(
+        extensions: &mut Vec<Self::Extension>,
+        instruction: &Instruction<Self>,
+    ) {
+        let mut register_extension = |extension: Self::Extension| {
+            if !extensions.contains(&extension) {
+                extensions.push(extension);
+            }
+        };
+        match instruction {
+            shared::Instruction::<Self>::HiMul(instruction) => {
+                register_extension(Extension::MulHi(instruction.out.elem()));
+            }
+            shared::Instruction::<Self>::ReverseBits(instruction) => {
+                // The 64-bit version is built from the 32-bit one
+                register_extension(Extension::ReverseBits(Elem::<Self>::U32));
+                if let Elem::I64 | Elem::U64 = instruction.out.elem() {
+                    register_extension(Extension::ReverseBits(Elem::<Self>::U64));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn register_warp_instruction_extension(
+        _extensions: &mut Vec<Self::Extension>,
+        _instruction: &WarpInstruction<Self>,
+    ) {
+    }
+}
+
+// Types
+
+impl DialectTypes<Self> for OpenClDialect {
+    fn item_can_be_optimized() -> bool {
+        false
+    }
+
+    fn compile_type_definitions(
+        f: &mut std::fmt::Formatter<'_>,
+        items: &std::collections::HashSet<crate::shared::Item<Self>>,
+        scalars: &[(Elem<Self>, usize)],
+        info: &cubecl_core::Info,
+        flags: &Flags<Self>,
+    ) -> std::fmt::Result {
+        // Sorted by name so the same kernel always compiles to the same source.
+        let mut items = items.iter().collect::<Vec<_>>();
+        items.sort_by_cached_key(|item| item.to_string());
+
+        for item in items {
+            let elem = item.elem;
+            let size = item.vectorization;
+            let alignment = elem.size() * size;
+            if size > 1 {
+                write!(
+                    f,
+                    "
+typedef struct __attribute__((aligned({alignment}))) {item} {{"
+                )?;
+
+                for i in 0..size {
+                    write!(
+                        f,
+                        "
+    {elem} i_{i};"
+                    )?;
+                }
+
+                write!(f, "\n}} {item};\n")?;
+            }
+        }
+
+        shared::type_info_definition_sized(f, info, scalars, flags.address_type)?;
+        f.write_str("typedef struct info_st info_st;\n")
+    }
+
+    fn compile_elem(
+        f: &mut std::fmt::Formatter<'_>,
+        elem: &shared::Elem<Self>,
+        _words: bool,
+    ) -> std::fmt::Result {
+        // OpenCL only has the word form of types
+        match elem {
+            shared::Elem::FP4(_)
+            | shared::Elem::FP4x2(_)
+            | shared::Elem::FP6(_)
+            | shared::Elem::FP6x2(_)
+            | shared::Elem::FP8(_)
+            | shared::Elem::FP8x2(_) => f.write_str("#error FP4/FP6/FP8 not supported in OpenCL\n"),
+            shared::Elem::F16 => f.write_str("half"),
+            shared::Elem::F16x2 => f.write_str("#error type F162 not supported!\n"),
+            shared::Elem::F32 => f.write_str("float"),
+            shared::Elem::F64 => f.write_str("double"),
+            shared::Elem::BF16 => f.write_str("#error type BF16 not supported in OpenCL\n"),
+            shared::Elem::BF16x2 => f.write_str("#error type BF162 not supported!\n"),
+            shared::Elem::TF32 => f.write_str("float"),
+            shared::Elem::I8 => f.write_str("char"),
+            shared::Elem::I16 => f.write_str("short"),
+            shared::Elem::I32 => f.write_str("int"),
+            shared::Elem::I64 => f.write_str("long"),
+            shared::Elem::U8 => f.write_str("uchar"),
+            shared::Elem::U16 => f.write_str("ushort"),
+            shared::Elem::U32 => f.write_str("uint"),
+            shared::Elem::U64 => f.write_str("ulong"),
+            shared::Elem::Bool => f.write_str("bool"),
+            shared::Elem::Barrier(_) => {
+                f.write_str("#error barrier objects not supported in OpenCL\n")
+            }
+            shared::Elem::Atomic(inner) => inner.fmt(f),
+            shared::Elem::_Dialect(_) => Ok(()),
+        }
+    }
+
+    fn compile_item(f: &mut std::fmt::Formatter<'_>, item: &Item<Self>) -> std::fmt::Result {
+        if 1 == item.vectorization {
+            return write!(f, "{}", item.elem);
+        }
+        if item.native {
+            write!(f, "{}{}", item.elem, item.vectorization)
+        } else {
+            write!(f, "{}_{}", item.elem, item.vectorization)
+        }
+    }
+
+    fn compile_atomic_kind(
+        f: &mut std::fmt::Formatter<'_>,
+        kind: &AtomicKind<Self>,
+    ) -> std::fmt::Result {
+        match kind {
+            AtomicKind::I32 => write!(f, "atomic_int"),
+            AtomicKind::I64 => write!(f, "atomic_long"),
+            AtomicKind::U32 => write!(f, "atomic_uint"),
+            AtomicKind::U64 => write!(f, "atomic_ulong"),
+            AtomicKind::F16 | AtomicKind::F16x2 => {
+                f.write_str("#error F16 atomics not supported in OpenCL\n")
+            }
+            AtomicKind::BF16 | AtomicKind::BF16x2 => {
+                f.write_str("#error BF16 atomics not supported in OpenCL\n")
+            }
+            AtomicKind::F32 => write!(f, "atomic_float"),
+            AtomicKind::F64 => write!(f, "atomic_double"),
+            AtomicKind::_Dialect(_) => Ok(()),
+        }
+    }
+
+    fn address_space_for_variable(variable: &Variable<Self>) -> String {
+        format!("{} ", AddressSpace::from(variable))
+    }
+
+    fn unsupported_elem(elem: &Elem<Self>) -> Option<String> {
+        match elem {
+            Elem::FP4(_)
+            | Elem::FP4x2(_)
+            | Elem::FP6(_)
+            | Elem::FP6x2(_)
+            | Elem::FP8(_)
+            | Elem::FP8x2(_)
+            | Elem::F16x2
+            | Elem::BF16
+            | Elem::BF16x2
+            | Elem::Barrier(_)
+            | Elem::Atomic(
+                AtomicKind::F16 | AtomicKind::F16x2 | AtomicKind::BF16 | AtomicKind::BF16x2,
+            ) => Some(format!("{elem:?} is not supported in OpenCL")),
+            _ => None,
+        }
+    }
+
+    fn cast(ty: impl Display, value: impl Display) -> String {
+        format!("(({ty})({value}))")
+    }
+
+    fn pointer_cast(ty: impl Display, value: impl Display) -> String {
+        format!("(({ty}*)({value}))")
+    }
+
+    fn reference_cast(ty: impl Display, value: impl Display) -> String {
+        format!("(*({ty}*)&({value}))")
+    }
+
+    fn aggregate_type(ty: impl Display) -> String {
+        format!("({ty})")
+    }
+
+    fn untyped_aggregate_type(ty: impl Display) -> String {
+        format!("({ty})")
+    }
+
+    fn compile_local_memory_qualifier(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", AddressSpace::Private)
+    }
+
+    fn compile_shared_memory_declaration(
+        f: &mut std::fmt::Formatter<'_>,
+        shared: &SharedMemory<Self>,
+    ) -> std::fmt::Result {
+        let address_space = AddressSpace::Local;
+        match shared {
+            SharedMemory::Array {
+                index,
+                item,
+                length,
+                offset,
+                ..
+            } => {
+                let size_bytes = length * item.size();
+                writeln!(f, "// Shared array size: {length}, {size_bytes} bytes")?;
+                writeln!(
+                    f,
+                    "{address_space} {item}* shared_memory_{index} = ({address_space} {item}*)&dynamic_shared_mem[{offset}];"
+                )
+            }
+            SharedMemory::Value {
+                index,
+                item,
+                offset,
+                ..
+            } => {
+                let size_bytes = item.size();
+                // C has no references, the value is accessed through its pointer instead
+                writeln!(f, "// Shared value size: {size_bytes} bytes")?;
+                writeln!(
+                    f,
+                    "#define shared_memory_{index} (*({address_space} {item}*)&dynamic_shared_mem[{offset}])"
+                )
+            }
+        }
+    }
+}
+
+// Kernel argument bindings
+
+impl DialectBindings<Self> for OpenClDialect {
+    fn compile_kernel_signature(
+        f: &mut std::fmt::Formatter<'_>,
+        kernel_name: &str,
+        tensor_maps: &[KernelArg<Self>],
+        buffers: &[KernelArg<Self>],
+        flags: &Flags<Self>,
+    ) -> std::fmt::Result {
+        debug_assert!(
+            tensor_maps.is_empty(),
+            "Tensor maps aren't supported for OpenCL"
+        );
+        let cube_dim = flags.cube_dim;
+        write!(
+            f,
+            "
+__kernel __attribute__((reqd_work_group_size({}, {}, {})))
+void {kernel_name}(",
+            cube_dim.x, cube_dim.y, cube_dim.z
+        )?;
+
+        let address_space = AddressSpace::Global;
+        let mut args = buffers
+            .iter()
+            .map(|binding| {
+                let (item, id) = (binding.item, binding.id);
+                match binding.vis {
+                    Visibility::Read => format!("{address_space} const {item}* buffer_{id}"),
+                    Visibility::ReadWrite => format!("{address_space} {item}* buffer_{id}"),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Kernel arguments can't be references, the info is either copied or read from a buffer
+        // when followed by the dynamic metadata.
+        if flags.has_info {
+            args.push(match flags.has_dynamic_meta {
+                true => format!("{address_space} const info_st* info_ptr"),
+                false => "const info_st info".to_string(),
+            });
+        }
+
+        for (i, arg) in args.iter().enumerate() {
+            let comma = if i > 0 { "," } else { "" };
+            write!(f, "{comma}\n    {arg}")?;
+        }
+        f.write_str("\n)")
+    }
+
+    fn compile_bindings_body(
+        f: &mut std::fmt::Formatter<'_>,
+        body: &shared::Body<Self>,
+    ) -> std::fmt::Result {
+        if !body.shared_memories.is_empty() {
+            let size = body
+                .shared_memories
+                .iter()
+                .map(|it| it.offset() + it.size())
+                .max()
+                .unwrap();
+            let align = body
+                .shared_memories
+                .iter()
+                .map(|it| it.align())
+                .max()
+                .unwrap();
+
+            writeln!(
+                f,
+                "{} uchar dynamic_shared_mem[{size}] __attribute__((aligned({align})));",
+                AddressSpace::Local
+            )?;
+        }
+        if body.has_dynamic_meta {
+            let address_space = AddressSpace::Global;
+            writeln!(f, "const info_st info = *info_ptr;")?;
+            writeln!(
+                f,
+                "const {address_space} {addr}* dynamic_meta = (const {address_space} {addr}*)(
+                    (const {address_space} char*)info_ptr + sizeof(info_st)
+                );\n",
+                addr = body.address_type,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Cube builtins dialect
+
+impl DialectCubeBuiltins<Self> for OpenClDialect {
+    /// Every position and dimension is given by a work-item function in `OpenCL`, so no builtin
+    /// depends on another one.
+    fn builtin_rules(flags: &CubeIndexFlags) -> CubeIndexFlags {
+        flags.clone()
+    }
+
+    fn compile_absolute_pos_tuple_computation(
+        _f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        // no need to compute it on OpenCL as there is a work-item function for each axis
+        Ok(())
+    }
+
+    fn compile_absolute_pos(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("absolute_pos")
+    }
+
+    fn compile_absolute_pos_x(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(uint)get_global_id(0)")
+    }
+
+    fn compile_absolute_pos_y(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(uint)get_global_id(1)")
+    }
+
+    fn compile_absolute_pos_z(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(uint)get_global_id(2)")
+    }
+
+    fn compile_cube_count(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("cube_count")
+    }
+
+    fn compile_cube_count_x(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(uint)get_num_groups(0)")
+    }
+
+    fn compile_cube_count_y(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(uint)get_num_groups(1)")
+    }
+
+    fn compile_cube_count_z(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(uint)get_num_groups(2)")
+    }
+
+    fn compile_cube_dim(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("cube_dim")
+    }
+
+    fn compile_cube_dim_x(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(uint)get_local_size(0)")
+    }
+
+    fn compile_cube_dim_y(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(uint)get_local_size(1)")
+    }
+
+    fn compile_cube_dim_z(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(uint)get_local_size(2)")
+    }
+
+    fn compile_cube_pos(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("cube_pos")
+    }
+
+    fn compile_cube_pos_x(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(uint)get_group_id(0)")
+    }
+
+    fn compile_cube_pos_y(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(uint)get_group_id(1)")
+    }
+
+    fn compile_cube_pos_z(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(uint)get_group_id(2)")
+    }
+
+    fn compile_unit_pos_computation(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let variable = Variable::<Self>::UnitPos;
+        let ty = variable.item();
+        writeln!(f, "{ty} {variable} = ({ty})get_local_linear_id();")
+    }
+
+    fn compile_unit_pos(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("unit_pos")
+    }
+
+    fn compile_unit_pos_x(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(uint)get_local_id(0)")
+    }
+
+    fn compile_unit_pos_y(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(uint)get_local_id(1)")
+    }
+
+    fn compile_unit_pos_z(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(uint)get_local_id(2)")
+    }
+
+    fn compile_plane_dim(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("get_sub_group_size()")
+    }
+
+    fn compile_plane_dim_checked(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("plane_dim_checked")
+    }
+
+    fn compile_plane_pos(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("get_sub_group_id()")
+    }
+
+    fn compile_unit_pos_plane(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("get_sub_group_local_id()")
+    }
+}
+
+// Instructions
+
+impl DialectInstructions<Self> for OpenClDialect {
+    // atomics
+    fn compile_atomic_add(
+        f: &mut std::fmt::Formatter<'_>,
+        lhs: &Variable<Self>,
+        rhs: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> std::fmt::Result {
+        let out = out.fmt_left();
+        writeln!(
+            f,
+            "{out} = atomic_fetch_add_explicit({lhs}, {rhs}, memory_order_relaxed);"
+        )
+    }
+
+    fn compile_atomic_and(
+        f: &mut std::fmt::Formatter<'_>,
+        lhs: &Variable<Self>,
+        rhs: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> std::fmt::Result {
+        let out = out.fmt_left();
+        writeln!(
+            f,
+            "{out} = atomic_fetch_and_explicit({lhs}, {rhs}, memory_order_relaxed);"
+        )
+    }
+
+    fn compile_atomic_cas(
+        f: &mut std::fmt::Formatter<'_>,
+        input: &Variable<Self>,
+        cmp: &Variable<Self>,
+        val: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> std::fmt::Result {
+        // The expected value is replaced by the previous one when the exchange fails, and is
+        // already equal to it otherwise.
+        let out_left = out.fmt_left();
+        writeln!(f, "{out_left} = {cmp};")?;
+        writeln!(
+            f,
+            "atomic_compare_exchange_strong_explicit({input}, &{out}, {val}, memory_order_relaxed, memory_order_relaxed);"
+        )
+    }
+
+    fn compile_atomic_load(
+        f: &mut std::fmt::Formatter<'_>,
+        input: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> std::fmt::Result {
+        let out = out.fmt_left();
+        writeln!(
+            f,
+            "{out} = atomic_load_explicit({input}, memory_order_relaxed);"
+        )
+    }
+
+    fn compile_atomic_max(
+        f: &mut std::fmt::Formatter<'_>,
+        lhs: &Variable<Self>,
+        rhs: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> std::fmt::Result {
+        let out = out.fmt_left();
+        writeln!(
+            f,
+            "{out} = atomic_fetch_max_explicit({lhs}, {rhs}, memory_order_relaxed);"
+        )
+    }
+
+    fn compile_atomic_min(
+        f: &mut std::fmt::Formatter<'_>,
+        lhs: &Variable<Self>,
+        rhs: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> std::fmt::Result {
+        let out = out.fmt_left();
+        writeln!(
+            f,
+            "{out} = atomic_fetch_min_explicit({lhs}, {rhs}, memory_order_relaxed);"
+        )
+    }
+
+    fn compile_atomic_or(
+        f: &mut std::fmt::Formatter<'_>,
+        lhs: &Variable<Self>,
+        rhs: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> std::fmt::Result {
+        let out = out.fmt_left();
+        writeln!(
+            f,
+            "{out} = atomic_fetch_or_explicit({lhs}, {rhs}, memory_order_relaxed);"
+        )
+    }
+
+    fn compile_atomic_store(
+        f: &mut std::fmt::Formatter<'_>,
+        input: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> std::fmt::Result {
+        writeln!(
+            f,
+            "atomic_store_explicit({out}, {input}, memory_order_relaxed);"
+        )
+    }
+
+    fn compile_atomic_sub(
+        f: &mut std::fmt::Formatter<'_>,
+        lhs: &Variable<Self>,
+        rhs: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> std::fmt::Result {
+        let out = out.fmt_left();
+        writeln!(
+            f,
+            "{out} = atomic_fetch_sub_explicit({lhs}, {rhs}, memory_order_relaxed);"
+        )
+    }
+
+    fn compile_atomic_swap(
+        f: &mut std::fmt::Formatter<'_>,
+        lhs: &Variable<Self>,
+        rhs: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> std::fmt::Result {
+        let out = out.fmt_left();
+        writeln!(
+            f,
+            "{out} = atomic_exchange_explicit({lhs}, {rhs}, memory_order_relaxed);"
+        )
+    }
+
+    fn compile_atomic_xor(
+        f: &mut std::fmt::Formatter<'_>,
+        lhs: &Variable<Self>,
+        rhs: &Variable<Self>,
+        out: &Variable<Self>,
+    ) -> std::fmt::Result {
+        let out = out.fmt_left();
+        writeln!(
+            f,
+            "{out} = atomic_fetch_xor_explicit({lhs}, {rhs}, memory_order_relaxed);"
+        )
+    }
+
+    fn compile_saturating_add(
+        f: &mut std::fmt::Formatter<'_>,
+        lhs: impl Display,
+        rhs: impl Display,
+        _item: Item<Self>,
+    ) -> std::fmt::Result {
+        write!(f, "add_sat({lhs}, {rhs})")
+    }
+
+    fn compile_saturating_sub(
+        f: &mut std::fmt::Formatter<'_>,
+        lhs: impl Display,
+        rhs: impl Display,
+        _item: Item<Self>,
+    ) -> std::fmt::Result {
+        write!(f, "sub_sat({lhs}, {rhs})")
+    }
+
+    // sync
+    fn compile_instruction_sync_threads(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "work_group_barrier(CLK_LOCAL_MEM_FENCE | CLK_GLOBAL_MEM_FENCE);"
+        )
+    }
+
+    fn compile_instruction_sync_warp(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "sub_group_barrier(CLK_LOCAL_MEM_FENCE);")
+    }
+
+    fn compile_instruction_thread_fence(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "atomic_work_item_fence(CLK_GLOBAL_MEM_FENCE, memory_order_seq_cst, memory_scope_device);"
+        )
+    }
+
+    // unary
+    fn compile_instruction_find_first_set<T: Component<Self>>(
+        f: &mut std::fmt::Formatter<'_>,
+        input: T,
+        out_elem: Elem<Self>,
+    ) -> std::fmt::Result {
+        write!(f, "({out_elem})({input} == 0 ? 0 : ctz({input}) + 1)")
+    }
+
+    fn compile_instruction_leading_zeros_scalar<T: Component<Self>>(
+        f: &mut std::fmt::Formatter<'_>,
+        input: T,
+        out_elem: Elem<Self>,
+    ) -> std::fmt::Result {
+        write!(f, "({out_elem})clz({input})")
+    }
+
+    fn compile_instruction_trailing_zeros_scalar<T: Component<Self>>(
+        f: &mut std::fmt::Formatter<'_>,
+        input: T,
+        out_elem: Elem<Self>,
+    ) -> std::fmt::Result {
+        write!(f, "({out_elem})ctz({input})")
+    }
+
+    fn compile_instruction_popcount_scalar<T: Component<Self>>(
+        f: &mut std::fmt::Formatter<'_>,
+        input: T,
+        out_elem: Elem<Self>,
+    ) -> std::fmt::Result {
+        write!(f, "({out_elem})popcount({input})")
+    }
+
+    fn compile_instruction_reverse_bits_scalar<T: Component<Self>>(
+        f: &mut std::fmt::Formatter<'_>,
+        input: T,
+        out_elem: Elem<Self>,
+    ) -> std::fmt::Result {
+        write!(f, "({out_elem})(")?;
+        match out_elem {
+            Elem::I32 | Elem::U32 => write!(f, "reverse_bits_u32((uint)({input}))"),
+            Elem::I64 | Elem::U64 => write!(f, "reverse_bits_u64((ulong)({input}))"),
+            _ => write!(
+                f,
+                "reverse_bits_u32({}) >> {}",
+                shared::unary::zero_extend(input),
+                (size_of::<u32>() - out_elem.size()) * 8
+            ),
+        }?;
+        write!(f, ")")
+    }
+
+    // others
+    fn compile_instruction_max_function_name(
+        f: &mut std::fmt::Formatter<'_>,
+        _item: Item<Self>,
+    ) -> std::fmt::Result {
+        write!(f, "max")
+    }
+
+    fn compile_instruction_min_function_name(
+        f: &mut std::fmt::Formatter<'_>,
+        _item: Item<Self>,
+    ) -> std::fmt::Result {
+        write!(f, "min")
+    }
+
+    fn compile_instruction_powf(
+        f: &mut std::fmt::Formatter<'_>,
+        lhs: &str,
+        rhs: &str,
+        elem: Elem<Self>,
+    ) -> std::fmt::Result {
+        write!(f, "pow({lhs}, ({elem})({rhs}))")
+    }
+
+    fn compile_instruction_hypot(
+        f: &mut std::fmt::Formatter<'_>,
+        lhs: &str,
+        rhs: &str,
+        _elem: Elem<Self>,
+    ) -> std::fmt::Result {
+        write!(f, "hypot({lhs}, {rhs})")
+    }
+
+    fn compile_instruction_rhypot(
+        f: &mut std::fmt::Formatter<'_>,
+        lhs: &str,
+        rhs: &str,
+        _elem: Elem<Self>,
+    ) -> std::fmt::Result {
+        write!(f, "rsqrt({lhs} * {lhs} + {rhs} * {rhs})")
+    }
+
+    fn compile_instruction_half_function_name_prefix() -> &'static str {
+        ""
+    }
+
+    fn compile_instruction_half2_function_name_prefix() -> &'static str {
+        ""
+    }
+
+    // Warp
+    fn compile_warp_shuffle(
+        f: &mut std::fmt::Formatter<'_>,
+        var: &str,
+        source: &str,
+    ) -> std::fmt::Result {
+        write!(f, "sub_group_shuffle({var}, {source})")
+    }
+
+    fn compile_warp_shuffle_xor(
+        f: &mut std::fmt::Formatter<'_>,
+        var: &str,
+        _elem: &Elem<Self>,
+        offset: &str,
+    ) -> std::fmt::Result {
+        write!(f, "sub_group_shuffle_xor({var}, {offset})")
+    }
+
+    fn compile_warp_shuffle_up(
+        f: &mut std::fmt::Formatter<'_>,
+        var: &str,
+        offset: &str,
+    ) -> std::fmt::Result {
+        write!(f, "sub_group_shuffle_up({var}, {offset})")
+    }
+
+    fn compile_warp_shuffle_down(
+        f: &mut std::fmt::Formatter<'_>,
+        var: &str,
+        offset: &str,
+    ) -> std::fmt::Result {
+        write!(f, "sub_group_shuffle_down({var}, {offset})")
+    }
+
+    fn compile_warp_all<T: Component<Self>>(
+        f: &mut std::fmt::Formatter<'_>,
+        input: &T,
+    ) -> std::fmt::Result {
+        write!(f, "(sub_group_all({input}) != 0)")
+    }
+
+    fn compile_warp_any<T: Component<Self>>(
+        f: &mut std::fmt::Formatter<'_>,
+        input: &T,
+    ) -> std::fmt::Result {
+        write!(f, "(sub_group_any({input}) != 0)")
+    }
+
+    fn compile_warp_ballot(
+        f: &mut std::fmt::Formatter<'_>,
+        input: &Variable<Self>,
+        out_elem: &Elem<Self>,
+    ) -> std::fmt::Result {
+        // Only the first component is used by sub-groups of 32 units or less
+        write!(f, "({out_elem})sub_group_ballot({input}).x")
+    }
+
+    fn compile_warp_elect(f: &mut std::fmt::Formatter<'_>, out: &str) -> std::fmt::Result {
+        writeln!(f, "{out} = sub_group_elect();")
+    }
+
+    fn compile_unreachable(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "__builtin_unreachable();")
+    }
+}
+
+// Coop Matrices dialect
+
+impl DialectWmmaCompiler<Self> for OpenClDialect {
+    fn compile_wmma_fragment_declaration(
+        f: &mut std::fmt::Formatter<'_>,
+        _var: &Variable<Self>,
+    ) -> std::fmt::Result {
+        f.write_str("#error WMMA not supported on OpenCL\n")
+    }
+
+    fn compile_wmma_instruction(
+        f: &mut std::fmt::Formatter<'_>,
+        _instruction: &WmmaInstruction<Self>,
+    ) -> std::fmt::Result {
+        f.write_str("#error WMMA not supported on OpenCL\n")
+    }
+
+    fn compile_manual_mma(
+        f: &mut std::fmt::Formatter<'_>,
+        _mma: shared::ManualMma<Self>,
+    ) -> std::fmt::Result {
+        f.write_str("#error manual mma not supported on OpenCL\n")
+    }
+
+    fn compile_scaled_mma(
+        f: &mut std::fmt::Formatter<'_>,
+        _mma: shared::ManualMma<Self>,
+        _scales_a: Variable<Self>,
+        _scales_b: Variable<Self>,
+        _scales_factor: u32,
+    ) -> std::fmt::Result {
+        f.write_str("#error scaled mma not supported on OpenCL\n")
+    }
+
+    fn supported_wmma_combinations(_arch: &OpenClArchitecture) -> SupportedMmaCombinations {
+        Vec::new()
+    }
+
+    fn supported_mma_combinations(_arch: &OpenClArchitecture) -> SupportedMmaCombinations {
+        Vec::new()
+    }
+}
+
+impl DialectProcessors<Self> for OpenClDialect {
+    fn processors() -> Vec<Box<dyn gpu::Processor>> {
+        Vec::new()
+    }
+}
+
+/// Registers the types supported by `OpenCL` C. Half and double precision floats depend on the
+/// `cl_khr_fp16` and `cl_khr_fp64` extensions of the device, and only integer atomics can be
+/// added without vendor extensions. Barrier objects, `bf16` and the minifloats are unsupported.
+pub fn register_supported_types(props: &mut DeviceProperties, fp16: bool, fp64: bool) {
+    props.register_address_type(gpu::AddressType::U32);
+    props.register_address_type(gpu::AddressType::U64);
+
+    let mut supported_types = vec![
+        gpu::ElemType::UInt(gpu::UIntKind::U8),
+        gpu::ElemType::UInt(gpu::UIntKind::U16),
+        gpu::ElemType::UInt(gpu::UIntKind::U32),
+        gpu::ElemType::UInt(gpu::UIntKind::U64),
+        gpu::ElemType::Int(gpu::IntKind::I8),
+        gpu::ElemType::Int(gpu::IntKind::I16),
+        gpu::ElemType::Int(gpu::IntKind::I32),
+        gpu::ElemType::Int(gpu::IntKind::I64),
+        gpu::ElemType::Float(gpu::FloatKind::F32),
+        gpu::ElemType::Float(gpu::FloatKind::Flex32),
+        gpu::ElemType::Bool,
+    ];
+    if fp16 {
+        supported_types.push(gpu::ElemType::Float(gpu::FloatKind::F16));
+    }
+    if fp64 {
+        supported_types.push(gpu::ElemType::Float(gpu::FloatKind::F64));
+    }
+
+    for ty in supported_types {
+        props.register_type_usage(ty, TypeUsage::all());
+    }
+
+    let supported_atomic_types = [
+        gpu::ElemType::Int(gpu::IntKind::I32),
+        gpu::ElemType::Int(gpu::IntKind::I64),
+        gpu::ElemType::UInt(gpu::UIntKind::U32),
+        gpu::ElemType::UInt(gpu::UIntKind::U64),
+    ];
+    for ty in supported_atomic_types {
+        props.register_atomic_type_usage(
+            gpu::Type::new(gpu::StorageType::Atomic(ty)),
+            AtomicUsage::all(),
+        );
+    }
+    props.register_atomic_type_usage(
+        gpu::Type::new(gpu::StorageType::Atomic(gpu::ElemType::Float(
+            gpu::FloatKind::F32,
+        ))),
+        AtomicUsage::LoadStore,
+    );
+}
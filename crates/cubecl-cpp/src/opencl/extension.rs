@@ -0,0 +1,62 @@
+use crate::{Dialect, shared::Elem};
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Extension<D: Dialect> {
+    MulHi(Elem<D>),
+    ReverseBits(Elem<D>),
+    #[default]
+    NoExtension,
+}
+
+/// The shared compiler uses the CUDA names, `OpenCL` has an overloaded `mul_hi` built-in instead.
+pub fn format_mulhi<D: Dialect>(
+    f: &mut core::fmt::Formatter<'_>,
+    out_elem: &Elem<D>,
+) -> core::fmt::Result {
+    let name = match out_elem {
+        Elem::I32 => "__mulhi",
+        Elem::U32 => "__umulhi",
+        Elem::I64 => "__mul64hi",
+        Elem::U64 => "__umul64hi",
+        _ => return Ok(()),
+    };
+    write!(
+        f,
+        "
+static inline {out_elem} {name}({out_elem} a, {out_elem} b) {{
+    return mul_hi(a, b);
+}}
+"
+    )
+}
+
+/// `OpenCL` C doesn't support overloading, the functions are suffixed by their type.
+pub fn format_reverse_bits<D: Dialect>(
+    f: &mut core::fmt::Formatter<'_>,
+    elem: &Elem<D>,
+) -> core::fmt::Result {
+    match elem {
+        Elem::U32 => write!(
+            f,
+            "
+static inline uint reverse_bits_u32(uint x) {{
+    x = ((x >> 1) & 0x55555555u) | ((x & 0x55555555u) << 1);
+    x = ((x >> 2) & 0x33333333u) | ((x & 0x33333333u) << 2);
+    x = ((x >> 4) & 0x0F0F0F0Fu) | ((x & 0x0F0F0F0Fu) << 4);
+    x = ((x >> 8) & 0x00FF00FFu) | ((x & 0x00FF00FFu) << 8);
+    return (x >> 16) | (x << 16);
+}}
+"
+        ),
+        Elem::U64 => write!(
+            f,
+            "
+static inline ulong reverse_bits_u64(ulong x) {{
+    return ((ulong)reverse_bits_u32((uint)x) << 32) | (ulong)reverse_bits_u32((uint)(x >> 32));
+}}
+"
+        ),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,11 @@
+pub mod address_space;
+pub mod arch;
+pub mod dialect;
+mod extension;
+
+pub use address_space::*;
+pub use dialect::*;
+use extension::*;
+
+#[cfg(test)]
+mod tests;
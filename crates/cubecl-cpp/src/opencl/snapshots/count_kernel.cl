@@ -0,0 +1,15 @@
+
+struct info_st {
+    uint static_meta[2];
+};
+typedef struct info_st info_st;
+
+__kernel __attribute__((reqd_work_group_size(32, 1, 1)))
+void count_kernel(
+    __global atomic_uint* buffer_0,
+    const info_st info
+) {
+__global atomic_uint* l_0 = &buffer_0[((uint)(0))];
+const uint l_1 = atomic_fetch_add_explicit(l_0, ((uint)(1)), memory_order_relaxed);
+
+}
\ No newline at end of file
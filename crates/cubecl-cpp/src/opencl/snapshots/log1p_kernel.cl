@@ -0,0 +1,22 @@
+#pragma OPENCL EXTENSION cl_khr_fp64 : enable
+
+struct info_st {
+    uint static_meta[4];
+};
+typedef struct info_st info_st;
+
+__kernel __attribute__((reqd_work_group_size(64, 1, 1)))
+void log1p_kernel(
+    __global const double* buffer_0,
+    __global double* buffer_1,
+    const info_st info
+) {
+uint absolute_pos = (
+                (uint)get_global_id(2) * (uint)get_num_groups(0) * (uint)get_local_size(0) * (uint)get_num_groups(1) * (uint)get_local_size(1))
+                + ((uint)get_global_id(1) * (uint)get_num_groups(0) * (uint)get_local_size(0))
+                + (uint)get_global_id(0);
+const double l_0 = buffer_0[absolute_pos];
+const double l_1 = log1p(l_0);
+buffer_1[absolute_pos] = l_1;
+
+}
\ No newline at end of file
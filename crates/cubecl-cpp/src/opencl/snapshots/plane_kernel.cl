@@ -0,0 +1,30 @@
+#pragma OPENCL EXTENSION cl_khr_subgroups : enable
+#pragma OPENCL EXTENSION cl_khr_subgroup_shuffle : enable
+#pragma OPENCL EXTENSION cl_khr_subgroup_shuffle_relative : enable
+#pragma OPENCL EXTENSION cl_khr_subgroup_ballot : enable
+#pragma OPENCL EXTENSION cl_khr_subgroup_non_uniform_vote : enable
+#pragma OPENCL EXTENSION cl_khr_subgroup_non_uniform_arithmetic : enable
+
+struct info_st {
+    uint static_meta[4];
+};
+typedef struct info_st info_st;
+
+__kernel __attribute__((reqd_work_group_size(128, 1, 1)))
+void plane_kernel(
+    __global const float* buffer_0,
+    __global float* buffer_1,
+    const info_st info
+) {
+uint unit_pos = (uint)get_local_linear_id();
+uint plane_dim_checked = min(get_sub_group_size(), (uint)get_local_size(0) * (uint)get_local_size(1) * (uint)get_local_size(2));
+const float l_0 = buffer_0[unit_pos];
+const float l_1 = (float){sub_group_reduce_add(l_0)
+};
+const bool l_2 = sub_group_elect();
+if (l_2) {
+const uint l_3 = unit_pos / get_sub_group_size();
+buffer_1[l_3] = l_1;
+}
+
+}
\ No newline at end of file
@@ -0,0 +1,33 @@
+
+struct info_st {
+    uint static_meta[4];
+};
+typedef struct info_st info_st;
+
+__kernel __attribute__((reqd_work_group_size(1, 1, 1)))
+void prefix_sum_kernel(
+    __global const float* buffer_0,
+    __global float* buffer_1,
+    const info_st info
+) {
+float l_mut_6;
+uint l_mut_7;
+float l_mut_8;
+uint l_mut_9;
+const uint l_1 = info.static_meta[((uint)(2))];
+l_mut_6 = ((float)(0.0));
+l_mut_7 = ((uint)(0));
+while (true) {
+const bool l_4 = l_mut_7 < l_1;
+const bool l_5 = !l_4;
+if (l_5) {
+break;}
+const float l_3 = buffer_0[l_mut_7];
+l_mut_8 = l_mut_6 + l_3;
+buffer_1[l_mut_7] = l_mut_8;
+l_mut_9 = l_mut_7 + ((int)(1));
+l_mut_6 = l_mut_8;
+l_mut_7 = l_mut_9;
+}
+
+}
\ No newline at end of file
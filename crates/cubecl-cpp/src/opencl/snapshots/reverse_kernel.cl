@@ -0,0 +1,24 @@
+
+struct info_st {
+    uint static_meta[4];
+};
+typedef struct info_st info_st;
+
+__kernel __attribute__((reqd_work_group_size(64, 1, 1)))
+void reverse_kernel(
+    __global const float* buffer_0,
+    __global float* buffer_1,
+    const info_st info
+) {
+uint unit_pos = (uint)get_local_linear_id();
+__local uchar dynamic_shared_mem[256] __attribute__((aligned(4)));
+// Shared array size: 64, 256 bytes
+__local float* shared_memory_0 = (__local float*)&dynamic_shared_mem[0];
+const float l_1 = buffer_0[unit_pos];
+shared_memory_0[unit_pos] = l_1;
+work_group_barrier(CLK_LOCAL_MEM_FENCE | CLK_GLOBAL_MEM_FENCE);
+const uint l_2 = ((uint)(63)) - unit_pos;
+const float l_3 = shared_memory_0[l_2];
+buffer_1[unit_pos] = l_3;
+
+}
\ No newline at end of file
@@ -0,0 +1,38 @@
+
+typedef struct __attribute__((aligned(16))) float_4 {
+    float i_0;
+    float i_1;
+    float i_2;
+    float i_3;
+} float_4;
+
+typedef struct __attribute__((aligned(16))) uint_4 {
+    uint i_0;
+    uint i_1;
+    uint i_2;
+    uint i_3;
+} uint_4;
+
+struct info_st {
+    uint static_meta[6];
+};
+typedef struct info_st info_st;
+
+__kernel __attribute__((reqd_work_group_size(64, 1, 1)))
+void scale_bits_kernel(
+    __global const float_4* buffer_0,
+    __global float_4* buffer_1,
+    __global uint_4* buffer_2,
+    const info_st info
+) {
+uint unit_pos = (uint)get_local_linear_id();
+const float_4 l_0 = buffer_0[unit_pos];
+const float_4 l_1 = (float_4){
+((float)(2.0)),((float)(2.0)),((float)(2.0)),((float)(2.0)),};
+const float_4 l_2 = (float_4){
+l_0.i_0 * l_1.i_0, l_0.i_1 * l_1.i_1, l_0.i_2 * l_1.i_2, l_0.i_3 * l_1.i_3, };
+buffer_1[unit_pos] = (*(__private float_4 const*)&(l_2));
+const uint_4 l_3 = (*( uint_4 const*)&(l_0));
+buffer_2[unit_pos] = (*(__private uint_4 const*)&(l_3));
+
+}
\ No newline at end of file
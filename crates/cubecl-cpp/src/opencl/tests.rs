@@ -0,0 +1,233 @@
+use cubecl_core::{self as cubecl, ir::AddressType, prelude::*};
+use cubecl_runtime::compiler::{CompilationError, Compiler};
+use half::f16;
+
+use crate::{
+    OpenClCompiler,
+    shared::{CompilationOptions, CppSupportedFeatures},
+};
+
+#[cube]
+fn double(input: &Array<f32>, output: &mut Array<f32>) {
+    output[ABSOLUTE_POS] = input[ABSOLUTE_POS] * 2.0;
+}
+
+#[cube]
+fn count(output: &mut Array<Atomic<u32>>) {
+    output[0].fetch_add(1u32);
+}
+
+#[cube]
+fn plane_total(input: &Array<f32>, output: &mut Array<f32>) {
+    let total = plane_sum(input[UNIT_POS as usize]);
+    if plane_elect() {
+        output[(UNIT_POS / PLANE_DIM) as usize] = total;
+    }
+}
+
+#[cube]
+fn reverse(input: &Array<f32>, output: &mut Array<f32>) {
+    let mut shared = SharedMemory::<f32>::new(64usize);
+    shared[UNIT_POS as usize] = input[UNIT_POS as usize];
+    sync_cube();
+    output[UNIT_POS as usize] = shared[63 - UNIT_POS as usize];
+}
+
+#[cube]
+fn scale_bits(
+    input: &Array<Vector<f32, Const<4>>>,
+    output: &mut Array<Vector<f32, Const<4>>>,
+    bits: &mut Array<Vector<u32, Const<4>>>,
+) {
+    let value = input[UNIT_POS as usize];
+    output[UNIT_POS as usize] = value * Vector::new(2.0);
+    bits[UNIT_POS as usize] = Vector::reinterpret(value);
+}
+
+#[cube]
+fn log1p_f64(input: &Array<f64>, output: &mut Array<f64>) {
+    output[ABSOLUTE_POS] = f64::log1p(input[ABSOLUTE_POS]);
+}
+
+#[cube]
+fn count_half(output: &mut Array<Atomic<f16>>) {
+    output[0].fetch_add(f16::new(1.0));
+}
+
+#[cube]
+fn prefix_sum(input: &Array<f32>, output: &mut Array<f32>) {
+    let mut acc = 0.0;
+    for i in 0..input.len() {
+        acc += input[i];
+        output[i] = acc;
+    }
+}
+
+fn compile(name: &str, cube_dim: CubeDim, expand: impl FnOnce(&mut KernelBuilder)) -> String {
+    compile_with(name, cube_dim, false, expand)
+}
+
+fn compile_with(
+    name: &str,
+    cube_dim: CubeDim,
+    optimize: bool,
+    expand: impl FnOnce(&mut KernelBuilder),
+) -> String {
+    try_compile(name, cube_dim, optimize, expand)
+        .unwrap()
+        .to_string()
+}
+
+fn try_compile(
+    name: &str,
+    cube_dim: CubeDim,
+    optimize: bool,
+    expand: impl FnOnce(&mut KernelBuilder),
+) -> Result<String, CompilationError> {
+    let mut builder = KernelBuilder::default();
+    AddressType::U32.register(&mut builder.scope);
+    expand(&mut builder);
+    let definition = builder.build(
+        KernelSettings::default()
+            .kernel_name(name)
+            .cube_dim(cube_dim),
+    );
+    let options = CompilationOptions {
+        supports_features: CppSupportedFeatures {
+            elect_sync: true,
+            ..Default::default()
+        },
+        optimize,
+        ..Default::default()
+    };
+    OpenClCompiler::default()
+        .compile(
+            definition,
+            &options,
+            ExecutionMode::Unchecked,
+            AddressType::U32.unsigned_type(),
+        )
+        .map(|kernel| kernel.to_string())
+}
+
+fn array_args(builder: &mut KernelBuilder) -> (NativeExpand<Array<f32>>, NativeExpand<Array<f32>>) {
+    let ty = f32::as_type(&builder.scope);
+    let input = builder.input_array(ty).into();
+    let output = builder.output_array(ty).into();
+    (input, output)
+}
+
+/// Compares the emitted `source` to the snapshot `name` in `src/opencl/snapshots`, as a whole.
+///
+/// Run the tests with `CUBECL_UPDATE_SNAPSHOTS=1` to write the snapshots after a change of the
+/// emitted code, then review their diff.
+fn assert_snapshot(name: &str, source: &str) {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/opencl/snapshots")
+        .join(name);
+
+    if std::env::var("CUBECL_UPDATE_SNAPSHOTS").is_ok() {
+        std::fs::write(&path, source).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("Can't read the snapshot {}: {err}", path.display()));
+    assert_eq!(
+        source, expected,
+        "The emitted source doesn't match the snapshot {name}"
+    );
+}
+
+#[test]
+fn elementwise_kernel() {
+    let source = compile("double_kernel", CubeDim::new_1d(64), |builder| {
+        let (input, output) = array_args(builder);
+        double::expand(&mut builder.scope, input, output);
+    });
+
+    assert_snapshot("double_kernel.cl", &source);
+}
+
+#[test]
+fn atomic_kernel() {
+    let source = compile("count_kernel", CubeDim::new_1d(32), |builder| {
+        let ty = Atomic::<u32>::as_type(&builder.scope);
+        let output = builder.output_array(ty).into();
+        count::expand(&mut builder.scope, output);
+    });
+
+    assert_snapshot("count_kernel.cl", &source);
+}
+
+#[test]
+fn plane_kernel() {
+    let source = compile("plane_kernel", CubeDim::new_1d(128), |builder| {
+        let (input, output) = array_args(builder);
+        plane_total::expand(&mut builder.scope, input, output);
+    });
+
+    assert_snapshot("plane_kernel.cl", &source);
+}
+
+#[test]
+fn shared_memory_kernel() {
+    let source = compile("reverse_kernel", CubeDim::new_1d(64), |builder| {
+        let (input, output) = array_args(builder);
+        reverse::expand(&mut builder.scope, input, output);
+    });
+
+    assert_snapshot("reverse_kernel.cl", &source);
+}
+
+#[test]
+fn optimized_loop_kernel() {
+    let source = compile_with("prefix_sum_kernel", CubeDim::new_1d(1), true, |builder| {
+        let (input, output) = array_args(builder);
+        prefix_sum::expand(&mut builder.scope, input, output);
+    });
+
+    // The range loop is lowered to a loop with an explicit break.
+    assert_snapshot("prefix_sum_kernel.cl", &source);
+}
+
+#[test]
+fn vectorized_kernel_is_c() {
+    let source = compile("scale_bits_kernel", CubeDim::new_1d(64), |builder| {
+        let ty = Vector::<f32, Const<4>>::as_type(&builder.scope);
+        let bits_ty = Vector::<u32, Const<4>>::as_type(&builder.scope);
+        let input = builder.input_array(ty).into();
+        let output = builder.output_array(ty).into();
+        let bits = builder.output_array(bits_ty).into();
+        scale_bits::expand(&mut builder.scope, input, output, bits);
+    });
+
+    // Vectors are C structs, without C++ casts or constructor-style conversions.
+    assert_snapshot("scale_bits_kernel.cl", &source);
+}
+
+#[test]
+fn f64_kernel_enables_extension() {
+    let source = compile("log1p_kernel", CubeDim::new_1d(64), |builder| {
+        let ty = f64::as_type(&builder.scope);
+        let input = builder.input_array(ty).into();
+        let output = builder.output_array(ty).into();
+        log1p_f64::expand(&mut builder.scope, input, output);
+    });
+
+    assert_snapshot("log1p_kernel.cl", &source);
+}
+
+#[test]
+fn unsupported_atomic_is_compilation_error() {
+    let result = try_compile("count_half_kernel", CubeDim::new_1d(32), false, |builder| {
+        let ty = Atomic::<f16>::as_type(&builder.scope);
+        let output = builder.output_array(ty).into();
+        count_half::expand(&mut builder.scope, output);
+    });
+
+    assert!(
+        matches!(result, Err(CompilationError::UnsupportedInstruction { .. })),
+        "{result:?}"
+    );
+}
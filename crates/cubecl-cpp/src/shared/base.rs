@@ -17,20 +17,24 @@ use cubecl_core::{
     prelude::{FastMath, KernelDefinition},
     server::ExecutionMode,
 };
-use cubecl_opt::{Optimizer, SharedLiveness};
+use cubecl_opt::{Optimizer, SharedLiveness, UnrollOptions};
 use cubecl_runtime::compiler::{CompilationError, Compiler};
 use std::{collections::HashSet, fmt::Debug};
 
 pub(super) static COUNTER_TMP_VAR: std::sync::atomic::AtomicU32 =
     std::sync::atomic::AtomicU32::new(0);
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Hash)]
 pub struct CompilationOptions {
     pub warp_size: u32,
     pub supports_features: CppSupportedFeatures,
+    /// Compile kernels from the output of the full optimizer instead of the unoptimized IR
+    pub optimize: bool,
+    /// Partially unroll the loops with a trip count known at compile time when optimizing
+    pub unroll_loops: bool,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Hash)]
 pub struct CppSupportedFeatures {
     pub grid_constants: bool,
     pub clusters: bool,
@@ -44,6 +48,8 @@ impl Default for CompilationOptions {
         Self {
             warp_size: 32,
             supports_features: Default::default(),
+            optimize: false,
+            unroll_loops: false,
         }
     }
 }
@@ -77,6 +83,7 @@ pub struct Flags<D: Dialect> {
     pub elem_fp8: bool,
     pub elem_bf16: bool,
     pub elem_f16: bool,
+    pub elem_f64: bool,
     pub elem_tf32: bool,
     pub indexes: CubeIndexFlags,
     pub op_barrier: bool,
@@ -123,6 +130,7 @@ impl<D: Dialect> Default for Flags<D> {
             elem_fp8: Default::default(),
             elem_bf16: Default::default(),
             elem_f16: Default::default(),
+            elem_f64: Default::default(),
             elem_tf32: Default::default(),
             indexes: Default::default(),
             op_barrier: Default::default(),
@@ -202,6 +210,15 @@ impl<D: Dialect> Compiler for CppCompiler<D> {
 
         let ir = self.clone().compile_ir(kernel, addr_type);
         COUNTER_TMP_VAR.store(0, std::sync::atomic::Ordering::Relaxed);
+
+        let elems = ir.items.iter().map(|item| item.elem);
+        let elems = elems.chain(ir.scalars.iter().map(|(elem, _)| *elem));
+        if let Some(reason) = elems.filter_map(|elem| D::unsupported_elem(&elem)).next() {
+            return Err(CompilationError::UnsupportedInstruction {
+                reason,
+                backtrace: BackTrace::capture(),
+            });
+        }
         Ok(ir)
     }
 
@@ -223,7 +240,24 @@ impl<D: Dialect> CppCompiler<D> {
         let metadata = self.build_metadata(&value);
         self.info = cubecl_core::Info::new(&value.scalars, metadata, address_type);
 
-        let instructions = self.compile_scope(&mut value.body.clone());
+        let (instructions, mut opt) = if self.compilation_options.optimize {
+            let mut opt = Optimizer::with_unroll(
+                value.body.clone(),
+                value.cube_dim,
+                vec![],
+                self.processors(),
+                self.compilation_options
+                    .unroll_loops
+                    .then(UnrollOptions::default),
+            );
+            (self.compile_scope(&mut opt.structured_scope()), opt)
+        } else {
+            let instructions = self.compile_scope(&mut value.body.clone());
+            (
+                instructions,
+                Optimizer::shared_only(value.body, value.cube_dim),
+            )
+        };
         let tensor_maps = value
             .tensor_maps
             .into_iter()
@@ -251,6 +285,7 @@ impl<D: Dialect> CppCompiler<D> {
             elem_fp8: self.flags.elem_fp8,
             elem_bf16: self.flags.elem_bf16,
             elem_f16: self.flags.elem_f16,
+            elem_f64: self.flags.elem_f64,
             elem_tf32: self.flags.elem_tf32,
             inst_tma: self.flags.inst_tma,
             inst_tma_im2col: self.flags.inst_tma_im2col,
@@ -265,7 +300,6 @@ impl<D: Dialect> CppCompiler<D> {
             address_type: self.compile_type(address_type.into()),
         };
 
-        let mut opt = Optimizer::shared_only(value.body, value.cube_dim);
         let shared_allocs = opt.analysis::<SharedLiveness>();
         let shared_memories = shared_allocs
             .allocations
@@ -372,15 +406,12 @@ impl<D: Dialect> CppCompiler<D> {
             .collect::<Vec<_>>();
         self.const_arrays.extend(const_arrays);
 
-        let checked_io: Box<dyn Processor> = Box::new(CheckedIoProcessor::new(
-            self.strategy,
-            self.kernel_name.clone(),
-        ));
-        let dialect_processors = D::processors();
-        let mut processors: Vec<&dyn Processor> = vec![&*checked_io];
-        processors.extend(dialect_processors.iter().map(|it| &**it));
-
-        let processing = scope.process(processors);
+        // The optimizer already applied the processors
+        let processors = match self.compilation_options.optimize {
+            true => Vec::new(),
+            false => self.processors(),
+        };
+        let processing = scope.process(processors.iter().map(|it| &**it));
 
         for var in processing.variables {
             instructions.push(Instruction::DeclareVariable {
@@ -396,6 +427,16 @@ impl<D: Dialect> CppCompiler<D> {
         instructions
     }
 
+    fn processors(&self) -> Vec<Box<dyn Processor>> {
+        let checked_io: Box<dyn Processor> = Box::new(CheckedIoProcessor::new(
+            self.strategy,
+            self.kernel_name.clone(),
+        ));
+        let mut processors = vec![checked_io];
+        processors.extend(D::processors());
+        processors
+    }
+
     fn compile_instruction(
         &mut self,
         instructions: &mut Vec<Instruction<D>>,
@@ -1070,6 +1111,7 @@ impl<D: Dialect> CppCompiler<D> {
             }),
             gpu::Branch::Return => instructions.push(Instruction::Return),
             gpu::Branch::Break => instructions.push(Instruction::Break),
+            gpu::Branch::Continue => instructions.push(Instruction::Continue),
             gpu::Branch::Unreachable => instructions.push(Instruction::Unreachable),
             gpu::Branch::RangeLoop(mut range_loop) => instructions.push(Instruction::RangeLoop {
                 i: self.compile_variable(range_loop.i),
@@ -1512,7 +1554,9 @@ impl<D: Dialect> CppCompiler<D> {
                 instructions.push(Instruction::CountBits(self.compile_unary(op, out)))
             }
             gpu::Bitwise::ReverseBits(op) => {
-                instructions.push(Instruction::ReverseBits(self.compile_unary(op, out)))
+                let instruction = Instruction::ReverseBits(self.compile_unary(op, out));
+                D::register_instruction_extension(&mut self.extensions, &instruction);
+                instructions.push(instruction)
             }
             gpu::Bitwise::ShiftLeft(op) => {
                 instructions.push(Instruction::ShiftLeft(self.compile_binary(op, out)))
@@ -2050,7 +2094,10 @@ impl<D: Dialect> CppCompiler<D> {
                 gpu::FloatKind::TF32 => Elem::TF32,
                 gpu::FloatKind::Flex32 => Elem::F32,
                 gpu::FloatKind::F32 => Elem::F32,
-                gpu::FloatKind::F64 => Elem::F64,
+                gpu::FloatKind::F64 => {
+                    self.flags.elem_f64 = true;
+                    Elem::F64
+                }
             },
             gpu::ElemType::Int(kind) => match kind {
                 gpu::IntKind::I8 => Elem::I8,
@@ -1,6 +1,6 @@
 use crate::shared::FmtLeft;
 
-use super::{Component, Dialect, Elem, Item, Variable};
+use super::{Component, Dialect, Elem, FmtWith, Item, Variable};
 use std::{
     fmt::{Display, Formatter},
     marker::PhantomData,
@@ -54,7 +54,7 @@ pub trait Binary<D: Dialect> {
         let mut write_op =
             |lhs: &Variable<D>, rhs: &Variable<D>, out: &Variable<D>, item_out: Item<D>| {
                 let out = out.fmt_left();
-                writeln!(f, "{out} = {item_out}{{")?;
+                writeln!(f, "{out} = {}{{", D::aggregate_type(item_out))?;
                 for i in 0..index {
                     let lhsi = lhs.index(i);
                     let rhsi = rhs.index(i);
@@ -76,7 +76,8 @@ pub trait Binary<D: Dialect> {
 
             writeln!(
                 f,
-                "{out} = reinterpret_cast<{addr_space}{item_out_original}&>({out_tmp});\n"
+                "{out} = {};\n",
+                D::reference_cast(format!("{addr_space}{item_out_original}"), out_tmp)
             )?;
 
             Ok(())
@@ -101,7 +102,7 @@ macro_rules! operator {
                     // this is because of fusion and vectorization that can do elemwise operations on vectorized type,
                     // the resulting elements need to be of the same type.
                     Elem::<D>::I16 | Elem::<D>::U16 | Elem::<D>::I8 | Elem::<D>::U8 => {
-                        write!(f, "{out_elem}({lhs} {} {rhs})", $op)
+                        f.write_str(&D::cast(out_elem, format!("{lhs} {} {rhs}", $op)))
                     }
                     _ => write!(f, "{lhs} {} {rhs}", $op),
                 }
@@ -174,7 +175,7 @@ impl<D: Dialect> Binary<D> for HiMul {
         let index = out.item().vectorization;
 
         let out = out.fmt_left();
-        writeln!(f, "{out} = {item_out}{{")?;
+        writeln!(f, "{out} = {}{{", D::aggregate_type(item_out))?;
         for i in 0..index {
             let lhsi = lhs.index(i);
             let rhsi = rhs.index(i);
@@ -228,11 +229,12 @@ impl<D: Dialect> Binary<D> for Powf {
         let rhs = rhs.to_string();
         match elem {
             Elem::F16 | Elem::F16x2 | Elem::BF16 | Elem::BF16x2 => {
-                let lhs = format!("float({lhs})");
-                let rhs = format!("float({rhs})");
-                write!(f, "{elem}(")?;
-                D::compile_instruction_powf(f, &lhs, &rhs, Elem::F32)?;
-                write!(f, ")")
+                let lhs = D::cast(Elem::<D>::F32, lhs);
+                let rhs = D::cast(Elem::<D>::F32, rhs);
+                let value = FmtWith(|f: &mut Formatter<'_>| {
+                    D::compile_instruction_powf(f, &lhs, &rhs, Elem::F32)
+                });
+                f.write_str(&D::cast(elem, value))
             }
             _ => D::compile_instruction_powf(f, &lhs, &rhs, elem),
         }
@@ -249,7 +251,7 @@ impl<D: Dialect> Binary<D> for Powf {
         let index = out.item().vectorization;
 
         let out = out.fmt_left();
-        writeln!(f, "{out} = {item_out}{{")?;
+        writeln!(f, "{out} = {}{{", D::aggregate_type(item_out))?;
         for i in 0..index {
             let lhsi = lhs.index(i);
             let rhsi = rhs.index(i);
@@ -291,15 +293,15 @@ impl<D: Dialect> Binary<D> for Powi {
         let rhs = rhs.to_string();
         match elem {
             Elem::F16 | Elem::F16x2 | Elem::BF16 | Elem::BF16x2 => {
-                let lhs = format!("float({lhs})");
-
-                write!(f, "{elem}(")?;
-                D::compile_instruction_powf(f, &lhs, &rhs, Elem::F32)?;
-                write!(f, ")")
+                let lhs = D::cast(Elem::<D>::F32, lhs);
+                let value = FmtWith(|f: &mut Formatter<'_>| {
+                    D::compile_instruction_powf(f, &lhs, &rhs, Elem::F32)
+                });
+                f.write_str(&D::cast(elem, value))
             }
             Elem::F64 => {
                 // RHS needs to be a double.
-                let rhs = format!("double({rhs})");
+                let rhs = D::cast(Elem::<D>::F64, rhs);
 
                 D::compile_instruction_powf(f, &lhs, &rhs, elem)
             }
@@ -318,7 +320,7 @@ impl<D: Dialect> Binary<D> for Powi {
         let index = out.item().vectorization;
 
         let out = out.fmt_left();
-        writeln!(f, "{out} = {item_out}{{")?;
+        writeln!(f, "{out} = {}{{", D::aggregate_type(item_out))?;
         for i in 0..index {
             let lhsi = lhs.index(i);
             let rhsi = rhs.index(i);
@@ -343,7 +345,8 @@ impl<D: Dialect> Binary<D> for ArcTan2 {
         let elem = item.elem;
         match elem {
             Elem::F16 | Elem::F16x2 | Elem::BF16 | Elem::BF16x2 => {
-                write!(f, "{elem}(atan2(float({lhs}), float({rhs})))")
+                let (lhs, rhs) = (D::cast(Elem::<D>::F32, lhs), D::cast(Elem::<D>::F32, rhs));
+                f.write_str(&D::cast(elem, format!("atan2({lhs}, {rhs})")))
             }
             _ => {
                 write!(f, "atan2({lhs}, {rhs})")
@@ -362,7 +365,7 @@ impl<D: Dialect> Binary<D> for ArcTan2 {
         let index = out.item().vectorization;
 
         let out = out.fmt_left();
-        writeln!(f, "{out} = {item_out}{{")?;
+        writeln!(f, "{out} = {}{{", D::aggregate_type(item_out))?;
         for i in 0..index {
             let lhsi = lhs.index(i);
             let rhsi = rhs.index(i);
@@ -394,11 +397,12 @@ impl<D: Dialect> Binary<D> for Hypot {
         let rhs = rhs.to_string();
         match elem {
             Elem::F16 | Elem::F16x2 | Elem::BF16 | Elem::BF16x2 => {
-                let lhs = format!("float({lhs})");
-                let rhs = format!("float({rhs})");
-                write!(f, "{elem}(")?;
-                D::compile_instruction_hypot(f, &lhs, &rhs, Elem::F32)?;
-                write!(f, ")")
+                let lhs = D::cast(Elem::<D>::F32, lhs);
+                let rhs = D::cast(Elem::<D>::F32, rhs);
+                let value = FmtWith(|f: &mut Formatter<'_>| {
+                    D::compile_instruction_hypot(f, &lhs, &rhs, Elem::F32)
+                });
+                f.write_str(&D::cast(elem, value))
             }
             _ => D::compile_instruction_hypot(f, &lhs, &rhs, elem),
         }
@@ -415,7 +419,7 @@ impl<D: Dialect> Binary<D> for Hypot {
         let index = out.item().vectorization;
 
         let out = out.fmt_left();
-        writeln!(f, "{out} = {item_out}{{")?;
+        writeln!(f, "{out} = {}{{", D::aggregate_type(item_out))?;
         for i in 0..index {
             let lhsi = lhs.index(i);
             let rhsi = rhs.index(i);
@@ -447,11 +451,12 @@ impl<D: Dialect> Binary<D> for Rhypot {
         let rhs = rhs.to_string();
         match elem {
             Elem::F16 | Elem::F16x2 | Elem::BF16 | Elem::BF16x2 => {
-                let lhs = format!("float({lhs})");
-                let rhs = format!("float({rhs})");
-                write!(f, "{elem}(")?;
-                D::compile_instruction_rhypot(f, &lhs, &rhs, Elem::F32)?;
-                write!(f, ")")
+                let lhs = D::cast(Elem::<D>::F32, lhs);
+                let rhs = D::cast(Elem::<D>::F32, rhs);
+                let value = FmtWith(|f: &mut Formatter<'_>| {
+                    D::compile_instruction_rhypot(f, &lhs, &rhs, Elem::F32)
+                });
+                f.write_str(&D::cast(elem, value))
             }
             _ => D::compile_instruction_rhypot(f, &lhs, &rhs, elem),
         }
@@ -468,7 +473,7 @@ impl<D: Dialect> Binary<D> for Rhypot {
         let index = out.item().vectorization;
 
         let out = out.fmt_left();
-        writeln!(f, "{out} = {item_out}{{")?;
+        writeln!(f, "{out} = {}{{", D::aggregate_type(item_out))?;
         for i in 0..index {
             let lhsi = lhs.index(i);
             let rhsi = rhs.index(i);
@@ -534,10 +539,8 @@ impl IndexAssign {
             let qualifier = out_list.const_qualifier();
             let tmp = Variable::tmp_declared(item);
 
-            writeln!(
-                f,
-                "{qualifier} {addr_space}{item} *{tmp} = reinterpret_cast<{qualifier} {item}*>({out_list});"
-            )?;
+            let ptr = D::pointer_cast(format!("{qualifier} {addr_space}{item}"), out_list);
+            writeln!(f, "{qualifier} {addr_space}{item} *{tmp} = {ptr};")?;
 
             return IndexAssign::format(f, index, value, &tmp, 0);
         }
@@ -565,10 +568,10 @@ impl IndexAssign {
         let item_rhs = rhs.item();
 
         let format_vec = |f: &mut Formatter<'_>, cast: bool| {
-            writeln!(f, "{item_out}{{")?;
+            writeln!(f, "{}{{", D::aggregate_type(item_out))?;
             for i in 0..item_out.vectorization {
                 if cast {
-                    writeln!(f, "{}({}),", item_out.elem, rhs.index(i))?;
+                    writeln!(f, "{},", D::cast(item_out.elem, rhs.index(i)))?;
                 } else {
                     writeln!(f, "{},", rhs.index(i))?;
                 }
@@ -584,14 +587,16 @@ impl IndexAssign {
             if item_out.vectorization > 1 {
                 format_vec(f, true)?;
             } else {
-                write!(f, "{}({rhs})", item_out.elem)?;
+                f.write_str(&D::cast(item_out.elem, rhs))?;
             }
             Ok(())
         } else if rhs.is_const() && item_rhs.vectorization > 1 {
             // Reinterpret cast in case rhs is optimized
-            write!(f, "reinterpret_cast<")?;
-            D::compile_local_memory_qualifier(f)?;
-            write!(f, " {item_out} const&>({rhs})")
+            let qualifier = FmtWith(D::compile_local_memory_qualifier);
+            f.write_str(&D::reference_cast(
+                format!("{qualifier} {item_out} const"),
+                rhs,
+            ))
         } else {
             write!(f, "{rhs}")
         }
@@ -641,10 +646,8 @@ impl Index {
             let qualifier = list.const_qualifier();
             let tmp = Variable::tmp_declared(item);
 
-            writeln!(
-                f,
-                "{qualifier} {addr_space}{item} *{tmp} = reinterpret_cast<{qualifier} {item}*>({list});"
-            )?;
+            let ptr = D::pointer_cast(format!("{qualifier} {addr_space}{item}"), list);
+            writeln!(f, "{qualifier} {addr_space}{item} *{tmp} = {ptr};")?;
 
             return Index::format(f, &tmp, index, out, 0);
         }
@@ -677,9 +680,13 @@ impl Index {
         let item_lhs = lhs.item();
 
         let format_vec = |f: &mut Formatter<'_>| {
-            writeln!(f, "{item_out}{{")?;
+            writeln!(f, "{}{{", D::aggregate_type(item_out))?;
             for i in 0..item_out.vectorization {
-                write!(f, "{}({lhs}[{rhs}].i_{i}),", item_out.elem)?;
+                write!(
+                    f,
+                    "{},",
+                    D::cast(item_out.elem, format!("{lhs}[{rhs}].i_{i}"))
+                )?;
             }
             f.write_str("}")?;
 
@@ -690,7 +697,7 @@ impl Index {
             if item_out.vectorization > 1 {
                 format_vec(f)
             } else {
-                write!(f, "{}({lhs}[{rhs}])", item_out.elem)
+                f.write_str(&D::cast(item_out.elem, format!("{lhs}[{rhs}]")))
             }
         } else {
             write!(f, "{lhs}[{rhs}]")
@@ -745,10 +752,9 @@ impl<D: Dialect> IndexVector<D> {
                 let qualifier = out.const_qualifier();
                 let addr_space = D::address_space_for_variable(out);
                 let out = out.fmt_left();
-                writeln!(
-                    f,
-                    "{out} = reinterpret_cast<{addr_space}{elem}{qualifier}*>(&{lhs})[{rhs}];"
-                )
+                let ptr =
+                    D::pointer_cast(format!("{addr_space}{elem}{qualifier}"), format!("&{lhs}"));
+                writeln!(f, "{out} = {ptr}[{rhs}];")
             }
         }
     }
@@ -139,10 +139,43 @@ pub trait DialectTypes<D: Dialect> {
     fn compile_polyfills(_f: &mut std::fmt::Formatter<'_>, _flags: &Flags<D>) -> std::fmt::Result {
         Ok(())
     }
-    /// Address space (for Metal dialect only).
+    /// Address space (for the Metal and `OpenCL` dialects only).
     fn address_space_for_variable(_variable: &Variable<D>) -> String {
         "".to_string()
     }
+    /// Why `elem` can't be used in a kernel, if it isn't supported by the dialect.
+    fn unsupported_elem(_elem: &Elem<D>) -> Option<String> {
+        None
+    }
+    /// Converts `value` to the type `ty`.
+    fn cast(ty: impl Display, value: impl Display) -> String {
+        format!("{ty}({value})")
+    }
+    /// Reinterprets the pointer `value` as a pointer to `ty`.
+    fn pointer_cast(ty: impl Display, value: impl Display) -> String {
+        format!("reinterpret_cast<{ty}*>({value})")
+    }
+    /// Reinterprets the memory of the lvalue `value` as `ty`, the result is still assignable.
+    fn reference_cast(ty: impl Display, value: impl Display) -> String {
+        format!("reinterpret_cast<{ty}&>({value})")
+    }
+    /// The type in front of the braced initializer of an aggregate of type `ty`.
+    fn aggregate_type(ty: impl Display) -> String {
+        format!("{ty}")
+    }
+    /// The type in front of a braced initializer whose type is deduced from the assigned variable.
+    fn untyped_aggregate_type(_ty: impl Display) -> String {
+        String::new()
+    }
+}
+
+/// Displays the output of a formatting function, to nest it in the casts of [`DialectTypes`].
+pub struct FmtWith<F>(pub F);
+
+impl<F: Fn(&mut std::fmt::Formatter<'_>) -> std::fmt::Result> Display for FmtWith<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self.0)(f)
+    }
 }
 
 // Kernel argument bindings
@@ -654,7 +687,11 @@ pub trait DialectInstructions<D: Dialect> {
         let elem = input.elem();
         match elem {
             Elem::F16 | Elem::F16x2 | Elem::BF16 | Elem::BF16x2 => {
-                write!(f, "{elem}(log1p(float({input})))")
+                write!(
+                    f,
+                    "{}",
+                    D::cast(elem, format!("log1p({})", D::cast(Elem::<D>::F32, input)))
+                )
             }
             _ => write!(f, "log1p({input})"),
         }
@@ -673,7 +710,11 @@ pub trait DialectInstructions<D: Dialect> {
         let elem = input.elem();
         match elem {
             Elem::F16 | Elem::F16x2 | Elem::BF16 | Elem::BF16x2 => {
-                write!(f, "{elem}(tanh(float({input})))")
+                write!(
+                    f,
+                    "{}",
+                    D::cast(elem, format!("tanh({})", D::cast(Elem::<D>::F32, input)))
+                )
             }
             _ => write!(f, "tanh({input})"),
         }
@@ -141,6 +141,7 @@ pub enum Instruction<D: Dialect> {
     },
     Return,
     Break,
+    Continue,
     Unreachable,
     Equal(BinaryInstruction<D>),
     NotEqual(BinaryInstruction<D>),
@@ -291,6 +292,7 @@ impl<D: Dialect> Display for Instruction<D> {
         match self {
             Instruction::Return => f.write_str("return;"),
             Instruction::Break => f.write_str("break;"),
+            Instruction::Continue => f.write_str("continue;"),
             Instruction::Unreachable => D::compile_unreachable(f),
             Instruction::DeclareVariable { var } => match var {
                 Variable::WmmaFragment { .. } => D::compile_wmma_fragment_declaration(f, var),
@@ -333,10 +335,8 @@ impl<D: Dialect> Display for Instruction<D> {
                 item.vectorization = *vector_size as usize;
                 let addr_space = D::address_space_for_variable(input);
 
-                writeln!(
-                    f,
-                    "{addr_space}{item} *{out} = reinterpret_cast<{item}*>({input});"
-                )
+                let ptr = D::pointer_cast(format!("{addr_space}{item}"), input);
+                writeln!(f, "{addr_space}{item} *{out} = {ptr};")
             }
             Instruction::Mul(it) => Mul::format(f, &it.lhs, &it.rhs, &it.out),
             Instruction::Div(it) => Div::format(f, &it.lhs, &it.rhs, &it.out),
@@ -465,7 +465,7 @@ for ({i_ty} {i} = {start}; {i} {cmp} {end}; {increment}) {{
                     let vf = usize::max(vf, vf_then);
                     let vf = usize::max(vf, vf_or_else);
 
-                    writeln!(f, "{out} = {item_out} {{")?;
+                    writeln!(f, "{out} = {} {{", D::aggregate_type(item_out))?;
                     for i in 0..vf {
                         let theni = then.index(i);
                         let or_elsei = or_else.index(i);
@@ -577,7 +577,7 @@ for ({i_ty} {i} = {start}; {i} {cmp} {end}; {increment}) {{
             Instruction::IsInf(it) => IsInf::format(f, &it.input, &it.out),
             Instruction::SyncThreads => D::compile_instruction_sync_threads(f),
             Instruction::SyncWarp => D::compile_instruction_sync_warp(f),
-            Instruction::ThreadFence => f.write_str("__threadfence();\n"),
+            Instruction::ThreadFence => D::compile_instruction_thread_fence(f),
             Instruction::Round(it) => Round::format(f, &it.input, &it.out),
             Instruction::Ceil(it) => Ceil::format(f, &it.input, &it.out),
             Instruction::Trunc(it) => Trunc::format(f, &it.input, &it.out),
@@ -605,10 +605,9 @@ for ({i_ty} {i} = {start}; {i} {cmp} {end}; {increment}) {{
                 } else {
                     let out = out.fmt_left();
                     let addr_space = D::address_space_for_variable(input);
-                    writeln!(
-                        f,
-                        "{out} = reinterpret_cast<{addr_space}{out_item}{qualifier}&>({input});"
-                    )
+                    let input =
+                        D::reference_cast(format!("{addr_space}{out_item}{qualifier}"), input);
+                    writeln!(f, "{out} = {input};")
                 }
             }
             Instruction::AtomicAdd(BinaryInstruction { lhs, rhs, out }) => {
@@ -775,7 +774,7 @@ impl<D: Dialect> Fma<D> {
         if num == 1 {
             writeln!(f, "{out} = fma({a}, {b}, {c});")
         } else {
-            writeln!(f, "{out} = {out_item}{{")?;
+            writeln!(f, "{out} = {}{{", D::aggregate_type(out_item))?;
 
             for i in 0..num {
                 let ai = a.index(i);
@@ -849,7 +848,7 @@ impl<D: Dialect> Clamp<D> {
                             out: &Variable<D>,
                             item_out: Item<D>| {
             let out = out.fmt_left();
-            writeln!(f, "{out} = {item_out}{{")?;
+            writeln!(f, "{out} = {}{{", D::aggregate_type(item_out))?;
             for i in 0..index {
                 let inputi = input.index(i);
                 let min_valuei = min_value.index(i);
@@ -872,7 +871,8 @@ impl<D: Dialect> Clamp<D> {
 
             writeln!(
                 f,
-                "{out} = reinterpret_cast<{addr_space}{item_out_original}&>({out_tmp});\n"
+                "{out} = {};\n",
+                D::reference_cast(format!("{addr_space}{item_out_original}"), out_tmp)
             )?;
 
             Ok(())
@@ -937,7 +937,7 @@ impl<D: Dialect> Remainder<D> {
         let mut write_op =
             |lhs: &Variable<D>, rhs: &Variable<D>, out: &Variable<D>, item_out: Item<D>| {
                 let out = out.fmt_left();
-                writeln!(f, "{out} = {item_out}{{")?;
+                writeln!(f, "{out} = {}{{", D::aggregate_type(item_out))?;
                 for i in 0..index {
                     let lhsi = lhs.index(i);
                     let rhsi = rhs.index(i);
@@ -961,10 +961,11 @@ impl<D: Dialect> Remainder<D> {
             let qualifier = out.const_qualifier();
             let out = out.fmt_left();
 
-            writeln!(
-                f,
-                "{out} = reinterpret_cast<{addr_space}{item_out_original}{qualifier}&>({out_tmp});\n"
-            )?;
+            let out_tmp = D::reference_cast(
+                format!("{addr_space}{item_out_original}{qualifier}"),
+                out_tmp,
+            );
+            writeln!(f, "{out} = {out_tmp};\n")?;
 
             Ok(())
         }
@@ -1032,7 +1033,7 @@ impl<D: Dialect, InvS: FunctionFmt<D>> Normalize<D, InvS> {
         if num == 1 {
             writeln!(f, "{out} = {input} * {norm};")
         } else {
-            write!(f, "{out} = {out_item}{{")?;
+            write!(f, "{out} = {}{{", D::aggregate_type(out_item))?;
             for i in 0..num {
                 let input_i = input.index(i);
 
@@ -27,3 +27,6 @@ pub use warp::*;
 
 #[cfg(feature = "metal")]
 pub type MslComputeKernel = ComputeKernel<crate::metal::MslDialect>;
+
+#[cfg(feature = "opencl")]
+pub type OpenClComputeKernel = ComputeKernel<crate::opencl::OpenClDialect>;
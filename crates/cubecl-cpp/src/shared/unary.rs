@@ -34,7 +34,7 @@ pub trait Unary<D: Dialect> {
         let mut write_op = |index, out_elem, input: &Variable<D>, out: &Variable<D>| {
             let out_item = out.item();
             let out = out.fmt_left();
-            writeln!(f, "{out} = {out_item}{{")?;
+            writeln!(f, "{out} = {}{{", D::aggregate_type(out_item))?;
 
             for i in 0..index {
                 let inputi = input.index(i);
@@ -65,10 +65,11 @@ pub trait Unary<D: Dialect> {
                 let qualifier = out.const_qualifier();
                 let addr_space = D::address_space_for_variable(out);
                 let out_fmt = out.fmt_left();
-                writeln!(
-                    f,
-                    "{out_fmt} = reinterpret_cast<{addr_space}{item_out_original}{qualifier}&>({out_tmp});\n"
-                )
+                let out_tmp = D::reference_cast(
+                    format!("{addr_space}{item_out_original}{qualifier}"),
+                    out_tmp,
+                );
+                writeln!(f, "{out_fmt} = {out_tmp};\n")
             } else {
                 write_op(index, out_elem, &input, &out_optimized)
             }
@@ -106,7 +107,9 @@ pub trait FunctionFmt<D: Dialect> {
         } else {
             match elem {
                 Elem::F16 | Elem::F16x2 | Elem::BF16 | Elem::BF16x2 => {
-                    write!(f, "{}({}(float({input})))", elem, Self::function_name(elem))
+                    let input = D::cast(Elem::<D>::F32, input);
+                    let value = format!("{}({input})", Self::function_name(elem));
+                    f.write_str(&D::cast(elem, value))
                 }
                 _ => write!(f, "{}({input})", Self::function_name(elem)),
             }
@@ -219,7 +222,7 @@ impl<D: Dialect> Unary<D> for Degrees {
         input: Input,
         elem: Elem<D>,
     ) -> std::fmt::Result {
-        write!(f, "{input}*{elem}(57.29577951308232f)")
+        write!(f, "{input}*{}", D::cast(elem, "57.29577951308232f"))
     }
 
     fn can_optimize() -> bool {
@@ -235,7 +238,7 @@ impl<D: Dialect> Unary<D> for Radians {
         input: Input,
         elem: Elem<D>,
     ) -> std::fmt::Result {
-        write!(f, "{input}*{elem}(0.017453292519943295f)")
+        write!(f, "{input}*{}", D::cast(elem, "0.017453292519943295f"))
     }
 
     fn can_optimize() -> bool {
@@ -245,10 +248,9 @@ impl<D: Dialect> Unary<D> for Radians {
 
 pub fn zero_extend<D: Dialect>(input: impl Component<D>) -> String {
     match input.elem() {
-        Elem::I8 => format!("{}({}({input}))", Elem::<D>::U32, Elem::<D>::U8),
-        Elem::I16 => format!("{}({}({input}))", Elem::<D>::U32, Elem::<D>::U16),
-        Elem::U8 => format!("{}({input})", Elem::<D>::U32),
-        Elem::U16 => format!("{}({input})", Elem::<D>::U32),
+        Elem::I8 => D::cast(Elem::<D>::U32, D::cast(Elem::<D>::U8, input)),
+        Elem::I16 => D::cast(Elem::<D>::U32, D::cast(Elem::<D>::U16, input)),
+        Elem::U8 | Elem::U16 => D::cast(Elem::<D>::U32, input),
         _ => unreachable!("zero extend only supports integer < 32 bits"),
     }
 }
@@ -374,7 +376,7 @@ impl<D: Dialect> Unary<D> for Assign {
         if elem != input.elem() {
             match elem {
                 Elem::TF32 => write!(f, "nvcuda::wmma::__float_to_tf32({input})"),
-                elem => write!(f, "{elem}({input})"),
+                elem => f.write_str(&D::cast(elem, input)),
             }
         } else {
             write!(f, "{input}")
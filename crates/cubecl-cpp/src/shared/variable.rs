@@ -245,14 +245,14 @@ impl<D: Dialect> Display for Variable<D> {
             Variable::GlobalScalar { id, elem } => write!(f, "info.scalars_{elem}[{id}]"),
             Variable::Constant(number, item) if item.vectorization <= 1 => {
                 let value = format_const(number, item);
-                write!(f, "{item}({value})")
+                f.write_str(&D::cast(item, value))
             }
             Variable::Constant(number, item) => {
                 let number = format_const(number, item);
                 let values = (0..item.vectorization)
-                    .map(|_| format!("{}({number})", item.elem()))
+                    .map(|_| D::cast(item.elem(), &number))
                     .collect::<Vec<_>>();
-                write!(f, "{item} {{ {} }}", values.join(","))
+                write!(f, "{} {{ {} }}", D::aggregate_type(item), values.join(","))
             }
             Variable::SharedArray(number, _, _) | Variable::Shared(number, _) => {
                 write!(f, "shared_memory_{number}")
@@ -352,11 +352,8 @@ impl<D: Dialect> Variable<D> {
         let addr_space = D::address_space_for_variable(self);
         let out_fmt = out.fmt_left();
 
-        writeln!(
-            f,
-            "{out_fmt} = reinterpret_cast<{addr_space}{elem}{qualifier}*>({self});"
-        )
-        .unwrap();
+        let ptr = D::pointer_cast(format!("{addr_space}{elem}{qualifier}"), self);
+        writeln!(f, "{out_fmt} = {ptr};").unwrap();
 
         out
     }
@@ -575,7 +572,7 @@ impl<D: Dialect> Variable<D> {
         if self.item() == item {
             self.to_string()
         } else {
-            format!("{item}({self})")
+            D::cast(item, self)
         }
     }
 }
@@ -642,29 +639,29 @@ impl<D: Dialect> Display for IndexedVariable<D> {
 
         if let Variable::Constant(value, item) = var {
             let value = format_const(value, item);
-            return write!(f, "{}({value})", item.elem());
+            return f.write_str(&D::cast(item.elem(), value));
         }
 
-        let ref_ = matches!(var, Variable::LocalConst { .. })
-            .then_some("const&")
-            .unwrap_or("&");
+        let qualifier = matches!(var, Variable::LocalConst { .. })
+            .then_some(" const")
+            .unwrap_or("");
 
         if self.var.item().vectorization > 1 {
             if self.optimized {
                 let item = self.var.item();
                 let addr_space = D::address_space_for_variable(&self.var);
-                write!(
-                    f,
-                    "(reinterpret_cast<{addr_space}{item} {ref_}>({var})).i_{}",
-                    self.index
-                )
+                let var = D::reference_cast(format!("{addr_space}{item}{qualifier}"), var);
+                write!(f, "({var}).i_{}", self.index)
             } else {
                 write!(f, "{var}.i_{}", self.index)
             }
         } else if self.optimized {
             let item = self.var.item();
             let addr_space = D::address_space_for_variable(&self.var);
-            write!(f, "reinterpret_cast<{addr_space}{item} {ref_}>({var})")
+            f.write_str(&D::reference_cast(
+                format!("{addr_space}{item}{qualifier}"),
+                var,
+            ))
         } else {
             write!(f, "{var}")
         }
@@ -674,18 +671,16 @@ impl<D: Dialect> Display for IndexedVariable<D> {
 impl<D: Dialect> FmtLeft for IndexedVariable<D> {
     fn fmt_left(&self) -> String {
         let var = &self.var;
-        let ref_ = matches!(var, Variable::LocalConst { .. })
-            .then_some("const&")
-            .unwrap_or("&");
+        let qualifier = matches!(var, Variable::LocalConst { .. })
+            .then_some(" const")
+            .unwrap_or("");
 
         let name = if self.var.item().vectorization > 1 {
             if self.optimized {
                 let item = self.var.item();
                 let addr_space = D::address_space_for_variable(&self.var);
-                format!(
-                    "(reinterpret_cast<{addr_space}{item} {ref_}>({var})).i_{}",
-                    self.index
-                )
+                let var = D::reference_cast(format!("{addr_space}{item}{qualifier}"), var);
+                format!("({var}).i_{}", self.index)
             } else {
                 format!("{var}.i_{}", self.index)
             }
@@ -112,10 +112,11 @@ impl<D: Dialect> Display for WarpInstruction<D> {
                     "Ballot can't support vectorized input"
                 );
                 let out_fmt = out.fmt_left();
+                let ty = D::untyped_aggregate_type(out.item());
                 write!(
                     f,
                     "
-{out_fmt} = {{ "
+{out_fmt} = {ty}{{ "
                 )?;
                 D::compile_warp_ballot(f, input, out.item().elem())?;
                 writeln!(f, ", 0, 0, 0 }};")
@@ -127,7 +128,8 @@ impl<D: Dialect> Display for WarpInstruction<D> {
                 out,
             } => {
                 let out_fmt = out.fmt_left();
-                write!(f, "{out_fmt} = {{ ")?;
+                let ty = D::untyped_aggregate_type(out.item());
+                write!(f, "{out_fmt} = {ty}{{ ")?;
                 for i in 0..input.item().vectorization {
                     let comma = if i > 0 { ", " } else { "" };
                     write!(f, "{comma}")?;
@@ -141,7 +143,8 @@ impl<D: Dialect> Display for WarpInstruction<D> {
             }
             WarpInstruction::ShuffleXor { input, mask, out } => {
                 let out_fmt = out.fmt_left();
-                write!(f, "{out_fmt} = {{ ")?;
+                let ty = D::untyped_aggregate_type(out.item());
+                write!(f, "{out_fmt} = {ty}{{ ")?;
                 for i in 0..input.item().vectorization {
                     let comma = if i > 0 { ", " } else { "" };
                     write!(f, "{comma}")?;
@@ -156,7 +159,8 @@ impl<D: Dialect> Display for WarpInstruction<D> {
             }
             WarpInstruction::ShuffleUp { input, delta, out } => {
                 let out_fmt = out.fmt_left();
-                write!(f, "{out_fmt} = {{ ")?;
+                let ty = D::untyped_aggregate_type(out.item());
+                write!(f, "{out_fmt} = {ty}{{ ")?;
                 for i in 0..input.item().vectorization {
                     let comma = if i > 0 { ", " } else { "" };
                     write!(f, "{comma}")?;
@@ -170,7 +174,8 @@ impl<D: Dialect> Display for WarpInstruction<D> {
             }
             WarpInstruction::ShuffleDown { input, delta, out } => {
                 let out_fmt = out.fmt_left();
-                write!(f, "{out_fmt} = {{ ")?;
+                let ty = D::untyped_aggregate_type(out.item());
+                write!(f, "{out_fmt} = {ty}{{ ")?;
                 for i in 0..input.item().vectorization {
                     let comma = if i > 0 { ", " } else { "" };
                     write!(f, "{comma}")?;
@@ -313,7 +318,8 @@ pub(crate) fn reduce_broadcast<D: Dialect>(
     id: &Variable<D>,
 ) -> core::fmt::Result {
     let out_fmt = out.fmt_left();
-    write!(f, "{out_fmt} = {{ ")?;
+    let ty = D::untyped_aggregate_type(out.item());
+    write!(f, "{out_fmt} = {ty}{{ ")?;
     for i in 0..input.item().vectorization {
         let comma = if i > 0 { ", " } else { "" };
         write!(f, "{comma}")?;
@@ -375,7 +381,7 @@ fn cast<D: Dialect>(input: &Variable<D>, target: Item<D>) -> String {
     if target != input.item() {
         let addr_space = D::address_space_for_variable(input);
         let qualifier = input.const_qualifier();
-        format!("reinterpret_cast<{addr_space}{target}{qualifier}&>({input})")
+        D::reference_cast(format!("{addr_space}{target}{qualifier}"), input)
     } else {
         format!("{input}")
     }
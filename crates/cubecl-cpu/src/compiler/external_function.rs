@@ -8,19 +8,26 @@ use tracel_llvm::mlir_rs::{
         },
     },
     ir::{
-        BlockLike, Identifier, Location, Region,
+        BlockLike, Identifier, Location, Region, Type,
         attribute::{StringAttribute, TypeAttribute},
         r#type::{FunctionType, IntegerType},
     },
 };
 
-use crate::compute::compute_task::sync_cube;
+use crate::compute::{
+    compute_task::sync_cube,
+    plane::{plane_op, sync_plane},
+    validation::report_out_of_bounds,
+};
 
 pub fn register_external_function(execution_engine: &ExecutionEngine) {
     unsafe {
         execution_engine.register_symbol("sync_cube", sync_cube as *mut ());
         // This is only there to fool the execution engine to generate .so for inspection even if symbol resolution will probably not work.
         execution_engine.register_symbol("_mlir_sync_cube", sync_cube as *mut ());
+        execution_engine.register_symbol("sync_plane", sync_plane as *mut ());
+        execution_engine.register_symbol("plane_op", plane_op as *mut ());
+        execution_engine.register_symbol("report_out_of_bounds", report_out_of_bounds as *mut ());
         execution_engine.register_symbol("rsqrtf", rsqrtf as *mut ());
         execution_engine.register_symbol("rsqrt", rsqrt as *mut ());
     }
@@ -55,12 +62,55 @@ pub fn add_external_function_to_module<'a>(
         )],
         Location::unknown(context),
     ));
-    let func_name = StringAttribute::new(context, "sync_cube");
-    let func_type = TypeAttribute::new(FunctionType::new(context, &[], &[]).into());
+    for name in ["sync_cube", "sync_plane"] {
+        let func_type = FunctionType::new(context, &[], &[]);
+        add_private_function(context, module, name, func_type);
+    }
+
+    // op, elem, vector_size, arg, input pointer and output pointer.
+    let integer_type = IntegerType::new(context, 32).into();
+    let index_type = Type::index(context);
+    let plane_op_type = FunctionType::new(
+        context,
+        &[
+            integer_type,
+            integer_type,
+            integer_type,
+            integer_type,
+            index_type,
+            index_type,
+        ],
+        &[],
+    );
+    add_private_function(context, module, "plane_op", plane_op_type);
+
+    // Kernel name, buffer name, whether the access is a write, index and length.
+    let pointer_type = llvm::r#type::pointer(context, 0);
+    let long_type = IntegerType::new(context, 64).into();
+    let report_type = FunctionType::new(
+        context,
+        &[
+            pointer_type,
+            pointer_type,
+            integer_type,
+            long_type,
+            long_type,
+        ],
+        &[],
+    );
+    add_private_function(context, module, "report_out_of_bounds", report_type);
+}
+
+fn add_private_function<'a>(
+    context: &'a Context,
+    module: &tracel_llvm::mlir_rs::ir::Module<'a>,
+    name: &str,
+    func_type: FunctionType<'a>,
+) {
     module.body().append_operation(func::func(
         context,
-        func_name,
-        func_type,
+        StringAttribute::new(context, name),
+        TypeAttribute::new(func_type.into()),
         Region::new(),
         &[(
             Identifier::new(context, "sym_visibility"),
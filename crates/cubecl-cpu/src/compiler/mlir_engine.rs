@@ -1,9 +1,11 @@
 use crate::compiler::mlir_data::MlirData;
 
 use super::{
-    external_function::register_external_function, passes::shared_memories::SharedMemories,
+    external_function::register_external_function,
+    passes::shared_memories::{SharedMemories, SharedMemory},
 };
 use cubecl_opt::Optimizer;
+use serde::{Deserialize, Serialize};
 
 use std::{
     fmt::{Debug, Display},
@@ -11,7 +13,7 @@ use std::{
 };
 
 use super::module::Module;
-use cubecl_core::{ir::StorageType, prelude::KernelDefinition};
+use cubecl_core::{CubeDim, ExecutionMode, ir::StorageType, prelude::KernelDefinition};
 use tracel_llvm::mlir_rs::{
     Context, ExecutionEngine,
     dialect::DialectRegistry,
@@ -20,7 +22,11 @@ use tracel_llvm::mlir_rs::{
 
 pub struct MlirKernel {
     execution_engine: ExecutionEngine,
+    /// The kernel lowered to the LLVM dialect.
+    pub lowered_module: String,
     pub shared_memories: SharedMemories,
+    /// Number of units grouped in a plane, the kernel must be launched with the same value.
+    pub plane_size: u32,
 }
 
 #[derive(Clone)]
@@ -34,43 +40,96 @@ impl Debug for MlirEngine {
 
 impl Display for MlirEngine {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "work in progress")
+        f.write_str(&self.0.lowered_module)
     }
 }
 
+/// A kernel persisted in the kernel cache. The lowered module only needs to be parsed and
+/// translated to machine code again, skipping the optimizer and the MLIR passes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MlirCacheEntry {
+    pub entrypoint_name: String,
+    pub cube_dim: CubeDim,
+    pub lowered_module: String,
+    pub shared_memories: Vec<SharedMemory>,
+    pub plane_size: u32,
+}
+
+fn create_context() -> Context {
+    let registry = DialectRegistry::new();
+    register_all_dialects(&registry);
+    register_all_passes();
+
+    let context = Context::new();
+    register_all_llvm_translations(&context);
+    context.enable_multi_threading(false);
+    context.append_dialect_registry(&registry);
+    context.load_all_available_dialects();
+    context
+}
+
 impl MlirEngine {
     pub fn from_cubecl_ir(
         kernel: KernelDefinition,
         opt: &Optimizer,
         shared_memories: SharedMemories,
         addr_type: StorageType,
+        plane_size: u32,
+        execution_mode: ExecutionMode,
     ) -> Self {
-        let registry = DialectRegistry::new();
-        register_all_dialects(&registry);
-        register_all_passes();
-
-        let context = Context::new();
-        register_all_llvm_translations(&context);
-        context.enable_multi_threading(false);
-        context.append_dialect_registry(&registry);
-        context.load_all_available_dialects();
-
+        let context = create_context();
         let mut module = Module::new(&context, kernel.options.kernel_name.clone());
 
-        module.visit_kernel(&kernel, opt, &shared_memories, addr_type);
+        module.visit_kernel(
+            &kernel,
+            opt,
+            &shared_memories,
+            addr_type,
+            plane_size,
+            execution_mode,
+        );
 
         module.run_pass();
 
+        let lowered_module = module.to_text();
         let execution_engine = module.into_execution_engine();
         register_external_function(&execution_engine);
         let kernel = MlirKernel {
             execution_engine,
+            lowered_module,
             shared_memories,
+            plane_size,
         };
         let mlir_kernel = Arc::new(kernel);
         Self(mlir_kernel)
     }
 
+    /// Creates the engine of a cached kernel, returns `None` if its module can't be parsed.
+    pub fn from_cache_entry(entry: &MlirCacheEntry) -> Option<Self> {
+        let context = create_context();
+        let module = tracel_llvm::mlir_rs::ir::Module::parse(&context, &entry.lowered_module)?;
+        let execution_engine = ExecutionEngine::new(&module, 0, &[], true);
+        register_external_function(&execution_engine);
+        let kernel = MlirKernel {
+            execution_engine,
+            lowered_module: entry.lowered_module.clone(),
+            shared_memories: SharedMemories(entry.shared_memories.clone()),
+            plane_size: entry.plane_size,
+        };
+        Some(Self(Arc::new(kernel)))
+    }
+
+    /// The entry persisting this kernel in the kernel cache.
+    pub fn cache_entry(&self, entrypoint_name: &str, cube_dim: CubeDim) -> MlirCacheEntry {
+        MlirCacheEntry {
+            entrypoint_name: entrypoint_name.to_string(),
+            cube_dim,
+            lowered_module: self.0.lowered_module.clone(),
+            shared_memories: self.0.shared_memories.0.clone(),
+            plane_size: self.0.plane_size,
+        }
+    }
+
     pub fn dump_object(&self, path: &str) {
         self.0.execution_engine.dump_to_object_file(path);
     }
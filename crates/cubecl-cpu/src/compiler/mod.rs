@@ -15,26 +15,41 @@ pub use visitor::elem::register_supported_types;
 use cubecl_core::{
     Compiler,
     ir::{self, StorageType},
-    post_processing::{
-        checked_io::CheckedIoProcessor, predicate::PredicateProcessor,
-        saturating::SaturatingArithmeticProcessor,
-    },
+    post_processing::{predicate::PredicateProcessor, saturating::SaturatingArithmeticProcessor},
     prelude::KernelDefinition,
     server::ExecutionMode,
 };
-use cubecl_opt::OptimizerBuilder;
+use cubecl_opt::{OptimizerBuilder, UnrollOptions};
 use mlir_engine::MlirEngine;
 
-use crate::compiler::passes::{
-    erf_transform::ErfTransform,
-    trigonometries_transform::{HypotTransform, RhypotTransform},
+use crate::{
+    DEFAULT_PLANE_SIZE,
+    compiler::passes::{
+        erf_transform::ErfTransform,
+        trigonometries_transform::{HypotTransform, RhypotTransform},
+        unsupported_instructions::find_unsupported_instruction,
+    },
 };
 
 #[derive(Clone, Debug, Default)]
 pub struct MlirCompiler {}
 
-#[derive(Default, Debug)]
-pub struct MlirCompilerOptions {}
+#[derive(Debug)]
+pub struct MlirCompilerOptions {
+    /// Number of units grouped in a plane.
+    pub plane_size: u32,
+    /// Partially unroll the loops with a trip count known at compile time.
+    pub unroll_loops: bool,
+}
+
+impl Default for MlirCompilerOptions {
+    fn default() -> Self {
+        Self {
+            plane_size: DEFAULT_PLANE_SIZE,
+            unroll_loops: false,
+        }
+    }
+}
 
 impl Compiler for MlirCompiler {
     type Representation = MlirEngine;
@@ -44,8 +59,8 @@ impl Compiler for MlirCompiler {
     fn compile(
         &mut self,
         mut kernel: KernelDefinition,
-        _compilation_options: &Self::CompilationOptions, // TODO pass this through the visitor, though it doesn't need anything for the moment
-        mode: ExecutionMode, // TODO support this by adding array bound checking
+        compilation_options: &Self::CompilationOptions,
+        mode: ExecutionMode,
         addr_type: StorageType,
     ) -> Result<Self::Representation, CompilationError> {
         let errors = kernel.body.pop_errors();
@@ -64,17 +79,25 @@ impl Compiler for MlirCompiler {
 
         #[cfg(feature = "mlir-dump")]
         dump_scope(&kernel.body, &kernel.options.kernel_name);
-        let opt = OptimizerBuilder::default()
+        // Bounds checks aren't added by the `CheckedIoProcessor`, the visitor emits them on every
+        // indexed access so they also cover shared, local and constant arrays.
+        let mut builder = OptimizerBuilder::default()
             .with_transformer(ErfTransform)
             .with_transformer(HypotTransform)
             .with_transformer(RhypotTransform)
-            .with_processor(CheckedIoProcessor::new(
-                mode,
-                kernel.options.kernel_name.clone(),
-            ))
             .with_processor(SaturatingArithmeticProcessor::new(true))
-            .with_processor(PredicateProcessor)
-            .optimize(kernel.body.clone(), kernel.cube_dim);
+            .with_processor(PredicateProcessor);
+        if compilation_options.unroll_loops {
+            builder = builder.with_unroll(UnrollOptions::default());
+        }
+        let opt = builder.optimize(kernel.body.clone(), kernel.cube_dim);
+
+        if let Some(reason) = find_unsupported_instruction(&opt) {
+            return Err(CompilationError::UnsupportedInstruction {
+                reason,
+                backtrace: BackTrace::capture(),
+            });
+        }
 
         let mut shared_memories = SharedMemories::default();
         shared_memories.visit(&opt);
@@ -86,6 +109,8 @@ impl Compiler for MlirCompiler {
             &opt,
             shared_memories,
             addr_type,
+            compilation_options.plane_size,
+            mode,
         ))
     }
 
@@ -1,4 +1,4 @@
-use cubecl_core::{ir::StorageType, prelude::KernelDefinition};
+use cubecl_core::{ExecutionMode, ir::StorageType, prelude::KernelDefinition};
 use cubecl_opt::Optimizer;
 use tracel_llvm::mlir_rs::{
     Context, ExecutionEngine,
@@ -34,6 +34,8 @@ impl<'a> Module<'a> {
         opt: &Optimizer,
         shared_memories: &SharedMemories,
         addr_type: StorageType,
+        plane_size: u32,
+        execution_mode: ExecutionMode,
     ) {
         Visitor::visit_kernel(
             self.context,
@@ -43,6 +45,8 @@ impl<'a> Module<'a> {
             opt,
             shared_memories,
             addr_type,
+            plane_size,
+            execution_mode,
         )
     }
 
@@ -96,6 +100,11 @@ impl<'a> Module<'a> {
         self.module.as_operation().verify();
     }
 
+    /// The textual form of the module, which can be parsed back by [`Module::parse`].
+    pub(super) fn to_text(&self) -> String {
+        self.module.as_operation().to_string()
+    }
+
     pub(super) fn into_execution_engine(self) -> ExecutionEngine {
         ExecutionEngine::new(&self.module, 0, &[], true)
     }
@@ -1,3 +1,4 @@
 pub mod erf_transform;
 pub mod shared_memories;
 pub mod trigonometries_transform;
+pub mod unsupported_instructions;
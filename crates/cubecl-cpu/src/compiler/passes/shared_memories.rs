@@ -1,7 +1,8 @@
 use cubecl_core::ir::{OperationReflect, StorageType, Variable, VariableKind};
 use cubecl_opt::Optimizer;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum SharedMemory {
     Array {
         id: u32,
@@ -31,12 +32,15 @@ impl SharedMemories {
     pub fn visit_variable(&mut self, variable: Variable) {
         // Alignment is ignored for the moment it is taken from the type
         match variable.kind {
-            VariableKind::SharedArray { id, length, .. }
-                if self.0.iter().all(|shared_memory| shared_memory.id() != id) =>
-            {
+            VariableKind::SharedArray {
+                id,
+                length,
+                unroll_factor,
+                ..
+            } if self.0.iter().all(|shared_memory| shared_memory.id() != id) => {
                 let elem = variable.storage_type();
                 let vectorization = variable.vector_size();
-                let length = length * vectorization;
+                let length = length * unroll_factor * vectorization;
                 self.0.push(SharedMemory::Array {
                     id,
                     ty: elem,
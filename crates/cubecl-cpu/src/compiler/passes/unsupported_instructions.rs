@@ -0,0 +1,33 @@
+use cubecl_core::ir::{CoopMma, Operation};
+use cubecl_opt::Optimizer;
+
+/// Returns a description of the first instruction of the kernel that can't be lowered on CPU.
+///
+/// Cooperative matrices are emulated with a full copy of the matrix per unit, so the instructions
+/// relying on the register layout of a plane (`mma`, `ldmatrix`, `stmatrix`...) have no lowering.
+/// These features aren't advertised by [`crate::compiler::register_supported_types`], but kernels
+/// can still use them unconditionally, so they're rejected before the MLIR lowering.
+pub fn find_unsupported_instruction(opt: &Optimizer) -> Option<String> {
+    for node in opt.program.node_indices() {
+        let ops = opt.program[node].ops.borrow();
+        for op in ops.values() {
+            let supported = match &op.operation {
+                Operation::CoopMma(cmma) => !matches!(
+                    cmma,
+                    CoopMma::RowIndex { .. }
+                        | CoopMma::ColIndex { .. }
+                        | CoopMma::LoadMatrix { .. }
+                        | CoopMma::StoreMatrix { .. }
+                        | CoopMma::ExecuteManual { .. }
+                        | CoopMma::ExecuteScaled { .. }
+                ),
+                Operation::Tma(_) => false,
+                _ => true,
+            };
+            if !supported {
+                return Some(format!("{} is not supported on CPU.", op.operation));
+            }
+        }
+    }
+    None
+}
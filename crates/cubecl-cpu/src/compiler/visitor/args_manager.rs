@@ -9,6 +9,7 @@ use tracel_llvm::mlir_rs::{
     dialect::{arith, memref},
     ir::{
         Block, BlockRef, Location, Region,
+        attribute::IntegerAttribute,
         r#type::{FunctionType, IntegerType, MemRefType},
     },
 };
@@ -32,6 +33,7 @@ pub(super) struct ArgsManagerBuilder<'a, 'b> {
     shared_memories: &'b SharedMemories,
     addr_type: Type<'a>,
     addr_size: usize,
+    plane_size: u32,
 }
 
 impl<'a, 'b> ArgsManagerBuilder<'a, 'b> {
@@ -41,6 +43,7 @@ impl<'a, 'b> ArgsManagerBuilder<'a, 'b> {
         location: Location<'a>,
         shared_memories: &'b SharedMemories,
         addr_type: StorageType,
+        plane_size: u32,
     ) -> Self {
         let total_arg_len = kernel.buffers.len()
             + kernel.scalars.len()
@@ -81,6 +84,7 @@ impl<'a, 'b> ArgsManagerBuilder<'a, 'b> {
             info,
             addr_type: addr_type.to_type(context),
             addr_size: addr_type.size(),
+            plane_size,
         };
 
         for binding in kernel.buffers.iter() {
@@ -141,6 +145,7 @@ impl<'a, 'b> ArgsManagerBuilder<'a, 'b> {
             ext_meta_positions: self.ext_meta_positions.clone(),
             addr_type: self.addr_type,
             addr_size: self.addr_size,
+            plane_size: self.plane_size,
         };
 
         let block = Block::new(&self.block_inputs);
@@ -254,6 +259,7 @@ pub(super) struct ArgsManager<'a> {
     pub builtin: [Option<Value<'a, 'a>>; NB_BUILTIN],
     pub addr_type: Type<'a>,
     pub addr_size: usize,
+    pub plane_size: u32,
 }
 
 const NB_PASSED_BUILTIN: usize = 9;
@@ -271,6 +277,7 @@ impl<'a> ArgsManager<'a> {
     pub fn compute_derived_args_builtin(
         &mut self,
         block: BlockRef<'a, 'a>,
+        context: &'a Context,
         location: Location<'a>,
     ) {
         let cube_dim_xy = block
@@ -304,6 +311,20 @@ impl<'a> ArgsManager<'a> {
             .addi(unit_pos_yz_corrected, self.get(Builtin::UnitPosX), location)
             .unwrap();
         self.set(Builtin::UnitPos, unit_pos);
+
+        // Units are grouped in planes following their linear position.
+        let plane_dim =
+            IntegerAttribute::new(IntegerType::new(context, 32).into(), self.plane_size as i64);
+        let plane_dim = block
+            .append_op_result(arith::constant(context, plane_dim.into(), location))
+            .unwrap();
+        self.set(Builtin::PlaneDim, plane_dim);
+        let plane_pos = block.divui(unit_pos, plane_dim, location).unwrap();
+        self.set(Builtin::PlanePos, plane_pos);
+        let unit_pos_plane = block
+            .append_op_result(arith::remui(unit_pos, plane_dim, location))
+            .unwrap();
+        self.set(Builtin::UnitPosPlane, unit_pos_plane);
     }
 
     pub fn set(&mut self, builtin: Builtin, value: Value<'a, 'a>) {
@@ -1,6 +1,7 @@
 use cubecl_core::ir::{
-    AddressType, DeviceProperties, ElemType, FloatKind, IntKind, OpaqueType, StorageType, UIntKind,
-    features::TypeUsage,
+    AddressType, DeviceProperties, ElemType, FloatKind, IntKind, OpaqueType, StorageType, Type,
+    UIntKind,
+    features::{AtomicUsage, MmaConfig, TypeUsage},
 };
 use tracel_llvm::mlir_rs::{
     dialect::index,
@@ -33,7 +34,7 @@ impl IntoType for ElemType {
 impl IntoType for StorageType {
     fn to_type<'a>(self, context: &'a Context) -> Type<'a> {
         match self {
-            StorageType::Scalar(ty) => ty.to_type(context),
+            StorageType::Scalar(ty) | StorageType::Atomic(ty) => ty.to_type(context),
             StorageType::Opaque(OpaqueType::Barrier(_)) => IntegerType::new(context, 32).into(),
             _ => todo!("This type is not implemented yet. {}", self),
         }
@@ -81,10 +82,6 @@ pub fn register_supported_types(props: &mut DeviceProperties) {
         ElemType::Int(IntKind::I16),
         ElemType::Int(IntKind::I32),
         ElemType::Int(IntKind::I64),
-        // Elem::AtomicInt(IntKind::I32),
-        // Elem::AtomicInt(IntKind::I64),
-        // Elem::AtomicUInt(UIntKind::U32),
-        // Elem::AtomicUInt(UIntKind::U64),
         ElemType::Float(FloatKind::BF16),
         ElemType::Float(FloatKind::F16),
         ElemType::Float(FloatKind::F32),
@@ -92,7 +89,42 @@ pub fn register_supported_types(props: &mut DeviceProperties) {
         // Elem::Bool,
     ];
 
+    let supported_atomic_types = [
+        ElemType::Int(IntKind::I32),
+        ElemType::Int(IntKind::I64),
+        ElemType::UInt(UIntKind::U32),
+        ElemType::UInt(UIntKind::U64),
+        ElemType::Float(FloatKind::F32),
+        ElemType::Float(FloatKind::F64),
+    ];
+
     for ty in supported_types {
         props.register_type_usage(ty, TypeUsage::all());
     }
+
+    for ty in supported_atomic_types {
+        props.register_atomic_type_usage(Type::new(StorageType::Atomic(ty)), AtomicUsage::all());
+    }
+
+    // Cooperative matrices are emulated in software, so the shapes are those of WMMA. Manual MMA,
+    // `ldmatrix` and `stmatrix` depend on the register layout of a plane and stay unsupported.
+    let supported_mma_types = [
+        (FloatKind::F16, FloatKind::F16),
+        (FloatKind::F16, FloatKind::F32),
+        (FloatKind::BF16, FloatKind::F32),
+    ];
+    let supported_mma_shapes = [(16, 16, 16), (32, 8, 16), (8, 32, 16)];
+
+    for (ab, cd) in supported_mma_types {
+        for (m, n, k) in supported_mma_shapes {
+            props.features.matmul.cmma.insert(MmaConfig {
+                a_type: ElemType::Float(ab).into(),
+                b_type: ElemType::Float(ab).into(),
+                cd_type: ElemType::Float(cd).into(),
+                m,
+                k,
+                n,
+            });
+        }
+    }
 }
@@ -11,6 +11,7 @@ use std::collections::HashMap;
 
 use args_manager::{ArgsManager, ArgsManagerBuilder};
 use cubecl_core::{
+    ExecutionMode,
     ir::{Builtin, StorageType},
     prelude::KernelDefinition,
 };
@@ -53,6 +54,11 @@ pub struct Visitor<'a> {
     pub location: Location<'a>,
 
     pub str_counter: usize,
+    /// Accesses to memory are bounds checked unless the kernel is unchecked.
+    pub execution_mode: ExecutionMode,
+    pub kernel_name: String,
+    /// The global strings already appended to the module, by content.
+    pub global_strs: HashMap<String, String>,
 
     pub(self) variables: Variables<'a>,
     pub(self) args_manager: ArgsManager<'a>,
@@ -69,6 +75,8 @@ impl<'a> Visitor<'a> {
         location: Location<'a>,
         args_manager: ArgsManager<'a>,
         opt: &Optimizer,
+        execution_mode: ExecutionMode,
+        kernel_name: String,
     ) -> Self {
         let blocks = HashMap::new();
         let blocks_args = HashMap::new();
@@ -85,6 +93,9 @@ impl<'a> Visitor<'a> {
             context,
             location,
             str_counter,
+            execution_mode,
+            kernel_name,
+            global_strs: HashMap::new(),
             args_manager,
             variables,
         }
@@ -147,6 +158,7 @@ impl<'a> Visitor<'a> {
             .into()
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn visit_kernel<'b: 'a>(
         context: &'a Context,
         location: Location<'a>,
@@ -155,6 +167,8 @@ impl<'a> Visitor<'a> {
         opt: &Optimizer,
         shared_memories: &SharedMemories,
         addr_type: StorageType,
+        plane_size: u32,
+        execution_mode: ExecutionMode,
     ) {
         let name = StringAttribute::new(context, "kernel");
 
@@ -163,7 +177,14 @@ impl<'a> Visitor<'a> {
             Attribute::unit(context),
         )];
 
-        let args = ArgsManagerBuilder::new(kernel, context, location, shared_memories, addr_type);
+        let args = ArgsManagerBuilder::new(
+            kernel,
+            context,
+            location,
+            shared_memories,
+            addr_type,
+            plane_size,
+        );
 
         let func_type = TypeAttribute::new(args.get_fn_type(context).into());
         for const_array in opt.const_arrays() {
@@ -204,7 +225,17 @@ impl<'a> Visitor<'a> {
                 let args = args.create_top_block(&region, context, location);
                 let block = region.first_block().unwrap();
 
-                Self::insert_builtin_loop(block, module, opt, context, location, args).unwrap();
+                Self::insert_builtin_loop(
+                    block,
+                    module,
+                    opt,
+                    context,
+                    location,
+                    args,
+                    execution_mode,
+                    &kernel.options.kernel_name,
+                )
+                .unwrap();
 
                 block.append_operation(func::r#return(&[], location));
 
@@ -215,6 +246,7 @@ impl<'a> Visitor<'a> {
         ));
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(self) fn insert_builtin_loop(
         block: BlockRef<'a, 'a>,
         module: &tracel_llvm::mlir_rs::ir::Module<'a>,
@@ -222,13 +254,15 @@ impl<'a> Visitor<'a> {
         context: &'a Context,
         location: Location<'a>,
         mut args: ArgsManager<'a>,
+        execution_mode: ExecutionMode,
+        kernel_name: &str,
     ) -> Result<(), Error> {
         let basic_block_id = opt.entry();
         let integer_type = IntegerType::new(context, 32).into();
         let start = block.const_int_from_type(context, location, 0, integer_type)?;
         let step = block.const_int_from_type(context, location, 1, integer_type)?;
 
-        args.compute_derived_args_builtin(block, location);
+        args.compute_derived_args_builtin(block, context, location);
 
         let cube_count_dim_x = block.muli(
             args.get(Builtin::CubeCountX),
@@ -386,6 +420,8 @@ impl<'a> Visitor<'a> {
                                     location,
                                     args,
                                     opt,
+                                    execution_mode,
+                                    kernel_name.to_string(),
                                 );
                                 visitor.visit_basic_block(basic_block_id, opt);
 
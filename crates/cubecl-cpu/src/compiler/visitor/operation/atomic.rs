@@ -0,0 +1,240 @@
+use cubecl_core::{
+    ir::{AtomicOp, BinaryOperator, CompareAndSwapOperator, IndexOperator, VariableKind},
+    server::MemoryAccess,
+};
+use tracel_llvm::mlir_rs::{
+    dialect::{
+        arith::{self, CmpfPredicate, CmpiPredicate},
+        ods::memref as memref_ods,
+    },
+    ir::{Block, Region, attribute::IntegerAttribute, r#type::IntegerType},
+};
+
+use crate::compiler::visitor::prelude::*;
+
+/// The `arith::AtomicRMWKind` enum, as defined in `ArithBase.td`.
+#[derive(Clone, Copy, Debug)]
+enum AtomicRmwKind {
+    AddF = 0,
+    AddI = 1,
+    Assign = 2,
+    MaximumF = 3,
+    MaxS = 4,
+    MaxU = 5,
+    MinimumF = 6,
+    MinS = 7,
+    MinU = 8,
+    OrI = 11,
+    AndI = 12,
+}
+
+impl<'a> Visitor<'a> {
+    /// Indexing an atomic array doesn't load anything, it only records the location used by the
+    /// following atomic operations. Out-of-bounds atomics are redirected to the first element, so
+    /// they don't produce the right value but stay memory safe.
+    pub(super) fn visit_atomic_index(
+        &mut self,
+        index: &IndexOperator,
+        out: Variable,
+        checked: bool,
+    ) {
+        let index_value = self
+            .get_access_index(
+                index.list,
+                index.index,
+                out.ty,
+                index.unroll_factor,
+                MemoryAccess::Read,
+                checked,
+            )
+            .index;
+        let memref = self.get_memory(index.list);
+        self.variables
+            .atomic_pointers
+            .insert(out.kind, (memref, index_value));
+    }
+
+    pub fn visit_atomic(&mut self, atomic: &AtomicOp, out: Variable) {
+        match atomic {
+            AtomicOp::Load(op) => {
+                // A plain load could be hoisted out of a loop spinning on the value, so it's
+                // emitted as an atomic operation that keeps the current value.
+                let value =
+                    self.append_generic_atomic_rmw(op.input, |_visitor, _block, current| current);
+                self.insert_variable(out, value);
+            }
+            AtomicOp::Store(op) => {
+                let value = self.get_variable(op.input);
+                self.append_atomic_rmw(AtomicRmwKind::Assign, out, value);
+            }
+            AtomicOp::Swap(op) => self.visit_atomic_rmw(AtomicRmwKind::Assign, op, out),
+            AtomicOp::Add(op) => {
+                let kind = match op.lhs.ty.is_float() {
+                    true => AtomicRmwKind::AddF,
+                    false => AtomicRmwKind::AddI,
+                };
+                self.visit_atomic_rmw(kind, op, out);
+            }
+            AtomicOp::Sub(op) => {
+                // There is no atomic subtraction, so we add the negated value instead.
+                let value = self.get_variable(op.rhs);
+                let (kind, negated) = match op.lhs.ty.is_float() {
+                    true => (
+                        AtomicRmwKind::AddF,
+                        self.append_operation_with_result(arith::negf(value, self.location)),
+                    ),
+                    false => {
+                        let zero = self.create_int_constant_from_item(op.rhs.ty, 0);
+                        (
+                            AtomicRmwKind::AddI,
+                            self.append_operation_with_result(arith::subi(
+                                zero,
+                                value,
+                                self.location,
+                            )),
+                        )
+                    }
+                };
+                let old = self.append_atomic_rmw(kind, op.lhs, negated);
+                self.insert_variable(out, old);
+            }
+            AtomicOp::Max(op) => {
+                let kind = if op.lhs.ty.is_float() {
+                    AtomicRmwKind::MaximumF
+                } else if op.lhs.ty.is_signed_int() {
+                    AtomicRmwKind::MaxS
+                } else {
+                    AtomicRmwKind::MaxU
+                };
+                self.visit_atomic_rmw(kind, op, out);
+            }
+            AtomicOp::Min(op) => {
+                let kind = if op.lhs.ty.is_float() {
+                    AtomicRmwKind::MinimumF
+                } else if op.lhs.ty.is_signed_int() {
+                    AtomicRmwKind::MinS
+                } else {
+                    AtomicRmwKind::MinU
+                };
+                self.visit_atomic_rmw(kind, op, out);
+            }
+            AtomicOp::And(op) => self.visit_atomic_rmw(AtomicRmwKind::AndI, op, out),
+            AtomicOp::Or(op) => self.visit_atomic_rmw(AtomicRmwKind::OrI, op, out),
+            AtomicOp::Xor(op) => {
+                // `xori` isn't an atomic kind in this version of MLIR.
+                let value = self.get_variable(op.rhs);
+                let old = self.append_generic_atomic_rmw(op.lhs, |visitor, block, current| {
+                    block
+                        .append_op_result(arith::xori(current, value, visitor.location))
+                        .unwrap()
+                });
+                self.insert_variable(out, old);
+            }
+            AtomicOp::CompareAndSwap(op) => self.visit_compare_and_swap(op, out),
+        }
+    }
+
+    fn visit_atomic_rmw(&mut self, kind: AtomicRmwKind, op: &BinaryOperator, out: Variable) {
+        let value = self.get_variable(op.rhs);
+        let old = self.append_atomic_rmw(kind, op.lhs, value);
+        self.insert_variable(out, old);
+    }
+
+    fn visit_compare_and_swap(&mut self, op: &CompareAndSwapOperator, out: Variable) {
+        let cmp = self.get_variable(op.cmp);
+        let val = self.get_variable(op.val);
+        let is_float = op.input.ty.is_float();
+
+        let old = self.append_generic_atomic_rmw(op.input, |visitor, block, current| {
+            let equal = match is_float {
+                true => arith::cmpf(
+                    visitor.context,
+                    CmpfPredicate::Oeq,
+                    current,
+                    cmp,
+                    visitor.location,
+                ),
+                false => arith::cmpi(
+                    visitor.context,
+                    CmpiPredicate::Eq,
+                    current,
+                    cmp,
+                    visitor.location,
+                ),
+            };
+            let equal = block.append_op_result(equal).unwrap();
+            block
+                .append_op_result(arith::select(equal, val, current, visitor.location))
+                .unwrap()
+        });
+        self.insert_variable(out, old);
+    }
+
+    /// Appends a `memref.atomic_rmw` on the location pointed by `pointer`, returning the old value.
+    fn append_atomic_rmw(
+        &mut self,
+        kind: AtomicRmwKind,
+        pointer: Variable,
+        value: Value<'a, 'a>,
+    ) -> Value<'a, 'a> {
+        let (memref, index) = self.get_atomic_pointer(pointer);
+        let kind = IntegerAttribute::new(IntegerType::new(self.context, 64).into(), kind as i64);
+        self.append_operation_with_result(memref_ods::atomic_rmw(
+            self.context,
+            value.r#type(),
+            value,
+            memref,
+            &[index],
+            kind.into(),
+            self.location,
+        ))
+    }
+
+    /// Appends a `memref.generic_atomic_rmw` on the location pointed by `pointer`, where `body`
+    /// computes the new value from the current one. Returns the old value.
+    fn append_generic_atomic_rmw(
+        &mut self,
+        pointer: Variable,
+        body: impl FnOnce(&Self, &Block<'a>, Value<'a, 'a>) -> Value<'a, 'a>,
+    ) -> Value<'a, 'a> {
+        let (memref, index) = self.get_atomic_pointer(pointer);
+        let r#type = pointer.storage_type().to_type(self.context);
+
+        let region = Region::new();
+        let block = Block::new(&[(r#type, self.location)]);
+        let current = block.argument(0).unwrap().into();
+        let result = body(self, &block, current);
+        block
+            .append_operation(memref_ods::atomic_yield(self.context, result, self.location).into());
+        region.append_block(block);
+
+        self.append_operation_with_result(memref_ods::generic_atomic_rmw(
+            self.context,
+            r#type,
+            memref,
+            &[index],
+            region,
+            self.location,
+        ))
+    }
+
+    fn get_atomic_pointer(&mut self, pointer: Variable) -> (Value<'a, 'a>, Value<'a, 'a>) {
+        match pointer.kind {
+            VariableKind::Shared { .. } => {
+                let memref = self.get_memory(pointer);
+                let zero = IntegerAttribute::new(Type::index(self.context), 0);
+                let zero = self.append_operation_with_result(arith::constant(
+                    self.context,
+                    zero.into(),
+                    self.location,
+                ));
+                (memref, zero)
+            }
+            _ => *self
+                .variables
+                .atomic_pointers
+                .get(&pointer.kind)
+                .expect("Atomic should have been indexed before"),
+        }
+    }
+}
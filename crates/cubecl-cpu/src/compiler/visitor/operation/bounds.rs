@@ -0,0 +1,207 @@
+use cubecl_core::{
+    ExecutionMode,
+    ir::{self, VariableKind},
+    server::MemoryAccess,
+};
+use tracel_llvm::mlir_rs::{
+    dialect::{
+        arith::{self, CmpiPredicate},
+        func, index, llvm,
+        ods::llvm as llvm_ods,
+        scf,
+    },
+    ir::{
+        Block, Operation, Region,
+        attribute::{FlatSymbolRefAttribute, IntegerAttribute},
+        r#type::IntegerType,
+    },
+};
+
+use crate::compiler::visitor::prelude::*;
+
+/// The index of an access to a list, in elements of the list.
+pub(super) struct CheckedIndex<'a> {
+    /// Replaced by zero when the access is out of bounds.
+    pub index: Value<'a, 'a>,
+    /// Whether the access is in bounds, `None` when the list isn't checked.
+    pub in_bounds: Option<Value<'a, 'a>>,
+}
+
+impl<'a> Visitor<'a> {
+    /// Computes the index of an access to `list`, in elements of the list. Unless the access or
+    /// the kernel is unchecked, out-of-bounds indices are replaced by zero, and reported to the
+    /// runtime in validate mode.
+    pub(super) fn get_access_index(
+        &mut self,
+        list: Variable,
+        index: Variable,
+        target_item: ir::Type,
+        unroll_factor: usize,
+        access: MemoryAccess,
+        checked: bool,
+    ) -> CheckedIndex<'a> {
+        let list_is_vectorized = list.ty.is_vectorized();
+        let length = match checked {
+            true => self.checked_length(list, unroll_factor),
+            false => None,
+        };
+        let Some(length) = length else {
+            return CheckedIndex {
+                index: self.get_index(index, target_item, list_is_vectorized),
+                in_bounds: None,
+            };
+        };
+
+        let index_type = Type::index(self.context);
+        let value = self.get_variable(index);
+        let value =
+            self.append_operation_with_result(index::casts(value, index_type, self.location));
+        let in_bounds = self.append_operation_with_result(arith::cmpi(
+            self.context,
+            CmpiPredicate::Ult,
+            value,
+            length,
+            self.location,
+        ));
+        if self.execution_mode == ExecutionMode::Validate {
+            self.append_report_out_of_bounds(list, access, in_bounds, value, length);
+        }
+
+        let zero = self.index_constant(0);
+        let value =
+            self.append_operation_with_result(arith::select(in_bounds, value, zero, self.location));
+        CheckedIndex {
+            index: self.scale_index(value, target_item, list_is_vectorized),
+            in_bounds: Some(in_bounds),
+        }
+    }
+
+    /// Appends `operation`, only executing it when `guard` holds.
+    pub(super) fn append_guarded(
+        &mut self,
+        guard: Option<Value<'a, 'a>>,
+        operation: Operation<'a>,
+    ) {
+        let Some(guard) = guard else {
+            self.block.append_operation(operation);
+            return;
+        };
+        let block = Block::new(&[]);
+        block.append_operation(operation);
+        block.append_operation(scf::r#yield(&[], self.location));
+        let region = Region::new();
+        region.append_block(block);
+        self.block
+            .append_operation(scf::r#if(guard, &[], region, Region::new(), self.location));
+    }
+
+    /// The number of vectors in `list`, or `None` if accesses to it aren't checked.
+    fn checked_length(&mut self, list: Variable, unroll_factor: usize) -> Option<Value<'a, 'a>> {
+        if self.execution_mode == ExecutionMode::Unchecked {
+            return None;
+        }
+        let length = match list.kind {
+            VariableKind::GlobalInputArray(_) | VariableKind::GlobalOutputArray(_) => {
+                let position = self.args_manager.buffer_position(list);
+                let offset = self.args_manager.metadata.buffer_len_index(position);
+                let length = self.load_metadata(offset);
+                let length = self.append_operation_with_result(index::casts(
+                    length,
+                    Type::index(self.context),
+                    self.location,
+                ));
+                if unroll_factor == 1 {
+                    return Some(length);
+                }
+                let unroll_factor = self.index_constant(unroll_factor as i64);
+                return Some(self.append_operation_with_result(arith::muli(
+                    length,
+                    unroll_factor,
+                    self.location,
+                )));
+            }
+            // Vectorized accesses index the unrolled vectors, like for global buffers.
+            VariableKind::LocalArray { length, .. } | VariableKind::SharedArray { length, .. } => {
+                length * unroll_factor
+            }
+            VariableKind::ConstantArray {
+                length,
+                unroll_factor,
+                ..
+            } => length * unroll_factor,
+            _ => return None,
+        };
+        Some(self.index_constant(length as i64))
+    }
+
+    /// Calls [`report_out_of_bounds`](crate::compute::validation::report_out_of_bounds) when the
+    /// access isn't in bounds.
+    fn append_report_out_of_bounds(
+        &mut self,
+        list: Variable,
+        access: MemoryAccess,
+        in_bounds: Value<'a, 'a>,
+        index: Value<'a, 'a>,
+        length: Value<'a, 'a>,
+    ) {
+        let kernel_name = self.kernel_name.clone();
+        let kernel = self.global_str_pointer(&kernel_name);
+        let buffer = self.global_str_pointer(&list.to_string());
+
+        let bool_type = IntegerType::new(self.context, 1).into();
+        let integer_type = IntegerType::new(self.context, 32).into();
+        let long_type = IntegerType::new(self.context, 64).into();
+        let constant = |r#type: Type<'a>, value: i64| {
+            self.append_operation_with_result(arith::constant(
+                self.context,
+                IntegerAttribute::new(r#type, value).into(),
+                self.location,
+            ))
+        };
+        let true_value = constant(bool_type, 1);
+        let write = constant(integer_type, (access == MemoryAccess::Write) as i64);
+        let out_of_bounds =
+            self.append_operation_with_result(arith::xori(in_bounds, true_value, self.location));
+        let index =
+            self.append_operation_with_result(index::casts(index, long_type, self.location));
+        let length =
+            self.append_operation_with_result(index::casts(length, long_type, self.location));
+
+        let call = func::call(
+            self.context,
+            FlatSymbolRefAttribute::new(self.context, "report_out_of_bounds"),
+            &[kernel, buffer, write, index, length],
+            &[],
+            self.location,
+        );
+        self.append_guarded(Some(out_of_bounds), call);
+    }
+
+    /// Returns a pointer to a null-terminated copy of `text`, stored once per module.
+    fn global_str_pointer(&mut self, text: &str) -> Value<'a, 'a> {
+        let global_name = match self.global_strs.get(text) {
+            Some(global_name) => global_name.clone(),
+            None => {
+                let global_name = self.append_global_str(&format!("{text}\0"));
+                self.global_strs
+                    .insert(text.to_string(), global_name.clone());
+                global_name
+            }
+        };
+        self.append_operation_with_result(llvm_ods::mlir_addressof(
+            self.context,
+            llvm::r#type::pointer(self.context, 0),
+            FlatSymbolRefAttribute::new(self.context, &global_name),
+            self.location,
+        ))
+    }
+
+    pub(super) fn index_constant(&self, value: i64) -> Value<'a, 'a> {
+        let index_type = Type::index(self.context);
+        self.append_operation_with_result(arith::constant(
+            self.context,
+            IntegerAttribute::new(index_type, value).into(),
+            self.location,
+        ))
+    }
+}
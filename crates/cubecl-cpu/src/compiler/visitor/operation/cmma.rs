@@ -0,0 +1,347 @@
+//! Software lowering of the cooperative matrix operations.
+//!
+//! Every unit holds a full copy of the matrices of its plane in local memory, stored row-major
+//! whatever their layout. Since the operations are uniform in a plane, all the copies stay
+//! identical, and only the first unit of the plane writes the result of a store.
+//!
+//! The matrices aren't shared between the units of a plane, so each unit needs local memory for
+//! all the elements of every matrix it uses, and each unit computes the full product of
+//! `execute`, multiplying the work by the plane size. This is only meant to run the kernels
+//! written for the shapes advertised in `register_supported_types`, such as the 16x16x16 tiles of
+//! the `cmma` runtime tests, not to be fast.
+
+use cubecl_core::ir::{
+    self, Builtin, CoopMma, MatrixIdent, MatrixLayout, StorageType, VariableKind,
+};
+use tracel_llvm::mlir_rs::{
+    dialect::{
+        arith::{self, CmpiPredicate},
+        index, memref, scf,
+    },
+    ir::{Block, Region, attribute::IntegerAttribute, r#type::IntegerType},
+};
+
+use crate::compiler::visitor::prelude::*;
+
+impl<'a> Visitor<'a> {
+    pub fn visit_cmma(&mut self, cmma: &CoopMma, out: Variable) {
+        match cmma {
+            CoopMma::Fill { value } => {
+                let matrix = self.get_memory(out);
+                let value = self.get_variable(*value);
+                let num_elems = matrix_of(out).num_elems();
+                self.append_for(num_elems, |this, index| {
+                    this.block.append_operation(memref::store(
+                        value,
+                        matrix,
+                        &[index],
+                        this.location,
+                    ));
+                });
+            }
+            CoopMma::Load {
+                value,
+                stride,
+                offset,
+                layout,
+            } => {
+                let mat = matrix_of(out);
+                let layout = layout.unwrap_or(mat.layout);
+                self.visit_cmma_load(*value, *stride, *offset, layout, out);
+            }
+            CoopMma::Execute {
+                mat_a,
+                mat_b,
+                mat_c,
+            } => self.visit_cmma_execute(*mat_a, *mat_b, *mat_c, out),
+            CoopMma::Store {
+                mat,
+                stride,
+                offset,
+                layout,
+            } => self.visit_cmma_store(*mat, *stride, *offset, *layout, out),
+            CoopMma::Cast { input } => {
+                let input_matrix = self.get_memory(*input);
+                let matrix = self.get_memory(out);
+                let (from, to) = (input.storage_type(), out.storage_type());
+                let num_elems = matrix_of(out).num_elems();
+                self.append_for(num_elems, |this, index| {
+                    let value = this.append_operation_with_result(memref::load(
+                        input_matrix,
+                        &[index],
+                        this.location,
+                    ));
+                    let value = this.cast_element(from, to, value);
+                    this.block.append_operation(memref::store(
+                        value,
+                        matrix,
+                        &[index],
+                        this.location,
+                    ));
+                });
+            }
+            CoopMma::RowIndex { .. }
+            | CoopMma::ColIndex { .. }
+            | CoopMma::LoadMatrix { .. }
+            | CoopMma::StoreMatrix { .. }
+            | CoopMma::ExecuteManual { .. }
+            | CoopMma::ExecuteScaled { .. } => {
+                unreachable!(
+                    "{cmma} is rejected before the lowering, see `find_unsupported_instruction`."
+                )
+            }
+        }
+    }
+
+    fn visit_cmma_load(
+        &mut self,
+        list: Variable,
+        stride: Variable,
+        offset: Variable,
+        layout: MatrixLayout,
+        out: Variable,
+    ) {
+        let matrix = self.get_memory(out);
+        let memory = self.get_memory(list);
+        let (rows, columns) = matrix_shape(&matrix_of(out));
+        let offset = self.get_element_offset(list, offset);
+        let stride = self.get_index_value(stride);
+        let (from, to) = (list.storage_type(), out.storage_type());
+
+        self.append_for(rows, |this, row| {
+            this.append_for(columns, |this, column| {
+                let source = this.strided_index(offset, stride, row, column, layout);
+                let value = this.append_operation_with_result(memref::load(
+                    memory,
+                    &[source],
+                    this.location,
+                ));
+                let value = this.cast_element(from, to, value);
+                let destination = this.row_major_index(row, column, columns);
+                this.block.append_operation(memref::store(
+                    value,
+                    matrix,
+                    &[destination],
+                    this.location,
+                ));
+            });
+        });
+    }
+
+    fn visit_cmma_store(
+        &mut self,
+        mat: Variable,
+        stride: Variable,
+        offset: Variable,
+        layout: MatrixLayout,
+        out: Variable,
+    ) {
+        let matrix = self.get_memory(mat);
+        let memory = self.get_memory(out);
+        let (rows, columns) = matrix_shape(&matrix_of(mat));
+        let offset = self.get_element_offset(out, offset);
+        let stride = self.get_index_value(stride);
+        let (from, to) = (mat.storage_type(), out.storage_type());
+
+        let lane = self.get_builtin(Builtin::UnitPosPlane);
+        let zero = self.append_operation_with_result(arith::constant(
+            self.context,
+            IntegerAttribute::new(IntegerType::new(self.context, 32).into(), 0).into(),
+            self.location,
+        ));
+        let is_leader = self.append_operation_with_result(arith::cmpi(
+            self.context,
+            CmpiPredicate::Eq,
+            lane,
+            zero,
+            self.location,
+        ));
+
+        self.append_for(rows, |this, row| {
+            this.append_for(columns, |this, column| {
+                let source = this.row_major_index(row, column, columns);
+                let value = this.append_operation_with_result(memref::load(
+                    matrix,
+                    &[source],
+                    this.location,
+                ));
+                let value = this.cast_element(from, to, value);
+                let destination = this.strided_index(offset, stride, row, column, layout);
+                let store = memref::store(value, memory, &[destination], this.location);
+                this.append_guarded(Some(is_leader), store);
+            });
+        });
+    }
+
+    /// Computes `D = A * B + C` in the element type of `D`. `C` is first copied to `D`, which is
+    /// then accumulated in place, so `C` and `D` can be the same matrix.
+    fn visit_cmma_execute(
+        &mut self,
+        mat_a: Variable,
+        mat_b: Variable,
+        mat_c: Variable,
+        out: Variable,
+    ) {
+        let (matrix_a, matrix_b) = (self.get_memory(mat_a), self.get_memory(mat_b));
+        let (matrix_c, matrix_d) = (self.get_memory(mat_c), self.get_memory(out));
+        let mat = matrix_of(out);
+        let (m, n, k) = (mat.m, mat.n, mat.k);
+        let (elem_a, elem_b) = (mat_a.storage_type(), mat_b.storage_type());
+        let (elem_c, elem_d) = (mat_c.storage_type(), out.storage_type());
+        let is_int = elem_d.is_int();
+
+        self.append_for(m * n, |this, index| {
+            let value =
+                this.append_operation_with_result(memref::load(matrix_c, &[index], this.location));
+            let value = this.cast_element(elem_c, elem_d, value);
+            this.block
+                .append_operation(memref::store(value, matrix_d, &[index], this.location));
+        });
+
+        self.append_for(m, |this, row| {
+            this.append_for(n, |this, column| {
+                let index_d = this.row_major_index(row, column, n);
+                this.append_for(k, |this, inner| {
+                    let index_a = this.row_major_index(row, inner, k);
+                    let index_b = this.row_major_index(inner, column, n);
+                    let a = this.append_operation_with_result(memref::load(
+                        matrix_a,
+                        &[index_a],
+                        this.location,
+                    ));
+                    let a = this.cast_element(elem_a, elem_d, a);
+                    let b = this.append_operation_with_result(memref::load(
+                        matrix_b,
+                        &[index_b],
+                        this.location,
+                    ));
+                    let b = this.cast_element(elem_b, elem_d, b);
+                    let d = this.append_operation_with_result(memref::load(
+                        matrix_d,
+                        &[index_d],
+                        this.location,
+                    ));
+                    let product = match is_int {
+                        true => arith::muli(a, b, this.location),
+                        false => arith::mulf(a, b, this.location),
+                    };
+                    let product = this.append_operation_with_result(product);
+                    let sum = match is_int {
+                        true => arith::addi(d, product, this.location),
+                        false => arith::addf(d, product, this.location),
+                    };
+                    let d = this.append_operation_with_result(sum);
+                    this.block.append_operation(memref::store(
+                        d,
+                        matrix_d,
+                        &[index_d],
+                        this.location,
+                    ));
+                });
+            });
+        });
+    }
+
+    /// Appends a loop over `0..count`, `body` receives the loop index.
+    fn append_for(&mut self, count: usize, body: impl FnOnce(&mut Self, Value<'a, 'a>)) {
+        let start = self.index_constant(0);
+        let end = self.index_constant(count as i64);
+        let step = self.index_constant(1);
+
+        let region = Region::new();
+        region.append_block(Block::new(&[(Type::index(self.context), self.location)]));
+        let loop_block = region.first_block().unwrap();
+        let parent_block = std::mem::replace(&mut self.block, loop_block);
+        body(self, loop_block.argument(0).unwrap().into());
+        self.block
+            .append_operation(scf::r#yield(&[], self.location));
+        self.block = parent_block;
+
+        self.block
+            .append_operation(scf::r#for(start, end, step, region, self.location));
+    }
+
+    /// The offset of a slice, converted from vectors of the list to elements of its memory.
+    fn get_element_offset(&mut self, list: Variable, offset: Variable) -> Value<'a, 'a> {
+        let offset = self.get_index_value(offset);
+        match list.vector_size() {
+            1 => offset,
+            vector_size => {
+                let vector_size = self.index_constant(vector_size as i64);
+                self.append_operation_with_result(arith::muli(offset, vector_size, self.location))
+            }
+        }
+    }
+
+    fn get_index_value(&mut self, variable: Variable) -> Value<'a, 'a> {
+        let value = self.get_variable(variable);
+        self.append_operation_with_result(index::casts(
+            value,
+            Type::index(self.context),
+            self.location,
+        ))
+    }
+
+    /// The index of the element at `(row, column)` in a list, following `layout`.
+    fn strided_index(
+        &self,
+        offset: Value<'a, 'a>,
+        stride: Value<'a, 'a>,
+        row: Value<'a, 'a>,
+        column: Value<'a, 'a>,
+        layout: MatrixLayout,
+    ) -> Value<'a, 'a> {
+        let (outer, inner) = match layout {
+            MatrixLayout::RowMajor => (row, column),
+            MatrixLayout::ColMajor => (column, row),
+            MatrixLayout::Undefined => panic!("The layout of a matrix access must be defined."),
+        };
+        let index = self.append_operation_with_result(arith::muli(outer, stride, self.location));
+        let index = self.append_operation_with_result(arith::addi(index, inner, self.location));
+        self.append_operation_with_result(arith::addi(offset, index, self.location))
+    }
+
+    fn row_major_index(
+        &self,
+        row: Value<'a, 'a>,
+        column: Value<'a, 'a>,
+        columns: usize,
+    ) -> Value<'a, 'a> {
+        let columns = self.index_constant(columns as i64);
+        let index = self.append_operation_with_result(arith::muli(row, columns, self.location));
+        self.append_operation_with_result(arith::addi(index, column, self.location))
+    }
+
+    fn cast_element(
+        &self,
+        from: StorageType,
+        to: StorageType,
+        value: Value<'a, 'a>,
+    ) -> Value<'a, 'a> {
+        if from == to {
+            return value;
+        }
+        let target = to.to_type(self.context);
+        if from.is_int() == to.is_int() {
+            self.get_cast_same_type_category(from, to, target, value)
+        } else {
+            self.get_cast_different_type_category(from, to, target, value)
+        }
+    }
+}
+
+fn matrix_of(variable: Variable) -> ir::Matrix {
+    match variable.kind {
+        VariableKind::Matrix { mat, .. } => mat,
+        _ => unreachable!("{variable} isn't a matrix"),
+    }
+}
+
+/// The number of rows and columns of the matrix.
+fn matrix_shape(mat: &ir::Matrix) -> (usize, usize) {
+    match mat.ident {
+        MatrixIdent::A => (mat.m, mat.k),
+        MatrixIdent::B => (mat.k, mat.n),
+        MatrixIdent::Accumulator => (mat.m, mat.n),
+    }
+}
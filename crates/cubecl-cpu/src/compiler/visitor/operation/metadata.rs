@@ -5,6 +5,11 @@ use crate::compiler::visitor::prelude::*;
 
 impl<'a> Visitor<'a> {
     fn append_metadata(&mut self, offset: u32, out: Variable) {
+        let result = self.load_metadata(offset);
+        self.insert_variable(out, result);
+    }
+
+    pub(super) fn load_metadata(&mut self, offset: u32) -> Value<'a, 'a> {
         let metadata_memref = self.args_manager.static_metadata_memref.unwrap();
         let offset = self
             .block
@@ -15,12 +20,7 @@ impl<'a> Visitor<'a> {
                 Type::index(self.context),
             )
             .unwrap();
-        let result = self.append_operation_with_result(memref::load(
-            metadata_memref,
-            &[offset],
-            self.location,
-        ));
-        self.insert_variable(out, result);
+        self.append_operation_with_result(memref::load(metadata_memref, &[offset], self.location))
     }
 
     fn append_extended_metadata(&mut self, offset: u32, dim: Variable, out: Variable) {
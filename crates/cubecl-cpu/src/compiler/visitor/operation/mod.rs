@@ -1,8 +1,12 @@
 pub(super) mod arithmetic;
+pub(super) mod atomic;
 pub(super) mod bitwise;
+pub(super) mod bounds;
+pub(super) mod cmma;
 pub(super) mod comparison;
 pub(super) mod metadata;
 pub(super) mod operator;
+pub(super) mod plane;
 pub(super) mod synchronization;
 
 use cubecl_core::ir::{
@@ -121,8 +125,8 @@ impl<'a> Visitor<'a> {
 
     pub fn visit_operation_with_out(&mut self, operation: &Operation, out: Variable) {
         match operation {
-            Operation::Atomic(_atomic) => {
-                todo!("Atomic operation are not yet supported");
+            Operation::Atomic(atomic) => {
+                self.visit_atomic(atomic, out);
             }
             Operation::Arithmetic(arithmetic) => {
                 self.visit_arithmetic(arithmetic, out);
@@ -152,7 +156,13 @@ impl<'a> Visitor<'a> {
             Operation::Operator(operator) => {
                 self.visit_operator_with_out(operator, out);
             }
-            Operation::CoopMma(_) | Operation::Plane(_) | Operation::Tma(_) => {
+            Operation::Plane(plane) => {
+                self.visit_plane(plane, out);
+            }
+            Operation::CoopMma(cmma) => {
+                self.visit_cmma(cmma, out);
+            }
+            Operation::Tma(_) => {
                 panic!("{operation} is not supported on CPU.");
             }
             Operation::Branch(_) => {
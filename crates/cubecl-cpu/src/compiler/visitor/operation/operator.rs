@@ -1,4 +1,7 @@
-use cubecl_core::ir::{IndexAssignOperator, IndexOperator, Operator, StorageType, VariableKind};
+use cubecl_core::{
+    ir::{IndexAssignOperator, IndexOperator, Operator, StorageType, VariableKind},
+    server::MemoryAccess,
+};
 use tracel_llvm::mlir_rs::{
     dialect::{
         arith, index, memref,
@@ -7,7 +10,7 @@ use tracel_llvm::mlir_rs::{
     ir::{Operation, r#type::IntegerType},
 };
 
-use crate::compiler::visitor::prelude::*;
+use crate::compiler::visitor::{operation::bounds::CheckedIndex, prelude::*};
 
 impl<'a> Visitor<'a> {
     pub fn visit_operator_with_out(&mut self, operator: &Operator, out: Variable) {
@@ -22,16 +25,31 @@ impl<'a> Visitor<'a> {
                 self.visit_cast(cast.input, out);
             }
             Operator::CopyMemory(copy_memory) => {
-                let memref = self.get_memory(copy_memory.input);
-                let in_index = self.get_index(
+                let CheckedIndex {
+                    index: in_index,
+                    in_bounds: in_bounds_read,
+                } = self.get_access_index(
+                    copy_memory.input,
                     copy_memory.in_index,
                     copy_memory.input.ty,
-                    copy_memory.input.ty.is_vectorized(),
+                    1,
+                    MemoryAccess::Read,
+                    true,
                 );
+                let CheckedIndex {
+                    index: out_index,
+                    in_bounds: in_bounds_write,
+                } = self.get_access_index(
+                    out,
+                    copy_memory.out_index,
+                    out.ty,
+                    1,
+                    MemoryAccess::Write,
+                    true,
+                );
+                let memref = self.get_memory(copy_memory.input);
                 let out_memref = self.get_memory(out);
-                let out_index =
-                    self.get_index(copy_memory.out_index, out.ty, out.ty.is_vectorized());
-                if out.ty.is_vectorized() {
+                let operation = if out.ty.is_vectorized() {
                     let result = out.ty.to_type(self.context);
                     let value = self.append_operation_with_result(vector::load(
                         self.context,
@@ -40,36 +58,44 @@ impl<'a> Visitor<'a> {
                         &[in_index],
                         self.location,
                     ));
-                    self.block.append_operation(vector::store(
-                        self.context,
-                        value,
-                        out_memref,
-                        &[out_index],
-                        self.location,
-                    ));
+                    vector::store(self.context, value, out_memref, &[out_index], self.location)
+                        .into()
                 } else {
                     let value = self.append_operation_with_result(memref::load(
                         memref,
                         &[in_index],
                         self.location,
                     ));
-                    self.block.append_operation(memref::store(
-                        value,
-                        out_memref,
-                        &[out_index],
-                        self.location,
-                    ));
-                }
+                    memref::store(value, out_memref, &[out_index], self.location)
+                };
+                let in_bounds = match (in_bounds_read, in_bounds_write) {
+                    (Some(read), Some(write)) => Some(
+                        self.append_operation_with_result(arith::andi(read, write, self.location)),
+                    ),
+                    (read, write) => read.or(write),
+                };
+                self.append_guarded(in_bounds, operation);
             }
             Operator::CopyMemoryBulk(_copy_memory_bulk) => {
                 todo!("copy_memory_bulk is not implemented {}", operator)
             }
-            Operator::Index(index) | Operator::UncheckedIndex(index) => {
-                let load_ssa = self.visit_index(index, out);
+            Operator::Index(index) if out.ty.is_atomic() => {
+                self.visit_atomic_index(index, out, true);
+            }
+            Operator::UncheckedIndex(index) if out.ty.is_atomic() => {
+                self.visit_atomic_index(index, out, false);
+            }
+            Operator::Index(index) => {
+                let load_ssa = self.visit_index(index, out, true);
+                self.insert_variable(out, load_ssa);
+            }
+            Operator::UncheckedIndex(index) => {
+                let load_ssa = self.visit_index(index, out, false);
                 self.insert_variable(out, load_ssa);
             }
-            Operator::IndexAssign(index_assign) | Operator::UncheckedIndexAssign(index_assign) => {
-                self.visit_index_assign(index_assign, out)
+            Operator::IndexAssign(index_assign) => self.visit_index_assign(index_assign, out, true),
+            Operator::UncheckedIndexAssign(index_assign) => {
+                self.visit_index_assign(index_assign, out, false)
             }
             Operator::InitVector(init_vector) => {
                 let inputs: Vec<_> = init_vector
@@ -149,10 +175,16 @@ impl<'a> Visitor<'a> {
         }
     }
 
-    fn visit_index(&mut self, index: &IndexOperator, out: Variable) -> Value<'a, 'a> {
+    fn visit_index(
+        &mut self,
+        index: &IndexOperator,
+        out: Variable,
+        checked: bool,
+    ) -> Value<'a, 'a> {
         assert!(index.vector_size == 0);
-        let mut index_value = self.get_index(index.index, out.ty, index.list.ty.is_vectorized());
         if !self.is_memory(index.list) {
+            let mut index_value =
+                self.get_index(index.index, out.ty, index.list.ty.is_vectorized());
             let to_extract = self.get_variable(index.list);
             // Item of size 1
             if !to_extract.r#type().is_vector() {
@@ -169,13 +201,26 @@ impl<'a> Visitor<'a> {
             }
             let vector_extract =
                 llvm::extractelement(self.context, res, to_extract, index_value, self.location);
-            self.append_operation_with_result(vector_extract)
-        } else if out.ty.is_vectorized() {
+            return self.append_operation_with_result(vector_extract);
+        }
+
+        let CheckedIndex {
+            index: index_value,
+            in_bounds,
+        } = self.get_access_index(
+            index.list,
+            index.index,
+            out.ty,
+            index.unroll_factor,
+            MemoryAccess::Read,
+            checked,
+        );
+        let memref = self.get_memory(index.list);
+        let value = if out.ty.is_vectorized() {
             let vector_type = Type::vector(
                 &[out.vector_size() as u64],
                 index.list.storage_type().to_type(self.context),
             );
-            let memref = self.get_memory(index.list);
             self.append_operation_with_result(vector::load(
                 self.context,
                 vector_type,
@@ -184,54 +229,75 @@ impl<'a> Visitor<'a> {
                 self.location,
             ))
         } else {
-            let memref = self.get_memory(index.list);
             self.append_operation_with_result(memref::load(memref, &[index_value], self.location))
+        };
+
+        // Out-of-bounds reads return zero, like the other runtimes.
+        match in_bounds {
+            Some(in_bounds) => {
+                let zero = match out.ty.is_float() {
+                    true => self.create_float_constant_from_item(out.ty, 0.0),
+                    false => self.create_int_constant_from_item(out.ty, 0),
+                };
+                self.append_operation_with_result(arith::select(
+                    in_bounds,
+                    value,
+                    zero,
+                    self.location,
+                ))
+            }
+            None => value,
         }
     }
 
-    fn visit_index_assign(&mut self, index_assign: &IndexAssignOperator, out: Variable) {
+    fn visit_index_assign(
+        &mut self,
+        index_assign: &IndexAssignOperator,
+        out: Variable,
+        checked: bool,
+    ) {
         assert!(index_assign.vector_size == 0);
         let value = self.get_variable(index_assign.value);
-        let memref = self.get_memory(out);
-        if matches!(
+        let is_local = matches!(
             out.kind,
             VariableKind::LocalMut { .. } | VariableKind::LocalConst { .. }
-        ) {
-            let indices = self.get_index(
-                index_assign.index,
-                index_assign.value.ty,
-                out.ty.is_vectorized(),
-            );
-            let operation = if index_assign.value.ty.is_vectorized() {
-                vector::store(self.context, value, memref, &[indices], self.location).into()
-            } else {
-                memref::store(value, memref, &[indices], self.location)
-            };
-            self.block.append_operation(operation);
-            return;
-        }
+        );
+        // Scalars stored in a vectorized list are splatted to the whole vector.
+        let target_item = match is_local || index_assign.value.ty.is_vectorized() {
+            true => index_assign.value.ty,
+            false => out.ty,
+        };
+        let CheckedIndex {
+            index: indices,
+            in_bounds,
+        } = self.get_access_index(
+            out,
+            index_assign.index,
+            target_item,
+            index_assign.unroll_factor,
+            MemoryAccess::Write,
+            checked,
+        );
+        let memref = self.get_memory(out);
+
         let operation = if index_assign.value.ty.is_vectorized() {
-            let indices = self.get_index(
-                index_assign.index,
-                index_assign.value.ty,
-                out.ty.is_vectorized(),
-            );
-            vector::store(self.context, value, memref, &[indices], self.location)
+            vector::store(self.context, value, memref, &[indices], self.location).into()
+        } else if is_local {
+            memref::store(value, memref, &[indices], self.location)
         } else {
             let vector_type = Type::vector(
                 &[out.vector_size() as u64],
                 index_assign.value.storage_type().to_type(self.context),
             );
-            let indices = self.get_index(index_assign.index, out.ty, out.ty.is_vectorized());
             let splat = self.append_operation_with_result(vector::splat(
                 self.context,
                 vector_type,
                 value,
                 self.location,
             ));
-            vector::store(self.context, splat, memref, &[indices], self.location)
+            vector::store(self.context, splat, memref, &[indices], self.location).into()
         };
-        self.block.append_operation(operation);
+        self.append_guarded(in_bounds, operation);
     }
 
     pub(crate) fn visit_cast(&mut self, to_cast: Variable, out: Variable) {
@@ -307,7 +373,7 @@ impl<'a> Visitor<'a> {
         }
     }
 
-    fn get_cast_same_type_category(
+    pub(crate) fn get_cast_same_type_category(
         &self,
         to_cast: StorageType,
         out: StorageType,
@@ -0,0 +1,198 @@
+use cubecl_core::ir::{self, Builtin, ElemType, Plane};
+use tracel_llvm::mlir_rs::{
+    dialect::{
+        arith::{self, CmpiPredicate},
+        func, memref,
+        ods::{memref as memref_ods, vector},
+    },
+    ir::{
+        attribute::{FlatSymbolRefAttribute, IntegerAttribute},
+        r#type::{IntegerType, MemRefType},
+    },
+};
+
+use crate::{
+    compiler::visitor::prelude::*,
+    compute::plane::{PlaneElem, PlaneOp},
+};
+
+impl<'a> Visitor<'a> {
+    /// Plane operations exchange the values of the lanes through the runtime, see
+    /// [`plane_op`](crate::compute::plane::plane_op).
+    pub fn visit_plane(&mut self, plane: &Plane, out: Variable) {
+        match plane {
+            Plane::Elect => {
+                // Control flow is uniform in a plane, so the first lane is always active.
+                let lane = self.args_manager.get(Builtin::UnitPosPlane);
+                let zero = IntegerAttribute::new(IntegerType::new(self.context, 32).into(), 0);
+                let zero = self.append_operation_with_result(arith::constant(
+                    self.context,
+                    zero.into(),
+                    self.location,
+                ));
+                let elected = self.append_operation_with_result(arith::cmpi(
+                    self.context,
+                    CmpiPredicate::Eq,
+                    lane,
+                    zero,
+                    self.location,
+                ));
+                self.insert_variable(out, elected);
+            }
+            Plane::All(op) => self.visit_plane_op(PlaneOp::All, op.input, None, out),
+            Plane::Any(op) => self.visit_plane_op(PlaneOp::Any, op.input, None, out),
+            Plane::Ballot(op) => self.visit_plane_op(PlaneOp::Ballot, op.input, None, out),
+            Plane::Broadcast(op) => {
+                self.visit_plane_op(PlaneOp::Broadcast, op.lhs, Some(op.rhs), out)
+            }
+            Plane::Shuffle(op) => self.visit_plane_op(PlaneOp::Shuffle, op.lhs, Some(op.rhs), out),
+            Plane::ShuffleXor(op) => {
+                self.visit_plane_op(PlaneOp::ShuffleXor, op.lhs, Some(op.rhs), out)
+            }
+            Plane::ShuffleUp(op) => {
+                self.visit_plane_op(PlaneOp::ShuffleUp, op.lhs, Some(op.rhs), out)
+            }
+            Plane::ShuffleDown(op) => {
+                self.visit_plane_op(PlaneOp::ShuffleDown, op.lhs, Some(op.rhs), out)
+            }
+            Plane::Sum(op) => self.visit_plane_op(PlaneOp::Sum, op.input, None, out),
+            Plane::InclusiveSum(op) => {
+                self.visit_plane_op(PlaneOp::InclusiveSum, op.input, None, out)
+            }
+            Plane::ExclusiveSum(op) => {
+                self.visit_plane_op(PlaneOp::ExclusiveSum, op.input, None, out)
+            }
+            Plane::Prod(op) => self.visit_plane_op(PlaneOp::Prod, op.input, None, out),
+            Plane::InclusiveProd(op) => {
+                self.visit_plane_op(PlaneOp::InclusiveProd, op.input, None, out)
+            }
+            Plane::ExclusiveProd(op) => {
+                self.visit_plane_op(PlaneOp::ExclusiveProd, op.input, None, out)
+            }
+            Plane::Min(op) => self.visit_plane_op(PlaneOp::Min, op.input, None, out),
+            Plane::Max(op) => self.visit_plane_op(PlaneOp::Max, op.input, None, out),
+        }
+    }
+
+    /// Stores the input in local memory and calls the runtime with pointers to it and to the
+    /// output, which is loaded back once every lane has contributed.
+    fn visit_plane_op(
+        &mut self,
+        op: PlaneOp,
+        input: Variable,
+        arg: Option<Variable>,
+        out: Variable,
+    ) {
+        let integer_type: Type<'a> = IntegerType::new(self.context, 32).into();
+        let index_type = Type::index(self.context);
+        let zero = IntegerAttribute::new(index_type, 0).into();
+        let zero =
+            self.append_operation_with_result(arith::constant(self.context, zero, self.location));
+
+        let mut value = self.get_variable(input);
+        if input.elem_type() == ElemType::Bool {
+            value = self.cast_to_u8(value, input.ty);
+        }
+        let input_memref = self.alloca_plane_value(input.ty);
+        let store = if input.ty.is_vectorized() {
+            vector::store(self.context, value, input_memref, &[zero], self.location).into()
+        } else {
+            memref::store(value, input_memref, &[zero], self.location)
+        };
+        self.block.append_operation(store);
+        let output_memref = self.alloca_plane_value(out.ty);
+
+        let arg = match arg {
+            Some(arg) => self.plane_arg(arg),
+            None => {
+                let zero = IntegerAttribute::new(integer_type, 0);
+                self.append_operation_with_result(arith::constant(
+                    self.context,
+                    zero.into(),
+                    self.location,
+                ))
+            }
+        };
+        let constant = |value: u32| {
+            let value = IntegerAttribute::new(integer_type, value as i64);
+            self.append_operation_with_result(arith::constant(
+                self.context,
+                value.into(),
+                self.location,
+            ))
+        };
+        let op = constant(op as u32);
+        let elem = constant(PlaneElem::from_storage(input.storage_type()) as u32);
+        let vector_size = constant(input.vector_size() as u32);
+
+        let input_pointer =
+            self.append_operation_with_result(memref_ods::extract_aligned_pointer_as_index(
+                self.context,
+                index_type,
+                input_memref,
+                self.location,
+            ));
+        let output_pointer =
+            self.append_operation_with_result(memref_ods::extract_aligned_pointer_as_index(
+                self.context,
+                index_type,
+                output_memref,
+                self.location,
+            ));
+
+        self.block.append_operation(func::call(
+            self.context,
+            FlatSymbolRefAttribute::new(self.context, "plane_op"),
+            &[op, elem, vector_size, arg, input_pointer, output_pointer],
+            &[],
+            self.location,
+        ));
+
+        let mut result = if out.ty.is_vectorized() {
+            self.append_operation_with_result(vector::load(
+                self.context,
+                out.ty.to_type(self.context),
+                output_memref,
+                &[zero],
+                self.location,
+            ))
+        } else {
+            self.append_operation_with_result(memref::load(output_memref, &[zero], self.location))
+        };
+        if out.elem_type() == ElemType::Bool {
+            result = self.cast_to_bool(result, out.ty);
+        }
+        self.insert_variable(out, result);
+    }
+
+    fn alloca_plane_value(&mut self, ty: ir::Type) -> Value<'a, 'a> {
+        let r#type = ty.storage_type().to_type(self.context);
+        let memref_type = MemRefType::new(r#type, &[ty.vector_size() as i64], None, None);
+        self.first_block
+            .unwrap()
+            .append_op_result(memref::alloca(
+                self.context,
+                memref_type,
+                &[],
+                &[],
+                None,
+                self.location,
+            ))
+            .unwrap()
+    }
+
+    /// The source lane or the offset of shuffles is always passed as a `u32` to the runtime.
+    fn plane_arg(&mut self, arg: Variable) -> Value<'a, 'a> {
+        let value = self.get_variable(arg);
+        let integer_type = IntegerType::new(self.context, 32).into();
+        match arg.storage_type().size() {
+            4 => value,
+            size if size > 4 => {
+                self.append_operation_with_result(arith::trunci(value, integer_type, self.location))
+            }
+            _ => {
+                self.append_operation_with_result(arith::extui(value, integer_type, self.location))
+            }
+        }
+    }
+}
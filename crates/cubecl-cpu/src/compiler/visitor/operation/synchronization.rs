@@ -6,17 +6,8 @@ use crate::compiler::visitor::prelude::*;
 impl<'a> Visitor<'a> {
     pub fn visit_synchronization(&mut self, synchronization: &Synchronization) {
         match synchronization {
-            Synchronization::SyncCube => {
-                let func_name = FlatSymbolRefAttribute::new(self.context, "sync_cube");
-                self.block.append_operation(func::call(
-                    self.context,
-                    func_name,
-                    &[],
-                    &[],
-                    self.location,
-                ));
-            }
-            Synchronization::SyncPlane => {} // NOOP plane size is 1 on CPU
+            Synchronization::SyncCube => self.append_sync_call("sync_cube"),
+            Synchronization::SyncPlane => self.append_sync_call("sync_plane"),
             Synchronization::SyncStorage => {
                 panic!("SyncStorage is not supported")
             }
@@ -25,4 +16,10 @@ impl<'a> Visitor<'a> {
             }
         }
     }
+
+    fn append_sync_call(&mut self, name: &str) {
+        let func_name = FlatSymbolRefAttribute::new(self.context, name);
+        self.block
+            .append_operation(func::call(self.context, func_name, &[], &[], self.location));
+    }
 }
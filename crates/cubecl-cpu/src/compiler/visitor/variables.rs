@@ -18,6 +18,8 @@ use super::prelude::*;
 pub struct Variables<'a> {
     pub local: HashMap<VariableKind, Value<'a, 'a>>,
     pub global_constant: HashMap<u32, ir::Type>,
+    /// The memory and index of atomic elements, by the variable holding the reference.
+    pub atomic_pointers: HashMap<VariableKind, (Value<'a, 'a>, Value<'a, 'a>)>,
 }
 
 impl<'a> Variables<'a> {
@@ -41,8 +43,12 @@ impl<'a> Visitor<'a> {
             VariableKind::LocalMut { .. } => {
                 self.insert_mutable_memory(variable, value, 1);
             }
-            VariableKind::LocalArray { length, .. } => {
-                self.insert_mutable_memory(variable, value, length);
+            VariableKind::LocalArray {
+                length,
+                unroll_factor,
+                ..
+            } => {
+                self.insert_mutable_memory(variable, value, length * unroll_factor);
             }
             VariableKind::Shared { .. } => {
                 self.insert_shared_memory(variable, value);
@@ -171,7 +177,12 @@ impl<'a> Visitor<'a> {
             VariableKind::LocalMut { .. } | VariableKind::LocalConst { .. } => {
                 self.get_mutable_memory(variable, 1)
             }
-            VariableKind::LocalArray { length, .. } => self.get_mutable_memory(variable, length),
+            VariableKind::LocalArray {
+                length,
+                unroll_factor,
+                ..
+            } => self.get_mutable_memory(variable, length * unroll_factor),
+            VariableKind::Matrix { mat, .. } => self.get_mutable_memory(variable, mat.num_elems()),
             VariableKind::ConstantArray {
                 id,
                 length,
@@ -381,11 +392,21 @@ impl<'a> Visitor<'a> {
         list_is_vectorized: bool,
     ) -> Value<'a, 'a> {
         let index = self.get_variable(variable);
-        let mut index = self.append_operation_with_result(index::casts(
+        let index = self.append_operation_with_result(index::casts(
             index,
             Type::index(self.context),
             self.location,
         ));
+        self.scale_index(index, target_item, list_is_vectorized)
+    }
+
+    /// Converts an index expressed in vectors of `target_item` to an index in elements of the list.
+    pub fn scale_index(
+        &self,
+        mut index: Value<'a, 'a>,
+        target_item: ir::Type,
+        list_is_vectorized: bool,
+    ) -> Value<'a, 'a> {
         if target_item.is_vectorized() && list_is_vectorized {
             let vectorization = target_item.vector_size() as i64;
             let shift = vectorization.ilog2() as i64;
@@ -1,6 +1,9 @@
 use cubecl_core::server::ExecutionMode;
 
-use crate::compiler::{mlir_data::MlirData, mlir_engine::MlirEngine};
+use crate::{
+    compiler::{mlir_data::MlirData, mlir_engine::MlirEngine},
+    compute::{plane::PlaneUnit, validation::ErrorSink},
+};
 use std::sync::{
     atomic::{AtomicI32, Ordering},
     mpsc,
@@ -48,6 +51,8 @@ pub struct ComputeTask {
     pub mlir_engine: MlirEngine,
     pub mlir_data: MlirData,
     pub unit_pos: [u32; 3],
+    pub plane: PlaneUnit,
+    pub errors: ErrorSink,
     pub kind: ExecutionMode,
 }
 
@@ -55,9 +60,13 @@ impl ComputeTask {
     pub fn compute(mut self) {
         self.mlir_data.push_builtin();
         self.mlir_data.builtin.set_unit_pos(self.unit_pos);
+        self.plane.bind();
+        self.errors.bind();
         unsafe {
             self.mlir_engine.run_kernel(&mut self.mlir_data);
         }
+        PlaneUnit::unbind();
+        ErrorSink::unbind();
         CURRENT_CUBE_DIM.fetch_sub(1, Ordering::AcqRel);
     }
 }
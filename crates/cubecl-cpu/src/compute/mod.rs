@@ -4,6 +4,8 @@ pub mod server;
 pub mod worker;
 
 pub(crate) mod alloc_controller;
+pub(crate) mod plane;
 pub(crate) mod queue;
 pub(crate) mod schedule;
 pub(crate) mod stream;
+pub(crate) mod validation;
@@ -0,0 +1,473 @@
+//! Emulation of plane operations.
+//!
+//! The units of a cube are grouped in planes of a fixed width, following their linear position.
+//! Every unit runs on its own worker thread, so lanes exchange their values through a buffer owned
+//! by their plane, synchronizing with a barrier before and after reading it. Units that exit the
+//! kernel early leave their plane, so the remaining lanes stop waiting for them and ignore their
+//! values, like inactive lanes on a GPU. Otherwise all units of a plane must reach the same plane
+//! operations, non-uniform control flow isn't supported.
+
+use cubecl_core::ir::{ElemType, FloatKind, IntKind, StorageType, UIntKind};
+use half::{bf16, f16};
+use std::{
+    cell::RefCell,
+    sync::{Arc, Condvar, Mutex, RwLock},
+};
+
+/// The operations executed by [`plane_op`], the discriminant is emitted in the compiled kernel.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlaneOp {
+    All,
+    Any,
+    Ballot,
+    Broadcast,
+    Shuffle,
+    ShuffleXor,
+    ShuffleUp,
+    ShuffleDown,
+    Sum,
+    InclusiveSum,
+    ExclusiveSum,
+    Prod,
+    InclusiveProd,
+    ExclusiveProd,
+    Min,
+    Max,
+}
+
+impl PlaneOp {
+    const ALL: [PlaneOp; 16] = [
+        PlaneOp::All,
+        PlaneOp::Any,
+        PlaneOp::Ballot,
+        PlaneOp::Broadcast,
+        PlaneOp::Shuffle,
+        PlaneOp::ShuffleXor,
+        PlaneOp::ShuffleUp,
+        PlaneOp::ShuffleDown,
+        PlaneOp::Sum,
+        PlaneOp::InclusiveSum,
+        PlaneOp::ExclusiveSum,
+        PlaneOp::Prod,
+        PlaneOp::InclusiveProd,
+        PlaneOp::ExclusiveProd,
+        PlaneOp::Min,
+        PlaneOp::Max,
+    ];
+}
+
+/// The element types supported by [`plane_op`], the discriminant is emitted in the compiled
+/// kernel.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlaneElem {
+    F16,
+    BF16,
+    F32,
+    F64,
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl PlaneElem {
+    const ALL: [PlaneElem; 12] = [
+        PlaneElem::F16,
+        PlaneElem::BF16,
+        PlaneElem::F32,
+        PlaneElem::F64,
+        PlaneElem::I8,
+        PlaneElem::I16,
+        PlaneElem::I32,
+        PlaneElem::I64,
+        PlaneElem::U8,
+        PlaneElem::U16,
+        PlaneElem::U32,
+        PlaneElem::U64,
+    ];
+
+    /// The element used to exchange values of the given type. Booleans are stored as bytes.
+    pub fn from_storage(ty: StorageType) -> Self {
+        match ty.elem_type() {
+            ElemType::Float(FloatKind::F16) => PlaneElem::F16,
+            ElemType::Float(FloatKind::BF16) => PlaneElem::BF16,
+            ElemType::Float(FloatKind::F32 | FloatKind::Flex32 | FloatKind::TF32) => PlaneElem::F32,
+            ElemType::Float(FloatKind::F64) => PlaneElem::F64,
+            ElemType::Int(IntKind::I8) => PlaneElem::I8,
+            ElemType::Int(IntKind::I16) => PlaneElem::I16,
+            ElemType::Int(IntKind::I32) => PlaneElem::I32,
+            ElemType::Int(IntKind::I64) => PlaneElem::I64,
+            ElemType::UInt(UIntKind::U8) | ElemType::Bool => PlaneElem::U8,
+            ElemType::UInt(UIntKind::U16) => PlaneElem::U16,
+            ElemType::UInt(UIntKind::U32) => PlaneElem::U32,
+            ElemType::UInt(UIntKind::U64) => PlaneElem::U64,
+            elem => panic!("Plane operations aren't supported for {elem} on CPU"),
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            PlaneElem::I8 | PlaneElem::U8 => 1,
+            PlaneElem::F16 | PlaneElem::BF16 | PlaneElem::I16 | PlaneElem::U16 => 2,
+            PlaneElem::F32 | PlaneElem::I32 | PlaneElem::U32 => 4,
+            PlaneElem::F64 | PlaneElem::I64 | PlaneElem::U64 => 8,
+        }
+    }
+}
+
+/// The units of a cube sharing the same plane.
+pub struct PlaneGroup {
+    sync: Mutex<PlaneSync>,
+    released: Condvar,
+    /// The number of units in the plane. It's smaller than the plane size for the last plane of
+    /// the cube when the number of units isn't a multiple of the plane size.
+    num_lanes: usize,
+    values: RwLock<Vec<u8>>,
+}
+
+/// The state of the barrier of a plane, which only waits for the lanes that are still running.
+struct PlaneSync {
+    active: Vec<bool>,
+    num_active: usize,
+    arrived: usize,
+    generation: u64,
+}
+
+impl PlaneSync {
+    fn release(&mut self, released: &Condvar) {
+        self.arrived = 0;
+        self.generation += 1;
+        released.notify_all();
+    }
+}
+
+impl PlaneGroup {
+    fn new(num_lanes: usize) -> Self {
+        Self {
+            sync: Mutex::new(PlaneSync {
+                active: vec![true; num_lanes],
+                num_active: num_lanes,
+                arrived: 0,
+                generation: 0,
+            }),
+            released: Condvar::new(),
+            num_lanes,
+            values: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Waits until every active lane of the plane reached the barrier.
+    fn wait(&self) {
+        let mut sync = self.sync.lock().unwrap();
+        sync.arrived += 1;
+
+        if sync.arrived >= sync.num_active {
+            sync.release(&self.released);
+            return;
+        }
+
+        let generation = sync.generation;
+        let _sync = self
+            .released
+            .wait_while(sync, |sync| sync.generation == generation)
+            .unwrap();
+    }
+
+    /// Removes an exited lane from the plane, releasing the lanes waiting for it.
+    fn exit(&self, lane: usize) {
+        let mut sync = self.sync.lock().unwrap();
+        sync.active[lane] = false;
+        sync.num_active -= 1;
+
+        if sync.arrived > 0 && sync.arrived >= sync.num_active {
+            sync.release(&self.released);
+        }
+    }
+
+    /// The lanes that didn't exit the kernel.
+    fn active_lanes(&self) -> Vec<bool> {
+        self.sync.lock().unwrap().active.clone()
+    }
+}
+
+/// The plane of a unit, with its position in the plane.
+#[derive(Clone)]
+pub struct PlaneUnit {
+    group: Arc<PlaneGroup>,
+    lane: usize,
+}
+
+thread_local! {
+    static CURRENT_PLANE: RefCell<Option<PlaneUnit>> = const { RefCell::new(None) };
+}
+
+/// Groups the units of a cube in planes, returning the plane of every unit following their linear
+/// position.
+pub fn create_plane_units(num_units: u32, plane_size: u32) -> Vec<PlaneUnit> {
+    let num_units = num_units as usize;
+    let plane_size = plane_size as usize;
+    let mut units = Vec::with_capacity(num_units);
+
+    for start in (0..num_units).step_by(plane_size) {
+        let num_lanes = plane_size.min(num_units - start);
+        let group = Arc::new(PlaneGroup::new(num_lanes));
+        units.extend((0..num_lanes).map(|lane| PlaneUnit {
+            group: group.clone(),
+            lane,
+        }));
+    }
+
+    units
+}
+
+impl PlaneUnit {
+    /// Registers the plane used by the kernel executed on the current thread.
+    pub fn bind(self) {
+        CURRENT_PLANE.set(Some(self));
+    }
+
+    /// Unregisters the plane of the current thread once its unit exited the kernel, which removes
+    /// the unit from its plane.
+    pub fn unbind() {
+        if let Some(unit) = CURRENT_PLANE.take() {
+            unit.group.exit(unit.lane);
+        }
+    }
+}
+
+pub extern "C" fn sync_plane() {
+    CURRENT_PLANE.with_borrow(|unit| {
+        let unit = unit.as_ref().expect("Plane should be bound to the worker");
+        unit.group.wait();
+    });
+}
+
+/// Executes a plane operation for the current unit.
+///
+/// `input` points to `vector_size` elements of type `elem` and `output` to a buffer of the same
+/// size, except for ballots where it's four `u32`. `arg` is the source lane or the offset of
+/// shuffles.
+pub extern "C" fn plane_op(
+    op: u32,
+    elem: u32,
+    vector_size: u32,
+    arg: u32,
+    input: usize,
+    output: usize,
+) {
+    let op = PlaneOp::ALL[op as usize];
+    let elem = PlaneElem::ALL[elem as usize];
+    let vector_size = vector_size as usize;
+    let size = elem.size() * vector_size;
+    let output_size = match op {
+        PlaneOp::Ballot => 4 * size_of::<u32>(),
+        _ => size,
+    };
+
+    // SAFETY: The kernel passes pointers to local memory with the right size.
+    let input = unsafe { core::slice::from_raw_parts(input as *const u8, size) };
+    let output = unsafe { core::slice::from_raw_parts_mut(output as *mut u8, output_size) };
+
+    CURRENT_PLANE.with_borrow(|unit| {
+        let unit = unit.as_ref().expect("Plane should be bound to the worker");
+        let group = &unit.group;
+
+        {
+            let mut values = group.values.write().unwrap();
+            values.resize(group.num_lanes * size, 0);
+            values[unit.lane * size..][..size].copy_from_slice(input);
+        }
+        // Wait for every lane to write its value.
+        group.wait();
+
+        {
+            // Lanes only exit after their last plane operation, so every active lane wrote a
+            // value.
+            let active = group.active_lanes();
+            let values = group.values.read().unwrap();
+            let exchange = Exchange {
+                values: &values,
+                active: &active,
+                lane: unit.lane,
+                vector_size,
+            };
+            exchange.execute(op, elem, arg as usize, output);
+        }
+        // Wait for every lane to read the values before they are overwritten by the next operation.
+        group.wait();
+    });
+}
+
+/// The values of all lanes of a plane, seen from one of them.
+struct Exchange<'a> {
+    values: &'a [u8],
+    /// Whether each lane of the plane is still running, exited lanes are ignored.
+    active: &'a [bool],
+    lane: usize,
+    vector_size: usize,
+}
+
+impl Exchange<'_> {
+    fn execute(&self, op: PlaneOp, elem: PlaneElem, arg: usize, output: &mut [u8]) {
+        match op {
+            PlaneOp::Ballot => self.ballot(output),
+            PlaneOp::Broadcast | PlaneOp::Shuffle => self.shuffle(Some(arg), output),
+            PlaneOp::ShuffleXor => self.shuffle(Some(self.lane ^ arg), output),
+            PlaneOp::ShuffleUp => self.shuffle(self.lane.checked_sub(arg), output),
+            PlaneOp::ShuffleDown => self.shuffle(Some(self.lane + arg), output),
+            _ => match elem {
+                PlaneElem::F16 => self.reduce::<f16>(op, output),
+                PlaneElem::BF16 => self.reduce::<bf16>(op, output),
+                PlaneElem::F32 => self.reduce::<f32>(op, output),
+                PlaneElem::F64 => self.reduce::<f64>(op, output),
+                PlaneElem::I8 => self.reduce::<i8>(op, output),
+                PlaneElem::I16 => self.reduce::<i16>(op, output),
+                PlaneElem::I32 => self.reduce::<i32>(op, output),
+                PlaneElem::I64 => self.reduce::<i64>(op, output),
+                PlaneElem::U8 => self.reduce::<u8>(op, output),
+                PlaneElem::U16 => self.reduce::<u16>(op, output),
+                PlaneElem::U32 => self.reduce::<u32>(op, output),
+                PlaneElem::U64 => self.reduce::<u64>(op, output),
+            },
+        }
+    }
+
+    /// Copies the value of the source lane, or the value of the current lane when the source is
+    /// outside of the plane or exited.
+    fn shuffle(&self, source: Option<usize>, output: &mut [u8]) {
+        let source = source
+            .filter(|source| self.active.get(*source).copied().unwrap_or(false))
+            .unwrap_or(self.lane);
+        let size = output.len();
+        output.copy_from_slice(&self.values[source * size..][..size]);
+    }
+
+    fn ballot(&self, output: &mut [u8]) {
+        let mut mask = [0u32; 4];
+        for lane in 0..self.active.len().min(128) {
+            if self.active[lane] && self.values[lane] != 0 {
+                mask[lane / 32] |= 1 << (lane % 32);
+            }
+        }
+        for (i, word) in mask.into_iter().enumerate() {
+            output[i * 4..][..4].copy_from_slice(&word.to_ne_bytes());
+        }
+    }
+
+    fn reduce<E: PlaneElement>(&self, op: PlaneOp, output: &mut [u8]) {
+        let lanes = match op {
+            PlaneOp::InclusiveSum | PlaneOp::InclusiveProd => 0..self.lane + 1,
+            PlaneOp::ExclusiveSum | PlaneOp::ExclusiveProd => 0..self.lane,
+            _ => 0..self.active.len(),
+        };
+
+        for i in 0..self.vector_size {
+            let values = lanes
+                .clone()
+                .filter(|lane| self.active[*lane])
+                .map(|lane| E::read(&self.values[(lane * self.vector_size + i) * E::SIZE..]));
+            let result = match op {
+                PlaneOp::Sum | PlaneOp::InclusiveSum | PlaneOp::ExclusiveSum => {
+                    values.fold(E::ZERO, E::add)
+                }
+                PlaneOp::Prod | PlaneOp::InclusiveProd | PlaneOp::ExclusiveProd => {
+                    values.fold(E::ONE, E::mul)
+                }
+                // Booleans are exchanged as bytes, so `all` and `any` are the minimum and maximum.
+                PlaneOp::Min | PlaneOp::All => values.reduce(E::min).unwrap(),
+                PlaneOp::Max | PlaneOp::Any => values.reduce(E::max).unwrap(),
+                _ => unreachable!("{op:?} isn't a reduction"),
+            };
+            result.write(&mut output[i * E::SIZE..]);
+        }
+    }
+}
+
+trait PlaneElement: Copy {
+    const SIZE: usize;
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn read(bytes: &[u8]) -> Self;
+    fn write(self, bytes: &mut [u8]);
+    fn add(self, other: Self) -> Self;
+    fn mul(self, other: Self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+}
+
+macro_rules! plane_element {
+    ($ty:ty, $zero:expr, $one:expr, $add:expr, $mul:expr, $min:expr, $max:expr) => {
+        impl PlaneElement for $ty {
+            const SIZE: usize = size_of::<$ty>();
+            const ZERO: Self = $zero;
+            const ONE: Self = $one;
+
+            fn read(bytes: &[u8]) -> Self {
+                <$ty>::from_ne_bytes(bytes[..Self::SIZE].try_into().unwrap())
+            }
+
+            fn write(self, bytes: &mut [u8]) {
+                bytes[..Self::SIZE].copy_from_slice(&self.to_ne_bytes());
+            }
+
+            fn add(self, other: Self) -> Self {
+                $add(self, other)
+            }
+
+            fn mul(self, other: Self) -> Self {
+                $mul(self, other)
+            }
+
+            fn min(self, other: Self) -> Self {
+                $min(self, other)
+            }
+
+            fn max(self, other: Self) -> Self {
+                $max(self, other)
+            }
+        }
+    };
+}
+
+macro_rules! plane_float {
+    ($($ty:ty: $zero:expr, $one:expr);*) => {
+        $(plane_element!(
+            $ty,
+            $zero,
+            $one,
+            |a, b| a + b,
+            |a, b| a * b,
+            <$ty>::min,
+            <$ty>::max
+        );)*
+    };
+}
+
+macro_rules! plane_int {
+    ($($ty:ty),*) => {
+        $(plane_element!(
+            $ty,
+            0,
+            1,
+            <$ty>::wrapping_add,
+            <$ty>::wrapping_mul,
+            Ord::min,
+            Ord::max
+        );)*
+    };
+}
+
+plane_float!(
+    f16: f16::ZERO, f16::ONE;
+    bf16: bf16::ZERO, bf16::ONE;
+    f32: 0.0, 1.0;
+    f64: 0.0, 1.0
+);
+plane_int!(i8, i16, i32, i64, u8, u16, u32, u64);
@@ -3,10 +3,14 @@ use crate::{
     compute::{
         runner::KernelRunner,
         schedule::{BindingsResource, ScheduleTask},
+        validation::ErrorSink,
     },
 };
 use cubecl_common::bytes::Bytes;
-use cubecl_core::{CubeDim, server::ExecutionMode};
+use cubecl_core::{
+    CubeDim,
+    server::{CopyLayout, ExecutionMode},
+};
 use cubecl_runtime::{logging::ServerLogger, storage::BytesResource};
 use std::sync::{Arc, OnceLock, mpsc::SyncSender};
 
@@ -21,14 +25,14 @@ pub struct CpuExecutionQueue {
 }
 
 enum QueueItem {
-    Task(ScheduleTask),
+    Task(ScheduleTask, ErrorSink),
     Flush(std::sync::mpsc::SyncSender<()>),
 }
 
 impl CpuExecutionQueue {
-    /// Adds a new task to the queue.
-    pub fn add(&self, task: ScheduleTask) {
-        self.sender.send(QueueItem::Task(task)).unwrap();
+    /// Adds a new task to the queue, kernels report their validation errors to `errors`.
+    pub fn add(&self, task: ScheduleTask, errors: ErrorSink) {
+        self.sender.send(QueueItem::Task(task, errors)).unwrap();
     }
 
     /// Flushes the queue, making sure all enqueued tasks before this point are executed.
@@ -54,7 +58,7 @@ impl CpuExecutionQueue {
             loop {
                 match receiver.recv() {
                     Ok(item) => match item {
-                        QueueItem::Task(task) => server.execute_task(task),
+                        QueueItem::Task(task, errors) => server.execute_task(task, errors),
                         QueueItem::Flush(sender) => sender.send(()).unwrap(),
                     },
                     Err(err) => panic!("{err:?}"),
@@ -71,21 +75,28 @@ struct CpuExecutionQueueServer {
 }
 
 impl CpuExecutionQueueServer {
-    fn execute_task(&mut self, task: ScheduleTask) {
+    fn execute_task(&mut self, task: ScheduleTask, errors: ErrorSink) {
         match task {
-            ScheduleTask::Write { data, buffer } => self.write(data, buffer),
+            ScheduleTask::Write {
+                data,
+                buffer,
+                layout,
+            } => self.write(data, buffer, layout),
             ScheduleTask::Execute {
                 mlir_engine,
                 bindings,
                 kind,
                 cube_dim,
                 cube_count,
-            } => self.kernel(mlir_engine, bindings, kind, cube_dim, cube_count),
+            } => self.kernel(mlir_engine, bindings, kind, cube_dim, cube_count, errors),
         }
     }
 
-    fn write(&mut self, data: Bytes, mut buffer: BytesResource) {
-        buffer.write().copy_from_slice(&data);
+    fn write(&mut self, data: Bytes, mut buffer: BytesResource, layout: Option<CopyLayout>) {
+        match layout {
+            Some(layout) => layout.scatter(&data, buffer.write()),
+            None => buffer.write().copy_from_slice(&data),
+        }
     }
 
     fn kernel(
@@ -95,8 +106,9 @@ impl CpuExecutionQueueServer {
         kind: ExecutionMode,
         cube_dim: CubeDim,
         cube_count: [u32; 3],
+        errors: ErrorSink,
     ) {
         self.runner
-            .execute_data(mlir_engine, bindings, kind, cube_dim, cube_count)
+            .execute_data(mlir_engine, bindings, kind, cube_dim, cube_count, errors)
     }
 }
@@ -2,7 +2,9 @@ use super::{
     compute_task::{
         BARRIER_COUNTER, BARRIER_TARGET, CURRENT_CUBE_DIM, ComputeTask, STOPPED_COUNTER,
     },
+    plane::create_plane_units,
     schedule::BindingsResource,
+    validation::ErrorSink,
     worker::Worker,
 };
 use crate::{
@@ -149,6 +151,7 @@ impl KernelRunner {
         kind: ExecutionMode,
         cube_dim: CubeDim,
         cube_count: [u32; 3],
+        errors: ErrorSink,
     ) {
         let (send, receive) = mpsc::channel();
         let mut msg_count = 0;
@@ -172,6 +175,8 @@ impl KernelRunner {
         mlir_data.builtin.set_cube_dim(cube_dim);
         mlir_data.builtin.set_cube_count(cube_count);
 
+        let planes = create_plane_units(cube_dim_size, mlir_engine.0.plane_size);
+
         let mut workers = self.workers.iter_mut();
         for unit_pos_x in 0..cube_dim.x {
             for unit_pos_y in 0..cube_dim.y {
@@ -180,11 +185,16 @@ impl KernelRunner {
                     let worker = workers.next().expect("The CubeDim are too large");
                     let mlir_engine = mlir_engine.clone();
                     let mlir_data = mlir_data.clone();
+                    let linear_pos =
+                        unit_pos_x + unit_pos_y * cube_dim.x + unit_pos_z * cube_dim.x * cube_dim.y;
+                    let plane = planes[linear_pos as usize].clone();
 
                     let compute_task = ComputeTask {
                         mlir_engine,
                         mlir_data,
                         unit_pos,
+                        plane,
+                        errors: errors.clone(),
                         kind,
                     };
                     msg_count += 1;
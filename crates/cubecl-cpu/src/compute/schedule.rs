@@ -1,8 +1,9 @@
 use crate::{compiler::mlir_engine::MlirEngine, compute::stream::CpuStream};
 use cubecl_common::bytes::Bytes;
 use cubecl_core::{
-    CubeDim, ExecutionMode, MemoryConfiguration, ir::MemoryDeviceProperties,
-    server::MetadataBindingInfo,
+    CubeDim, ExecutionMode, MemoryConfiguration,
+    ir::MemoryDeviceProperties,
+    server::{CopyLayout, MetadataBindingInfo},
 };
 use cubecl_runtime::{
     logging::ServerLogger,
@@ -14,7 +15,14 @@ use std::sync::Arc;
 /// Defines tasks that can be scheduled on a cpu stream.
 pub enum ScheduleTask {
     /// Represents a task to write data to a buffer.
-    Write { data: Bytes, buffer: BytesResource },
+    ///
+    /// The data is scattered to the elements of the layout when one is provided, otherwise it is
+    /// copied as is.
+    Write {
+        data: Bytes,
+        buffer: BytesResource,
+        layout: Option<CopyLayout>,
+    },
     /// Represents a task to execute a kernel.
     Execute {
         mlir_engine: MlirEngine,
@@ -28,10 +36,15 @@ pub enum ScheduleTask {
 impl core::fmt::Debug for ScheduleTask {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Write { data, buffer } => f
+            Self::Write {
+                data,
+                buffer,
+                layout,
+            } => f
                 .debug_struct("Write")
                 .field("data", data)
                 .field("buffer", buffer)
+                .field("layout", layout)
                 .finish(),
             Self::Execute {
                 mlir_engine: _,
@@ -1,6 +1,9 @@
 use crate::{
-    CpuCompiler,
-    compiler::MlirCompilerOptions,
+    CpuCompiler, CpuServerInfo,
+    compiler::{
+        MlirCompiler, MlirCompilerOptions,
+        mlir_engine::{MlirCacheEntry, MlirEngine},
+    },
     compute::{
         runner::CpuKernel,
         schedule::{BindingsResource, ScheduleTask, ScheduledCpuBackend},
@@ -10,20 +13,24 @@ use cubecl_common::{
     backtrace::BackTrace, bytes::Bytes, profile::ProfileDuration, stream_id::StreamId,
 };
 use cubecl_core::{
-    CompilationError, CubeCount, ExecutionMode, MemoryConfiguration, MemoryUsage,
+    CompilationError, CubeCount, ExecutionMode, MemoryConfiguration, MemoryTrace, MemoryUsage,
+    device::DeviceId,
     future::DynFut,
-    ir::MemoryDeviceProperties,
+    hash::StableHash,
+    ir::{ElemType, MemoryDeviceProperties},
+    prelude::CompiledKernel,
     server::{
-        Binding, ComputeServer, CopyDescriptor, IoError, KernelArguments, ProfileError,
-        ProfilingToken, ServerCommunication, ServerError, ServerUtilities,
+        Binding, ComputeServer, CopyDescriptor, CopyLayout, HostCommunication, IoError,
+        KernelArguments, ProfileError, ProfilingToken, ReduceOperation, ServerCommunication,
+        ServerError, ServerUtilities,
     },
-    zspace::{Shape, Strides, strides},
 };
 use cubecl_runtime::{
     allocator::ContiguousMemoryLayoutPolicy,
     compiler::CubeTask,
     config::GlobalConfig,
     id::KernelId,
+    kernel_cache::KernelCache,
     logging::ServerLogger,
     memory_management::{ManagedMemoryHandle, MemoryAllocationMode},
     storage::{BytesStorage, ComputeStorage, ManagedResource},
@@ -36,14 +43,20 @@ pub struct CpuServer {
     scheduler: SchedulerMultiStream<ScheduledCpuBackend>,
     utilities: Arc<ServerUtilities<CpuServer>>,
     compilation_cache: HashMap<KernelId, CpuKernel>,
+    /// The persistent cache of the compiled kernels.
+    kernel_cache: Option<KernelCache<MlirCacheEntry>>,
     // A buffer that can be used to store stream id without extra allocations.
     streams_pool: Vec<StreamId>,
+    plane_size: u32,
+    communication: HostCommunication,
 }
 
 impl CpuServer {
     pub fn new(
+        device_id: DeviceId,
         memory_properties: MemoryDeviceProperties,
         memory_config: MemoryConfiguration,
+        plane_size: u32,
         utilities: Arc<ServerUtilities<CpuServer>>,
     ) -> Self {
         let backend =
@@ -61,11 +74,21 @@ impl CpuServer {
             },
         );
 
+        let kernel_cache = KernelCache::new(
+            "cpu",
+            "mlir",
+            &(plane_size, config.compilation.unroll_loops),
+            utilities.properties_hash,
+        );
+
         Self {
             scheduler,
             utilities,
             compilation_cache: HashMap::new(),
+            kernel_cache,
             streams_pool: Vec::new(),
+            plane_size,
+            communication: HostCommunication::new(device_id),
         }
     }
 
@@ -131,16 +154,35 @@ impl CpuServer {
         bindings: BindingsResource,
         kind: ExecutionMode,
     ) -> Result<ScheduleTask, CompilationError> {
-        let kernel_id = kernel.id();
+        let mut kernel_id = kernel.id();
+        kernel_id.mode(kind);
         let kernel = if let Some(kernel) = self.compilation_cache.get(&kernel_id) {
             kernel
         } else {
-            let kernel = kernel.compile(
-                &mut Default::default(),
-                &MlirCompilerOptions::default(),
-                kind,
-                kernel.address_type(),
-            )?;
+            let kernel = match self.load_cached_kernel(&kernel_id) {
+                Ok(kernel) => kernel,
+                Err(key) => {
+                    let compiled = kernel.compile(
+                        &mut Default::default(),
+                        &MlirCompilerOptions {
+                            plane_size: self.plane_size,
+                            unroll_loops: GlobalConfig::get().compilation.unroll_loops,
+                        },
+                        kind,
+                        kernel.address_type(),
+                    )?;
+                    if let Some(key) = key
+                        && let Some(cache) = &mut self.kernel_cache
+                        && let Some(engine) = &compiled.repr
+                    {
+                        cache.insert(
+                            key,
+                            engine.cache_entry(&compiled.entrypoint_name, compiled.cube_dim),
+                        );
+                    }
+                    compiled
+                }
+            };
             self.compilation_cache
                 .insert(kernel_id.clone(), CpuKernel::new(kernel));
             self.compilation_cache
@@ -163,6 +205,30 @@ impl CpuServer {
         Ok(task)
     }
 
+    /// Loads a kernel from the kernel cache, returns the key to insert it with if the cache is
+    /// enabled but doesn't contain the kernel.
+    fn load_cached_kernel(
+        &self,
+        kernel_id: &KernelId,
+    ) -> Result<CompiledKernel<MlirCompiler>, Option<StableHash>> {
+        let Some(cache) = &self.kernel_cache else {
+            return Err(None);
+        };
+        let key = cache.key(kernel_id);
+        let entry = cache.get(&key).ok_or(Some(key))?;
+        let engine = MlirEngine::from_cache_entry(entry).ok_or(Some(key))?;
+        log::trace!("Using MLIR cache");
+
+        Ok(CompiledKernel {
+            entrypoint_name: entry.entrypoint_name.clone(),
+            debug_name: None,
+            source: entry.lowered_module.clone(),
+            repr: Some(engine),
+            cube_dim: entry.cube_dim,
+            debug_info: None,
+        })
+    }
+
     pub(crate) fn utilities(&self) -> Arc<ServerUtilities<Self>> {
         self.utilities.clone()
     }
@@ -172,7 +238,7 @@ impl ComputeServer for CpuServer {
     type Kernel = Box<dyn CubeTask<CpuCompiler>>;
     type Storage = BytesStorage;
     type MemoryLayoutPolicy = ContiguousMemoryLayoutPolicy;
-    type Info = ();
+    type Info = CpuServerInfo;
 
     fn logger(&self) -> Arc<ServerLogger> {
         self.scheduler.logger.clone()
@@ -213,18 +279,31 @@ impl ComputeServer for CpuServer {
             if !streams.contains(&desc.handle.stream) {
                 streams.push(desc.handle.stream);
             }
+            let layout = CopyLayout::new(desc.shape.clone(), desc.strides.clone(), desc.elem_size);
             let stream = self.scheduler.stream(&stream_id);
             let result = stream.read_async(desc);
-            results.push(result);
+            results.push((result, layout));
         }
 
         self.scheduler.execute_streams(streams);
 
         Box::pin(async move {
-            for result in results {
-                match result.await {
-                    Ok(val) => resources.push(val),
+            for (result, layout) in results {
+                let bytes = match result.await {
+                    Ok(val) => val,
                     Err(err) => return Err(err.into()),
+                };
+
+                if layout.is_contiguous() {
+                    resources.push(bytes);
+                } else if layout.span() <= bytes.len() {
+                    // Non-contiguous tensors are gathered after the zero-copy read.
+                    resources.push(Bytes::from_bytes_vec(layout.gather(&bytes)));
+                } else {
+                    return Err(IoError::UnsupportedStrides {
+                        backtrace: BackTrace::capture(),
+                    }
+                    .into());
                 }
             }
 
@@ -236,14 +315,17 @@ impl ComputeServer for CpuServer {
         for (desc, data) in descriptors {
             let stream = self.scheduler.stream(&desc.handle.stream);
 
-            if contiguous_strides(&desc.shape) != desc.strides {
-                stream.error(ServerError::Io(IoError::UnsupportedStrides {
-                    backtrace: BackTrace::capture(),
-                }));
+            if !stream.is_healthy() {
                 return;
             }
 
-            if !stream.is_healthy() {
+            let layout = CopyLayout::new(desc.shape, desc.strides, desc.elem_size);
+            if data.len() != layout.size() {
+                stream.error(ServerError::Io(IoError::InvalidSize {
+                    expected: layout.size(),
+                    actual: data.len(),
+                    backtrace: BackTrace::capture(),
+                }));
                 return;
             }
 
@@ -254,9 +336,24 @@ impl ComputeServer for CpuServer {
                     return;
                 }
             };
+
+            // Non-contiguous tensors are scattered when the task is executed, so the bytes
+            // between elements are left unchanged.
+            let (_, len) = resource.get_write_ptr_and_length();
+            let layout = match layout.is_contiguous() {
+                true => None,
+                false if layout.span() <= len => Some(layout),
+                false => {
+                    stream.error(ServerError::Io(IoError::UnsupportedStrides {
+                        backtrace: BackTrace::capture(),
+                    }));
+                    return;
+                }
+            };
             let task = ScheduleTask::Write {
                 data,
                 buffer: resource,
+                layout,
             };
 
             self.scheduler.register(stream_id, task, &[]);
@@ -273,6 +370,11 @@ impl ComputeServer for CpuServer {
         stream.memory_management.cleanup(true)
     }
 
+    fn memory_trace(&mut self, stream_id: StreamId) -> Result<MemoryTrace, ServerError> {
+        let stream = self.scheduler.stream(&stream_id);
+        Ok(stream.memory_management.take_trace())
+    }
+
     unsafe fn launch(
         &mut self,
         kernel: Self::Kernel,
@@ -287,8 +389,11 @@ impl ComputeServer for CpuServer {
             .iter()
             .for_each(|b| self.streams_pool.push(b.stream));
         let bindings = self.prepare_bindings(bindings);
+        let kernel_name = kernel.name();
         let task = self.prepare_task(kernel, count, bindings, kind).unwrap();
 
+        let stream = self.scheduler.stream(&stream_id);
+        stream.memory_management.trace_launch(kernel_name);
         self.scheduler.register(stream_id, task, &self.streams_pool);
     }
 
@@ -349,15 +454,101 @@ impl ComputeServer for CpuServer {
     }
 }
 
+/// Logical CPU devices share the host memory, so collective operations are staged on the host.
 impl ServerCommunication for CpuServer {
     const SERVER_COMM_ENABLED: bool = false;
-}
 
-pub(crate) fn contiguous_strides(shape: &Shape) -> Strides {
-    let rank = shape.len();
-    let mut strides = strides![1; rank];
-    for i in (0..rank - 1).rev() {
-        strides[i] = strides[i + 1] * shape[i + 1];
+    fn sync_collective(&mut self, _stream_id: StreamId) -> Result<(), ServerError> {
+        // Host collectives are done by the time they return.
+        Ok(())
+    }
+
+    fn all_reduce(
+        &mut self,
+        src: Binding,
+        dst: Binding,
+        dtype: ElemType,
+        stream_id: StreamId,
+        op: ReduceOperation,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        let communication = self.communication;
+        communication.all_reduce(self, src, dst, dtype, stream_id, op, device_ids)
+    }
+
+    fn broadcast(
+        &mut self,
+        src: Binding,
+        dst: Binding,
+        _dtype: ElemType,
+        stream_id: StreamId,
+        root: DeviceId,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        let communication = self.communication;
+        communication.broadcast(self, src, dst, stream_id, root, device_ids)
+    }
+
+    fn reduce(
+        &mut self,
+        src: Binding,
+        dst: Binding,
+        dtype: ElemType,
+        stream_id: StreamId,
+        op: ReduceOperation,
+        root: DeviceId,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        let communication = self.communication;
+        communication.reduce(self, src, dst, dtype, stream_id, op, root, device_ids)
+    }
+
+    fn all_gather(
+        &mut self,
+        src: Binding,
+        dst: Binding,
+        _dtype: ElemType,
+        stream_id: StreamId,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        let communication = self.communication;
+        communication.all_gather(self, src, dst, stream_id, device_ids)
+    }
+
+    fn reduce_scatter(
+        &mut self,
+        src: Binding,
+        dst: Binding,
+        dtype: ElemType,
+        stream_id: StreamId,
+        op: ReduceOperation,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        let communication = self.communication;
+        communication.reduce_scatter(self, src, dst, dtype, stream_id, op, device_ids)
+    }
+
+    fn send(
+        &mut self,
+        src: Binding,
+        _dtype: ElemType,
+        stream_id: StreamId,
+        peer: DeviceId,
+        _device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        let communication = self.communication;
+        communication.send(self, src, stream_id, peer)
+    }
+
+    fn recv(
+        &mut self,
+        dst: Binding,
+        _dtype: ElemType,
+        stream_id: StreamId,
+        peer: DeviceId,
+        _device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        let communication = self.communication;
+        communication.recv(self, dst, stream_id, peer)
     }
-    strides
 }
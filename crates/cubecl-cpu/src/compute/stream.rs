@@ -1,5 +1,6 @@
 use crate::compute::{
     alloc_controller::CpuAllocController, queue::CpuExecutionQueue, schedule::ScheduleTask,
+    validation::ErrorSink,
 };
 use cubecl_common::{bytes::Bytes, profile::ProfileDuration};
 use cubecl_core::{
@@ -26,6 +27,7 @@ pub struct CpuStream {
     pub(crate) memory_management: MemoryManagement<BytesStorage>,
     pub(crate) timestamps: TimestampProfiler,
     errors: Vec<ServerError>,
+    validation: ErrorSink,
 }
 
 impl core::fmt::Debug for CpuStream {
@@ -53,15 +55,17 @@ impl CpuStream {
             timestamps: TimestampProfiler::default(),
             queue: CpuExecutionQueue::get(logger),
             errors: Vec::new(),
+            validation: ErrorSink::default(),
         }
     }
 
     pub fn enqueue_task(&mut self, task: ScheduleTask) {
-        self.queue.add(task);
+        self.queue.add(task, self.validation.clone());
     }
 
     pub fn flush(&mut self, mode: StreamErrorMode) -> Result<(), ServerError> {
         self.queue.flush();
+        self.errors.extend(self.validation.drain());
 
         self.flush_errors(mode)
     }
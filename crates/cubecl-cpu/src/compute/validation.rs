@@ -0,0 +1,83 @@
+//! Reporting of the out-of-bounds accesses detected by kernels compiled in
+//! [validate](cubecl_core::ExecutionMode::Validate) mode.
+//!
+//! Every stream owns an [`ErrorSink`] that is bound to the worker threads while they execute the
+//! kernels of that stream. Invalid accesses are skipped by the compiled kernel, so the errors are
+//! only collected here and returned to the user when the stream is flushed.
+
+use cubecl_common::backtrace::BackTrace;
+use cubecl_core::server::{MemoryAccess, ServerError};
+use std::{
+    cell::RefCell,
+    ffi::{CStr, c_char},
+    sync::{Arc, Mutex},
+};
+
+/// A single kernel can do millions of invalid accesses, only the first ones are kept.
+const MAX_REPORTED_ERRORS: usize = 16;
+
+thread_local! {
+    static CURRENT_SINK: RefCell<Option<ErrorSink>> = const { RefCell::new(None) };
+}
+
+/// Collects the errors reported by kernels while they execute.
+#[derive(Clone, Debug, Default)]
+pub struct ErrorSink {
+    errors: Arc<Mutex<Vec<ServerError>>>,
+}
+
+impl ErrorSink {
+    /// Registers the sink receiving the errors of the kernel executed on the current thread.
+    pub fn bind(self) {
+        CURRENT_SINK.set(Some(self));
+    }
+
+    /// Unregisters the sink of the current thread.
+    pub fn unbind() {
+        CURRENT_SINK.set(None);
+    }
+
+    /// Takes all the errors reported since the last call.
+    pub fn drain(&self) -> Vec<ServerError> {
+        core::mem::take(&mut *self.errors.lock().unwrap())
+    }
+
+    fn push(&self, error: ServerError) {
+        let mut errors = self.errors.lock().unwrap();
+        if errors.len() < MAX_REPORTED_ERRORS {
+            errors.push(error);
+        }
+    }
+}
+
+/// Called by kernels compiled in validate mode when an access is out of bounds.
+///
+/// `kernel` and `buffer` point to null-terminated strings stored in the compiled module, `index`
+/// and `length` are expressed in elements of the buffer.
+pub extern "C" fn report_out_of_bounds(
+    kernel: *const c_char,
+    buffer: *const c_char,
+    write: u32,
+    index: u64,
+    length: u64,
+) {
+    // SAFETY: The strings are global constants of the kernel module, which outlives its execution.
+    let (kernel, buffer) = unsafe { (CStr::from_ptr(kernel), CStr::from_ptr(buffer)) };
+    let access = match write {
+        0 => MemoryAccess::Read,
+        _ => MemoryAccess::Write,
+    };
+    let error = ServerError::OutOfBounds {
+        kernel: kernel.to_string_lossy().into_owned(),
+        buffer: buffer.to_string_lossy().into_owned(),
+        access,
+        index,
+        length,
+        backtrace: BackTrace::capture(),
+    };
+
+    CURRENT_SINK.with_borrow(|sink| match sink {
+        Some(sink) => sink.push(error),
+        None => log::error!("{error}"),
+    });
+}
@@ -1,17 +1,23 @@
 use cubecl_common::device::{Device, DeviceId};
 
+/// A logical CPU device. Every device runs on the same host, see
+/// [`RuntimeOptions::device_count`](crate::RuntimeOptions::device_count).
 #[derive(new, Clone, PartialEq, Eq, Default, Hash, Debug)]
-pub struct CpuDevice;
+pub struct CpuDevice {
+    pub index: usize,
+}
 
 impl Device for CpuDevice {
-    fn from_id(_device_id: DeviceId) -> Self {
-        Self
+    fn from_id(device_id: DeviceId) -> Self {
+        Self {
+            index: device_id.index_id as usize,
+        }
     }
 
     fn to_id(&self) -> DeviceId {
         DeviceId {
             type_id: 0,
-            index_id: 0,
+            index_id: self.index as u32,
         }
     }
 }
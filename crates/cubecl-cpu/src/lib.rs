@@ -70,6 +70,81 @@ mod tests {
         out[idx] = sum;
     }
 
+    #[cube(launch)]
+    fn plane_sum_partial(out: &mut Array<u32>) {
+        out[UNIT_POS as usize] = plane_sum(1u32);
+    }
+
+    #[cube(launch)]
+    fn plane_sum_early_return(out: &mut Array<u32>, len: u32) {
+        if UNIT_POS >= len {
+            terminate!();
+        }
+        out[UNIT_POS as usize] = plane_sum(1u32);
+    }
+
+    /// Every plane multiplies its own tile of `lhs` by `rhs`.
+    #[cube(launch)]
+    fn cmma_tile_per_plane(lhs: &Array<f16>, rhs: &Array<f16>, out: &mut Array<f32>) {
+        let offset = PLANE_POS as usize * 256;
+        let a = cmma::Matrix::<f16>::from_slice(
+            cmma::MatrixIdent::A,
+            16usize,
+            16usize,
+            16usize,
+            cmma::MatrixLayout::RowMajor,
+            &lhs.slice(offset, offset + 256),
+            16,
+        );
+        let b = cmma::Matrix::<f16>::from_slice(
+            cmma::MatrixIdent::B,
+            16usize,
+            16usize,
+            16usize,
+            cmma::MatrixLayout::ColMajor,
+            &rhs.to_slice(),
+            16,
+        );
+        let c = cmma::Matrix::<f32>::from_value(
+            cmma::MatrixIdent::Accumulator,
+            16usize,
+            16usize,
+            16usize,
+            cmma::MatrixLayout::Undefined,
+            0.0,
+        );
+
+        cmma::execute::<f16, f16, f32, f32>(&a, &b, &c, &c);
+
+        cmma::store(
+            &mut out.slice_mut(offset, offset + 256),
+            &c,
+            16,
+            cmma::MatrixLayout::RowMajor,
+        );
+    }
+
+    #[cube(launch)]
+    fn atomic_flag_spin(flag: &mut Array<Atomic<u32>>, out: &mut Array<u32>) {
+        if UNIT_POS == 0 {
+            // Only terminates if the flag is reloaded from memory on every iteration.
+            while flag[0].load() == 0u32 {}
+            out[0] = 1u32;
+        } else {
+            flag[0].store(1u32);
+        }
+    }
+
+    #[cube(launch)]
+    fn out_of_bounds_checked(out: &mut Array<u32>, index: u32) {
+        let mut mem = SharedMemory::<u32>::new(4usize);
+        mem[0] = 1u32;
+        mem[index as usize] = 2u32;
+        out[index as usize] = 3u32;
+        out[0] = mem[0];
+        out[1] = mem[index as usize];
+    }
+
     #[test]
     fn test_barrier_smoke_cpu() {
         let client = TestRuntime::client(&Default::default());
@@ -145,6 +220,199 @@ mod tests {
         let actual = u32::from_bytes(&bytes);
         assert_eq!(actual, &[28u32; 8]);
     }
+
+    #[test]
+    fn test_plane_sum_partial_plane_cpu() {
+        let client = TestRuntime::client(&Default::default());
+        let plane_size = client.properties().hardware.plane_size_max;
+        // The last plane of the cube only has a quarter of the lanes.
+        let num_units = plane_size + plane_size.div_ceil(4);
+        let out = client.empty(num_units as usize * core::mem::size_of::<u32>());
+
+        unsafe {
+            plane_sum_partial::launch::<TestRuntime>(
+                &client,
+                CubeCount::new_single(),
+                CubeDim::new_1d(num_units),
+                ArrayArg::from_raw_parts(out.clone(), num_units as usize),
+            )
+        }
+
+        let bytes = client.read_one_unchecked(out);
+        let actual = u32::from_bytes(&bytes);
+        let expected: Vec<u32> = (0..num_units)
+            .map(|unit| plane_size.min(num_units - unit / plane_size * plane_size))
+            .collect();
+        assert_eq!(actual, expected.as_slice());
+    }
+
+    #[test]
+    fn test_plane_sum_early_return_cpu() {
+        let client = TestRuntime::client(&Default::default());
+        let plane_size = client.properties().hardware.plane_size_max;
+        // The units past the bounds check exit before the plane operation, in the middle of the
+        // second plane.
+        let num_units = 2 * plane_size;
+        let len = plane_size + plane_size / 2;
+        let out = client.create_from_slice(u32::as_bytes(&vec![0; num_units as usize]));
+
+        unsafe {
+            plane_sum_early_return::launch::<TestRuntime>(
+                &client,
+                CubeCount::new_single(),
+                CubeDim::new_1d(num_units),
+                ArrayArg::from_raw_parts(out.clone(), num_units as usize),
+                len,
+            )
+        }
+
+        let bytes = client.read_one_unchecked(out);
+        let actual = u32::from_bytes(&bytes);
+        let expected: Vec<u32> = (0..num_units)
+            .map(|unit| match unit < len {
+                true => plane_size.min(len - unit / plane_size * plane_size),
+                false => 0,
+            })
+            .collect();
+        assert_eq!(actual, expected.as_slice());
+    }
+
+    #[test]
+    fn test_cmma_tile_per_plane_cpu() {
+        use cubecl_core::ir::{ElemType, FloatKind, features::MmaConfig};
+
+        const NUM_PLANES: usize = 2;
+
+        let client = TestRuntime::client(&Default::default());
+        // The shape of the `cmma` runtime tests, which would otherwise be skipped.
+        assert!(client.features().matmul.cmma.contains(&MmaConfig {
+            a_type: ElemType::Float(FloatKind::F16).into(),
+            b_type: ElemType::Float(FloatKind::F16).into(),
+            cd_type: ElemType::Float(FloatKind::F32).into(),
+            m: 16,
+            k: 16,
+            n: 16,
+        }));
+        let plane_size = client.properties().hardware.plane_size_max;
+        let lhs: Vec<f32> = (0..NUM_PLANES * 256)
+            .map(|i| ((i + i / 256) % 5) as f32)
+            .collect();
+        let rhs: Vec<f32> = (0..256).map(|i| (i % 3) as f32).collect();
+
+        let to_f16 = |values: &[f32]| values.iter().map(|v| f16::from_f32(*v)).collect::<Vec<_>>();
+        let lhs_handle = client.create_from_slice(f16::as_bytes(&to_f16(&lhs)));
+        let rhs_handle = client.create_from_slice(f16::as_bytes(&to_f16(&rhs)));
+        let out = client.empty(NUM_PLANES * 256 * core::mem::size_of::<f32>());
+
+        unsafe {
+            cmma_tile_per_plane::launch::<TestRuntime>(
+                &client,
+                CubeCount::new_single(),
+                CubeDim::new_1d(NUM_PLANES as u32 * plane_size),
+                ArrayArg::from_raw_parts(lhs_handle, NUM_PLANES * 256),
+                ArrayArg::from_raw_parts(rhs_handle, 256),
+                ArrayArg::from_raw_parts(out.clone(), NUM_PLANES * 256),
+            )
+        }
+
+        // `rhs` is loaded column-major, so the tile is multiplied by its transpose.
+        let expected: Vec<f32> = (0..NUM_PLANES * 256)
+            .map(|i| {
+                let (tile, row, column) = (i / 256, i % 256 / 16, i % 16);
+                (0..16)
+                    .map(|k| lhs[tile * 256 + row * 16 + k] * rhs[column * 16 + k])
+                    .sum()
+            })
+            .collect();
+        let bytes = client.read_one_unchecked(out);
+        let actual = f32::from_bytes(&bytes);
+        assert_eq!(actual, expected.as_slice());
+    }
+
+    #[test]
+    fn test_atomic_flag_spin_cpu() {
+        let client = TestRuntime::client(&Default::default());
+        let flag = client.create_from_slice(u32::as_bytes(&[0]));
+        let out = client.empty(core::mem::size_of::<u32>());
+
+        unsafe {
+            atomic_flag_spin::launch::<TestRuntime>(
+                &client,
+                CubeCount::new_single(),
+                CubeDim::new_1d(2),
+                ArrayArg::from_raw_parts(flag, 1),
+                ArrayArg::from_raw_parts(out.clone(), 1),
+            )
+        }
+
+        let bytes = client.read_one_unchecked(out);
+        let actual = u32::from_bytes(&bytes);
+        assert_eq!(actual, &[1]);
+    }
+
+    #[test]
+    fn test_out_of_bounds_checked_cpu() {
+        let client = TestRuntime::client(&Default::default());
+        let out = client.empty(2 * core::mem::size_of::<u32>());
+
+        unsafe {
+            out_of_bounds_checked::launch::<TestRuntime>(
+                &client,
+                CubeCount::new_single(),
+                CubeDim::new_1d(1),
+                ArrayArg::from_raw_parts(out.clone(), 2),
+                8,
+            )
+        }
+
+        // Out-of-bounds writes are skipped and out-of-bounds reads return zero.
+        let bytes = client.read_one_unchecked(out);
+        let actual = u32::from_bytes(&bytes);
+        assert_eq!(actual, &[1, 0]);
+    }
+
+    #[test]
+    fn test_register_layout_mma_not_advertised_cpu() {
+        let client = TestRuntime::client(&Default::default());
+        let matmul = &client.properties().features.matmul;
+
+        assert!(!matmul.cmma.is_empty());
+        assert!(matmul.mma.is_empty());
+        assert!(matmul.scaled_mma.is_empty());
+        assert!(matmul.ldmatrix.is_empty());
+        assert!(matmul.stmatrix.is_empty());
+    }
+
+    #[test]
+    fn test_report_out_of_bounds_cpu() {
+        use crate::compute::validation::{ErrorSink, report_out_of_bounds};
+        use cubecl_core::server::{MemoryAccess, ServerError};
+
+        let sink = ErrorSink::default();
+        sink.clone().bind();
+        report_out_of_bounds(c"kernel".as_ptr(), c"output(0)".as_ptr(), 1, 8, 2);
+        ErrorSink::unbind();
+
+        let errors = sink.drain();
+        match errors.as_slice() {
+            [
+                ServerError::OutOfBounds {
+                    kernel,
+                    buffer,
+                    access,
+                    index,
+                    length,
+                    ..
+                },
+            ] => {
+                assert_eq!(kernel, "kernel");
+                assert_eq!(buffer, "output(0)");
+                assert_eq!(*access, MemoryAccess::Write);
+                assert_eq!((*index, *length), (8, 2));
+            }
+            other => panic!("Should be a single out-of-bounds error, is {other:?}"),
+        }
+    }
 }
 
 pub mod compiler;
@@ -3,27 +3,70 @@ use crate::{
     compute::server::CpuServer,
     device::CpuDevice,
 };
-use cubecl_common::{device::DeviceService, profile::TimingMethod};
+use cubecl_common::{
+    device::{Device, DeviceService},
+    profile::TimingMethod,
+};
 use cubecl_core::{
     MemoryConfiguration, Runtime,
     client::ComputeClient,
     device::{DeviceId, ServerUtilitiesHandle},
     ir::{
         DeviceProperties, HardwareProperties, MemoryDeviceProperties, TargetProperties, VectorSize,
-        features::Features,
+        features::{Features, Plane},
     },
     server::ServerUtilities,
     zspace::{Shape, Strides},
 };
-use cubecl_runtime::{allocator::ContiguousMemoryLayoutPolicy, logging::ServerLogger};
-use cubecl_std::tensor::is_contiguous;
+use cubecl_runtime::{
+    allocator::ContiguousMemoryLayoutPolicy, logging::ServerLogger, runtime::emulated_plane_size,
+};
 use std::sync::Arc;
 use sysinfo::System;
 
-#[derive(Default)]
+/// The plane width emulated when no other value is configured.
+pub const DEFAULT_PLANE_SIZE: u32 = 32;
+
 pub struct RuntimeOptions {
     /// Configures the memory management.
     pub memory_config: MemoryConfiguration,
+    /// Number of units grouped in a plane, defaults to `CUBECL_CPU_PLANE_SIZE` when set. Should
+    /// be a power of two smaller or equal to 128, other values are clamped.
+    pub plane_size: u32,
+    /// Number of logical devices enumerated by the client, defaults to `CUBECL_CPU_DEVICE_COUNT`
+    /// when set. Collective operations between them are staged on the host.
+    pub device_count: u32,
+}
+
+/// The information of a CPU server, used to enumerate the logical devices.
+#[derive(Debug, Clone, Copy)]
+pub struct CpuServerInfo {
+    /// The number of logical devices, see [`RuntimeOptions::device_count`].
+    pub device_count: u32,
+}
+
+impl Default for RuntimeOptions {
+    fn default() -> Self {
+        Self {
+            memory_config: Default::default(),
+            plane_size: resolve_plane_size(),
+            device_count: resolve_device_count(),
+        }
+    }
+}
+
+fn resolve_plane_size() -> u32 {
+    std::env::var("CUBECL_CPU_PLANE_SIZE")
+        .ok()
+        .and_then(|value| value.parse::<u32>().ok())
+        .unwrap_or(DEFAULT_PLANE_SIZE)
+}
+
+fn resolve_device_count() -> u32 {
+    std::env::var("CUBECL_CPU_DEVICE_COUNT")
+        .ok()
+        .and_then(|value| value.parse::<u32>().ok())
+        .unwrap_or(1)
 }
 
 #[derive(Debug, Clone)]
@@ -32,62 +75,8 @@ pub struct CpuRuntime;
 pub type CpuCompiler = MlirCompiler;
 
 impl DeviceService for CpuServer {
-    fn init(_device_id: cubecl_common::device::DeviceId) -> Self {
-        let options = RuntimeOptions::default();
-        let max_cube_dim = (u32::MAX, u32::MAX, u32::MAX);
-        let max_cube_count = (u32::MAX, u32::MAX, u32::MAX);
-        let system = System::new_all();
-        let max_shared_memory_size = system
-            .cgroup_limits()
-            .map(|g| g.total_memory)
-            .unwrap_or(system.total_memory()) as usize;
-        let logger = cubecl_common::stub::Arc::new(ServerLogger::default());
-
-        let available_parallelism = std::thread::available_parallelism()
-            .expect("Can't get available parallelism on this platform")
-            .get();
-
-        let topology = HardwareProperties {
-            load_width: 512,
-            plane_size_min: 1,
-            plane_size_max: 1,
-            max_bindings: u32::MAX,
-            max_shared_memory_size,
-            max_cube_count,
-            num_cpu_cores: Some(available_parallelism as u32),
-            max_units_per_cube: u32::MAX,
-            max_cube_dim,
-            num_streaming_multiprocessors: None,
-            num_tensor_cores: None,
-            min_tensor_cores_dim: None,
-            max_vector_size: VectorSize::MAX,
-        };
-
-        const ALIGNMENT: u64 = 8;
-
-        let mem_properties = MemoryDeviceProperties {
-            max_page_size: max_shared_memory_size as u64,
-            alignment: ALIGNMENT,
-        };
-
-        let mut device_props = DeviceProperties::new(
-            Features {
-                unaligned_io: true,
-                ..Default::default()
-            },
-            mem_properties.clone(),
-            topology,
-            TimingMethod::Device,
-        );
-        register_supported_types(&mut device_props);
-
-        let utilities = ServerUtilities::new(
-            device_props,
-            logger,
-            (),
-            ContiguousMemoryLayoutPolicy::new(ALIGNMENT as usize),
-        );
-        CpuServer::new(mem_properties, options.memory_config, Arc::new(utilities))
+    fn init(device_id: cubecl_common::device::DeviceId) -> Self {
+        create_server(device_id, RuntimeOptions::default())
     }
 
     fn utilities(&self) -> ServerUtilitiesHandle {
@@ -95,6 +84,87 @@ impl DeviceService for CpuServer {
     }
 }
 
+/// Initialize the client of the given device with the given options.
+///
+/// # Panics
+///
+/// If the client of the device was already initialized.
+pub fn init(device: &CpuDevice, options: RuntimeOptions) -> ComputeClient<CpuRuntime> {
+    ComputeClient::init(device, create_server(device.to_id(), options))
+}
+
+pub(crate) fn create_server(device_id: DeviceId, options: RuntimeOptions) -> CpuServer {
+    let plane_size = emulated_plane_size(options.plane_size);
+    assert!(
+        options.device_count > 0,
+        "There should be at least one CPU device"
+    );
+    let max_cube_dim = (u32::MAX, u32::MAX, u32::MAX);
+    let max_cube_count = (u32::MAX, u32::MAX, u32::MAX);
+    let system = System::new_all();
+    let max_shared_memory_size = system
+        .cgroup_limits()
+        .map(|g| g.total_memory)
+        .unwrap_or(system.total_memory()) as usize;
+    let logger = cubecl_common::stub::Arc::new(ServerLogger::default());
+
+    let available_parallelism = std::thread::available_parallelism()
+        .expect("Can't get available parallelism on this platform")
+        .get();
+
+    let topology = HardwareProperties {
+        load_width: 512,
+        plane_size_min: plane_size,
+        plane_size_max: plane_size,
+        max_bindings: u32::MAX,
+        max_shared_memory_size,
+        max_cube_count,
+        num_cpu_cores: Some(available_parallelism as u32),
+        max_units_per_cube: u32::MAX,
+        max_cube_dim,
+        num_streaming_multiprocessors: None,
+        num_tensor_cores: None,
+        min_tensor_cores_dim: None,
+        max_vector_size: VectorSize::MAX,
+    };
+
+    const ALIGNMENT: u64 = 8;
+
+    let mem_properties = MemoryDeviceProperties {
+        max_page_size: max_shared_memory_size as u64,
+        alignment: ALIGNMENT,
+    };
+
+    let mut device_props = DeviceProperties::new(
+        Features {
+            plane: Plane::Ops | Plane::Sync,
+            unaligned_io: true,
+            ..Default::default()
+        },
+        mem_properties.clone(),
+        topology,
+        TimingMethod::Device,
+    );
+    register_supported_types(&mut device_props);
+
+    let info = CpuServerInfo {
+        device_count: options.device_count,
+    };
+    let utilities = ServerUtilities::new(
+        device_props,
+        logger,
+        info,
+        ContiguousMemoryLayoutPolicy::new(ALIGNMENT as usize),
+    );
+    CpuServer::new(
+        device_id,
+        mem_properties,
+        options.memory_config,
+        plane_size,
+        Arc::new(utilities),
+    )
+}
+
 impl Runtime for CpuRuntime {
     type Compiler = CpuCompiler;
     type Server = CpuServer;
@@ -112,8 +182,9 @@ impl Runtime for CpuRuntime {
         (u32::MAX, u32::MAX, u32::MAX)
     }
 
-    fn can_read_tensor(shape: &Shape, strides: &Strides) -> bool {
-        is_contiguous(shape, strides)
+    fn can_read_tensor(_shape: &Shape, _strides: &Strides) -> bool {
+        // Non-contiguous tensors are gathered on the host after the read.
+        true
     }
 
     fn target_properties() -> TargetProperties {
@@ -123,13 +194,12 @@ impl Runtime for CpuRuntime {
         }
     }
 
-    fn enumerate_devices(
-        _: u16,
-        _: &<Self::Server as cubecl_core::server::ComputeServer>::Info,
-    ) -> Vec<DeviceId> {
-        vec![DeviceId {
-            type_id: 0,
-            index_id: 0,
-        }]
+    fn enumerate_devices(_: u16, info: &CpuServerInfo) -> Vec<DeviceId> {
+        (0..info.device_count)
+            .map(|index_id| DeviceId {
+                type_id: 0,
+                index_id,
+            })
+            .collect()
     }
 }
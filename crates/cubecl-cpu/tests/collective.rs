@@ -0,0 +1,72 @@
+//! Runs the collective tests between multiple logical CPU devices, which are staged on the host.
+
+use cubecl_core::{
+    Runtime,
+    client::ComputeClient,
+    device::DeviceId,
+    ir::TargetProperties,
+    zspace::{Shape, Strides},
+};
+use cubecl_cpu::{
+    CpuCompiler, CpuDevice, CpuRuntime, CpuServerInfo, RuntimeOptions, compute::server::CpuServer,
+    init,
+};
+use std::sync::Once;
+
+/// The number of logical devices the collectives are tested with.
+const DEVICE_COUNT: u32 = 4;
+
+/// The CPU runtime, with its default device initialized to enumerate [`DEVICE_COUNT`] devices
+/// before any test retrieves its client.
+#[derive(Debug, Clone)]
+pub struct MultiDeviceRuntime;
+
+impl Runtime for MultiDeviceRuntime {
+    type Compiler = CpuCompiler;
+    type Server = CpuServer;
+    type Device = CpuDevice;
+
+    fn client(device: &Self::Device) -> ComputeClient<Self> {
+        static INIT: Once = Once::new();
+        INIT.call_once(|| {
+            let options = RuntimeOptions {
+                device_count: DEVICE_COUNT,
+                ..Default::default()
+            };
+            init(&CpuDevice::default(), options);
+        });
+        ComputeClient::load(device)
+    }
+
+    fn name(_client: &ComputeClient<Self>) -> &'static str {
+        "cpu-multi-device"
+    }
+
+    fn max_cube_count() -> (u32, u32, u32) {
+        CpuRuntime::max_cube_count()
+    }
+
+    fn can_read_tensor(shape: &Shape, strides: &Strides) -> bool {
+        CpuRuntime::can_read_tensor(shape, strides)
+    }
+
+    fn target_properties() -> TargetProperties {
+        CpuRuntime::target_properties()
+    }
+
+    fn enumerate_devices(type_id: u16, info: &CpuServerInfo) -> Vec<DeviceId> {
+        CpuRuntime::enumerate_devices(type_id, info)
+    }
+}
+
+#[test]
+fn enumerates_configured_devices() {
+    let client = MultiDeviceRuntime::client(&CpuDevice::default());
+    assert_eq!(client.enumerate_devices(0).len(), DEVICE_COUNT as usize);
+}
+
+mod tests {
+    pub type TestRuntime = super::MultiDeviceRuntime;
+
+    cubecl_core::testgen_all_reduce!();
+}
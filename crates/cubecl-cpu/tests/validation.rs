@@ -0,0 +1,102 @@
+//! Runs kernels with out-of-bounds accesses, with the unchecked launches validated by the global
+//! config of this test binary.
+
+use cubecl_core as cubecl;
+use cubecl_core::{
+    prelude::*,
+    server::{MemoryAccess, ServerError},
+};
+use cubecl_cpu::{CpuDevice, CpuRuntime};
+use cubecl_runtime::config::{
+    GlobalConfig,
+    compilation::{BoundsCheckMode, CompilationConfig},
+};
+use std::sync::Once;
+
+#[cube(launch, launch_unchecked)]
+fn write_out_of_bounds(out: &mut Array<u32>, index: u32) {
+    out[index as usize] = 1u32;
+}
+
+/// The client of the default device, created once unchecked launches are validated.
+fn client() -> ComputeClient<CpuRuntime> {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        GlobalConfig::set(GlobalConfig {
+            compilation: CompilationConfig {
+                check_mode: BoundsCheckMode::Validate,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+    });
+    CpuRuntime::client(&CpuDevice::default())
+}
+
+#[test]
+fn validate_reports_out_of_bounds_on_flush() {
+    let client = client();
+    // The array is the first half of the buffer, so invalid writes would land in the second half.
+    let handle = client.create_from_slice(u32::as_bytes(&[0, 0, 9, 9]));
+    let array = handle.clone().offset_end(2 * size_of::<u32>() as u64);
+
+    unsafe {
+        write_out_of_bounds::launch_unchecked::<CpuRuntime>(
+            &client,
+            CubeCount::new_single(),
+            CubeDim::new_1d(1),
+            ArrayArg::from_raw_parts(array, 2),
+            3,
+        )
+    }
+
+    match client.flush() {
+        Err(ServerError::ServerUnhealthy { errors, .. }) => match errors.as_slice() {
+            [
+                ServerError::OutOfBounds {
+                    kernel,
+                    buffer,
+                    access,
+                    index,
+                    length,
+                    ..
+                },
+            ] => {
+                assert_eq!(kernel, "write_out_of_bounds");
+                assert_eq!(buffer, "output(0)");
+                assert_eq!(*access, MemoryAccess::Write);
+                assert_eq!((*index, *length), (3, 2));
+            }
+            other => panic!("Should be a single out-of-bounds error, is {other:?}"),
+        },
+        other => panic!("Should report the out-of-bounds write, is {other:?}"),
+    }
+
+    let bytes = client.read_one_unchecked(handle);
+    assert_eq!(u32::from_bytes(&bytes), &[0, 0, 9, 9]);
+}
+
+#[test]
+fn checked_skips_out_of_bounds_writes() {
+    let client = client();
+    let handle = client.create_from_slice(u32::as_bytes(&[0, 0, 9, 9]));
+    let array = handle.clone().offset_end(2 * size_of::<u32>() as u64);
+
+    // Safe launches are always checked, even when unchecked launches are validated.
+    unsafe {
+        write_out_of_bounds::launch::<CpuRuntime>(
+            &client,
+            CubeCount::new_single(),
+            CubeDim::new_1d(1),
+            ArrayArg::from_raw_parts(array, 2),
+            3,
+        )
+    }
+
+    assert!(
+        client.flush().is_ok(),
+        "Checked launches don't report errors"
+    );
+    let bytes = client.read_one_unchecked(handle);
+    assert_eq!(u32::from_bytes(&bytes), &[0, 0, 9, 9]);
+}
@@ -13,7 +13,7 @@ use cubecl_common::{
 #[cfg(debug_assertions)]
 use cubecl_core::zspace::striding::try_check_pitched_row_major_strides;
 use cubecl_core::{
-    MemoryUsage,
+    MemoryTrace, MemoryUsage,
     future::DynFut,
     server::{
         Binding, CopyDescriptor, ExecutionMode, Handle, IoError, LaunchError, ProfileError,
@@ -86,6 +86,19 @@ impl<'a> Command<'a> {
         self.streams.current().memory_management_gpu.cleanup(true)
     }
 
+    /// Take the gpu memory events traced on the current stream.
+    pub fn memory_trace(&mut self) -> MemoryTrace {
+        self.streams.current().memory_management_gpu.take_trace()
+    }
+
+    /// Record the launch of a kernel in the gpu memory trace of the current stream.
+    pub fn trace_launch(&mut self, kernel: &str) {
+        self.streams
+            .current()
+            .memory_management_gpu
+            .trace_launch(kernel)
+    }
+
     /// Set the [`MemoryAllocationMode`] for the current stream.
     ///
     /// # Parameters
@@ -1,6 +1,12 @@
 use std::{collections::HashMap, sync::OnceLock};
 
-use cubecl_core::{device::DeviceId, ir::ElemType, server::ReduceOperation, stub::Mutex};
+use cubecl_common::backtrace::BackTrace;
+use cubecl_core::{
+    device::DeviceId,
+    ir::ElemType,
+    server::{ReduceOperation, ServerError},
+    stub::Mutex,
+};
 
 /// An ID unique to any unordered combination of devices.
 #[derive(Debug, Hash, Eq, PartialEq)]
@@ -44,9 +50,24 @@ pub(crate) fn to_nccl_op(op: ReduceOperation) -> cudarc::nccl::sys::ncclRedOp_t
     match op {
         ReduceOperation::Sum => cudarc::nccl::sys::ncclRedOp_t::ncclSum,
         ReduceOperation::Mean => cudarc::nccl::sys::ncclRedOp_t::ncclAvg,
+        ReduceOperation::Min => cudarc::nccl::sys::ncclRedOp_t::ncclMin,
+        ReduceOperation::Max => cudarc::nccl::sys::ncclRedOp_t::ncclMax,
+        ReduceOperation::Prod => cudarc::nccl::sys::ncclRedOp_t::ncclProd,
     }
 }
 
+/// The rank of `device` in the communicator of `device_ids`.
+pub(crate) fn nccl_rank(device_ids: &[DeviceId], device: DeviceId) -> Result<i32, ServerError> {
+    device_ids
+        .iter()
+        .position(|id| *id == device)
+        .map(|rank| rank as i32)
+        .ok_or_else(|| ServerError::Generic {
+            reason: format!("The device {device:?} isn't part of the collective"),
+            backtrace: BackTrace::capture(),
+        })
+}
+
 pub(crate) fn get_nccl_dtype_count(
     dtype: ElemType,
     size: u64,
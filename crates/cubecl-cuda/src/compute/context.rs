@@ -13,13 +13,11 @@ use crate::{
     install::{cccl_include_path, include_path},
 };
 use cubecl_core::{
-    compilation_cache::CompilationCache,
-    hash::StableHash,
     server::ResourceLimitError,
     {ir::DeviceProperties, prelude::*},
 };
 use cubecl_runtime::timestamp_profiler::TimestampProfiler;
-use cubecl_runtime::{compiler::CubeTask, logging::ServerLogger};
+use cubecl_runtime::{compiler::CubeTask, kernel_cache::KernelCache, logging::ServerLogger};
 use cudarc::driver::DriverError;
 use cudarc::driver::sys::CUfunc_st;
 use cudarc::driver::sys::{CUctx_st, CUfunction_attribute, CUtensorMap};
@@ -30,13 +28,11 @@ use std::str::FromStr;
 use std::sync::Arc;
 use std::{ffi::CStr, os::raw::c_void};
 
-use cubecl_common::cache::CacheOption;
-
 #[derive(Debug)]
 pub(crate) struct CudaContext {
     pub context: *mut CUctx_st,
     pub module_names: HashMap<KernelId, CompiledKernel>,
-    ptx_cache: Option<CompilationCache<StableHash, PtxCacheEntry>>,
+    ptx_cache: Option<KernelCache<PtxCacheEntry>>,
     pub timestamps: TimestampProfiler,
     pub arch: CudaArchitecture,
     pub compilation_options: CompilationOptions,
@@ -67,18 +63,12 @@ impl CudaContext {
         Self {
             context,
             module_names: HashMap::new(),
-            ptx_cache: {
-                let config = cubecl_runtime::config::GlobalConfig::get();
-                if let Some(cache) = &config.compilation.cache {
-                    let root = cache.root();
-                    Some(CompilationCache::new(
-                        "ptx",
-                        CacheOption::default().name("cuda").root(root),
-                    ))
-                } else {
-                    None
-                }
-            },
+            ptx_cache: KernelCache::new(
+                "cuda",
+                "ptx",
+                &(&compilation_options, arch.version),
+                properties.checksum(),
+            ),
             arch,
             timestamps: TimestampProfiler::default(),
             compilation_options,
@@ -100,10 +90,10 @@ impl CudaContext {
         mode: ExecutionMode,
         logger: Arc<ServerLogger>,
     ) -> Result<(), LaunchError> {
-        let hash = if let Some(cache) = &self.ptx_cache {
-            let hash = kernel_id.stable_hash();
+        let key = if let Some(cache) = &self.ptx_cache {
+            let key = cache.key(kernel_id);
 
-            if let Some(entry) = cache.get(&hash) {
+            if let Some(entry) = cache.get(&key) {
                 log::trace!("Using PTX cache");
 
                 self.load_ptx(
@@ -115,7 +105,7 @@ impl CudaContext {
                 )?;
                 return Ok(());
             }
-            Some(hash)
+            Some(key)
         } else {
             None
         };
@@ -212,17 +202,14 @@ impl CudaContext {
         let repr = kernel_compiled.repr.unwrap();
 
         if let Some(cache) = &mut self.ptx_cache {
-            let result = cache.insert(
-                hash.unwrap(),
+            cache.insert(
+                key.unwrap(),
                 PtxCacheEntry {
                     entrypoint_name: kernel_compiled.entrypoint_name.clone(),
                     shared_mem_bytes: repr.shared_memory_size(),
                     ptx: ptx.clone(),
                 },
             );
-            if let Err(err) = result {
-                log::warn!("Unable to save the ptx {err:?}");
-            }
         }
 
         self.load_ptx(
@@ -3,7 +3,9 @@ use crate::{
     CudaCompiler,
     compute::{
         command::{Command, write_to_cpu},
-        communication::{CudaCommId, get_nccl_comm_id, get_nccl_dtype_count, to_nccl_op},
+        communication::{
+            CudaCommId, get_nccl_comm_id, get_nccl_dtype_count, nccl_rank, to_nccl_op,
+        },
         context::CudaContext,
         stream::CudaStreamBackend,
         sync::Fence,
@@ -30,7 +32,7 @@ use cubecl_runtime::{
     compiler::CubeTask,
     config::GlobalConfig,
     logging::ServerLogger,
-    memory_management::{ManagedMemoryHandle, MemoryAllocationMode, MemoryUsage},
+    memory_management::{ManagedMemoryHandle, MemoryAllocationMode, MemoryTrace, MemoryUsage},
     server::ComputeServer,
     storage::{ComputeStorage, ManagedResource},
     stream::MultiStream,
@@ -257,6 +259,17 @@ impl ComputeServer for CudaServer {
         command.memory_cleanup()
     }
 
+    fn memory_trace(&mut self, stream_id: StreamId) -> Result<MemoryTrace, ServerError> {
+        let mut command = self.command_no_inputs(
+            stream_id,
+            StreamErrorMode {
+                ignore: false,
+                flush: false,
+            },
+        )?;
+        Ok(command.memory_trace())
+    }
+
     fn allocation_mode(&mut self, mode: MemoryAllocationMode, stream_id: StreamId) {
         let mut command = match self.command_no_inputs(
             stream_id,
@@ -284,63 +297,202 @@ impl ServerCommunication for CudaServer {
         op: ReduceOperation,
         device_ids: Vec<DeviceId>,
     ) -> Result<(), ServerError> {
-        // We create a command on the server to retrieve the correct resource of the source and the destination
-        // from the memory pools.
-        if src.stream != dst.stream {
-            for stream in [src.stream, dst.stream].iter() {
-                let mut command = self.command_no_inputs(
-                    *stream,
-                    StreamErrorMode {
-                        ignore: false,
-                        flush: false,
-                    },
-                )?;
-                command.error(ServerError::Generic {
-                    reason: "Source and destination should be on the same stream.".into(),
-                    backtrace: BackTrace::capture(),
-                });
-            }
+        let resources = self.collective_resources(vec![src, dst], stream_id)?;
+        let comm = self.communicator(device_ids);
+        let (nccl_dtype, count) = get_nccl_dtype_count(dtype, resources[0].size);
+
+        // SAFETY: The resource pointers are valid device pointers, `comm` is a valid NCCL
+        // communicator initialized via `comm_init_rank` and `self.comm_stream` is a valid CUDA
+        // stream dedicated to collective operations.
+        unsafe {
+            cudarc::nccl::result::all_reduce(
+                resources[0].ptr as *const _,
+                resources[1].ptr as *mut _,
+                count,
+                nccl_dtype,
+                to_nccl_op(op),
+                comm,
+                self.comm_stream as _,
+            )
+            .unwrap();
         }
 
-        let mut command_src = self.command(
-            stream_id,
-            [&src, &dst].into_iter(),
-            StreamErrorMode {
-                ignore: false,
-                flush: false,
-            },
-        )?;
-        let resource_src = command_src.resource(src)?;
-        let resource_dst = command_src.resource(dst)?;
+        Ok(())
+    }
 
-        let stream = command_src.streams.current().sys;
+    fn broadcast(
+        &mut self,
+        src: Binding,
+        dst: Binding,
+        dtype: ElemType,
+        stream_id: StreamId,
+        root: DeviceId,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        let root = nccl_rank(&device_ids, root)?;
+        let resources = self.collective_resources(vec![src, dst], stream_id)?;
+        let comm = self.communicator(device_ids);
+        let (nccl_dtype, count) = get_nccl_dtype_count(dtype, resources[1].size);
 
-        // We need to free the command before accessing communicators.
-        core::mem::drop(command_src);
+        // SAFETY: See `all_reduce`.
+        unsafe {
+            cudarc::nccl::result::broadcast(
+                resources[0].ptr as *const _,
+                resources[1].ptr as *mut _,
+                count,
+                nccl_dtype,
+                root,
+                comm,
+                self.comm_stream as _,
+            )
+            .unwrap();
+        }
 
-        // Wait for data to be ready on compute stream.
-        Fence::new(stream).wait_async(self.comm_stream);
+        Ok(())
+    }
 
-        // Get the communicator, if it doesn't exist, initialize it.
-        let id = CudaCommId::from(device_ids.clone());
-        let entry = self.communicators.get(&id);
-        let comm = match entry {
-            Some(c) => *c,
-            None => self.create_communicator(device_ids),
-        };
+    fn reduce(
+        &mut self,
+        src: Binding,
+        dst: Binding,
+        dtype: ElemType,
+        stream_id: StreamId,
+        op: ReduceOperation,
+        root: DeviceId,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        let root = nccl_rank(&device_ids, root)?;
+        let resources = self.collective_resources(vec![src, dst], stream_id)?;
+        let comm = self.communicator(device_ids);
+        let (nccl_dtype, count) = get_nccl_dtype_count(dtype, resources[0].size);
 
-        // Perform the `cudarc::nccl::result::all_reduce` operation.
-        let (nccl_dtype, count) = get_nccl_dtype_count(dtype, resource_src.size);
-        // SAFETY: `resource_src.ptr` and `resource_dst.ptr` are valid device pointers.
-        // `comm` is a valid NCCL communicator initialized via `comm_init_rank`.
-        // `self.comm_stream` is a valid CUDA stream dedicated to collective operations.
+        // SAFETY: See `all_reduce`.
         unsafe {
-            cudarc::nccl::result::all_reduce(
-                resource_src.ptr as *const _,
-                resource_dst.ptr as *mut _,
+            cudarc::nccl::result::reduce(
+                resources[0].ptr as *const _,
+                resources[1].ptr as *mut _,
                 count,
                 nccl_dtype,
                 to_nccl_op(op),
+                root,
+                comm,
+                self.comm_stream as _,
+            )
+            .unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn all_gather(
+        &mut self,
+        src: Binding,
+        dst: Binding,
+        dtype: ElemType,
+        stream_id: StreamId,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        let resources = self.collective_resources(vec![src, dst], stream_id)?;
+        let comm = self.communicator(device_ids);
+        // The count is the number of elements sent by each device.
+        let (nccl_dtype, count) = get_nccl_dtype_count(dtype, resources[0].size);
+
+        // SAFETY: See `all_reduce`.
+        unsafe {
+            cudarc::nccl::result::all_gather(
+                resources[0].ptr as *const _,
+                resources[1].ptr as *mut _,
+                count,
+                nccl_dtype,
+                comm,
+                self.comm_stream as _,
+            )
+            .unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn reduce_scatter(
+        &mut self,
+        src: Binding,
+        dst: Binding,
+        dtype: ElemType,
+        stream_id: StreamId,
+        op: ReduceOperation,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        let resources = self.collective_resources(vec![src, dst], stream_id)?;
+        let comm = self.communicator(device_ids);
+        // The count is the number of elements received by each device.
+        let (nccl_dtype, count) = get_nccl_dtype_count(dtype, resources[1].size);
+
+        // SAFETY: See `all_reduce`.
+        unsafe {
+            cudarc::nccl::result::reduce_scatter(
+                resources[0].ptr as *const _,
+                resources[1].ptr as *mut _,
+                count,
+                nccl_dtype,
+                to_nccl_op(op),
+                comm,
+                self.comm_stream as _,
+            )
+            .unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn send(
+        &mut self,
+        src: Binding,
+        dtype: ElemType,
+        stream_id: StreamId,
+        peer: DeviceId,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        let peer = nccl_rank(&device_ids, peer)?;
+        let resources = self.collective_resources(vec![src], stream_id)?;
+        let comm = self.communicator(device_ids);
+        let (nccl_dtype, count) = get_nccl_dtype_count(dtype, resources[0].size);
+
+        // SAFETY: See `all_reduce`.
+        unsafe {
+            cudarc::nccl::result::send(
+                resources[0].ptr as *const _,
+                count,
+                nccl_dtype,
+                peer,
+                comm,
+                self.comm_stream as _,
+            )
+            .unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn recv(
+        &mut self,
+        dst: Binding,
+        dtype: ElemType,
+        stream_id: StreamId,
+        peer: DeviceId,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        let peer = nccl_rank(&device_ids, peer)?;
+        let resources = self.collective_resources(vec![dst], stream_id)?;
+        let comm = self.communicator(device_ids);
+        let (nccl_dtype, count) = get_nccl_dtype_count(dtype, resources[0].size);
+
+        // SAFETY: See `all_reduce`.
+        unsafe {
+            cudarc::nccl::result::recv(
+                resources[0].ptr as *mut _,
+                count,
+                nccl_dtype,
+                peer,
                 comm,
                 self.comm_stream as _,
             )
@@ -734,6 +886,7 @@ impl CudaServer {
                 flush: false,
             },
         )?;
+        command.trace_launch(kernel.name());
 
         let count = match count {
             CubeCount::Static(x, y, z) => (x, y, z),
@@ -962,6 +1115,66 @@ impl CudaServer {
         Ok(())
     }
 
+    /// Retrieve the resources used by a collective operation and make the communication stream
+    /// wait for the data to be ready on the compute stream.
+    fn collective_resources(
+        &mut self,
+        bindings: Vec<Binding>,
+        stream_id: StreamId,
+    ) -> Result<Vec<GpuResource>, ServerError> {
+        // We create a command on the server to retrieve the correct resources from the memory
+        // pools.
+        let first = bindings[0].stream;
+        if bindings.iter().any(|binding| binding.stream != first) {
+            for binding in bindings.iter() {
+                let mut command = self.command_no_inputs(
+                    binding.stream,
+                    StreamErrorMode {
+                        ignore: false,
+                        flush: false,
+                    },
+                )?;
+                command.error(ServerError::Generic {
+                    reason: "All buffers of a collective operation should be on the same stream."
+                        .into(),
+                    backtrace: BackTrace::capture(),
+                });
+            }
+        }
+
+        let mut command = self.command(
+            stream_id,
+            bindings.iter(),
+            StreamErrorMode {
+                ignore: false,
+                flush: false,
+            },
+        )?;
+        let resources = bindings
+            .into_iter()
+            .map(|binding| command.resource(binding))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let stream = command.streams.current().sys;
+
+        // We need to free the command before accessing communicators.
+        core::mem::drop(command);
+
+        // Wait for data to be ready on compute stream.
+        Fence::new(stream).wait_async(self.comm_stream);
+
+        Ok(resources)
+    }
+
+    /// Get the communicator for the given devices, if it doesn't exist, initialize it.
+    fn communicator(&mut self, device_ids: Vec<DeviceId>) -> *mut ncclComm {
+        let id = CudaCommId::from(device_ids.clone());
+        match self.communicators.get(&id) {
+            Some(comm) => *comm,
+            None => self.create_communicator(device_ids),
+        }
+    }
+
     fn create_communicator(&mut self, device_ids: Vec<DeviceId>) -> *mut ncclComm {
         let id = CudaCommId::from(device_ids.clone());
         let mut comm = MaybeUninit::uninit();
@@ -29,7 +29,8 @@ use cubecl_cpp::{
     },
 };
 use cubecl_runtime::{
-    allocator::PitchedMemoryLayoutPolicy, client::ComputeClient, logging::ServerLogger,
+    allocator::PitchedMemoryLayoutPolicy, client::ComputeClient, config::GlobalConfig,
+    logging::ServerLogger,
 };
 use cudarc::driver::sys::{CUDA_VERSION, cuDeviceTotalMem_v2};
 use std::{mem::MaybeUninit, sync::Arc};
@@ -109,6 +110,8 @@ impl DeviceService for CudaServer {
                 fast_math: true,
                 ..Default::default()
             },
+            optimize: GlobalConfig::get().compilation.optimize,
+            unroll_loops: GlobalConfig::get().compilation.unroll_loops,
             ..Default::default()
         };
 
@@ -7,7 +7,7 @@ use crate::{
 };
 use cubecl_common::{backtrace::BackTrace, bytes::Bytes, stream_id::StreamId};
 use cubecl_core::{
-    MemoryUsage,
+    MemoryTrace, MemoryUsage,
     bytes::AllocationProperty,
     future::DynFut,
     server::{
@@ -70,6 +70,19 @@ impl<'a> Command<'a> {
         self.streams.current().memory_management_gpu.cleanup(true)
     }
 
+    /// Take the gpu memory events traced on the current stream.
+    pub fn memory_trace(&mut self) -> MemoryTrace {
+        self.streams.current().memory_management_gpu.take_trace()
+    }
+
+    /// Record the launch of a kernel in the gpu memory trace of the current stream.
+    pub fn trace_launch(&mut self, kernel: &str) {
+        self.streams
+            .current()
+            .memory_management_gpu
+            .trace_launch(kernel)
+    }
+
     /// Set the [`MemoryAllocationMode`] for the current stream.
     ///
     /// # Parameters
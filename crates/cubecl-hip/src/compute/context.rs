@@ -2,10 +2,7 @@ use super::storage::gpu::GpuResource;
 use crate::runtime::HipCompiler;
 use crate::{compute::stream::Stream, runtime::HipComputeKernel};
 use cubecl_common::backtrace::BackTrace;
-use cubecl_common::cache::CacheOption;
-use cubecl_common::hash::StableHash;
 use cubecl_core::{
-    compilation_cache::CompilationCache,
     server::ResourceLimitError,
     {ir::DeviceProperties, prelude::*},
 };
@@ -17,7 +14,7 @@ use cubecl_runtime::{
     compiler::CompilationError,
     validation::{validate_cube_dim, validate_units},
 };
-use cubecl_runtime::{compiler::CubeTask, logging::ServerLogger};
+use cubecl_runtime::{compiler::CubeTask, kernel_cache::KernelCache, logging::ServerLogger};
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashMap;
@@ -31,7 +28,7 @@ pub(crate) struct HipContext {
     pub timestamps: TimestampProfiler,
     pub compilation_options: CompilationOptions,
     pub properties: DeviceProperties,
-    pub compilation_cache: Option<CompilationCache<StableHash, CompilationCacheEntry>>,
+    pub compilation_cache: Option<KernelCache<CompilationCacheEntry>>,
 }
 
 #[derive(Debug)]
@@ -54,19 +51,13 @@ impl HipContext {
         Self {
             module_names: HashMap::new(),
             timestamps: TimestampProfiler::default(),
+            compilation_cache: KernelCache::new(
+                "hip",
+                "hip-kernel",
+                &compilation_options,
+                properties.checksum(),
+            ),
             compilation_options,
-            compilation_cache: {
-                let config = cubecl_runtime::config::GlobalConfig::get();
-                if let Some(cache) = &config.compilation.cache {
-                    let root = cache.root();
-                    Some(CompilationCache::new(
-                        "hip-kernel",
-                        CacheOption::default().name("hip").root(root),
-                    ))
-                } else {
-                    None
-                }
-            },
             properties,
         }
     }
@@ -79,9 +70,9 @@ impl HipContext {
         mode: ExecutionMode,
         logger: Arc<ServerLogger>,
     ) -> Result<(), LaunchError> {
-        let hash = if let Some(cache) = self.compilation_cache.as_ref() {
-            let hash = kernel_id.stable_hash();
-            if let Some(entry) = cache.get(&hash) {
+        let key = if let Some(cache) = self.compilation_cache.as_ref() {
+            let key = cache.key(kernel_id);
+            if let Some(entry) = cache.get(&key) {
                 log::trace!("Using compilation cache");
                 self.load_compiled_binary(
                     entry.binary.clone(),
@@ -92,7 +83,7 @@ impl HipContext {
                 )?;
                 return Ok(());
             }
-            Some(hash)
+            Some(key)
         } else {
             None
         };
@@ -244,16 +235,14 @@ impl HipContext {
         let repr = jitc_kernel.repr.unwrap();
 
         if let Some(cache) = self.compilation_cache.as_mut() {
-            cache
-                .insert(
-                    hash.unwrap(),
-                    CompilationCacheEntry {
-                        entrypoint_name: jitc_kernel.entrypoint_name.clone(),
-                        shared_mem_bytes: repr.shared_memory_size(),
-                        binary: code.clone(),
-                    },
-                )
-                .unwrap();
+            cache.insert(
+                key.unwrap(),
+                CompilationCacheEntry {
+                    entrypoint_name: jitc_kernel.entrypoint_name.clone(),
+                    shared_mem_bytes: repr.shared_memory_size(),
+                    binary: code.clone(),
+                },
+            );
         }
 
         self.load_compiled_binary(
@@ -20,7 +20,7 @@ use cubecl_runtime::{
     compiler::CubeTask,
     config::GlobalConfig,
     logging::ServerLogger,
-    memory_management::{ManagedMemoryHandle, MemoryAllocationMode, MemoryUsage},
+    memory_management::{ManagedMemoryHandle, MemoryAllocationMode, MemoryTrace, MemoryUsage},
     server::ComputeServer,
     storage::{ComputeStorage, ManagedResource},
     stream::MultiStream,
@@ -235,6 +235,17 @@ impl ComputeServer for HipServer {
         command.memory_cleanup()
     }
 
+    fn memory_trace(&mut self, stream_id: StreamId) -> Result<MemoryTrace, ServerError> {
+        let mut command = self.command_no_inputs(
+            stream_id,
+            StreamErrorMode {
+                ignore: false,
+                flush: false,
+            },
+        )?;
+        Ok(command.memory_trace())
+    }
+
     fn allocation_mode(&mut self, mode: MemoryAllocationMode, stream_id: StreamId) {
         let mut command = match self.command_no_inputs(
             stream_id,
@@ -352,6 +363,7 @@ impl HipServer {
                 flush: false,
             },
         )?;
+        command.trace_launch(kernel.name());
 
         let count = match count {
             CubeCount::Static(x, y, z) => (x, y, z),
@@ -29,7 +29,8 @@ use cubecl_cpp::{
 };
 use cubecl_hip_sys::{HIP_SUCCESS, hipDeviceScheduleSpin, hipGetDeviceCount, hipSetDeviceFlags};
 use cubecl_runtime::{
-    allocator::PitchedMemoryLayoutPolicy, client::ComputeClient, logging::ServerLogger,
+    allocator::PitchedMemoryLayoutPolicy, client::ComputeClient, config::GlobalConfig,
+    logging::ServerLogger,
 };
 use std::{ffi::CStr, mem::MaybeUninit, sync::Arc};
 
@@ -185,6 +186,8 @@ impl DeviceService for HipServer {
                 fast_math: true,
                 ..Default::default()
             },
+            optimize: GlobalConfig::get().compilation.optimize,
+            unroll_loops: GlobalConfig::get().compilation.unroll_loops,
         };
         let hip_ctx = HipContext::new(comp_opts, device_props.clone());
         let logger = Arc::new(ServerLogger::default());
@@ -0,0 +1,371 @@
+use core::fmt::Display;
+
+use cubecl_common::backtrace::BackTrace;
+use cubecl_core::{
+    CubeDim, Info, Metadata,
+    ir::{
+        Branch, Id, Instruction, NonSemantic, Operation, OperationReflect, Processor, Scope,
+        StorageType, Variable, VariableKind,
+    },
+    post_processing::checked_io::CheckedIoProcessor,
+    prelude::KernelDefinition,
+    server::ExecutionMode,
+};
+use cubecl_opt::{Optimizer, UnrollOptions};
+use cubecl_runtime::compiler::CompilationError;
+use hashbrown::HashMap;
+
+use crate::{compiler::InterpreterCompilerOptions, compute::value::Value};
+
+pub(crate) type BlockId = usize;
+
+/// A kernel lowered to a tree of blocks that can be walked by the interpreter.
+///
+/// Structured control flow is kept as is, since every unit keeps its own stack of blocks while
+/// running, which makes it trivial to suspend a unit in the middle of a loop when it reaches a
+/// synchronization point.
+#[derive(Debug)]
+pub struct InterpretedKernel {
+    pub(crate) kernel_name: String,
+    pub(crate) cube_dim: CubeDim,
+    pub(crate) info: Info,
+    pub(crate) address_type: StorageType,
+    /// Position of each buffer in the bindings, indexed by variable id.
+    pub(crate) buffer_positions: HashMap<Id, usize>,
+    /// Position of each buffer in the extended metadata, indexed by variable id.
+    pub(crate) ext_meta_positions: Vec<u32>,
+    /// Constant arrays, already encoded in their memory representation.
+    pub(crate) const_arrays: HashMap<Id, Vec<u8>>,
+    /// Total size of the shared memories in bytes.
+    pub(crate) shared_memory_size: usize,
+    pub(crate) blocks: Vec<Block>,
+    pub(crate) entry: BlockId,
+}
+
+// SAFETY: Scopes are the only part of the IR that isn't thread safe, and they are only held by
+// branch instructions, which are all lowered into blocks.
+unsafe impl Send for InterpretedKernel {}
+unsafe impl Sync for InterpretedKernel {}
+
+#[derive(Debug, Default)]
+pub(crate) struct Block {
+    pub(crate) nodes: Vec<Node>,
+}
+
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum Node {
+    Instruction(Instruction),
+    If {
+        cond: Variable,
+        then: BlockId,
+    },
+    IfElse {
+        cond: Variable,
+        then: BlockId,
+        or_else: BlockId,
+    },
+    Switch {
+        value: Variable,
+        cases: Vec<(Variable, BlockId)>,
+        default: BlockId,
+    },
+    RangeLoop {
+        i: Variable,
+        start: Variable,
+        end: Variable,
+        step: Option<Variable>,
+        inclusive: bool,
+        body: BlockId,
+    },
+    Loop {
+        body: BlockId,
+    },
+    Return,
+    Break,
+    Continue,
+    Unreachable,
+}
+
+impl InterpretedKernel {
+    pub(crate) fn lower(
+        kernel: KernelDefinition,
+        options: &InterpreterCompilerOptions,
+        mode: ExecutionMode,
+        address_type: StorageType,
+    ) -> Result<Self, CompilationError> {
+        let (metadata, ext_meta_positions) = build_metadata(&kernel);
+        let info = Info::new(&kernel.scalars, metadata, address_type);
+
+        let buffer_positions = kernel
+            .buffers
+            .iter()
+            .enumerate()
+            .map(|(pos, buffer)| (buffer.id, pos))
+            .collect();
+
+        let checked_io = CheckedIoProcessor::new(mode, kernel.options.kernel_name.clone());
+        let (mut body, checked_io) = match options.optimize {
+            true => {
+                let mut opt = Optimizer::with_unroll(
+                    kernel.body,
+                    kernel.cube_dim,
+                    vec![],
+                    vec![Box::new(checked_io)],
+                    options.unroll_loops.then(UnrollOptions::default),
+                );
+                (opt.structured_scope(), None)
+            }
+            false => (kernel.body, Some(checked_io)),
+        };
+
+        let mut lowering = Lowering {
+            blocks: Vec::new(),
+            const_arrays: HashMap::new(),
+            shared_memories: HashMap::new(),
+            checked_io,
+        };
+        let entry = lowering.lower_scope(&mut body)?;
+
+        Ok(Self {
+            kernel_name: kernel.options.kernel_name,
+            cube_dim: kernel.cube_dim,
+            info,
+            address_type,
+            buffer_positions,
+            ext_meta_positions,
+            const_arrays: lowering.const_arrays,
+            shared_memory_size: lowering.shared_memories.values().sum(),
+            blocks: lowering.blocks,
+            entry,
+        })
+    }
+
+    fn fmt_block(
+        &self,
+        f: &mut core::fmt::Formatter<'_>,
+        block: BlockId,
+        depth: usize,
+    ) -> core::fmt::Result {
+        let indent = "    ".repeat(depth);
+
+        for node in self.blocks[block].nodes.iter() {
+            match node {
+                Node::Instruction(instruction) => writeln!(f, "{indent}{instruction}")?,
+                Node::If { cond, then } => {
+                    writeln!(f, "{indent}if {cond} {{")?;
+                    self.fmt_block(f, *then, depth + 1)?;
+                    writeln!(f, "{indent}}}")?;
+                }
+                Node::IfElse {
+                    cond,
+                    then,
+                    or_else,
+                } => {
+                    writeln!(f, "{indent}if {cond} {{")?;
+                    self.fmt_block(f, *then, depth + 1)?;
+                    writeln!(f, "{indent}}} else {{")?;
+                    self.fmt_block(f, *or_else, depth + 1)?;
+                    writeln!(f, "{indent}}}")?;
+                }
+                Node::Switch {
+                    value,
+                    cases,
+                    default,
+                } => {
+                    writeln!(f, "{indent}switch {value} {{")?;
+                    for (case, block) in cases {
+                        writeln!(f, "{indent}    case {case}:")?;
+                        self.fmt_block(f, *block, depth + 2)?;
+                    }
+                    writeln!(f, "{indent}    default:")?;
+                    self.fmt_block(f, *default, depth + 2)?;
+                    writeln!(f, "{indent}}}")?;
+                }
+                Node::RangeLoop {
+                    i,
+                    start,
+                    end,
+                    step,
+                    inclusive,
+                    body,
+                } => {
+                    let range = if *inclusive { "..=" } else { ".." };
+                    write!(f, "{indent}for {i} in {start}{range}{end}")?;
+                    if let Some(step) = step {
+                        write!(f, " step {step}")?;
+                    }
+                    writeln!(f, " {{")?;
+                    self.fmt_block(f, *body, depth + 1)?;
+                    writeln!(f, "{indent}}}")?;
+                }
+                Node::Loop { body } => {
+                    writeln!(f, "{indent}loop {{")?;
+                    self.fmt_block(f, *body, depth + 1)?;
+                    writeln!(f, "{indent}}}")?;
+                }
+                Node::Return => writeln!(f, "{indent}return")?,
+                Node::Break => writeln!(f, "{indent}break")?,
+                Node::Continue => writeln!(f, "{indent}continue")?,
+                Node::Unreachable => writeln!(f, "{indent}unreachable")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for InterpretedKernel {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(
+            f,
+            "kernel {} ({}, {}, {}) {{",
+            self.kernel_name, self.cube_dim.x, self.cube_dim.y, self.cube_dim.z
+        )?;
+        self.fmt_block(f, self.entry, 1)?;
+        writeln!(f, "}}")
+    }
+}
+
+struct Lowering {
+    blocks: Vec<Block>,
+    const_arrays: HashMap<Id, Vec<u8>>,
+    /// Size in bytes of each shared memory used by the kernel.
+    shared_memories: HashMap<Id, usize>,
+    /// `None` if the optimizer already applied it.
+    checked_io: Option<CheckedIoProcessor>,
+}
+
+impl Lowering {
+    fn lower_scope(&mut self, scope: &mut Scope) -> Result<BlockId, CompilationError> {
+        let id = self.blocks.len();
+        self.blocks.push(Block::default());
+
+        for (var, values) in scope.const_arrays.drain(..) {
+            let mut bytes = Vec::with_capacity(values.len() * var.ty.size());
+            for value in values {
+                Value::from_constant(&value).encode(var.ty, &mut bytes);
+            }
+            self.const_arrays.insert(var.index().unwrap(), bytes);
+        }
+
+        let processors = self.checked_io.iter().map(|it| it as &dyn Processor);
+        let processing = scope.process(processors);
+
+        let mut nodes = Vec::with_capacity(processing.instructions.len());
+        for instruction in processing.instructions {
+            if let Some(node) = self.lower_instruction(instruction)? {
+                nodes.push(node);
+            }
+        }
+        self.blocks[id].nodes = nodes;
+
+        Ok(id)
+    }
+
+    fn lower_instruction(
+        &mut self,
+        instruction: Instruction,
+    ) -> Result<Option<Node>, CompilationError> {
+        self.register_shared_memories(&instruction);
+
+        let node = match instruction.operation {
+            Operation::Branch(branch) => match branch {
+                Branch::If(mut op) => Node::If {
+                    cond: op.cond,
+                    then: self.lower_scope(&mut op.scope)?,
+                },
+                Branch::IfElse(mut op) => Node::IfElse {
+                    cond: op.cond,
+                    then: self.lower_scope(&mut op.scope_if)?,
+                    or_else: self.lower_scope(&mut op.scope_else)?,
+                },
+                Branch::Switch(mut op) => {
+                    let mut cases = Vec::with_capacity(op.cases.len());
+                    for (value, scope) in op.cases.iter_mut() {
+                        cases.push((*value, self.lower_scope(scope)?));
+                    }
+                    Node::Switch {
+                        value: op.value,
+                        cases,
+                        default: self.lower_scope(&mut op.scope_default)?,
+                    }
+                }
+                Branch::RangeLoop(mut op) => Node::RangeLoop {
+                    i: op.i,
+                    start: op.start,
+                    end: op.end,
+                    step: op.step,
+                    inclusive: op.inclusive,
+                    body: self.lower_scope(&mut op.scope)?,
+                },
+                Branch::Loop(mut op) => Node::Loop {
+                    body: self.lower_scope(&mut op.scope)?,
+                },
+                Branch::Return => Node::Return,
+                Branch::Break => Node::Break,
+                Branch::Continue => Node::Continue,
+                Branch::Unreachable => Node::Unreachable,
+            },
+            Operation::Marker(_) => return Ok(None),
+            Operation::NonSemantic(NonSemantic::Print { .. }) => Node::Instruction(instruction),
+            Operation::NonSemantic(_) => return Ok(None),
+            Operation::CoopMma(_) | Operation::Barrier(_) | Operation::Tma(_) => {
+                return Err(CompilationError::UnsupportedInstruction {
+                    reason: format!("Can't interpret instruction {instruction}"),
+                    backtrace: BackTrace::capture(),
+                });
+            }
+            _ => Node::Instruction(instruction),
+        };
+
+        Ok(Some(node))
+    }
+
+    fn register_shared_memories(&mut self, instruction: &Instruction) {
+        let args = instruction.operation.args().unwrap_or_default();
+
+        for var in instruction.out.iter().chain(args.iter()) {
+            match var.kind {
+                VariableKind::SharedArray {
+                    id,
+                    length,
+                    unroll_factor,
+                    ..
+                } => {
+                    let size = length * unroll_factor * var.ty.size();
+                    self.shared_memories.insert(id, size);
+                }
+                VariableKind::Shared { id } => {
+                    self.shared_memories.insert(id, var.ty.size());
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn build_metadata(kernel: &KernelDefinition) -> (Metadata, Vec<u32>) {
+    let mut num_ext = 0;
+
+    let mut all_meta: Vec<_> = kernel
+        .buffers
+        .iter()
+        .chain(kernel.tensor_maps.iter())
+        .map(|buf| (buf.id, buf.has_extended_meta))
+        .collect();
+
+    all_meta.sort_by_key(|(id, _)| *id);
+
+    let mut ext_meta_positions = Vec::with_capacity(all_meta.len());
+    for (_, has_extended_meta) in &all_meta {
+        ext_meta_positions.push(num_ext);
+        if *has_extended_meta {
+            num_ext += 1;
+        }
+    }
+
+    let num_meta = all_meta.len();
+
+    (Metadata::new(num_meta as u32, num_ext), ext_meta_positions)
+}
@@ -0,0 +1,120 @@
+pub mod kernel;
+
+use cubecl_common::backtrace::BackTrace;
+use cubecl_core::{
+    Compiler,
+    ir::{
+        AddressType, DeviceProperties, ElemType, FloatKind, IntKind, StorageType, Type, UIntKind,
+        features::{AtomicUsage, TypeUsage},
+    },
+    prelude::KernelDefinition,
+    server::ExecutionMode,
+};
+use cubecl_runtime::compiler::CompilationError;
+
+pub use kernel::InterpretedKernel;
+
+/// Lowers kernel definitions into a form that can be directly executed by the interpreter.
+#[derive(Clone, Debug, Default)]
+pub struct InterpreterCompiler {}
+
+#[derive(Default, Debug, Clone, Copy)]
+pub struct InterpreterCompilerOptions {
+    /// Interpret the output of the full optimizer instead of the unoptimized IR.
+    pub optimize: bool,
+    /// Partially unroll the loops with a trip count known at compile time when optimizing.
+    pub unroll_loops: bool,
+}
+
+impl Compiler for InterpreterCompiler {
+    type Representation = InterpretedKernel;
+
+    type CompilationOptions = InterpreterCompilerOptions;
+
+    fn compile(
+        &mut self,
+        mut kernel: KernelDefinition,
+        compilation_options: &Self::CompilationOptions,
+        mode: ExecutionMode,
+        addr_type: StorageType,
+    ) -> Result<Self::Representation, CompilationError> {
+        let errors = kernel.body.pop_errors();
+        if !errors.is_empty() {
+            let mut reason = "Can't compile interpreted kernel\nCaused by:\n  ".to_string();
+            for error in errors {
+                reason += error.as_str();
+                reason += "\n";
+            }
+
+            return Err(CompilationError::Validation {
+                reason,
+                backtrace: BackTrace::capture(),
+            });
+        }
+
+        InterpretedKernel::lower(kernel, compilation_options, mode, addr_type)
+    }
+
+    fn elem_size(&self, elem: ElemType) -> usize {
+        elem.size()
+    }
+
+    fn extension(&self) -> &'static str {
+        "ir"
+    }
+}
+
+pub fn register_supported_types(props: &mut DeviceProperties) {
+    props.register_address_type(AddressType::U32);
+    props.register_address_type(AddressType::U64);
+
+    let supported_types = [
+        ElemType::UInt(UIntKind::U8),
+        ElemType::UInt(UIntKind::U16),
+        ElemType::UInt(UIntKind::U32),
+        ElemType::UInt(UIntKind::U64),
+        ElemType::Int(IntKind::I8),
+        ElemType::Int(IntKind::I16),
+        ElemType::Int(IntKind::I32),
+        ElemType::Int(IntKind::I64),
+        ElemType::Float(FloatKind::BF16),
+        ElemType::Float(FloatKind::F16),
+        ElemType::Float(FloatKind::F32),
+        ElemType::Float(FloatKind::Flex32),
+        ElemType::Float(FloatKind::F64),
+        ElemType::Bool,
+    ];
+
+    for ty in supported_types {
+        props.register_type_usage(ty, TypeUsage::all());
+    }
+
+    // Minifloats are only converted from/to `f32`, so they can be stored and cast like on GPUs
+    // without native arithmetic.
+    let minifloats = [
+        ElemType::Float(FloatKind::E4M3),
+        ElemType::Float(FloatKind::E5M2),
+        ElemType::Float(FloatKind::UE8M0),
+    ];
+
+    for ty in minifloats {
+        props.register_type_usage(ty, TypeUsage::Conversion | TypeUsage::Buffer);
+    }
+
+    // Units never run concurrently, so every atomic operation is trivially atomic.
+    let atomic_types = [
+        ElemType::Int(IntKind::I32),
+        ElemType::Int(IntKind::I64),
+        ElemType::UInt(UIntKind::U32),
+        ElemType::UInt(UIntKind::U64),
+        ElemType::Float(FloatKind::F16),
+        ElemType::Float(FloatKind::BF16),
+        ElemType::Float(FloatKind::F32),
+        ElemType::Float(FloatKind::F64),
+    ];
+
+    for ty in atomic_types {
+        props.register_type_usage(StorageType::Atomic(ty), TypeUsage::all());
+        props.register_atomic_type_usage(Type::new(StorageType::Atomic(ty)), AtomicUsage::all());
+    }
+}
@@ -0,0 +1,1015 @@
+//! Execution of interpreted kernels.
+//!
+//! Cubes are executed one after the other. Inside a cube, every unit keeps its own registers and
+//! stack of blocks, and runs until it finishes or reaches an instruction that involves other
+//! units. Plane instructions are then executed collectively by the units of a plane that reached
+//! them, and cube barriers are released once every remaining unit is waiting on one. This gives
+//! the same observable behavior as a device, as long as the kernel is free of data races.
+
+use cubecl_common::printf::format_printf;
+use cubecl_core::{
+    CubeDim,
+    ir::{
+        AtomicOp, Builtin, Id, Instruction, Metadata, NonSemantic, Operation, Operator, Plane,
+        StorageType, Synchronization, Type, Variable, VariableKind,
+    },
+};
+use hashbrown::HashMap;
+
+use crate::compiler::kernel::{BlockId, InterpretedKernel, Node};
+
+use super::{
+    ops::{self, elem_of, numeric2},
+    plane::{self, Participant},
+    value::{Pointer, Region, Scalar, Value, value_elem},
+};
+
+/// A global buffer bound to the kernel.
+///
+/// Buffers are accessed through raw pointers, since the same memory can be bound more than once
+/// to the same kernel.
+pub(crate) struct GlobalBuffer {
+    ptr: *mut u8,
+    len: usize,
+}
+
+impl GlobalBuffer {
+    /// # Safety
+    ///
+    /// The memory must stay valid for `len` bytes until the end of the kernel execution.
+    pub(crate) unsafe fn new(ptr: *mut u8, len: usize) -> Self {
+        Self { ptr, len }
+    }
+}
+
+pub(crate) struct KernelLaunch<'a> {
+    pub(crate) kernel: &'a InterpretedKernel,
+    pub(crate) buffers: Vec<GlobalBuffer>,
+    pub(crate) info: &'a [u8],
+    /// Offset of the dynamic metadata in bytes.
+    pub(crate) dynamic_meta_offset: usize,
+    pub(crate) cube_count: [u32; 3],
+    pub(crate) plane_size: u32,
+}
+
+struct CubeState {
+    pos: [u32; 3],
+    shared: HashMap<Id, Vec<u8>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Status {
+    Ready,
+    /// Waiting on the plane instruction at the current position.
+    PlaneWait,
+    /// Waiting on a cube barrier, the position is already after the barrier.
+    CubeWait,
+    Done,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum FrameKind {
+    Block,
+    Loop,
+    /// The location of the range loop node, to evaluate the condition at each iteration.
+    RangeLoop {
+        block: BlockId,
+        pc: usize,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Frame {
+    block: BlockId,
+    pc: usize,
+    kind: FrameKind,
+}
+
+struct UnitState {
+    pos: [u32; 3],
+    index: u32,
+    registers: HashMap<VariableKind, Value>,
+    local_arrays: HashMap<Id, Vec<u8>>,
+    frames: Vec<Frame>,
+    status: Status,
+}
+
+impl UnitState {
+    fn new(index: u32, cube_dim: CubeDim, entry: BlockId) -> Self {
+        let x = index % cube_dim.x;
+        let y = (index / cube_dim.x) % cube_dim.y;
+        let z = index / (cube_dim.x * cube_dim.y);
+
+        Self {
+            pos: [x, y, z],
+            index,
+            registers: HashMap::new(),
+            local_arrays: HashMap::new(),
+            frames: vec![Frame {
+                block: entry,
+                pc: 0,
+                kind: FrameKind::Block,
+            }],
+            status: Status::Ready,
+        }
+    }
+
+    fn location(&self) -> (BlockId, usize) {
+        let frame = self.frames.last().expect("Waiting units have a frame");
+        (frame.block, frame.pc)
+    }
+
+    fn advance(&mut self) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.pc += 1;
+        }
+    }
+}
+
+enum Step {
+    Next,
+    PlaneWait,
+    CubeWait,
+}
+
+impl KernelLaunch<'_> {
+    pub(crate) fn execute(&self) {
+        let [count_x, count_y, count_z] = self.cube_count;
+
+        for z in 0..count_z {
+            for y in 0..count_y {
+                for x in 0..count_x {
+                    self.execute_cube([x, y, z]);
+                }
+            }
+        }
+    }
+
+    fn execute_cube(&self, pos: [u32; 3]) {
+        let cube_dim = self.kernel.cube_dim;
+        let mut cube = CubeState {
+            pos,
+            shared: HashMap::new(),
+        };
+        let mut units: Vec<UnitState> = (0..cube_dim.num_elems())
+            .map(|index| UnitState::new(index, cube_dim, self.kernel.entry))
+            .collect();
+
+        loop {
+            for unit in units.iter_mut() {
+                if unit.status == Status::Ready {
+                    Executor::new(self, &mut cube, unit).run();
+                }
+            }
+
+            if units.iter().all(|unit| unit.status == Status::Done) {
+                break;
+            }
+
+            if self.execute_plane_instructions(&mut cube, &mut units) {
+                continue;
+            }
+
+            // Every unit left is waiting on a cube barrier. Finished units count as arrived, like
+            // they do on most devices.
+            for unit in units.iter_mut() {
+                if unit.status == Status::CubeWait {
+                    unit.status = Status::Ready;
+                }
+            }
+        }
+    }
+
+    /// Executes all pending plane instructions, returns whether any was executed.
+    fn execute_plane_instructions(&self, cube: &mut CubeState, units: &mut [UnitState]) -> bool {
+        let mut executed = false;
+
+        for plane in units.chunks_mut(self.plane_size as usize) {
+            while let Some(first) = plane.iter().position(|u| u.status == Status::PlaneWait) {
+                let location = plane[first].location();
+                let members: Vec<usize> = plane
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, u)| u.status == Status::PlaneWait && u.location() == location)
+                    .map(|(i, _)| i)
+                    .collect();
+
+                self.execute_plane_instruction(cube, plane, &members, location);
+
+                for i in members {
+                    plane[i].advance();
+                    plane[i].status = Status::Ready;
+                }
+                executed = true;
+            }
+        }
+
+        executed
+    }
+
+    fn execute_plane_instruction(
+        &self,
+        cube: &mut CubeState,
+        plane: &mut [UnitState],
+        members: &[usize],
+        (block, pc): (BlockId, usize),
+    ) {
+        let Node::Instruction(instruction) = &self.kernel.blocks[block].nodes[pc] else {
+            unreachable!("Units only wait on instructions");
+        };
+        let Operation::Plane(op) = &instruction.operation else {
+            // Plane barriers are only a meeting point.
+            return;
+        };
+
+        let (input, rhs) = match op {
+            Plane::Elect => (None, None),
+            Plane::All(op)
+            | Plane::Any(op)
+            | Plane::Ballot(op)
+            | Plane::Sum(op)
+            | Plane::InclusiveSum(op)
+            | Plane::ExclusiveSum(op)
+            | Plane::Prod(op)
+            | Plane::InclusiveProd(op)
+            | Plane::ExclusiveProd(op)
+            | Plane::Min(op)
+            | Plane::Max(op) => (Some(op.input), None),
+            Plane::Broadcast(op)
+            | Plane::Shuffle(op)
+            | Plane::ShuffleXor(op)
+            | Plane::ShuffleUp(op)
+            | Plane::ShuffleDown(op) => (Some(op.lhs), Some(op.rhs)),
+        };
+
+        let participants: Vec<Participant> = members
+            .iter()
+            .map(|&i| {
+                let mut executor = Executor::new(self, cube, &mut plane[i]);
+                Participant {
+                    lane: (executor.unit.index % self.plane_size) as usize,
+                    input: input.map(|var| executor.read(&var)),
+                    rhs: rhs.map(|var| executor.read(&var)),
+                }
+            })
+            .collect();
+
+        let out = instruction.out();
+        let results = plane::execute(op, &participants, out.ty, self.plane_size as usize);
+
+        for (&i, result) in members.iter().zip(results) {
+            Executor::new(self, cube, &mut plane[i]).write(&out, result);
+        }
+    }
+}
+
+struct Executor<'a, 'b> {
+    launch: &'a KernelLaunch<'a>,
+    kernel: &'a InterpretedKernel,
+    cube: &'b mut CubeState,
+    unit: &'b mut UnitState,
+}
+
+impl<'a, 'b> Executor<'a, 'b> {
+    fn new(launch: &'a KernelLaunch<'a>, cube: &'b mut CubeState, unit: &'b mut UnitState) -> Self {
+        Self {
+            launch,
+            kernel: launch.kernel,
+            cube,
+            unit,
+        }
+    }
+
+    /// Runs the unit until it finishes or needs to wait on other units.
+    fn run(&mut self) {
+        let kernel = self.kernel;
+
+        while let Some(frame) = self.unit.frames.last() {
+            let (block, pc) = (frame.block, frame.pc);
+            let nodes = &kernel.blocks[block].nodes;
+
+            let Some(node) = nodes.get(pc) else {
+                self.end_block();
+                continue;
+            };
+
+            match node {
+                Node::Instruction(instruction) => match self.instruction(instruction) {
+                    Step::Next => self.unit.advance(),
+                    Step::PlaneWait => {
+                        self.unit.status = Status::PlaneWait;
+                        return;
+                    }
+                    Step::CubeWait => {
+                        self.unit.advance();
+                        self.unit.status = Status::CubeWait;
+                        return;
+                    }
+                },
+                Node::If { cond, then } => {
+                    self.unit.advance();
+                    if self.read(cond).first().as_bool() {
+                        self.push(*then, FrameKind::Block);
+                    }
+                }
+                Node::IfElse {
+                    cond,
+                    then,
+                    or_else,
+                } => {
+                    self.unit.advance();
+                    match self.read(cond).first().as_bool() {
+                        true => self.push(*then, FrameKind::Block),
+                        false => self.push(*or_else, FrameKind::Block),
+                    }
+                }
+                Node::Switch {
+                    value,
+                    cases,
+                    default,
+                } => {
+                    self.unit.advance();
+                    let elem = elem_of(value.ty);
+                    let value = self.read(value).first().cast(elem);
+                    let target = cases
+                        .iter()
+                        .find(|(case, _)| Value::from_constant(case).first().cast(elem) == value)
+                        .map(|(_, block)| *block)
+                        .unwrap_or(*default);
+                    self.push(target, FrameKind::Block);
+                }
+                Node::RangeLoop {
+                    i,
+                    start,
+                    end,
+                    inclusive,
+                    body,
+                    ..
+                } => {
+                    self.unit.advance();
+                    let start = self.read(start);
+                    self.write(i, start);
+                    if self.range_condition(i, end, *inclusive) {
+                        self.push(*body, FrameKind::RangeLoop { block, pc });
+                    }
+                }
+                Node::Loop { body } => {
+                    self.unit.advance();
+                    self.push(*body, FrameKind::Loop);
+                }
+                Node::Return | Node::Unreachable => self.unit.frames.clear(),
+                Node::Break => {
+                    while let Some(frame) = self.unit.frames.pop() {
+                        if matches!(frame.kind, FrameKind::Loop | FrameKind::RangeLoop { .. }) {
+                            break;
+                        }
+                    }
+                }
+                Node::Continue => {
+                    while let Some(frame) = self.unit.frames.last() {
+                        if matches!(frame.kind, FrameKind::Loop | FrameKind::RangeLoop { .. }) {
+                            break;
+                        }
+                        self.unit.frames.pop();
+                    }
+                    self.end_block();
+                }
+            }
+        }
+
+        self.unit.status = Status::Done;
+    }
+
+    fn push(&mut self, block: BlockId, kind: FrameKind) {
+        self.unit.frames.push(Frame { block, pc: 0, kind });
+    }
+
+    fn end_block(&mut self) {
+        let frame = *self.unit.frames.last().unwrap();
+
+        match frame.kind {
+            FrameKind::Block => {
+                self.unit.frames.pop();
+            }
+            FrameKind::Loop => self.unit.frames.last_mut().unwrap().pc = 0,
+            FrameKind::RangeLoop { block, pc } => {
+                let Node::RangeLoop {
+                    i,
+                    end,
+                    step,
+                    inclusive,
+                    ..
+                } = &self.kernel.blocks[block].nodes[pc]
+                else {
+                    unreachable!("Range loop frames point to a range loop");
+                };
+
+                let elem = elem_of(i.ty);
+                let current = self.read(i).first().cast(elem);
+                let step = match step {
+                    Some(step) => self.read(step).first().cast(elem),
+                    None => Scalar::UInt(1).cast(elem),
+                };
+                let next = numeric2(
+                    current,
+                    step,
+                    |a, b| a + b,
+                    i64::wrapping_add,
+                    u64::wrapping_add,
+                );
+                self.write(i, Value::scalar(next));
+
+                match self.range_condition(i, end, *inclusive) {
+                    true => self.unit.frames.last_mut().unwrap().pc = 0,
+                    false => {
+                        self.unit.frames.pop();
+                    }
+                }
+            }
+        }
+    }
+
+    fn range_condition(&mut self, i: &Variable, end: &Variable, inclusive: bool) -> bool {
+        let elem = elem_of(i.ty);
+        let i = self.read(i).first().cast(elem);
+        let end = self.read(end).first().cast(elem);
+
+        match inclusive {
+            true => numeric_le(i, end),
+            false => !numeric_le(end, i),
+        }
+    }
+
+    fn instruction(&mut self, instruction: &Instruction) -> Step {
+        let out = instruction.out;
+
+        match &instruction.operation {
+            Operation::Copy(input) => {
+                let value = self.read(input);
+                self.write(&out.unwrap(), value);
+            }
+            Operation::Arithmetic(op) => {
+                let out = out.unwrap();
+                let value = ops::arithmetic(op, out.ty, &mut |var| self.read(var));
+                self.write(&out, value);
+            }
+            Operation::Comparison(op) => {
+                let out = out.unwrap();
+                let value = ops::comparison(op, out.ty, &mut |var| self.read(var));
+                self.write(&out, value);
+            }
+            Operation::Bitwise(op) => {
+                let out = out.unwrap();
+                let value = ops::bitwise(op, out.ty, &mut |var| self.read(var));
+                self.write(&out, value);
+            }
+            Operation::Operator(op) => self.operator(op, out.unwrap()),
+            Operation::Atomic(op) => self.atomic(op, out.unwrap()),
+            Operation::Metadata(op) => {
+                let out = out.unwrap();
+                let value = self.metadata(op);
+                self.write(&out, Value::scalar(Scalar::UInt(value)));
+            }
+            Operation::Synchronization(sync) => match sync {
+                Synchronization::SyncCube | Synchronization::SyncStorage => return Step::CubeWait,
+                Synchronization::SyncPlane => return Step::PlaneWait,
+                Synchronization::SyncAsyncProxyShared => {}
+            },
+            Operation::Plane(_) => return Step::PlaneWait,
+            Operation::NonSemantic(NonSemantic::Print {
+                format_string,
+                args,
+            }) => {
+                // Vector arguments are flattened, so each lane consumes one conversion.
+                let args = args
+                    .iter()
+                    .flat_map(|arg| self.read(arg).lanes().to_vec())
+                    .map(|lane| vec![lane.print_value()])
+                    .collect::<Vec<_>>();
+                print!("{}", format_printf(format_string, &args));
+            }
+            Operation::NonSemantic(_) | Operation::Marker(_) => {}
+            Operation::Branch(_)
+            | Operation::CoopMma(_)
+            | Operation::Barrier(_)
+            | Operation::Tma(_) => unreachable!("Rejected or lowered during compilation"),
+        }
+
+        Step::Next
+    }
+
+    fn operator(&mut self, op: &Operator, out: Variable) {
+        match op {
+            Operator::Index(op) | Operator::UncheckedIndex(op) => {
+                let value = self.index(&op.list, &op.index, op.vector_size, out.ty);
+                self.write(&out, value);
+            }
+            Operator::IndexAssign(op) | Operator::UncheckedIndexAssign(op) => {
+                let index = self.read(&op.index).first().as_usize();
+                let value = self.read(&op.value);
+
+                match self.memory(&out) {
+                    Some(region) => {
+                        let item = item_type(out.ty, op.vector_size);
+                        self.store(region, index * item.size(), item, value);
+                    }
+                    None => {
+                        let mut current = self.read(&out);
+                        if let Value::Lanes(lanes) = &mut current
+                            && index < lanes.len()
+                        {
+                            lanes[index] = value.first().cast(elem_of(out.ty));
+                        }
+                        self.write(&out, current);
+                    }
+                }
+            }
+            Operator::CopyMemory(op) => {
+                let in_index = self.read(&op.in_index).first().as_usize();
+                let out_index = self.read(&op.out_index).first().as_usize();
+                self.copy_memory(&op.input, in_index, &out, out_index);
+            }
+            Operator::CopyMemoryBulk(op) => {
+                let in_index = self.read(&op.in_index).first().as_usize();
+                let out_index = self.read(&op.out_index).first().as_usize();
+                for i in 0..op.len {
+                    self.copy_memory(&op.input, in_index + i, &out, out_index + i);
+                }
+            }
+            Operator::InitVector(op) => {
+                let lanes = op
+                    .inputs
+                    .iter()
+                    .map(|input| self.read(input).first())
+                    .collect();
+                self.write(&out, Value::Lanes(lanes));
+            }
+            Operator::And(op) => {
+                let value = self.logical(&op.lhs, &op.rhs, out.ty, |a, b| a && b);
+                self.write(&out, value);
+            }
+            Operator::Or(op) => {
+                let value = self.logical(&op.lhs, &op.rhs, out.ty, |a, b| a || b);
+                self.write(&out, value);
+            }
+            Operator::Not(op) => {
+                let input = self.read(&op.input);
+                let lanes = input
+                    .lanes()
+                    .iter()
+                    .map(|lane| Scalar::Bool(!lane.as_bool()))
+                    .collect();
+                self.write(&out, Value::Lanes(lanes));
+            }
+            Operator::Cast(op) => {
+                let value = self.read(&op.input).cast(out.ty);
+                self.write(&out, value);
+            }
+            Operator::Reinterpret(op) => {
+                let mut bytes = Vec::new();
+                self.read(&op.input).encode(op.input.ty, &mut bytes);
+                bytes.resize(out.ty.size(), 0);
+                self.write(&out, Value::decode(out.ty, &bytes));
+            }
+            Operator::Select(op) => {
+                let cond = self.read(&op.cond);
+                let then = self.read(&op.then);
+                let or_else = self.read(&op.or_else);
+                let lanes = (0..out.ty.vector_size().max(1))
+                    .map(|i| match cond.lane(i).as_bool() {
+                        true => then.lane(i),
+                        false => or_else.lane(i),
+                    })
+                    .collect();
+                self.write(&out, Value::Lanes(lanes));
+            }
+        }
+    }
+
+    fn logical(
+        &mut self,
+        lhs: &Variable,
+        rhs: &Variable,
+        out: Type,
+        f: impl Fn(bool, bool) -> bool,
+    ) -> Value {
+        let lhs = self.read(lhs);
+        let rhs = self.read(rhs);
+        let lanes = (0..out.vector_size().max(1))
+            .map(|i| Scalar::Bool(f(lhs.lane(i).as_bool(), rhs.lane(i).as_bool())))
+            .collect();
+        Value::Lanes(lanes)
+    }
+
+    fn index(&mut self, list: &Variable, index: &Variable, vector_size: usize, out: Type) -> Value {
+        let index = self.read(index).first().as_usize();
+
+        match self.memory(list) {
+            Some(region) => {
+                let item = item_type(list.ty, vector_size);
+                let offset = index * item.size();
+
+                match out.is_atomic() {
+                    true => Value::Pointer(Pointer { region, offset }),
+                    false => self.load(region, offset, item).cast(out),
+                }
+            }
+            None => {
+                let value = self.read(list);
+                let lane = value
+                    .lanes()
+                    .get(index)
+                    .copied()
+                    .unwrap_or(Scalar::zero(elem_of(out)));
+                Value::scalar(lane).cast(out)
+            }
+        }
+    }
+
+    fn copy_memory(&mut self, input: &Variable, in_index: usize, out: &Variable, out_index: usize) {
+        let (Some(input_region), Some(out_region)) = (self.memory(input), self.memory(out)) else {
+            unreachable!("Memory copies are only done between arrays");
+        };
+
+        let value = self.load(input_region, in_index * input.ty.size(), input.ty);
+        self.store(out_region, out_index * out.ty.size(), out.ty, value);
+    }
+
+    fn atomic(&mut self, op: &AtomicOp, out: Variable) {
+        match op {
+            AtomicOp::Load(op) => {
+                let pointer = self.read(&op.input).pointer();
+                let value = self.load_atomic(pointer, op.input.ty);
+                self.write(&out, Value::scalar(value));
+            }
+            AtomicOp::Store(op) => {
+                let pointer = self.read(&out).pointer();
+                let value = self.read(&op.input);
+                self.store(
+                    pointer.region,
+                    pointer.offset,
+                    atomic_value_type(out.ty),
+                    value,
+                );
+            }
+            AtomicOp::CompareAndSwap(op) => {
+                let pointer = self.read(&op.input).pointer();
+                let ty = atomic_value_type(op.input.ty);
+                let elem = elem_of(ty);
+                let old = self.load_atomic(pointer, op.input.ty);
+                let cmp = self.read(&op.cmp).first().cast(elem);
+
+                if old == cmp {
+                    let val = self.read(&op.val);
+                    self.store(pointer.region, pointer.offset, ty, val);
+                }
+                self.write(&out, Value::scalar(old));
+            }
+            AtomicOp::Swap(op) => self.atomic_rmw(op.lhs, op.rhs, out, |_, val| val),
+            AtomicOp::Add(op) => self.atomic_rmw(op.lhs, op.rhs, out, |a, b| {
+                numeric2(a, b, |a, b| a + b, i64::wrapping_add, u64::wrapping_add)
+            }),
+            AtomicOp::Sub(op) => self.atomic_rmw(op.lhs, op.rhs, out, |a, b| {
+                numeric2(a, b, |a, b| a - b, i64::wrapping_sub, u64::wrapping_sub)
+            }),
+            AtomicOp::Max(op) => self.atomic_rmw(op.lhs, op.rhs, out, |a, b| {
+                numeric2(a, b, f64::max, i64::max, u64::max)
+            }),
+            AtomicOp::Min(op) => self.atomic_rmw(op.lhs, op.rhs, out, |a, b| {
+                numeric2(a, b, f64::min, i64::min, u64::min)
+            }),
+            AtomicOp::And(op) => self.atomic_rmw(op.lhs, op.rhs, out, |a, b| {
+                numeric2(a, b, |a, _| a, |a, b| a & b, |a, b| a & b)
+            }),
+            AtomicOp::Or(op) => self.atomic_rmw(op.lhs, op.rhs, out, |a, b| {
+                numeric2(a, b, |a, _| a, |a, b| a | b, |a, b| a | b)
+            }),
+            AtomicOp::Xor(op) => self.atomic_rmw(op.lhs, op.rhs, out, |a, b| {
+                numeric2(a, b, |a, _| a, |a, b| a ^ b, |a, b| a ^ b)
+            }),
+        }
+    }
+
+    fn atomic_rmw(
+        &mut self,
+        ptr: Variable,
+        val: Variable,
+        out: Variable,
+        f: impl Fn(Scalar, Scalar) -> Scalar,
+    ) {
+        let pointer = self.read(&ptr).pointer();
+        let ty = atomic_value_type(ptr.ty);
+        let old = self.load_atomic(pointer, ptr.ty);
+        let val = self.read(&val).first().cast(elem_of(ty));
+        let new = f(old, val);
+
+        self.store(pointer.region, pointer.offset, ty, Value::scalar(new));
+        self.write(&out, Value::scalar(old));
+    }
+
+    fn load_atomic(&mut self, pointer: Pointer, ty: Type) -> Scalar {
+        self.load(pointer.region, pointer.offset, atomic_value_type(ty))
+            .first()
+    }
+
+    fn metadata(&mut self, op: &Metadata) -> u64 {
+        let metadata = &self.kernel.info.metadata;
+
+        match op {
+            Metadata::Length { var } => match var.kind {
+                VariableKind::SharedArray {
+                    length,
+                    unroll_factor,
+                    ..
+                }
+                | VariableKind::LocalArray {
+                    length,
+                    unroll_factor,
+                    ..
+                }
+                | VariableKind::ConstantArray {
+                    length,
+                    unroll_factor,
+                    ..
+                } => (length * unroll_factor) as u64,
+                _ => self.static_meta(metadata.len_index(global_id(var))),
+            },
+            Metadata::BufferLength { var } => {
+                self.static_meta(metadata.buffer_len_index(global_id(var)))
+            }
+            Metadata::Rank { var } => {
+                let position = self.ext_meta_position(var);
+                self.static_meta(metadata.rank_index(position))
+            }
+            Metadata::Shape { dim, var } => {
+                let position = self.ext_meta_position(var);
+                let dim = self.read(dim).first().as_u64();
+                self.dynamic_meta(metadata.shape_offset_index(position), dim)
+            }
+            Metadata::Stride { dim, var } => {
+                let position = self.ext_meta_position(var);
+                let dim = self.read(dim).first().as_u64();
+                self.dynamic_meta(metadata.stride_offset_index(position), dim)
+            }
+        }
+    }
+
+    fn ext_meta_position(&self, var: &Variable) -> u32 {
+        self.kernel.ext_meta_positions[global_id(var) as usize]
+    }
+
+    fn static_meta(&self, index: u32) -> u64 {
+        let field = self
+            .kernel
+            .info
+            .sized_meta
+            .expect("Kernels using metadata have static metadata");
+        let offset = field.offset + index as usize * field.ty.size();
+        self.info_value(field.ty, offset).as_u64()
+    }
+
+    fn dynamic_meta(&self, offset_index: u32, dim: u64) -> u64 {
+        let address = self.kernel.address_type;
+        let start = self.static_meta(offset_index);
+        let offset = self.launch.dynamic_meta_offset + (start + dim) as usize * address.size();
+        self.info_value(address, offset).as_u64()
+    }
+
+    fn info_value(&self, ty: StorageType, offset: usize) -> Scalar {
+        match self.launch.info.get(offset..offset + ty.size()) {
+            Some(bytes) => Scalar::decode(value_elem(ty), bytes),
+            None => Scalar::zero(value_elem(ty)),
+        }
+    }
+
+    fn read(&mut self, var: &Variable) -> Value {
+        match var.kind {
+            VariableKind::Constant(_) => Value::from_constant(var),
+            VariableKind::LocalMut { .. }
+            | VariableKind::LocalConst { .. }
+            | VariableKind::Versioned { .. } => self
+                .unit
+                .registers
+                .get(&var.kind)
+                .cloned()
+                .unwrap_or_else(|| Value::zero(var.ty)),
+            VariableKind::Builtin(builtin) => {
+                Value::scalar(Scalar::UInt(self.builtin(builtin))).cast(var.ty)
+            }
+            VariableKind::GlobalScalar(id) => {
+                let storage = var.storage_type();
+                let field = self
+                    .kernel
+                    .info
+                    .scalars
+                    .iter()
+                    .find(|field| field.ty == storage)
+                    .expect("Scalar should be registered");
+                let offset = field.offset + id as usize * storage.size();
+                Value::scalar(self.info_value(storage, offset)).cast(var.ty)
+            }
+            VariableKind::Shared { .. } => {
+                let region = self.memory(var).unwrap();
+                match var.ty.is_atomic() {
+                    true => Value::Pointer(Pointer { region, offset: 0 }),
+                    false => self.load(region, 0, var.ty),
+                }
+            }
+            _ => match self.memory(var) {
+                Some(region) => Value::Pointer(Pointer { region, offset: 0 }),
+                None => unreachable!("Can't read variable {var}"),
+            },
+        }
+    }
+
+    fn write(&mut self, var: &Variable, value: Value) {
+        match var.kind {
+            VariableKind::LocalMut { .. }
+            | VariableKind::LocalConst { .. }
+            | VariableKind::Versioned { .. } => {
+                self.unit.registers.insert(var.kind, value.cast(var.ty));
+            }
+            VariableKind::Shared { .. } => {
+                let region = self.memory(var).unwrap();
+                self.store(region, 0, var.ty, value);
+            }
+            _ => unreachable!("Can't write to variable {var}"),
+        }
+    }
+
+    fn builtin(&self, builtin: Builtin) -> u64 {
+        let [unit_x, unit_y, unit_z] = self.unit.pos.map(u64::from);
+        let [cube_x, cube_y, cube_z] = self.cube.pos.map(u64::from);
+        let [count_x, count_y, count_z] = self.launch.cube_count.map(u64::from);
+        let dim = self.kernel.cube_dim;
+        let [dim_x, dim_y, dim_z] = [dim.x, dim.y, dim.z].map(u64::from);
+        let plane_size = self.launch.plane_size as u64;
+
+        let absolute_x = cube_x * dim_x + unit_x;
+        let absolute_y = cube_y * dim_y + unit_y;
+        let absolute_z = cube_z * dim_z + unit_z;
+
+        match builtin {
+            Builtin::UnitPos => self.unit.index as u64,
+            Builtin::UnitPosX => unit_x,
+            Builtin::UnitPosY => unit_y,
+            Builtin::UnitPosZ => unit_z,
+            Builtin::CubePosCluster
+            | Builtin::CubePosClusterX
+            | Builtin::CubePosClusterY
+            | Builtin::CubePosClusterZ => 0,
+            Builtin::CubePos => cube_x + cube_y * count_x + cube_z * count_x * count_y,
+            Builtin::CubePosX => cube_x,
+            Builtin::CubePosY => cube_y,
+            Builtin::CubePosZ => cube_z,
+            Builtin::CubeDim => dim_x * dim_y * dim_z,
+            Builtin::CubeDimX => dim_x,
+            Builtin::CubeDimY => dim_y,
+            Builtin::CubeDimZ => dim_z,
+            Builtin::CubeClusterDim
+            | Builtin::CubeClusterDimX
+            | Builtin::CubeClusterDimY
+            | Builtin::CubeClusterDimZ => 1,
+            Builtin::CubeCount => count_x * count_y * count_z,
+            Builtin::CubeCountX => count_x,
+            Builtin::CubeCountY => count_y,
+            Builtin::CubeCountZ => count_z,
+            Builtin::PlaneDim => plane_size,
+            Builtin::PlanePos => self.unit.index as u64 / plane_size,
+            Builtin::UnitPosPlane => self.unit.index as u64 % plane_size,
+            Builtin::AbsolutePos => {
+                let size_x = count_x * dim_x;
+                let size_y = count_y * dim_y;
+                absolute_z * size_x * size_y + absolute_y * size_x + absolute_x
+            }
+            Builtin::AbsolutePosX => absolute_x,
+            Builtin::AbsolutePosY => absolute_y,
+            Builtin::AbsolutePosZ => absolute_z,
+        }
+    }
+
+    /// The memory region of an array, allocating shared and local arrays on first use.
+    fn memory(&mut self, var: &Variable) -> Option<Region> {
+        let region = match var.kind {
+            VariableKind::GlobalInputArray(id) | VariableKind::GlobalOutputArray(id) => {
+                Region::Global(self.kernel.buffer_positions[&id])
+            }
+            VariableKind::ConstantArray { id, .. } => Region::Const(id),
+            VariableKind::SharedArray {
+                id,
+                length,
+                unroll_factor,
+                ..
+            } => {
+                let size = length * unroll_factor * var.ty.size();
+                self.cube.shared.entry(id).or_insert_with(|| vec![0; size]);
+                Region::Shared(id)
+            }
+            VariableKind::Shared { id } => {
+                let size = var.ty.size();
+                self.cube.shared.entry(id).or_insert_with(|| vec![0; size]);
+                Region::Shared(id)
+            }
+            VariableKind::LocalArray {
+                id,
+                length,
+                unroll_factor,
+            } => {
+                let size = length * unroll_factor * var.ty.size();
+                self.unit
+                    .local_arrays
+                    .entry(id)
+                    .or_insert_with(|| vec![0; size]);
+                Region::Local(id)
+            }
+            _ => return None,
+        };
+
+        Some(region)
+    }
+
+    /// Loads a value, reading out of bounds returns zero.
+    fn load(&self, region: Region, offset: usize, ty: Type) -> Value {
+        let size = ty.size();
+        let bytes = match region {
+            Region::Global(pos) => {
+                let buffer = &self.launch.buffers[pos];
+                if offset + size > buffer.len {
+                    return Value::zero(ty);
+                }
+                // SAFETY: The range is within the buffer, which outlives the launch.
+                unsafe { core::slice::from_raw_parts(buffer.ptr.add(offset), size) }
+            }
+            Region::Shared(id) => match self.cube.shared[&id].get(offset..offset + size) {
+                Some(bytes) => bytes,
+                None => return Value::zero(ty),
+            },
+            Region::Local(id) => match self.unit.local_arrays[&id].get(offset..offset + size) {
+                Some(bytes) => bytes,
+                None => return Value::zero(ty),
+            },
+            Region::Const(id) => match self.kernel.const_arrays[&id].get(offset..offset + size) {
+                Some(bytes) => bytes,
+                None => return Value::zero(ty),
+            },
+        };
+
+        Value::decode(ty, bytes)
+    }
+
+    /// Stores a value, writing out of bounds is ignored.
+    fn store(&mut self, region: Region, offset: usize, ty: Type, value: Value) {
+        let mut bytes = Vec::with_capacity(ty.size());
+        value.encode(ty, &mut bytes);
+        let size = bytes.len();
+
+        let memory = match region {
+            Region::Global(pos) => {
+                let buffer = &self.launch.buffers[pos];
+                if offset + size <= buffer.len {
+                    // SAFETY: The range is within the buffer, which outlives the launch.
+                    unsafe {
+                        core::ptr::copy_nonoverlapping(
+                            bytes.as_ptr(),
+                            buffer.ptr.add(offset),
+                            size,
+                        );
+                    }
+                }
+                return;
+            }
+            Region::Shared(id) => self.cube.shared.get_mut(&id).unwrap(),
+            Region::Local(id) => self.unit.local_arrays.get_mut(&id).unwrap(),
+            Region::Const(_) => return,
+        };
+
+        if let Some(dest) = memory.get_mut(offset..offset + size) {
+            dest.copy_from_slice(&bytes);
+        }
+    }
+}
+
+/// The type of the items of an array, when indexed with the given vector size.
+fn item_type(list: Type, vector_size: usize) -> Type {
+    match vector_size {
+        0 => list,
+        vector_size => list.with_vector_size(vector_size),
+    }
+}
+
+/// Atomics are stored like their underlying type.
+fn atomic_value_type(ty: Type) -> Type {
+    Type::scalar(value_elem(ty.storage_type()))
+}
+
+fn global_id(var: &Variable) -> u32 {
+    var.index().expect("Variable should have an id")
+}
+
+fn numeric_le(lhs: Scalar, rhs: Scalar) -> bool {
+    match (lhs, rhs) {
+        (Scalar::Int(a), Scalar::Int(b)) => a <= b,
+        (Scalar::UInt(a), Scalar::UInt(b)) => a <= b,
+        (a, b) => a.as_f64() <= b.as_f64(),
+    }
+}
@@ -0,0 +1,6 @@
+pub mod server;
+
+pub(crate) mod interpreter;
+pub(crate) mod ops;
+pub(crate) mod plane;
+pub(crate) mod value;
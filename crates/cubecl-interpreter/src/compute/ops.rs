@@ -0,0 +1,494 @@
+//! Lane-wise evaluation of the pure operations.
+//!
+//! Operands are first converted to the type the operation is computed in, which is the output
+//! type for arithmetic and the input type for comparisons and bit counting. Results are then
+//! converted to the output type, which takes care of wrapping and rounding.
+
+use cubecl_core::ir::{
+    Arithmetic, Bitwise, Comparison, ElemType, IntKind, Type, UIntKind, Variable,
+};
+
+use super::value::{Scalar, Value, value_elem};
+
+pub(crate) fn elem_of(ty: Type) -> ElemType {
+    value_elem(ty.storage_type())
+}
+
+fn map1(input: &Value, elem: ElemType, out: Type, f: impl Fn(Scalar) -> Scalar) -> Value {
+    let lanes = (0..out.vector_size().max(1))
+        .map(|i| f(input.lane(i).cast(elem)))
+        .collect();
+    Value::Lanes(lanes).cast(out)
+}
+
+fn map2(
+    lhs: &Value,
+    rhs: &Value,
+    elem: ElemType,
+    out: Type,
+    f: impl Fn(Scalar, Scalar) -> Scalar,
+) -> Value {
+    let lanes = (0..out.vector_size().max(1))
+        .map(|i| f(lhs.lane(i).cast(elem), rhs.lane(i).cast(elem)))
+        .collect();
+    Value::Lanes(lanes).cast(out)
+}
+
+fn map3(
+    a: &Value,
+    b: &Value,
+    c: &Value,
+    elem: ElemType,
+    out: Type,
+    f: impl Fn(Scalar, Scalar, Scalar) -> Scalar,
+) -> Value {
+    let lanes = (0..out.vector_size().max(1))
+        .map(|i| {
+            f(
+                a.lane(i).cast(elem),
+                b.lane(i).cast(elem),
+                c.lane(i).cast(elem),
+            )
+        })
+        .collect();
+    Value::Lanes(lanes).cast(out)
+}
+
+fn float(f: impl Fn(f64) -> f64) -> impl Fn(Scalar) -> Scalar {
+    move |a| Scalar::Float(f(a.as_f64()))
+}
+
+fn float2(f: impl Fn(f64, f64) -> f64) -> impl Fn(Scalar, Scalar) -> Scalar {
+    move |a, b| Scalar::Float(f(a.as_f64(), b.as_f64()))
+}
+
+/// Applies a binary operation on operands of the same kind.
+pub(crate) fn numeric2(
+    a: Scalar,
+    b: Scalar,
+    float: impl Fn(f64, f64) -> f64,
+    int: impl Fn(i64, i64) -> i64,
+    uint: impl Fn(u64, u64) -> u64,
+) -> Scalar {
+    match (a, b) {
+        (Scalar::Float(a), Scalar::Float(b)) => Scalar::Float(float(a, b)),
+        (Scalar::Int(a), Scalar::Int(b)) => Scalar::Int(int(a, b)),
+        (Scalar::UInt(a), Scalar::UInt(b)) => Scalar::UInt(uint(a, b)),
+        (Scalar::Bool(a), Scalar::Bool(b)) => Scalar::Bool(uint(a as u64, b as u64) != 0),
+        (a, b) => unreachable!("Operands should have the same kind, got {a:?} and {b:?}"),
+    }
+}
+
+pub(crate) fn arithmetic(
+    op: &Arithmetic,
+    out: Type,
+    read: &mut dyn FnMut(&Variable) -> Value,
+) -> Value {
+    let elem = elem_of(out);
+
+    macro_rules! unary {
+        ($op:expr, $f:expr) => {{
+            let input = read(&$op.input);
+            map1(&input, elem, out, $f)
+        }};
+    }
+
+    macro_rules! binary {
+        ($op:expr, $f:expr) => {{
+            let lhs = read(&$op.lhs);
+            let rhs = read(&$op.rhs);
+            map2(&lhs, &rhs, elem, out, $f)
+        }};
+    }
+
+    match op {
+        Arithmetic::Add(op) => binary!(op, |a, b| numeric2(
+            a,
+            b,
+            |a, b| a + b,
+            i64::wrapping_add,
+            u64::wrapping_add
+        )),
+        Arithmetic::SaturatingAdd(op) => binary!(op, |a, b| saturating(a, b, elem, true)),
+        Arithmetic::Sub(op) => binary!(op, |a, b| numeric2(
+            a,
+            b,
+            |a, b| a - b,
+            i64::wrapping_sub,
+            u64::wrapping_sub
+        )),
+        Arithmetic::SaturatingSub(op) => binary!(op, |a, b| saturating(a, b, elem, false)),
+        Arithmetic::Mul(op) => binary!(op, |a, b| numeric2(
+            a,
+            b,
+            |a, b| a * b,
+            i64::wrapping_mul,
+            u64::wrapping_mul
+        )),
+        Arithmetic::Div(op) => binary!(op, |a, b| numeric2(
+            a,
+            b,
+            |a, b| a / b,
+            |a, b| a.checked_div(b).unwrap_or(0),
+            |a, b| a.checked_div(b).unwrap_or(0)
+        )),
+        Arithmetic::Modulo(op) => binary!(op, |a, b| numeric2(
+            a,
+            b,
+            |a, b| a % b,
+            |a, b| a.checked_rem(b).unwrap_or(0),
+            |a, b| a.checked_rem(b).unwrap_or(0)
+        )),
+        Arithmetic::Remainder(op) => binary!(op, |a, b| numeric2(
+            a,
+            b,
+            |a, b| a - b * (a / b).floor(),
+            |a, b| match a.checked_rem(b) {
+                Some(rem) if rem != 0 && (rem < 0) != (b < 0) => rem + b,
+                Some(rem) => rem,
+                None => 0,
+            },
+            |a, b| a.checked_rem(b).unwrap_or(0)
+        )),
+        Arithmetic::MulHi(op) => binary!(op, |a, b| mul_hi(a, b, elem)),
+        Arithmetic::Max(op) => binary!(op, |a, b| numeric2(a, b, f64::max, i64::max, u64::max)),
+        Arithmetic::Min(op) => binary!(op, |a, b| numeric2(a, b, f64::min, i64::min, u64::min)),
+        Arithmetic::Powf(op) => binary!(op, float2(f64::powf)),
+        Arithmetic::Powi(op) => {
+            let lhs = read(&op.lhs);
+            let rhs = read(&op.rhs);
+            let lanes = (0..out.vector_size().max(1))
+                .map(|i| {
+                    let base = lhs.lane(i).as_f64();
+                    Scalar::Float(base.powi(rhs.lane(i).as_i64() as i32))
+                })
+                .collect();
+            Value::Lanes(lanes).cast(out)
+        }
+        Arithmetic::Hypot(op) => binary!(op, float2(f64::hypot)),
+        Arithmetic::Rhypot(op) => binary!(op, float2(|a, b| a.hypot(b).recip())),
+        Arithmetic::Fma(op) => {
+            let a = read(&op.a);
+            let b = read(&op.b);
+            let c = read(&op.c);
+            map3(&a, &b, &c, elem, out, |a, b, c| {
+                numeric2(
+                    numeric2(a, b, |a, b| a * b, i64::wrapping_mul, u64::wrapping_mul),
+                    c,
+                    |a, b| a + b,
+                    i64::wrapping_add,
+                    u64::wrapping_add,
+                )
+            })
+        }
+        Arithmetic::Clamp(op) => {
+            let input = read(&op.input);
+            let min = read(&op.min_value);
+            let max = read(&op.max_value);
+            map3(&input, &min, &max, elem, out, |x, min, max| {
+                let x = numeric2(x, min, f64::max, i64::max, u64::max);
+                numeric2(x, max, f64::min, i64::min, u64::min)
+            })
+        }
+        Arithmetic::Abs(op) => unary!(op, |a| match a {
+            Scalar::Float(a) => Scalar::Float(a.abs()),
+            Scalar::Int(a) => Scalar::Int(a.wrapping_abs()),
+            other => other,
+        }),
+        Arithmetic::Neg(op) => unary!(op, |a| match a {
+            Scalar::Float(a) => Scalar::Float(-a),
+            Scalar::Int(a) => Scalar::Int(a.wrapping_neg()),
+            Scalar::UInt(a) => Scalar::UInt(a.wrapping_neg()),
+            other => other,
+        }),
+        Arithmetic::Exp(op) => unary!(op, float(f64::exp)),
+        Arithmetic::Log(op) => unary!(op, float(f64::ln)),
+        Arithmetic::Log1p(op) => unary!(op, float(f64::ln_1p)),
+        Arithmetic::Cos(op) => unary!(op, float(f64::cos)),
+        Arithmetic::Sin(op) => unary!(op, float(f64::sin)),
+        Arithmetic::Tan(op) => unary!(op, float(f64::tan)),
+        Arithmetic::Tanh(op) => unary!(op, float(f64::tanh)),
+        Arithmetic::Sinh(op) => unary!(op, float(f64::sinh)),
+        Arithmetic::Cosh(op) => unary!(op, float(f64::cosh)),
+        Arithmetic::ArcCos(op) => unary!(op, float(f64::acos)),
+        Arithmetic::ArcSin(op) => unary!(op, float(f64::asin)),
+        Arithmetic::ArcTan(op) => unary!(op, float(f64::atan)),
+        Arithmetic::ArcSinh(op) => unary!(op, float(f64::asinh)),
+        Arithmetic::ArcCosh(op) => unary!(op, float(f64::acosh)),
+        Arithmetic::ArcTanh(op) => unary!(op, float(f64::atanh)),
+        Arithmetic::ArcTan2(op) => binary!(op, float2(f64::atan2)),
+        Arithmetic::Degrees(op) => unary!(op, float(f64::to_degrees)),
+        Arithmetic::Radians(op) => unary!(op, float(f64::to_radians)),
+        Arithmetic::Sqrt(op) => unary!(op, float(f64::sqrt)),
+        Arithmetic::InverseSqrt(op) => unary!(op, float(|a| a.sqrt().recip())),
+        Arithmetic::Round(op) => unary!(op, float(f64::round_ties_even)),
+        Arithmetic::Floor(op) => unary!(op, float(f64::floor)),
+        Arithmetic::Ceil(op) => unary!(op, float(f64::ceil)),
+        Arithmetic::Trunc(op) => unary!(op, float(f64::trunc)),
+        Arithmetic::Erf(op) => unary!(op, float(erf)),
+        Arithmetic::Recip(op) => unary!(op, float(f64::recip)),
+        Arithmetic::Magnitude(op) => {
+            let input = read(&op.input);
+            let sum: f64 = input.lanes().iter().map(|lane| lane.as_f64().powi(2)).sum();
+            Value::scalar(Scalar::Float(sum.sqrt())).cast(out)
+        }
+        Arithmetic::Normalize(op) => {
+            let input = read(&op.input);
+            let sum: f64 = input.lanes().iter().map(|lane| lane.as_f64().powi(2)).sum();
+            let norm = sum.sqrt();
+            map1(&input, elem, out, float(|a| a / norm))
+        }
+        Arithmetic::Dot(op) => {
+            let lhs = read(&op.lhs);
+            let rhs = read(&op.rhs);
+            let lhs_elem = elem_of(op.lhs.ty);
+            let vector_size = op.lhs.ty.vector_size().max(1);
+            let mut sum = Scalar::zero(lhs_elem);
+            for i in 0..vector_size {
+                let product = numeric2(
+                    lhs.lane(i).cast(lhs_elem),
+                    rhs.lane(i).cast(lhs_elem),
+                    |a, b| a * b,
+                    i64::wrapping_mul,
+                    u64::wrapping_mul,
+                );
+                sum = numeric2(
+                    sum,
+                    product,
+                    |a, b| a + b,
+                    i64::wrapping_add,
+                    u64::wrapping_add,
+                )
+                .cast(lhs_elem);
+            }
+            Value::scalar(sum).cast(out)
+        }
+    }
+}
+
+fn saturating(a: Scalar, b: Scalar, elem: ElemType, add: bool) -> Scalar {
+    let (min, max) = int_range(elem);
+    let result = match add {
+        true => a.as_i128() + b.as_i128(),
+        false => a.as_i128() - b.as_i128(),
+    };
+    let result = result.clamp(min, max);
+
+    match a {
+        Scalar::Int(_) => Scalar::Int(result as i64),
+        Scalar::UInt(_) => Scalar::UInt(result as u64),
+        _ => numeric2(
+            a,
+            b,
+            |a, b| if add { a + b } else { a - b },
+            |a, _| a,
+            |a, _| a,
+        ),
+    }
+}
+
+fn mul_hi(a: Scalar, b: Scalar, elem: ElemType) -> Scalar {
+    let bits = elem.size_bits() as u32;
+    let product = a.as_i128().wrapping_mul(b.as_i128());
+
+    match a {
+        Scalar::Int(_) => Scalar::Int((product >> bits) as i64),
+        Scalar::UInt(_) => Scalar::UInt(((a.as_u64() as u128 * b.as_u64() as u128) >> bits) as u64),
+        other => other,
+    }
+}
+
+fn int_range(elem: ElemType) -> (i128, i128) {
+    match elem {
+        ElemType::Int(IntKind::I8) => (i8::MIN as i128, i8::MAX as i128),
+        ElemType::Int(IntKind::I16) => (i16::MIN as i128, i16::MAX as i128),
+        ElemType::Int(IntKind::I32) => (i32::MIN as i128, i32::MAX as i128),
+        ElemType::Int(IntKind::I64) => (i64::MIN as i128, i64::MAX as i128),
+        ElemType::UInt(UIntKind::U8) => (0, u8::MAX as i128),
+        ElemType::UInt(UIntKind::U16) => (0, u16::MAX as i128),
+        ElemType::UInt(UIntKind::U32) => (0, u32::MAX as i128),
+        ElemType::UInt(UIntKind::U64) => (0, u64::MAX as i128),
+        _ => (i128::MIN, i128::MAX),
+    }
+}
+
+/// Error function, using the approximation from Abramowitz and Stegun (7.1.26).
+///
+/// The maximum absolute error is `1.5e-7`, which is below the precision of `f32`.
+fn erf(x: f64) -> f64 {
+    const A1: f64 = 0.254829592;
+    const A2: f64 = -0.284496736;
+    const A3: f64 = 1.421413741;
+    const A4: f64 = -1.453152027;
+    const A5: f64 = 1.061405429;
+    const P: f64 = 0.3275911;
+
+    let sign = x.signum();
+    let x = x.abs();
+    let t = 1.0 / (1.0 + P * x);
+    let y = 1.0 - (((((A5 * t + A4) * t) + A3) * t + A2) * t + A1) * t * (-x * x).exp();
+
+    sign * y
+}
+
+pub(crate) fn comparison(
+    op: &Comparison,
+    out: Type,
+    read: &mut dyn FnMut(&Variable) -> Value,
+) -> Value {
+    macro_rules! compare {
+        ($op:expr, $f:expr) => {{
+            let lhs = read(&$op.lhs);
+            let rhs = read(&$op.rhs);
+            let elem = elem_of($op.lhs.ty);
+            let f = $f;
+            let lanes = (0..out.vector_size().max(1))
+                .map(|i| {
+                    let lhs = lhs.lane(i).cast(elem);
+                    let rhs = rhs.lane(i).cast(elem);
+                    Scalar::Bool(f(partial_cmp(lhs, rhs)))
+                })
+                .collect();
+            Value::Lanes(lanes).cast(out)
+        }};
+    }
+
+    use core::cmp::Ordering;
+
+    match op {
+        Comparison::Lower(op) => compare!(op, |ord| ord == Some(Ordering::Less)),
+        Comparison::LowerEqual(op) => compare!(op, |ord| matches!(
+            ord,
+            Some(Ordering::Less | Ordering::Equal)
+        )),
+        Comparison::Equal(op) => compare!(op, |ord| ord == Some(Ordering::Equal)),
+        Comparison::NotEqual(op) => compare!(op, |ord| ord != Some(Ordering::Equal)),
+        Comparison::GreaterEqual(op) => compare!(op, |ord| matches!(
+            ord,
+            Some(Ordering::Greater | Ordering::Equal)
+        )),
+        Comparison::Greater(op) => compare!(op, |ord| ord == Some(Ordering::Greater)),
+        Comparison::IsNan(op) => {
+            let input = read(&op.input);
+            map1(&input, elem_of(op.input.ty), out, |a| {
+                Scalar::Bool(a.as_f64().is_nan())
+            })
+        }
+        Comparison::IsInf(op) => {
+            let input = read(&op.input);
+            map1(&input, elem_of(op.input.ty), out, |a| {
+                Scalar::Bool(a.as_f64().is_infinite())
+            })
+        }
+    }
+}
+
+fn partial_cmp(lhs: Scalar, rhs: Scalar) -> Option<core::cmp::Ordering> {
+    match (lhs, rhs) {
+        (Scalar::Float(a), Scalar::Float(b)) => a.partial_cmp(&b),
+        (Scalar::Int(a), Scalar::Int(b)) => Some(a.cmp(&b)),
+        (Scalar::UInt(a), Scalar::UInt(b)) => Some(a.cmp(&b)),
+        (Scalar::Bool(a), Scalar::Bool(b)) => Some(a.cmp(&b)),
+        (a, b) => unreachable!("Operands should have the same kind, got {a:?} and {b:?}"),
+    }
+}
+
+pub(crate) fn bitwise(op: &Bitwise, out: Type, read: &mut dyn FnMut(&Variable) -> Value) -> Value {
+    let elem = elem_of(out);
+
+    macro_rules! binary {
+        ($op:expr, $f:expr) => {{
+            let lhs = read(&$op.lhs);
+            let rhs = read(&$op.rhs);
+            let f = $f;
+            map2(&lhs, &rhs, elem, out, |a, b| {
+                with_bits(a, f(a.as_u64(), b.as_u64()))
+            })
+        }};
+    }
+
+    // Bit counting is done on the input type, and converted to the output type after.
+    macro_rules! count {
+        ($op:expr, $f:expr) => {{
+            let input = read(&$op.input);
+            let elem = elem_of($op.input.ty);
+            let width = elem.size_bits() as u32;
+            let mask = bit_mask(width);
+            let f = $f;
+            map1(&input, elem, out, |a| {
+                Scalar::UInt(f(a.as_u64() & mask, width) as u64)
+            })
+        }};
+    }
+
+    let width = elem.size_bits() as u32;
+
+    match op {
+        Bitwise::BitwiseAnd(op) => binary!(op, |a, b| a & b),
+        Bitwise::BitwiseOr(op) => binary!(op, |a, b| a | b),
+        Bitwise::BitwiseXor(op) => binary!(op, |a, b| a ^ b),
+        Bitwise::ShiftLeft(op) => {
+            binary!(op, |a: u64, b: u64| a.wrapping_shl(b as u32 % width))
+        }
+        Bitwise::ShiftRight(op) => {
+            let lhs = read(&op.lhs);
+            let rhs = read(&op.rhs);
+            map2(&lhs, &rhs, elem, out, |a, b| {
+                let shift = b.as_u64() as u32 % width;
+                match a {
+                    // Signed values are sign extended, so the shift is arithmetic.
+                    Scalar::Int(a) => Scalar::Int(a >> shift),
+                    a => with_bits(a, (a.as_u64() & bit_mask(width)) >> shift),
+                }
+            })
+        }
+        Bitwise::BitwiseNot(op) => {
+            let input = read(&op.input);
+            map1(&input, elem, out, |a| with_bits(a, !a.as_u64()))
+        }
+        Bitwise::ReverseBits(op) => {
+            let input = read(&op.input);
+            map1(&input, elem, out, |a| {
+                with_bits(a, a.as_u64().reverse_bits() >> (64 - width))
+            })
+        }
+        Bitwise::CountOnes(op) => count!(op, |bits: u64, _| bits.count_ones()),
+        Bitwise::LeadingZeros(op) => {
+            count!(op, |bits: u64, width| bits.leading_zeros() - (64 - width))
+        }
+        Bitwise::TrailingZeros(op) => {
+            count!(op, |bits: u64, width: u32| bits.trailing_zeros().min(width))
+        }
+        Bitwise::FindFirstSet(op) => count!(op, |bits: u64, _| match bits {
+            0 => 0,
+            bits => bits.trailing_zeros() + 1,
+        }),
+    }
+}
+
+fn bit_mask(width: u32) -> u64 {
+    match width {
+        64 => u64::MAX,
+        width => (1 << width) - 1,
+    }
+}
+
+/// Creates a scalar of the same kind from raw bits. The result is normalized by the final cast.
+fn with_bits(kind: Scalar, bits: u64) -> Scalar {
+    match kind {
+        Scalar::Int(_) => Scalar::Int(bits as i64),
+        Scalar::Bool(_) => Scalar::Bool(bits & 1 != 0),
+        _ => Scalar::UInt(bits),
+    }
+}
+
+impl Scalar {
+    fn as_i128(self) -> i128 {
+        match self {
+            Scalar::Bool(val) => val as i128,
+            Scalar::Int(val) => val as i128,
+            Scalar::UInt(val) => val as i128,
+            Scalar::Float(val) => val as i128,
+        }
+    }
+}
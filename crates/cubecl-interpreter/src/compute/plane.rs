@@ -0,0 +1,144 @@
+//! Collective evaluation of plane operations.
+//!
+//! Only the units that reached the same plane instruction take part in it, which matches the
+//! semantics of non-uniform control flow on devices: inactive lanes don't contribute to
+//! reductions, and shuffling from an inactive lane returns the unit's own value.
+
+use cubecl_core::ir::{ElemType, Plane, Type};
+
+use super::{
+    ops::{elem_of, numeric2},
+    value::{Scalar, Value},
+};
+
+pub(crate) struct Participant {
+    /// Position of the unit in its plane.
+    pub(crate) lane: usize,
+    pub(crate) input: Option<Value>,
+    pub(crate) rhs: Option<Value>,
+}
+
+pub(crate) fn execute(
+    op: &Plane,
+    participants: &[Participant],
+    out: Type,
+    plane_size: usize,
+) -> Vec<Value> {
+    let elem = elem_of(out);
+    let vector_size = out.vector_size().max(1);
+
+    let input = |p: &Participant| p.input.clone().unwrap().cast(out);
+    let source = |lane: usize| participants.iter().find(|p| p.lane == lane);
+
+    match op {
+        Plane::Elect => {
+            let elected = participants.iter().map(|p| p.lane).min();
+            participants
+                .iter()
+                .map(|p| Value::scalar(Scalar::Bool(Some(p.lane) == elected)))
+                .collect()
+        }
+        Plane::All(_) | Plane::Any(_) => {
+            let all = matches!(op, Plane::All(_));
+            let lanes = (0..vector_size)
+                .map(|i| {
+                    let mut values = participants.iter().map(|p| input(p).lane(i).as_bool());
+                    Scalar::Bool(match all {
+                        true => values.all(|val| val),
+                        false => values.any(|val| val),
+                    })
+                })
+                .collect();
+            let result = Value::Lanes(lanes);
+            vec![result; participants.len()]
+        }
+        Plane::Ballot(_) => {
+            let mut mask = [0u64; 4];
+            for p in participants {
+                let vote = p.input.as_ref().unwrap().first().as_bool();
+                if vote && p.lane < 128 {
+                    mask[p.lane / 32] |= 1 << (p.lane % 32);
+                }
+            }
+            let result = Value::Lanes(mask.into_iter().map(Scalar::UInt).collect()).cast(out);
+            vec![result; participants.len()]
+        }
+        Plane::Broadcast(_) | Plane::Shuffle(_) => participants
+            .iter()
+            .map(|p| {
+                let lane = p.rhs.as_ref().unwrap().first().as_usize();
+                input(source(lane).unwrap_or(p))
+            })
+            .collect(),
+        Plane::ShuffleXor(_) | Plane::ShuffleUp(_) | Plane::ShuffleDown(_) => participants
+            .iter()
+            .map(|p| {
+                let offset = p.rhs.as_ref().unwrap().first().as_usize();
+                let lane = match op {
+                    Plane::ShuffleXor(_) => Some(p.lane ^ offset),
+                    Plane::ShuffleUp(_) => p.lane.checked_sub(offset),
+                    _ => Some(p.lane + offset).filter(|lane| *lane < plane_size),
+                };
+                input(lane.and_then(source).unwrap_or(p))
+            })
+            .collect(),
+        Plane::Sum(_) | Plane::Prod(_) | Plane::Min(_) | Plane::Max(_) => {
+            let result = reduce(op, elem, participants.iter().map(input), vector_size)
+                .expect("At least one unit participates");
+            vec![result.cast(out); participants.len()]
+        }
+        Plane::InclusiveSum(_)
+        | Plane::ExclusiveSum(_)
+        | Plane::InclusiveProd(_)
+        | Plane::ExclusiveProd(_) => {
+            let inclusive = matches!(op, Plane::InclusiveSum(_) | Plane::InclusiveProd(_));
+            let identity = match op {
+                Plane::InclusiveSum(_) | Plane::ExclusiveSum(_) => 0.0,
+                _ => 1.0,
+            };
+
+            participants
+                .iter()
+                .map(|p| {
+                    let prefix = participants
+                        .iter()
+                        .filter(|other| match inclusive {
+                            true => other.lane <= p.lane,
+                            false => other.lane < p.lane,
+                        })
+                        .map(input);
+                    reduce(op, elem, prefix, vector_size)
+                        .unwrap_or_else(|| Value::scalar(Scalar::Float(identity)))
+                        .cast(out)
+                })
+                .collect()
+        }
+    }
+}
+
+fn reduce(
+    op: &Plane,
+    elem: ElemType,
+    values: impl Iterator<Item = Value>,
+    vector_size: usize,
+) -> Option<Value> {
+    values.reduce(|acc, value| {
+        let lanes = (0..vector_size)
+            .map(|i| {
+                let (a, b) = (acc.lane(i), value.lane(i));
+                let result = match op {
+                    Plane::Sum(_) | Plane::InclusiveSum(_) | Plane::ExclusiveSum(_) => {
+                        numeric2(a, b, |a, b| a + b, i64::wrapping_add, u64::wrapping_add)
+                    }
+                    Plane::Prod(_) | Plane::InclusiveProd(_) | Plane::ExclusiveProd(_) => {
+                        numeric2(a, b, |a, b| a * b, i64::wrapping_mul, u64::wrapping_mul)
+                    }
+                    Plane::Min(_) => numeric2(a, b, f64::min, i64::min, u64::min),
+                    _ => numeric2(a, b, f64::max, i64::max, u64::max),
+                };
+                result.cast(elem)
+            })
+            .collect();
+        Value::Lanes(lanes)
+    })
+}
@@ -0,0 +1,392 @@
+use crate::{
+    InterpreterCompiler,
+    compiler::{InterpretedKernel, InterpreterCompilerOptions},
+    compute::interpreter::{GlobalBuffer, KernelLaunch},
+};
+use cubecl_common::{
+    backtrace::BackTrace, bytes::Bytes, profile::ProfileDuration, stream_id::StreamId,
+};
+use cubecl_core::{
+    CubeCount, ExecutionMode, MemoryConfiguration, MemoryTrace, MemoryUsage,
+    future::DynFut,
+    ir::MemoryDeviceProperties,
+    server::{
+        Binding, ComputeServer, CopyDescriptor, CopyLayout, IoError, KernelArguments, LaunchError,
+        ProfileError, ProfilingToken, ResourceLimitError, ServerCommunication, ServerError,
+        ServerUtilities,
+    },
+};
+use cubecl_runtime::{
+    allocator::ContiguousMemoryLayoutPolicy,
+    compiler::CubeTask,
+    id::KernelId,
+    logging::ServerLogger,
+    memory_management::{
+        ManagedMemoryHandle, MemoryAllocationMode, MemoryManagement, MemoryManagementOptions,
+    },
+    storage::{BytesStorage, ComputeStorage, ManagedResource},
+    timestamp_profiler::TimestampProfiler,
+    validation::{validate_cube_dim, validate_units},
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// A compute server executing kernels with the IR interpreter.
+///
+/// Kernels are executed synchronously when they are launched, so all streams share the same
+/// memory pool and are always in sync.
+pub struct InterpreterServer {
+    memory_management: MemoryManagement<BytesStorage>,
+    timestamps: TimestampProfiler,
+    utilities: Arc<ServerUtilities<InterpreterServer>>,
+    compilation_cache: HashMap<KernelId, Arc<InterpretedKernel>>,
+    errors: Vec<ServerError>,
+    plane_size: u32,
+    compilation_options: InterpreterCompilerOptions,
+}
+
+impl core::fmt::Debug for InterpreterServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InterpreterServer")
+            .field("plane_size", &self.plane_size)
+            .finish()
+    }
+}
+
+impl InterpreterServer {
+    pub fn new(
+        memory_properties: MemoryDeviceProperties,
+        memory_config: MemoryConfiguration,
+        plane_size: u32,
+        compilation_options: InterpreterCompilerOptions,
+        utilities: Arc<ServerUtilities<InterpreterServer>>,
+    ) -> Self {
+        let memory_management = MemoryManagement::from_configuration(
+            BytesStorage::default(),
+            &memory_properties,
+            memory_config,
+            utilities.logger.clone(),
+            MemoryManagementOptions::new("Interpreter"),
+        );
+
+        Self {
+            memory_management,
+            timestamps: TimestampProfiler::default(),
+            utilities,
+            compilation_cache: HashMap::new(),
+            errors: Vec::new(),
+            plane_size,
+            compilation_options,
+        }
+    }
+
+    fn compile(
+        &mut self,
+        kernel: Box<dyn CubeTask<InterpreterCompiler>>,
+        kind: ExecutionMode,
+    ) -> Result<Arc<InterpretedKernel>, LaunchError> {
+        let kernel_id = kernel.id();
+        if let Some(kernel) = self.compilation_cache.get(&kernel_id) {
+            return Ok(kernel.clone());
+        }
+
+        let compiled = kernel.compile(
+            &mut Default::default(),
+            &self.compilation_options,
+            kind,
+            kernel.address_type(),
+        )?;
+        let interpreted = Arc::new(
+            compiled
+                .repr
+                .expect("Interpreted kernels are always lowered"),
+        );
+        self.validate_shared(&interpreted)?;
+
+        self.compilation_cache
+            .insert(kernel_id, interpreted.clone());
+        Ok(interpreted)
+    }
+
+    fn validate_shared(&self, kernel: &InterpretedKernel) -> Result<(), LaunchError> {
+        let max_smem = self.utilities.properties.hardware.max_shared_memory_size;
+        if kernel.shared_memory_size > max_smem {
+            Err(ResourceLimitError::SharedMemory {
+                requested: kernel.shared_memory_size,
+                max: max_smem,
+                backtrace: BackTrace::capture(),
+            }
+            .into())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn execute(
+        &mut self,
+        kernel: Box<dyn CubeTask<InterpreterCompiler>>,
+        count: CubeCount,
+        bindings: KernelArguments,
+        kind: ExecutionMode,
+    ) -> Result<(), LaunchError> {
+        let properties = &self.utilities.properties;
+        validate_cube_dim(properties, &kernel.id())?;
+        validate_units(properties, &kernel.id())?;
+
+        let kernel = self.compile(kernel, kind)?;
+
+        let cube_count = match count {
+            CubeCount::Static(x, y, z) => [x, y, z],
+            CubeCount::Dynamic(binding) => {
+                let resource = self.memory_management.get_resource(
+                    binding.memory,
+                    binding.offset_start,
+                    binding.offset_end,
+                )?;
+                let bytes = resource.read();
+                let x = u32::from_ne_bytes(bytes[0..4].try_into().unwrap());
+                let y = u32::from_ne_bytes(bytes[4..8].try_into().unwrap());
+                let z = u32::from_ne_bytes(bytes[8..12].try_into().unwrap());
+                [x, y, z]
+            }
+        };
+
+        // Store all the resources we'll be using, so the memory stays valid during the execution.
+        let resources = bindings
+            .buffers
+            .into_iter()
+            .map(|binding| {
+                self.memory_management.get_resource(
+                    binding.memory,
+                    binding.offset_start,
+                    binding.offset_end,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let buffers = resources
+            .iter()
+            .map(|resource| {
+                let (ptr, len) = resource.get_write_ptr_and_length();
+                // SAFETY: The resources are kept alive until the end of the execution.
+                unsafe { GlobalBuffer::new(ptr, len) }
+            })
+            .collect();
+
+        let launch = KernelLaunch {
+            kernel: &kernel,
+            buffers,
+            info: bytemuck::cast_slice(&bindings.info.data),
+            dynamic_meta_offset: bindings.info.dynamic_metadata_offset * size_of::<u64>(),
+            cube_count,
+            plane_size: self.plane_size,
+        };
+        launch.execute();
+
+        Ok(())
+    }
+
+    fn flush_errors(&mut self) -> Result<(), ServerError> {
+        let errors = core::mem::take(&mut self.errors);
+
+        if errors.is_empty() {
+            return Ok(());
+        }
+
+        self.timestamps.error(ProfileError::Unknown {
+            reason: alloc::format!("{:?}", errors),
+            backtrace: BackTrace::capture(),
+        });
+
+        Err(ServerError::ServerUnhealthy {
+            errors,
+            backtrace: BackTrace::capture(),
+        })
+    }
+
+    pub(crate) fn utilities(&self) -> Arc<ServerUtilities<Self>> {
+        self.utilities.clone()
+    }
+}
+
+impl ComputeServer for InterpreterServer {
+    type Kernel = Box<dyn CubeTask<InterpreterCompiler>>;
+    type Storage = BytesStorage;
+    type MemoryLayoutPolicy = ContiguousMemoryLayoutPolicy;
+    type Info = ();
+
+    fn logger(&self) -> Arc<ServerLogger> {
+        self.utilities.logger.clone()
+    }
+
+    fn staging(
+        &mut self,
+        _sizes: &[usize],
+        _stream_id: StreamId,
+    ) -> Result<Vec<Bytes>, ServerError> {
+        Err(IoError::UnsupportedIoOperation {
+            backtrace: BackTrace::capture(),
+        }
+        .into())
+    }
+
+    fn utilities(&self) -> Arc<ServerUtilities<Self>> {
+        self.utilities.clone()
+    }
+
+    fn initialize_memory(&mut self, memory: ManagedMemoryHandle, size: u64, _stream_id: StreamId) {
+        let reserved = self.memory_management.reserve(size).unwrap();
+        self.memory_management.bind(reserved, memory, 0).unwrap();
+    }
+
+    fn read(
+        &mut self,
+        descriptors: Vec<CopyDescriptor>,
+        _stream_id: StreamId,
+    ) -> DynFut<Result<Vec<Bytes>, ServerError>> {
+        let mut results = Vec::with_capacity(descriptors.len());
+
+        for desc in descriptors {
+            let layout = CopyLayout::new(desc.shape, desc.strides, desc.elem_size);
+            let len = desc.handle.size_in_used() as usize;
+            let resource = match self.memory_management.get_resource(
+                desc.handle.memory,
+                desc.handle.offset_start,
+                desc.handle.offset_end,
+            ) {
+                Ok(resource) => resource,
+                Err(err) => return Box::pin(async move { Err(err.into()) }),
+            };
+            let data = &resource.read()[..len];
+
+            let bytes = match layout.is_contiguous() {
+                true => data.to_vec(),
+                false if layout.span() <= len => layout.gather(data),
+                false => return Box::pin(async move { Err(unsupported_strides().into()) }),
+            };
+            results.push(Bytes::from_bytes_vec(bytes));
+        }
+
+        Box::pin(async move { Ok(results) })
+    }
+
+    fn write(&mut self, descriptors: Vec<(CopyDescriptor, Bytes)>, _stream_id: StreamId) {
+        for (desc, data) in descriptors {
+            let layout = CopyLayout::new(desc.shape, desc.strides, desc.elem_size);
+            let len = desc.handle.size_in_used() as usize;
+            if data.len() != layout.size() {
+                self.errors.push(ServerError::Io(IoError::InvalidSize {
+                    expected: layout.size(),
+                    actual: data.len(),
+                    backtrace: BackTrace::capture(),
+                }));
+                return;
+            }
+            if layout.span() > len {
+                self.errors.push(ServerError::Io(unsupported_strides()));
+                return;
+            }
+
+            let mut resource = match self.memory_management.get_resource(
+                desc.handle.memory,
+                desc.handle.offset_start,
+                desc.handle.offset_end,
+            ) {
+                Ok(resource) => resource,
+                Err(err) => {
+                    self.errors.push(ServerError::Io(err));
+                    return;
+                }
+            };
+
+            let buffer = &mut resource.write()[..len];
+            match layout.is_contiguous() {
+                true => buffer[..data.len()].copy_from_slice(&data),
+                // The bytes between elements are left unchanged.
+                false => layout.scatter(&data, buffer),
+            }
+        }
+    }
+
+    fn memory_usage(&mut self, _stream_id: StreamId) -> Result<MemoryUsage, ServerError> {
+        Ok(self.memory_management.memory_usage())
+    }
+
+    fn memory_cleanup(&mut self, _stream_id: StreamId) {
+        self.memory_management.cleanup(true)
+    }
+
+    fn memory_trace(&mut self, _stream_id: StreamId) -> Result<MemoryTrace, ServerError> {
+        Ok(self.memory_management.take_trace())
+    }
+
+    unsafe fn launch(
+        &mut self,
+        kernel: Self::Kernel,
+        count: CubeCount,
+        bindings: KernelArguments,
+        kind: ExecutionMode,
+        _stream_id: StreamId,
+    ) {
+        self.memory_management.trace_launch(kernel.name());
+        if let Err(err) = self.execute(kernel, count, bindings, kind) {
+            self.errors.push(ServerError::Launch(err));
+        }
+    }
+
+    fn flush(&mut self, _stream_id: StreamId) -> Result<(), ServerError> {
+        self.flush_errors()
+    }
+
+    fn sync(&mut self, _stream_id: StreamId) -> DynFut<Result<(), ServerError>> {
+        let result = self.flush_errors();
+
+        Box::pin(async move { result })
+    }
+
+    fn start_profile(&mut self, _stream_id: StreamId) -> Result<ProfilingToken, ServerError> {
+        self.flush_errors()?;
+
+        Ok(self.timestamps.start())
+    }
+
+    fn end_profile(
+        &mut self,
+        _stream_id: StreamId,
+        token: ProfilingToken,
+    ) -> Result<ProfileDuration, ProfileError> {
+        if let Err(err) = self.flush_errors() {
+            self.timestamps.error(ProfileError::Server(Box::new(err)));
+        }
+
+        self.timestamps.stop(token)
+    }
+
+    fn get_resource(
+        &mut self,
+        binding: Binding,
+        _stream_id: StreamId,
+    ) -> Result<ManagedResource<<Self::Storage as ComputeStorage>::Resource>, ServerError> {
+        let memory = binding.memory.clone();
+        let resource = self.memory_management.get_resource(
+            binding.memory,
+            binding.offset_start,
+            binding.offset_end,
+        )?;
+
+        Ok(ManagedResource::new(memory, resource))
+    }
+
+    fn allocation_mode(&mut self, mode: MemoryAllocationMode, _stream_id: StreamId) {
+        self.memory_management.mode(mode);
+    }
+}
+
+impl ServerCommunication for InterpreterServer {
+    const SERVER_COMM_ENABLED: bool = false;
+}
+
+fn unsupported_strides() -> IoError {
+    IoError::UnsupportedStrides {
+        backtrace: BackTrace::capture(),
+    }
+}
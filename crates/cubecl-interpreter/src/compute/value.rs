@@ -0,0 +1,361 @@
+use cubecl_common::{e2m1, e4m3, e5m2, printf::PrintValue, ue8m0};
+use cubecl_core::ir::{
+    ConstantValue, ElemType, FloatKind, Id, IntKind, StorageType, Type, UIntKind, Variable,
+    VariableKind,
+};
+use half::{bf16, f16};
+use smallvec::{SmallVec, smallvec};
+
+/// A single lane of a value.
+///
+/// Values are always kept normalized for the type they were produced with: integers are wrapped to
+/// the width of their type and floats are rounded to their precision. This way the interpreter
+/// can compute everything with 64-bit arithmetic and still observe the same results as a device
+/// computing with the real type.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Scalar {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+}
+
+/// A memory region that can be pointed to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum Region {
+    /// A global buffer, by its position in the bindings.
+    Global(usize),
+    Shared(Id),
+    Local(Id),
+    Const(Id),
+}
+
+/// The address of an element, used by atomic operations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Pointer {
+    pub(crate) region: Region,
+    /// Offset in bytes from the start of the region.
+    pub(crate) offset: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    Lanes(SmallVec<[Scalar; 4]>),
+    Pointer(Pointer),
+}
+
+/// The element type used to compute with values of the given storage type.
+///
+/// Packed types are opaque bit containers, so they are manipulated as unsigned integers of the
+/// same size.
+pub(crate) fn value_elem(storage: StorageType) -> ElemType {
+    match storage {
+        StorageType::Scalar(elem) | StorageType::Atomic(elem) => elem,
+        StorageType::Packed(..) => match storage.size() {
+            1 => ElemType::UInt(UIntKind::U8),
+            2 => ElemType::UInt(UIntKind::U16),
+            3 | 4 => ElemType::UInt(UIntKind::U32),
+            _ => ElemType::UInt(UIntKind::U64),
+        },
+        StorageType::Opaque(_) => ElemType::UInt(UIntKind::U64),
+    }
+}
+
+impl Scalar {
+    pub(crate) fn zero(elem: ElemType) -> Self {
+        match elem {
+            ElemType::Float(_) => Scalar::Float(0.0),
+            ElemType::Int(_) => Scalar::Int(0),
+            ElemType::UInt(_) => Scalar::UInt(0),
+            ElemType::Bool => Scalar::Bool(false),
+        }
+    }
+
+    pub(crate) fn from_constant(value: ConstantValue) -> Self {
+        match value {
+            ConstantValue::Int(val) => Scalar::Int(val),
+            ConstantValue::Float(val) => Scalar::Float(val),
+            ConstantValue::UInt(val) => Scalar::UInt(val),
+            ConstantValue::Bool(val) => Scalar::Bool(val),
+        }
+    }
+
+    pub(crate) fn as_f64(self) -> f64 {
+        match self {
+            Scalar::Bool(val) => val as u8 as f64,
+            Scalar::Int(val) => val as f64,
+            Scalar::UInt(val) => val as f64,
+            Scalar::Float(val) => val,
+        }
+    }
+
+    pub(crate) fn as_i64(self) -> i64 {
+        match self {
+            Scalar::Bool(val) => val as i64,
+            Scalar::Int(val) => val,
+            Scalar::UInt(val) => val as i64,
+            Scalar::Float(val) => val as i64,
+        }
+    }
+
+    pub(crate) fn as_u64(self) -> u64 {
+        match self {
+            Scalar::Bool(val) => val as u64,
+            Scalar::Int(val) => val as u64,
+            Scalar::UInt(val) => val,
+            Scalar::Float(val) => val as u64,
+        }
+    }
+
+    pub(crate) fn print_value(self) -> PrintValue {
+        match self {
+            Scalar::Bool(val) => PrintValue::UInt(val as u64),
+            Scalar::Int(val) => PrintValue::Int(val),
+            Scalar::UInt(val) => PrintValue::UInt(val),
+            Scalar::Float(val) => PrintValue::Float(val),
+        }
+    }
+
+    pub(crate) fn as_usize(self) -> usize {
+        self.as_u64() as usize
+    }
+
+    pub(crate) fn as_bool(self) -> bool {
+        match self {
+            Scalar::Bool(val) => val,
+            Scalar::Int(val) => val != 0,
+            Scalar::UInt(val) => val != 0,
+            Scalar::Float(val) => val != 0.0,
+        }
+    }
+
+    /// The raw two's complement bits of an integer value.
+    fn bits(self) -> u64 {
+        match self {
+            Scalar::Bool(val) => val as u64,
+            Scalar::Int(val) => val as u64,
+            Scalar::UInt(val) => val,
+            Scalar::Float(val) => val as i64 as u64,
+        }
+    }
+
+    /// Converts the scalar with the same semantics as a numeric cast in the kernel.
+    ///
+    /// Float to integer conversions saturate and map `NaN` to zero, integer conversions wrap.
+    pub(crate) fn cast(self, elem: ElemType) -> Self {
+        match elem {
+            ElemType::Bool => Scalar::Bool(self.as_bool()),
+            ElemType::Int(kind) => Scalar::Int(match self {
+                Scalar::Float(val) => match kind {
+                    IntKind::I8 => val as i8 as i64,
+                    IntKind::I16 => val as i16 as i64,
+                    IntKind::I32 => val as i32 as i64,
+                    IntKind::I64 => val as i64,
+                },
+                other => wrap_int(other.bits(), kind),
+            }),
+            ElemType::UInt(kind) => Scalar::UInt(match self {
+                Scalar::Float(val) => match kind {
+                    UIntKind::U8 => val as u8 as u64,
+                    UIntKind::U16 => val as u16 as u64,
+                    UIntKind::U32 => val as u32 as u64,
+                    UIntKind::U64 => val as u64,
+                },
+                other => wrap_uint(other.bits(), kind),
+            }),
+            ElemType::Float(kind) => Scalar::Float(round_float(self.as_f64(), kind)),
+        }
+    }
+
+    /// Reads a scalar of the given type from its memory representation.
+    pub(crate) fn decode(elem: ElemType, bytes: &[u8]) -> Self {
+        fn array<const N: usize>(bytes: &[u8]) -> [u8; N] {
+            bytes[..N].try_into().unwrap()
+        }
+
+        match elem {
+            ElemType::Bool => Scalar::Bool(bytes[0] != 0),
+            ElemType::Int(kind) => Scalar::Int(match kind {
+                IntKind::I8 => i8::from_ne_bytes(array(bytes)) as i64,
+                IntKind::I16 => i16::from_ne_bytes(array(bytes)) as i64,
+                IntKind::I32 => i32::from_ne_bytes(array(bytes)) as i64,
+                IntKind::I64 => i64::from_ne_bytes(array(bytes)),
+            }),
+            ElemType::UInt(kind) => Scalar::UInt(match kind {
+                UIntKind::U8 => bytes[0] as u64,
+                UIntKind::U16 => u16::from_ne_bytes(array(bytes)) as u64,
+                UIntKind::U32 => u32::from_ne_bytes(array(bytes)) as u64,
+                UIntKind::U64 => u64::from_ne_bytes(array(bytes)),
+            }),
+            ElemType::Float(kind) => Scalar::Float(match kind {
+                FloatKind::E2M1 => e2m1::from_bits(bytes[0]).to_f64(),
+                // No conversion is provided for 6-bit floats, they are only moved around.
+                FloatKind::E2M3 | FloatKind::E3M2 => bytes[0] as f64,
+                FloatKind::E4M3 => e4m3::from_bits(bytes[0]).to_f64(),
+                FloatKind::E5M2 => e5m2::from_bits(bytes[0]).to_f64(),
+                FloatKind::UE8M0 => ue8m0::from_bits(bytes[0]).to_f64(),
+                FloatKind::F16 => f16::from_ne_bytes(array(bytes)).to_f64(),
+                FloatKind::BF16 => bf16::from_ne_bytes(array(bytes)).to_f64(),
+                FloatKind::Flex32 | FloatKind::F32 | FloatKind::TF32 => {
+                    f32::from_ne_bytes(array(bytes)) as f64
+                }
+                FloatKind::F64 => f64::from_ne_bytes(array(bytes)),
+            }),
+        }
+    }
+
+    /// Appends the memory representation of the scalar, converted to the given type.
+    pub(crate) fn encode(self, elem: ElemType, out: &mut Vec<u8>) {
+        match self.cast(elem) {
+            Scalar::Bool(val) => out.push(val as u8),
+            Scalar::Int(val) => match elem.size() {
+                1 => out.extend_from_slice(&(val as i8).to_ne_bytes()),
+                2 => out.extend_from_slice(&(val as i16).to_ne_bytes()),
+                4 => out.extend_from_slice(&(val as i32).to_ne_bytes()),
+                _ => out.extend_from_slice(&val.to_ne_bytes()),
+            },
+            Scalar::UInt(val) => match elem.size() {
+                1 => out.push(val as u8),
+                2 => out.extend_from_slice(&(val as u16).to_ne_bytes()),
+                4 => out.extend_from_slice(&(val as u32).to_ne_bytes()),
+                _ => out.extend_from_slice(&val.to_ne_bytes()),
+            },
+            Scalar::Float(val) => match elem {
+                ElemType::Float(FloatKind::E2M1) => out.push(e2m1::from_f64(val).to_bits()),
+                ElemType::Float(FloatKind::E2M3 | FloatKind::E3M2) => out.push(val as u8),
+                ElemType::Float(FloatKind::E4M3) => out.push(e4m3::from_f64(val).to_bits()),
+                ElemType::Float(FloatKind::E5M2) => out.push(e5m2::from_f64(val).to_bits()),
+                ElemType::Float(FloatKind::UE8M0) => out.push(ue8m0::from_f64(val).to_bits()),
+                ElemType::Float(FloatKind::F16) => {
+                    out.extend_from_slice(&f16::from_f64(val).to_ne_bytes())
+                }
+                ElemType::Float(FloatKind::BF16) => {
+                    out.extend_from_slice(&bf16::from_f64(val).to_ne_bytes())
+                }
+                ElemType::Float(FloatKind::F64) => out.extend_from_slice(&val.to_ne_bytes()),
+                _ => out.extend_from_slice(&(val as f32).to_ne_bytes()),
+            },
+        }
+    }
+}
+
+fn wrap_int(bits: u64, kind: IntKind) -> i64 {
+    match kind {
+        IntKind::I8 => bits as i8 as i64,
+        IntKind::I16 => bits as i16 as i64,
+        IntKind::I32 => bits as i32 as i64,
+        IntKind::I64 => bits as i64,
+    }
+}
+
+fn wrap_uint(bits: u64, kind: UIntKind) -> u64 {
+    match kind {
+        UIntKind::U8 => bits as u8 as u64,
+        UIntKind::U16 => bits as u16 as u64,
+        UIntKind::U32 => bits as u32 as u64,
+        UIntKind::U64 => bits,
+    }
+}
+
+fn round_float(val: f64, kind: FloatKind) -> f64 {
+    match kind {
+        FloatKind::E2M1 => e2m1::from_f64(val).to_f64(),
+        FloatKind::E2M3 | FloatKind::E3M2 => val,
+        FloatKind::E4M3 => e4m3::from_f64(val).to_f64(),
+        FloatKind::E5M2 => e5m2::from_f64(val).to_f64(),
+        FloatKind::UE8M0 => ue8m0::from_f64(val).to_f64(),
+        FloatKind::F16 => f16::from_f64(val).to_f64(),
+        FloatKind::BF16 => bf16::from_f64(val).to_f64(),
+        FloatKind::Flex32 | FloatKind::F32 | FloatKind::TF32 => val as f32 as f64,
+        FloatKind::F64 => val,
+    }
+}
+
+impl Value {
+    pub(crate) fn zero(ty: Type) -> Self {
+        let elem = value_elem(ty.storage_type());
+        Value::Lanes(smallvec![Scalar::zero(elem); ty.vector_size().max(1)])
+    }
+
+    pub(crate) fn scalar(value: Scalar) -> Self {
+        Value::Lanes(smallvec![value])
+    }
+
+    pub(crate) fn from_constant(var: &Variable) -> Self {
+        match var.kind {
+            VariableKind::Constant(value) => {
+                let elem = value_elem(var.storage_type());
+                let scalar = Scalar::from_constant(value).cast(elem);
+                Value::Lanes(smallvec![scalar; var.ty.vector_size().max(1)])
+            }
+            _ => unreachable!("Expected a constant, got {var}"),
+        }
+    }
+
+    pub(crate) fn lanes(&self) -> &[Scalar] {
+        match self {
+            Value::Lanes(lanes) => lanes,
+            Value::Pointer(_) => panic!("Expected a value, got a pointer"),
+        }
+    }
+
+    /// The lane at the given index, broadcasting single lane values.
+    pub(crate) fn lane(&self, index: usize) -> Scalar {
+        let lanes = self.lanes();
+        match lanes.len() {
+            1 => lanes[0],
+            _ => lanes[index],
+        }
+    }
+
+    pub(crate) fn first(&self) -> Scalar {
+        self.lanes()[0]
+    }
+
+    pub(crate) fn pointer(&self) -> Pointer {
+        match self {
+            Value::Pointer(pointer) => *pointer,
+            Value::Lanes(_) => panic!("Expected a pointer, got a value"),
+        }
+    }
+
+    /// Converts the value to the given type, broadcasting lanes if necessary.
+    pub(crate) fn cast(self, ty: Type) -> Self {
+        let lanes = match self {
+            Value::Pointer(_) => return self,
+            Value::Lanes(lanes) => lanes,
+        };
+
+        let elem = value_elem(ty.storage_type());
+        let vector_size = ty.vector_size().max(1);
+
+        let lanes = (0..vector_size)
+            .map(|i| match lanes.len() {
+                1 => lanes[0],
+                _ => lanes.get(i).copied().unwrap_or(Scalar::zero(elem)),
+            })
+            .map(|lane| lane.cast(elem))
+            .collect();
+
+        Value::Lanes(lanes)
+    }
+
+    /// Appends the memory representation of the value, converted to the given type.
+    pub(crate) fn encode(&self, ty: Type, out: &mut Vec<u8>) {
+        let elem = value_elem(ty.storage_type());
+        for i in 0..ty.vector_size().max(1) {
+            self.lane(i).encode(elem, out);
+        }
+    }
+
+    pub(crate) fn decode(ty: Type, bytes: &[u8]) -> Self {
+        let elem = value_elem(ty.storage_type());
+        let size = ty.storage_type().size();
+
+        Value::Lanes(
+            (0..ty.vector_size().max(1))
+                .map(|i| Scalar::decode(elem, &bytes[i * size..]))
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,17 @@
+use cubecl_common::device::{Device, DeviceId};
+
+#[derive(new, Clone, PartialEq, Eq, Default, Hash, Debug)]
+pub struct InterpreterDevice;
+
+impl Device for InterpreterDevice {
+    fn from_id(_device_id: DeviceId) -> Self {
+        Self
+    }
+
+    fn to_id(&self) -> DeviceId {
+        DeviceId {
+            type_id: 0,
+            index_id: 0,
+        }
+    }
+}
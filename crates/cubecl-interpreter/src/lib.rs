@@ -0,0 +1,119 @@
+#[macro_use]
+extern crate derive_new;
+
+extern crate alloc;
+
+#[cfg(test)]
+#[allow(unexpected_cfgs)]
+mod tests {
+    pub type TestRuntime = crate::InterpreterRuntime;
+
+    pub use half::f16;
+
+    use cubecl_core as cubecl;
+    use cubecl_core::prelude::*;
+
+    cubecl_core::testgen_all!(f32: [f16, f32, f64], i32: [i8, i16, i32, i64], u32: [u8, u16, u32, u64]);
+
+    #[cube(launch)]
+    fn sync_cube_two_phase(out: &mut Array<u32>) {
+        let mut mem = SharedMemory::<u32>::new(4usize);
+        let idx = UNIT_POS as usize;
+        mem[idx] = (idx as u32) + 1;
+        sync_cube();
+
+        if UNIT_POS == 0 {
+            let mut sum = 0u32;
+            for i in 0..4 {
+                sum += mem[i];
+            }
+            mem[0] = sum;
+        }
+        sync_cube();
+
+        out[idx] = mem[0];
+    }
+
+    #[cube(launch)]
+    fn sync_cube_in_loop(out: &mut Array<u32>) {
+        let mut mem = SharedMemory::<u32>::new(8usize);
+        let idx = UNIT_POS as usize;
+        mem[idx] = 1;
+        sync_cube();
+
+        // Tree reduction, every unit reaches the barriers in the loop the same number of times.
+        let mut stride = 4usize;
+        while stride > 0 {
+            if idx < stride {
+                mem[idx] += mem[idx + stride];
+            }
+            sync_cube();
+            stride /= 2;
+        }
+
+        out[idx] = mem[0];
+    }
+
+    #[test]
+    fn test_sync_cube_two_phase_interpreter() {
+        let client = TestRuntime::client(&Default::default());
+        let out = client.empty(4 * core::mem::size_of::<u32>());
+
+        unsafe {
+            sync_cube_two_phase::launch::<TestRuntime>(
+                &client,
+                CubeCount::new_single(),
+                CubeDim::new_1d(4),
+                ArrayArg::from_raw_parts(out.clone(), 4),
+            )
+        }
+
+        let bytes = client.read_one_unchecked(out);
+        let actual = u32::from_bytes(&bytes);
+        assert_eq!(actual, &[10u32; 4]);
+    }
+
+    #[test]
+    fn test_sync_cube_in_loop_interpreter() {
+        let client = TestRuntime::client(&Default::default());
+        let out = client.empty(8 * core::mem::size_of::<u32>());
+
+        unsafe {
+            sync_cube_in_loop::launch::<TestRuntime>(
+                &client,
+                CubeCount::new_single(),
+                CubeDim::new_1d(8),
+                ArrayArg::from_raw_parts(out.clone(), 8),
+            )
+        }
+
+        let bytes = client.read_one_unchecked(out);
+        let actual = u32::from_bytes(&bytes);
+        assert_eq!(actual, &[8u32; 8]);
+    }
+
+    #[test]
+    fn test_read_scalar_tensor_interpreter() {
+        use cubecl_core::{
+            server::CopyDescriptor,
+            zspace::{shape, strides},
+        };
+
+        let client = TestRuntime::client(&Default::default());
+        let handle = client.create_from_slice(u32::as_bytes(&[42]));
+        let descriptor =
+            CopyDescriptor::new(handle.binding(), shape![], strides![], size_of::<u32>());
+
+        let bytes = client.read_one_unchecked_tensor(descriptor);
+        assert_eq!(u32::from_bytes(&bytes), &[42]);
+    }
+}
+
+pub mod compiler;
+pub mod compute;
+pub mod device;
+pub mod runtime;
+
+pub use compiler::InterpreterCompiler;
+pub use device::InterpreterDevice;
+pub use runtime::*;
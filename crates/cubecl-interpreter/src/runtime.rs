@@ -0,0 +1,167 @@
+use crate::{
+    compiler::{InterpreterCompiler, InterpreterCompilerOptions, register_supported_types},
+    compute::server::InterpreterServer,
+    device::InterpreterDevice,
+};
+use cubecl_common::{device::DeviceService, profile::TimingMethod};
+use cubecl_core::{
+    MemoryConfiguration, Runtime,
+    client::ComputeClient,
+    device::{DeviceId, ServerUtilitiesHandle},
+    ir::{
+        DeviceProperties, HardwareProperties, MemoryDeviceProperties, TargetProperties, VectorSize,
+        features::{Features, Plane},
+    },
+    server::ServerUtilities,
+    zspace::{Shape, Strides},
+};
+use cubecl_runtime::{
+    allocator::ContiguousMemoryLayoutPolicy, config::GlobalConfig, logging::ServerLogger,
+    runtime::emulated_plane_size,
+};
+use std::sync::Arc;
+
+/// The plane width emulated when no other value is configured.
+pub const DEFAULT_PLANE_SIZE: u32 = 32;
+
+pub struct RuntimeOptions {
+    /// Configures the memory management.
+    pub memory_config: MemoryConfiguration,
+    /// Number of units grouped in a plane by the interpreter.
+    pub plane_size: u32,
+    /// Interpret kernels from the output of the full optimizer. Defaults to the `optimize`
+    /// compilation option of the global config.
+    pub optimize: bool,
+}
+
+impl Default for RuntimeOptions {
+    fn default() -> Self {
+        Self {
+            memory_config: Default::default(),
+            plane_size: DEFAULT_PLANE_SIZE,
+            optimize: GlobalConfig::get().compilation.optimize,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InterpreterRuntime;
+
+impl DeviceService for InterpreterServer {
+    fn init(_device_id: cubecl_common::device::DeviceId) -> Self {
+        create_server(RuntimeOptions::default())
+    }
+
+    fn utilities(&self) -> ServerUtilitiesHandle {
+        self.utilities() as ServerUtilitiesHandle
+    }
+}
+
+/// Initialize the client of the given device with the given options.
+///
+/// # Panics
+///
+/// If the client of the device was already initialized.
+pub fn init(
+    device: &InterpreterDevice,
+    options: RuntimeOptions,
+) -> ComputeClient<InterpreterRuntime> {
+    ComputeClient::init(device, create_server(options))
+}
+
+pub(crate) fn create_server(options: RuntimeOptions) -> InterpreterServer {
+    let plane_size = emulated_plane_size(options.plane_size);
+    let logger = cubecl_common::stub::Arc::new(ServerLogger::default());
+
+    let topology = HardwareProperties {
+        load_width: 128,
+        plane_size_min: plane_size,
+        plane_size_max: plane_size,
+        max_bindings: u32::MAX,
+        max_shared_memory_size: 64 * 1024,
+        max_cube_count: (u32::MAX, u32::MAX, u32::MAX),
+        num_cpu_cores: None,
+        max_units_per_cube: 1024,
+        max_cube_dim: (1024, 1024, 64),
+        num_streaming_multiprocessors: None,
+        num_tensor_cores: None,
+        min_tensor_cores_dim: None,
+        max_vector_size: VectorSize::MAX,
+    };
+
+    const ALIGNMENT: u64 = 8;
+
+    let mem_properties = MemoryDeviceProperties {
+        max_page_size: 1024 * 1024 * 512,
+        alignment: ALIGNMENT,
+    };
+
+    let mut device_props = DeviceProperties::new(
+        Features {
+            plane: Plane::Ops | Plane::Sync | Plane::NonUniformControlFlow,
+            unaligned_io: true,
+            ..Default::default()
+        },
+        mem_properties.clone(),
+        topology,
+        TimingMethod::System,
+    );
+    register_supported_types(&mut device_props);
+
+    let utilities = ServerUtilities::new(
+        device_props,
+        logger,
+        (),
+        ContiguousMemoryLayoutPolicy::new(ALIGNMENT as usize),
+    );
+    InterpreterServer::new(
+        mem_properties,
+        options.memory_config,
+        plane_size,
+        InterpreterCompilerOptions {
+            optimize: options.optimize,
+            unroll_loops: GlobalConfig::get().compilation.unroll_loops,
+        },
+        Arc::new(utilities),
+    )
+}
+
+impl Runtime for InterpreterRuntime {
+    type Compiler = InterpreterCompiler;
+    type Server = InterpreterServer;
+    type Device = InterpreterDevice;
+
+    fn client(device: &Self::Device) -> ComputeClient<Self> {
+        ComputeClient::load(device)
+    }
+
+    fn name(_client: &ComputeClient<Self>) -> &'static str {
+        "interpreter"
+    }
+
+    fn max_cube_count() -> (u32, u32, u32) {
+        (u32::MAX, u32::MAX, u32::MAX)
+    }
+
+    fn can_read_tensor(_shape: &Shape, _strides: &Strides) -> bool {
+        // Non-contiguous tensors are gathered on the host during the read.
+        true
+    }
+
+    fn target_properties() -> TargetProperties {
+        TargetProperties {
+            // Manual MMA isn't emulated, so the layouts are never used.
+            mma: Default::default(),
+        }
+    }
+
+    fn enumerate_devices(
+        _: u16,
+        _: &<Self::Server as cubecl_core::server::ComputeServer>::Info,
+    ) -> Vec<DeviceId> {
+        vec![DeviceId {
+            type_id: 0,
+            index_id: 0,
+        }]
+    }
+}
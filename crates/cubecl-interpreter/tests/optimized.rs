@@ -0,0 +1,67 @@
+//! Runs the runtime tests on the output of the full optimizer, lowered back to structured scopes
+//! like for the source backends.
+
+use cubecl_core::{
+    Runtime,
+    client::ComputeClient,
+    device::DeviceId,
+    ir::TargetProperties,
+    zspace::{Shape, Strides},
+};
+use cubecl_interpreter::{
+    InterpreterCompiler, InterpreterDevice, InterpreterRuntime, RuntimeOptions,
+    compute::server::InterpreterServer, init,
+};
+use std::sync::Once;
+
+/// The interpreter runtime, with its device initialized to optimize kernels before any test
+/// retrieves its client.
+#[derive(Debug, Clone)]
+pub struct OptimizedRuntime;
+
+impl Runtime for OptimizedRuntime {
+    type Compiler = InterpreterCompiler;
+    type Server = InterpreterServer;
+    type Device = InterpreterDevice;
+
+    fn client(device: &Self::Device) -> ComputeClient<Self> {
+        static INIT: Once = Once::new();
+        INIT.call_once(|| {
+            let options = RuntimeOptions {
+                optimize: true,
+                ..Default::default()
+            };
+            init(device, options);
+        });
+        ComputeClient::load(device)
+    }
+
+    fn name(_client: &ComputeClient<Self>) -> &'static str {
+        "interpreter-optimized"
+    }
+
+    fn max_cube_count() -> (u32, u32, u32) {
+        InterpreterRuntime::max_cube_count()
+    }
+
+    fn can_read_tensor(shape: &Shape, strides: &Strides) -> bool {
+        InterpreterRuntime::can_read_tensor(shape, strides)
+    }
+
+    fn target_properties() -> TargetProperties {
+        InterpreterRuntime::target_properties()
+    }
+
+    fn enumerate_devices(type_id: u16, info: &()) -> Vec<DeviceId> {
+        InterpreterRuntime::enumerate_devices(type_id, info)
+    }
+}
+
+#[allow(unexpected_cfgs)]
+mod tests {
+    pub type TestRuntime = super::OptimizedRuntime;
+
+    pub use half::f16;
+
+    cubecl_core::testgen_all!(f32: [f16, f32, f64], i32: [i8, i16, i32, i64], u32: [u8, u16, u32, u64]);
+}
@@ -26,6 +26,8 @@ pub enum Branch {
     Return,
     /// A break statement.
     Break,
+    /// A continue statement.
+    Continue,
     /// Unreachable block end (equivalent to `unreachable_unchecked()`)
     Unreachable,
 }
@@ -69,6 +71,7 @@ impl Display for Branch {
             Branch::Loop(loop_) => write!(f, "loop {}", loop_.scope),
             Branch::Return => write!(f, "return"),
             Branch::Break => write!(f, "break"),
+            Branch::Continue => write!(f, "continue"),
             Branch::Unreachable => write!(f, "unreachable"),
         }
     }
@@ -471,6 +471,18 @@ impl ConstantValue {
             StorageType::Opaque(_) => unimplemented!("Opaque constants aren't supported"),
         }
     }
+
+    /// Returns the raw bits of a constant of the packed type `ty`, with the value repeated in
+    /// every packed lane. Used by backends that represent packed types as plain integers.
+    pub fn packed_bits(&self, ty: StorageType) -> u64 {
+        match ty {
+            StorageType::Packed(ElemType::Float(FloatKind::E2M1), 2) => {
+                let bits = e2m1::from_f64(self.as_f64()).to_bits() as u64 & 0xF;
+                bits | (bits << 4)
+            }
+            other => unimplemented!("Unsupported packed type {other}"),
+        }
+    }
 }
 
 impl Display for ConstantValue {
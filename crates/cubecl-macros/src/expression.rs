@@ -7,7 +7,7 @@ use syn::{
 
 use crate::{
     operator::Operator,
-    scope::{Context, ManagedVar, Scope},
+    scope::{Context, LoopExits, ManagedVar, Scope},
     statement::Statement,
 };
 
@@ -80,8 +80,12 @@ pub enum Expression {
         ident: Ident,
         args: Vec<Expression>,
     },
-    Continue(Span),
-    Return(Span),
+    Continue,
+    Return {
+        expr: Option<Box<Expression>>,
+        nested: bool,
+        span: Span,
+    },
     ForLoop {
         range: Box<Expression>,
         unroll: Option<Box<Expression>>,
@@ -89,10 +93,12 @@ pub enum Expression {
         var_ty: Option<syn::Type>,
         block: Block,
         scope: Scope,
+        exits: LoopExits,
     },
     Loop {
         block: Block,
         scope: Scope,
+        exits: LoopExits,
     },
     If {
         condition: Box<Expression>,
@@ -269,11 +269,29 @@ impl Expression {
                 let path = frontend_path();
                 quote![#path::branch::break_expand(scope);]
             }
-            Expression::Continue(span) => error!(*span, "Continue not supported yet"),
-            Expression::Return(span) => error!(
-                *span,
-                "Return not supported yet. Consider using the terminate!() macro instead."
-            ),
+            Expression::Continue => {
+                let path = frontend_path();
+                quote![#path::branch::continue_expand(scope);]
+            }
+            Expression::Return { expr, nested, span } => {
+                // Returns from different paths are merged at runtime, so literals can't stay
+                // comptime.
+                let value = match expr {
+                    Some(expr) if expr.as_const_primitive(context).is_some() => {
+                        expr.to_tokens(context)
+                    }
+                    Some(expr) => expr
+                        .as_const(context)
+                        .unwrap_or_else(|| expr.to_tokens(context)),
+                    None => quote![()],
+                };
+                quote_spanned! {*span=>
+                    {
+                        let _value = #value;
+                        __cube_return.expand_return(scope, _value, #nested);
+                    }
+                }
+            }
             Expression::Cast { from, to } => {
                 let cast = prelude_type("Cast");
                 let from = from.to_tokens(context);
@@ -290,6 +308,7 @@ impl Expression {
                 var_ty,
                 block,
                 scope,
+                exits,
             } => {
                 let for_ty = frontend_type("branch");
 
@@ -300,20 +319,37 @@ impl Expression {
                     .unwrap_or(quote![false]);
                 let block = context.in_fn_mut(scope, |ctx| block.to_tokens(ctx));
                 let var_ty = var_ty.as_ref().map(|it| quote![: #it]);
+                let continues = exits.continues;
+                let nested = exits.nested;
+                let propagate = exits
+                    .returns
+                    .then(|| quote![__cube_return.expand_propagate(scope, #nested);]);
 
                 quote! {
                     {
                         let _range = #range;
                         let _unroll = #unroll;
-                        #for_ty::for_expand(scope, _range, _unroll, |scope, #var_name #var_ty| #block);
+                        #for_ty::for_expand(scope, _range, _unroll, #continues, |scope, #var_name #var_ty| #block);
                     }
+                    #propagate
                 }
             }
-            Expression::Loop { block, scope } => {
+            Expression::Loop {
+                block,
+                scope,
+                exits,
+            } => {
                 let loop_ty = frontend_type("branch");
                 let block = context.in_fn_mut(scope, |ctx| block.to_tokens(ctx));
+                let nested = exits.nested;
+                let propagate = exits
+                    .returns
+                    .then(|| quote![__cube_return.expand_propagate(scope, #nested);]);
 
-                quote![#loop_ty::loop_expand(scope, |scope| #block);]
+                quote! {
+                    #loop_ty::loop_expand(scope, |scope| #block);
+                    #propagate
+                }
             }
             Expression::If {
                 condition,
@@ -2,16 +2,19 @@ use std::collections::HashMap;
 
 use darling::usage::{CollectLifetimes as _, CollectTypeParams as _, GenericsExt as _, Purpose};
 use inflections::case::to_snake_case;
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::{ToTokens, format_ident, quote, quote_spanned};
 use syn::{Ident, TypeParamBound};
 
 use crate::{
+    expression::{Block, Expression},
     parse::kernel::{
         DefinedGeneric, KernelBody, KernelFn, KernelParam, KernelReturns, KernelSignature, Launch,
         strip_ref,
     },
     paths::{frontend_type, prelude_type},
+    scope::Context,
+    statement::Statement,
 };
 
 impl KernelFn {
@@ -19,6 +22,9 @@ impl KernelFn {
         let vis = &self.vis;
         let sig = &self.sig;
         let body = match &self.body {
+            KernelBody::Block(block) if self.context.returns > 0 => {
+                &early_return_body(block, &mut self.context)
+            }
             KernelBody::Block(block) => &block.to_tokens(&mut self.context),
             KernelBody::Verbatim(tokens) => tokens,
         };
@@ -86,6 +92,48 @@ impl KernelFn {
     }
 }
 
+/// Expand a body containing `return` statements. The body is expanded in its own scope, where
+/// every path ends with a return.
+fn early_return_body(block: &Block, context: &mut Context) -> TokenStream {
+    let return_expand = frontend_type("ReturnExpand");
+    let nested = context.nested_return;
+
+    let inner: Vec<_> = block.inner.iter().map(|it| it.to_tokens(context)).collect();
+    let ends_with_return = matches!(
+        block.inner.last(),
+        Some(Statement::Expression { expression, .. })
+            if matches!(**expression, Expression::Return { .. })
+    );
+    let tail = match &block.ret {
+        Some(ret) if matches!(**ret, Expression::Return { .. }) => Some(ret.clone()),
+        Some(ret) => Some(Box::new(Expression::Return {
+            expr: Some(ret.clone()),
+            nested: false,
+            span: Span::call_site(),
+        })),
+        None if ends_with_return => None,
+        None => Some(Box::new(Expression::Return {
+            expr: None,
+            nested: false,
+            span: Span::call_site(),
+        })),
+    };
+    let tail = tail.map(|tail| tail.to_tokens(context));
+
+    quote! {
+        {
+            let mut __cube_return = #return_expand::new(scope, #nested);
+            let mut __cube_return_body = __cube_return.body(scope);
+            {
+                let scope = &mut __cube_return_body;
+                #(#inner)*
+                #tail
+            }
+            __cube_return.finish(scope, __cube_return_body)
+        }
+    }
+}
+
 fn trait_imports() -> TokenStream {
     let into_runtime = prelude_type("IntoRuntime");
     let assign = prelude_type("Assign");
@@ -44,15 +44,17 @@ pub fn expand_for_loop(
         return expand_for_in_loop(var.ident, right, for_loop.body, context);
     }
 
-    let (block, scope) = context.in_scope(|context| {
-        context.push_variable(
-            var.ident.clone(),
-            var.ty.clone(),
-            false,
-            var.is_ref,
-            var.is_mut,
-        );
-        Block::from_block(for_loop.body, context)
+    let ((block, scope), exits) = context.in_loop(unroll.is_some(), |context| {
+        context.in_scope(|context| {
+            context.push_variable(
+                var.ident.clone(),
+                var.ty.clone(),
+                false,
+                var.is_ref,
+                var.is_mut,
+            );
+            Block::from_block(for_loop.body, context)
+        })
     })?;
 
     Ok(Expression::ForLoop {
@@ -62,6 +64,7 @@ pub fn expand_for_loop(
         var_ty: var.ty,
         block,
         scope,
+        exits,
     })
 }
 
@@ -71,11 +74,13 @@ fn expand_for_in_loop(
     block: syn::Block,
     context: &mut Context,
 ) -> syn::Result<Expression> {
-    let statements = block
-        .stmts
-        .into_iter()
-        .map(|stmt| Statement::from_stmt(stmt, context))
-        .collect::<Result<Vec<_>, _>>()?;
+    let statements = context.in_comptime_loop(|context| {
+        block
+            .stmts
+            .into_iter()
+            .map(|stmt| Statement::from_stmt(stmt, context))
+            .collect::<Result<Vec<_>, _>>()
+    })?;
 
     let right = right.to_tokens(context);
     let statements = statements.into_iter().map(|it| it.to_tokens(context));
@@ -90,8 +95,14 @@ fn expand_for_in_loop(
 }
 
 pub fn expand_loop(loop_expr: ExprLoop, context: &mut Context) -> syn::Result<Expression> {
-    let (block, scope) = context.in_scope(|ctx| Block::from_block(loop_expr.body, ctx))?;
-    Ok(Expression::Loop { block, scope })
+    let ((block, scope), exits) = context.in_loop(false, |ctx| {
+        ctx.in_scope(|ctx| Block::from_block(loop_expr.body, ctx))
+    })?;
+    Ok(Expression::Loop {
+        block,
+        scope,
+        exits,
+    })
 }
 
 pub fn expand_if(if_expr: ExprIf, context: &mut Context) -> syn::Result<Expression> {
@@ -183,8 +183,20 @@ impl Expression {
             Expr::Const(block) => Expression::Verbatim {
                 tokens: quote![#block],
             },
-            Expr::Continue(cont) => Expression::Continue(cont.span()),
-            Expr::Return(ret) => Expression::Return(ret.span()),
+            Expr::Continue(cont) => {
+                context.register_continue(cont.span())?;
+                Expression::Continue
+            }
+            Expr::Return(ret) => {
+                let span = ret.span();
+                let nested = context.register_return(span)?;
+                let expr = ret
+                    .expr
+                    .map(|expr| Expression::from_expr(*expr, context))
+                    .transpose()?
+                    .map(Box::new);
+                Expression::Return { expr, nested, span }
+            }
             Expr::ForLoop(for_loop) => expand_for_loop(for_loop, context)?,
             Expr::Loop(loop_expr) => expand_loop(loop_expr, context)?,
             Expr::If(if_expr) if is_let(&if_expr.cond) => expand_if_let(if_expr, context)?,
@@ -393,11 +405,13 @@ impl Expression {
                 inner: Box::new(Expression::from_expr(*reference.expr, context)?),
             },
             Expr::Closure(expr) => {
-                let (body, scope) = context.in_scope(|ctx| {
-                    for arg in expr.inputs.iter() {
-                        add_variables_from_pat(arg, ctx);
-                    }
-                    Expression::from_expr(*expr.body, ctx)
+                let (body, scope) = context.in_closure(|ctx| {
+                    ctx.in_scope(|ctx| {
+                        for arg in expr.inputs.iter() {
+                            add_variables_from_pat(arg, ctx);
+                        }
+                        Expression::from_expr(*expr.body, ctx)
+                    })
                 })?;
                 let body = Box::new(body);
                 let params = expr.inputs.into_iter().collect();
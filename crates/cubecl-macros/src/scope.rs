@@ -4,6 +4,7 @@ use std::{
     sync::atomic::{AtomicUsize, Ordering},
 };
 
+use proc_macro2::Span;
 use quote::format_ident;
 use syn::{Ident, Type, parse_quote};
 
@@ -46,6 +47,17 @@ pub const KEYWORDS: [&str; 31] = [
 pub type Scope = usize;
 type ManagedScope = Vec<ManagedVar>;
 
+/// How the body of a loop exits early.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LoopExits {
+    /// Whether the body returns from the function.
+    pub returns: bool,
+    /// Whether the body continues to the next iteration.
+    pub continues: bool,
+    /// Whether the loop is itself nested in a loop of the function.
+    pub nested: bool,
+}
+
 #[derive(Clone, Debug)]
 pub struct Context {
     pub return_type: Type,
@@ -53,6 +65,17 @@ pub struct Context {
     level: usize,
     mut_scope_idx: usize,
     pub debug_symbols: bool,
+    /// The number of runtime loops around the expression being parsed.
+    loop_depth: usize,
+    /// Whether the innermost loop may be unrolled, leaving `continue` without a target.
+    unrolled: bool,
+    /// Whether the innermost loop contains a `continue`.
+    continues: bool,
+    in_closure: bool,
+    /// The number of `return` statements in the function.
+    pub returns: usize,
+    /// Whether the function returns from inside one of its loops.
+    pub nested_return: bool,
 }
 
 impl Context {
@@ -78,6 +101,12 @@ impl Context {
             level: 0,
             mut_scope_idx: 0,
             debug_symbols,
+            loop_depth: 0,
+            unrolled: false,
+            continues: false,
+            in_closure: false,
+            returns: 0,
+            nested_return: false,
         }
     }
 
@@ -126,6 +155,80 @@ impl Context {
         Ok((res, self.scopes.len()))
     }
 
+    /// Parse the body of a loop, `unrolled` if it may be unrolled at expansion.
+    pub fn in_loop<T>(
+        &mut self,
+        unrolled: bool,
+        with: impl FnOnce(&mut Self) -> syn::Result<T>,
+    ) -> syn::Result<(T, LoopExits)> {
+        let returns = self.returns;
+        let outer = (
+            replace(&mut self.unrolled, unrolled),
+            replace(&mut self.continues, false),
+        );
+        let nested = self.loop_depth > 0;
+        self.loop_depth += 1;
+        let res = with(self);
+        self.loop_depth -= 1;
+        let continues = self.continues;
+        (self.unrolled, self.continues) = outer;
+        let exits = LoopExits {
+            returns: self.returns > returns,
+            continues,
+            nested,
+        };
+        Ok((res?, exits))
+    }
+
+    /// Parse the body of a comptime loop, which is always unrolled.
+    pub fn in_comptime_loop<T>(
+        &mut self,
+        with: impl FnOnce(&mut Self) -> syn::Result<T>,
+    ) -> syn::Result<T> {
+        let unrolled = replace(&mut self.unrolled, true);
+        let res = with(self);
+        self.unrolled = unrolled;
+        res
+    }
+
+    /// Parse the body of a closure, where `return` would return from the closure itself.
+    pub fn in_closure<T>(
+        &mut self,
+        with: impl FnOnce(&mut Self) -> syn::Result<T>,
+    ) -> syn::Result<T> {
+        let (in_closure, loop_depth) = (replace(&mut self.in_closure, true), self.loop_depth);
+        self.loop_depth = 0;
+        let res = with(self);
+        self.in_closure = in_closure;
+        self.loop_depth = loop_depth;
+        res
+    }
+
+    /// Register a `return` statement, returning whether it's nested in a loop of the function.
+    pub fn register_return(&mut self, span: Span) -> syn::Result<bool> {
+        if self.in_closure {
+            return Err(syn::Error::new(span, "Return not supported in closures"));
+        }
+
+        let nested = self.loop_depth > 0;
+        self.returns += 1;
+        self.nested_return |= nested;
+        Ok(nested)
+    }
+
+    /// Register a `continue` statement, which must target a loop that isn't unrolled.
+    pub fn register_continue(&mut self, span: Span) -> syn::Result<()> {
+        if self.unrolled {
+            return Err(syn::Error::new(
+                span,
+                "Continue not supported in unrolled loops",
+            ));
+        }
+
+        self.continues = true;
+        Ok(())
+    }
+
     /// Mutable closures (for loops) have different behaviour because outer vars
     /// must be cloned
     pub fn in_fn_mut<T>(&mut self, scope: &Scope, with: impl FnOnce(&mut Self) -> T) -> T {
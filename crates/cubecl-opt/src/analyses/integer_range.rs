@@ -21,9 +21,9 @@ pub struct Range {
 /// optimization passes. Reasons for integers being bounded but not constant might be: the modulo
 /// operator (bounds it to `0..m`), or `UNIT_POS` (bounded by `CubeDim`). Bounds can be transferred
 /// between simple arithmetic, so we can determine the possible range of a good number of variables.
-/// This is currently only used in index bound analysis.
+/// This is currently only used to eliminate bounds checks, see
+/// [`EliminateBoundsChecks`](crate::passes::EliminateBoundsChecks).
 #[derive(Debug, Default)]
-#[allow(unused)]
 pub struct Ranges {
     int_ranges: HashMap<VarId, Range>,
 }
@@ -196,9 +196,13 @@ mod range_ops {
         fn add(self, rhs: Self) -> Self::Output {
             let lower_bound = self.lower_bound.zip(rhs.lower_bound);
             let upper_bound = self.upper_bound.zip(rhs.upper_bound);
+            let upper_bound = upper_bound.and_then(|(lhs, rhs)| lhs.checked_add(rhs));
             Self {
-                lower_bound: lower_bound.map(|(lhs, rhs)| lhs + rhs),
-                upper_bound: upper_bound.map(|(lhs, rhs)| lhs + rhs),
+                // The addition might wrap around if the upper bound overflows
+                lower_bound: lower_bound
+                    .filter(|_| upper_bound.is_some())
+                    .map(|(lhs, rhs)| lhs + rhs),
+                upper_bound,
             }
         }
     }
@@ -207,11 +211,16 @@ mod range_ops {
         type Output = Range;
 
         fn sub(self, rhs: Self) -> Self::Output {
-            let lower_bound = self.lower_bound.zip(rhs.lower_bound);
-            let upper_bound = self.upper_bound.zip(rhs.upper_bound);
+            // The subtraction might wrap around unless the smallest `lhs` is larger than the
+            // largest `rhs`.
+            let lower_bound = self
+                .lower_bound
+                .zip(rhs.upper_bound)
+                .and_then(|(lhs, rhs)| lhs.checked_sub(rhs));
+            let upper_bound = self.upper_bound.zip(rhs.lower_bound);
             Self {
-                lower_bound: lower_bound.map(|(lhs, rhs)| lhs - rhs),
-                upper_bound: upper_bound.map(|(lhs, rhs)| lhs - rhs),
+                lower_bound,
+                upper_bound: lower_bound.and(upper_bound).map(|(lhs, rhs)| lhs - rhs),
             }
         }
     }
@@ -222,9 +231,13 @@ mod range_ops {
         fn mul(self, rhs: Self) -> Self::Output {
             let lower_bound = self.lower_bound.zip(rhs.lower_bound);
             let upper_bound = self.upper_bound.zip(rhs.upper_bound);
+            let upper_bound = upper_bound.and_then(|(lhs, rhs)| lhs.checked_mul(rhs));
             Self {
-                lower_bound: lower_bound.map(|(lhs, rhs)| lhs * rhs),
-                upper_bound: upper_bound.map(|(lhs, rhs)| lhs * rhs),
+                // The multiplication might wrap around if the upper bound overflows
+                lower_bound: lower_bound
+                    .filter(|_| upper_bound.is_some())
+                    .map(|(lhs, rhs)| lhs * rhs),
+                upper_bound,
             }
         }
     }
@@ -233,10 +246,12 @@ mod range_ops {
         type Output = Range;
 
         fn div(self, rhs: Self) -> Self::Output {
-            let lower_bound = self.lower_bound.zip(rhs.lower_bound);
-            let upper_bound = self.upper_bound.zip(rhs.upper_bound);
+            let lower_bound = self.lower_bound.zip(rhs.upper_bound);
+            let upper_bound = self.upper_bound.zip(rhs.lower_bound);
             Self {
-                lower_bound: lower_bound.map(|(lhs, rhs)| lhs.checked_div(rhs).unwrap_or(lhs)),
+                lower_bound: lower_bound
+                    .map(|(lhs, rhs)| lhs.checked_div(rhs).unwrap_or(lhs))
+                    .or(self.lower_bound.map(|_| 0)),
                 upper_bound: upper_bound.map(|(lhs, rhs)| lhs.checked_div(rhs).unwrap_or(lhs)),
             }
         }
@@ -246,13 +261,16 @@ mod range_ops {
         type Output = Range;
 
         fn rem(self, rhs: Self) -> Self::Output {
-            if rhs.lower_bound.is_none() || rhs.upper_bound.is_none() {
-                return self;
-            }
-            let rhs_upper = rhs.upper_bound.unwrap();
+            let rhs_upper = match rhs.upper_bound.and_then(|upper| upper.checked_sub(1)) {
+                Some(upper) => upper,
+                None => return self,
+            };
             Range {
                 lower_bound: Some(0),
-                upper_bound: Some(rhs_upper - 1),
+                upper_bound: Some(match self.upper_bound {
+                    Some(upper) => upper.min(rhs_upper),
+                    None => rhs_upper,
+                }),
             }
         }
     }
@@ -98,6 +98,15 @@ impl Optimizer {
                 self.program.add_edge(current_block, *loop_break, 0);
                 ControlFlowAction::AbortBlock
             }
+            Branch::Continue => {
+                let current_block = self.current_block.take().unwrap();
+                let loop_continue = self
+                    .loop_continue
+                    .back()
+                    .expect("Can't continue outside loop");
+                self.program.add_edge(current_block, *loop_continue, 0);
+                ControlFlowAction::AbortBlock
+            }
         }
     }
 
@@ -249,18 +258,21 @@ impl Optimizer {
         let body = self.program.add_node(BasicBlock::default());
         let next = self.program.add_node(BasicBlock::default());
 
+        let continue_target = self.program.add_node(BasicBlock::default());
+        self.program[continue_target]
+            .block_use
+            .push(BlockUse::ContinueTarget);
+
         self.program.add_edge(header, body, 0);
 
         self.loop_break.push_back(next);
+        self.loop_continue.push_back(continue_target);
 
         self.current_block = Some(body);
         self.parse_scope(loop_.scope);
-        let continue_target = self.program.add_node(BasicBlock::default());
-        self.program[continue_target]
-            .block_use
-            .push(BlockUse::ContinueTarget);
 
         self.loop_break.pop_back();
+        self.loop_continue.pop_back();
 
         if let Some(current_block) = self.current_block {
             self.program.add_edge(current_block, continue_target, 0);
@@ -300,24 +312,44 @@ impl Optimizer {
         self.program.add_edge(header, body, 0);
         self.program.add_edge(header, next, 0);
 
+        // Only used if the body continues, since the increment must then be shared by every
+        // path that loops back to the header.
+        let continue_block = self.program.add_node(BasicBlock::default());
+
         self.loop_break.push_back(next);
+        self.loop_continue.push_back(continue_block);
 
         self.current_block = Some(body);
         self.parse_scope(range_loop.scope);
 
         self.loop_break.pop_back();
+        self.loop_continue.pop_back();
 
-        let current_block = self.current_block.expect("For loop has no loopback path");
+        let has_continue = self
+            .program
+            .edges_directed(continue_block, Direction::Incoming)
+            .next()
+            .is_some();
 
-        let continue_target = if self.program[current_block]
-            .block_use
-            .contains(&BlockUse::Merge)
-        {
-            let target = self.program.add_node(BasicBlock::default());
-            self.program.add_edge(current_block, target, 0);
-            target
+        let continue_target = if has_continue {
+            if let Some(current_block) = self.current_block {
+                self.program.add_edge(current_block, continue_block, 0);
+            }
+            continue_block
         } else {
-            current_block
+            self.program.remove_node(continue_block);
+            let current_block = self.current_block.expect("For loop has no loopback path");
+
+            if self.program[current_block]
+                .block_use
+                .contains(&BlockUse::Merge)
+            {
+                let target = self.program.add_node(BasicBlock::default());
+                self.program.add_edge(current_block, target, 0);
+                target
+            } else {
+                current_block
+            }
         };
 
         self.program.add_edge(continue_target, header, 0);
@@ -351,7 +383,7 @@ impl Optimizer {
                 merge: next,
             };
         }
-        self.program[current_block]
+        self.program[continue_target]
             .ops
             .borrow_mut()
             .push(Instruction::new(
@@ -20,7 +20,8 @@
 //! # Representing [`PhiInstruction`] in non-SSA languages
 //!
 //! Phi instructions can be simulated by generating a mutable variable for each phi, then assigning
-//! `value` to it in each relevant `block`.
+//! `value` to it in each relevant `block`. [`Optimizer::structured_scope`] does this for backends
+//! that emit structured source code, by lowering the optimized graph back to a [`Scope`].
 //!
 
 #![allow(unknown_lints, unnecessary_transmutes)]
@@ -41,9 +42,10 @@ use cubecl_ir::{
 use gvn::GvnPass;
 use passes::{
     CompositeMerge, ConstEval, ConstOperandSimplify, CopyTransform, DisaggregateArray,
-    EliminateConstBranches, EliminateDeadBlocks, EliminateDeadPhi, EliminateUnusedVariables,
-    EmptyBranchToSelect, InlineAssignments, MergeBlocks, MergeSameExpressions, OptimizerPass,
-    ReduceStrength, RemoveIndexScalar,
+    EliminateBoundsChecks, EliminateConstBranches, EliminateDeadBlocks, EliminateDeadPhi,
+    EliminateUnusedVariables, EmptyBranchToSelect, InlineAssignments, LoopInvariantCodeMotion,
+    MergeBlocks, MergeSameExpressions, OptimizerPass, PartialUnroll, ReduceStrength,
+    RemoveIndexScalar,
 };
 use petgraph::{
     Direction,
@@ -60,12 +62,14 @@ mod gvn;
 mod instructions;
 mod passes;
 mod phi_frontiers;
+mod structured;
 mod transformers;
 mod version;
 
 pub use analyses::uniformity::Uniformity;
 pub use block::*;
 pub use control_flow::*;
+pub use passes::UnrollOptions;
 pub use petgraph::graph::{EdgeIndex, NodeIndex};
 pub use transformers::*;
 pub use version::PhiInstruction;
@@ -142,6 +146,8 @@ pub struct Optimizer {
     current_block: Option<NodeIndex>,
     /// The current loop's break target
     loop_break: VecDeque<NodeIndex>,
+    /// The current loop's continue target
+    loop_continue: VecDeque<NodeIndex>,
     /// The single return block
     pub ret: NodeIndex,
     /// Root scope to allocate variables on
@@ -150,6 +156,8 @@ pub struct Optimizer {
     pub(crate) cube_dim: CubeDim,
     pub(crate) transformers: Vec<Rc<dyn IrTransformer>>,
     pub(crate) processors: Rc<Vec<Box<dyn Processor>>>,
+    /// Unroll loops with a known trip count, if enabled
+    pub(crate) unroll: Option<UnrollOptions>,
 }
 
 // Needed for WGPU server
@@ -163,12 +171,14 @@ impl Default for Optimizer {
             allocator: Default::default(),
             current_block: Default::default(),
             loop_break: Default::default(),
+            loop_continue: Default::default(),
             ret: Default::default(),
             root_scope: Scope::root(false),
             cube_dim: CubeDim::new_1d(1),
             analysis_cache: Default::default(),
             transformers: Default::default(),
             processors: Default::default(),
+            unroll: None,
         }
     }
 }
@@ -181,6 +191,18 @@ impl Optimizer {
         cube_dim: CubeDim,
         transformers: Vec<Rc<dyn IrTransformer>>,
         processors: Vec<Box<dyn Processor>>,
+    ) -> Self {
+        Self::with_unroll(expand, cube_dim, transformers, processors, None)
+    }
+
+    /// Create a new optimizer like [`Optimizer::new`], partially unrolling the loops with a trip
+    /// count known at compile time if `unroll` is set.
+    pub fn with_unroll(
+        expand: Scope,
+        cube_dim: CubeDim,
+        transformers: Vec<Rc<dyn IrTransformer>>,
+        processors: Vec<Box<dyn Processor>>,
+        unroll: Option<UnrollOptions>,
     ) -> Self {
         let mut opt = Self {
             root_scope: expand.clone(),
@@ -188,6 +210,7 @@ impl Optimizer {
             allocator: expand.allocator.clone(),
             transformers,
             processors: Rc::new(processors),
+            unroll,
             ..Default::default()
         };
         opt.run_opt();
@@ -242,6 +265,25 @@ impl Optimizer {
             self.apply_post_ssa_passes();
         }
 
+        let bounds_count = AtomicCounter::new(0);
+        log::debug!("Applying {}", EliminateBoundsChecks.name());
+        EliminateBoundsChecks.apply_post_ssa(self, bounds_count.clone());
+        if bounds_count.get() > 0 {
+            self.apply_post_ssa_passes();
+        }
+
+        let loop_count = AtomicCounter::new(0);
+        log::debug!("Applying {}", LoopInvariantCodeMotion.name());
+        LoopInvariantCodeMotion.apply_post_ssa(self, loop_count.clone());
+        if let Some(options) = self.unroll {
+            let mut unroll = PartialUnroll { options };
+            log::debug!("Applying {}", unroll.name());
+            unroll.apply_post_ssa(self, loop_count.clone());
+        }
+        if loop_count.get() > 0 {
+            self.apply_post_ssa_passes();
+        }
+
         self.split_free();
         self.analysis::<SharedLiveness>();
 
@@ -418,7 +460,8 @@ impl Optimizer {
             });
         }
 
-        let is_break = processed.instructions.contains(&Branch::Break.into());
+        let is_break = processed.instructions.contains(&Branch::Break.into())
+            || processed.instructions.contains(&Branch::Continue.into());
 
         for mut instruction in processed.instructions {
             let mut removed = false;
@@ -494,10 +537,19 @@ pub fn visit_noop(_opt: &mut Optimizer, _var: &mut Variable) {}
 mod test {
     use cubecl_core as cubecl;
     use cubecl_core::cube;
+    use cubecl_core::post_processing::checked_io::CheckedIoProcessor;
     use cubecl_core::prelude::*;
-    use cubecl_ir::{ElemType, ManagedVariable, Type, UIntKind, Variable, VariableKind};
+    use std::collections::HashSet;
+
+    use cubecl_ir::{
+        Arithmetic, Branch, Comparison, ElemType, Id, Instruction, ManagedVariable, Operation,
+        Operator, Type, UIntKind, Variable, VariableKind,
+    };
 
-    use crate::Optimizer;
+    use crate::{
+        AtomicCounter, BlockUse, Optimizer, OptimizerBuilder, UnrollOptions,
+        passes::{LoopInvariantCodeMotion, OptimizerPass, find_loops},
+    };
 
     #[allow(unused)]
     #[cube(launch)]
@@ -533,4 +585,308 @@ mod test {
         let opt = Optimizer::new(ctx, CubeDim::new_1d(1), vec![], vec![]);
         println!("{opt}")
     }
+
+    /// A root scope with `u32` indices.
+    fn root_scope() -> Scope {
+        let mut ctx = Scope::root(false);
+        ctx.register_type::<usize>(ElemType::UInt(UIntKind::U32).into());
+        ctx
+    }
+
+    fn u32_variable(kind: VariableKind) -> ManagedVariable {
+        ManagedVariable::Plain(Variable::new(
+            kind,
+            Type::scalar(ElemType::UInt(UIntKind::U32)),
+        ))
+    }
+
+    fn scalar(id: Id) -> NativeExpand<u32> {
+        u32_variable(VariableKind::GlobalScalar(id)).into()
+    }
+
+    fn input_array(id: Id) -> NativeExpand<Array<u32>> {
+        u32_variable(VariableKind::GlobalInputArray(id)).into()
+    }
+
+    fn output_array(id: Id) -> NativeExpand<Array<u32>> {
+        u32_variable(VariableKind::GlobalOutputArray(id)).into()
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn continue_kernel(x: u32, cond: u32, out: &mut Array<u32>) {
+        for i in 0..x {
+            if i == cond {
+                continue;
+            }
+            out[i as usize] = i;
+        }
+    }
+
+    #[test_log::test]
+    fn test_continue_shares_increment() {
+        let mut ctx = root_scope();
+        continue_kernel::expand(&mut ctx, scalar(0), scalar(1), output_array(0));
+        let opt = Optimizer::new(ctx, CubeDim::new_1d(1), vec![], vec![]);
+
+        let continue_target = opt
+            .node_ids()
+            .into_iter()
+            .find(|node| {
+                opt.program[*node]
+                    .block_use
+                    .contains(&BlockUse::ContinueTarget)
+            })
+            .expect("Loop should have a continue target");
+        assert_eq!(opt.predecessors(continue_target).len(), 2);
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn bounds_kernel(input: &Array<u32>, out: &mut Array<u32>) {
+        for i in 0..input.buffer_len() {
+            if i < out.buffer_len() {
+                out[i] = input[i];
+            }
+        }
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn unproven_bounds_kernel(input: &Array<u32>, out: &mut Array<u32>) {
+        for i in 0..input.len() {
+            out[i] = input[i];
+        }
+    }
+
+    fn checked_opt(expand: impl FnOnce(&mut Scope)) -> Optimizer {
+        let mut ctx = root_scope();
+        expand(&mut ctx);
+        let checked_io = CheckedIoProcessor::new(ExecutionMode::Checked, "bounds".into());
+        Optimizer::new(ctx, CubeDim::new_1d(1), vec![], vec![Box::new(checked_io)])
+    }
+
+    fn count_ops(opt: &Optimizer, filter: impl Fn(&Operation) -> bool) -> usize {
+        opt.node_ids()
+            .into_iter()
+            .flat_map(|node| {
+                opt.program[node]
+                    .ops
+                    .borrow()
+                    .values()
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .filter(|op| filter(&op.operation))
+            .count()
+    }
+
+    fn is_bounds_check(op: &Operation) -> bool {
+        matches!(
+            op,
+            Operation::Comparison(Comparison::Lower(_))
+                | Operation::Operator(Operator::Select(_))
+                | Operation::Arithmetic(Arithmetic::Min(_))
+        )
+    }
+
+    #[test_log::test]
+    fn test_eliminate_bounds_checks() {
+        let opt = checked_opt(|ctx| {
+            bounds_kernel::expand(ctx, input_array(0), output_array(0));
+        });
+
+        // Only the loop and the explicit comparisons are left
+        assert_eq!(count_ops(&opt, is_bounds_check), 2);
+    }
+
+    #[test_log::test]
+    fn test_keep_unproven_bounds_checks() {
+        let opt = checked_opt(|ctx| {
+            unproven_bounds_kernel::expand(ctx, input_array(0), output_array(0));
+        });
+
+        // `len` can be larger than `buffer_len`, so the checks must be kept
+        assert!(count_ops(&opt, is_bounds_check) > 1);
+        assert_eq!(
+            count_ops(&opt, |op| matches!(
+                op,
+                Operation::Operator(Operator::Select(_))
+            )),
+            1
+        );
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn invariant_kernel(x: u32, y: u32, out: &mut Array<u32>) {
+        for i in 0..x {
+            out[i as usize] = i * (x * y);
+        }
+    }
+
+    #[test_log::test]
+    fn test_hoist_loop_invariant() {
+        let mut ctx = root_scope();
+        invariant_kernel::expand(&mut ctx, scalar(0), scalar(1), output_array(0));
+        let mut opt = Optimizer::shared_only(ctx, CubeDim::new_1d(1));
+        let changes = AtomicCounter::new(0);
+        LoopInvariantCodeMotion.apply_post_ssa(&mut opt, changes.clone());
+        assert!(changes.get() > 0);
+
+        let is_invariant = |op: &Operation| match op {
+            Operation::Arithmetic(Arithmetic::Mul(op)) => {
+                matches!(op.lhs.kind, VariableKind::GlobalScalar(_))
+                    && matches!(op.rhs.kind, VariableKind::GlobalScalar(_))
+            }
+            _ => false,
+        };
+        let loop_info = &find_loops(&opt)[0];
+        for block in &loop_info.blocks {
+            let ops = opt.program[*block].ops.borrow();
+            assert!(!ops.values().any(|inst| is_invariant(&inst.operation)));
+        }
+        let preheader = loop_info.preheader.unwrap();
+        let ops = opt.program[preheader].ops.borrow();
+        assert!(ops.values().any(|inst| is_invariant(&inst.operation)));
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn unroll_kernel(out: &mut Array<u32>) {
+        let mut acc = 0;
+        for i in 0..16u32 {
+            acc += i;
+        }
+        out[0] = acc;
+    }
+
+    #[test_log::test]
+    fn test_partial_unroll() {
+        let mut ctx = root_scope();
+        unroll_kernel::expand(&mut ctx, output_array(0));
+        let opt = OptimizerBuilder::default()
+            .with_unroll(UnrollOptions::default())
+            .optimize(ctx, CubeDim::new_1d(1));
+
+        // The accumulation and the increment are repeated 8 times
+        let is_add = |op: &Operation| matches!(op, Operation::Arithmetic(Arithmetic::Add(_)));
+        assert_eq!(count_ops(&opt, is_add), 16);
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn structured_kernel(x: u32, cond: u32, out: &mut Array<u32>) {
+        let mut acc = 0;
+        let mut last = 0;
+        for i in 0..x {
+            if i == cond {
+                continue;
+            }
+            if i > 100 {
+                break;
+            }
+            last = i * 2;
+            acc += last;
+        }
+        out[0] = acc;
+        out[1] = last;
+    }
+
+    #[test_log::test]
+    fn test_structured_scope() {
+        let mut ctx = root_scope();
+        structured_kernel::expand(&mut ctx, scalar(0), scalar(1), output_array(0));
+        let mut opt = Optimizer::new(ctx, CubeDim::new_1d(1), vec![], vec![]);
+        let scope = opt.structured_scope();
+
+        let loops = scope
+            .instructions
+            .iter()
+            .filter(|inst| matches!(inst.operation, Operation::Branch(Branch::Loop(_))))
+            .count();
+        assert_eq!(loops, 1);
+        let visible = scope.locals.iter().copied().collect();
+        assert_scoped(&mut opt, &scope.instructions, &visible);
+    }
+
+    /// Asserts that every local is declared, or defined in the same or an enclosing scope before
+    /// it's read.
+    fn assert_scoped(
+        opt: &mut Optimizer,
+        instructions: &[Instruction],
+        visible: &HashSet<Variable>,
+    ) {
+        let mut visible = visible.clone();
+        let is_visible = |visible: &HashSet<Variable>, var: &Variable| match var.kind {
+            VariableKind::Versioned { .. } => false,
+            VariableKind::LocalConst { .. } | VariableKind::LocalMut { .. } => {
+                visible.contains(var)
+            }
+            _ => true,
+        };
+
+        for inst in instructions {
+            let mut inst = inst.clone();
+            match &inst.operation {
+                Operation::Branch(Branch::If(if_)) => {
+                    assert!(is_visible(&visible, &if_.cond));
+                    assert_scoped(opt, &if_.scope.instructions, &visible);
+                }
+                Operation::Branch(Branch::IfElse(if_else)) => {
+                    assert!(is_visible(&visible, &if_else.cond));
+                    assert_scoped(opt, &if_else.scope_if.instructions, &visible);
+                    assert_scoped(opt, &if_else.scope_else.instructions, &visible);
+                }
+                Operation::Branch(Branch::Loop(loop_)) => {
+                    assert_scoped(opt, &loop_.scope.instructions, &visible);
+                }
+                Operation::Branch(_) => {}
+                _ => {
+                    opt.visit_operation(&mut inst.operation, &mut inst.out, |_, var| {
+                        assert!(is_visible(&visible, var), "{var} isn't visible");
+                    });
+                    if let Some(out) = inst.out {
+                        assert!(!matches!(out.kind, VariableKind::Versioned { .. }));
+                        visible.insert(out);
+                    }
+                }
+            }
+        }
+    }
+
+    #[cube]
+    fn first_greater(input: &Array<u32>, value: u32) -> u32 {
+        for i in 0..input.len() {
+            if input[i] > value {
+                return i as u32;
+            }
+        }
+        input.len() as u32
+    }
+
+    #[allow(unused)]
+    #[cube(launch)]
+    fn early_return_kernel(input: &Array<u32>, value: u32, out: &mut Array<u32>) {
+        out[0] = first_greater(input, value);
+    }
+
+    #[test_log::test]
+    fn test_structured_scope_break_past_merge() {
+        let mut ctx = root_scope();
+        early_return_kernel::expand(&mut ctx, input_array(0), scalar(0), output_array(0));
+        let mut opt = Optimizer::new(ctx, CubeDim::new_1d(1), vec![], vec![]);
+        let scope = opt.structured_scope();
+
+        // The return breaks out of the inner loop to the block following its split merge, which
+        // must still be recognized as a break
+        let loops = scope
+            .instructions
+            .iter()
+            .filter(|inst| matches!(inst.operation, Operation::Branch(Branch::Loop(_))))
+            .count();
+        assert_eq!(loops, 1);
+        let visible = scope.locals.iter().copied().collect();
+        assert_scoped(&mut opt, &scope.instructions, &visible);
+    }
 }
@@ -0,0 +1,325 @@
+use std::{collections::HashMap, rc::Rc};
+
+use cubecl_ir::{
+    Arithmetic, Comparison, ElemType, Metadata, Operation, Operator, Variable, VariableKind,
+};
+
+use crate::{
+    AtomicCounter, ControlFlow, NodeIndex, Optimizer, VarId,
+    analyses::{
+        dominance::Dominators,
+        integer_range::{Ranges, var_id},
+    },
+};
+
+use super::OptimizerPass;
+
+/// Eliminates the bounds checks inserted by checked IO when the index is provably in bounds. A
+/// check `index < len` is proven either by the integer ranges of both operands, or by a dominating
+/// branch that already compared `index` (or a larger value) to the same `len`. These branches come
+/// from range loop headers (`for i in 0..arr.buffer_len()`) and explicit `if` guards.
+///
+/// Proven comparisons, the selects and branches that depend on them and the `index.min(len - 1)`
+/// clamp are folded, the rest is then cleaned up by constant evaluation and dead code elimination.
+///
+/// Note that `len()` and `buffer_len()` are never assumed to be related, since the length of a
+/// broadcast tensor can exceed the length of its buffer.
+pub struct EliminateBoundsChecks;
+
+/// A value that can be compared symbolically, regardless of the SSA variable that holds it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Value {
+    Var(VarId),
+    Length(Variable),
+    BufferLength(Variable),
+    Other(Variable),
+}
+
+/// `lhs < rhs` holds in every block dominated by `region`.
+struct Fact {
+    region: NodeIndex,
+    lhs: Variable,
+    rhs: Value,
+}
+
+struct BoundsContext {
+    defs: HashMap<VarId, Operation>,
+    facts: Vec<Fact>,
+    conditions: Vec<(NodeIndex, Value, bool)>,
+    ranges: Rc<Ranges>,
+    dominators: Rc<Dominators>,
+}
+
+impl OptimizerPass for EliminateBoundsChecks {
+    fn apply_post_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
+        opt.invalidate_analysis::<Ranges>();
+        let context = BoundsContext::new(opt);
+
+        for block in opt.node_ids() {
+            let ops = opt.program[block].ops.clone();
+            for op in ops.borrow_mut().values_mut() {
+                match &op.operation {
+                    Operation::Comparison(_) => {
+                        if let Some(value) = context.compare(opt, block, &op.operation) {
+                            op.operation = Operation::Copy(value.into());
+                            changes.inc();
+                        }
+                    }
+                    Operation::Operator(Operator::Select(select)) => {
+                        match context.condition(opt, block, &select.cond) {
+                            Some(true) => op.operation = Operation::Copy(select.then),
+                            Some(false) => op.operation = Operation::Copy(select.or_else),
+                            None => continue,
+                        }
+                        changes.inc();
+                    }
+                    // `index.min(len - 1)` is `index` when `index < len`
+                    Operation::Arithmetic(Arithmetic::Min(bin_op)) if is_uint(&bin_op.lhs) => {
+                        let clamped = [(bin_op.lhs, bin_op.rhs), (bin_op.rhs, bin_op.lhs)]
+                            .into_iter()
+                            .find(|(value, max)| {
+                                context
+                                    .minus_one(max)
+                                    .is_some_and(|len| context.is_lower(opt, block, value, &len))
+                            });
+                        if let Some((value, _)) = clamped {
+                            op.operation = Operation::Copy(value);
+                            changes.inc();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            // Let `EliminateConstBranches` remove the branch
+            let mut control_flow = opt.program[block].control_flow.borrow_mut();
+            if let ControlFlow::IfElse { cond, .. } = &mut *control_flow
+                && cond.as_const().is_none()
+                && let Some(value) = context.condition(opt, block, cond)
+            {
+                *cond = value.into();
+                changes.inc();
+            }
+        }
+    }
+}
+
+impl BoundsContext {
+    fn new(opt: &mut Optimizer) -> Self {
+        let mut defs = HashMap::new();
+        for block in opt.node_ids() {
+            for op in opt.program[block].ops.borrow().values() {
+                if let Some(id) = op.out.as_ref().and_then(var_id) {
+                    defs.insert(id, op.operation.clone());
+                }
+            }
+        }
+
+        let mut this = BoundsContext {
+            defs,
+            facts: Vec::new(),
+            conditions: Vec::new(),
+            ranges: opt.analysis::<Ranges>(),
+            dominators: opt.analysis::<Dominators>(),
+        };
+
+        for block in opt.node_ids() {
+            let control_flow = opt.program[block].control_flow.borrow().clone();
+            match control_flow {
+                ControlFlow::IfElse {
+                    cond,
+                    then,
+                    or_else,
+                    ..
+                } if then != or_else => {
+                    // The branch must be the only way into the region, otherwise the condition
+                    // doesn't hold for the whole region.
+                    if opt.predecessors(then).len() == 1 {
+                        this.add_facts(then, &cond, true);
+                    }
+                    if opt.predecessors(or_else).len() == 1 {
+                        this.add_facts(or_else, &cond, false);
+                    }
+                }
+                ControlFlow::LoopBreak {
+                    break_cond, body, ..
+                } if opt.predecessors(body).len() == 1 => {
+                    this.add_facts(body, &break_cond, true);
+                }
+                _ => {}
+            }
+        }
+
+        this
+    }
+
+    /// Registers the facts implied by `cond` being equal to `value` in `region`.
+    fn add_facts(&mut self, region: NodeIndex, cond: &Variable, value: bool) {
+        if cond.ty.vector_size() > 1 {
+            return;
+        }
+        if let Some(key) = self.value(cond) {
+            self.conditions.push((region, key, value));
+        }
+
+        let Some(operation) = self.def(cond).cloned() else {
+            return;
+        };
+
+        let (lhs, rhs) = match (operation, value) {
+            (Operation::Comparison(Comparison::Lower(op)), true)
+            | (Operation::Comparison(Comparison::GreaterEqual(op)), false) => (op.lhs, op.rhs),
+            (Operation::Comparison(Comparison::Greater(op)), true)
+            | (Operation::Comparison(Comparison::LowerEqual(op)), false) => (op.rhs, op.lhs),
+            (Operation::Operator(Operator::Not(op)), value) => {
+                return self.add_facts(region, &op.input, !value);
+            }
+            (Operation::Operator(Operator::And(op)), true) => {
+                self.add_facts(region, &op.lhs, true);
+                return self.add_facts(region, &op.rhs, true);
+            }
+            _ => return,
+        };
+
+        if lhs.ty.vector_size() > 1 {
+            return;
+        }
+
+        let Some(rhs) = self.value(&rhs).filter(|_| self.value(&lhs).is_some()) else {
+            return;
+        };
+        self.facts.push(Fact { region, lhs, rhs });
+    }
+
+    /// The value of `cond` in `block`, if it is known.
+    fn condition(&self, opt: &Optimizer, block: NodeIndex, cond: &Variable) -> Option<bool> {
+        if let Some(value) = cond.as_const() {
+            return Some(value.as_bool());
+        }
+
+        let key = self.value(cond)?;
+        let known = self.dominators(block).find_map(|dominator| {
+            self.conditions
+                .iter()
+                .find(|(region, value, _)| *region == dominator && *value == key)
+                .map(|(_, _, known)| *known)
+        });
+        known.or_else(|| self.compare(opt, block, self.def(cond)?))
+    }
+
+    /// The result of a comparison in `block`, if it is known.
+    fn compare(&self, opt: &Optimizer, block: NodeIndex, operation: &Operation) -> Option<bool> {
+        match operation {
+            Operation::Comparison(Comparison::Lower(op)) => {
+                self.is_lower(opt, block, &op.lhs, &op.rhs).then_some(true)
+            }
+            Operation::Comparison(Comparison::Greater(op)) => {
+                self.is_lower(opt, block, &op.rhs, &op.lhs).then_some(true)
+            }
+            Operation::Comparison(Comparison::GreaterEqual(op)) => {
+                self.is_lower(opt, block, &op.lhs, &op.rhs).then_some(false)
+            }
+            Operation::Comparison(Comparison::LowerEqual(op)) => {
+                self.is_lower(opt, block, &op.rhs, &op.lhs).then_some(false)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether `lhs < rhs` always holds in `block`.
+    fn is_lower(&self, opt: &Optimizer, block: NodeIndex, lhs: &Variable, rhs: &Variable) -> bool {
+        if lhs.ty.vector_size() > 1 || rhs.ty.vector_size() > 1 {
+            return false;
+        }
+
+        if let Some(upper) = self.ranges.range_of(opt, lhs).upper_bound
+            && let Some(lower) = self.ranges.range_of(opt, rhs).lower_bound
+            && upper < lower
+        {
+            return true;
+        }
+
+        let Some(rhs) = self.value(rhs) else {
+            return false;
+        };
+        self.dominators(block).any(|dominator| {
+            self.facts.iter().any(|fact| {
+                fact.region == dominator
+                    && fact.rhs == rhs
+                    && self.is_lower_equal(opt, lhs, &fact.lhs)
+            })
+        })
+    }
+
+    /// Whether `lhs <= rhs` always holds.
+    fn is_lower_equal(&self, opt: &Optimizer, lhs: &Variable, rhs: &Variable) -> bool {
+        if self.value(lhs).is_some() && self.value(lhs) == self.value(rhs) {
+            return true;
+        }
+        if !is_uint(lhs) || !is_uint(rhs) {
+            return false;
+        }
+
+        if let Some(upper) = self.ranges.range_of(opt, lhs).upper_bound
+            && let Some(lower) = self.ranges.range_of(opt, rhs).lower_bound
+            && upper <= lower
+        {
+            return true;
+        }
+
+        match self.def(lhs) {
+            Some(Operation::Copy(value)) => self.is_lower_equal(opt, value, rhs),
+            Some(Operation::Arithmetic(Arithmetic::Min(op))) => {
+                self.is_lower_equal(opt, &op.lhs, rhs) || self.is_lower_equal(opt, &op.rhs, rhs)
+            }
+            // Unsigned division by a non-zero value never increases the dividend
+            Some(Operation::Arithmetic(Arithmetic::Div(op)))
+                if self.ranges.range_of(opt, &op.rhs).lower_bound >= Some(1) =>
+            {
+                self.is_lower_equal(opt, &op.lhs, rhs)
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `len` if `var` is `len - 1`.
+    fn minus_one(&self, var: &Variable) -> Option<Variable> {
+        match self.def(var)? {
+            Operation::Arithmetic(Arithmetic::Sub(op)) if op.rhs.is_constant(1) => Some(op.lhs),
+            Operation::Copy(value) => self.minus_one(value),
+            _ => None,
+        }
+    }
+
+    /// The symbolic value of `var`, or `None` if it's mutable and can't be reasoned about.
+    fn value(&self, var: &Variable) -> Option<Value> {
+        match self.def(var) {
+            Some(Operation::Copy(value)) => self.value(value),
+            Some(Operation::Metadata(Metadata::Length { var })) => Some(Value::Length(*var)),
+            Some(Operation::Metadata(Metadata::BufferLength { var })) => {
+                Some(Value::BufferLength(*var))
+            }
+            _ => match var.kind {
+                VariableKind::Versioned { .. } | VariableKind::LocalConst { .. } => {
+                    var_id(var).map(Value::Var)
+                }
+                VariableKind::GlobalScalar(_)
+                | VariableKind::Builtin(_)
+                | VariableKind::Constant(_) => Some(Value::Other(*var)),
+                _ => None,
+            },
+        }
+    }
+
+    fn dominators(&self, block: NodeIndex) -> impl Iterator<Item = NodeIndex> + '_ {
+        self.dominators.dominators(block).into_iter().flatten()
+    }
+
+    fn def(&self, var: &Variable) -> Option<&Operation> {
+        var_id(var).and_then(|id| self.defs.get(&id))
+    }
+}
+
+fn is_uint(var: &Variable) -> bool {
+    matches!(var.elem_type(), ElemType::UInt(_))
+}
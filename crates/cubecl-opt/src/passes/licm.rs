@@ -0,0 +1,120 @@
+use std::collections::HashSet;
+
+use cubecl_ir::{
+    Arithmetic, Instruction, Operation, OperationReflect, Operator, Variable, VariableKind,
+};
+
+use crate::{AtomicCounter, Optimizer, analyses::liveness::Liveness};
+
+use super::{OptimizerPass, find_loops, loops::LoopInfo};
+
+/// Hoists pure instructions that only depend on values defined outside of a loop into the block
+/// right before the loop (the preheader), so they're only executed once.
+///
+/// Like GVN, this assumes the loop is executed at least once, so it never hoists instructions that
+/// read memory or could trap (integer division by a non-constant).
+pub struct LoopInvariantCodeMotion;
+
+impl OptimizerPass for LoopInvariantCodeMotion {
+    fn apply_post_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
+        // Hoisting out of an inner loop can make the instruction invariant in the outer loop, and
+        // inner loops are visited first.
+        for loop_info in find_loops(opt) {
+            let Some(preheader) = loop_info.preheader else {
+                continue;
+            };
+
+            let mut defined = loop_defs(opt, &loop_info);
+            let mut hoisted = Vec::new();
+            // Sorted so the hoisted instructions have a deterministic order
+            let mut blocks = loop_info.blocks.iter().copied().collect::<Vec<_>>();
+            blocks.sort();
+
+            // Iterate until no more instructions are hoisted, since hoisting an instruction can
+            // make its users invariant.
+            let mut changed = true;
+            while changed {
+                changed = false;
+                for block in &blocks {
+                    let ops = opt.program[*block].ops.clone();
+                    let mut ops = ops.borrow_mut();
+                    let invariant = ops
+                        .indices()
+                        .filter(|idx| is_invariant(&ops[*idx], &defined))
+                        .collect::<Vec<_>>();
+                    for idx in invariant {
+                        let inst = ops.remove(idx).unwrap();
+                        defined.remove(&inst.out());
+                        hoisted.push(inst);
+                        changed = true;
+                    }
+                }
+            }
+
+            if !hoisted.is_empty() {
+                let ops = opt.program[preheader].ops.clone();
+                let mut ops = ops.borrow_mut();
+                for inst in hoisted {
+                    ops.push(inst);
+                    changes.inc();
+                }
+                opt.invalidate_analysis::<Liveness>();
+            }
+        }
+    }
+}
+
+/// All variables defined in the loop.
+fn loop_defs(opt: &Optimizer, loop_info: &LoopInfo) -> HashSet<Variable> {
+    let mut defined = HashSet::new();
+    for block in &loop_info.blocks {
+        for phi in opt.program[*block].phi_nodes.borrow().iter() {
+            defined.insert(phi.out);
+        }
+        for inst in opt.program[*block].ops.borrow().values() {
+            if let Some(out) = inst.out {
+                defined.insert(out);
+            }
+        }
+    }
+    defined
+}
+
+fn is_invariant(inst: &Instruction, defined: &HashSet<Variable>) -> bool {
+    let Some(out) = inst.out else {
+        return false;
+    };
+    if !matches!(
+        out.kind,
+        VariableKind::Versioned { .. } | VariableKind::LocalConst { .. }
+    ) {
+        return false;
+    }
+
+    let operation = &inst.operation;
+    if !operation.is_pure() {
+        return false;
+    }
+    match operation {
+        // Memory might be written by the loop, or the index might only be valid in the loop
+        Operation::Operator(Operator::Index(_) | Operator::UncheckedIndex(_)) => return false,
+        Operation::Arithmetic(
+            Arithmetic::Div(op) | Arithmetic::Modulo(op) | Arithmetic::Remainder(op),
+        ) if out.ty.is_int() && op.rhs.as_const().is_none_or(|it| it.as_u64() == 0) => {
+            return false;
+        }
+        _ => {}
+    }
+
+    let Some(args) = operation.args() else {
+        return false;
+    };
+    args.iter().all(|arg| match arg.kind {
+        VariableKind::LocalMut { .. }
+        | VariableKind::Shared { .. }
+        | VariableKind::Matrix { .. }
+        | VariableKind::Pipeline { .. }
+        | VariableKind::BarrierToken { .. } => false,
+        _ => !defined.contains(arg),
+    })
+}
@@ -0,0 +1,100 @@
+use std::collections::HashSet;
+
+use cubecl_ir::Variable;
+
+use crate::{ControlFlow, NodeIndex, Optimizer};
+
+/// A natural loop of the program graph, see <https://en.wikipedia.org/wiki/Control-flow_graph#Loop_management>
+#[derive(Debug, Clone)]
+pub(crate) struct LoopInfo {
+    /// The block that contains the loop control flow
+    pub header: NodeIndex,
+    /// The first block of the loop body
+    pub body: NodeIndex,
+    /// The block that jumps back to the header
+    pub continue_target: NodeIndex,
+    /// The break condition, if this is a loop with a header condition
+    pub break_cond: Option<Variable>,
+    /// The single block outside the loop that jumps to the header, if any
+    pub preheader: Option<NodeIndex>,
+    /// All blocks of the loop, including the header
+    pub blocks: HashSet<NodeIndex>,
+}
+
+/// Find all loops of the program, with inner loops coming before the loops that contain them.
+pub(crate) fn find_loops(opt: &Optimizer) -> Vec<LoopInfo> {
+    let mut loops = Vec::new();
+
+    for header in opt.node_ids() {
+        let (body, continue_target, break_cond) = match &*opt.program[header].control_flow.borrow()
+        {
+            ControlFlow::Loop {
+                body,
+                continue_target,
+                ..
+            } => (*body, *continue_target, None),
+            ControlFlow::LoopBreak {
+                break_cond,
+                body,
+                continue_target,
+                ..
+            } => (*body, *continue_target, Some(*break_cond)),
+            _ => continue,
+        };
+
+        // Every block that reaches the continue target without going through the header
+        let mut blocks = HashSet::from([header]);
+        let mut stack = vec![continue_target];
+        while let Some(block) = stack.pop() {
+            if blocks.insert(block) {
+                stack.extend(opt.predecessors(block));
+            }
+        }
+
+        let outside = opt
+            .predecessors(header)
+            .into_iter()
+            .filter(|pred| !blocks.contains(pred))
+            .collect::<Vec<_>>();
+        let preheader = match outside.as_slice() {
+            [pred] if opt.successors(*pred).len() == 1 => Some(*pred),
+            _ => None,
+        };
+
+        loops.push(LoopInfo {
+            header,
+            body,
+            continue_target,
+            break_cond,
+            preheader,
+            blocks,
+        });
+    }
+
+    loops.sort_by_key(|it| it.blocks.len());
+    loops
+}
+
+impl LoopInfo {
+    /// Whether the blocks of the loop body form a single path from `body` to `continue_target`,
+    /// without any branches, breaks or continues.
+    pub fn straight_body(&self, opt: &Optimizer) -> Option<Vec<NodeIndex>> {
+        let mut path = vec![self.body];
+        let mut block = self.body;
+        loop {
+            let block_data = &opt.program[block];
+            let is_straight = matches!(*block_data.control_flow.borrow(), ControlFlow::None)
+                && block_data.phi_nodes.borrow().is_empty()
+                && opt.predecessors(block).len() == 1
+                && opt.successors(block).len() == 1;
+            if !is_straight {
+                return None;
+            }
+            if block == self.continue_target {
+                return Some(path);
+            }
+            block = opt.successors(block)[0];
+            path.push(block);
+        }
+    }
+}
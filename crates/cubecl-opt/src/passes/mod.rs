@@ -1,3 +1,4 @@
+mod bounds_check;
 mod composite;
 mod constant_prop;
 mod dead_code;
@@ -5,10 +6,14 @@ mod disaggregate_array;
 mod expression_merge;
 mod index_merge;
 mod inlined_if_to_select;
+mod licm;
+mod loops;
 mod reduce_strength;
+mod unroll;
 
 use std::any::type_name;
 
+pub use bounds_check::*;
 pub use composite::*;
 pub use constant_prop::*;
 pub use dead_code::*;
@@ -16,7 +21,11 @@ pub use disaggregate_array::*;
 pub use expression_merge::*;
 pub use index_merge::*;
 pub use inlined_if_to_select::*;
+pub use licm::*;
 pub use reduce_strength::*;
+pub use unroll::*;
+
+pub(crate) use loops::find_loops;
 
 use crate::AtomicCounter;
 
@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+
+use cubecl_ir::{Arithmetic, Comparison, Instruction, Operation, Variable, VariableKind};
+
+use crate::{AtomicCounter, NodeIndex, Optimizer, analyses::liveness::Liveness};
+
+use super::{OptimizerPass, find_loops, loops::LoopInfo};
+
+/// Options for [`PartialUnroll`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnrollOptions {
+    /// The maximum number of times the loop body is repeated in a single iteration.
+    pub max_factor: u32,
+    /// The maximum number of instructions of the unrolled loop body.
+    pub max_instructions: usize,
+}
+
+impl Default for UnrollOptions {
+    fn default() -> Self {
+        Self {
+            max_factor: 8,
+            max_instructions: 64,
+        }
+    }
+}
+
+/// Partially unrolls range loops with a trip count known at compile time, by repeating the body
+/// multiple times in each iteration. This complements `#[unroll]`, which always fully unrolls the
+/// loop during expansion.
+///
+/// Only loops with a body without branches are unrolled, and the factor is the largest divisor of
+/// the trip count for which the unrolled body stays under the instruction budget, so no remainder
+/// loop is needed.
+pub struct PartialUnroll {
+    pub options: UnrollOptions,
+}
+
+impl OptimizerPass for PartialUnroll {
+    fn apply_post_ssa(&mut self, opt: &mut Optimizer, changes: AtomicCounter) {
+        let defs = const_defs(opt);
+
+        for loop_info in find_loops(opt) {
+            let Some(trip_count) = trip_count(opt, &loop_info, &defs) else {
+                continue;
+            };
+            // Only the header phis and the condition can be in the header, since the header isn't
+            // duplicated
+            if opt.program[loop_info.header].ops.borrow().num_elements() > 1 {
+                continue;
+            }
+            let Some(path) = loop_info.straight_body(opt) else {
+                continue;
+            };
+
+            let body = path
+                .iter()
+                .flat_map(|block| {
+                    let ops = opt.program[*block].ops.borrow();
+                    ops.values().cloned().collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            let factor = self.factor(trip_count, body.len());
+            if factor < 2 {
+                continue;
+            }
+
+            log::debug!("Unrolling loop with {trip_count} iterations by {factor}");
+            if unroll(opt, &loop_info, &path, body, factor) {
+                changes.inc();
+            }
+        }
+    }
+}
+
+impl PartialUnroll {
+    fn factor(&self, trip_count: u64, body_len: usize) -> u64 {
+        let max_factor = (self.options.max_factor as u64).min(trip_count);
+        (1..=max_factor)
+            .rev()
+            .find(|factor| {
+                trip_count.is_multiple_of(*factor)
+                    && body_len * *factor as usize <= self.options.max_instructions
+            })
+            .unwrap_or(1)
+    }
+}
+
+/// Repeat the body `factor` times, renaming the variables defined in each copy and feeding the
+/// loop carried values (header phis) of each copy into the next one.
+fn unroll(
+    opt: &mut Optimizer,
+    loop_info: &LoopInfo,
+    path: &[NodeIndex],
+    body: Vec<Instruction>,
+    factor: u64,
+) -> bool {
+    let phis = opt.program[loop_info.header].phi_nodes.clone();
+    let mut phis = phis.borrow_mut();
+    let back_edges = phis
+        .iter()
+        .map(|phi| {
+            phi.entries
+                .iter()
+                .position(|entry| entry.block == loop_info.continue_target)
+        })
+        .collect::<Option<Vec<_>>>();
+    let Some(back_edges) = back_edges else {
+        return false;
+    };
+
+    let mut unrolled = body.clone();
+    // The names of the variables defined by the previous copy of the body
+    let mut renames = HashMap::<Variable, Variable>::new();
+    for _ in 1..factor {
+        let mut next = HashMap::new();
+        for (phi, entry) in phis.iter().zip(&back_edges) {
+            let value = phi.entries[*entry].value;
+            next.insert(phi.out, renames.get(&value).copied().unwrap_or(value));
+        }
+        renames = next;
+
+        for mut inst in body.clone() {
+            let mut out = inst.out;
+            opt.visit_operation(&mut inst.operation, &mut out, |_, var| {
+                if let Some(renamed) = renames.get(var) {
+                    *var = *renamed;
+                }
+            });
+            if let Some(var) = inst.out
+                && matches!(
+                    var.kind,
+                    VariableKind::Versioned { .. } | VariableKind::LocalConst { .. }
+                )
+            {
+                let renamed = *opt.allocator.create_local(var.ty);
+                renames.insert(var, renamed);
+                inst.out = Some(renamed);
+            }
+            unrolled.push(inst);
+        }
+    }
+
+    // The last copy feeds the next iteration
+    for (phi, entry) in phis.iter_mut().zip(&back_edges) {
+        let value = &mut phi.entries[*entry].value;
+        *value = renames.get(value).copied().unwrap_or(*value);
+    }
+
+    for (i, block) in path.iter().enumerate() {
+        let ops = &mut *opt.program[*block].ops.borrow_mut();
+        ops.clear();
+        if i == 0 {
+            for inst in unrolled.drain(..) {
+                ops.push(inst);
+            }
+        }
+    }
+    opt.invalidate_analysis::<Liveness>();
+    true
+}
+
+/// Find the trip count of a range loop, the header must look like
+/// ```ignore
+/// i = phi [preheader: start][continue_target: i_next]
+/// cond = i < end;
+/// ```
+/// with `i_next = i + step` somewhere in the body, and `start`, `end` and `step` constants.
+fn trip_count(
+    opt: &Optimizer,
+    loop_info: &LoopInfo,
+    defs: &HashMap<Variable, i128>,
+) -> Option<u64> {
+    let preheader = loop_info.preheader?;
+    let break_cond = loop_info.break_cond?;
+    let header = &opt.program[loop_info.header];
+
+    let ops = header.ops.borrow();
+    let cond = ops
+        .values()
+        .find(|inst| inst.out == Some(break_cond))?
+        .operation
+        .clone();
+    let (index, end, inclusive) = match cond {
+        Operation::Comparison(Comparison::Lower(op)) => (op.lhs, op.rhs, false),
+        Operation::Comparison(Comparison::LowerEqual(op)) => (op.lhs, op.rhs, true),
+        _ => return None,
+    };
+    let end = const_value(&end, defs)?;
+
+    let phis = header.phi_nodes.borrow();
+    let phi = phis.iter().find(|phi| phi.out == index)?;
+    let value_from = |block| {
+        phi.entries
+            .iter()
+            .find(|entry| entry.block == block)
+            .map(|entry| entry.value)
+    };
+    let start = const_value(&value_from(preheader)?, defs)?;
+    let next = value_from(loop_info.continue_target)?;
+
+    let step = loop_info.blocks.iter().find_map(|block| {
+        let ops = opt.program[*block].ops.borrow();
+        let inst = ops.values().find(|inst| inst.out == Some(next))?;
+        match &inst.operation {
+            Operation::Arithmetic(Arithmetic::Add(op)) if op.lhs == index => {
+                const_value(&op.rhs, defs)
+            }
+            Operation::Arithmetic(Arithmetic::Add(op)) if op.rhs == index => {
+                const_value(&op.lhs, defs)
+            }
+            _ => None,
+        }
+    })?;
+    if step <= 0 {
+        return None;
+    }
+
+    let end = if inclusive { end + 1 } else { end };
+    let trip_count = if end > start {
+        (end - start + step - 1) / step
+    } else {
+        0
+    };
+
+    trip_count.try_into().ok()
+}
+
+fn const_value(var: &Variable, defs: &HashMap<Variable, i128>) -> Option<i128> {
+    match var.as_const() {
+        Some(value) => value.try_as_i64().map(|it| it as i128),
+        None => defs.get(var).copied(),
+    }
+}
+
+/// Variables that are copies of integer constants.
+fn const_defs(opt: &Optimizer) -> HashMap<Variable, i128> {
+    let mut defs = HashMap::new();
+    for block in opt.node_ids() {
+        for inst in opt.program[block].ops.borrow().values() {
+            if let Operation::Copy(value) = &inst.operation
+                && let Some(value) = value.as_const().and_then(|it| it.try_as_i64())
+            {
+                defs.insert(inst.out(), value as i128);
+            }
+        }
+    }
+    defs
+}
@@ -11,7 +11,9 @@ use super::version::{PhiEntry, PhiInstruction};
 impl Optimizer {
     /// Places a phi node for each live variable at each frontier
     pub fn place_phi_nodes(&mut self) {
-        let keys: Vec<_> = self.program.variables.keys().cloned().collect();
+        // Sorted so phis are always placed in the same order, and the output is deterministic.
+        let mut keys: Vec<_> = self.program.variables.keys().cloned().collect();
+        keys.sort();
         let writes = self.analysis::<Writes>();
         let liveness = self.analysis::<Liveness>();
         let dom_frontiers = self.analysis::<DomFrontiers>();
@@ -0,0 +1,627 @@
+use std::collections::{HashMap, HashSet};
+
+use cubecl_ir::{
+    Allocator, Branch, ConstantValue, ElemType, If, IfElse, Instruction, Loop, Operation, Operator,
+    Scope, Switch, UnaryOperator, Variable, VariableKind,
+};
+
+use crate::{ControlFlow, NodeIndex, Optimizer};
+
+impl Optimizer {
+    /// Lowers the optimized program back to a structured [`Scope`], for backends that emit
+    /// structured source code instead of consuming the control flow graph directly.
+    ///
+    /// Loops, branches and switches are recovered from the graph. Phi nodes and versioned
+    /// variables become mutable locals declared in the root scope, with a copy at the end of each
+    /// incoming edge. Constants that end up being used outside the structured scope that defines
+    /// them are promoted to mutable locals as well.
+    ///
+    /// The processors passed to the optimizer have already been applied to the returned scope.
+    pub fn structured_scope(&mut self) -> Scope {
+        let mut root = self.root_scope.child();
+        let entry = self.entry();
+
+        let mut structurizer = Structurizer {
+            opt: self,
+            loops: Vec::new(),
+            scopes: vec![0],
+            next_scope: 1,
+            defs: HashMap::new(),
+            escaping: HashSet::new(),
+        };
+        structurizer.region(&mut root, entry, None);
+
+        let Structurizer { escaping, .. } = structurizer;
+        let mut renamer = Renamer {
+            allocator: self.allocator.clone(),
+            escaping,
+            renames: HashMap::new(),
+            locals: Vec::new(),
+            matrices: Vec::new(),
+        };
+        renamer.rename_all(self, &mut root.instructions);
+
+        for local in renamer.locals {
+            root.add_local_mut(local);
+        }
+        for matrix in renamer.matrices {
+            root.add_matrix(matrix);
+        }
+        for array in self.const_arrays() {
+            let var = Variable::new(
+                VariableKind::ConstantArray {
+                    id: array.id,
+                    length: array.length,
+                    unroll_factor: 1,
+                },
+                array.item,
+            );
+            root.const_arrays.push((var, array.values));
+        }
+
+        root
+    }
+}
+
+/// The targets of a loop that can be reached through `break` and `continue`.
+#[derive(Clone, Copy)]
+struct LoopTargets {
+    header: NodeIndex,
+    continue_target: NodeIndex,
+    merge: NodeIndex,
+    /// The block following `merge` if it only forwards the exit of the header, which breaks from
+    /// the body jump to directly.
+    after_merge: Option<NodeIndex>,
+}
+
+/// Ways to leave the current structured region.
+enum Exit {
+    Break,
+    Continue,
+    Return,
+}
+
+struct Structurizer<'a> {
+    opt: &'a mut Optimizer,
+    /// The loops enclosing the region that's currently emitted, innermost last
+    loops: Vec<LoopTargets>,
+    /// The ids of the structured scopes enclosing the current instruction
+    scopes: Vec<usize>,
+    next_scope: usize,
+    /// The scope of the most recent definition of each constant
+    defs: HashMap<Variable, usize>,
+    /// Constants that are used outside of the scope that defines them
+    escaping: HashSet<Variable>,
+}
+
+impl Structurizer<'_> {
+    /// Emits `block` and the blocks following it into `scope`, until `stop` is reached or control
+    /// flow leaves the region through `break`, `continue` or `return`.
+    fn region(&mut self, scope: &mut Scope, mut block: NodeIndex, stop: Option<NodeIndex>) {
+        loop {
+            if Some(self.target(block)) == stop {
+                return;
+            }
+            if let Some(exit) = self.exit(block) {
+                match exit {
+                    Exit::Break => push(scope, Branch::Break),
+                    Exit::Continue => self.continue_loop(scope, block),
+                    // Falling off the end of the kernel already returns
+                    Exit::Return if self.scopes.len() > 1 => push(scope, Branch::Return),
+                    Exit::Return => {}
+                }
+                return;
+            }
+
+            let control_flow = self.opt.program[block].control_flow.borrow().clone();
+            let next = match control_flow {
+                ControlFlow::Loop {
+                    body,
+                    continue_target,
+                    merge,
+                } => self.emit_loop(scope, block, None, body, continue_target, merge),
+                ControlFlow::LoopBreak {
+                    break_cond,
+                    body,
+                    continue_target,
+                    merge,
+                } => self.emit_loop(scope, block, Some(break_cond), body, continue_target, merge),
+                ControlFlow::IfElse {
+                    cond,
+                    then,
+                    or_else,
+                    ..
+                } => {
+                    self.ops(scope, block);
+                    self.emit_if(scope, block, cond, then, or_else)
+                }
+                ControlFlow::Switch {
+                    value,
+                    default,
+                    branches,
+                    ..
+                } => {
+                    self.ops(scope, block);
+                    self.emit_switch(scope, block, value, default, branches)
+                }
+                ControlFlow::Return => {
+                    self.ops(scope, block);
+                    if self.scopes.len() > 1 {
+                        push(scope, Branch::Return);
+                    }
+                    None
+                }
+                ControlFlow::Unreachable => {
+                    self.ops(scope, block);
+                    push(scope, Branch::Unreachable);
+                    None
+                }
+                ControlFlow::None => {
+                    self.ops(scope, block);
+                    let next = self.opt.successors(block).first().copied();
+                    if let Some(next) = next {
+                        self.phi_copies(scope, block, next);
+                    }
+                    next
+                }
+            };
+
+            match next {
+                Some(next) => block = next,
+                None => return,
+            }
+        }
+    }
+
+    fn emit_if(
+        &mut self,
+        scope: &mut Scope,
+        block: NodeIndex,
+        cond: Variable,
+        then: NodeIndex,
+        or_else: NodeIndex,
+    ) -> Option<NodeIndex> {
+        if then == or_else {
+            self.phi_copies(scope, block, then);
+            return Some(then);
+        }
+
+        let join = self.join(&[then, or_else]);
+        let scope_if = self.arm(scope, block, then, join);
+        let scope_else = self.arm(scope, block, or_else, join);
+        self.read(&cond);
+
+        match (
+            scope_if.instructions.is_empty(),
+            scope_else.instructions.is_empty(),
+        ) {
+            (true, true) => {}
+            (_, true) => push(
+                scope,
+                Branch::If(Box::new(If {
+                    cond,
+                    scope: scope_if,
+                })),
+            ),
+            _ => push(
+                scope,
+                Branch::IfElse(Box::new(IfElse {
+                    cond,
+                    scope_if,
+                    scope_else,
+                })),
+            ),
+        }
+        join
+    }
+
+    fn emit_switch(
+        &mut self,
+        scope: &mut Scope,
+        block: NodeIndex,
+        value: Variable,
+        default: NodeIndex,
+        branches: Vec<(u32, NodeIndex)>,
+    ) -> Option<NodeIndex> {
+        let arms = [default]
+            .into_iter()
+            .chain(branches.iter().map(|(_, case)| *case))
+            .collect::<Vec<_>>();
+        let join = self.join(&arms);
+
+        let scope_default = self.arm(scope, block, default, join);
+        let cases = branches
+            .into_iter()
+            .map(|(case_value, case)| {
+                // Case values are stored as the raw bits of the switch value
+                let case_value = match value.elem_type() {
+                    ElemType::Int(_) => ConstantValue::Int(case_value as i32 as i64),
+                    _ => ConstantValue::UInt(case_value as u64),
+                };
+                let case_value = Variable::constant(case_value, value.ty);
+                (case_value, self.arm(scope, block, case, join))
+            })
+            .collect();
+        self.read(&value);
+
+        push(
+            scope,
+            Branch::Switch(Box::new(Switch {
+                value,
+                scope_default,
+                cases,
+            })),
+        );
+        join
+    }
+
+    /// Emits the loop with `header` as an infinite loop. The header is emitted at the start of the
+    /// loop body, followed by the break condition, and every edge to the continue target
+    /// duplicates the continue target before continuing.
+    fn emit_loop(
+        &mut self,
+        scope: &mut Scope,
+        header: NodeIndex,
+        break_cond: Option<Variable>,
+        body: NodeIndex,
+        continue_target: NodeIndex,
+        merge: NodeIndex,
+    ) -> Option<NodeIndex> {
+        let mut loop_scope = scope.child();
+        self.enter();
+        let after_merge = self.forwarded_merge(header, merge);
+        self.loops.push(LoopTargets {
+            header,
+            continue_target,
+            merge,
+            after_merge,
+        });
+
+        self.ops(&mut loop_scope, header);
+        if let Some(cond) = break_cond {
+            self.read(&cond);
+            let not_cond = *self.opt.allocator.create_local(cond.ty);
+            push(
+                &mut loop_scope,
+                Instruction::new(Operator::Not(UnaryOperator { input: cond }), not_cond),
+            );
+
+            let mut exit = loop_scope.child();
+            self.enter();
+            self.phi_copies(&mut exit, header, merge);
+            if let Some(after_merge) = after_merge {
+                self.phi_copies(&mut exit, merge, after_merge);
+            }
+            push(&mut exit, Branch::Break);
+            self.leave();
+            push(
+                &mut loop_scope,
+                Branch::If(Box::new(If {
+                    cond: not_cond,
+                    scope: exit,
+                })),
+            );
+        }
+
+        self.phi_copies(&mut loop_scope, header, body);
+        self.region(&mut loop_scope, body, None);
+        if loop_scope.instructions.last() == Some(&Branch::Continue.into()) {
+            loop_scope.instructions.pop();
+        }
+
+        self.loops.pop();
+        self.leave();
+        push(scope, Branch::Loop(Box::new(Loop { scope: loop_scope })));
+
+        // Loops without a break never reach their merge block
+        let is_reachable =
+            self.opt.program.contains_node(merge) && !self.opt.predecessors(merge).is_empty();
+        after_merge.or(is_reachable.then_some(merge))
+    }
+
+    /// The successor of `merge` if it's an empty block that's only reached from `header`. Splitting
+    /// critical edges inserts these between the header and a merge that's also reached by breaking
+    /// from the body, so the exit of the header is emitted up to the successor instead.
+    fn forwarded_merge(&self, header: NodeIndex, merge: NodeIndex) -> Option<NodeIndex> {
+        if !self.opt.program.contains_node(merge) {
+            return None;
+        }
+        let block = &self.opt.program[merge];
+        let is_empty = block.ops.borrow().is_empty()
+            && block.phi_nodes.borrow().is_empty()
+            && matches!(*block.control_flow.borrow(), ControlFlow::None);
+        match (
+            self.opt.predecessors(merge).as_slice(),
+            self.opt.successors(merge).as_slice(),
+        ) {
+            ([pred], [next]) if is_empty && *pred == header => Some(*next),
+            _ => None,
+        }
+    }
+
+    /// Emits the continue target of the innermost loop and jumps back to the header.
+    fn continue_loop(&mut self, scope: &mut Scope, continue_target: NodeIndex) {
+        let header = self.loops.last().unwrap().header;
+        self.ops(scope, continue_target);
+        self.phi_copies(scope, continue_target, header);
+        push(scope, Branch::Continue);
+    }
+
+    /// Emits the branch from `from` to `to` as a new child scope, which ends at `join`.
+    fn arm(
+        &mut self,
+        scope: &mut Scope,
+        from: NodeIndex,
+        to: NodeIndex,
+        join: Option<NodeIndex>,
+    ) -> Scope {
+        let mut arm = scope.child();
+        self.enter();
+        self.phi_copies(&mut arm, from, to);
+        self.region(&mut arm, to, join);
+        self.leave();
+        arm
+    }
+
+    /// The block where all `arms` meet again, if any. If all arms leave the region the same way
+    /// (i.e. they all return), the join is the target of that exit. Arms that always break or
+    /// return are ignored if the others don't meet them.
+    fn join(&self, arms: &[NodeIndex]) -> Option<NodeIndex> {
+        let reachable = arms
+            .iter()
+            .map(|arm| self.reachable(*arm))
+            .collect::<Vec<_>>();
+        if let Some(join) = self.first_common(&reachable) {
+            return Some(join);
+        }
+
+        let open = reachable
+            .into_iter()
+            .filter(|blocks| {
+                blocks
+                    .iter()
+                    .any(|block| !matches!(self.exit(*block), Some(Exit::Break | Exit::Return)))
+            })
+            .collect::<Vec<_>>();
+        self.first_common(&open)
+    }
+
+    /// The first block that's in every set, which is the one that reaches all other common blocks.
+    fn first_common(&self, reachable: &[HashSet<NodeIndex>]) -> Option<NodeIndex> {
+        let (first, rest) = reachable.split_first()?;
+        let common = first
+            .iter()
+            .filter(|block| rest.iter().all(|it| it.contains(block)))
+            .copied()
+            .collect::<Vec<_>>();
+
+        common.iter().copied().find(|candidate| {
+            let reachable = self.reachable(*candidate);
+            common.iter().all(|it| reachable.contains(it))
+        })
+    }
+
+    /// All blocks that can be reached from `start` without leaving the current region. Nested
+    /// loops are skipped over, and exits are included but not followed.
+    fn reachable(&self, start: NodeIndex) -> HashSet<NodeIndex> {
+        let mut visited = HashSet::new();
+        let mut stack = vec![start];
+        while let Some(block) = stack.pop() {
+            let block = self.target(block);
+            if !visited.insert(block) || self.exit(block).is_some() {
+                continue;
+            }
+            match &*self.opt.program[block].control_flow.borrow() {
+                ControlFlow::Loop { merge, .. } | ControlFlow::LoopBreak { merge, .. } => {
+                    if self.opt.program.contains_node(*merge) {
+                        stack.push(*merge);
+                    }
+                }
+                _ => stack.extend(self.opt.successors(block)),
+            }
+        }
+        visited
+    }
+
+    /// How jumping to `block` leaves the current region, if it does.
+    fn exit(&self, block: NodeIndex) -> Option<Exit> {
+        if let Some(targets) = self.loops.last() {
+            if block == targets.merge || Some(block) == targets.after_merge {
+                return Some(Exit::Break);
+            }
+            if block == targets.continue_target {
+                return Some(Exit::Continue);
+            }
+        }
+        self.return_block(block).map(|_| Exit::Return)
+    }
+
+    /// Maps empty blocks that only lead to the return block to the return block itself, so
+    /// different paths to the return have the same target.
+    fn target(&self, block: NodeIndex) -> NodeIndex {
+        match self.loops.last() {
+            Some(targets)
+                if block == targets.merge
+                    || Some(block) == targets.after_merge
+                    || block == targets.continue_target =>
+            {
+                block
+            }
+            _ => self.return_block(block).unwrap_or(block),
+        }
+    }
+
+    fn return_block(&self, mut block: NodeIndex) -> Option<NodeIndex> {
+        loop {
+            let block_data = &self.opt.program[block];
+            if !block_data.ops.borrow().is_empty() {
+                return None;
+            }
+            match &*block_data.control_flow.borrow() {
+                ControlFlow::Return => return Some(block),
+                ControlFlow::None => match self.opt.successors(block).as_slice() {
+                    [next] => block = *next,
+                    _ => return None,
+                },
+                _ => return None,
+            }
+        }
+    }
+
+    /// Emits the instructions of `block`.
+    fn ops(&mut self, scope: &mut Scope, block: NodeIndex) {
+        let ops = self.opt.program[block].ops.clone();
+        let ops = ops.borrow().values().cloned().collect::<Vec<_>>();
+        let current = *self.scopes.last().unwrap();
+
+        for mut inst in ops {
+            self.opt
+                .visit_operation(&mut inst.operation, &mut inst.out, |_, var| {
+                    if let Some(def) = self.defs.get(var)
+                        && !self.scopes.contains(def)
+                    {
+                        self.escaping.insert(*var);
+                    }
+                });
+            if let Some(out) = inst.out
+                && matches!(out.kind, VariableKind::LocalConst { .. })
+            {
+                self.defs.insert(out, current);
+            }
+            scope.instructions.push(inst);
+        }
+    }
+
+    /// Assigns the values coming from `from` to the phi nodes of `to`.
+    fn phi_copies(&mut self, scope: &mut Scope, from: NodeIndex, to: NodeIndex) {
+        let copies = self.opt.program[to]
+            .phi_nodes
+            .borrow()
+            .iter()
+            .filter_map(|phi| {
+                let entry = phi.entries.iter().find(|entry| entry.block == from)?;
+                Some((phi.out, entry.value))
+            })
+            .filter(|(out, value)| out != value)
+            .collect::<Vec<_>>();
+        for (_, value) in &copies {
+            self.read(value);
+        }
+
+        // The phis are assigned in parallel, so values that are themselves phis of the same block
+        // must be read before any of them is assigned.
+        let is_parallel = copies
+            .iter()
+            .any(|(_, value)| copies.iter().any(|(out, _)| out == value));
+        if is_parallel {
+            let temps = copies
+                .into_iter()
+                .map(|(out, value)| {
+                    let temp = *self.opt.allocator.create_local(out.ty);
+                    push(scope, Instruction::new(Operation::Copy(value), temp));
+                    (out, temp)
+                })
+                .collect::<Vec<_>>();
+            for (out, temp) in temps {
+                push(scope, Instruction::new(Operation::Copy(temp), out));
+            }
+        } else {
+            for (out, value) in copies {
+                push(scope, Instruction::new(Operation::Copy(value), out));
+            }
+        }
+    }
+
+    fn read(&mut self, var: &Variable) {
+        if let Some(def) = self.defs.get(var)
+            && !self.scopes.contains(def)
+        {
+            self.escaping.insert(*var);
+        }
+    }
+
+    fn enter(&mut self) {
+        self.scopes.push(self.next_scope);
+        self.next_scope += 1;
+    }
+
+    fn leave(&mut self) {
+        self.scopes.pop();
+    }
+}
+
+/// Replaces SSA variables with mutable locals where needed, and collects the variables that must
+/// be declared in the root scope.
+struct Renamer {
+    allocator: Allocator,
+    escaping: HashSet<Variable>,
+    renames: HashMap<Variable, Variable>,
+    locals: Vec<Variable>,
+    matrices: Vec<Variable>,
+}
+
+impl Renamer {
+    fn rename_all(&mut self, opt: &mut Optimizer, instructions: &mut [Instruction]) {
+        for inst in instructions {
+            match &mut inst.operation {
+                Operation::Branch(branch) => match branch {
+                    Branch::If(if_) => {
+                        self.rename(&mut if_.cond);
+                        self.rename_all(opt, &mut if_.scope.instructions);
+                    }
+                    Branch::IfElse(if_else) => {
+                        self.rename(&mut if_else.cond);
+                        self.rename_all(opt, &mut if_else.scope_if.instructions);
+                        self.rename_all(opt, &mut if_else.scope_else.instructions);
+                    }
+                    Branch::Switch(switch) => {
+                        self.rename(&mut switch.value);
+                        self.rename_all(opt, &mut switch.scope_default.instructions);
+                        for (_, case) in &mut switch.cases {
+                            self.rename_all(opt, &mut case.instructions);
+                        }
+                    }
+                    Branch::Loop(loop_) => self.rename_all(opt, &mut loop_.scope.instructions),
+                    _ => {}
+                },
+                _ => {
+                    opt.visit_out(&mut inst.out, |_, var| self.rename(var));
+                    opt.visit_operation(&mut inst.operation, &mut inst.out, |_, var| {
+                        self.rename(var)
+                    });
+                }
+            }
+        }
+    }
+
+    fn rename(&mut self, var: &mut Variable) {
+        match var.kind {
+            VariableKind::Versioned { .. } => {}
+            VariableKind::LocalConst { .. } if self.escaping.contains(var) => {}
+            VariableKind::LocalMut { .. } => {
+                if !self.locals.contains(var) {
+                    self.locals.push(*var);
+                }
+                return;
+            }
+            VariableKind::Matrix { .. } => {
+                if !self.matrices.contains(var) {
+                    self.matrices.push(*var);
+                }
+                return;
+            }
+            _ => return,
+        }
+
+        let renamed = *self.renames.entry(*var).or_insert_with(|| {
+            let local = *self.allocator.create_local_restricted(var.ty);
+            self.locals.push(local);
+            local
+        });
+        *var = renamed;
+    }
+}
+
+fn push(scope: &mut Scope, instruction: impl Into<Instruction>) {
+    scope.instructions.push(instruction.into());
+}
@@ -1,4 +1,4 @@
-use crate::Optimizer;
+use crate::{Optimizer, UnrollOptions};
 use cubecl_core::CubeDim;
 use cubecl_ir::{Instruction, Processor, Scope};
 use std::rc::Rc;
@@ -8,6 +8,7 @@ use std::rc::Rc;
 pub struct OptimizerBuilder {
     transformers: Vec<Rc<dyn IrTransformer>>,
     processors: Vec<Box<dyn Processor>>,
+    unroll: Option<UnrollOptions>,
 }
 
 impl OptimizerBuilder {
@@ -22,9 +23,21 @@ impl OptimizerBuilder {
         self
     }
 
+    /// Partially unroll the loops with a trip count known at compile time
+    pub fn with_unroll(mut self, options: UnrollOptions) -> Self {
+        self.unroll = Some(options);
+        self
+    }
+
     /// Build and run optimizer on the scope
     pub fn optimize(self, expand: Scope, cube_dim: CubeDim) -> Optimizer {
-        Optimizer::new(expand, cube_dim, self.transformers, self.processors)
+        Optimizer::with_unroll(
+            expand,
+            cube_dim,
+            self.transformers,
+            self.processors,
+            self.unroll,
+        )
     }
 }
 
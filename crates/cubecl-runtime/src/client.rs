@@ -2,7 +2,7 @@ use crate::{
     config::{TypeNameFormatLevel, type_name_format},
     kernel::KernelMetadata,
     logging::ProfileLevel,
-    memory_management::{MemoryAllocationMode, MemoryUsage},
+    memory_management::{MemoryAllocationMode, MemoryTrace, MemoryUsage},
     runtime::Runtime,
     server::{
         ComputeServer, CopyDescriptor, CubeCount, ExecutionMode, Handle, IoError, KernelArguments,
@@ -192,6 +192,20 @@ impl<R: Runtime> ComputeClient<R> {
         self.read_tensor(vec![descriptor]).remove(0)
     }
 
+    /// Writes the contiguous data of each descriptor to its tensor, which may have any strides.
+    /// The bytes between the elements of the tensor are left unchanged.
+    ///
+    /// # Remarks
+    ///
+    /// The data must hold exactly the elements of the tensor, otherwise the write fails the next
+    /// time the server is synchronized.
+    pub fn write_tensor(&self, descriptors: Vec<(CopyDescriptor, Bytes)>) {
+        let stream_id = self.stream_id();
+        self.device.submit(move |server| {
+            server.write(descriptors, stream_id);
+        });
+    }
+
     /// Given a resource handle, returns the storage resource.
     pub fn get_resource(
         &self,
@@ -264,7 +278,7 @@ impl<R: Runtime> ComputeClient<R> {
                         layout.strides.clone(),
                         desc.elem_size,
                     ),
-                    Bytes::from_bytes_vec(data.to_vec()),
+                    data,
                 )
             })
             .collect::<Vec<_>>();
@@ -614,6 +628,153 @@ impl<R: Runtime> ComputeClient<R> {
         });
     }
 
+    /// Perform a `broadcast` of the data of the `root` device on the given devices.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip(self, src, dst, dtype, device_ids))
+    )]
+    pub fn broadcast(
+        &self,
+        src: Handle,
+        dst: Handle,
+        dtype: ElemType,
+        root: DeviceId,
+        device_ids: Vec<DeviceId>,
+    ) {
+        if DeviceHandle::<R::Server>::is_blocking() {
+            panic!("Can't use `broadcast` with a blocking device handle");
+        }
+
+        let stream_id = self.stream_id();
+        let src = src.binding();
+        let dst = dst.binding();
+
+        self.device.submit(move |server| {
+            server
+                .broadcast(src, dst, dtype, stream_id, root, device_ids)
+                .unwrap();
+        });
+    }
+
+    /// Perform a `reduce` operation on the given devices, writing the result on the `root` device.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip(self, src, dst, dtype, device_ids, op))
+    )]
+    pub fn reduce(
+        &self,
+        src: Handle,
+        dst: Handle,
+        dtype: ElemType,
+        root: DeviceId,
+        device_ids: Vec<DeviceId>,
+        op: ReduceOperation,
+    ) {
+        if DeviceHandle::<R::Server>::is_blocking() {
+            panic!("Can't use `reduce` with a blocking device handle");
+        }
+
+        let stream_id = self.stream_id();
+        let src = src.binding();
+        let dst = dst.binding();
+
+        self.device.submit(move |server| {
+            server
+                .reduce(src, dst, dtype, stream_id, op, root, device_ids)
+                .unwrap();
+        });
+    }
+
+    /// Perform an `all_gather` operation on the given devices.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip(self, src, dst, dtype, device_ids))
+    )]
+    pub fn all_gather(&self, src: Handle, dst: Handle, dtype: ElemType, device_ids: Vec<DeviceId>) {
+        if DeviceHandle::<R::Server>::is_blocking() {
+            panic!("Can't use `all_gather` with a blocking device handle");
+        }
+
+        let stream_id = self.stream_id();
+        let src = src.binding();
+        let dst = dst.binding();
+
+        self.device.submit(move |server| {
+            server
+                .all_gather(src, dst, dtype, stream_id, device_ids)
+                .unwrap();
+        });
+    }
+
+    /// Perform a `reduce_scatter` operation on the given devices.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip(self, src, dst, dtype, device_ids, op))
+    )]
+    pub fn reduce_scatter(
+        &self,
+        src: Handle,
+        dst: Handle,
+        dtype: ElemType,
+        device_ids: Vec<DeviceId>,
+        op: ReduceOperation,
+    ) {
+        if DeviceHandle::<R::Server>::is_blocking() {
+            panic!("Can't use `reduce_scatter` with a blocking device handle");
+        }
+
+        let stream_id = self.stream_id();
+        let src = src.binding();
+        let dst = dst.binding();
+
+        self.device.submit(move |server| {
+            server
+                .reduce_scatter(src, dst, dtype, stream_id, op, device_ids)
+                .unwrap();
+        });
+    }
+
+    /// Send data to the `peer` device, which must call [`recv`](Self::recv) with the same devices.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip(self, src, dtype, device_ids))
+    )]
+    pub fn send(&self, src: Handle, dtype: ElemType, peer: DeviceId, device_ids: Vec<DeviceId>) {
+        if DeviceHandle::<R::Server>::is_blocking() {
+            panic!("Can't use `send` with a blocking device handle");
+        }
+
+        let stream_id = self.stream_id();
+        let src = src.binding();
+
+        self.device.submit(move |server| {
+            server
+                .send(src, dtype, stream_id, peer, device_ids)
+                .unwrap();
+        });
+    }
+
+    /// Receive data from the `peer` device, which must call [`send`](Self::send) with the same
+    /// devices.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip(self, dst, dtype, device_ids))
+    )]
+    pub fn recv(&self, dst: Handle, dtype: ElemType, peer: DeviceId, device_ids: Vec<DeviceId>) {
+        if DeviceHandle::<R::Server>::is_blocking() {
+            panic!("Can't use `recv` with a blocking device handle");
+        }
+
+        let stream_id = self.stream_id();
+        let dst = dst.binding();
+
+        self.device.submit(move |server| {
+            server
+                .recv(dst, dtype, stream_id, peer, device_ids)
+                .unwrap();
+        });
+    }
+
     /// Transfer data from one client to another
     ///
     /// Make sure the source description can be read in a contiguous manner.
@@ -818,6 +979,17 @@ impl<R: Runtime> ComputeClient<R> {
             .unwrap()
     }
 
+    /// Take the memory events traced on this client's stream since the last call.
+    ///
+    /// The trace is empty unless [memory tracing](crate::config::memory::MemoryTracingConfig) is
+    /// enabled.
+    pub fn memory_trace(&self) -> Result<MemoryTrace, ServerError> {
+        let stream_id = self.stream_id();
+        self.device
+            .submit_blocking(move |server| server.memory_trace(stream_id))
+            .unwrap()
+    }
+
     /// Get all devices of a specific type available to this runtime
     pub fn enumerate_devices(&self, type_id: u16) -> Vec<DeviceId> {
         R::enumerate_devices(type_id, self.info())
@@ -13,12 +13,42 @@ pub struct AutotuneConfig {
     #[serde(default)]
     pub level: AutotuneLevel,
 
+    /// Budget limiting the time spent autotuning a single key.
+    #[serde(default)]
+    pub budget: AutotuneBudget,
+
     /// Cache location for storing autotune results.
     #[serde(default)]
     #[cfg(std_io)]
     pub cache: CacheConfig,
 }
 
+/// Budget limiting the time spent autotuning a single key, unbounded by default.
+///
+/// Every tunable is benchmarked with the same number of samples unless some limits are set, which
+/// can stall the first executions of an operation with many tunables.
+#[derive(Default, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct AutotuneBudget {
+    /// The maximum wall-clock time spent benchmarking a key, in milliseconds.
+    ///
+    /// Once exceeded, the remaining tunables are skipped as soon as one of them succeeded.
+    #[serde(default)]
+    pub max_duration_ms: Option<u64>,
+
+    /// The number of samples of the first round of successive halving.
+    ///
+    /// Every tunable is first benchmarked with that many samples, then only the fastest half is
+    /// benchmarked again with twice as many samples, until a single tunable is left or the usual
+    /// number of samples is reached.
+    #[serde(default)]
+    pub halving_samples: Option<usize>,
+
+    /// Abort the benchmark of a tunable when its first sample is that many times slower than the
+    /// fastest tunable so far.
+    #[serde(default)]
+    pub abort_ratio: Option<f64>,
+}
+
 /// Log levels for autotune logging in `CubeCL`.
 #[derive(Default, Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
 pub enum AutotuneLogLevel {
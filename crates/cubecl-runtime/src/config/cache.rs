@@ -1,6 +1,93 @@
+/// Persistent cache options.
+///
+/// Either a [location](CacheLocation) only, such as `cache = "global"`, or a table with the location
+/// and the limits of the cache, such as `cache = { location = "global", max_size_mb = 512 }`.
+#[derive(Default, Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(from = "CacheConfigRepr")]
+pub struct CacheConfig {
+    /// Where the cache is stored.
+    pub location: CacheLocation,
+
+    /// The maximum size of the cache on disk, in megabytes.
+    ///
+    /// Only used by the cache of compiled kernels, which evicts its oldest entries when it is
+    /// opened, so it can grow past that size in a process that keeps compiling new kernels.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_size_mb: Option<u64>,
+
+    /// The maximum number of days since an entry was written to the cache.
+    ///
+    /// Only used by the cache of compiled kernels, which evicts the expired entries when it is
+    /// opened.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u64>,
+}
+
+impl CacheConfig {
+    /// Returns the root directory for the cache.
+    pub fn root(&self) -> std::path::PathBuf {
+        self.location.root()
+    }
+
+    /// Applies the limits to the options of a cache.
+    pub fn apply_limits(
+        &self,
+        mut option: cubecl_common::cache::CacheOption,
+    ) -> cubecl_common::cache::CacheOption {
+        if let Some(max_size_mb) = self.max_size_mb {
+            option = option.max_size(max_size_mb * 1024 * 1024);
+        }
+        if let Some(max_age_days) = self.max_age_days {
+            option = option.max_age(core::time::Duration::from_secs(max_age_days * 24 * 60 * 60));
+        }
+        option
+    }
+}
+
+impl From<CacheLocation> for CacheConfig {
+    fn from(location: CacheLocation) -> Self {
+        Self {
+            location,
+            ..Default::default()
+        }
+    }
+}
+
+/// The accepted forms of a [cache config](CacheConfig).
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum CacheConfigRepr {
+    Location(CacheLocation),
+    Table {
+        #[serde(default)]
+        location: CacheLocation,
+        #[serde(default)]
+        max_size_mb: Option<u64>,
+        #[serde(default)]
+        max_age_days: Option<u64>,
+    },
+}
+
+impl From<CacheConfigRepr> for CacheConfig {
+    fn from(repr: CacheConfigRepr) -> Self {
+        match repr {
+            CacheConfigRepr::Location(location) => location.into(),
+            CacheConfigRepr::Table {
+                location,
+                max_size_mb,
+                max_age_days,
+            } => Self {
+                location,
+                max_size_mb,
+                max_age_days,
+            },
+        }
+    }
+}
+
 /// Cache location options.
 #[derive(Default, Clone, Debug, serde::Serialize, serde::Deserialize)]
-pub enum CacheConfig {
+pub enum CacheLocation {
     /// Stores cache in the current working directory.
     #[serde(rename = "local")]
     Local,
@@ -19,7 +106,7 @@ pub enum CacheConfig {
     File(std::path::PathBuf),
 }
 
-impl CacheConfig {
+impl CacheLocation {
     /// Returns the root directory for the cache.
     pub fn root(&self) -> std::path::PathBuf {
         match self {
@@ -46,3 +133,49 @@ impl CacheConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Section {
+        cache: CacheConfig,
+    }
+
+    #[test]
+    fn cache_config_accepts_location_only() {
+        let section: Section = toml::from_str(r#"cache = "global""#).unwrap();
+
+        assert!(matches!(section.cache.location, CacheLocation::Global));
+        assert_eq!(section.cache.max_size_mb, None);
+        assert_eq!(section.cache.max_age_days, None);
+    }
+
+    #[test]
+    fn cache_config_accepts_location_and_limits() {
+        let section: Section =
+            toml::from_str(r#"cache = { location = { file = "kernels" }, max_size_mb = 512 }"#)
+                .unwrap();
+
+        assert!(
+            matches!(section.cache.location, CacheLocation::File(path) if path.as_os_str() == "kernels")
+        );
+        assert_eq!(section.cache.max_size_mb, Some(512));
+        assert_eq!(section.cache.max_age_days, None);
+    }
+
+    #[test]
+    fn cache_config_round_trips() {
+        let config = CacheConfig {
+            location: CacheLocation::Local,
+            max_size_mb: None,
+            max_age_days: Some(30),
+        };
+        let content = toml::to_string(&Section { cache: config }).unwrap();
+        let section: Section = toml::from_str(&content).unwrap();
+
+        assert!(matches!(section.cache.location, CacheLocation::Local));
+        assert_eq!(section.cache.max_age_days, Some(30));
+    }
+}
@@ -8,13 +8,23 @@ pub struct CompilationConfig {
     /// Logger configuration for compilation logs, using binary log levels.
     #[serde(default)]
     pub logger: LoggerConfig<CompilationLogLevel>,
-    /// Cache location for storing compiled kernels.
+    /// Cache location and limits for storing compiled kernels, disabled when not set.
     #[serde(default)]
     #[cfg(std_io)]
     pub cache: Option<CacheConfig>,
     /// Controls whether kernel launches enforce bounds checks.
     #[serde(default)]
     pub check_mode: BoundsCheckMode,
+    /// Run the full optimizer for backends that compile the IR to source code (WGSL, CUDA, HIP
+    /// and Metal) and for the interpreter, which removes redundant instructions at the cost of a
+    /// longer compilation.
+    #[serde(default)]
+    pub optimize: bool,
+    /// Partially unroll the loops with a trip count known at compile time. Only applies to
+    /// backends running the full optimizer, so WGSL, CUDA, HIP, Metal and the interpreter also need
+    /// `optimize`.
+    #[serde(default)]
+    pub unroll_loops: bool,
 }
 
 /// Bounds checks options.
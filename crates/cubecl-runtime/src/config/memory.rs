@@ -9,6 +9,26 @@ pub struct MemoryConfig {
     /// Configuration for persistent memory pools.
     #[serde(default)]
     pub persistent_memory: PersistentMemory,
+    /// Configuration for tracing memory events.
+    #[serde(default)]
+    pub tracing: MemoryTracingConfig,
+}
+
+/// Configuration of the memory event tracer, disabled by default.
+///
+/// When enabled, every reservation, binding, cleanup and kernel launch is recorded with the memory
+/// usage at that time, see [`MemoryTrace`](crate::memory_management::MemoryTrace).
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Default)]
+pub struct MemoryTracingConfig {
+    /// Whether memory events are traced.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Whether a backtrace is captured with every event, which slows down every allocation.
+    #[serde(default)]
+    pub backtrace: bool,
+    /// The maximum number of events kept in memory, the oldest are dropped first.
+    #[serde(default)]
+    pub max_events: Option<usize>,
 }
 
 /// Configuration options for persistent memory pools in `CubeCL` runtimes.
@@ -0,0 +1,70 @@
+use core::hash::Hash;
+
+use cubecl_common::{
+    cache::{CacheOption, CacheValue},
+    compilation_cache::CompilationCache,
+    hash::{StableHash, StableHasher},
+};
+
+use crate::{config::GlobalConfig, id::KernelId};
+
+/// A persistent cache of compiled kernels, usable by any runtime.
+///
+/// Entries are keyed by the [kernel id](KernelId), the compilation options and the device
+/// properties, and are stored in a directory specific to the version of `CubeCL`, so they are
+/// never reused by a compiler that could produce something different. The location and the limits
+/// of the cache come from the compilation settings of the [global config](GlobalConfig).
+#[derive(Debug)]
+pub struct KernelCache<V: CacheValue> {
+    cache: CompilationCache<StableHash, V>,
+    context: StableHash,
+}
+
+impl<V: CacheValue + core::fmt::Debug> KernelCache<V> {
+    /// Opens the cache of the kernels compiled to `format` by `runtime`, returns `None` if the
+    /// compilation cache is disabled.
+    ///
+    /// The `properties_hash` is a stable hash of the properties of the device the kernels are
+    /// compiled for, see [`DeviceProperties::checksum`](cubecl_ir::DeviceProperties::checksum).
+    pub fn new(
+        runtime: &str,
+        format: &str,
+        compilation_options: &impl Hash,
+        properties_hash: u64,
+    ) -> Option<Self> {
+        let config = GlobalConfig::get();
+        let cache = config.compilation.cache.as_ref()?;
+        let option = CacheOption::default()
+            .name(runtime)
+            .version(env!("CARGO_PKG_VERSION"))
+            .root(cache.root());
+        let option = cache.apply_limits(option);
+
+        let mut hasher = StableHasher::new();
+        compilation_options.hash(&mut hasher);
+        properties_hash.hash(&mut hasher);
+
+        Some(Self {
+            cache: CompilationCache::new(format, option),
+            context: hasher.finalize(),
+        })
+    }
+
+    /// The key of a kernel in the cache.
+    pub fn key(&self, kernel_id: &KernelId) -> StableHash {
+        StableHasher::hash_one(&(self.context, kernel_id.stable_hash()))
+    }
+
+    /// Fetch a compiled kernel from the cache.
+    pub fn get(&self, key: &StableHash) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    /// Insert a compiled kernel in the cache, failing to do so only logs a warning since the
+    /// kernel can always be compiled again.
+    pub fn insert(&mut self, key: StableHash, value: V) {
+        if let Err(err) = self.cache.insert(key, value) {
+            log::warn!("Unable to save the compiled kernel {err:?}");
+        }
+    }
+}
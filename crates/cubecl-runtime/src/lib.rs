@@ -46,6 +46,9 @@ pub mod tma;
 
 /// Compiler trait and related types
 pub mod compiler;
+/// Persistent cache of compiled kernels.
+#[cfg(std_io)]
+pub mod kernel_cache;
 /// Runtime trait and related types
 pub mod runtime;
 /// Simple system profiling using timestamps.
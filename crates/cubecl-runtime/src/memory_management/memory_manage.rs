@@ -1,11 +1,12 @@
 use super::{
-    MemoryConfiguration, MemoryPoolOptions, MemoryUsage, PoolType,
+    MemoryConfiguration, MemoryEventKind, MemoryPoolOptions, MemoryTrace, MemoryTracer,
+    MemoryUsage, PoolType,
     memory_pool::{ExclusiveMemoryPool, MemoryPool, PersistentPool, SlicedPool},
 };
 use crate::{
     config::{
         GlobalConfig,
-        memory::{MemoryLogLevel, PersistentMemory},
+        memory::{MemoryLogLevel, MemoryTracingConfig, PersistentMemory},
     },
     logging::ServerLogger,
     memory_management::{BytesFormat, memory_pool::Slice},
@@ -123,6 +124,7 @@ pub struct MemoryManagement<Storage> {
     mode: MemoryAllocationMode,
     config: PersistentMemory,
     logger: Arc<ServerLogger>,
+    tracer: Option<MemoryTracer>,
 }
 
 fn generate_bucket_sizes(
@@ -160,6 +162,8 @@ pub struct MemoryManagementOptions {
     name: String,
     /// The [`MemoryAllocationOption`] used by this instance.
     memory: MemoryAllocationOption,
+    /// The tracing configuration, overriding the [`GlobalConfig`] when provided.
+    tracing: Option<MemoryTracingConfig>,
 }
 
 impl MemoryManagementOptions {
@@ -168,6 +172,7 @@ impl MemoryManagementOptions {
         Self {
             name: name.into(),
             memory: MemoryAllocationOption::FromConfig,
+            tracing: None,
         }
     }
 
@@ -176,6 +181,12 @@ impl MemoryManagementOptions {
         self.memory = MemoryAllocationOption::Provided(mode);
         self
     }
+
+    /// Traces the memory events with the provided configuration instead of the [`GlobalConfig`].
+    pub fn tracing(mut self, tracing: MemoryTracingConfig) -> Self {
+        self.tracing = Some(tracing);
+        self
+    }
 }
 
 #[derive(Default, Debug)]
@@ -329,7 +340,11 @@ impl<Storage: ComputeStorage> MemoryManagement<Storage> {
             })
             .collect();
 
-        let config = GlobalConfig::get().memory.persistent_memory.clone();
+        let global_config = GlobalConfig::get();
+        let config = global_config.memory.persistent_memory.clone();
+        let tracing = options
+            .tracing
+            .unwrap_or_else(|| global_config.memory.tracing.clone());
 
         let mode = match options.memory {
             MemoryAllocationOption::Provided(mode) => mode,
@@ -353,6 +368,7 @@ impl<Storage: ComputeStorage> MemoryManagement<Storage> {
             mode,
             config,
             logger,
+            tracer: tracing.enabled.then(|| MemoryTracer::new(tracing)),
         }
     }
 
@@ -383,12 +399,38 @@ impl<Storage: ComputeStorage> MemoryManagement<Storage> {
             || "Manual memory cleanup ...".to_string(),
         );
 
+        if self.tracer.is_none() {
+            self.persistent
+                .cleanup(&mut self.storage, self.alloc_reserve_count, explicit);
+
+            for pool in self.pools.iter_mut() {
+                pool.cleanup(&mut self.storage, self.alloc_reserve_count, explicit);
+            }
+            return;
+        }
+
+        let mut freed = Vec::new();
+        let reserved = self.persistent.get_memory_usage().bytes_reserved;
         self.persistent
             .cleanup(&mut self.storage, self.alloc_reserve_count, explicit);
+        let released = reserved - self.persistent.get_memory_usage().bytes_reserved;
+        freed.push((self.pools.len() as u8, released));
 
-        for pool in self.pools.iter_mut() {
+        for (index, pool) in self.pools.iter_mut().enumerate() {
+            let reserved = pool.get_memory_usage().bytes_reserved;
             pool.cleanup(&mut self.storage, self.alloc_reserve_count, explicit);
+            let released = reserved - pool.get_memory_usage().bytes_reserved;
+            freed.push((index as u8, released));
         }
+
+        let mut total = 0;
+        for (pool, released) in freed {
+            if released > 0 {
+                self.trace(MemoryEventKind::Free, Some(pool), released);
+                total += released;
+            }
+        }
+        self.trace(MemoryEventKind::Cleanup { explicit }, None, total);
     }
 
     /// Returns the storage from the specified binding
@@ -449,6 +491,29 @@ impl<Storage: ComputeStorage> MemoryManagement<Storage> {
     /// Finds a spot in memory for a resource with the given size in bytes, and returns a handle to it
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
     pub fn reserve(&mut self, size: u64) -> Result<ManagedMemoryHandle, IoError> {
+        if self.tracer.is_none() {
+            return self.reserve_slice(size);
+        }
+
+        let reserved = self.memory_usage().bytes_reserved;
+        let result = self.reserve_slice(size);
+
+        match &result {
+            Ok(handle) => {
+                let pool = handle.descriptor().location().pool;
+                let kind = match self.memory_usage().bytes_reserved > reserved {
+                    true => MemoryEventKind::Alloc,
+                    false => MemoryEventKind::Reserve,
+                };
+                self.trace(kind, Some(pool), size);
+            }
+            Err(_) => self.trace(MemoryEventKind::ReserveFailed, None, size),
+        }
+
+        result
+    }
+
+    fn reserve_slice(&mut self, size: u64) -> Result<ManagedMemoryHandle, IoError> {
         // If this happens every nanosecond, counts overflows after 585 years, so not worth thinking too
         // hard about overflow here.
         self.alloc_reserve_count += 1;
@@ -555,6 +620,39 @@ impl<Storage: ComputeStorage> MemoryManagement<Storage> {
         log::info!("{}", self.memory_usage());
     }
 
+    /// Whether the memory events are traced.
+    pub fn is_tracing(&self) -> bool {
+        self.tracer.is_some()
+    }
+
+    /// Record the launch of a kernel with the current memory usage, when tracing is enabled.
+    pub fn trace_launch(&mut self, kernel: &str) {
+        if self.tracer.is_some() {
+            self.trace(
+                MemoryEventKind::Launch {
+                    kernel: kernel.to_string(),
+                },
+                None,
+                0,
+            );
+        }
+    }
+
+    /// Take the memory events traced since the last call, empty if tracing isn't enabled.
+    pub fn take_trace(&mut self) -> MemoryTrace {
+        match &mut self.tracer {
+            Some(tracer) => tracer.take(),
+            None => MemoryTrace::default(),
+        }
+    }
+
+    fn trace(&mut self, kind: MemoryEventKind, pool: Option<u8>, size: u64) {
+        let usage = self.memory_usage();
+        if let Some(tracer) = &mut self.tracer {
+            tracer.record(kind, pool, size, &usage);
+        }
+    }
+
     /// Binds the given [handle](HandleId) to a [`MemorySlot`].
     pub fn bind(
         &mut self,
@@ -572,6 +670,14 @@ impl<Storage: ComputeStorage> MemoryManagement<Storage> {
         }
 
         let pool_index = descriptor.location().pool as usize;
+        if self.tracer.is_some() {
+            let size = self
+                .find(reserved.clone().binding())
+                .map(|slice| slice.storage.size())
+                .unwrap_or_default();
+            self.trace(MemoryEventKind::Bind, Some(pool_index as u8), size);
+        }
+
         if pool_index >= self.pools.len() {
             return self.persistent.bind(reserved, assigned, cursor);
         }
@@ -633,6 +739,7 @@ mod tests {
         MemoryManagementOptions {
             name: "test".into(),
             memory: MemoryAllocationOption::FromConfig,
+            tracing: None,
         }
     }
 
@@ -687,6 +794,54 @@ mod tests {
         assert_eq!(usage, usage_new);
     }
 
+    #[test_log::test]
+    fn test_memory_tracing() {
+        let mut memory_management = MemoryManagement::from_configuration(
+            BytesStorage::default(),
+            &DUMMY_MEM_PROPS,
+            MemoryConfiguration::Custom {
+                pool_options: vec![MemoryPoolOptions {
+                    pool_type: PoolType::ExclusivePages {
+                        max_alloc_size: 512,
+                    },
+                    dealloc_period: None,
+                }],
+            },
+            Arc::new(ServerLogger::default()),
+            options().tracing(MemoryTracingConfig {
+                enabled: true,
+                ..Default::default()
+            }),
+        );
+        assert!(memory_management.is_tracing());
+
+        let handle = memory_management.reserve(100).unwrap();
+        memory_management.trace_launch("kernel");
+        drop(handle);
+        let _handle = memory_management.reserve(100).unwrap();
+        memory_management.cleanup(true);
+        assert!(memory_management.reserve(1024).is_err());
+
+        let trace = memory_management.take_trace();
+        let kinds: Vec<_> = trace.events().iter().map(|event| &event.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                &MemoryEventKind::Alloc,
+                &MemoryEventKind::Launch {
+                    kernel: "kernel".into()
+                },
+                &MemoryEventKind::Reserve,
+                &MemoryEventKind::Cleanup { explicit: true },
+                &MemoryEventKind::ReserveFailed,
+            ]
+        );
+        assert_eq!(trace.events()[0].pool, Some(0));
+        assert_eq!(trace.events()[1].bytes_in_use, 100);
+        assert_eq!(trace.kernel_peaks()[0].bytes_in_use, 100);
+        assert!(memory_management.take_trace().events().is_empty());
+    }
+
     #[test_log::test]
     fn alloc_two_chunks_on_one_page() {
         let page_size = 2048;
@@ -11,6 +11,9 @@ pub use base::*;
 mod memory_manage;
 pub use memory_manage::*;
 
+mod tracer;
+pub use tracer::*;
+
 use alloc::vec::Vec;
 
 /// The type of memory pool to use.
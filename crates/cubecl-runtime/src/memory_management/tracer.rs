@@ -0,0 +1,532 @@
+use super::{BytesFormat, MemoryUsage};
+use crate::config::memory::MemoryTracingConfig;
+
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+use core::time::Duration;
+use cubecl_common::{backtrace::BackTrace, stream_id::StreamId};
+use hashbrown::HashMap;
+use web_time::Instant;
+
+const BINARY_MAGIC: &[u8; 4] = b"CCMT";
+const BINARY_VERSION: u8 = 1;
+const NO_POOL: u8 = u8::MAX;
+
+/// The kind of a traced [memory event](MemoryEvent).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemoryEventKind {
+    /// A slice was reserved in memory that was already allocated.
+    Reserve,
+    /// A new page was allocated to reserve a slice.
+    Alloc,
+    /// A reservation failed, usually because the device is out of memory.
+    ReserveFailed,
+    /// A reserved slice was bound to another handle.
+    Bind,
+    /// Memory was released by a pool during a cleanup.
+    Free,
+    /// The memory pools were cleaned up.
+    Cleanup {
+        /// Whether the cleanup was requested by the user.
+        explicit: bool,
+    },
+    /// A kernel was launched.
+    Launch {
+        /// The name of the kernel.
+        kernel: String,
+    },
+}
+
+impl MemoryEventKind {
+    fn name(&self) -> &str {
+        match self {
+            MemoryEventKind::Reserve => "reserve",
+            MemoryEventKind::Alloc => "alloc",
+            MemoryEventKind::ReserveFailed => "reserve_failed",
+            MemoryEventKind::Bind => "bind",
+            MemoryEventKind::Free => "free",
+            MemoryEventKind::Cleanup { .. } => "cleanup",
+            MemoryEventKind::Launch { kernel } => kernel,
+        }
+    }
+
+    fn tag(&self) -> u8 {
+        match self {
+            MemoryEventKind::Reserve => 0,
+            MemoryEventKind::Alloc => 1,
+            MemoryEventKind::ReserveFailed => 2,
+            MemoryEventKind::Bind => 3,
+            MemoryEventKind::Free => 4,
+            MemoryEventKind::Cleanup { .. } => 5,
+            MemoryEventKind::Launch { .. } => 6,
+        }
+    }
+}
+
+/// An event traced by the [memory management](super::MemoryManagement).
+#[derive(Debug, Clone)]
+pub struct MemoryEvent {
+    /// What happened.
+    pub kind: MemoryEventKind,
+    /// The time since the tracing started.
+    pub timestamp: Duration,
+    /// The stream that caused the event.
+    pub stream_id: StreamId,
+    /// The index of the pool involved, the persistent pool comes after the dynamic ones.
+    pub pool: Option<u8>,
+    /// The number of bytes involved.
+    pub size: u64,
+    /// The number of bytes in use after the event.
+    pub bytes_in_use: u64,
+    /// The number of bytes reserved on the device after the event.
+    pub bytes_reserved: u64,
+    /// Where the event happened, when backtraces are captured.
+    pub backtrace: Option<BackTrace>,
+}
+
+/// The memory usage when a kernel was launched, over all of its launches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KernelMemoryPeak {
+    /// The name of the kernel.
+    pub kernel: String,
+    /// The number of times the kernel was launched.
+    pub launches: u64,
+    /// The maximum number of bytes in use when the kernel was launched.
+    pub bytes_in_use: u64,
+    /// The maximum number of bytes reserved on the device when the kernel was launched.
+    pub bytes_reserved: u64,
+}
+
+impl core::fmt::Display for KernelMemoryPeak {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} ({} launches): {} in use, {} reserved",
+            self.kernel,
+            self.launches,
+            BytesFormat::new(self.bytes_in_use),
+            BytesFormat::new(self.bytes_reserved),
+        )
+    }
+}
+
+/// The memory events traced on a stream, see [`MemoryTracingConfig`].
+#[derive(Debug, Clone, Default)]
+pub struct MemoryTrace {
+    events: Vec<MemoryEvent>,
+    dropped: u64,
+}
+
+impl MemoryTrace {
+    /// The traced events, from the oldest to the most recent.
+    pub fn events(&self) -> &[MemoryEvent] {
+        &self.events
+    }
+
+    /// The number of events dropped because the trace exceeded the maximum number of events.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// The memory usage of every launched kernel, from the one launched with the most memory
+    /// reserved.
+    pub fn kernel_peaks(&self) -> Vec<KernelMemoryPeak> {
+        let mut peaks = HashMap::<&str, KernelMemoryPeak>::new();
+
+        for event in self.events.iter() {
+            if let MemoryEventKind::Launch { kernel } = &event.kind {
+                let peak = peaks
+                    .entry(kernel.as_str())
+                    .or_insert_with(|| KernelMemoryPeak {
+                        kernel: kernel.clone(),
+                        launches: 0,
+                        bytes_in_use: 0,
+                        bytes_reserved: 0,
+                    });
+                peak.launches += 1;
+                peak.bytes_in_use = peak.bytes_in_use.max(event.bytes_in_use);
+                peak.bytes_reserved = peak.bytes_reserved.max(event.bytes_reserved);
+            }
+        }
+
+        let mut peaks: Vec<_> = peaks.into_values().collect();
+        peaks.sort_by(|a, b| {
+            b.bytes_reserved
+                .cmp(&a.bytes_reserved)
+                .then_with(|| b.bytes_in_use.cmp(&a.bytes_in_use))
+                .then_with(|| a.kernel.cmp(&b.kernel))
+        });
+        peaks
+    }
+
+    /// A report of the memory usage of every launched kernel, see [`Self::kernel_peaks`].
+    pub fn peak_report(&self) -> String {
+        let mut report = String::from("Kernel Memory Peaks:\n");
+        for peak in self.kernel_peaks() {
+            report += &format!("  {peak}\n");
+        }
+        report
+    }
+
+    /// Serialize the trace to the JSON format of the Chrome trace viewer (`chrome://tracing` or
+    /// Perfetto), with the memory usage as counters and every event as an instant.
+    pub fn to_chrome_trace(&self) -> String {
+        let mut json = String::from("{\"traceEvents\":[");
+
+        for (i, event) in self.events.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            let ts = event.timestamp.as_nanos() as f64 / 1000.0;
+            let tid = event.stream_id.value;
+            let category = match event.kind {
+                MemoryEventKind::Launch { .. } => "launch",
+                _ => "memory",
+            };
+
+            write!(
+                json,
+                "{{\"name\":\"memory\",\"ph\":\"C\",\"ts\":{ts},\"pid\":0,\"args\":{{\"in_use\":{},\"reserved\":{}}}}},",
+                event.bytes_in_use, event.bytes_reserved
+            )
+            .unwrap();
+            write!(
+                json,
+                "{{\"name\":\"{}\",\"cat\":\"{category}\",\"ph\":\"i\",\"s\":\"t\",\"ts\":{ts},\"pid\":0,\"tid\":{tid},\"args\":{{\"size\":{}",
+                escape_json(event.kind.name()),
+                event.size
+            )
+            .unwrap();
+            if let Some(pool) = event.pool {
+                write!(json, ",\"pool\":{pool}").unwrap();
+            }
+            if let Some(backtrace) = &event.backtrace {
+                write!(
+                    json,
+                    ",\"backtrace\":\"{}\"",
+                    escape_json(&format!("{backtrace}"))
+                )
+                .unwrap();
+            }
+            json.push_str("}}");
+        }
+
+        json.push_str("],\"displayTimeUnit\":\"ms\"}");
+        json
+    }
+
+    /// Serialize the trace to a compact binary format, without the backtraces.
+    ///
+    /// Every integer is little-endian. The format starts with the `CCMT` magic, a version byte,
+    /// the number of dropped and traced events as `u64`, followed by the events.
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(21 + self.events.len() * 42);
+        bytes.extend_from_slice(BINARY_MAGIC);
+        bytes.push(BINARY_VERSION);
+        bytes.extend_from_slice(&self.dropped.to_le_bytes());
+        bytes.extend_from_slice(&(self.events.len() as u64).to_le_bytes());
+
+        for event in self.events.iter() {
+            bytes.push(event.kind.tag());
+            bytes.extend_from_slice(&(event.timestamp.as_nanos() as u64).to_le_bytes());
+            bytes.extend_from_slice(&event.stream_id.value.to_le_bytes());
+            bytes.push(event.pool.unwrap_or(NO_POOL));
+            bytes.extend_from_slice(&event.size.to_le_bytes());
+            bytes.extend_from_slice(&event.bytes_in_use.to_le_bytes());
+            bytes.extend_from_slice(&event.bytes_reserved.to_le_bytes());
+
+            match &event.kind {
+                MemoryEventKind::Cleanup { explicit } => bytes.push(*explicit as u8),
+                MemoryEventKind::Launch { kernel } => {
+                    bytes.extend_from_slice(&(kernel.len() as u32).to_le_bytes());
+                    bytes.extend_from_slice(kernel.as_bytes());
+                }
+                _ => {}
+            }
+        }
+
+        bytes
+    }
+
+    /// Deserialize a trace serialized with [`Self::to_binary`], returning `None` if it's invalid.
+    pub fn from_binary(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader { bytes };
+
+        if reader.take(4)? != BINARY_MAGIC || reader.u8()? != BINARY_VERSION {
+            return None;
+        }
+        let dropped = reader.u64()?;
+        let num_events = reader.u64()?;
+
+        let mut events = Vec::new();
+        for _ in 0..num_events {
+            let tag = reader.u8()?;
+            let timestamp = Duration::from_nanos(reader.u64()?);
+            let stream_id = StreamId {
+                value: reader.u64()?,
+            };
+            let pool = match reader.u8()? {
+                NO_POOL => None,
+                pool => Some(pool),
+            };
+            let size = reader.u64()?;
+            let bytes_in_use = reader.u64()?;
+            let bytes_reserved = reader.u64()?;
+
+            let kind = match tag {
+                0 => MemoryEventKind::Reserve,
+                1 => MemoryEventKind::Alloc,
+                2 => MemoryEventKind::ReserveFailed,
+                3 => MemoryEventKind::Bind,
+                4 => MemoryEventKind::Free,
+                5 => MemoryEventKind::Cleanup {
+                    explicit: reader.u8()? != 0,
+                },
+                6 => {
+                    let len = reader.u32()? as usize;
+                    let kernel = String::from_utf8(reader.take(len)?.to_vec()).ok()?;
+                    MemoryEventKind::Launch { kernel }
+                }
+                _ => return None,
+            };
+
+            events.push(MemoryEvent {
+                kind,
+                timestamp,
+                stream_id,
+                pool,
+                size,
+                bytes_in_use,
+                bytes_reserved,
+                backtrace: None,
+            });
+        }
+
+        Some(Self { events, dropped })
+    }
+}
+
+/// Records the memory events of a [memory management](super::MemoryManagement).
+pub(crate) struct MemoryTracer {
+    start: Instant,
+    config: MemoryTracingConfig,
+    events: VecDeque<MemoryEvent>,
+    dropped: u64,
+}
+
+impl MemoryTracer {
+    pub(crate) fn new(config: MemoryTracingConfig) -> Self {
+        Self {
+            start: Instant::now(),
+            config,
+            events: VecDeque::new(),
+            dropped: 0,
+        }
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        kind: MemoryEventKind,
+        pool: Option<u8>,
+        size: u64,
+        usage: &MemoryUsage,
+    ) {
+        if let Some(max_events) = self.config.max_events {
+            if max_events == 0 {
+                self.dropped += 1;
+                return;
+            }
+            if self.events.len() >= max_events {
+                self.events.pop_front();
+                self.dropped += 1;
+            }
+        }
+
+        self.events.push_back(MemoryEvent {
+            kind,
+            timestamp: self.start.elapsed(),
+            stream_id: StreamId::current(),
+            pool,
+            size,
+            bytes_in_use: usage.bytes_in_use,
+            bytes_reserved: usage.bytes_reserved,
+            backtrace: self.config.backtrace.then(BackTrace::capture),
+        });
+    }
+
+    /// Take the events traced so far.
+    pub(crate) fn take(&mut self) -> MemoryTrace {
+        MemoryTrace {
+            events: self.events.drain(..).collect(),
+            dropped: core::mem::take(&mut self.dropped),
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < len {
+            return None;
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+}
+
+fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    fn usage(bytes_in_use: u64, bytes_reserved: u64) -> MemoryUsage {
+        MemoryUsage {
+            number_allocs: 1,
+            bytes_in_use,
+            bytes_padding: 0,
+            bytes_reserved,
+        }
+    }
+
+    fn launch(kernel: &str) -> MemoryEventKind {
+        MemoryEventKind::Launch {
+            kernel: kernel.to_string(),
+        }
+    }
+
+    fn trace() -> MemoryTrace {
+        let mut tracer = MemoryTracer::new(MemoryTracingConfig {
+            enabled: true,
+            ..Default::default()
+        });
+        tracer.record(MemoryEventKind::Alloc, Some(1), 256, &usage(256, 1024));
+        tracer.record(launch("matmul"), None, 0, &usage(256, 1024));
+        tracer.record(MemoryEventKind::Reserve, Some(0), 64, &usage(320, 1024));
+        tracer.record(launch("\"add\""), None, 0, &usage(320, 1024));
+        tracer.record(MemoryEventKind::Free, Some(1), 512, &usage(320, 512));
+        tracer.record(
+            MemoryEventKind::Cleanup { explicit: true },
+            None,
+            512,
+            &usage(320, 512),
+        );
+        tracer.record(launch("matmul"), None, 0, &usage(512, 2048));
+        tracer.take()
+    }
+
+    #[test_log::test]
+    fn test_kernel_peaks() {
+        let peaks = trace().kernel_peaks();
+
+        assert_eq!(
+            peaks,
+            vec![
+                KernelMemoryPeak {
+                    kernel: "matmul".to_string(),
+                    launches: 2,
+                    bytes_in_use: 512,
+                    bytes_reserved: 2048,
+                },
+                KernelMemoryPeak {
+                    kernel: "\"add\"".to_string(),
+                    launches: 1,
+                    bytes_in_use: 320,
+                    bytes_reserved: 1024,
+                },
+            ]
+        );
+    }
+
+    #[test_log::test]
+    fn test_binary_roundtrip() {
+        let trace = trace();
+        let decoded = MemoryTrace::from_binary(&trace.to_binary()).unwrap();
+
+        assert_eq!(decoded.dropped(), trace.dropped());
+        assert_eq!(decoded.events().len(), trace.events().len());
+        for (decoded, event) in decoded.events().iter().zip(trace.events()) {
+            assert_eq!(decoded.kind, event.kind);
+            assert_eq!(decoded.timestamp.as_nanos(), event.timestamp.as_nanos());
+            assert_eq!(decoded.stream_id, event.stream_id);
+            assert_eq!(decoded.pool, event.pool);
+            assert_eq!(decoded.size, event.size);
+            assert_eq!(decoded.bytes_in_use, event.bytes_in_use);
+            assert_eq!(decoded.bytes_reserved, event.bytes_reserved);
+        }
+
+        let bytes = trace.to_binary();
+        assert!(MemoryTrace::from_binary(&bytes[..bytes.len() - 1]).is_none());
+        assert!(MemoryTrace::from_binary(b"not a trace").is_none());
+    }
+
+    #[test_log::test]
+    fn test_chrome_trace() {
+        let json = trace().to_chrome_trace();
+
+        assert!(json.starts_with("{\"traceEvents\":["), "{json}");
+        assert!(json.ends_with("],\"displayTimeUnit\":\"ms\"}"), "{json}");
+        assert!(
+            json.contains("\"name\":\"\\\"add\\\"\",\"cat\":\"launch\""),
+            "{json}"
+        );
+        assert!(
+            json.contains("\"args\":{\"in_use\":512,\"reserved\":2048}"),
+            "{json}"
+        );
+        assert!(json.contains("\"args\":{\"size\":64,\"pool\":0}"), "{json}");
+    }
+
+    #[test_log::test]
+    fn test_max_events() {
+        let mut tracer = MemoryTracer::new(MemoryTracingConfig {
+            enabled: true,
+            backtrace: false,
+            max_events: Some(2),
+        });
+        for size in 0..5 {
+            tracer.record(MemoryEventKind::Reserve, Some(0), size, &usage(size, 64));
+        }
+
+        let trace = tracer.take();
+        assert_eq!(trace.dropped(), 3);
+        let sizes: Vec<_> = trace.events().iter().map(|event| event.size).collect();
+        assert_eq!(sizes, vec![3, 4]);
+        assert!(tracer.take().events().is_empty());
+    }
+}
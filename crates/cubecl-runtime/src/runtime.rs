@@ -50,3 +50,39 @@ pub trait Runtime: Sized + Send + Sync + 'static + core::fmt::Debug + Clone {
         Self::enumerate_devices(0, info)
     }
 }
+
+/// The largest plane emulated by the runtimes executing kernels on the host, since ballots are
+/// limited to 128 lanes.
+pub const MAX_EMULATED_PLANE_SIZE: u32 = 128;
+
+/// Validate the plane size configured for a runtime emulating planes on the host, clamping it to
+/// the closest power of two smaller or equal to [`MAX_EMULATED_PLANE_SIZE`].
+pub fn emulated_plane_size(plane_size: u32) -> u32 {
+    if plane_size.is_power_of_two() && plane_size <= MAX_EMULATED_PLANE_SIZE {
+        return plane_size;
+    }
+
+    let clamped = plane_size
+        .clamp(1, MAX_EMULATED_PLANE_SIZE)
+        .next_power_of_two()
+        .min(MAX_EMULATED_PLANE_SIZE);
+    log::warn!(
+        "The plane size should be a power of two smaller or equal to {MAX_EMULATED_PLANE_SIZE}, \
+        got {plane_size}. Using {clamped} instead."
+    );
+    clamped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emulated_plane_size_clamps_unsupported_sizes() {
+        assert_eq!(emulated_plane_size(8), 8);
+        // Unsupported sizes are clamped to the closest supported one
+        assert_eq!(emulated_plane_size(0), 1);
+        assert_eq!(emulated_plane_size(24), 32);
+        assert_eq!(emulated_plane_size(512), 128);
+    }
+}
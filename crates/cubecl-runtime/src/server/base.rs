@@ -5,14 +5,13 @@ use crate::{
     config::{GlobalConfig, compilation::BoundsCheckMode},
     kernel::KernelMetadata,
     logging::ServerLogger,
-    memory_management::{ManagedMemoryHandle, MemoryAllocationMode, MemoryUsage},
+    memory_management::{ManagedMemoryHandle, MemoryAllocationMode, MemoryTrace, MemoryUsage},
     runtime::Runtime,
     server::Binding,
     storage::{ComputeStorage, ManagedResource},
     tma::{OobFill, TensorMapFormat, TensorMapInterleave, TensorMapPrefetch, TensorMapSwizzle},
 };
 use alloc::boxed::Box;
-#[cfg(feature = "profile-tracy")]
 use alloc::format;
 use alloc::string::String;
 use alloc::sync::Arc;
@@ -279,6 +278,26 @@ pub enum ServerError {
     #[error("An execution error happened during profiling\nCaused by:\n  {0}")]
     Io(#[from] IoError),
 
+    /// An out-of-bounds memory access was detected while validating a kernel.
+    #[error(
+        "Out-of-bounds {access} in kernel {kernel} on buffer {buffer} at index {index}, length is {length}\nBacktrace:\n{backtrace}"
+    )]
+    OutOfBounds {
+        /// The name of the kernel doing the access.
+        kernel: String,
+        /// The buffer being accessed.
+        buffer: String,
+        /// Whether the access is a read or a write.
+        access: MemoryAccess,
+        /// The index of the access.
+        index: u64,
+        /// The length of the buffer.
+        length: u64,
+        /// The backtrace for this error.
+        #[cfg_attr(std_io, serde(skip))]
+        backtrace: BackTrace,
+    },
+
     /// The server is an invalid state.
     #[error("The server is in an invalid state\nCaused by:\n  {errors:?}")]
     ServerUnhealthy {
@@ -290,6 +309,25 @@ pub enum ServerError {
     },
 }
 
+/// The kind of memory access reported by an [out-of-bounds](ServerError::OutOfBounds) error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(std_io, derive(serde::Serialize, serde::Deserialize))]
+pub enum MemoryAccess {
+    /// A load from a buffer.
+    Read,
+    /// A store to a buffer.
+    Write,
+}
+
+impl core::fmt::Display for MemoryAccess {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MemoryAccess::Read => f.write_str("read"),
+            MemoryAccess::Write => f.write_str("write"),
+        }
+    }
+}
+
 /// How errors are handled in a stream when executing a task.
 #[derive(Clone, Copy)]
 pub struct StreamErrorMode {
@@ -384,6 +422,10 @@ where
     /// Ask the server to release memory that it can release.
     fn memory_cleanup(&mut self, stream_id: StreamId);
 
+    /// Take the memory events traced since the last call, see
+    /// [`MemoryTracingConfig`](crate::config::memory::MemoryTracingConfig).
+    fn memory_trace(&mut self, stream_id: StreamId) -> Result<MemoryTrace, ServerError>;
+
     /// Enable collecting timestamps.
     fn start_profile(&mut self, stream_id: StreamId) -> Result<ProfilingToken, ServerError>;
 
@@ -399,15 +441,25 @@ where
 }
 
 /// Different reduce operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReduceOperation {
     /// Sum.
     Sum,
     /// Mean.
     Mean,
+    /// Minimum.
+    Min,
+    /// Maximum.
+    Max,
+    /// Product.
+    Prod,
 }
 
 /// Defines functions for optimized data transfer between servers, supporting custom communication
 /// mechanisms such as peer-to-peer communication or specialized implementations.
+///
+/// Collective operations are issued by every device of `device_ids`, and the rank of a device in
+/// the collective is its position in `device_ids`, so all devices must pass the same list.
 pub trait ServerCommunication {
     /// Indicates whether server-to-server communication is enabled for this implementation.
     const SERVER_COMM_ENABLED: bool;
@@ -423,7 +475,7 @@ pub trait ServerCommunication {
     /// Returns a `Result` containing an `ServerError` if the operation fails.
     #[allow(unused_variables)]
     fn sync_collective(&mut self, stream_id: StreamId) -> Result<(), ServerError> {
-        todo!() // For backends other than cuda.
+        Err(collective_unsupported("sync_collective"))
     }
 
     /// Performs an `all_reduce` operation on the input data and writes it to the output buffer.
@@ -450,7 +502,168 @@ pub trait ServerCommunication {
         op: ReduceOperation,
         device_ids: Vec<DeviceId>,
     ) -> Result<(), ServerError> {
-        unimplemented!()
+        Err(collective_unsupported("all_reduce"))
+    }
+
+    /// Copies the data of the `root` device to the output buffer of every device.
+    /// see <https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/usage/collectives.html#broadcast>
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The data to broadcast, only read on the `root` device.
+    /// * `dst` - Where to write the result.
+    /// * `stream_id` - The data's stream id.
+    /// * `root` - The device that owns the data.
+    /// * `device_ids` - The list of device ids taking part in the broadcast.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing an `ServerError` if the operation fails.
+    #[allow(unused_variables)]
+    fn broadcast(
+        &mut self,
+        src: Binding,
+        dst: Binding,
+        dtype: ElemType,
+        stream_id: StreamId,
+        root: DeviceId,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        Err(collective_unsupported("broadcast"))
+    }
+
+    /// Performs a `reduce` operation on the input data and writes it to the output buffer of the
+    /// `root` device.
+    /// see <https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/usage/collectives.html#reduce>
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The data to be reduced.
+    /// * `dst` - Where to write the result, only written on the `root` device.
+    /// * `stream_id` - The data's stream id.
+    /// * `op` - The reduce's aggregation operation e.g. mean, sum, etc.
+    /// * `root` - The device receiving the result.
+    /// * `device_ids` - The list of device ids from which to `reduce`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing an `ServerError` if the operation fails.
+    #[allow(unused_variables, clippy::too_many_arguments)]
+    fn reduce(
+        &mut self,
+        src: Binding,
+        dst: Binding,
+        dtype: ElemType,
+        stream_id: StreamId,
+        op: ReduceOperation,
+        root: DeviceId,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        Err(collective_unsupported("reduce"))
+    }
+
+    /// Concatenates the input data of every device, in rank order, into the output buffer of every
+    /// device. The output buffer must be `device_ids.len()` times the size of the input buffer.
+    /// see <https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/usage/collectives.html#allgather>
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The data to be gathered.
+    /// * `dst` - Where to write the result.
+    /// * `stream_id` - The data's stream id.
+    /// * `device_ids` - The list of device ids from which to `all_gather`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing an `ServerError` if the operation fails.
+    #[allow(unused_variables)]
+    fn all_gather(
+        &mut self,
+        src: Binding,
+        dst: Binding,
+        dtype: ElemType,
+        stream_id: StreamId,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        Err(collective_unsupported("all_gather"))
+    }
+
+    /// Performs a `reduce` operation on the input data and scatters the result, each device
+    /// receiving the block at its rank. The input buffer must be `device_ids.len()` times the size
+    /// of the output buffer.
+    /// see <https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/usage/collectives.html#reducescatter>
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The data to be reduced.
+    /// * `dst` - Where to write this device's block of the result.
+    /// * `stream_id` - The data's stream id.
+    /// * `op` - The reduce's aggregation operation e.g. mean, sum, etc.
+    /// * `device_ids` - The list of device ids from which to `reduce_scatter`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing an `ServerError` if the operation fails.
+    #[allow(unused_variables)]
+    fn reduce_scatter(
+        &mut self,
+        src: Binding,
+        dst: Binding,
+        dtype: ElemType,
+        stream_id: StreamId,
+        op: ReduceOperation,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        Err(collective_unsupported("reduce_scatter"))
+    }
+
+    /// Sends the input data to the `peer` device, which must issue a matching [`recv`](Self::recv).
+    /// see <https://docs.nvidia.com/deeplearning/nccl/user-guide/docs/usage/p2p.html>
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The data to send.
+    /// * `stream_id` - The data's stream id.
+    /// * `peer` - The device receiving the data.
+    /// * `device_ids` - The list of device ids of the communicator.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing an `ServerError` if the operation fails.
+    #[allow(unused_variables)]
+    fn send(
+        &mut self,
+        src: Binding,
+        dtype: ElemType,
+        stream_id: StreamId,
+        peer: DeviceId,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        Err(collective_unsupported("send"))
+    }
+
+    /// Receives data from the `peer` device, which must issue a matching [`send`](Self::send).
+    ///
+    /// # Arguments
+    ///
+    /// * `dst` - Where to write the data.
+    /// * `stream_id` - The data's stream id.
+    /// * `peer` - The device sending the data.
+    /// * `device_ids` - The list of device ids of the communicator.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing an `ServerError` if the operation fails.
+    #[allow(unused_variables)]
+    fn recv(
+        &mut self,
+        dst: Binding,
+        dtype: ElemType,
+        stream_id: StreamId,
+        peer: DeviceId,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        Err(collective_unsupported("recv"))
     }
 
     /// Copies data from a source server to a destination server.
@@ -490,6 +703,13 @@ pub trait ServerCommunication {
     }
 }
 
+fn collective_unsupported(operation: &str) -> ServerError {
+    ServerError::Generic {
+        reason: format!("`{operation}` is not supported by this server"),
+        backtrace: BackTrace::capture(),
+    }
+}
+
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 /// Profiling identification so that the server can support recursive and overlapping profilings.
 pub struct ProfilingToken {
@@ -654,6 +874,18 @@ pub enum IoError {
         backtrace: BackTrace,
     },
 
+    /// The size of the data doesn't match the layout it is copied with
+    #[error("expected {expected} bytes for the copy, got {actual}\n{backtrace}")]
+    InvalidSize {
+        /// The size of the layout in bytes.
+        expected: usize,
+        /// The size of the data in bytes.
+        actual: usize,
+        /// The backtrace.
+        #[cfg_attr(std_io, serde(skip))]
+        backtrace: BackTrace,
+    },
+
     /// Memory wasn't found in the memory pool
     #[error("couldn't find resource for that handle: {reason}\n{backtrace}")]
     NotFound {
@@ -0,0 +1,720 @@
+use super::{Binding, ComputeServer, CopyDescriptor, ReduceOperation, ServerError};
+use alloc::{format, vec, vec::Vec};
+use core::any::TypeId;
+use cubecl_common::{backtrace::BackTrace, bytes::Bytes, device::DeviceId, stream_id::StreamId};
+use cubecl_ir::{ElemType, FloatKind, IntKind, UIntKind};
+use half::{bf16, f16};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Condvar, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+/// Host-staged implementation of the operations of
+/// [`ServerCommunication`](super::ServerCommunication), for runtimes without a native
+/// communication library.
+///
+/// Each device reads its input buffer to the host and hands it to the other devices of the
+/// collective. The last device to arrive computes the result on the host, and every device then
+/// writes its part of the result to its output buffer. A server opts in by forwarding its
+/// [`ServerCommunication`](super::ServerCommunication) methods to this type.
+///
+/// Collective operations block the device thread until every device of the collective issued the
+/// same operation, so they must be issued by every device before waiting on any of them. A device
+/// that fails before handing its input aborts the collective on every device, and devices that
+/// never arrive make the others fail after a timeout.
+#[derive(Debug, Clone, Copy)]
+pub struct HostCommunication {
+    device: DeviceId,
+    timeout: Duration,
+}
+
+/// How long a device waits for the other devices of a collective by default.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+
+impl HostCommunication {
+    /// Create the host communication of the given device.
+    pub fn new(device: DeviceId) -> Self {
+        Self {
+            device,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Set how long a device waits for the other devices of a collective before failing.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Host-staged [`all_reduce`](super::ServerCommunication::all_reduce).
+    #[allow(clippy::too_many_arguments)]
+    pub fn all_reduce<S: ComputeServer>(
+        &self,
+        server: &mut S,
+        src: Binding,
+        dst: Binding,
+        dtype: ElemType,
+        stream_id: StreamId,
+        op: ReduceOperation,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        check_dtype(dtype)?;
+        let input = read(server, src, stream_id);
+        let output = self.exchange::<S>(&device_ids, input, move |inputs| {
+            let result = reduce(&inputs, dtype, op);
+            vec![Some(result); inputs.len()]
+        })?;
+        write(server, dst, output, stream_id);
+        Ok(())
+    }
+
+    /// Host-staged [`broadcast`](super::ServerCommunication::broadcast).
+    #[allow(clippy::too_many_arguments)]
+    pub fn broadcast<S: ComputeServer>(
+        &self,
+        server: &mut S,
+        src: Binding,
+        dst: Binding,
+        stream_id: StreamId,
+        root: DeviceId,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        let root = rank(&device_ids, root)?;
+        let input = match self.device == device_ids[root] {
+            true => read(server, src, stream_id),
+            false => Ok(Vec::new()),
+        };
+        let output = self.exchange::<S>(&device_ids, input, move |mut inputs| {
+            let result = core::mem::take(&mut inputs[root]);
+            vec![Some(result); inputs.len()]
+        })?;
+        write(server, dst, output, stream_id);
+        Ok(())
+    }
+
+    /// Host-staged [`reduce`](super::ServerCommunication::reduce).
+    #[allow(clippy::too_many_arguments)]
+    pub fn reduce<S: ComputeServer>(
+        &self,
+        server: &mut S,
+        src: Binding,
+        dst: Binding,
+        dtype: ElemType,
+        stream_id: StreamId,
+        op: ReduceOperation,
+        root: DeviceId,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        check_dtype(dtype)?;
+        let root = rank(&device_ids, root)?;
+        let input = read(server, src, stream_id);
+        let output = self.exchange::<S>(&device_ids, input, move |inputs| {
+            let mut outputs = vec![None; inputs.len()];
+            outputs[root] = Some(reduce(&inputs, dtype, op));
+            outputs
+        })?;
+        write(server, dst, output, stream_id);
+        Ok(())
+    }
+
+    /// Host-staged [`all_gather`](super::ServerCommunication::all_gather).
+    pub fn all_gather<S: ComputeServer>(
+        &self,
+        server: &mut S,
+        src: Binding,
+        dst: Binding,
+        stream_id: StreamId,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        let input = read(server, src, stream_id);
+        let output = self.exchange::<S>(&device_ids, input, |inputs| {
+            let result = inputs.concat();
+            vec![Some(result); inputs.len()]
+        })?;
+        write(server, dst, output, stream_id);
+        Ok(())
+    }
+
+    /// Host-staged [`reduce_scatter`](super::ServerCommunication::reduce_scatter).
+    #[allow(clippy::too_many_arguments)]
+    pub fn reduce_scatter<S: ComputeServer>(
+        &self,
+        server: &mut S,
+        src: Binding,
+        dst: Binding,
+        dtype: ElemType,
+        stream_id: StreamId,
+        op: ReduceOperation,
+        device_ids: Vec<DeviceId>,
+    ) -> Result<(), ServerError> {
+        check_dtype(dtype)?;
+        let input = check_scatter(src.size_in_used(), dtype, device_ids.len())
+            .and_then(|_| read(server, src, stream_id));
+        let output = self.exchange::<S>(&device_ids, input, move |inputs| {
+            let result = reduce(&inputs, dtype, op);
+            let block_size = result.len() / inputs.len();
+            result
+                .chunks(block_size)
+                .map(|block| Some(block.to_vec()))
+                .collect()
+        })?;
+        write(server, dst, output, stream_id);
+        Ok(())
+    }
+
+    /// Host-staged [`send`](super::ServerCommunication::send), which doesn't wait for the data to
+    /// be received.
+    pub fn send<S: ComputeServer>(
+        &self,
+        server: &mut S,
+        src: Binding,
+        stream_id: StreamId,
+        peer: DeviceId,
+    ) -> Result<(), ServerError> {
+        let data = read(server, src, stream_id)?;
+        mailbox().send((TypeId::of::<S>(), self.device, peer), data);
+        Ok(())
+    }
+
+    /// Host-staged [`recv`](super::ServerCommunication::recv), which blocks until the `peer`
+    /// sent the data.
+    pub fn recv<S: ComputeServer>(
+        &self,
+        server: &mut S,
+        dst: Binding,
+        stream_id: StreamId,
+        peer: DeviceId,
+    ) -> Result<(), ServerError> {
+        let data = mailbox().recv((TypeId::of::<S>(), peer, self.device));
+        write(server, dst, Some(data), stream_id);
+        Ok(())
+    }
+
+    /// Hand the `input` of this device to the other devices and wait for the output of the
+    /// collective, computed by the last device to arrive with `combine`. A failed `input` aborts
+    /// the collective on every device.
+    fn exchange<S: 'static>(
+        &self,
+        device_ids: &[DeviceId],
+        input: Result<Vec<u8>, ServerError>,
+        combine: impl FnOnce(Vec<Vec<u8>>) -> Vec<Option<Vec<u8>>>,
+    ) -> Result<Option<Vec<u8>>, ServerError> {
+        let rank = rank(device_ids, self.device)?;
+        let group = {
+            let mut groups = groups().lock().unwrap();
+            groups
+                .entry((TypeId::of::<S>(), device_ids.to_vec()))
+                .or_default()
+                .clone()
+        };
+
+        group.exchange(rank, device_ids, input, combine, self.timeout)
+    }
+}
+
+type GroupKey = (TypeId, Vec<DeviceId>);
+type MailboxKey = (TypeId, DeviceId, DeviceId);
+
+fn groups() -> &'static Mutex<HashMap<GroupKey, Arc<Group>>> {
+    static GROUPS: OnceLock<Mutex<HashMap<GroupKey, Arc<Group>>>> = OnceLock::new();
+    GROUPS.get_or_init(Default::default)
+}
+
+fn mailbox() -> &'static Mailbox {
+    static MAILBOX: OnceLock<Mailbox> = OnceLock::new();
+    MAILBOX.get_or_init(Default::default)
+}
+
+/// The state shared by the devices of a collective.
+#[derive(Default)]
+struct Group {
+    state: Mutex<GroupState>,
+    condvar: Condvar,
+}
+
+#[derive(Default)]
+struct GroupState {
+    /// The inputs of the current collective, by rank.
+    inputs: Vec<Option<Result<Vec<u8>, ServerError>>>,
+    /// The outputs of the current collective, by rank, once every device arrived. Holds the
+    /// error that aborted the collective if a device failed.
+    outputs: Option<Result<Vec<Option<Vec<u8>>>, ServerError>>,
+    /// The number of devices that didn't take their output yet.
+    pending: usize,
+}
+
+impl Group {
+    fn exchange(
+        &self,
+        rank: usize,
+        device_ids: &[DeviceId],
+        input: Result<Vec<u8>, ServerError>,
+        combine: impl FnOnce(Vec<Vec<u8>>) -> Vec<Option<Vec<u8>>>,
+        timeout: Duration,
+    ) -> Result<Option<Vec<u8>>, ServerError> {
+        let size = device_ids.len();
+        let mut state = self.state.lock().unwrap();
+        // Devices that are done with the previous collective must wait for the others to take
+        // their output.
+        while state.outputs.is_some() {
+            state = self.condvar.wait(state).unwrap();
+        }
+
+        state.inputs.resize_with(size, || None);
+        state.inputs[rank] = Some(input);
+
+        if state.inputs.iter().all(Option::is_some) {
+            let inputs = state
+                .inputs
+                .drain(..)
+                .map(Option::unwrap)
+                .enumerate()
+                .map(|(rank, input)| {
+                    input.map_err(|err| ServerError::Generic {
+                        reason: format!(
+                            "The collective was aborted since device {:?} failed: {err}",
+                            device_ids[rank]
+                        ),
+                        backtrace: BackTrace::capture(),
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>();
+            state.outputs = Some(inputs.map(combine));
+            state.pending = size;
+            self.condvar.notify_all();
+        }
+
+        let deadline = Instant::now() + timeout;
+        while state.outputs.is_none() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                // Leave the collective, so the devices arriving later don't combine a stale input.
+                state.inputs[rank] = None;
+                return Err(ServerError::Generic {
+                    reason: format!(
+                        "Timed out after {timeout:?} waiting for the other devices of the collective"
+                    ),
+                    backtrace: BackTrace::capture(),
+                });
+            }
+            state = self.condvar.wait_timeout(state, remaining).unwrap().0;
+        }
+
+        let output = match state.outputs.as_mut().unwrap() {
+            Ok(outputs) => Ok(outputs[rank].take()),
+            Err(err) => Err(err.clone()),
+        };
+        state.pending -= 1;
+        if state.pending == 0 {
+            state.outputs = None;
+            self.condvar.notify_all();
+        }
+
+        output
+    }
+}
+
+/// The messages sent between pairs of devices.
+#[derive(Default)]
+struct Mailbox {
+    messages: Mutex<HashMap<MailboxKey, VecDeque<Vec<u8>>>>,
+    condvar: Condvar,
+}
+
+impl Mailbox {
+    fn send(&self, key: MailboxKey, data: Vec<u8>) {
+        let mut messages = self.messages.lock().unwrap();
+        messages.entry(key).or_default().push_back(data);
+        self.condvar.notify_all();
+    }
+
+    fn recv(&self, key: MailboxKey) -> Vec<u8> {
+        let mut messages = self.messages.lock().unwrap();
+        loop {
+            if let Some(data) = messages.get_mut(&key).and_then(|queue| queue.pop_front()) {
+                return data;
+            }
+            messages = self.condvar.wait(messages).unwrap();
+        }
+    }
+}
+
+fn read<S: ComputeServer>(
+    server: &mut S,
+    binding: Binding,
+    stream_id: StreamId,
+) -> Result<Vec<u8>, ServerError> {
+    let size = binding.size_in_used() as usize;
+    let descriptor = CopyDescriptor::new(binding, [size].into(), [1].into(), 1);
+    let bytes = cubecl_common::future::block_on(server.read(vec![descriptor], stream_id))?;
+
+    Ok(bytes[0].to_vec())
+}
+
+fn write<S: ComputeServer>(
+    server: &mut S,
+    binding: Binding,
+    data: Option<Vec<u8>>,
+    stream_id: StreamId,
+) {
+    let Some(data) = data else {
+        return;
+    };
+    let size = binding.size_in_used() as usize;
+    let descriptor = CopyDescriptor::new(binding, [size].into(), [1].into(), 1);
+    server.write(vec![(descriptor, Bytes::from_bytes_vec(data))], stream_id);
+}
+
+fn rank(device_ids: &[DeviceId], device: DeviceId) -> Result<usize, ServerError> {
+    device_ids
+        .iter()
+        .position(|id| *id == device)
+        .ok_or_else(|| ServerError::Generic {
+            reason: format!("The device {device:?} isn't part of the collective"),
+            backtrace: BackTrace::capture(),
+        })
+}
+
+/// Checks that the input of a `reduce_scatter`, of `size` bytes, can be split in a block of at
+/// least one element for each device.
+fn check_scatter(size: u64, dtype: ElemType, devices: usize) -> Result<(), ServerError> {
+    let len = size as usize / dtype.size();
+    if len >= devices && len.is_multiple_of(devices) {
+        return Ok(());
+    }
+    Err(ServerError::Generic {
+        reason: format!(
+            "Can't scatter {len} elements between {devices} devices, the length should be a \
+            non-zero multiple of the device count"
+        ),
+        backtrace: BackTrace::capture(),
+    })
+}
+
+fn check_dtype(dtype: ElemType) -> Result<(), ServerError> {
+    match host_dtype(dtype) {
+        Some(_) => Ok(()),
+        None => Err(ServerError::Generic {
+            reason: format!("Host collectives don't support {dtype}"),
+            backtrace: BackTrace::capture(),
+        }),
+    }
+}
+
+/// The element types that can be reduced on the host.
+#[derive(Clone, Copy)]
+enum HostDType {
+    F16,
+    BF16,
+    F32,
+    F64,
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+fn host_dtype(dtype: ElemType) -> Option<HostDType> {
+    let dtype = match dtype {
+        ElemType::Float(FloatKind::F16) => HostDType::F16,
+        ElemType::Float(FloatKind::BF16) => HostDType::BF16,
+        ElemType::Float(FloatKind::F32 | FloatKind::Flex32 | FloatKind::TF32) => HostDType::F32,
+        ElemType::Float(FloatKind::F64) => HostDType::F64,
+        ElemType::Int(IntKind::I8) => HostDType::I8,
+        ElemType::Int(IntKind::I16) => HostDType::I16,
+        ElemType::Int(IntKind::I32) => HostDType::I32,
+        ElemType::Int(IntKind::I64) => HostDType::I64,
+        ElemType::UInt(UIntKind::U8) => HostDType::U8,
+        ElemType::UInt(UIntKind::U16) => HostDType::U16,
+        ElemType::UInt(UIntKind::U32) => HostDType::U32,
+        ElemType::UInt(UIntKind::U64) => HostDType::U64,
+        _ => return None,
+    };
+    Some(dtype)
+}
+
+/// Reduce the inputs element-wise. The element type must be supported, see [`check_dtype`].
+fn reduce(inputs: &[Vec<u8>], dtype: ElemType, op: ReduceOperation) -> Vec<u8> {
+    match host_dtype(dtype).expect("Element type should be checked") {
+        HostDType::F16 => reduce_typed::<f16>(inputs, op),
+        HostDType::BF16 => reduce_typed::<bf16>(inputs, op),
+        HostDType::F32 => reduce_typed::<f32>(inputs, op),
+        HostDType::F64 => reduce_typed::<f64>(inputs, op),
+        HostDType::I8 => reduce_typed::<i8>(inputs, op),
+        HostDType::I16 => reduce_typed::<i16>(inputs, op),
+        HostDType::I32 => reduce_typed::<i32>(inputs, op),
+        HostDType::I64 => reduce_typed::<i64>(inputs, op),
+        HostDType::U8 => reduce_typed::<u8>(inputs, op),
+        HostDType::U16 => reduce_typed::<u16>(inputs, op),
+        HostDType::U32 => reduce_typed::<u32>(inputs, op),
+        HostDType::U64 => reduce_typed::<u64>(inputs, op),
+    }
+}
+
+fn reduce_typed<E: HostElement>(inputs: &[Vec<u8>], op: ReduceOperation) -> Vec<u8> {
+    let mut acc = inputs[0].clone();
+
+    for input in &inputs[1..] {
+        for (acc, input) in acc
+            .chunks_exact_mut(E::SIZE)
+            .zip(input.chunks_exact(E::SIZE))
+        {
+            let (lhs, rhs) = (E::read(acc), E::read(input));
+            let value = match op {
+                ReduceOperation::Sum | ReduceOperation::Mean => lhs.add(rhs),
+                ReduceOperation::Prod => lhs.mul(rhs),
+                ReduceOperation::Min if rhs < lhs => rhs,
+                ReduceOperation::Max if rhs > lhs => rhs,
+                ReduceOperation::Min | ReduceOperation::Max => lhs,
+            };
+            value.write(acc);
+        }
+    }
+
+    if op == ReduceOperation::Mean {
+        for acc in acc.chunks_exact_mut(E::SIZE) {
+            E::read(acc).div_count(inputs.len()).write(acc);
+        }
+    }
+
+    acc
+}
+
+/// An element that can be reduced on the host.
+trait HostElement: Copy + PartialOrd {
+    const SIZE: usize;
+
+    fn read(bytes: &[u8]) -> Self;
+    fn write(self, bytes: &mut [u8]);
+    fn add(self, other: Self) -> Self;
+    fn mul(self, other: Self) -> Self;
+    fn div_count(self, count: usize) -> Self;
+}
+
+macro_rules! host_element_int {
+    ($($ty:ty),*) => {
+        $(impl HostElement for $ty {
+            const SIZE: usize = size_of::<$ty>();
+
+            fn read(bytes: &[u8]) -> Self {
+                <$ty>::from_ne_bytes(bytes.try_into().unwrap())
+            }
+            fn write(self, bytes: &mut [u8]) {
+                bytes.copy_from_slice(&self.to_ne_bytes());
+            }
+            fn add(self, other: Self) -> Self {
+                self.wrapping_add(other)
+            }
+            fn mul(self, other: Self) -> Self {
+                self.wrapping_mul(other)
+            }
+            fn div_count(self, count: usize) -> Self {
+                self / count as $ty
+            }
+        })*
+    };
+}
+
+macro_rules! host_element_float {
+    ($($ty:ty),*) => {
+        $(impl HostElement for $ty {
+            const SIZE: usize = size_of::<$ty>();
+
+            fn read(bytes: &[u8]) -> Self {
+                <$ty>::from_ne_bytes(bytes.try_into().unwrap())
+            }
+            fn write(self, bytes: &mut [u8]) {
+                bytes.copy_from_slice(&self.to_ne_bytes());
+            }
+            fn add(self, other: Self) -> Self {
+                self + other
+            }
+            fn mul(self, other: Self) -> Self {
+                self * other
+            }
+            fn div_count(self, count: usize) -> Self {
+                self / count as f32 as Self
+            }
+        })*
+    };
+}
+
+host_element_int!(i8, i16, i32, i64, u8, u16, u32, u64);
+host_element_float!(f32, f64);
+
+macro_rules! host_element_half {
+    ($($ty:ty),*) => {
+        $(impl HostElement for $ty {
+            const SIZE: usize = size_of::<$ty>();
+
+            fn read(bytes: &[u8]) -> Self {
+                <$ty>::from_ne_bytes(bytes.try_into().unwrap())
+            }
+            fn write(self, bytes: &mut [u8]) {
+                bytes.copy_from_slice(&self.to_ne_bytes());
+            }
+            fn add(self, other: Self) -> Self {
+                <$ty>::from_f32(self.to_f32() + other.to_f32())
+            }
+            fn mul(self, other: Self) -> Self {
+                <$ty>::from_f32(self.to_f32() * other.to_f32())
+            }
+            fn div_count(self, count: usize) -> Self {
+                <$ty>::from_f32(self.to_f32() / count as f32)
+            }
+        })*
+    };
+}
+
+host_element_half!(f16, bf16);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f32_bytes(values: &[f32]) -> Vec<u8> {
+        values.iter().flat_map(|it| it.to_ne_bytes()).collect()
+    }
+
+    fn from_f32_bytes(bytes: &[u8]) -> Vec<f32> {
+        bytes.chunks_exact(4).map(f32::read).collect()
+    }
+
+    #[test]
+    fn reduce_operations() {
+        let inputs = vec![f32_bytes(&[1.0, -2.0]), f32_bytes(&[3.0, 4.0])];
+        let dtype = ElemType::Float(FloatKind::F32);
+
+        let expected = [
+            (ReduceOperation::Sum, [4.0, 2.0]),
+            (ReduceOperation::Mean, [2.0, 1.0]),
+            (ReduceOperation::Min, [1.0, -2.0]),
+            (ReduceOperation::Max, [3.0, 4.0]),
+            (ReduceOperation::Prod, [3.0, -8.0]),
+        ];
+        for (op, expected) in expected {
+            assert_eq!(from_f32_bytes(&reduce(&inputs, dtype, op)), expected);
+        }
+    }
+
+    #[test]
+    fn exchange_between_threads() {
+        const DEVICES: u32 = 4;
+        let device_ids = (0..DEVICES)
+            .map(|index| DeviceId::new(u16::MAX, index))
+            .collect::<Vec<_>>();
+
+        let threads = device_ids
+            .iter()
+            .map(|&device| {
+                let comm = HostCommunication::new(device);
+                let device_ids = device_ids.clone();
+                std::thread::spawn(move || {
+                    // Multiple rounds, to make sure faster devices don't mix collectives
+                    (0..8)
+                        .map(|round| {
+                            let value = (device.index_id + round) as f32;
+                            let input = Ok(f32_bytes(&[value]));
+                            let output = comm.exchange::<()>(&device_ids, input, sum);
+                            from_f32_bytes(&output.unwrap().unwrap())[0]
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let expected = (0..8)
+            .map(|round| (0..DEVICES).map(|index| (index + round) as f32).sum())
+            .collect::<Vec<f32>>();
+        for thread in threads {
+            assert_eq!(thread.join().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn scatter_length_is_validated() {
+        let dtype = ElemType::Float(FloatKind::F32);
+
+        assert!(check_scatter(4 * 8, dtype, 4).is_ok());
+        assert!(check_scatter(4 * 4, dtype, 4).is_ok());
+        assert!(check_scatter(4 * 6, dtype, 4).is_err());
+        assert!(check_scatter(4 * 2, dtype, 4).is_err());
+        assert!(check_scatter(0, dtype, 4).is_err());
+    }
+
+    #[test]
+    fn failed_device_aborts_exchange() {
+        let device_ids = (0..3)
+            .map(|index| DeviceId::new(u16::MAX - 1, index))
+            .collect::<Vec<_>>();
+
+        let threads = device_ids
+            .iter()
+            .map(|&device| {
+                let comm = HostCommunication::new(device);
+                let device_ids = device_ids.clone();
+                std::thread::spawn(move || {
+                    let input = match device.index_id {
+                        1 => Err(ServerError::Generic {
+                            reason: "Can't read the input".into(),
+                            backtrace: BackTrace::capture(),
+                        }),
+                        _ => Ok(f32_bytes(&[1.0])),
+                    };
+                    let aborted = comm.exchange::<()>(&device_ids, input, sum);
+                    // The group must be usable for the next collective
+                    let next = comm.exchange::<()>(&device_ids, Ok(f32_bytes(&[1.0])), sum);
+                    (aborted, from_f32_bytes(&next.unwrap().unwrap()))
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for thread in threads {
+            let (aborted, next) = thread.join().unwrap();
+            match aborted {
+                Err(ServerError::Generic { reason, .. }) => {
+                    assert!(reason.contains("Can't read the input"), "{reason}")
+                }
+                other => panic!("The collective should be aborted, got {other:?}"),
+            }
+            assert_eq!(next, [3.0]);
+        }
+    }
+
+    #[test]
+    fn missing_device_times_out() {
+        let device_ids = (0..2)
+            .map(|index| DeviceId::new(u16::MAX - 2, index))
+            .collect::<Vec<_>>();
+        let comm = HostCommunication::new(device_ids[0]).with_timeout(Duration::from_millis(50));
+
+        let output = comm.exchange::<()>(&device_ids, Ok(f32_bytes(&[1.0])), sum);
+        assert!(
+            matches!(output, Err(ServerError::Generic { .. })),
+            "{output:?}"
+        );
+    }
+
+    #[test]
+    fn device_outside_collective_fails() {
+        let device_ids = vec![DeviceId::new(u16::MAX - 3, 0)];
+        let comm = HostCommunication::new(DeviceId::new(u16::MAX - 3, 1));
+
+        let output = comm.exchange::<()>(&device_ids, Ok(f32_bytes(&[1.0])), sum);
+        assert!(output.is_err());
+    }
+
+    fn sum(inputs: Vec<Vec<u8>>) -> Vec<Option<Vec<u8>>> {
+        let sum = reduce(
+            &inputs,
+            ElemType::Float(FloatKind::F32),
+            ReduceOperation::Sum,
+        );
+        vec![Some(sum); inputs.len()]
+    }
+}
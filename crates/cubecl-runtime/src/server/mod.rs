@@ -1,5 +1,11 @@
 mod base;
+#[cfg(multi_threading)]
+mod collective;
 mod handle;
+mod strided;
 
 pub use base::*;
+#[cfg(multi_threading)]
+pub use collective::*;
 pub use handle::*;
+pub use strided::*;
@@ -0,0 +1,210 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use cubecl_zspace::{Shape, Strides};
+
+/// The layout of the elements of a [copy](super::CopyDescriptor), used by runtimes that gather
+/// or scatter non-contiguous tensors on the host.
+///
+/// Strides are in elements and may describe any layout, including pitched rows, transposed
+/// axes and slices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CopyLayout {
+    /// Shape of the copied tensor.
+    pub shape: Shape,
+    /// Strides of the copied tensor, in elements.
+    pub strides: Strides,
+    /// Size of each element in bytes.
+    pub elem_size: usize,
+}
+
+impl CopyLayout {
+    /// Create a new copy layout.
+    pub fn new(shape: Shape, strides: Strides, elem_size: usize) -> Self {
+        Self {
+            shape,
+            strides,
+            elem_size,
+        }
+    }
+
+    /// The number of elements of the tensor.
+    pub fn num_elems(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    /// The size of the tensor in bytes, once contiguous.
+    pub fn size(&self) -> usize {
+        self.num_elems() * self.elem_size
+    }
+
+    /// Whether the elements are stored contiguously in row-major order, so they can be copied
+    /// without any gather or scatter.
+    pub fn is_contiguous(&self) -> bool {
+        let mut expected = 1;
+        for (dim, stride) in self.shape.iter().zip(self.strides.iter()).rev() {
+            if *dim != 1 && *stride != expected {
+                return false;
+            }
+            expected *= dim;
+        }
+        true
+    }
+
+    /// The number of bytes between the start of the buffer and the end of the last element.
+    pub fn span(&self) -> usize {
+        if self.num_elems() == 0 {
+            return 0;
+        }
+
+        let last = self
+            .shape
+            .iter()
+            .zip(self.strides.iter())
+            .map(|(dim, stride)| (dim - 1) * stride)
+            .sum::<usize>();
+        (last + 1) * self.elem_size
+    }
+
+    /// Gather the elements of `src` into a contiguous buffer.
+    ///
+    /// # Panics
+    ///
+    /// If `src` is smaller than the [span](Self::span) of the layout.
+    pub fn gather(&self, src: &[u8]) -> Vec<u8> {
+        assert!(src.len() >= self.span(), "Source buffer is too small");
+
+        let mut dst = vec![0; self.size()];
+        self.for_each_run(|contiguous, strided, len| {
+            dst[contiguous..contiguous + len].copy_from_slice(&src[strided..strided + len]);
+        });
+        dst
+    }
+
+    /// Scatter the contiguous elements of `src` to their position in `dst`. The bytes of `dst`
+    /// outside of the elements are left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// If `src` doesn't hold exactly the elements of the layout, or if `dst` is smaller than the
+    /// [span](Self::span) of the layout.
+    pub fn scatter(&self, src: &[u8], dst: &mut [u8]) {
+        assert_eq!(src.len(), self.size(), "Source buffer has the wrong size");
+        assert!(dst.len() >= self.span(), "Destination buffer is too small");
+
+        self.for_each_run(|contiguous, strided, len| {
+            dst[strided..strided + len].copy_from_slice(&src[contiguous..contiguous + len]);
+        });
+    }
+
+    /// Call `func` with the contiguous offset, the strided offset and the length in bytes of each
+    /// run of elements that is contiguous in both layouts.
+    fn for_each_run(&self, mut func: impl FnMut(usize, usize, usize)) {
+        let num_elems = self.num_elems();
+        if num_elems == 0 {
+            return;
+        }
+
+        let rank = self.shape.len();
+        if rank == 0 {
+            func(0, 0, self.elem_size);
+            return;
+        }
+
+        let inner_dim = self.shape[rank - 1];
+        let inner_stride = self.strides[rank - 1];
+        let (run_elems, run_stride) = match inner_stride == 1 {
+            true => (inner_dim, inner_dim),
+            false => (1, inner_stride),
+        };
+        let runs_per_row = inner_dim / run_elems;
+        let run_len = run_elems * self.elem_size;
+
+        let mut index = vec![0; rank - 1];
+        let mut contiguous = 0;
+        for _ in 0..num_elems / inner_dim {
+            let row = index
+                .iter()
+                .zip(self.strides.iter())
+                .map(|(i, stride)| i * stride)
+                .sum::<usize>();
+
+            for run in 0..runs_per_row {
+                let strided = (row + run * run_stride) * self.elem_size;
+                func(contiguous, strided, run_len);
+                contiguous += run_len;
+            }
+
+            // Increment the index of the outer dimensions, innermost first.
+            for dim in (0..rank - 1).rev() {
+                index[dim] += 1;
+                if index[dim] < self.shape[dim] {
+                    break;
+                }
+                index[dim] = 0;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cubecl_zspace::{shape, strides};
+
+    /// A 2x3 tensor of `u16` transposed from a 3x2 buffer.
+    fn transposed() -> CopyLayout {
+        CopyLayout::new(shape![2, 3], strides![1, 2], 2)
+    }
+
+    #[test_log::test]
+    fn contiguous_layouts() {
+        assert!(CopyLayout::new(shape![2, 3], strides![3, 1], 4).is_contiguous());
+        assert!(CopyLayout::new(shape![2, 1, 3], strides![3, 7, 1], 4).is_contiguous());
+        assert!(!CopyLayout::new(shape![2, 3], strides![4, 1], 4).is_contiguous());
+        assert!(!transposed().is_contiguous());
+    }
+
+    #[test_log::test]
+    fn span() {
+        assert_eq!(CopyLayout::new(shape![2, 3], strides![4, 1], 4).span(), 28);
+        assert_eq!(transposed().span(), 12);
+        assert_eq!(CopyLayout::new(shape![0, 3], strides![4, 1], 4).span(), 0);
+    }
+
+    #[test_log::test]
+    fn gather_pitched_rows() {
+        let layout = CopyLayout::new(shape![2, 3], strides![4, 1], 1);
+        let src = [0, 1, 2, 9, 3, 4, 5];
+
+        assert_eq!(layout.gather(&src), vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test_log::test]
+    fn gather_transposed() {
+        let src = [0u8, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5];
+
+        assert_eq!(
+            transposed().gather(&src),
+            vec![0, 0, 2, 2, 4, 4, 1, 1, 3, 3, 5, 5]
+        );
+    }
+
+    #[test_log::test]
+    fn scatter_keeps_gaps() {
+        let layout = CopyLayout::new(shape![2, 2], strides![3, 1], 1);
+        let mut dst = [9; 5];
+        layout.scatter(&[0, 1, 2, 3], &mut dst);
+
+        assert_eq!(dst, [0, 1, 9, 2, 3]);
+    }
+
+    #[test_log::test]
+    fn scatter_gather_round_trip() {
+        let layout = CopyLayout::new(shape![2, 3, 2], strides![1, 4, 2], 4);
+        let src = (0..layout.size() as u8).collect::<Vec<_>>();
+        let mut dst = vec![0; layout.span()];
+        layout.scatter(&src, &mut dst);
+
+        assert_eq!(layout.gather(&dst), src);
+    }
+}
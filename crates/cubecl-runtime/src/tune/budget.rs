@@ -0,0 +1,178 @@
+use alloc::vec::Vec;
+use core::time::Duration;
+use web_time::Instant;
+
+use super::tune_benchmark::NUM_SAMPLES;
+use crate::config::autotune::AutotuneBudget;
+
+/// Tracks the [budget](AutotuneBudget) while autotuning a single key.
+#[derive(Debug)]
+pub(crate) struct TuneBudget {
+    start: Instant,
+    max_duration: Option<Duration>,
+    halving_samples: Option<usize>,
+    abort_ratio: Option<f64>,
+    best_score: Option<u64>,
+}
+
+/// The tunables of the next round of successive halving.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct NextRound {
+    /// The fastest half of the tunables, to benchmark again with more samples.
+    pub survivors: Vec<usize>,
+    /// The other tunables with their score.
+    pub discarded: Vec<(usize, u64)>,
+}
+
+impl TuneBudget {
+    pub(crate) fn new(config: &AutotuneBudget) -> Self {
+        Self {
+            start: Instant::now(),
+            max_duration: config.max_duration_ms.map(Duration::from_millis),
+            halving_samples: config.halving_samples.map(|samples| samples.max(1)),
+            abort_ratio: config.abort_ratio,
+            best_score: None,
+        }
+    }
+
+    /// Whether the maximum duration of the autotuning is exceeded.
+    pub(crate) fn is_exhausted(&self) -> bool {
+        match self.max_duration {
+            Some(max_duration) => self.start.elapsed() >= max_duration,
+            None => false,
+        }
+    }
+
+    /// The number of samples to profile during the given round of successive halving.
+    pub(crate) fn num_samples(&self, round: u32) -> usize {
+        match self.halving_samples {
+            Some(samples) => samples
+                .saturating_mul(2usize.saturating_pow(round))
+                .min(NUM_SAMPLES),
+            None => NUM_SAMPLES,
+        }
+    }
+
+    /// Whether a single sample of each tunable should be profiled first, so the tunables much
+    /// slower than the fastest one can be aborted early.
+    pub(crate) fn should_probe(&self) -> bool {
+        self.abort_ratio.is_some()
+    }
+
+    /// Whether a tunable with the given score is too slow compared to the fastest one.
+    pub(crate) fn should_abort(&self, score: u64) -> bool {
+        match (self.abort_ratio, self.best_score) {
+            (Some(ratio), Some(best)) => score as f64 > best as f64 * ratio,
+            _ => false,
+        }
+    }
+
+    /// Register the score of a tunable that succeeded.
+    pub(crate) fn register(&mut self, score: u64) {
+        self.best_score = Some(match self.best_score {
+            Some(best) => best.min(score),
+            None => score,
+        });
+    }
+
+    /// Split the tunables benchmarked during a round of successive halving.
+    ///
+    /// Returns `None` when there is no round left.
+    pub(crate) fn next_round(
+        &self,
+        round: u32,
+        mut scores: Vec<(usize, u64)>,
+    ) -> Option<NextRound> {
+        if self.halving_samples.is_none()
+            || scores.len() <= 1
+            || self.num_samples(round) >= NUM_SAMPLES
+            || self.is_exhausted()
+        {
+            return None;
+        }
+
+        scores.sort_by_key(|(_, score)| *score);
+        let discarded = scores.split_off(scores.len().div_ceil(2));
+        let survivors = scores.into_iter().map(|(index, _)| index).collect();
+
+        Some(NextRound {
+            survivors,
+            discarded,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test_log::test]
+    fn test_budget_unbounded_by_default() {
+        let budget = TuneBudget::new(&AutotuneBudget::default());
+
+        assert!(!budget.is_exhausted());
+        assert!(!budget.should_probe());
+        assert_eq!(budget.num_samples(0), NUM_SAMPLES);
+        assert_eq!(budget.next_round(0, vec![(0, 10), (1, 20)]), None);
+    }
+
+    #[test_log::test]
+    fn test_budget_successive_halving() {
+        let budget = TuneBudget::new(&AutotuneBudget {
+            halving_samples: Some(2),
+            ..Default::default()
+        });
+
+        assert_eq!(budget.num_samples(0), 2);
+        assert_eq!(budget.num_samples(1), 4);
+        assert_eq!(budget.num_samples(2), 8);
+        assert_eq!(budget.num_samples(3), NUM_SAMPLES);
+
+        assert_eq!(
+            budget.next_round(0, vec![(0, 40), (1, 10), (2, 30), (3, 20), (4, 50)]),
+            Some(NextRound {
+                survivors: vec![1, 3, 2],
+                discarded: vec![(0, 40), (4, 50)],
+            })
+        );
+        assert_eq!(
+            budget.next_round(1, vec![(1, 15), (2, 12)]),
+            Some(NextRound {
+                survivors: vec![2],
+                discarded: vec![(1, 15)],
+            })
+        );
+
+        assert_eq!(budget.next_round(2, vec![(2, 12)]), None);
+        assert_eq!(budget.next_round(3, vec![(1, 15), (2, 12)]), None);
+    }
+
+    #[test_log::test]
+    fn test_budget_abort_ratio() {
+        let mut budget = TuneBudget::new(&AutotuneBudget {
+            abort_ratio: Some(4.0),
+            ..Default::default()
+        });
+
+        assert!(budget.should_probe());
+        assert!(!budget.should_abort(1000), "Nothing to compare to yet.");
+
+        budget.register(100);
+        budget.register(200);
+        assert!(!budget.should_abort(400));
+        assert!(budget.should_abort(401));
+    }
+
+    #[test_log::test]
+    fn test_budget_max_duration() {
+        let budget = TuneBudget::new(&AutotuneBudget {
+            max_duration_ms: Some(0),
+            halving_samples: Some(1),
+            ..Default::default()
+        });
+
+        assert!(budget.is_exhausted());
+        assert_eq!(budget.next_round(0, vec![(0, 10), (1, 20)]), None);
+    }
+}
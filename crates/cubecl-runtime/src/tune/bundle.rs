@@ -0,0 +1,296 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use thiserror::Error;
+
+use super::{AutotuneKey, AutotuneResult};
+use crate::{client::ComputeClient, runtime::Runtime};
+
+/// The version of the bundle format, increased whenever it changes in an incompatible way.
+pub const AUTOTUNE_BUNDLE_VERSION: u32 = 1;
+
+/// The autotune results of a [tuner](super::LocalTuner) for a device, in a portable format.
+///
+/// A bundle is exported on a machine where the kernels were tuned, then shipped and imported on
+/// machines with the same kind of device, so they don't have to tune the kernels again. Bundles
+/// are only imported for the same [model of device](AutotuneDevice) they were exported from, and
+/// every entry keeps the checksum of the [tunable set](super::TunableSet) it was tuned with,
+/// entries tuned with different tunables are rejected when imported.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(bound = "K: AutotuneKey")]
+pub struct AutotuneBundle<K: AutotuneKey> {
+    version: u32,
+    tuner: String,
+    device: AutotuneDevice,
+    entries: Vec<AutotuneBundleEntry<K>>,
+}
+
+/// The hardware an [autotune bundle](AutotuneBundle) was tuned on.
+///
+/// It doesn't include the index of the device, so a bundle can be imported for any device of the
+/// same model on a machine.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AutotuneDevice {
+    /// The name of the runtime on the device.
+    pub name: String,
+    /// The checksum of the device properties, see
+    /// [`DeviceProperties::checksum`](cubecl_ir::DeviceProperties::checksum).
+    pub properties: u64,
+    /// The version of `CubeCL` the kernels were tuned with.
+    pub cubecl_version: String,
+}
+
+impl AutotuneDevice {
+    /// The hardware the `client` runs on.
+    pub fn new<R: Runtime>(client: &ComputeClient<R>) -> Self {
+        Self {
+            name: R::name(client).to_string(),
+            properties: client.properties().checksum(),
+            cubecl_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+impl core::fmt::Display for AutotuneDevice {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} (properties {:016x}, CubeCL {})",
+            self.name, self.properties, self.cubecl_version
+        )
+    }
+}
+
+/// The autotune result of a key in a [bundle](AutotuneBundle).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(bound = "K: AutotuneKey")]
+pub struct AutotuneBundleEntry<K: AutotuneKey> {
+    /// The autotune key.
+    pub key: K,
+    /// The checksum of the tunable set the key was tuned with.
+    pub checksum: String,
+    /// The index of the fastest tunable.
+    pub fastest_index: usize,
+    /// The outcome of every tunable, including their timings.
+    pub results: Vec<AutotuneResult>,
+}
+
+/// How many entries of a [bundle](AutotuneBundle) were imported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AutotuneImport {
+    /// The entries that are now used by the tuner.
+    pub imported: usize,
+    /// The entries tuned with different tunables, or already tuned locally.
+    pub rejected: usize,
+}
+
+/// Error when saving, loading or importing an [autotune bundle](AutotuneBundle).
+#[derive(Error, Debug)]
+pub enum AutotuneBundleError {
+    /// The bundle file can't be read or written.
+    #[error("Can't access the autotune bundle\nCaused by:\n  {0}")]
+    Io(#[from] std::io::Error),
+    /// The bundle file isn't a valid bundle.
+    #[error("The autotune bundle is malformed\nCaused by:\n  {0}")]
+    Format(#[from] serde_json::Error),
+    /// The bundle was saved with another version of the format.
+    #[error("The autotune bundle has version {found}, but version {expected} is expected")]
+    Version {
+        /// The version of the bundle.
+        found: u32,
+        /// The version supported by this crate.
+        expected: u32,
+    },
+    /// The bundle was exported from another tuner.
+    #[error("The autotune bundle was exported from tuner {found}, not {expected}")]
+    Tuner {
+        /// The tuner of the bundle.
+        found: String,
+        /// The tuner the bundle is imported to.
+        expected: String,
+    },
+    /// The bundle was exported from another model of device, whose results don't apply.
+    #[error("The autotune bundle was exported from device {found}, not {expected}")]
+    Device {
+        /// The device of the bundle.
+        found: AutotuneDevice,
+        /// The device the bundle is imported for.
+        expected: AutotuneDevice,
+    },
+}
+
+impl<K: AutotuneKey> AutotuneBundle<K> {
+    pub(crate) fn new(
+        tuner: &str,
+        device: AutotuneDevice,
+        entries: Vec<AutotuneBundleEntry<K>>,
+    ) -> Self {
+        Self {
+            version: AUTOTUNE_BUNDLE_VERSION,
+            tuner: tuner.to_string(),
+            device,
+            entries,
+        }
+    }
+
+    /// The name of the tuner the bundle was exported from.
+    pub fn tuner(&self) -> &str {
+        &self.tuner
+    }
+
+    /// The hardware the bundle was exported from.
+    pub fn device(&self) -> &AutotuneDevice {
+        &self.device
+    }
+
+    /// The version of `CubeCL` the bundle was exported with.
+    pub fn cubecl_version(&self) -> &str {
+        &self.device.cubecl_version
+    }
+
+    /// The autotune results of the bundle.
+    pub fn entries(&self) -> &[AutotuneBundleEntry<K>] {
+        &self.entries
+    }
+
+    /// Merge the entries of another bundle of the same tuner into this one.
+    ///
+    /// When both bundles have an entry for the same key, the entry of `other` is kept, so merging
+    /// a newer bundle updates the results.
+    pub fn merge(&mut self, other: Self) -> Result<(), AutotuneBundleError> {
+        self.check_tuner(&other.tuner)?;
+
+        for entry in other.entries {
+            match self
+                .entries
+                .iter_mut()
+                .find(|current| current.key == entry.key)
+            {
+                Some(current) => *current = entry,
+                None => self.entries.push(entry),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Save the bundle to a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), AutotuneBundleError> {
+        let content = serde_json::to_vec_pretty(self)?;
+        std::fs::write(path, content)?;
+
+        Ok(())
+    }
+
+    /// Load a bundle from a file, failing if it was saved with another version of the format.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, AutotuneBundleError> {
+        let content = std::fs::read(path)?;
+
+        // Check the version first, since the rest of the format might have changed.
+        #[derive(Deserialize)]
+        struct Header {
+            version: u32,
+        }
+        let header: Header = serde_json::from_slice(&content)?;
+        if header.version != AUTOTUNE_BUNDLE_VERSION {
+            return Err(AutotuneBundleError::Version {
+                found: header.version,
+                expected: AUTOTUNE_BUNDLE_VERSION,
+            });
+        }
+
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    pub(crate) fn check_tuner(&self, tuner: &str) -> Result<(), AutotuneBundleError> {
+        if self.tuner != tuner {
+            return Err(AutotuneBundleError::Tuner {
+                found: self.tuner.clone(),
+                expected: tuner.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn check_device(&self, device: &AutotuneDevice) -> Result<(), AutotuneBundleError> {
+        if &self.device != device {
+            return Err(AutotuneBundleError::Device {
+                found: self.device.clone(),
+                expected: device.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn into_entries(self) -> Vec<AutotuneBundleEntry<K>> {
+        self.entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn device(name: &str, properties: u64) -> AutotuneDevice {
+        AutotuneDevice {
+            name: name.to_string(),
+            properties,
+            cubecl_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    fn entry(key: &str, fastest_index: usize) -> AutotuneBundleEntry<String> {
+        AutotuneBundleEntry {
+            key: key.to_string(),
+            checksum: "checksum".to_string(),
+            fastest_index,
+            results: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn merge_keeps_entries_of_other() {
+        let mut bundle = AutotuneBundle::new(
+            "tuner",
+            device("cuda", 42),
+            vec![entry("a", 0), entry("b", 0)],
+        );
+        let other = AutotuneBundle::new(
+            "tuner",
+            device("cuda", 42),
+            vec![entry("b", 1), entry("c", 1)],
+        );
+
+        bundle.merge(other).unwrap();
+
+        assert_eq!(
+            bundle.entries(),
+            &[entry("a", 0), entry("b", 1), entry("c", 1)]
+        );
+    }
+
+    #[test]
+    fn check_device_accepts_same_model() {
+        let bundle = AutotuneBundle::<String>::new("tuner", device("cuda", 42), Vec::new());
+
+        assert!(bundle.check_device(&device("cuda", 42)).is_ok());
+    }
+
+    #[test]
+    fn check_device_rejects_other_model() {
+        let bundle = AutotuneBundle::<String>::new("tuner", device("cuda", 42), Vec::new());
+
+        assert!(matches!(
+            bundle.check_device(&device("cuda", 7)),
+            Err(AutotuneBundleError::Device { .. })
+        ));
+        assert!(matches!(
+            bundle.check_device(&device("hip", 42)),
+            Err(AutotuneBundleError::Device { .. })
+        ));
+    }
+}
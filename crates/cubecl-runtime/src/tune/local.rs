@@ -1,13 +1,15 @@
 use super::{AutotuneKey, AutotuneOutput, TunableSet, Tuner};
+#[cfg(std_io)]
+use crate::tune::{AutotuneBundle, AutotuneBundleError, AutotuneDevice, AutotuneImport};
 use crate::{client::ComputeClient, runtime::Runtime, tune::TuneCacheResult};
-use alloc::string::ToString;
+use alloc::string::{String, ToString};
 use alloc::sync::Arc;
 use core::{
     any::{Any, TypeId},
     fmt::Display,
     hash::Hash,
 };
-use cubecl_common::map::SharedStateMap;
+use cubecl_common::map::{SharedState, SharedStateMap};
 use hashbrown::HashMap;
 
 /// A local tuner allows to create a tuner for a specific key that can be different from the server
@@ -93,6 +95,54 @@ where
         self.state.clear()
     }
 
+    /// Export every autotune result of the device `id` into a [bundle](AutotuneBundle), including
+    /// the results persisted by previous runs.
+    #[cfg(std_io)]
+    pub fn export<R: Runtime>(&self, id: &ID, client: &ComputeClient<R>) -> AutotuneBundle<AK> {
+        let tuner_state = self.tuner_state(id);
+        let mut tuner = tuner_state.write();
+
+        tuner.export(&self.tuner_name(), AutotuneDevice::new(client))
+    }
+
+    /// Import a [bundle](AutotuneBundle) for the device `id`, failing if it was exported from
+    /// another tuner or another model of device than the one of the `client`.
+    ///
+    /// Entries are validated against the checksum of the [tunable set](TunableSet) they are used
+    /// with, so results tuned with other tunables are rejected. Keys that were already tuned
+    /// locally keep their result.
+    #[cfg(std_io)]
+    pub fn import<R: Runtime, In, Out>(
+        &self,
+        id: &ID,
+        client: &ComputeClient<R>,
+        operations: &TunableSet<AK, In, Out>,
+        bundle: AutotuneBundle<AK>,
+    ) -> Result<AutotuneImport, AutotuneBundleError>
+    where
+        In: Clone + Send + 'static,
+        Out: AutotuneOutput,
+    {
+        let name = self.tuner_name();
+        bundle.check_tuner(&name)?;
+        bundle.check_device(&AutotuneDevice::new(client))?;
+
+        let checksum = operations.compute_checksum();
+        let tuner_state = self.tuner_state(id);
+        let mut tuner = tuner_state.write();
+
+        Ok(tuner.import(bundle.into_entries(), &checksum, operations.len()))
+    }
+
+    fn tuner_name(&self) -> String {
+        self.name.replace("::", "-")
+    }
+
+    fn tuner_state(&self, id: &ID) -> SharedState<Tuner<AK>> {
+        self.state
+            .get_or_init(id, |id| Tuner::new(&self.tuner_name(), &id.to_string()))
+    }
+
     #[cfg(feature = "autotune-checks")]
     fn checks<In: Send + Clone + 'static, Out: AutotuneOutput>(
         &self,
@@ -142,10 +192,7 @@ where
         let key = operations.generate_key(&inputs);
 
         // If this is cached and ready, use the operation.
-        let tuner_state = self.state.get_or_init(id, move |id| {
-            let name = self.name.replace("::", "-");
-            Tuner::new(&name, &id.to_string())
-        });
+        let tuner_state = self.tuner_state(id);
         let tuner = tuner_state.read();
 
         let mut tuner = match tuner.fastest(&key) {
@@ -57,6 +57,9 @@
 //! done by using [`#[diagnostic::on_unimplemented(...)]`](https://doc.rust-lang.org/reference/attributes/diagnostics.html#the-diagnosticon_unimplemented-attribute).
 
 mod base;
+mod budget;
+#[cfg(std_io)]
+mod bundle;
 mod function_tunable;
 mod input_generator;
 mod key_generator;
@@ -68,6 +71,8 @@ mod tuner;
 mod util;
 
 pub use base::*;
+#[cfg(std_io)]
+pub use bundle::*;
 pub use function_tunable::*;
 pub use input_generator::*;
 pub use key_generator::*;
@@ -11,8 +11,15 @@ pub struct TuneBenchmark<R: Runtime, In: Clone + Send + 'static, Out: Send + 'st
     operation: Arc<dyn TuneFn<Inputs = In, Output = Out>>,
     inputs: In,
     client: ComputeClient<R>,
+    #[new(value = "NUM_SAMPLES")]
+    num_samples: usize,
+    #[new(value = "true")]
+    warmup: bool,
 }
 
+/// The number of samples of a [benchmark](TuneBenchmark) by default.
+pub(crate) const NUM_SAMPLES: usize = 10;
+
 /// The trait to be implemented by an autotune output.
 pub trait AutotuneOutput: Send + 'static {
     #[cfg(feature = "autotune-checks")]
@@ -29,6 +36,18 @@ impl AutotuneOutput for () {
 }
 
 impl<R: Runtime, In: Clone + Send + 'static, Out: AutotuneOutput> TuneBenchmark<R, In, Out> {
+    /// Set the number of samples to profile.
+    pub fn with_samples(mut self, num_samples: usize) -> Self {
+        self.num_samples = num_samples;
+        self
+    }
+
+    /// Skip the warmup, when the operation was just profiled with the same inputs.
+    pub fn without_warmup(mut self) -> Self {
+        self.warmup = false;
+        self
+    }
+
     /// Benchmark how long this operation takes for a number of samples.
     ///
     /// Returns at least one duration, otherwise an error is returned.
@@ -45,13 +64,14 @@ impl<R: Runtime, In: Clone + Send + 'static, Out: AutotuneOutput> TuneBenchmark<
     }
 
     fn profile_exclusive(self) -> Result<Vec<ProfileDuration>, AutotuneError> {
-        self.warmup()?;
+        if self.warmup {
+            self.warmup()?;
+        }
 
         let operation = self.operation.clone();
         let name = operation.name().to_string();
-        let num_samples = 10;
         let mut durations = Vec::new();
-        for _ in 0..num_samples {
+        for _ in 0..self.num_samples {
             let result: Result<
                 (Result<Out, AutotuneError>, ProfileDuration),
                 crate::server::ProfileError,
@@ -8,6 +8,9 @@ use cubecl_common::cache::CacheError;
 #[cfg(std_io)]
 use serde::{Deserialize, Serialize};
 
+#[cfg(std_io)]
+use super::AutotuneBundleEntry;
+
 use super::{AutotuneError, AutotuneKey, AutotuneOutcome};
 use alloc::string::String;
 use hashbrown::HashMap;
@@ -236,6 +239,45 @@ impl<K: AutotuneKey> TuneCache<K> {
         // .expect();
     }
 
+    /// All the entries of the persistent cache, including the ones of other tunable sets.
+    pub(crate) fn export(&mut self) -> Vec<AutotuneBundleEntry<K>> {
+        let mut entries = Vec::new();
+        self.persistent_cache.for_each(|key, value| {
+            entries.push(AutotuneBundleEntry {
+                key: key.key.clone(),
+                checksum: key.checksum.clone(),
+                fastest_index: value.fastest_index,
+                results: value.results.clone(),
+            });
+        });
+        entries
+    }
+
+    /// Import an entry with a valid checksum, unless the key was already tuned locally.
+    ///
+    /// Returns whether the entry was imported.
+    pub(crate) fn import(&mut self, entry: AutotuneBundleEntry<K>) -> bool {
+        if let Some(CacheEntry::Done { checksum, .. }) = self.in_memory_cache.get_mut(&entry.key) {
+            match checksum {
+                ChecksumState::Match => return false,
+                ChecksumState::ToBeVerified(expected) if expected == &entry.checksum => {
+                    *checksum = ChecksumState::Match;
+                    return false;
+                }
+                _ => {}
+            }
+        }
+
+        self.cache_insert(entry.key.clone(), entry.fastest_index);
+        self.persistent_cache_insert(
+            entry.key,
+            entry.checksum,
+            entry.fastest_index,
+            entry.results,
+        );
+        true
+    }
+
     /// Load the persistent cache data from disk
     pub(crate) fn load(&mut self) {
         log::info!("Load autotune cache ...");
@@ -11,11 +11,17 @@ use core::time::Duration;
 use alloc::string::{String, ToString};
 use cubecl_common::benchmark::{BenchmarkComputations, BenchmarkDurations};
 
-use crate::config::{Logger, autotune::AutotuneLogLevel};
+use crate::config::{
+    GlobalConfig, Logger,
+    autotune::{AutotuneBudget, AutotuneLogLevel},
+};
 use crate::server::LaunchError;
+#[cfg(std_io)]
+use crate::tune::{AutotuneBundle, AutotuneBundleEntry, AutotuneDevice, AutotuneImport};
 use crate::tune::{AutotuneResult, TuneBenchmark, TuneCache};
 use crate::{client::ComputeClient, runtime::Runtime};
 
+use super::budget::TuneBudget;
 use super::{AutotuneKey, AutotuneOutput, TunableSet, TuneCacheResult, TuneFn, TunePlan};
 
 #[derive(Debug)]
@@ -23,6 +29,7 @@ use super::{AutotuneKey, AutotuneOutput, TunableSet, TuneCacheResult, TuneFn, Tu
 pub struct Tuner<K: AutotuneKey> {
     tune_cache: TuneCache<K>,
     logger: Logger,
+    budget: AutotuneBudget,
     channel: (Sender<AutotuneMessage<K>>, Receiver<AutotuneMessage<K>>),
     pub(crate) autotuning: HashSet<K>,
 }
@@ -89,6 +96,14 @@ pub enum AutotuneError {
         /// The name of the skipped kernel.
         name: String,
     },
+    /// The kernel is discarded by the [autotune budget](AutotuneBudget), being slower than the
+    /// other kernels.
+    Discarded {
+        /// The name of the discarded kernel.
+        name: String,
+        /// The score of the kernel when it was discarded, lower is better.
+        score: u64,
+    },
 
     /// An error happened when launching a kernel.
     Launch(LaunchError),
@@ -109,6 +124,7 @@ impl<K: AutotuneKey> Tuner<K> {
         Self {
             tune_cache: TuneCache::new(name, device_id),
             logger: Logger::new(),
+            budget: GlobalConfig::get().autotune.budget.clone(),
             channel,
             autotuning: HashSet::new(),
         }
@@ -129,6 +145,44 @@ impl<K: AutotuneKey> Tuner<K> {
         self.tune_cache.validate_checksum(key, checksum)
     }
 
+    /// Export the autotune results of the tuner, see [`LocalTuner::export`](super::LocalTuner::export).
+    #[cfg(std_io)]
+    pub fn export(&mut self, name: &str, device: AutotuneDevice) -> AutotuneBundle<K> {
+        self.handle_results();
+        AutotuneBundle::new(name, device, self.tune_cache.export())
+    }
+
+    /// Import autotune results, only keeping the entries tuned with the same `checksum` and whose
+    /// fastest index exists among the `num_tunables`.
+    #[cfg(std_io)]
+    pub fn import(
+        &mut self,
+        entries: Vec<AutotuneBundleEntry<K>>,
+        checksum: &str,
+        num_tunables: usize,
+    ) -> AutotuneImport {
+        let mut import = AutotuneImport::default();
+
+        for entry in entries {
+            let valid = entry.checksum == checksum && entry.fastest_index < num_tunables;
+
+            if valid && self.tune_cache.import(entry) {
+                import.imported += 1;
+            } else {
+                import.rejected += 1;
+            }
+        }
+
+        if let AutotuneLogLevel::Full = self.logger.log_level_autotune() {
+            self.logger.log_autotune(&format!(
+                "Imported {} autotune entries, rejected {}",
+                import.imported, import.rejected
+            ));
+        }
+
+        import
+    }
+
     /// Handle an autotune result message, see [`execute_autotune`]
     fn handle_result(&mut self, msg: AutotuneMessage<K>) {
         match msg {
@@ -271,6 +325,7 @@ impl<K: AutotuneKey> Tuner<K> {
 
         #[cfg(std_io)]
         let checksum = tunables.compute_checksum();
+        let budget = self.budget.clone();
         let context_logs = match self.logger.log_level_autotune() {
             AutotuneLogLevel::Disabled => false,
             AutotuneLogLevel::Minimal => false,
@@ -289,6 +344,7 @@ impl<K: AutotuneKey> Tuner<K> {
                 results,
                 #[cfg(std_io)]
                 checksum,
+                &budget,
                 context_logs,
             )
             .await
@@ -331,6 +387,7 @@ impl<K: AutotuneKey> Tuner<K> {
         test_inputs: In,
         mut results: Vec<AutotuneResult>,
         #[cfg(std_io)] checksum: String,
+        budget: &AutotuneBudget,
         context_logs: bool,
     ) -> AutotuneMessage<K> {
         let context_logs = match Self::execute_tune_plan(
@@ -339,6 +396,7 @@ impl<K: AutotuneKey> Tuner<K> {
             autotunables,
             &test_inputs,
             &mut results,
+            budget,
             context_logs,
         )
         .await
@@ -383,12 +441,14 @@ impl<K: AutotuneKey> Tuner<K> {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn execute_tune_plan<In: Clone + Send + 'static, Out: AutotuneOutput, R: Runtime>(
         client: &ComputeClient<R>,
         plan: &mut TunePlan,
         autotunables: Vec<Arc<dyn TuneFn<Inputs = In, Output = Out> + 'static>>,
         test_inputs: &In,
         results: &mut [AutotuneResult],
+        budget: &AutotuneBudget,
         context_logs: bool,
     ) -> Result<Option<String>, AutotuneError> {
         #[derive(Debug)]
@@ -402,6 +462,7 @@ impl<K: AutotuneKey> Tuner<K> {
             true => Some("".to_string()),
             false => None,
         };
+        let mut budget = TuneBudget::new(budget);
 
         loop {
             let mut num_success = 0;
@@ -413,30 +474,50 @@ impl<K: AutotuneKey> Tuner<K> {
                 });
             }
 
-            for index in tunable_indices {
-                let op = &autotunables[index];
-                let name = op.name().to_string();
-                let tuner = TuneBenchmark::new(op.clone(), test_inputs.clone(), client.clone());
-                let profiles = tuner.profile().map(|bench| (name, index, bench));
-
-                match profiles {
-                    Ok(result) => {
-                        // Wait for the results to come in, and determine the outcome.
-                        let (name, index, profiles) = result;
-                        let result = Self::process_autotune(name, index, profiles).await;
-                        match result {
-                            Ok(val) => {
-                                results[index] = AutotuneResult::success(val);
+            // Successive halving: every round benchmarks the fastest tunables of the previous
+            // round with more samples.
+            let mut candidates = tunable_indices;
+            let mut round = 0;
+            loop {
+                let num_samples = budget.num_samples(round);
+                let mut scores = Vec::with_capacity(candidates.len());
+
+                for index in candidates {
+                    if num_success > 0 && budget.is_exhausted() {
+                        break;
+                    }
+
+                    let op = &autotunables[index];
+                    let result =
+                        Self::benchmark(client, op, index, test_inputs, num_samples, &budget).await;
+
+                    match result {
+                        Ok(val) => {
+                            let score = val.computation.score();
+                            budget.register(score);
+                            scores.push((index, score));
+                            results[index] = AutotuneResult::success(val);
+                            if round == 0 {
                                 num_success += 1;
                             }
-                            Err(err) => {
-                                results[index] = AutotuneResult::error(err);
-                            }
+                        }
+                        Err(err) => {
+                            results[index] = AutotuneResult::error(err);
                         }
                     }
-                    Err(err) => {
-                        results[index] = AutotuneResult::error(err);
+                }
+
+                match budget.next_round(round, scores) {
+                    Some(next) => {
+                        for (index, score) in next.discarded {
+                            let name = autotunables[index].name().to_string();
+                            results[index] =
+                                AutotuneResult::error(AutotuneError::Discarded { name, score });
+                        }
+                        candidates = next.survivors;
+                        round += 1;
                     }
+                    None => break,
                 }
             }
 
@@ -448,6 +529,39 @@ impl<K: AutotuneKey> Tuner<K> {
         Ok(context_logs)
     }
 
+    /// Benchmark a single tunable, aborting it after a single sample when it's much slower than
+    /// the fastest tunable so far.
+    async fn benchmark<In: Clone + Send + 'static, Out: AutotuneOutput, R: Runtime>(
+        client: &ComputeClient<R>,
+        op: &Arc<dyn TuneFn<Inputs = In, Output = Out> + 'static>,
+        index: usize,
+        test_inputs: &In,
+        num_samples: usize,
+        budget: &TuneBudget,
+    ) -> Result<AutotuneOutcome, AutotuneError> {
+        let name = op.name().to_string();
+        let benchmark = TuneBenchmark::new(op.clone(), test_inputs.clone(), client.clone());
+
+        if !budget.should_probe() {
+            let profiles = benchmark.with_samples(num_samples).profile()?;
+            return Self::process_autotune(name, index, profiles).await;
+        }
+
+        let probe = benchmark.with_samples(1).profile()?;
+        let probe = Self::process_autotune(name.clone(), index, probe).await?;
+        let score = probe.computation.score();
+        if budget.should_abort(score) {
+            return Err(AutotuneError::Discarded { name, score });
+        }
+
+        // The probe already warmed up the tunable.
+        let profiles = TuneBenchmark::new(op.clone(), test_inputs.clone(), client.clone())
+            .with_samples(num_samples)
+            .without_warmup()
+            .profile()?;
+        Self::process_autotune(name, index, profiles).await
+    }
+
     async fn process_autotune(
         name: String,
         index: usize,
@@ -0,0 +1,125 @@
+//! Autotunes fake tunables of known cost, with the autotune budget set by the global config of this
+//! test binary.
+#[allow(dead_code, reason = "Only the dummy client is used")]
+mod dummy;
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread::sleep,
+    time::Duration,
+};
+
+use cubecl_runtime::{
+    config::{
+        GlobalConfig,
+        autotune::{AutotuneBudget, AutotuneConfig},
+        cache::CacheLocation,
+    },
+    local_tuner,
+    tune::{AutotuneError, LocalTuner, Tunable, TunableSet, TuneFn},
+};
+use dummy::{DummyDevice, test_client};
+
+/// A tunable sleeping for `cost` and counting its executions.
+#[derive(Clone)]
+struct FakeTunable {
+    name: &'static str,
+    cost: Duration,
+    executions: Arc<AtomicUsize>,
+}
+
+impl FakeTunable {
+    fn new(name: &'static str, cost_ms: u64) -> Self {
+        Self {
+            name,
+            cost: Duration::from_millis(cost_ms),
+            executions: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    fn executions(&self) -> usize {
+        self.executions.load(Ordering::Relaxed)
+    }
+}
+
+impl TuneFn for FakeTunable {
+    type Inputs = ();
+    type Output = ();
+
+    fn execute(&self, _inputs: ()) -> Result<(), AutotuneError> {
+        self.executions.fetch_add(1, Ordering::Relaxed);
+        sleep(self.cost);
+        Ok(())
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+}
+
+#[test_log::test]
+#[cfg(feature = "std")]
+fn autotune_budget_discards_slow_tunables() {
+    static TUNER: LocalTuner<String, String> = local_tuner!("autotune_budget");
+
+    // Results persisted by a previous run would skip the autotuning.
+    let cache = tempfile::tempdir().unwrap();
+    GlobalConfig::set(GlobalConfig {
+        autotune: AutotuneConfig {
+            budget: AutotuneBudget {
+                max_duration_ms: Some(200),
+                halving_samples: Some(1),
+                abort_ratio: Some(4.0),
+            },
+            cache: CacheLocation::File(cache.path().to_path_buf()).into(),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+
+    let fast = FakeTunable::new("fast", 1);
+    let slow = FakeTunable::new("slow", 100);
+    let late = FakeTunable::new("late", 1);
+
+    let device = "device".to_string();
+    let client = test_client(&DummyDevice);
+    let tunables = [fast.clone(), slow.clone(), late.clone()];
+    let set = TUNER.init(move || {
+        tunables.iter().fold(
+            TunableSet::new(|_: &()| "key".to_string(), |_: &String, _: &()| ()),
+            |set, tunable| set.with(Tunable::new(tunable.name, tunable.clone())),
+        )
+    });
+
+    TUNER.execute(&device, &client, set.clone(), ());
+
+    assert_eq!(
+        slow.executions(),
+        4,
+        "The slow tunable is aborted after its warmup and a single sample."
+    );
+    assert_eq!(
+        late.executions(),
+        0,
+        "The budget is exhausted before the last tunable."
+    );
+
+    let bundle = TUNER.export(&device, &client);
+    let entry = bundle
+        .entries()
+        .iter()
+        .find(|entry| entry.key == "key")
+        .unwrap();
+    assert_eq!(entry.fastest_index, 0);
+
+    let executions = [fast.executions(), slow.executions(), late.executions()];
+    TUNER.execute(&device, &client, set, ());
+    assert_eq!(
+        [fast.executions(), slow.executions(), late.executions()],
+        [executions[0] + 1, executions[1], executions[2]],
+        "The fastest tunable is cached."
+    );
+}
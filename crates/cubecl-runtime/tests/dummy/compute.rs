@@ -109,7 +109,7 @@ impl Runtime for DummyRuntime {
     }
 
     fn name(_client: &ComputeClient<Self>) -> &'static str {
-        unimplemented!()
+        "dummy"
     }
 
     fn max_cube_count() -> (u32, u32, u32) {
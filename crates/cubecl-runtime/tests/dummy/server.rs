@@ -11,7 +11,9 @@ use cubecl_runtime::{
     id::KernelId,
     kernel::{CompiledKernel, KernelMetadata},
     logging::ServerLogger,
-    memory_management::{ManagedMemoryHandle, MemoryAllocationMode, MemoryManagement, MemoryUsage},
+    memory_management::{
+        ManagedMemoryHandle, MemoryAllocationMode, MemoryManagement, MemoryTrace, MemoryUsage,
+    },
     server::{
         Binding, ComputeServer, CopyDescriptor, CubeCount, CubeDim, ExecutionMode, Handle,
         KernelArguments, ProfileError, ProfilingToken, ServerCommunication, ServerError,
@@ -183,6 +185,8 @@ impl ComputeServer for DummyServer {
         mode: ExecutionMode,
         stream_id: StreamId,
     ) {
+        self.memory_management.trace_launch(kernel.name());
+
         let mut resources: Vec<_> = bindings
             .buffers
             .into_iter()
@@ -226,6 +230,10 @@ impl ComputeServer for DummyServer {
         self.memory_management.cleanup(true);
     }
 
+    fn memory_trace(&mut self, _stream_id: StreamId) -> Result<MemoryTrace, ServerError> {
+        Ok(self.memory_management.take_trace())
+    }
+
     fn start_profile(&mut self, _stream_id: StreamId) -> Result<ProfilingToken, ServerError> {
         Ok(self.timestamps.start())
     }
@@ -100,3 +100,72 @@ fn autotune_basic_multiplication_execution() {
     // If slow kernel was selected it would output [0, 1, 2]
     assert_eq!(obtained_resource, Vec::from([0, 4, 8]));
 }
+
+#[test_log::test]
+#[cfg(feature = "std")]
+fn autotune_bundle_export_import() {
+    use cubecl_runtime::tune::AutotuneBundle;
+
+    // Two tuners with the same name, as in two processes sharing a bundle.
+    static TUNER: LocalTuner<String, String> = local_tuner!("autotune_bundle_export_import");
+    static IMPORTER: LocalTuner<String, String> = local_tuner!("autotune_bundle_export_import");
+
+    let device = "device".to_string();
+    let client = test_client(&DummyDevice);
+    let create_handles = || {
+        let lhs = client.create_from_slice(&[0, 1, 2]);
+        let rhs = client.create_from_slice(&[4, 4, 4]);
+        let out = client.empty(3);
+        vec![lhs, rhs, out]
+    };
+    let shapes = vec![vec![1, 3], vec![1, 3], vec![1, 3]];
+
+    let test_set = TUNER.init(move || {
+        let client = test_client(&DummyDevice);
+        dummy::addition_set(client, shapes.clone())
+    });
+    TUNER.execute(&device, &client, test_set.clone(), create_handles());
+
+    let bundle = TUNER.export(&device, &client);
+    assert!(
+        bundle
+            .entries()
+            .iter()
+            .any(|entry| entry.key.starts_with("add"))
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("autotune-bundle.json");
+    bundle.save(&path).unwrap();
+    let bundle = AutotuneBundle::<String>::load(&path).unwrap();
+
+    let import = IMPORTER
+        .import(&device, &client, &test_set, bundle.clone())
+        .unwrap();
+    assert_eq!(import.imported + import.rejected, bundle.entries().len());
+
+    let handles = create_handles();
+    let out = handles[2].clone();
+    IMPORTER.execute(&device, &client, test_set.clone(), handles);
+    let obtained_resource = client.read_one(out).unwrap().to_vec();
+    assert_eq!(obtained_resource, Vec::from([4, 5, 6]));
+
+    let result = IMPORTER.import(
+        &"other-device".to_string(),
+        &client,
+        &test_set,
+        bundle.clone(),
+    );
+    assert!(
+        result.is_ok(),
+        "Bundles are imported for any device of the same model."
+    );
+
+    let stale_set = dummy::addition_set(client.clone(), vec![vec![1, 3]; 3])
+        .with_custom_checksum(|_| "stale".to_string());
+    let import = TUNER.import(&device, &client, &stale_set, bundle).unwrap();
+    assert_eq!(
+        import.imported, 0,
+        "Entries tuned with other tunables are rejected."
+    );
+}
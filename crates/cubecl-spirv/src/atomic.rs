@@ -356,7 +356,7 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
 
     fn semantics_of(&mut self, var: &crate::variable::Variable) -> MemorySemantics {
         match self.scope_of(var) {
-            Scope::Device => MemorySemantics::UNIFORM_MEMORY,
+            Scope::Device => T::buffer_memory_semantics(),
             Scope::Workgroup => MemorySemantics::WORKGROUP_MEMORY,
             Scope::Subgroup => MemorySemantics::SUBGROUP_MEMORY,
             other => unreachable!("Invalid scope for atomic operation, {other:?}"),
@@ -11,13 +11,15 @@ use cubecl_core::{
     Compiler, CubeDim, Info, Metadata, WgpuCompilationOptions,
     ir::{self as core, ElemType, InstructionModes, StorageType, UIntKind, features::EnumSet},
     post_processing::{
-        checked_io::CheckedIoProcessor, saturating::SaturatingArithmeticProcessor,
-        unroll::UnrollProcessor,
+        checked_io::CheckedIoProcessor, packed::PackedArithmeticProcessor,
+        saturating::SaturatingArithmeticProcessor, unroll::UnrollProcessor,
     },
     prelude::{FastMath, KernelDefinition},
     server::ExecutionMode,
 };
-use cubecl_opt::{BasicBlock, NodeIndex, Optimizer, OptimizerBuilder, SharedLiveness, Uniformity};
+use cubecl_opt::{
+    BasicBlock, NodeIndex, Optimizer, OptimizerBuilder, SharedLiveness, Uniformity, UnrollOptions,
+};
 use cubecl_runtime::{
     compiler::CompilationError,
     config::{GlobalConfig, compilation::CompilationLogLevel},
@@ -235,7 +237,7 @@ impl<Target: SpirvTarget> SpirvCompiler<Target> {
 
         let mut target = self.target.clone();
 
-        let mut opt = OptimizerBuilder::default()
+        let mut builder = OptimizerBuilder::default()
             .with_transformer(ErfTransform)
             .with_transformer(BitwiseTransform::new(
                 self.compilation_options.vulkan.supports_arbitrary_bitwise,
@@ -246,9 +248,13 @@ impl<Target: SpirvTarget> SpirvCompiler<Target> {
                 self.mode,
                 kernel.options.kernel_name.clone(),
             ))
+            .with_processor(PackedArithmeticProcessor::new())
             .with_processor(UnrollProcessor::new(MAX_VECTORIZATION))
-            .with_processor(SaturatingArithmeticProcessor::new(true))
-            .optimize(kernel.body.clone(), kernel.cube_dim);
+            .with_processor(SaturatingArithmeticProcessor::new(true));
+        if self.compilation_options.unroll_loops {
+            builder = builder.with_unroll(UnrollOptions::default());
+        }
+        let mut opt = builder.optimize(kernel.body.clone(), kernel.cube_dim);
 
         self.uniformity = opt.analysis::<Uniformity>();
         self.shared_liveness = opt.analysis::<SharedLiveness>();
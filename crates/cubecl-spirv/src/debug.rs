@@ -20,7 +20,8 @@ use std::borrow::Cow;
 
 use cubecl_core::ir::{self as core, CubeFnSource, Id, SourceLoc, Variable};
 use hashbrown::HashMap;
-use rspirv::spirv::{DebugInfoFlags, FunctionControl, Word};
+use rspirv::dr::Instruction;
+use rspirv::spirv::{DebugInfoFlags, FunctionControl, Op, Word};
 use rspirv::sr::{
     nonsemantic_debugprintf::DebugPrintfBuilder, nonsemantic_shader_debuginfo_100::DebugInfoBuilder,
 };
@@ -139,7 +140,8 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
 
     pub fn declare_main(&mut self, kernel_name: &str) -> (Word, impl Fn(&mut Self) + 'static) {
         let void = self.type_void();
-        let voidf = self.type_function(void, vec![]);
+        let parameters = self.state.parameters.clone();
+        let voidf = self.type_function(void, parameters.iter().map(|(_, ty)| *ty));
 
         let definition = self
             .debug_info
@@ -161,6 +163,13 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
             .unwrap();
         self.debug_name(main, kernel_name);
 
+        // Parameter ids are allocated with the bindings, so they can't be generated by the builder
+        let function = self.selected_function().unwrap();
+        for (id, ty) in parameters {
+            let param = Instruction::new(Op::FunctionParameter, Some(ty), Some(id), vec![]);
+            self.module_mut().functions[function].parameters.push(param);
+        }
+
         let func_id = definition.map(|it| it.id);
 
         let setup = move |b: &mut Self| {
@@ -1,8 +1,14 @@
-use rspirv::spirv::Word;
+use rspirv::{
+    dr::Operand,
+    spirv::{CLOp, Op, Word},
+};
 
-use crate::SpirvCompiler;
+use crate::{
+    SpirvCompiler,
+    item::{Elem, Item},
+};
 
-use super::{GLCompute, SpirvTarget};
+use super::{GLCompute, Kernel, SpirvTarget};
 
 pub trait TargetExtensions<T: SpirvTarget> {
     fn round(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word);
@@ -229,3 +235,240 @@ pub mod glcompute {
         }
     }
 }
+
+pub mod kernel {
+    use super::*;
+
+    /// The name of the `OpenCL` extended instruction set. `rspirv` imports it as `OpenCL.std.100`,
+    /// which isn't accepted by consumers, so the instructions are emitted manually.
+    const OPENCL_STD: &str = "OpenCL.std";
+
+    fn opencl_std<T: SpirvTarget>(
+        b: &mut SpirvCompiler<T>,
+        op: CLOp,
+        ty: Word,
+        args: impl IntoIterator<Item = Word>,
+        out: Word,
+    ) {
+        let existing = b
+            .module_ref()
+            .ext_inst_imports
+            .iter()
+            .find(|inst| inst.operands[0] == Operand::LiteralString(OPENCL_STD.into()))
+            .and_then(|inst| inst.result_id);
+        let set = existing.unwrap_or_else(|| b.ext_inst_import(OPENCL_STD));
+        let args = args.into_iter().map(Operand::IdRef);
+        b.ext_inst(ty, Some(out), set, op as u32, args).unwrap();
+    }
+
+    /// Recover the item of an integer type, to create constants of the same type.
+    fn int_item<T: SpirvTarget>(b: &SpirvCompiler<T>, ty: Word) -> Item {
+        let types = &b.module_ref().types_global_values;
+        let find = |id| types.iter().find(|it| it.result_id == Some(id)).unwrap();
+        let inst = find(ty);
+        match inst.class.opcode {
+            Op::TypeInt => Item::Scalar(Elem::Int(inst.operands[0].unwrap_literal_bit32(), false)),
+            Op::TypeVector => {
+                let elem = find(inst.operands[0].unwrap_id_ref());
+                let width = elem.operands[0].unwrap_literal_bit32();
+                Item::Vector(
+                    Elem::Int(width, false),
+                    inst.operands[1].unwrap_literal_bit32(),
+                )
+            }
+            other => unreachable!("Bit scans are only defined on integers, got {other:?}"),
+        }
+    }
+
+    impl<T: SpirvTarget> TargetExtensions<T> for Kernel {
+        fn round(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::round, ty, [input], out);
+        }
+
+        fn f_abs(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::fabs, ty, [input], out);
+        }
+
+        fn s_abs(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::s_abs, ty, [input], out);
+        }
+
+        fn floor(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::floor, ty, [input], out);
+        }
+
+        fn ceil(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::ceil, ty, [input], out);
+        }
+
+        fn trunc(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::trunc, ty, [input], out);
+        }
+
+        fn sin(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::sin, ty, [input], out);
+        }
+
+        fn cos(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::cos, ty, [input], out);
+        }
+
+        fn tan(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::tan, ty, [input], out);
+        }
+
+        fn tanh(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::tanh, ty, [input], out);
+        }
+
+        fn sinh(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::sinh, ty, [input], out);
+        }
+
+        fn cosh(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::cosh, ty, [input], out);
+        }
+
+        fn asin(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::asin, ty, [input], out);
+        }
+
+        fn acos(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::acos, ty, [input], out);
+        }
+
+        fn atan(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::atan, ty, [input], out);
+        }
+
+        fn asinh(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::asinh, ty, [input], out);
+        }
+
+        fn acosh(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::acosh, ty, [input], out);
+        }
+
+        fn atanh(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::atanh, ty, [input], out);
+        }
+
+        fn degrees(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::degrees, ty, [input], out);
+        }
+
+        fn radians(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::radians, ty, [input], out);
+        }
+
+        fn atan2(b: &mut SpirvCompiler<T>, ty: Word, lhs: Word, rhs: Word, out: Word) {
+            opencl_std(b, CLOp::atan2, ty, [lhs, rhs], out);
+        }
+
+        fn pow(b: &mut SpirvCompiler<T>, ty: Word, lhs: Word, rhs: Word, out: Word) {
+            opencl_std(b, CLOp::pow, ty, [lhs, rhs], out);
+        }
+
+        fn exp(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::exp, ty, [input], out);
+        }
+
+        fn log(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::log, ty, [input], out);
+        }
+
+        fn sqrt(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::sqrt, ty, [input], out);
+        }
+
+        fn inverse_sqrt(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::rsqrt, ty, [input], out);
+        }
+
+        fn f_min(b: &mut SpirvCompiler<T>, ty: Word, lhs: Word, rhs: Word, out: Word) {
+            opencl_std(b, CLOp::fmin, ty, [lhs, rhs], out);
+        }
+
+        fn u_min(b: &mut SpirvCompiler<T>, ty: Word, lhs: Word, rhs: Word, out: Word) {
+            opencl_std(b, CLOp::u_min, ty, [lhs, rhs], out);
+        }
+
+        fn s_min(b: &mut SpirvCompiler<T>, ty: Word, lhs: Word, rhs: Word, out: Word) {
+            opencl_std(b, CLOp::s_min, ty, [lhs, rhs], out);
+        }
+
+        fn f_max(b: &mut SpirvCompiler<T>, ty: Word, lhs: Word, rhs: Word, out: Word) {
+            opencl_std(b, CLOp::fmax, ty, [lhs, rhs], out);
+        }
+
+        fn u_max(b: &mut SpirvCompiler<T>, ty: Word, lhs: Word, rhs: Word, out: Word) {
+            opencl_std(b, CLOp::u_max, ty, [lhs, rhs], out);
+        }
+
+        fn s_max(b: &mut SpirvCompiler<T>, ty: Word, lhs: Word, rhs: Word, out: Word) {
+            opencl_std(b, CLOp::s_max, ty, [lhs, rhs], out);
+        }
+
+        fn f_clamp(
+            b: &mut SpirvCompiler<T>,
+            ty: Word,
+            input: Word,
+            min: Word,
+            max: Word,
+            out: Word,
+        ) {
+            opencl_std(b, CLOp::fclamp, ty, [input, min, max], out);
+        }
+
+        fn u_clamp(
+            b: &mut SpirvCompiler<T>,
+            ty: Word,
+            input: Word,
+            min: Word,
+            max: Word,
+            out: Word,
+        ) {
+            opencl_std(b, CLOp::u_clamp, ty, [input, min, max], out);
+        }
+
+        fn s_clamp(
+            b: &mut SpirvCompiler<T>,
+            ty: Word,
+            input: Word,
+            min: Word,
+            max: Word,
+            out: Word,
+        ) {
+            opencl_std(b, CLOp::s_clamp, ty, [input, min, max], out);
+        }
+
+        fn magnitude(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::length, ty, [input], out);
+        }
+
+        fn normalize(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            opencl_std(b, CLOp::normalize, ty, [input], out);
+        }
+
+        fn find_msb(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            let item = int_item(b, ty);
+            let max_bit = item.const_u32(b, item.elem().size() * 8 - 1);
+            let leading_zeros = b.id();
+            opencl_std(b, CLOp::clz, ty, [input], leading_zeros);
+            b.i_sub(ty, Some(out), max_bit, leading_zeros).unwrap();
+        }
+
+        fn find_lsb(b: &mut SpirvCompiler<T>, ty: Word, input: Word, out: Word) {
+            // `ctz` returns the bit width for zero, but `FindILsb` returns -1
+            let item = int_item(b, ty);
+            let zero = item.const_u32(b, 0);
+            let not_found = b.not(ty, None, zero).unwrap();
+            let trailing_zeros = b.id();
+            opencl_std(b, CLOp::ctz, ty, [input], trailing_zeros);
+            let bool_ty = item.same_vectorization(Elem::Bool).id(b);
+            let is_zero = b.i_equal(bool_ty, None, input, zero).unwrap();
+            b.select(ty, Some(out), is_zero, not_found, trailing_zeros)
+                .unwrap();
+        }
+    }
+}
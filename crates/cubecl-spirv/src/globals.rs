@@ -250,9 +250,11 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
     }
 
     fn load_builtin(&mut self, builtin: BuiltIn, item: &Item) -> Word {
-        let item_id = item.id(self);
-        let id = self.builtin(builtin, item.clone());
-        self.load(item_id, None, id, None, vec![]).unwrap()
+        let builtin_item = T::builtin_item(builtin, item.clone());
+        let item_id = builtin_item.id(self);
+        let id = self.builtin(builtin, builtin_item.clone());
+        let value = self.load(item_id, None, id, None, vec![]).unwrap();
+        builtin_item.cast_to(self, None, value, item)
     }
 }
 
@@ -220,6 +220,12 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
 
                 self.write_indexed(&out, &index, value_id);
             }
+            Operator::Cast(op)
+                if op.input.storage_type() != out.storage_type()
+                    && (op.input.ty.packing_factor() > 1 || out.ty.packing_factor() > 1) =>
+            {
+                self.compile_packed_cast(op.input, out, uniform);
+            }
             Operator::Cast(op) => {
                 let input = self.compile_variable(op.input);
                 let out = self.compile_variable(out);
@@ -253,8 +259,14 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
                 });
             }
             Operator::Reinterpret(op) => {
-                self.compile_unary_op(op, out, uniform, |b, _, ty, input, out| {
-                    b.bitcast(ty, Some(out), input).unwrap();
+                let in_item = self.compile_type(op.input.ty);
+                self.compile_unary_op(op, out, uniform, |b, out_item, ty, input, out| {
+                    // Packed types share their representation with the matching uint
+                    if in_item == out_item {
+                        b.copy_object(ty, Some(out), input).unwrap();
+                    } else {
+                        b.bitcast(ty, Some(out), input).unwrap();
+                    }
                 })
             }
             Operator::InitVector(op) => {
@@ -332,9 +332,7 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
                     unimplemented!("Barrier type not supported in SPIR-V")
                 }
             },
-            core::StorageType::Packed(_, _) => {
-                unimplemented!("Packed types not yet supported in SPIR-V")
-            }
+            core::StorageType::Packed(_, _) => self.compile_packed_type(ty),
         }
     }
 
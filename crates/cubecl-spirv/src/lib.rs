@@ -22,12 +22,16 @@ mod instruction;
 mod item;
 mod lookups;
 mod metadata;
+mod packed;
 mod subgroup;
 mod sync;
 mod target;
 mod transformers;
 mod variable;
 
+#[cfg(test)]
+mod tests;
+
 pub use compiler::*;
 use serde::{Deserialize, Serialize};
 pub use target::*;
@@ -22,6 +22,9 @@ use crate::{
 #[derive(Clone, Debug, Default)]
 pub struct LookupTables {
     pub buffers: Vec<Word>,
+    /// Ids and types of the parameters of the entry point, for targets that pass bindings as
+    /// kernel arguments.
+    pub parameters: Vec<(Word, Word)>,
     pub scalar_bindings: HashMap<ir::StorageType, u32>,
     pub info: Word,
     pub cube_dims: Vec<Word>,
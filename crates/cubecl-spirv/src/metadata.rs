@@ -161,12 +161,9 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
         let ty_id = ty.id(self);
         let storage_class = T::info_storage_class(self);
         let ptr_ty = Item::Pointer(storage_class, Box::new(ty)).id(self);
-        let info = self.state.info;
         let offset = self.const_u32(self.state.scalar_bindings.len() as u32 + 1);
         let index = self.read(index);
-        let info_ptr = self
-            .access_chain(ptr_ty, None, info, vec![offset, index])
-            .unwrap();
+        let info_ptr = T::dynamic_meta_pointer(self, ptr_ty, offset, index);
         self.load(ty_id, out, info_ptr, None, vec![]).unwrap()
     }
 
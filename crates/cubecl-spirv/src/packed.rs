@@ -0,0 +1,196 @@
+//! Lowering for packed storage types. SPIR-V has no packed minifloats, so packed values are
+//! stored as plain unsigned integers holding the raw bits, and converted with bit manipulation.
+//! Lanes are little endian, i.e. the first logical element is in the lowest bits.
+
+use cubecl_core::ir::{self as core, ElemType, FloatKind, StorageType, UIntKind};
+use rspirv::spirv::Word;
+
+use crate::{
+    SpirvCompiler, SpirvTarget,
+    item::{Elem, Item},
+};
+
+/// Midpoints between consecutive `e2m1` magnitudes. A magnitude is encoded as the number of
+/// thresholds it exceeds. Ties round to the even code, so the comparison is inclusive for odd
+/// codes.
+const E2M1_THRESHOLDS: [(f32, bool); 7] = [
+    (0.25, false),
+    (0.75, true),
+    (1.25, false),
+    (1.75, true),
+    (2.5, false),
+    (3.5, true),
+    (5.0, false),
+];
+
+impl<T: SpirvTarget> SpirvCompiler<T> {
+    pub fn compile_packed_type(&mut self, ty: StorageType) -> Elem {
+        match ty {
+            StorageType::Packed(ElemType::Float(FloatKind::E2M1), 2) => {
+                self.compile_elem(ElemType::UInt(UIntKind::U8))
+            }
+            other => unimplemented!("Packed type {other} not yet supported in SPIR-V"),
+        }
+    }
+
+    /// Cast to or from a packed type, by unpacking to or packing from `f32` lanes.
+    pub fn compile_packed_cast(
+        &mut self,
+        input: core::Variable,
+        out: core::Variable,
+        uniform: bool,
+    ) {
+        let in_ty = input.storage_type();
+        let out_ty = out.storage_type();
+
+        let input = self.compile_variable(input);
+        let out = self.compile_variable(out);
+        let in_id = self.read(&input);
+        let out_id = self.write_id(&out);
+        self.mark_uniformity(out_id, uniform);
+
+        match (in_ty, out_ty) {
+            (StorageType::Packed(..), StorageType::Packed(..)) => {
+                let unpacked = self.unpack_e2m1(&input.item(), in_id);
+                let f32_item =
+                    Item::Vector(Elem::Float(32, None), input.item().vectorization() * 2);
+                self.pack_e2m1(&f32_item, unpacked, &out.item(), out_id);
+            }
+            (StorageType::Packed(..), _) => {
+                let unpacked = self.unpack_e2m1(&input.item(), in_id);
+                let f32_item = Item::Vector(Elem::Float(32, None), out.item().vectorization());
+                f32_item.cast_to(self, Some(out_id), unpacked, &out.item());
+            }
+            _ => self.pack_e2m1(&input.item(), in_id, &out.item(), out_id),
+        }
+
+        self.write(&out, out_id);
+    }
+
+    /// Unpack `e2m1x2` bytes into `f32` lanes, twice as many as the input.
+    fn unpack_e2m1(&mut self, item: &Item, value: Word) -> Word {
+        let words = item.vectorization();
+        let lanes = words * 2;
+        let word_item = item.same_vectorization(Elem::Int(32, false));
+        let word_ty = word_item.id(self);
+        let lane_item = Item::Vector(Elem::Int(32, false), lanes);
+        let lane_ty = lane_item.id(self);
+
+        let word = self.u_convert(word_ty, None, value).unwrap();
+        let mask = word_item.const_u32(self, 0xF);
+        let four = word_item.const_u32(self, 4);
+        let lo = self.bitwise_and(word_ty, None, word, mask).unwrap();
+        let hi = self.shift_right_logical(word_ty, None, word, four).unwrap();
+        let hi = self.bitwise_and(word_ty, None, hi, mask).unwrap();
+        let nibbles = match words {
+            1 => self.composite_construct(lane_ty, None, [lo, hi]).unwrap(),
+            _ => {
+                let components = (0..words).flat_map(|i| [i, words + i]);
+                self.vector_shuffle(lane_ty, None, lo, hi, components)
+                    .unwrap()
+            }
+        };
+
+        // Magnitudes 0 and 1 are the subnormals 0.0 and 0.5, the rest follow the regular
+        // exponent/mantissa layout shifted into the f32 position.
+        let seven = lane_item.const_u32(self, 7);
+        let eight = lane_item.const_u32(self, 8);
+        let two = lane_item.const_u32(self, 2);
+        let shift_sign = lane_item.const_u32(self, 28);
+        let shift_mag = lane_item.const_u32(self, 22);
+        let half = lane_item.const_u32(self, 0x3F00_0000);
+
+        let mag = self.bitwise_and(lane_ty, None, nibbles, seven).unwrap();
+        let sign = self.bitwise_and(lane_ty, None, nibbles, eight).unwrap();
+        let sign = self
+            .shift_left_logical(lane_ty, None, sign, shift_sign)
+            .unwrap();
+
+        let bool_ty = Item::Vector(Elem::Bool, lanes).id(self);
+        let is_subnormal = self.u_less_than(bool_ty, None, mag, two).unwrap();
+        let subnormal = self.i_mul(lane_ty, None, mag, half).unwrap();
+        let normal = self
+            .shift_left_logical(lane_ty, None, mag, shift_mag)
+            .unwrap();
+        let normal = self.i_add(lane_ty, None, normal, half).unwrap();
+        let bits = self
+            .select(lane_ty, None, is_subnormal, subnormal, normal)
+            .unwrap();
+        let bits = self.bitwise_or(lane_ty, None, bits, sign).unwrap();
+
+        let f32_ty = Item::Vector(Elem::Float(32, None), lanes).id(self);
+        self.bitcast(f32_ty, None, bits).unwrap()
+    }
+
+    /// Pack lanes of `item` into `e2m1x2` bytes, rounding to nearest even and saturating.
+    fn pack_e2m1(&mut self, item: &Item, value: Word, out_item: &Item, out_id: Word) {
+        let lanes = item.vectorization();
+        assert!(
+            lanes >= 2 && lanes.is_multiple_of(2),
+            "Packing e2m1x2 requires an even number of lanes"
+        );
+        let words = lanes / 2;
+
+        let f32_item = Item::Vector(Elem::Float(32, None), lanes);
+        let f32_ty = f32_item.id(self);
+        let lane_item = Item::Vector(Elem::Int(32, false), lanes);
+        let lane_ty = lane_item.id(self);
+        let bool_ty = Item::Vector(Elem::Bool, lanes).id(self);
+
+        let value = item.cast_to(self, None, value, &f32_item);
+        let bits = self.bitcast(lane_ty, None, value).unwrap();
+
+        let shift_sign = lane_item.const_u32(self, 31);
+        let three = lane_item.const_u32(self, 3);
+        let abs_mask = lane_item.const_u32(self, 0x7FFF_FFFF);
+        let one = lane_item.const_u32(self, 1);
+        let zero = lane_item.const_u32(self, 0);
+
+        let sign = self
+            .shift_right_logical(lane_ty, None, bits, shift_sign)
+            .unwrap();
+        let sign = self.shift_left_logical(lane_ty, None, sign, three).unwrap();
+        let abs = self.bitwise_and(lane_ty, None, bits, abs_mask).unwrap();
+        let abs = self.bitcast(f32_ty, None, abs).unwrap();
+
+        let mut code = zero;
+        for (threshold, inclusive) in E2M1_THRESHOLDS {
+            let threshold = f32_item.constant(self, threshold.into());
+            let exceeds = match inclusive {
+                true => self.f_ord_greater_than_equal(bool_ty, None, abs, threshold),
+                false => self.f_ord_greater_than(bool_ty, None, abs, threshold),
+            }
+            .unwrap();
+            let step = self.select(lane_ty, None, exceeds, one, zero).unwrap();
+            code = self.i_add(lane_ty, None, code, step).unwrap();
+        }
+        let nibbles = self.bitwise_or(lane_ty, None, code, sign).unwrap();
+
+        let word_item = out_item.same_vectorization(Elem::Int(32, false));
+        let word_ty = word_item.id(self);
+        let (lo, hi) = match words {
+            1 => {
+                let lo = self.composite_extract(word_ty, None, nibbles, [0]).unwrap();
+                let hi = self.composite_extract(word_ty, None, nibbles, [1]).unwrap();
+                (lo, hi)
+            }
+            _ => {
+                let lo = (0..words).map(|i| 2 * i);
+                let lo = self
+                    .vector_shuffle(word_ty, None, nibbles, nibbles, lo)
+                    .unwrap();
+                let hi = (0..words).map(|i| 2 * i + 1);
+                let hi = self
+                    .vector_shuffle(word_ty, None, nibbles, nibbles, hi)
+                    .unwrap();
+                (lo, hi)
+            }
+        };
+        let four = word_item.const_u32(self, 4);
+        let hi = self.shift_left_logical(word_ty, None, hi, four).unwrap();
+        let word = self.bitwise_or(word_ty, None, lo, hi).unwrap();
+
+        let out_ty = out_item.id(self);
+        self.u_convert(out_ty, Some(out_id), word).unwrap();
+    }
+}
@@ -25,7 +25,7 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
                 // Adopting wgpu semantics
                 let scope_exec = self.const_u32(Scope::Workgroup as u32);
                 let scope_mem = self.const_u32(Scope::Device as u32);
-                let semantics = MemorySemantics::ACQUIRE_RELEASE | MemorySemantics::UNIFORM_MEMORY;
+                let semantics = MemorySemantics::ACQUIRE_RELEASE | T::buffer_memory_semantics();
                 let semantics = self.const_u32(semantics.bits());
                 self.control_barrier(scope_exec, scope_mem, semantics)
                     .unwrap();
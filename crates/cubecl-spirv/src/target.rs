@@ -1,14 +1,18 @@
 use cubecl_core::prelude::{KernelArg, Visibility};
 use rspirv::{
-    dr::Operand,
+    dr::{Builder, Operand},
     spirv::{
-        self, AddressingModel, Capability, Decoration, ExecutionMode, ExecutionModel, MemoryModel,
-        StorageClass, Word,
+        self, AddressingModel, BuiltIn, Capability, Decoration, ExecutionMode, ExecutionModel,
+        FunctionParameterAttribute, MemoryModel, MemorySemantics, Op, StorageClass, Word,
     },
 };
 use std::{fmt::Debug, iter};
 
-use crate::{SpirvCompiler, extensions::TargetExtensions, item::Item};
+use crate::{
+    SpirvCompiler,
+    extensions::TargetExtensions,
+    item::{Elem, Item},
+};
 
 pub trait SpirvTarget:
     TargetExtensions<Self> + Debug + Clone + Default + Send + Sync + 'static
@@ -28,6 +32,26 @@ pub trait SpirvTarget:
     ) -> Word;
     fn generate_info_binding(&mut self, b: &mut SpirvCompiler<Self>, offset: u32) -> Word;
     fn info_storage_class(b: &mut SpirvCompiler<Self>) -> StorageClass;
+    /// Get a pointer to the element at `index` of a buffer binding.
+    fn buffer_pointer(
+        b: &mut SpirvCompiler<Self>,
+        buffer: Word,
+        item: &Item,
+        index: Word,
+        in_bounds: bool,
+    ) -> Word;
+    /// Get a pointer to the element at `index` of the dynamic metadata, stored in `field` of the
+    /// info binding.
+    fn dynamic_meta_pointer(
+        b: &mut SpirvCompiler<Self>,
+        ptr_ty: Word,
+        field: Word,
+        index: Word,
+    ) -> Word;
+    /// Memory semantics of the memory backing the buffer bindings.
+    fn buffer_memory_semantics() -> MemorySemantics;
+    /// The item a builtin variable is declared with, for an `item` it's read as.
+    fn builtin_item(builtin: BuiltIn, item: Item) -> Item;
 
     fn set_kernel_name(&mut self, name: impl Into<String>);
 }
@@ -88,52 +112,7 @@ impl SpirvTarget for GLCompute {
         for cap in caps.iter() {
             b.capability(*cap);
         }
-
-        if caps.contains(&Capability::CooperativeMatrixKHR) {
-            b.extension("SPV_KHR_cooperative_matrix");
-        }
-
-        if caps.contains(&Capability::AtomicFloat16AddEXT) {
-            b.extension("SPV_EXT_shader_atomic_float16_add");
-        }
-
-        if caps.contains(&Capability::AtomicFloat32AddEXT)
-            | caps.contains(&Capability::AtomicFloat64AddEXT)
-        {
-            b.extension("SPV_EXT_shader_atomic_float_add");
-        }
-
-        if caps.contains(&Capability::AtomicFloat16MinMaxEXT)
-            | caps.contains(&Capability::AtomicFloat32MinMaxEXT)
-            | caps.contains(&Capability::AtomicFloat64MinMaxEXT)
-        {
-            b.extension("SPV_EXT_shader_atomic_float_min_max");
-        }
-
-        if caps.contains(&Capability::AtomicFloat16VectorNV) {
-            b.extension("SPV_NV_shader_atomic_fp16_vector");
-        }
-
-        if caps.contains(&Capability::BFloat16TypeKHR)
-            || caps.contains(&Capability::BFloat16CooperativeMatrixKHR)
-            || caps.contains(&Capability::BFloat16DotProductKHR)
-        {
-            b.extension("SPV_KHR_bfloat16");
-        }
-
-        if caps.contains(&Capability::Float8EXT)
-            || caps.contains(&Capability::Float8CooperativeMatrixEXT)
-        {
-            b.extension("SPV_EXT_float8");
-        }
-
-        if caps.contains(&Capability::FloatControls2) {
-            b.extension("SPV_KHR_float_controls2");
-        }
-
-        if b.debug_symbols {
-            b.extension("SPV_KHR_non_semantic_info");
-        }
+        declare_extensions(b, &caps);
 
         if version < (1, 5) {
             b.extension("SPV_KHR_vulkan_memory_model");
@@ -340,6 +319,288 @@ impl SpirvTarget for GLCompute {
         }
     }
 
+    fn buffer_pointer(
+        b: &mut SpirvCompiler<Self>,
+        buffer: Word,
+        item: &Item,
+        index: Word,
+        in_bounds: bool,
+    ) -> Word {
+        let access_chain = if in_bounds {
+            Builder::in_bounds_access_chain
+        } else {
+            Builder::access_chain
+        };
+        let ptr_ty = Item::Pointer(StorageClass::StorageBuffer, Box::new(item.clone())).id(b);
+        let zero = b.const_u32(0);
+        access_chain(b, ptr_ty, None, buffer, vec![zero, index]).unwrap()
+    }
+
+    fn dynamic_meta_pointer(
+        b: &mut SpirvCompiler<Self>,
+        ptr_ty: Word,
+        field: Word,
+        index: Word,
+    ) -> Word {
+        let info = b.state.info;
+        b.access_chain(ptr_ty, None, info, vec![field, index])
+            .unwrap()
+    }
+
+    fn buffer_memory_semantics() -> MemorySemantics {
+        MemorySemantics::UNIFORM_MEMORY
+    }
+
+    fn builtin_item(_builtin: BuiltIn, item: Item) -> Item {
+        item
+    }
+
+    fn set_kernel_name(&mut self, name: impl Into<String>) {
+        self.kernel_name = name.into();
+    }
+}
+
+/// Declare the extensions required by the capabilities used in the kernel.
+fn declare_extensions<T: SpirvTarget>(b: &mut SpirvCompiler<T>, caps: &[Capability]) {
+    if caps.contains(&Capability::CooperativeMatrixKHR) {
+        b.extension("SPV_KHR_cooperative_matrix");
+    }
+
+    if caps.contains(&Capability::AtomicFloat16AddEXT) {
+        b.extension("SPV_EXT_shader_atomic_float16_add");
+    }
+
+    if caps.contains(&Capability::AtomicFloat32AddEXT)
+        | caps.contains(&Capability::AtomicFloat64AddEXT)
+    {
+        b.extension("SPV_EXT_shader_atomic_float_add");
+    }
+
+    if caps.contains(&Capability::AtomicFloat16MinMaxEXT)
+        | caps.contains(&Capability::AtomicFloat32MinMaxEXT)
+        | caps.contains(&Capability::AtomicFloat64MinMaxEXT)
+    {
+        b.extension("SPV_EXT_shader_atomic_float_min_max");
+    }
+
+    if caps.contains(&Capability::AtomicFloat16VectorNV) {
+        b.extension("SPV_NV_shader_atomic_fp16_vector");
+    }
+
+    if caps.contains(&Capability::BFloat16TypeKHR)
+        || caps.contains(&Capability::BFloat16CooperativeMatrixKHR)
+        || caps.contains(&Capability::BFloat16DotProductKHR)
+    {
+        b.extension("SPV_KHR_bfloat16");
+    }
+
+    if caps.contains(&Capability::Float8EXT)
+        || caps.contains(&Capability::Float8CooperativeMatrixEXT)
+    {
+        b.extension("SPV_EXT_float8");
+    }
+
+    if caps.contains(&Capability::FloatControls2) {
+        b.extension("SPV_KHR_float_controls2");
+    }
+
+    if b.debug_symbols {
+        b.extension("SPV_KHR_non_semantic_info");
+    }
+}
+
+/// Targets `OpenCL` and Level Zero, using the `Kernel` execution model with physical addressing.
+///
+/// Bindings are passed as kernel arguments in binding order, with the same ABI as the `OpenCL`
+/// dialect of the C++ compiler. Buffers are `CrossWorkgroup` pointers to their elements. The info
+/// is passed by value, unless it's followed by dynamic metadata, in which case it's passed as a
+/// `CrossWorkgroup` pointer to the info struct with the dynamic metadata right after it.
+#[derive(Clone)]
+pub struct Kernel {
+    kernel_name: String,
+}
+
+impl Default for Kernel {
+    fn default() -> Self {
+        Self {
+            kernel_name: "main".into(),
+        }
+    }
+}
+
+impl Debug for Kernel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("kernel")
+    }
+}
+
+impl SpirvTarget for Kernel {
+    fn set_modes(
+        &mut self,
+        b: &mut SpirvCompiler<Self>,
+        main: Word,
+        builtins: Vec<Word>,
+        cube_dims: Vec<u32>,
+    ) {
+        let version = b.compilation_options.vulkan.max_spirv_version;
+
+        // Only `Input` and `Output` variables are part of the interface before SPIR-V 1.4
+        let interface: Vec<u32> = if version < (1, 4) {
+            builtins
+        } else {
+            builtins
+                .into_iter()
+                .chain(b.state.shared_arrays.values().map(|it| it.id))
+                .chain(b.state.shared.values().map(|it| it.id))
+                .collect()
+        };
+
+        b.capability(Capability::Kernel);
+        b.capability(Capability::Addresses);
+        // Builtins are `size_t`
+        b.capabilities.insert(Capability::Int64);
+
+        let caps: Vec<_> = b.capabilities.iter().copied().collect();
+        for cap in caps.iter() {
+            b.capability(*cap);
+        }
+        declare_extensions(b, &caps);
+
+        // Uniformity and relaxed precision are only hints, and require the `Shader` capability
+        b.module_mut().annotations.retain(|inst| {
+            inst.class.opcode != Op::Decorate
+                || !matches!(
+                    inst.operands[1],
+                    Operand::Decoration(Decoration::Uniform | Decoration::RelaxedPrecision)
+                )
+        });
+
+        b.memory_model(AddressingModel::Physical64, MemoryModel::OpenCL);
+        b.entry_point(ExecutionModel::Kernel, main, &self.kernel_name, interface);
+        b.execution_mode(main, spirv::ExecutionMode::LocalSize, cube_dims);
+    }
+
+    fn generate_binding(
+        &mut self,
+        b: &mut SpirvCompiler<Self>,
+        binding: KernelArg,
+        name: String,
+    ) -> Word {
+        let item = b.compile_type(binding.ty);
+        let ptr_ty = Item::Pointer(StorageClass::CrossWorkgroup, Box::new(item)).id(b);
+
+        let param = b.id();
+        b.debug_name(param, name);
+
+        if matches!(binding.visibility, Visibility::Read) {
+            b.decorate(
+                param,
+                Decoration::FuncParamAttr,
+                [Operand::FunctionParameterAttribute(
+                    FunctionParameterAttribute::NoWrite,
+                )],
+            );
+        }
+
+        b.state.parameters.push((param, ptr_ty));
+        param
+    }
+
+    /// Generate the info struct and parameter. Kernel structs can't have explicit offsets, but
+    /// every field is padded to a multiple of the largest alignment, so the natural layout
+    /// matches the offsets of the info.
+    fn generate_info_binding(&mut self, b: &mut SpirvCompiler<Self>, _index: u32) -> Word {
+        let address_type = b.addr_type;
+        let mut fields = Vec::new();
+
+        let scalars = b.info.scalars.clone();
+        for field in scalars.into_iter().chain(b.info.sized_meta) {
+            let scalar_ty = b.compile_storage_type(field.ty);
+            fields.push(Item::Array(
+                Box::new(Item::Scalar(scalar_ty)),
+                field.padded_size() as u32,
+            ));
+        }
+
+        // Only used as a base for pointer arithmetic, so the length doesn't matter
+        if b.info.has_dynamic_meta {
+            let scalar_ty = b.compile_storage_type(address_type);
+            fields.push(Item::Array(Box::new(Item::Scalar(scalar_ty)), 1));
+        }
+
+        let location = Self::info_storage_class(b);
+        let ptr_ty = Item::Pointer(location, Box::new(Item::Struct(fields))).id(b);
+
+        let param = b.id();
+        b.debug_name(param, "info");
+
+        let attribute = match location {
+            StorageClass::Function => FunctionParameterAttribute::ByVal,
+            _ => FunctionParameterAttribute::NoWrite,
+        };
+        b.decorate(
+            param,
+            Decoration::FuncParamAttr,
+            [Operand::FunctionParameterAttribute(attribute)],
+        );
+
+        b.state.parameters.push((param, ptr_ty));
+        param
+    }
+
+    fn info_storage_class(b: &mut SpirvCompiler<Self>) -> StorageClass {
+        if b.info.has_dynamic_meta {
+            StorageClass::CrossWorkgroup
+        } else {
+            StorageClass::Function
+        }
+    }
+
+    fn buffer_pointer(
+        b: &mut SpirvCompiler<Self>,
+        buffer: Word,
+        item: &Item,
+        index: Word,
+        in_bounds: bool,
+    ) -> Word {
+        let ptr_ty = Item::Pointer(StorageClass::CrossWorkgroup, Box::new(item.clone())).id(b);
+        if in_bounds {
+            b.in_bounds_ptr_access_chain(ptr_ty, None, buffer, index, [])
+                .unwrap()
+        } else {
+            b.ptr_access_chain(ptr_ty, None, buffer, index, []).unwrap()
+        }
+    }
+
+    fn dynamic_meta_pointer(
+        b: &mut SpirvCompiler<Self>,
+        ptr_ty: Word,
+        field: Word,
+        index: Word,
+    ) -> Word {
+        let info = b.state.info;
+        let zero = b.const_u32(0);
+        let start = b
+            .access_chain(ptr_ty, None, info, vec![field, zero])
+            .unwrap();
+        b.ptr_access_chain(ptr_ty, None, start, index, []).unwrap()
+    }
+
+    fn buffer_memory_semantics() -> MemorySemantics {
+        MemorySemantics::CROSS_WORKGROUP_MEMORY
+    }
+
+    fn builtin_item(builtin: BuiltIn, item: Item) -> Item {
+        match builtin {
+            BuiltIn::NumWorkgroups
+            | BuiltIn::WorkgroupId
+            | BuiltIn::LocalInvocationId
+            | BuiltIn::GlobalInvocationId
+            | BuiltIn::LocalInvocationIndex => item.same_vectorization(Elem::Int(64, false)),
+            _ => item,
+        }
+    }
+
     fn set_kernel_name(&mut self, name: impl Into<String>) {
         self.kernel_name = name.into();
     }
@@ -0,0 +1,418 @@
+use cubecl_common::e2m1x2;
+use cubecl_core::{
+    self as cubecl, Compiler, WgpuCompilationOptions, ir::AddressType, prelude::*,
+    server::ExecutionMode,
+};
+use rspirv::{
+    dr::{Instruction, Module, Operand, load_words},
+    spirv::{
+        AddressingModel, CLOp, Capability, Decoration, ExecutionModel, FunctionParameterAttribute,
+        MemoryModel, MemorySemantics, Op, StorageClass, Word,
+    },
+};
+
+use crate::{GLCompute, Kernel, SpirvCompiler, SpirvTarget};
+
+#[cube]
+fn double(input: &Array<f32>, output: &mut Array<f32>) {
+    output[ABSOLUTE_POS] = input[ABSOLUTE_POS] * 2.0;
+}
+
+#[cube]
+fn count(output: &mut Array<Atomic<u32>>) {
+    output[0].fetch_add(1u32);
+    sync_storage();
+}
+
+#[cube]
+fn math(input: &Array<f32>, output: &mut Array<f32>) {
+    let x = input[UNIT_POS as usize];
+    output[UNIT_POS as usize] = f32::sqrt(x) + f32::sin(x) + f32::max(x, 0.0);
+}
+
+#[cube]
+fn leading_zeros(input: &Array<u32>, output: &mut Array<u32>) {
+    output[UNIT_POS as usize] = u32::leading_zeros(input[UNIT_POS as usize]);
+}
+
+#[cube]
+fn scale(input: &Array<f32>, output: &mut Array<f32>, factor: f32) {
+    if ABSOLUTE_POS < input.len() {
+        output[ABSOLUTE_POS] = input[ABSOLUTE_POS] * factor;
+    }
+}
+
+#[cube]
+fn first_row(input: &Tensor<f32>, output: &mut Array<f32>) {
+    if UNIT_POS < input.shape(1) as u32 {
+        output[UNIT_POS as usize] = input[UNIT_POS as usize * input.stride(1)];
+    }
+}
+
+#[cube]
+fn fp4_round_trip(
+    input: &mut Array<Vector<f32, Const<4>>>,
+    output: &mut Array<Vector<e2m1x2, Const<2>>>,
+) {
+    output[ABSOLUTE_POS] = Vector::cast_from(input[ABSOLUTE_POS]);
+    input[ABSOLUTE_POS] = Vector::cast_from(output[ABSOLUTE_POS]);
+}
+
+/// Compile a kernel and parse the assembled module back, which validates its encoding.
+fn compile<Target: SpirvTarget>(
+    name: &str,
+    cube_dim: CubeDim,
+    expand: impl FnOnce(&mut KernelBuilder),
+) -> Module {
+    let mut builder = KernelBuilder::default();
+    AddressType::U32.register(&mut builder.scope);
+    expand(&mut builder);
+    let definition = builder.build(
+        KernelSettings::default()
+            .kernel_name(name)
+            .cube_dim(cube_dim),
+    );
+    let mut options = WgpuCompilationOptions::default();
+    options.vulkan.max_spirv_version = (1, 2);
+
+    let kernel = SpirvCompiler::<Target>::default()
+        .compile(
+            definition,
+            &options,
+            ExecutionMode::Unchecked,
+            AddressType::U32.unsigned_type(),
+        )
+        .unwrap();
+    load_words(&kernel.assembled_module).unwrap()
+}
+
+fn array_args<E: CubePrimitive>(
+    builder: &mut KernelBuilder,
+) -> (NativeExpand<Array<E>>, NativeExpand<Array<E>>) {
+    let ty = E::as_type(&builder.scope);
+    let input = builder.input_array(ty).into();
+    let output = builder.output_array(ty).into();
+    (input, output)
+}
+
+fn find_type(module: &Module, id: Word) -> &Instruction {
+    module
+        .types_global_values
+        .iter()
+        .find(|inst| inst.result_id == Some(id))
+        .unwrap()
+}
+
+/// Storage classes of the parameters of the entry point.
+fn parameter_classes(module: &Module) -> Vec<StorageClass> {
+    module.functions[0]
+        .parameters
+        .iter()
+        .map(|param| {
+            let ty = find_type(module, param.result_type.unwrap());
+            assert_eq!(ty.class.opcode, Op::TypePointer);
+            ty.operands[0].unwrap_storage_class()
+        })
+        .collect()
+}
+
+fn parameter_attributes(module: &Module, index: usize) -> Vec<FunctionParameterAttribute> {
+    let id = module.functions[0].parameters[index].result_id.unwrap();
+    module
+        .annotations
+        .iter()
+        .filter(|inst| inst.operands[0] == Operand::IdRef(id))
+        .filter(|inst| inst.operands[1] == Operand::Decoration(Decoration::FuncParamAttr))
+        .map(|inst| inst.operands[2].unwrap_function_parameter_attribute())
+        .collect()
+}
+
+fn capabilities(module: &Module) -> Vec<Capability> {
+    module
+        .capabilities
+        .iter()
+        .map(|inst| inst.operands[0].unwrap_capability())
+        .collect()
+}
+
+fn instructions(module: &Module) -> impl Iterator<Item = &Instruction> {
+    module
+        .functions
+        .iter()
+        .flat_map(|func| func.blocks.iter())
+        .flat_map(|block| block.instructions.iter())
+}
+
+fn ext_insts(module: &Module) -> Vec<u32> {
+    instructions(module)
+        .filter(|inst| inst.class.opcode == Op::ExtInst)
+        .map(|inst| inst.operands[1].unwrap_literal_ext_inst_integer())
+        .collect()
+}
+
+/// Check the module only uses what's allowed in the `OpenCL` environment.
+fn assert_opencl_module(module: &Module, name: &str) {
+    let memory_model = module.memory_model.as_ref().unwrap();
+    assert_eq!(
+        memory_model.operands,
+        vec![
+            Operand::AddressingModel(AddressingModel::Physical64),
+            Operand::MemoryModel(MemoryModel::OpenCL)
+        ]
+    );
+
+    let entry_point = &module.entry_points[0];
+    assert_eq!(
+        entry_point.operands[0],
+        Operand::ExecutionModel(ExecutionModel::Kernel)
+    );
+    assert_eq!(entry_point.operands[2], Operand::LiteralString(name.into()));
+
+    let capabilities = capabilities(module);
+    assert!(capabilities.contains(&Capability::Kernel));
+    assert!(capabilities.contains(&Capability::Addresses));
+    assert!(!capabilities.contains(&Capability::Shader));
+
+    for inst in module.annotations.iter() {
+        if let Operand::Decoration(decoration) = inst.operands[1] {
+            assert!(
+                !matches!(
+                    decoration,
+                    Decoration::Block
+                        | Decoration::DescriptorSet
+                        | Decoration::Binding
+                        | Decoration::Offset
+                        | Decoration::ArrayStride
+                        | Decoration::Uniform
+                ),
+                "Shader decoration {decoration:?} in kernel"
+            );
+        }
+    }
+
+    for inst in module.types_global_values.iter() {
+        assert_ne!(inst.class.opcode, Op::TypeRuntimeArray);
+        if matches!(inst.class.opcode, Op::TypePointer | Op::Variable) {
+            let class = inst.operands[0].unwrap_storage_class();
+            assert!(
+                !matches!(class, StorageClass::StorageBuffer | StorageClass::Uniform),
+                "Shader storage class {class:?} in kernel"
+            );
+        }
+    }
+
+    for import in module.ext_inst_imports.iter() {
+        assert_eq!(
+            import.operands[0],
+            Operand::LiteralString("OpenCL.std".into())
+        );
+    }
+}
+
+#[test]
+fn kernel_elementwise() {
+    let module = compile::<Kernel>("double_kernel", CubeDim::new_1d(64), |builder| {
+        let (input, output) = array_args::<f32>(builder);
+        double::expand(&mut builder.scope, input, output);
+    });
+
+    assert_opencl_module(&module, "double_kernel");
+    assert_eq!(
+        parameter_classes(&module),
+        vec![
+            StorageClass::CrossWorkgroup,
+            StorageClass::CrossWorkgroup,
+            StorageClass::Function
+        ]
+    );
+    assert_eq!(
+        parameter_attributes(&module, 0),
+        vec![FunctionParameterAttribute::NoWrite]
+    );
+    assert!(parameter_attributes(&module, 1).is_empty());
+
+    let execution_mode = &module.execution_modes[0];
+    assert_eq!(
+        execution_mode.operands[2..],
+        [
+            Operand::LiteralBit32(64),
+            Operand::LiteralBit32(1),
+            Operand::LiteralBit32(1)
+        ]
+    );
+
+    // Builtins are `size_t` vectors, and buffers are indexed with pointer arithmetic
+    let global_id = module
+        .annotations
+        .iter()
+        .find(|inst| inst.operands[1] == Operand::Decoration(Decoration::BuiltIn))
+        .map(|inst| inst.operands[0].unwrap_id_ref())
+        .unwrap();
+    let global_id = module
+        .types_global_values
+        .iter()
+        .find(|inst| inst.result_id == Some(global_id))
+        .unwrap();
+    let pointer = find_type(&module, global_id.result_type.unwrap());
+    let vector = find_type(&module, pointer.operands[1].unwrap_id_ref());
+    let elem = find_type(&module, vector.operands[0].unwrap_id_ref());
+    assert_eq!(elem.operands[0], Operand::LiteralBit32(64));
+
+    assert!(instructions(&module).any(|inst| inst.class.opcode == Op::PtrAccessChain));
+    assert!(!instructions(&module).any(|inst| inst.class.opcode == Op::AccessChain));
+}
+
+#[test]
+fn kernel_atomics() {
+    let module = compile::<Kernel>("count_kernel", CubeDim::new_1d(32), |builder| {
+        let ty = Atomic::<u32>::as_type(&builder.scope);
+        let output = builder.output_array(ty).into();
+        count::expand(&mut builder.scope, output);
+    });
+
+    assert_opencl_module(&module, "count_kernel");
+
+    let semantics = module
+        .types_global_values
+        .iter()
+        .filter(|inst| inst.class.opcode == Op::Constant)
+        .map(|inst| inst.operands[0].clone())
+        .collect::<Vec<_>>();
+    let cross_workgroup =
+        MemorySemantics::CROSS_WORKGROUP_MEMORY | MemorySemantics::ACQUIRE_RELEASE;
+    assert!(semantics.contains(&Operand::LiteralBit32(cross_workgroup.bits())));
+    let uniform = MemorySemantics::UNIFORM_MEMORY | MemorySemantics::ACQUIRE_RELEASE;
+    assert!(!semantics.contains(&Operand::LiteralBit32(uniform.bits())));
+}
+
+#[test]
+fn kernel_math() {
+    let module = compile::<Kernel>("math_kernel", CubeDim::new_1d(32), |builder| {
+        let (input, output) = array_args::<f32>(builder);
+        math::expand(&mut builder.scope, input, output);
+    });
+
+    assert_opencl_module(&module, "math_kernel");
+    assert_eq!(module.ext_inst_imports.len(), 1);
+
+    let ext_insts = ext_insts(&module);
+    assert!(ext_insts.contains(&(CLOp::sqrt as u32)));
+    assert!(ext_insts.contains(&(CLOp::sin as u32)));
+    assert!(ext_insts.contains(&(CLOp::fmax as u32)));
+}
+
+#[test]
+fn kernel_bit_scan() {
+    let module = compile::<Kernel>("clz_kernel", CubeDim::new_1d(32), |builder| {
+        let (input, output) = array_args::<u32>(builder);
+        leading_zeros::expand(&mut builder.scope, input, output);
+    });
+
+    assert_opencl_module(&module, "clz_kernel");
+    assert_eq!(ext_insts(&module), vec![CLOp::clz as u32]);
+}
+
+#[test]
+fn kernel_info_by_value() {
+    let module = compile::<Kernel>("scale_kernel", CubeDim::new_1d(64), |builder| {
+        let (input, output) = array_args::<f32>(builder);
+        let factor = builder
+            .scalar(f32::as_type_native_unchecked().storage_type())
+            .into();
+        scale::expand(&mut builder.scope, input, output, factor);
+    });
+
+    assert_opencl_module(&module, "scale_kernel");
+    assert_eq!(
+        parameter_classes(&module),
+        vec![
+            StorageClass::CrossWorkgroup,
+            StorageClass::CrossWorkgroup,
+            StorageClass::Function
+        ]
+    );
+    assert_eq!(
+        parameter_attributes(&module, 2),
+        vec![FunctionParameterAttribute::ByVal]
+    );
+}
+
+#[test]
+fn kernel_info_with_dynamic_meta() {
+    let module = compile::<Kernel>("first_row_kernel", CubeDim::new_1d(64), |builder| {
+        let ty = f32::as_type(&builder.scope);
+        let input = builder.input_tensor(ty).into();
+        let output = builder.output_array(ty).into();
+        first_row::expand(&mut builder.scope, input, output);
+    });
+
+    assert_opencl_module(&module, "first_row_kernel");
+    assert_eq!(
+        parameter_classes(&module),
+        vec![
+            StorageClass::CrossWorkgroup,
+            StorageClass::CrossWorkgroup,
+            StorageClass::CrossWorkgroup
+        ]
+    );
+    assert_eq!(
+        parameter_attributes(&module, 2),
+        vec![FunctionParameterAttribute::NoWrite]
+    );
+
+    // The dynamic metadata is indexed from the start of its field
+    let info = module.functions[0].parameters[2].result_id.unwrap();
+    let fields = instructions(&module)
+        .filter(|inst| {
+            inst.class.opcode == Op::AccessChain && inst.operands[0] == Operand::IdRef(info)
+        })
+        .map(|inst| Operand::IdRef(inst.result_id.unwrap()))
+        .collect::<Vec<_>>();
+    assert!(instructions(&module).any(|inst| {
+        inst.class.opcode == Op::PtrAccessChain && fields.contains(&inst.operands[0])
+    }));
+}
+
+#[test]
+fn gl_compute_is_unchanged() {
+    let module = compile::<GLCompute>("double_kernel", CubeDim::new_1d(64), |builder| {
+        let (input, output) = array_args::<f32>(builder);
+        double::expand(&mut builder.scope, input, output);
+    });
+
+    let memory_model = module.memory_model.as_ref().unwrap();
+    assert_eq!(
+        memory_model.operands,
+        vec![
+            Operand::AddressingModel(AddressingModel::Logical),
+            Operand::MemoryModel(MemoryModel::Vulkan)
+        ]
+    );
+    assert_eq!(
+        module.entry_points[0].operands[0],
+        Operand::ExecutionModel(ExecutionModel::GLCompute)
+    );
+    assert!(capabilities(&module).contains(&Capability::Shader));
+    assert!(module.functions[0].parameters.is_empty());
+}
+
+#[test]
+fn packed_fp4_is_lowered_to_bytes() {
+    let module = compile::<GLCompute>("fp4_kernel", CubeDim::new_1d(64), |builder| {
+        let input = Vector::<f32, Const<4>>::as_type(&builder.scope);
+        let output = Vector::<e2m1x2, Const<2>>::as_type(&builder.scope);
+        let input = builder.output_array(input).into();
+        let output = builder.output_array(output).into();
+        fp4_round_trip::expand(&mut builder.scope, input, output);
+    });
+
+    assert!(capabilities(&module).contains(&Capability::Int8));
+    let ops = instructions(&module)
+        .map(|inst| inst.class.opcode)
+        .collect::<Vec<_>>();
+    // Packing narrows the `u32` words to bytes, unpacking widens them back
+    assert!(ops.contains(&Op::UConvert));
+    assert!(ops.contains(&Op::FOrdGreaterThanEqual));
+    assert!(ops.contains(&Op::Bitcast));
+    assert!(!ops.contains(&Op::FConvert));
+}
@@ -300,7 +300,10 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
     pub fn compile_variable(&mut self, variable: ir::Variable) -> Variable {
         let item = variable.ty;
         match variable.kind {
-            ir::VariableKind::Constant(value) => {
+            ir::VariableKind::Constant(mut value) => {
+                if let ir::StorageType::Packed(..) = item.storage_type() {
+                    value = ir::ConstantValue::UInt(value.packed_bits(item.storage_type()));
+                }
                 let item = self.compile_type(item);
                 let const_val = (value, item.clone()).into();
 
@@ -442,10 +445,7 @@ impl<T: SpirvTarget> SpirvCompiler<T> {
         let index_id = self.read(index);
         match variable {
             Variable::GlobalInputArray(id, item, _) | Variable::GlobalOutputArray(id, item, _) => {
-                let ptr_ty =
-                    Item::Pointer(StorageClass::StorageBuffer, Box::new(item.clone())).id(self);
-                let zero = self.const_u32(0);
-                let id = access_chain(self, ptr_ty, None, *id, vec![zero, index_id]).unwrap();
+                let id = T::buffer_pointer(self, *id, item, index_id, unchecked);
 
                 IndexedVariable::Pointer(id, item.clone())
             }
@@ -19,5 +19,9 @@ pub mod tensor;
 /// Event utilities.
 pub mod event;
 
+pub mod random;
+pub mod reduce;
+pub mod scan;
+
 #[cfg(feature = "export_tests")]
 pub mod tests;
@@ -27,6 +27,43 @@ pub fn dequantize_aligned<Q: Scalar, S: CubePrimitive, F: Numeric, NQ: Size, NF:
 pub fn unpack_cast_u32<F: Numeric, NQ: Size, NF: Size>(
     value: Vector<u32, NQ>,
     #[comptime] scheme: QuantScheme,
+) -> Vector<F, NF> {
+    match scheme.value {
+        QuantValue::E2M1 => unpack_cast_e2m1::<F, NQ, NF>(value),
+        _ => unpack_cast_masked::<F, NQ, NF>(value, scheme),
+    }
+}
+
+/// Unpack `e2m1` values by reinterpreting each `u32` as four `e2m1x2`, so the conversion doesn't
+/// go through `u8`, which isn't available on every backend.
+#[cube]
+fn unpack_cast_e2m1<F: Numeric, NQ: Size, NF: Size>(value: Vector<u32, NQ>) -> Vector<F, NF> {
+    let num_packed = 4usize;
+    let num_quants = 8usize;
+    let size!(NP) = num_packed;
+    let size!(NU) = num_quants;
+
+    let mut out = Vector::<F, NF>::empty();
+
+    #[unroll]
+    for vector_idx in 0..value.size() {
+        let packed = Vector::<e2m1x2, NP>::reinterpret(value[vector_idx]);
+        let float_values = Vector::<F, NU>::cast_from(packed);
+        let out_offset = vector_idx * num_quants;
+
+        #[unroll]
+        for quant_idx in 0..num_quants {
+            out[out_offset + quant_idx] = float_values[quant_idx];
+        }
+    }
+
+    out
+}
+
+#[cube]
+fn unpack_cast_masked<F: Numeric, NQ: Size, NF: Size>(
+    value: Vector<u32, NQ>,
+    #[comptime] scheme: QuantScheme,
 ) -> Vector<F, NF> {
     let num_quants = scheme.num_quants();
     let native_packing = scheme.native_packing();
@@ -0,0 +1,94 @@
+use core::f32::consts::TAU;
+use cubecl::prelude::*;
+use cubecl_core as cubecl;
+
+use super::{CounterRng, CounterRngExpand};
+
+/// `2^-24`, the spacing between the uniform floats generated from the top 24 bits of a `u32`.
+pub(crate) const UNIFORM_SCALE: f32 = 1.0 / 16_777_216.0;
+
+/// Returns `N` random `u32`. Lane `i` is the word `i % 4` of the block `i / 4`.
+///
+/// The vector size must be 1, 2 or a multiple of 4.
+#[cube]
+pub fn random_bits<R: CounterRng, N: Size>(rng: &mut R) -> Vector<u32, N> {
+    let mut bits = Vector::<u32, N>::empty();
+    let size = bits.size();
+    let (num_blocks, per_block) = comptime!(blocks(size));
+
+    #[unroll]
+    for block_idx in 0..num_blocks {
+        let block = rng.next_block();
+        #[unroll]
+        for i in 0..per_block {
+            bits[block_idx * per_block + i] = block[i];
+        }
+    }
+
+    bits
+}
+
+/// Returns `N` floats uniformly distributed in `[0, 1)`.
+///
+/// The floats are generated from the top 24 bits of [`random_bits`] in `f32`, so every value is a
+/// multiple of `2^-24`. Types with a lower precision than `f32` may round them up to `1`.
+#[cube]
+pub fn uniform<R: CounterRng, F: Float, N: Size>(rng: &mut R) -> Vector<F, N> {
+    let bits = random_bits::<R, N>(rng);
+    let value = Vector::<f32, N>::cast_from(bits >> Vector::new(8u32));
+    Vector::<F, N>::cast_from(value * Vector::new(UNIFORM_SCALE))
+}
+
+/// Returns `N` normally distributed floats with the given mean and standard deviation, using the
+/// Box-Muller transform.
+///
+/// The words `2k` and `2k + 1` of each block produce the lanes `2k` and `2k + 1`, so a
+/// vector of size 1 still consumes a whole block. The vector size must be 1, 2 or a multiple of 4.
+#[cube]
+pub fn normal<R: CounterRng, F: Float, N: Size>(rng: &mut R, mean: F, std: F) -> Vector<F, N> {
+    let mut out = Vector::<F, N>::empty();
+    let size = out.size();
+    let (num_blocks, per_block) = comptime!(blocks(size));
+    let num_pairs = comptime!(per_block.div_ceil(2));
+
+    #[unroll]
+    for block_idx in 0..num_blocks {
+        let block = rng.next_block();
+        #[unroll]
+        for pair in 0..num_pairs {
+            // Shifted to `(0, 1]` to avoid the logarithm of zero.
+            let u1 = (f32::cast_from(block[pair * 2] >> 8u32) + f32::new(1.0)) * UNIFORM_SCALE;
+            let u2 = f32::cast_from(block[pair * 2 + 1] >> 8u32) * UNIFORM_SCALE;
+            let radius = f32::sqrt(f32::new(-2.0) * f32::ln(u1));
+            let theta = TAU * u2;
+
+            let lane = block_idx * per_block + pair * 2;
+            out[lane] = F::cast_from(radius * f32::cos(theta)) * std + mean;
+            if comptime!(per_block > 1) {
+                out[lane + 1] = F::cast_from(radius * f32::sin(theta)) * std + mean;
+            }
+        }
+    }
+
+    out
+}
+
+/// Returns `N` floats that are `1` with probability `p` and `0` otherwise.
+///
+/// Lane `i` is `1` when lane `i` of [`uniform`] is lower than `p`.
+#[cube]
+pub fn bernoulli<R: CounterRng, F: Float, N: Size>(rng: &mut R, p: F) -> Vector<F, N> {
+    let value = uniform::<R, f32, N>(rng);
+    let keep = value.less_than(Vector::new(f32::cast_from(p)));
+    Vector::<F, N>::cast_from(keep)
+}
+
+/// The number of blocks used for a vector of the given size, and the number of words used in
+/// each block.
+pub(crate) fn blocks(size: usize) -> (usize, usize) {
+    assert!(
+        size <= 2 || size.is_multiple_of(4),
+        "Random vectors must have a size of 1, 2 or a multiple of 4, got {size}"
+    );
+    (size.div_ceil(4), size.min(4))
+}
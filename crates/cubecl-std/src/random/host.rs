@@ -0,0 +1,259 @@
+//! Host implementation of the counter-based generators and distributions, producing the same
+//! numbers as the kernel functions.
+//!
+//! The bits and the uniform and bernoulli distributions are bit-exact. The normal distribution
+//! depends on the precision of `ln`, `sqrt`, `sin` and `cos` on each backend, so it's only
+//! accurate to a few ulps.
+
+use alloc::vec::Vec;
+use core::f32::consts::TAU;
+
+use super::{
+    distribution::{UNIFORM_SCALE, blocks},
+    philox::{PHILOX_M0, PHILOX_M1, PHILOX_ROUNDS, PHILOX_W0, PHILOX_W1},
+    threefry::{THREEFRY_PARITY, THREEFRY_ROTATIONS},
+};
+
+/// A counter-based random number generator on the host.
+pub trait CounterRng {
+    /// Returns the random bits of the current counter, then increments the offset.
+    fn next_block(&mut self) -> [u32; 4];
+}
+
+/// The counter shared by both generators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Counter {
+    offset: u64,
+    subsequence: u32,
+}
+
+impl Counter {
+    fn next(&mut self) -> [u32; 4] {
+        let counter = [
+            self.offset as u32,
+            (self.offset >> 32) as u32,
+            self.subsequence,
+            0,
+        ];
+        self.offset = self.offset.wrapping_add(1);
+        counter
+    }
+}
+
+/// Host version of [`Philox`](super::Philox).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Philox {
+    seed: u32,
+    counter: Counter,
+}
+
+impl Philox {
+    /// Create the generator of the unit at the given absolute position.
+    pub fn new(seed: u32, offset: u32, subsequence: u32) -> Self {
+        Self {
+            seed,
+            counter: Counter {
+                offset: offset as u64,
+                subsequence,
+            },
+        }
+    }
+}
+
+impl CounterRng for Philox {
+    fn next_block(&mut self) -> [u32; 4] {
+        philox4x32_10(self.counter.next(), [self.seed, 0])
+    }
+}
+
+/// Host version of [`Threefry`](super::Threefry).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Threefry {
+    seed: u32,
+    counter: Counter,
+}
+
+impl Threefry {
+    /// Create the generator of the unit at the given absolute position.
+    pub fn new(seed: u32, offset: u32, subsequence: u32) -> Self {
+        Self {
+            seed,
+            counter: Counter {
+                offset: offset as u64,
+                subsequence,
+            },
+        }
+    }
+}
+
+impl CounterRng for Threefry {
+    fn next_block(&mut self) -> [u32; 4] {
+        threefry4x32_20(self.counter.next(), [self.seed, 0, 0, 0])
+    }
+}
+
+/// Host version of [`philox4x32_10`](super::philox4x32_10).
+pub fn philox4x32_10(counter: [u32; 4], key: [u32; 2]) -> [u32; 4] {
+    let [mut c0, mut c1, mut c2, mut c3] = counter;
+    let [mut k0, mut k1] = key;
+
+    for _ in 0..PHILOX_ROUNDS {
+        let product0 = PHILOX_M0 as u64 * c0 as u64;
+        let product1 = PHILOX_M1 as u64 * c2 as u64;
+        let (hi0, lo0) = ((product0 >> 32) as u32, product0 as u32);
+        let (hi1, lo1) = ((product1 >> 32) as u32, product1 as u32);
+
+        [c0, c1, c2, c3] = [hi1 ^ c1 ^ k0, lo1, hi0 ^ c3 ^ k1, lo0];
+
+        k0 = k0.wrapping_add(PHILOX_W0);
+        k1 = k1.wrapping_add(PHILOX_W1);
+    }
+
+    [c0, c1, c2, c3]
+}
+
+/// Host version of [`threefry4x32_20`](super::threefry4x32_20).
+pub fn threefry4x32_20(counter: [u32; 4], key: [u32; 4]) -> [u32; 4] {
+    let parity = key.iter().fold(THREEFRY_PARITY, |acc, k| acc ^ k);
+    let schedule = [key[0], key[1], key[2], key[3], parity];
+
+    let mut x = core::array::from_fn::<u32, 4, _>(|i| counter[i].wrapping_add(key[i]));
+
+    for round in 0..20 {
+        let [r0, r1] = THREEFRY_ROTATIONS[round % 8];
+        let (a, b, c, d) = if round % 2 == 0 {
+            (0, 1, 2, 3)
+        } else {
+            (0, 3, 2, 1)
+        };
+        x[a] = x[a].wrapping_add(x[b]);
+        x[b] = x[b].rotate_left(r0) ^ x[a];
+        x[c] = x[c].wrapping_add(x[d]);
+        x[d] = x[d].rotate_left(r1) ^ x[c];
+
+        if round % 4 == 3 {
+            let injection = round / 4 + 1;
+            for (i, x) in x.iter_mut().enumerate() {
+                *x = x.wrapping_add(schedule[(injection + i) % 5]);
+            }
+            x[3] = x[3].wrapping_add(injection as u32);
+        }
+    }
+
+    x
+}
+
+/// Host version of [`random_bits`](super::random_bits) for a vector of the given size.
+pub fn random_bits<R: CounterRng>(rng: &mut R, size: usize) -> Vec<u32> {
+    let (num_blocks, per_block) = blocks(size);
+    (0..num_blocks)
+        .flat_map(|_| rng.next_block().into_iter().take(per_block))
+        .collect()
+}
+
+/// Host version of [`uniform`](super::uniform) for a vector of the given size.
+pub fn uniform<R: CounterRng>(rng: &mut R, size: usize) -> Vec<f32> {
+    random_bits(rng, size)
+        .into_iter()
+        .map(|bits| (bits >> 8) as f32 * UNIFORM_SCALE)
+        .collect()
+}
+
+/// Host version of [`normal`](super::normal) for a vector of the given size.
+pub fn normal<R: CounterRng>(rng: &mut R, size: usize, mean: f32, std: f32) -> Vec<f32> {
+    let (num_blocks, per_block) = blocks(size);
+    let mut out = Vec::with_capacity(size);
+
+    for _ in 0..num_blocks {
+        let block = rng.next_block();
+        for pair in block.chunks(2).take(per_block.div_ceil(2)) {
+            let u1 = ((pair[0] >> 8) as f32 + 1.0) * UNIFORM_SCALE;
+            let u2 = (pair[1] >> 8) as f32 * UNIFORM_SCALE;
+            let radius = (-2.0 * u1.ln()).sqrt();
+            let theta = TAU * u2;
+
+            out.push(radius * theta.cos() * std + mean);
+            if per_block > 1 {
+                out.push(radius * theta.sin() * std + mean);
+            }
+        }
+    }
+
+    out
+}
+
+/// Host version of [`bernoulli`](super::bernoulli) for a vector of the given size.
+pub fn bernoulli<R: CounterRng>(rng: &mut R, size: usize, p: f32) -> Vec<f32> {
+    uniform(rng, size)
+        .into_iter()
+        .map(|value| if value < p { 1.0 } else { 0.0 })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Known-answer tests from Random123.
+    const PI_COUNTER: [u32; 4] = [0x243f6a88, 0x85a308d3, 0x13198a2e, 0x03707344];
+
+    #[test]
+    fn philox4x32_10_known_answers() {
+        assert_eq!(
+            philox4x32_10([0; 4], [0; 2]),
+            [0x6627e8d5, 0xe169c58d, 0xbc57ac4c, 0x9b00dbd8]
+        );
+        assert_eq!(
+            philox4x32_10([u32::MAX; 4], [u32::MAX; 2]),
+            [0x408f276d, 0x41c83b0e, 0xa20bc7c6, 0x6d5451fd]
+        );
+        assert_eq!(
+            philox4x32_10(PI_COUNTER, [0xa4093822, 0x299f31d0]),
+            [0xd16cfe09, 0x94fdcceb, 0x5001e420, 0x24126ea1]
+        );
+    }
+
+    #[test]
+    fn threefry4x32_20_known_answers() {
+        assert_eq!(
+            threefry4x32_20([0; 4], [0; 4]),
+            [0x9c6ca96a, 0xe17eae66, 0xfc10ecd4, 0x5256a7d8]
+        );
+        assert_eq!(
+            threefry4x32_20([u32::MAX; 4], [u32::MAX; 4]),
+            [0x2a881696, 0x57012287, 0xf6c7446e, 0xa16a6732]
+        );
+        assert_eq!(
+            threefry4x32_20(PI_COUNTER, [0xa4093822, 0x299f31d0, 0x082efa98, 0xec4e6c89]),
+            [0x59cd1dbb, 0xb8879579, 0x86b5d00c, 0xac8b6d84]
+        );
+    }
+
+    #[test]
+    fn offset_carries_into_high_word() {
+        let mut counter = Counter {
+            offset: u32::MAX as u64,
+            subsequence: 7,
+        };
+        assert_eq!(counter.next(), [u32::MAX, 0, 7, 0]);
+        assert_eq!(counter.next(), [0, 1, 7, 0]);
+    }
+
+    #[test]
+    fn distributions_are_in_range() {
+        let mut rng = Philox::new(42, 0, 0);
+        let uniform = uniform(&mut rng, 1024);
+        assert!(uniform.iter().all(|value| (0.0..1.0).contains(value)));
+
+        let normal = normal(&mut rng, 1024, 0.0, 1.0);
+        let mean = normal.iter().sum::<f32>() / normal.len() as f32;
+        assert!(mean.abs() < 0.1, "Mean should be close to 0, got {mean}");
+
+        let bernoulli = bernoulli(&mut rng, 1024, 0.25);
+        let ratio = bernoulli.iter().sum::<f32>() / bernoulli.len() as f32;
+        assert!(
+            (ratio - 0.25).abs() < 0.05,
+            "Ratio should be close to 0.25, got {ratio}"
+        );
+    }
+}
@@ -0,0 +1,61 @@
+//! Counter-based random number generation for `CubeCL` kernels.
+//!
+//! Counter-based generators (Salmon et al., *Parallel Random Numbers: As Easy as 1, 2, 3*) are
+//! stateless bijections from a counter and a key to random bits, so every unit can draw from its
+//! own stream without any state stored in global memory. Two generators are provided:
+//!
+//! - [`Philox`]: Philox4x32-10, based on multiplications. Same as cuRAND and `PyTorch`.
+//! - [`Threefry`]: Threefry4x32-20, based on additions, rotations and xors. Useful when
+//!   multiplications are slow.
+//!
+//! A generator is keyed by a seed, and its counter is made of the offset (in blocks of four `u32`)
+//! and the subsequence, which defaults to [`ABSOLUTE_POS`](cubecl::prelude::ABSOLUTE_POS). The
+//! same seed, offset and position always produce the same numbers, and the host can advance the
+//! offset between launches to get fresh numbers. Each call to a distribution consumes
+//! `ceil(N / 4)` blocks.
+//!
+//! The [`host`] module implements the same generators and distributions on the CPU, to check
+//! that kernels are reproducible across backends.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! #[cube(launch)]
+//! fn dropout<F: Float, N: Size>(
+//!     input: &Array<Vector<F, N>>,
+//!     output: &mut Array<Vector<F, N>>,
+//!     seed: u32,
+//!     offset: u32,
+//! ) {
+//!     let mut rng = Philox::new(seed, offset);
+//!     let keep = bernoulli::<Philox, F, N>(&mut rng, F::new(0.9));
+//!     output[ABSOLUTE_POS] = input[ABSOLUTE_POS] * keep / Vector::new(F::new(0.9));
+//! }
+//! ```
+
+mod distribution;
+mod philox;
+mod threefry;
+
+/// Host reference implementation.
+pub mod host;
+
+pub use distribution::*;
+pub use philox::*;
+pub use threefry::*;
+
+use cubecl::prelude::*;
+use cubecl_core as cubecl;
+
+/// A block of random bits produced by a counter-based generator.
+pub type RandomBlock = Vector<u32, Const<4>>;
+
+/// A counter-based random number generator.
+#[cube]
+pub trait CounterRng: CubeType + Send + Sync + 'static {
+    /// Create a generator for the given subsequence.
+    fn with_subsequence(seed: u32, offset: u32, subsequence: u32) -> Self;
+
+    /// Returns the random bits of the current counter, then increments the offset.
+    fn next_block(&mut self) -> RandomBlock;
+}
@@ -0,0 +1,89 @@
+use cubecl::prelude::*;
+use cubecl_core as cubecl;
+
+use super::{CounterRng, CounterRngExpand, RandomBlock};
+
+pub(crate) const PHILOX_M0: u32 = 0xD251_1F53;
+pub(crate) const PHILOX_M1: u32 = 0xCD9E_8D57;
+pub(crate) const PHILOX_W0: u32 = 0x9E37_79B9;
+pub(crate) const PHILOX_W1: u32 = 0xBB67_AE85;
+pub(crate) const PHILOX_ROUNDS: u32 = 10;
+
+/// The Philox4x32-10 generator.
+///
+/// The key is `(seed, 0)` and the counter is `(offset_lo, offset_hi, subsequence, 0)`.
+#[derive(CubeType, Clone, Copy)]
+pub struct Philox {
+    seed: u32,
+    offset_lo: u32,
+    offset_hi: u32,
+    subsequence: u32,
+}
+
+#[cube]
+impl Philox {
+    /// Create a generator with a subsequence per unit.
+    pub fn new(seed: u32, offset: u32) -> Philox {
+        Philox::with_subsequence(seed, offset, ABSOLUTE_POS as u32)
+    }
+}
+
+#[cube]
+impl CounterRng for Philox {
+    fn with_subsequence(seed: u32, offset: u32, subsequence: u32) -> Self {
+        Philox {
+            seed,
+            offset_lo: offset,
+            offset_hi: 0u32,
+            subsequence,
+        }
+    }
+
+    fn next_block(&mut self) -> RandomBlock {
+        let block = philox4x32_10(
+            self.offset_lo,
+            self.offset_hi,
+            self.subsequence,
+            0u32,
+            self.seed,
+            0u32,
+        );
+        self.offset_lo += 1u32;
+        self.offset_hi += (self.offset_lo == 0u32) as u32;
+        block
+    }
+}
+
+/// Apply the ten rounds of Philox4x32 to a counter.
+#[cube]
+pub fn philox4x32_10(c0: u32, c1: u32, c2: u32, c3: u32, k0: u32, k1: u32) -> RandomBlock {
+    let mut c0 = c0;
+    let mut c1 = c1;
+    let mut c2 = c2;
+    let mut c3 = c3;
+    let mut k0 = k0;
+    let mut k1 = k1;
+
+    #[unroll]
+    for _ in 0..PHILOX_ROUNDS {
+        let hi0 = u32::mul_hi(PHILOX_M0, c0);
+        let lo0 = PHILOX_M0 * c0;
+        let hi1 = u32::mul_hi(PHILOX_M1, c2);
+        let lo1 = PHILOX_M1 * c2;
+
+        c0 = hi1 ^ c1 ^ k0;
+        c1 = lo1;
+        c2 = hi0 ^ c3 ^ k1;
+        c3 = lo0;
+
+        k0 += PHILOX_W0;
+        k1 += PHILOX_W1;
+    }
+
+    let mut block = RandomBlock::empty();
+    block[0] = c0;
+    block[1] = c1;
+    block[2] = c2;
+    block[3] = c3;
+    block
+}
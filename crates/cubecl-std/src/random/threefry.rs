@@ -0,0 +1,127 @@
+use cubecl::prelude::*;
+use cubecl_core as cubecl;
+
+use super::{CounterRng, CounterRngExpand, RandomBlock};
+
+pub(crate) const THREEFRY_PARITY: u32 = 0x1BD1_1BDA;
+/// The rotations of the two mixes of each round, repeating every eight rounds.
+pub(crate) const THREEFRY_ROTATIONS: [[u32; 2]; 8] = [
+    [10, 26],
+    [11, 21],
+    [13, 27],
+    [23, 5],
+    [6, 20],
+    [17, 11],
+    [25, 10],
+    [18, 20],
+];
+
+/// The Threefry4x32-20 generator.
+///
+/// The key is `(seed, 0, 0, 0)` and the counter is `(offset_lo, offset_hi, subsequence, 0)`.
+#[derive(CubeType, Clone, Copy)]
+pub struct Threefry {
+    seed: u32,
+    offset_lo: u32,
+    offset_hi: u32,
+    subsequence: u32,
+}
+
+#[cube]
+impl Threefry {
+    /// Create a generator with a subsequence per unit.
+    pub fn new(seed: u32, offset: u32) -> Threefry {
+        Threefry::with_subsequence(seed, offset, ABSOLUTE_POS as u32)
+    }
+}
+
+#[cube]
+impl CounterRng for Threefry {
+    fn with_subsequence(seed: u32, offset: u32, subsequence: u32) -> Self {
+        Threefry {
+            seed,
+            offset_lo: offset,
+            offset_hi: 0u32,
+            subsequence,
+        }
+    }
+
+    fn next_block(&mut self) -> RandomBlock {
+        let block = threefry4x32_20(
+            self.offset_lo,
+            self.offset_hi,
+            self.subsequence,
+            0u32,
+            self.seed,
+            0u32,
+            0u32,
+            0u32,
+        );
+        self.offset_lo += 1u32;
+        self.offset_hi += (self.offset_lo == 0u32) as u32;
+        block
+    }
+}
+
+/// Apply the twenty rounds of Threefry4x32 to a counter, injecting the key every four rounds.
+#[cube]
+#[allow(clippy::too_many_arguments)]
+pub fn threefry4x32_20(
+    c0: u32,
+    c1: u32,
+    c2: u32,
+    c3: u32,
+    k0: u32,
+    k1: u32,
+    k2: u32,
+    k3: u32,
+) -> RandomBlock {
+    let k4 = k0 ^ k1 ^ k2 ^ k3 ^ THREEFRY_PARITY;
+
+    let (x0, x1, x2, x3) = (c0 + k0, c1 + k1, c2 + k2, c3 + k3);
+
+    let (x0, x1, x2, x3) = threefry_rounds(x0, x1, x2, x3, 0usize);
+    let (x0, x1, x2, x3) = (x0 + k1, x1 + k2, x2 + k3, x3 + k4 + 1u32);
+    let (x0, x1, x2, x3) = threefry_rounds(x0, x1, x2, x3, 4usize);
+    let (x0, x1, x2, x3) = (x0 + k2, x1 + k3, x2 + k4, x3 + k0 + 2u32);
+    let (x0, x1, x2, x3) = threefry_rounds(x0, x1, x2, x3, 0usize);
+    let (x0, x1, x2, x3) = (x0 + k3, x1 + k4, x2 + k0, x3 + k1 + 3u32);
+    let (x0, x1, x2, x3) = threefry_rounds(x0, x1, x2, x3, 4usize);
+    let (x0, x1, x2, x3) = (x0 + k4, x1 + k0, x2 + k1, x3 + k2 + 4u32);
+    let (x0, x1, x2, x3) = threefry_rounds(x0, x1, x2, x3, 0usize);
+    let (x0, x1, x2, x3) = (x0 + k0, x1 + k1, x2 + k2, x3 + k3 + 5u32);
+
+    let mut block = RandomBlock::empty();
+    block[0] = x0;
+    block[1] = x1;
+    block[2] = x2;
+    block[3] = x3;
+    block
+}
+
+/// Four rounds of Threefry4x32, using the rotations starting at `first`.
+#[cube]
+fn threefry_rounds(
+    x0: u32,
+    x1: u32,
+    x2: u32,
+    x3: u32,
+    #[comptime] first: usize,
+) -> (u32, u32, u32, u32) {
+    let (x0, x1) = mix(x0, x1, comptime![THREEFRY_ROTATIONS[first][0]]);
+    let (x2, x3) = mix(x2, x3, comptime![THREEFRY_ROTATIONS[first][1]]);
+    let (x0, x3) = mix(x0, x3, comptime![THREEFRY_ROTATIONS[first + 1][0]]);
+    let (x2, x1) = mix(x2, x1, comptime![THREEFRY_ROTATIONS[first + 1][1]]);
+    let (x0, x1) = mix(x0, x1, comptime![THREEFRY_ROTATIONS[first + 2][0]]);
+    let (x2, x3) = mix(x2, x3, comptime![THREEFRY_ROTATIONS[first + 2][1]]);
+    let (x0, x3) = mix(x0, x3, comptime![THREEFRY_ROTATIONS[first + 3][0]]);
+    let (x2, x1) = mix(x2, x1, comptime![THREEFRY_ROTATIONS[first + 3][1]]);
+    (x0, x1, x2, x3)
+}
+
+#[cube]
+fn mix(a: u32, b: u32, #[comptime] rotation: u32) -> (u32, u32) {
+    let a = a + b;
+    let b = ((b << rotation) | (b >> comptime![32 - rotation])) ^ a;
+    (a, b)
+}
@@ -0,0 +1,191 @@
+use cubecl::prelude::*;
+use cubecl_core as cubecl;
+
+use crate::tensor::View;
+
+use super::{ReduceAccumulator, ReduceOperation};
+
+/// Reduce the elements `start, start + step, ...` of a view into an accumulator.
+///
+/// `start` and `step` are in elements and must be multiples of the vector size.
+#[cube]
+pub fn reduce_view_strided<In: Numeric, Acc: Numeric, N: Size>(
+    input: &View<Vector<In, N>, usize>,
+    start: usize,
+    step: usize,
+    #[comptime] operation: ReduceOperation,
+) -> ReduceAccumulator<Acc> {
+    let vector_size = input.vector_size();
+    let mut acc = ReduceAccumulator::<Acc>::new(operation);
+
+    for pos in range_stepped(start, input.shape(), step) {
+        let vector = input.read(pos);
+        #[unroll]
+        for i in 0..vector_size {
+            acc.accumulate(Acc::cast_from(vector[i]), (pos + i) as u32, operation);
+        }
+    }
+
+    acc
+}
+
+/// Reduce a view with a single unit.
+#[cube]
+pub fn reduce_unit<In: Numeric, Acc: Numeric, N: Size>(
+    input: &View<Vector<In, N>, usize>,
+    #[comptime] operation: ReduceOperation,
+) -> ReduceAccumulator<Acc> {
+    let vector_size = input.vector_size();
+    reduce_view_strided::<In, Acc, N>(input, 0, vector_size, operation)
+}
+
+/// Reduce a view with all units of a plane. Every unit of the plane gets the result.
+///
+/// Requires [`Plane::Ops`](cubecl_core::ir::features::Plane::Ops).
+#[cube]
+pub fn reduce_plane<In: Numeric, Acc: Numeric, N: Size>(
+    input: &View<Vector<In, N>, usize>,
+    #[comptime] operation: ReduceOperation,
+) -> ReduceAccumulator<Acc> {
+    let vector_size = input.vector_size();
+    let acc = reduce_view_strided::<In, Acc, N>(
+        input,
+        UNIT_POS_PLANE as usize * vector_size,
+        PLANE_DIM as usize * vector_size,
+        operation,
+    );
+    acc.plane_merge(operation)
+}
+
+/// Reduce a view with all units of a one-dimensional cube of at most `cube_size` units. Every
+/// unit of the cube gets the result.
+///
+/// With `use_planes`, each plane is reduced with plane operations before merging the planes
+/// through shared memory, otherwise the whole cube is reduced with a tree in shared memory, which
+/// requires the cube size to be a power of two.
+#[cube]
+pub fn reduce_cube<In: Numeric, Acc: Numeric, N: Size>(
+    input: &View<Vector<In, N>, usize>,
+    #[comptime] operation: ReduceOperation,
+    #[comptime] cube_size: u32,
+    #[comptime] use_planes: bool,
+) -> ReduceAccumulator<Acc> {
+    let vector_size = input.vector_size();
+    let acc = reduce_view_strided::<In, Acc, N>(
+        input,
+        UNIT_POS as usize * vector_size,
+        CUBE_DIM as usize * vector_size,
+        operation,
+    );
+
+    if comptime!(use_planes) {
+        merge_cube_planes(acc, operation, cube_size)
+    } else {
+        merge_cube_tree(acc, operation, cube_size)
+    }
+}
+
+/// Reduce each plane, then fold the results of all planes in every unit.
+#[cube]
+fn merge_cube_planes<A: Numeric>(
+    acc: ReduceAccumulator<A>,
+    #[comptime] operation: ReduceOperation,
+    #[comptime] cube_size: u32,
+) -> ReduceAccumulator<A> {
+    let plane_size_min = comptime::hardware_properties().comptime().plane_size_min;
+    let mut shared =
+        SharedAccumulators::<A>::new(comptime!(cube_size.div_ceil(plane_size_min)), operation);
+
+    let acc = acc.plane_merge(operation);
+    if UNIT_POS_PLANE == 0 {
+        shared.write(PLANE_POS as usize, acc);
+    }
+    sync_cube();
+
+    let num_planes = CUBE_DIM.div_ceil(PLANE_DIM);
+    let mut result = ReduceAccumulator::<A>::new(operation);
+    for plane in 0..num_planes as usize {
+        result.merge(shared.read(plane), operation);
+    }
+    result
+}
+
+/// Reduce the cube with a tree in shared memory, halving the number of active units each step.
+#[cube]
+fn merge_cube_tree<A: Numeric>(
+    acc: ReduceAccumulator<A>,
+    #[comptime] operation: ReduceOperation,
+    #[comptime] cube_size: u32,
+) -> ReduceAccumulator<A> {
+    let mut shared = SharedAccumulators::<A>::new(cube_size, operation);
+    let unit = UNIT_POS as usize;
+
+    shared.write(unit, acc);
+    sync_cube();
+
+    let mut stride = CUBE_DIM as usize / 2;
+    while stride > 0 {
+        if unit < stride {
+            let mut acc = shared.read(unit);
+            acc.merge(shared.read(unit + stride), operation);
+            shared.write(unit, acc);
+        }
+        sync_cube();
+        stride /= 2;
+    }
+
+    shared.read(0)
+}
+
+/// Accumulators stored in shared memory, one array per field.
+#[derive(CubeType)]
+struct SharedAccumulators<A: Numeric> {
+    values: SharedMemory<A>,
+    m2s: SharedMemory<A>,
+    counts: SharedMemory<u32>,
+    indices: SharedMemory<u32>,
+    #[cube(comptime)]
+    operation: ReduceOperation,
+}
+
+#[cube]
+impl<A: Numeric> SharedAccumulators<A> {
+    fn new(#[comptime] size: u32, #[comptime] operation: ReduceOperation) -> Self {
+        let size = comptime!(size as usize);
+        // Unused fields only get a single slot.
+        let moments_size = comptime!(if operation.uses_moments() { size } else { 1 });
+        let indices_size = comptime!(if operation.is_arg() { size } else { 1 });
+
+        SharedAccumulators::<A> {
+            values: SharedMemory::new(size),
+            m2s: SharedMemory::new(moments_size),
+            counts: SharedMemory::new(moments_size),
+            indices: SharedMemory::new(indices_size),
+            operation,
+        }
+    }
+
+    fn write(&mut self, pos: usize, acc: ReduceAccumulator<A>) {
+        self.values[pos] = acc.value;
+        if comptime!(self.operation.uses_moments()) {
+            self.m2s[pos] = acc.m2;
+            self.counts[pos] = acc.count;
+        }
+        if comptime!(self.operation.is_arg()) {
+            self.indices[pos] = acc.index;
+        }
+    }
+
+    fn read(&self, pos: usize) -> ReduceAccumulator<A> {
+        let mut acc = ReduceAccumulator::<A>::new(self.operation);
+        acc.value = self.values[pos];
+        if comptime!(self.operation.uses_moments()) {
+            acc.m2 = self.m2s[pos];
+            acc.count = self.counts[pos];
+        }
+        if comptime!(self.operation.is_arg()) {
+            acc.index = self.indices[pos];
+        }
+        acc
+    }
+}
@@ -0,0 +1,124 @@
+use cubecl::prelude::*;
+use cubecl_core::{self as cubecl, ir::StorageType};
+
+use crate::tensor::{
+    View,
+    layout::{Coords1d, Layout, LayoutExpand},
+    r#virtual::VirtualTensor,
+};
+
+use super::{ReduceOperation, ReduceStrategy, reduce_cube, reduce_plane, reduce_unit};
+
+/// Layout over the elements of a tensor along one axis, indexed in elements.
+#[derive(CubeType, Clone)]
+pub struct AxisLayout {
+    /// The offset of the first element, in elements.
+    offset: usize,
+    /// The stride of the axis, in elements.
+    stride: usize,
+    len: usize,
+    #[cube(comptime)]
+    vector_size: VectorSize,
+}
+
+#[cube]
+impl AxisLayout {
+    /// Create a new axis layout. The stride must be 1 when the vector size is greater than 1.
+    pub fn new(
+        offset: usize,
+        stride: usize,
+        len: usize,
+        #[comptime] vector_size: VectorSize,
+    ) -> Self {
+        AxisLayout {
+            offset,
+            stride,
+            len,
+            vector_size,
+        }
+    }
+}
+
+#[cube]
+impl Layout for AxisLayout {
+    type Coordinates = Coords1d;
+    type SourceCoordinates = Coords1d;
+
+    fn to_source_pos(&self, pos: Self::Coordinates) -> usize {
+        (self.offset + pos * self.stride) / self.vector_size
+    }
+
+    fn to_source_pos_checked(&self, pos: Self::Coordinates) -> (usize, bool) {
+        (self.to_source_pos(pos), self.is_in_bounds(pos))
+    }
+
+    fn shape(&self) -> Self::Coordinates {
+        self.len
+    }
+
+    fn is_in_bounds(&self, pos: Self::Coordinates) -> bool {
+        pos < self.len
+    }
+}
+
+/// Reduce the input along `axis` into an output of the same rank, with a shape of 1 on `axis`.
+///
+/// Each output element is reduced by one unit, plane or cube depending on the strategy. Vectors
+/// can only be used when the reduced axis is contiguous. `cube_size` is only used by the cube
+/// strategy, to size its shared memory.
+#[cube(launch_unchecked, address_type = "dynamic")]
+pub(crate) fn reduce_kernel<In: Numeric, Acc: Numeric, Out: Numeric, N: Size>(
+    input: &Tensor<Vector<In, N>>,
+    output: &mut Tensor<Out>,
+    axis: usize,
+    #[comptime] operation: ReduceOperation,
+    #[comptime] strategy: ReduceStrategy,
+    #[comptime] cube_size: u32,
+    #[define(In, Acc, Out)] _dtypes: [StorageType; 3],
+) {
+    let reduce_index = match strategy {
+        ReduceStrategy::Unit => ABSOLUTE_POS,
+        ReduceStrategy::Plane => CUBE_POS * (CUBE_DIM / PLANE_DIM) as usize + PLANE_POS as usize,
+        ReduceStrategy::Cube { .. } => CUBE_POS,
+    };
+
+    if reduce_index >= output.len() {
+        terminate!();
+    }
+
+    let input = VirtualTensor::<In, N>::new::<Tensor<Vector<In, N>>>(input);
+    let rank = input.rank();
+    let vector_size = input.vector_size();
+
+    let mut input_offset = 0;
+    let mut output_offset = 0;
+    let mut remainder = reduce_index;
+    for i in 0..rank {
+        let dim = rank - i - 1;
+        let coordinate = remainder % output.shape(dim);
+        remainder /= output.shape(dim);
+        input_offset += coordinate * input.stride(dim);
+        output_offset += coordinate * output.stride(dim);
+    }
+
+    let len = input.shape(axis);
+    let layout = AxisLayout::new(input_offset, input.stride(axis), len, vector_size);
+    let view: View<Vector<In, N>, usize> = input.view(layout);
+
+    let acc = match strategy {
+        ReduceStrategy::Unit => reduce_unit::<In, Acc, N>(&view, operation),
+        ReduceStrategy::Plane => reduce_plane::<In, Acc, N>(&view, operation),
+        ReduceStrategy::Cube { use_planes } => {
+            reduce_cube::<In, Acc, N>(&view, operation, cube_size, use_planes)
+        }
+    };
+
+    let is_writer = match strategy {
+        ReduceStrategy::Unit => true,
+        ReduceStrategy::Plane => UNIT_POS_PLANE == 0,
+        ReduceStrategy::Cube { .. } => UNIT_POS == 0,
+    };
+    if is_writer {
+        output[output_offset] = acc.finalize::<Out>(len, operation);
+    }
+}
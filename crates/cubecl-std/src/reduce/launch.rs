@@ -0,0 +1,196 @@
+use core::fmt::Display;
+
+use cubecl::prelude::*;
+use cubecl_core::{
+    self as cubecl, calculate_cube_count_elemwise,
+    ir::{StorageType, features::Plane},
+    tensor_vector_size_parallel,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{ReduceOperation, kernel::reduce_kernel};
+
+/// How each output element of a reduction is computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ReduceStrategy {
+    /// A single unit reduces each output element. Best when there are many short reductions.
+    Unit,
+    /// A plane reduces each output element with plane operations.
+    Plane,
+    /// A whole cube reduces each output element. Best when there are few long reductions.
+    Cube {
+        /// Merge the units of each plane with plane operations before merging the planes in
+        /// shared memory, instead of using a shared memory tree for the whole cube.
+        use_planes: bool,
+    },
+}
+
+impl ReduceStrategy {
+    /// Whether the strategy requires [`Plane::Ops`].
+    pub fn requires_planes(&self) -> bool {
+        matches!(
+            self,
+            ReduceStrategy::Plane | ReduceStrategy::Cube { use_planes: true }
+        )
+    }
+}
+
+/// The element types of a reduction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReduceDtypes {
+    pub input: StorageType,
+    /// The type of the accumulator, i.e. `f32` to sum `f16` values.
+    pub accumulation: StorageType,
+    /// The output type. Must be an integer for [`ReduceOperation::ArgMin`] and
+    /// [`ReduceOperation::ArgMax`].
+    pub output: StorageType,
+}
+
+impl ReduceDtypes {
+    /// Use the same type for the input, the accumulation and the output.
+    pub fn new(dtype: StorageType) -> Self {
+        Self {
+            input: dtype,
+            accumulation: dtype,
+            output: dtype,
+        }
+    }
+}
+
+/// An error preventing a reduction from being launched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReduceError {
+    /// The reduced axis is out of bounds.
+    InvalidAxis { axis: usize, rank: usize },
+    /// The output shape isn't the input shape with a size of 1 on the reduced axis.
+    MismatchedShape {
+        expected: Vec<usize>,
+        actual: Vec<usize>,
+    },
+    /// The strategy requires plane operations, but they aren't supported by the device.
+    PlanesUnsupported,
+}
+
+impl Display for ReduceError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ReduceError::InvalidAxis { axis, rank } => {
+                write!(f, "Can't reduce axis {axis} of a tensor of rank {rank}")
+            }
+            ReduceError::MismatchedShape { expected, actual } => write!(
+                f,
+                "The reduce output should have shape {expected:?}, but has shape {actual:?}"
+            ),
+            ReduceError::PlanesUnsupported => {
+                write!(f, "The reduce strategy requires plane operations")
+            }
+        }
+    }
+}
+
+impl core::error::Error for ReduceError {}
+
+/// The maximum number of units of a cube reducing a single output element.
+const MAX_CUBE_SIZE: u32 = 256;
+
+/// Reduce `input` along `axis` into `output` with the given strategy.
+///
+/// The output must have the same rank as the input, with a size of 1 on the reduced axis.
+pub fn launch_reduce<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: TensorBinding<R>,
+    output: TensorBinding<R>,
+    axis: usize,
+    operation: ReduceOperation,
+    dtypes: ReduceDtypes,
+    strategy: ReduceStrategy,
+) -> Result<(), ReduceError> {
+    validate(client, &input, &output, axis, strategy)?;
+
+    let reduce_len = input.shape[axis];
+    let num_reductions = output.size();
+
+    let vector_size = tensor_vector_size_parallel(
+        client.io_optimized_vector_sizes(dtypes.input.size()),
+        &input.shape,
+        &input.strides,
+        axis,
+    );
+
+    let plane_size = client.properties().hardware.plane_size_max;
+    let (cube_dim, cube_count, cube_size) = match strategy {
+        ReduceStrategy::Unit => {
+            let cube_dim = CubeDim::new(client, num_reductions);
+            let cube_count = calculate_cube_count_elemwise(client, num_reductions, cube_dim);
+            (cube_dim, cube_count, 0)
+        }
+        ReduceStrategy::Plane => {
+            // Planes of the same cube reduce different output elements.
+            let planes_per_cube = (MAX_CUBE_SIZE / plane_size).max(1);
+            let cube_dim = CubeDim::new_1d(plane_size * planes_per_cube);
+            let cube_count =
+                CubeCount::new_1d(num_reductions.div_ceil(planes_per_cube as usize) as u32);
+            (cube_dim, cube_count, 0)
+        }
+        ReduceStrategy::Cube { .. } => {
+            // Avoid idle units for short reductions.
+            let cube_size = (reduce_len.div_ceil(vector_size) as u32)
+                .next_power_of_two()
+                .clamp(plane_size, MAX_CUBE_SIZE.max(plane_size));
+            let cube_dim = CubeDim::new_1d(cube_size);
+            let cube_count = CubeCount::new_1d(num_reductions as u32);
+            (cube_dim, cube_count, cube_size)
+        }
+    };
+
+    let address_type = input
+        .required_address_type(dtypes.input.size())
+        .max(output.required_address_type(dtypes.output.size()));
+
+    unsafe {
+        reduce_kernel::launch_unchecked::<R>(
+            client,
+            cube_count,
+            cube_dim,
+            address_type,
+            vector_size,
+            input.into_tensor_arg(),
+            output.into_tensor_arg(),
+            axis,
+            operation,
+            strategy,
+            cube_size,
+            [dtypes.input, dtypes.accumulation, dtypes.output],
+        );
+    }
+
+    Ok(())
+}
+
+pub(crate) fn validate<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &TensorBinding<R>,
+    output: &TensorBinding<R>,
+    axis: usize,
+    strategy: ReduceStrategy,
+) -> Result<(), ReduceError> {
+    let rank = input.shape.len();
+    if axis >= rank {
+        return Err(ReduceError::InvalidAxis { axis, rank });
+    }
+
+    let mut expected = input.shape.to_vec();
+    expected[axis] = 1;
+    if output.shape.as_slice() != expected.as_slice() {
+        return Err(ReduceError::MismatchedShape {
+            expected,
+            actual: output.shape.to_vec(),
+        });
+    }
+
+    if strategy.requires_planes() && !client.features().plane.contains(Plane::Ops) {
+        return Err(ReduceError::PlanesUnsupported);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+//! Reductions along an axis for `CubeCL` kernels.
+//!
+//! Supports the sum, product, minimum, maximum, arg minimum, arg maximum, mean and variance
+//! of [`ReduceOperation`], accumulated in a [`ReduceAccumulator`].
+//!
+//! - Cube-level functions reduce a one-dimensional [`View`](crate::tensor::View) with a single
+//!   unit ([`reduce_unit`]), a plane ([`reduce_plane`]) or a whole cube ([`reduce_cube`]). A view
+//!   along any axis of a [`VirtualTensor`](crate::tensor::r#virtual::VirtualTensor) can be created
+//!   with an [`AxisLayout`].
+//! - Device-level functions reduce a tensor along an axis, either with a given
+//!   [`ReduceStrategy`] ([`launch_reduce`]) or with the fastest one found by autotuning
+//!   ([`reduce`]).
+//!
+//! Plane operations are used when [`Plane::Ops`](cubecl_core::ir::features::Plane::Ops) is supported,
+//! and trees in shared memory otherwise.
+
+mod cube;
+mod kernel;
+mod launch;
+mod operation;
+mod tune;
+
+pub use cube::*;
+pub use kernel::AxisLayout;
+pub use launch::*;
+pub use operation::*;
+pub use tune::*;
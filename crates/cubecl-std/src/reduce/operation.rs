@@ -0,0 +1,201 @@
+use cubecl::prelude::*;
+use cubecl_core as cubecl;
+use serde::{Deserialize, Serialize};
+
+/// The operation applied by a reduction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ReduceOperation {
+    Sum,
+    Prod,
+    Min,
+    Max,
+    /// The index of the minimum, the first one on ties.
+    ArgMin,
+    /// The index of the maximum, the first one on ties.
+    ArgMax,
+    Mean,
+    /// The population variance. Elements are accumulated with Welford's algorithm and partial
+    /// results merged with Chan's formula, so values far from zero don't lose precision.
+    Variance,
+}
+
+impl ReduceOperation {
+    /// Whether the operation outputs an index instead of a value.
+    pub fn is_arg(&self) -> bool {
+        matches!(self, ReduceOperation::ArgMin | ReduceOperation::ArgMax)
+    }
+
+    /// Whether the accumulator keeps the count and the sum of squared differences from the mean.
+    pub(crate) fn uses_moments(&self) -> bool {
+        matches!(self, ReduceOperation::Variance)
+    }
+}
+
+/// The partial result of a reduction.
+///
+/// The minimum and maximum start at the finite bounds of `A`, so infinities are only handled
+/// correctly by the arg reductions when at least one element is reduced.
+#[derive(CubeType, Clone, Copy)]
+pub struct ReduceAccumulator<A: Numeric> {
+    /// The sum, product, minimum or maximum of the reduced elements, or their mean for
+    /// [`ReduceOperation::Variance`].
+    pub value: A,
+    /// The sum of squared differences from the mean, only used by [`ReduceOperation::Variance`].
+    pub m2: A,
+    /// The number of reduced elements, only used by [`ReduceOperation::Variance`].
+    pub count: u32,
+    /// The index of the selected element, only used by the arg reductions. `u32::MAX` when no
+    /// element was reduced.
+    pub index: u32,
+}
+
+#[cube]
+impl<A: Numeric> ReduceAccumulator<A> {
+    /// Create the accumulator of an empty reduction.
+    pub fn new(#[comptime] operation: ReduceOperation) -> ReduceAccumulator<A> {
+        let value = match operation {
+            ReduceOperation::Sum | ReduceOperation::Mean | ReduceOperation::Variance => {
+                A::from_int(0)
+            }
+            ReduceOperation::Prod => A::from_int(1),
+            ReduceOperation::Min | ReduceOperation::ArgMin => A::max_value(),
+            ReduceOperation::Max | ReduceOperation::ArgMax => A::min_value(),
+        };
+
+        ReduceAccumulator::<A> {
+            value,
+            m2: A::from_int(0),
+            count: 0,
+            index: u32::MAX,
+        }
+    }
+
+    /// Reduce the element at `index` into the accumulator.
+    pub fn accumulate(&mut self, value: A, index: u32, #[comptime] operation: ReduceOperation) {
+        match operation {
+            ReduceOperation::Sum | ReduceOperation::Mean => {
+                self.value += value;
+            }
+            ReduceOperation::Variance => {
+                self.count += 1;
+                let delta = value - self.value;
+                self.value += delta / A::cast_from(self.count);
+                self.m2 += delta * (value - self.value);
+            }
+            ReduceOperation::Prod => {
+                self.value *= value;
+            }
+            ReduceOperation::Min => {
+                self.value = min(self.value, value);
+            }
+            ReduceOperation::Max => {
+                self.value = max(self.value, value);
+            }
+            ReduceOperation::ArgMin | ReduceOperation::ArgMax => {
+                let other = ReduceAccumulator::<A> {
+                    value,
+                    m2: A::from_int(0),
+                    count: 0,
+                    index,
+                };
+                self.merge(other, operation);
+            }
+        }
+    }
+
+    /// Merge the accumulator of another part of the same reduction.
+    pub fn merge(&mut self, other: ReduceAccumulator<A>, #[comptime] operation: ReduceOperation) {
+        match operation {
+            ReduceOperation::Sum | ReduceOperation::Mean => {
+                self.value += other.value;
+            }
+            ReduceOperation::Variance => {
+                let count = self.count + other.count;
+                let delta = other.value - self.value;
+                let weight = A::cast_from(other.count) / A::cast_from(max(count, 1));
+                self.value += delta * weight;
+                self.m2 += other.m2 + delta * delta * A::cast_from(self.count) * weight;
+                self.count = count;
+            }
+            ReduceOperation::Prod => {
+                self.value *= other.value;
+            }
+            ReduceOperation::Min => {
+                self.value = min(self.value, other.value);
+            }
+            ReduceOperation::Max => {
+                self.value = max(self.value, other.value);
+            }
+            ReduceOperation::ArgMin | ReduceOperation::ArgMax => {
+                let better = if comptime!(operation == ReduceOperation::ArgMin) {
+                    other.value < self.value
+                } else {
+                    other.value > self.value
+                };
+                let tie = other.value == self.value && other.index < self.index;
+                let replace = other.index != u32::MAX && (self.index == u32::MAX || better || tie);
+
+                self.value = select(replace, other.value, self.value);
+                self.index = select(replace, other.index, self.index);
+            }
+        }
+    }
+
+    /// Merge the accumulators of all units in the plane. Every unit gets the result.
+    pub fn plane_merge(&self, #[comptime] operation: ReduceOperation) -> ReduceAccumulator<A> {
+        let mut result = *self;
+        match operation {
+            ReduceOperation::Sum | ReduceOperation::Mean => {
+                result.value = plane_sum(self.value);
+            }
+            ReduceOperation::Variance => {
+                // The differences are taken from the mean of the whole plane, which keeps the
+                // merge as stable as merging pairwise.
+                let count = plane_sum(self.count);
+                let count_float = A::cast_from(self.count);
+                let mean = plane_sum(self.value * count_float) / A::cast_from(max(count, 1));
+                let delta = self.value - mean;
+                result.value = mean;
+                result.m2 = plane_sum(self.m2 + delta * delta * count_float);
+                result.count = count;
+            }
+            ReduceOperation::Prod => {
+                result.value = plane_prod(self.value);
+            }
+            ReduceOperation::Min => {
+                result.value = plane_min(self.value);
+            }
+            ReduceOperation::Max => {
+                result.value = plane_max(self.value);
+            }
+            ReduceOperation::ArgMin | ReduceOperation::ArgMax => {
+                let value = if comptime!(operation == ReduceOperation::ArgMin) {
+                    plane_min(self.value)
+                } else {
+                    plane_max(self.value)
+                };
+                let candidate = select(self.value == value, self.index, u32::MAX);
+                result.value = value;
+                result.index = plane_min(candidate);
+            }
+        }
+        result
+    }
+
+    /// Compute the output of a reduction over `count` elements.
+    pub fn finalize<Out: Numeric>(
+        &self,
+        count: usize,
+        #[comptime] operation: ReduceOperation,
+    ) -> Out {
+        match operation {
+            ReduceOperation::Sum
+            | ReduceOperation::Prod
+            | ReduceOperation::Min
+            | ReduceOperation::Max => Out::cast_from(self.value),
+            ReduceOperation::ArgMin | ReduceOperation::ArgMax => Out::cast_from(self.index),
+            ReduceOperation::Mean => Out::cast_from(self.value / A::cast_from(count)),
+            ReduceOperation::Variance => Out::cast_from(self.m2 / A::cast_from(count)),
+        }
+    }
+}
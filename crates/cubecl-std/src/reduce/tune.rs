@@ -0,0 +1,125 @@
+use cubecl::prelude::*;
+use cubecl_core::{self as cubecl, AutotuneKey, CubeTuneId};
+use cubecl_runtime::tune::{LocalTuner, Tunable, TunableSet, local_tuner};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    ReduceDtypes, ReduceError, ReduceOperation, ReduceStrategy, launch::validate, launch_reduce,
+};
+
+/// The key used to select the fastest reduce strategy.
+#[derive(AutotuneKey, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ReduceAutotuneKey {
+    operation: ReduceOperation,
+    #[autotune(anchor)]
+    reduce_len: usize,
+    #[autotune(anchor)]
+    num_reductions: usize,
+    /// Whether the reduced axis is contiguous, which allows vectorization.
+    contiguous: bool,
+    input_dtype: StorageType,
+    accumulation_dtype: StorageType,
+    output_dtype: StorageType,
+}
+
+impl ReduceAutotuneKey {
+    fn generate<R: Runtime>(
+        input: &TensorBinding<R>,
+        output: &TensorBinding<R>,
+        axis: usize,
+        operation: ReduceOperation,
+        dtypes: &ReduceDtypes,
+    ) -> Self {
+        Self::new(
+            operation,
+            input.shape[axis],
+            output.size(),
+            input.strides[axis] == 1,
+            dtypes.input,
+            dtypes.accumulation,
+            dtypes.output,
+        )
+    }
+}
+
+/// The strategies benchmarked by [`reduce`].
+const STRATEGIES: [ReduceStrategy; 4] = [
+    ReduceStrategy::Unit,
+    ReduceStrategy::Plane,
+    ReduceStrategy::Cube { use_planes: true },
+    ReduceStrategy::Cube { use_planes: false },
+];
+
+/// Reduce `input` along `axis` into `output`, using the fastest strategy for the shape.
+///
+/// Strategies that require plane operations are skipped when the device doesn't support them.
+/// See [`launch_reduce`] to select the strategy manually.
+pub fn reduce<R: Runtime>(
+    client: &ComputeClient<R>,
+    device: &R::Device,
+    input: TensorBinding<R>,
+    output: TensorBinding<R>,
+    axis: usize,
+    operation: ReduceOperation,
+    dtypes: ReduceDtypes,
+) -> Result<(), ReduceError> {
+    // Check the arguments once, so tuning only fails on unsupported strategies.
+    validate(client, &input, &output, axis, ReduceStrategy::Unit)?;
+
+    static TUNER: LocalTuner<ReduceAutotuneKey, CubeTuneId> = local_tuner!();
+
+    let tunables = TUNER.init(|| {
+        let mut set = TunableSet::new(
+            |input: &TensorBinding<R>,
+             output: &TensorBinding<R>,
+             axis: &usize,
+             operation: &ReduceOperation,
+             dtypes: &ReduceDtypes,
+             _client: &ComputeClient<R>| {
+                ReduceAutotuneKey::generate(input, output, *axis, *operation, dtypes)
+            },
+            |_key: &ReduceAutotuneKey,
+             input: &TensorBinding<R>,
+             output: &TensorBinding<R>,
+             axis: &usize,
+             operation: &ReduceOperation,
+             dtypes: &ReduceDtypes,
+             client: &ComputeClient<R>| {
+                (
+                    input.clone(),
+                    output.clone(),
+                    *axis,
+                    *operation,
+                    *dtypes,
+                    client.clone(),
+                )
+            },
+        );
+
+        for strategy in STRATEGIES {
+            set = set.with(Tunable::new(
+                format!("{strategy:?}"),
+                move |input: TensorBinding<R>,
+                      output: TensorBinding<R>,
+                      axis: usize,
+                      operation: ReduceOperation,
+                      dtypes: ReduceDtypes,
+                      client: ComputeClient<R>| {
+                    launch_reduce(&client, input, output, axis, operation, dtypes, strategy)
+                        .map_err(|err| err.to_string())
+                },
+            ));
+        }
+
+        set
+    });
+
+    TUNER.execute(
+        &CubeTuneId::new::<R>(client, device),
+        client,
+        tunables,
+        (input, output, axis, operation, dtypes, client.clone()),
+    );
+
+    Ok(())
+}
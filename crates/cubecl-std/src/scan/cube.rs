@@ -0,0 +1,125 @@
+use cubecl::prelude::*;
+use cubecl_core as cubecl;
+
+use super::ScanOperator;
+
+/// Scan the values of all units in the plane, in lane order.
+///
+/// Requires [`Plane::Ops`](cubecl_core::ir::features::Plane::Ops).
+#[cube]
+pub fn plane_scan<T: Numeric, O: ScanOperator>(value: T, #[comptime] inclusive: bool) -> T {
+    let lane = UNIT_POS_PLANE;
+    let mut acc = value;
+
+    let mut delta = 1;
+    while delta < PLANE_DIM {
+        // Lanes below `delta` read their own value, which is discarded.
+        let earlier = plane_shuffle_up(acc, delta);
+        acc = select(lane >= delta, O::combine::<T>(earlier, acc), acc);
+        delta *= 2;
+    }
+
+    if comptime!(inclusive) {
+        acc
+    } else {
+        let earlier = plane_shuffle_up(acc, 1);
+        select(lane > 0, earlier, O::identity::<T>())
+    }
+}
+
+/// Scan the values of all units in a one-dimensional cube of at most `cube_size` units, in unit
+/// order. Returns the scanned value of the unit and the result of the whole cube.
+///
+/// With `use_planes`, each plane is scanned with plane operations before combining the totals of
+/// the planes through shared memory, otherwise the whole cube is scanned in shared memory.
+#[cube]
+pub fn scan_cube<T: Numeric, O: ScanOperator>(
+    value: T,
+    #[comptime] inclusive: bool,
+    #[comptime] cube_size: u32,
+    #[comptime] use_planes: bool,
+) -> (T, T) {
+    if comptime!(use_planes) {
+        scan_cube_planes::<T, O>(value, inclusive, cube_size)
+    } else {
+        scan_cube_shared::<T, O>(value, inclusive, cube_size)
+    }
+}
+
+/// Scan each plane, then combine the totals of the earlier planes in every unit.
+#[cube]
+fn scan_cube_planes<T: Numeric, O: ScanOperator>(
+    value: T,
+    #[comptime] inclusive: bool,
+    #[comptime] cube_size: u32,
+) -> (T, T) {
+    let plane_size_min = comptime::hardware_properties().comptime().plane_size_min;
+    let mut totals = SharedMemory::<T>::new(comptime!(cube_size.div_ceil(plane_size_min) as usize));
+
+    let plane_inclusive = plane_scan::<T, O>(value, true);
+    // The last plane may be partial, so its total is held by the last unit of the cube.
+    if UNIT_POS_PLANE == PLANE_DIM - 1 || UNIT_POS == CUBE_DIM - 1 {
+        totals[PLANE_POS as usize] = plane_inclusive;
+    }
+    sync_cube();
+
+    let mut prefix = O::identity::<T>();
+    for plane in 0..PLANE_POS as usize {
+        prefix = O::combine::<T>(prefix, totals[plane]);
+    }
+
+    let num_planes = CUBE_DIM.div_ceil(PLANE_DIM);
+    let mut total = O::identity::<T>();
+    for plane in 0..num_planes as usize {
+        total = O::combine::<T>(total, totals[plane]);
+    }
+
+    let plane_value = if comptime!(inclusive) {
+        plane_inclusive
+    } else {
+        let earlier = plane_shuffle_up(plane_inclusive, 1);
+        select(UNIT_POS_PLANE > 0, earlier, O::identity::<T>())
+    };
+
+    (O::combine::<T>(prefix, plane_value), total)
+}
+
+/// Scan the cube in shared memory, doubling the distance between combined units each step.
+#[cube]
+fn scan_cube_shared<T: Numeric, O: ScanOperator>(
+    value: T,
+    #[comptime] inclusive: bool,
+    #[comptime] cube_size: u32,
+) -> (T, T) {
+    let mut shared = SharedMemory::<T>::new(comptime!(cube_size as usize));
+    let unit = UNIT_POS as usize;
+    let cube_dim = CUBE_DIM as usize;
+
+    shared[unit] = value;
+    sync_cube();
+
+    let mut offset = 1;
+    while offset < cube_dim {
+        let mut acc = shared[unit];
+        if unit >= offset {
+            acc = O::combine::<T>(shared[unit - offset], acc);
+        }
+        sync_cube();
+        shared[unit] = acc;
+        sync_cube();
+        offset *= 2;
+    }
+
+    let total = shared[cube_dim - 1];
+    let scanned = if comptime!(inclusive) {
+        shared[unit]
+    } else {
+        let mut earlier = O::identity::<T>();
+        if unit > 0 {
+            earlier = shared[unit - 1];
+        }
+        earlier
+    };
+
+    (scanned, total)
+}
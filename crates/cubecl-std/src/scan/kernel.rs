@@ -0,0 +1,139 @@
+use cubecl::prelude::*;
+use cubecl_core::{self as cubecl, ir::StorageType};
+
+use super::{ScanOperator, ScanStrategy, SelectPredicate, scan_cube};
+
+/// Scan the elements of the block of each unit, returning the scanned elements and the result of
+/// the whole block. Elements past `len` are replaced by the identity.
+#[cube]
+fn scan_unit<T: Numeric, O: ScanOperator>(
+    input: &Tensor<T>,
+    start: usize,
+    #[comptime] inclusive: bool,
+    #[comptime] items_per_unit: u32,
+) -> (Array<T>, T) {
+    let mut values = Array::<T>::new(comptime!(items_per_unit as usize));
+    let mut acc = O::identity::<T>();
+
+    #[unroll]
+    for i in 0..items_per_unit as usize {
+        let pos = start + i;
+        let mut value = O::identity::<T>();
+        if pos < input.len() {
+            value = input[pos];
+        }
+        let before = acc;
+        acc = O::combine::<T>(acc, value);
+        values[i] = if comptime!(inclusive) { acc } else { before };
+    }
+
+    (values, acc)
+}
+
+/// The position of the first element of the unit.
+#[cube]
+fn unit_start(#[comptime] strategy: ScanStrategy) -> usize {
+    CUBE_POS * comptime!(strategy.block_size() as usize)
+        + UNIT_POS as usize * comptime!(strategy.items_per_unit as usize)
+}
+
+/// Write the result of the block of each cube to `partials`.
+#[cube(launch_unchecked)]
+pub(crate) fn scan_reduce_kernel<T: Numeric, O: ScanOperator>(
+    input: &Tensor<T>,
+    partials: &mut Tensor<T>,
+    #[comptime] strategy: ScanStrategy,
+    #[define(T)] _dtype: StorageType,
+) {
+    if CUBE_POS >= partials.len() {
+        terminate!();
+    }
+
+    let start = unit_start(strategy);
+    let (_, unit_total) = scan_unit::<T, O>(input, start, true, strategy.items_per_unit);
+    let (_, cube_total) =
+        scan_cube::<T, O>(unit_total, true, strategy.cube_size, strategy.use_planes);
+
+    if UNIT_POS == 0 {
+        partials[CUBE_POS] = cube_total;
+    }
+}
+
+/// Scan the block of each cube, starting from the result of the earlier blocks in `prefixes`
+/// when `has_prefix` is set.
+#[cube(launch_unchecked)]
+pub(crate) fn scan_kernel<T: Numeric, O: ScanOperator>(
+    input: &Tensor<T>,
+    output: &mut Tensor<T>,
+    prefixes: &Tensor<T>,
+    #[comptime] strategy: ScanStrategy,
+    #[comptime] inclusive: bool,
+    #[comptime] has_prefix: bool,
+    #[define(T)] _dtype: StorageType,
+) {
+    if CUBE_POS * comptime!(strategy.block_size() as usize) >= input.len() {
+        terminate!();
+    }
+
+    let start = unit_start(strategy);
+    let (values, unit_total) = scan_unit::<T, O>(input, start, inclusive, strategy.items_per_unit);
+    let (mut prefix, _) =
+        scan_cube::<T, O>(unit_total, false, strategy.cube_size, strategy.use_planes);
+    if comptime!(has_prefix) {
+        prefix = O::combine::<T>(prefixes[CUBE_POS], prefix);
+    }
+
+    #[unroll]
+    for i in 0..strategy.items_per_unit as usize {
+        let pos = start + i;
+        if pos < output.len() {
+            output[pos] = O::combine::<T>(prefix, values[i]);
+        }
+    }
+}
+
+/// Write 1 to `flags` for the selected elements of the input, and 0 for the others.
+#[cube(launch_unchecked)]
+pub(crate) fn flag_kernel<T: Numeric, P: SelectPredicate>(
+    input: &Tensor<T>,
+    flags: &mut Tensor<u32>,
+    arg: InputScalar,
+    #[define(T)] _dtype: StorageType,
+) {
+    if ABSOLUTE_POS >= input.len() {
+        terminate!();
+    }
+
+    let selected = P::select::<T>(input[ABSOLUTE_POS], arg.get::<T>());
+    flags[ABSOLUTE_POS] = select(selected, 1u32, 0u32);
+}
+
+/// Move the flagged elements to the front of the output, in order, using the exclusive scan of
+/// the flags as their positions. With `partition`, the other elements follow, also in order.
+#[cube(launch_unchecked)]
+pub(crate) fn compact_kernel<T: Numeric>(
+    input: &Tensor<T>,
+    flags: &Tensor<u32>,
+    positions: &Tensor<u32>,
+    output: &mut Tensor<T>,
+    num_selected: &mut Tensor<u32>,
+    #[comptime] partition: bool,
+    #[define(T)] _dtype: StorageType,
+) {
+    let len = input.len();
+    if ABSOLUTE_POS >= len {
+        terminate!();
+    }
+
+    let total = positions[len - 1] + flags[len - 1];
+    if ABSOLUTE_POS == 0 {
+        num_selected[0] = total;
+    }
+
+    let position = positions[ABSOLUTE_POS] as usize;
+    if flags[ABSOLUTE_POS] != 0 {
+        output[position] = input[ABSOLUTE_POS];
+    } else if comptime!(partition) {
+        output[total as usize + ABSOLUTE_POS - position] = input[ABSOLUTE_POS];
+    }
+}
@@ -0,0 +1,385 @@
+use core::fmt::Display;
+
+use cubecl::prelude::*;
+use cubecl_core::{
+    self as cubecl, calculate_cube_count_elemwise,
+    ir::{StorageType, features::Plane},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::tensor::{TensorHandle, is_contiguous};
+
+use super::{
+    AddOp, NotEqual, ScanOperator, SelectPredicate,
+    kernel::{compact_kernel, flag_kernel, scan_kernel, scan_reduce_kernel},
+};
+
+/// Whether each output element includes the input element at the same position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ScanKind {
+    /// `output[i] = input[0] op ... op input[i]`.
+    Inclusive,
+    /// `output[i] = identity op input[0] op ... op input[i - 1]`.
+    Exclusive,
+}
+
+/// How the blocks of a scan are split between the units of a cube.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ScanStrategy {
+    /// Scan each plane with plane operations before combining the planes in shared memory,
+    /// instead of scanning the whole cube in shared memory.
+    pub use_planes: bool,
+    /// The number of units of each cube.
+    pub cube_size: u32,
+    /// The number of contiguous elements scanned sequentially by each unit.
+    pub items_per_unit: u32,
+}
+
+impl ScanStrategy {
+    /// The default strategy for the device, using plane operations when they are supported.
+    pub fn new<R: Runtime>(client: &ComputeClient<R>) -> Self {
+        let hardware = &client.properties().hardware;
+        Self {
+            use_planes: client.features().plane.contains(Plane::Ops),
+            cube_size: DEFAULT_CUBE_SIZE
+                .max(hardware.plane_size_max)
+                .min(hardware.max_units_per_cube),
+            items_per_unit: DEFAULT_ITEMS_PER_UNIT,
+        }
+    }
+
+    /// The number of elements scanned by each cube.
+    pub fn block_size(&self) -> u32 {
+        self.cube_size * self.items_per_unit
+    }
+}
+
+/// An error preventing a scan or a compaction from being launched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanError {
+    /// A tensor isn't contiguous.
+    NonContiguous {
+        shape: Vec<usize>,
+        strides: Vec<usize>,
+    },
+    /// A tensor doesn't have the same number of elements as the input.
+    MismatchedSize { expected: usize, actual: usize },
+    /// The cube size or the number of items per unit is zero, or the cube size exceeds the
+    /// maximum number of units per cube.
+    InvalidStrategy(ScanStrategy),
+    /// The strategy requires plane operations, but they aren't supported by the device.
+    PlanesUnsupported,
+}
+
+impl Display for ScanError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ScanError::NonContiguous { shape, strides } => write!(
+                f,
+                "Can't scan a tensor with shape {shape:?} and non-contiguous strides {strides:?}"
+            ),
+            ScanError::MismatchedSize { expected, actual } => write!(
+                f,
+                "Expected a tensor of {expected} elements, but got {actual} elements"
+            ),
+            ScanError::InvalidStrategy(strategy) => {
+                write!(
+                    f,
+                    "The scan strategy {strategy:?} isn't valid for the device"
+                )
+            }
+            ScanError::PlanesUnsupported => {
+                write!(f, "The scan strategy requires plane operations")
+            }
+        }
+    }
+}
+
+impl core::error::Error for ScanError {}
+
+const DEFAULT_CUBE_SIZE: u32 = 256;
+const DEFAULT_ITEMS_PER_UNIT: u32 = 4;
+
+/// Scan the elements of `input` into `output` with the operator `O`, using the default
+/// [`ScanStrategy`].
+///
+/// Both tensors must be contiguous and have the same number of elements, which are scanned in
+/// row-major order.
+pub fn scan<R: Runtime, O: ScanOperator>(
+    client: &ComputeClient<R>,
+    input: TensorBinding<R>,
+    output: TensorBinding<R>,
+    kind: ScanKind,
+    dtype: StorageType,
+) -> Result<(), ScanError> {
+    launch_scan::<R, O>(
+        client,
+        input,
+        output,
+        kind,
+        dtype,
+        ScanStrategy::new(client),
+    )
+}
+
+/// Scan the elements of `input` into `output` with the operator `O` and the given strategy.
+///
+/// Each cube scans a block of elements. When there is more than one block, the results of the
+/// blocks are reduced and scanned first, recursively, and each cube then starts from the result
+/// of the earlier blocks.
+pub fn launch_scan<R: Runtime, O: ScanOperator>(
+    client: &ComputeClient<R>,
+    input: TensorBinding<R>,
+    output: TensorBinding<R>,
+    kind: ScanKind,
+    dtype: StorageType,
+    strategy: ScanStrategy,
+) -> Result<(), ScanError> {
+    validate_strategy(client, strategy)?;
+    validate_contiguous(&input)?;
+    validate_contiguous(&output)?;
+    validate_size(&output, input.size())?;
+
+    scan_contiguous::<R, O>(client, input, output, kind, dtype, strategy);
+    Ok(())
+}
+
+fn scan_contiguous<R: Runtime, O: ScanOperator>(
+    client: &ComputeClient<R>,
+    input: TensorBinding<R>,
+    output: TensorBinding<R>,
+    kind: ScanKind,
+    dtype: StorageType,
+    strategy: ScanStrategy,
+) {
+    let len = input.size();
+    if len == 0 {
+        return;
+    }
+
+    let num_blocks = len.div_ceil(strategy.block_size() as usize);
+    let cube_dim = CubeDim::new_1d(strategy.cube_size);
+    let cube_count =
+        calculate_cube_count_elemwise(client, num_blocks * strategy.cube_size as usize, cube_dim);
+
+    // A single block doesn't need any prefix, so a placeholder is bound instead.
+    let has_prefix = num_blocks > 1;
+    let prefixes = TensorHandle::<R>::empty(client, vec![num_blocks], dtype);
+
+    if has_prefix {
+        let partials = TensorHandle::<R>::empty(client, vec![num_blocks], dtype);
+        unsafe {
+            scan_reduce_kernel::launch_unchecked::<O, R>(
+                client,
+                cube_count.clone(),
+                cube_dim,
+                input.clone().into_tensor_arg(),
+                partials.clone().binding().into_tensor_arg(),
+                strategy,
+                dtype,
+            );
+        }
+        scan_contiguous::<R, O>(
+            client,
+            partials.binding(),
+            prefixes.clone().binding(),
+            ScanKind::Exclusive,
+            dtype,
+            strategy,
+        );
+    }
+
+    unsafe {
+        scan_kernel::launch_unchecked::<O, R>(
+            client,
+            cube_count,
+            cube_dim,
+            input.into_tensor_arg(),
+            output.into_tensor_arg(),
+            prefixes.binding().into_tensor_arg(),
+            strategy,
+            kind == ScanKind::Inclusive,
+            has_prefix,
+            dtype,
+        );
+    }
+}
+
+/// Copy the elements of `input` with a non-zero `flag` to the front of `output`, in order, and
+/// write their count to `num_selected`.
+///
+/// `flags` and `num_selected` are `u32` tensors. The elements of `output` past the selected ones
+/// are left unchanged, and nothing is written for an empty input.
+pub fn compact<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: TensorBinding<R>,
+    flags: TensorBinding<R>,
+    output: TensorBinding<R>,
+    num_selected: TensorBinding<R>,
+    dtype: StorageType,
+) -> Result<(), ScanError> {
+    validate_contiguous(&flags)?;
+    validate_size(&flags, input.size())?;
+
+    let flags = launch_flags::<R, NotEqual>(
+        client,
+        flags,
+        InputScalar::new(0, u32::as_type_native_unchecked().storage_type()),
+        u32::as_type_native_unchecked().storage_type(),
+    );
+    launch_compact(client, input, flags, output, num_selected, dtype, false)
+}
+
+/// Copy the elements of `input` selected by the predicate `P` to the front of `output`, in
+/// order, and write their count to `num_selected`.
+///
+/// Each element is compared to `arg`, and `num_selected` is a `u32` tensor. The elements of
+/// `output` past the selected ones are left unchanged, and nothing is written for an empty input.
+pub fn select_if<R: Runtime, P: SelectPredicate>(
+    client: &ComputeClient<R>,
+    input: TensorBinding<R>,
+    arg: InputScalar,
+    output: TensorBinding<R>,
+    num_selected: TensorBinding<R>,
+    dtype: StorageType,
+) -> Result<(), ScanError> {
+    validate_contiguous(&input)?;
+
+    let flags = launch_flags::<R, P>(client, input.clone(), arg, dtype);
+    launch_compact(client, input, flags, output, num_selected, dtype, false)
+}
+
+/// Reorder the elements of `input` into `output`, with the elements selected by the predicate `P`
+/// first and the others after them, and write the number of selected elements to
+/// `num_selected`.
+///
+/// The partition is stable: both groups keep the order of the input. Each element is compared to
+/// `arg`, and `num_selected` is a `u32` tensor.
+pub fn partition<R: Runtime, P: SelectPredicate>(
+    client: &ComputeClient<R>,
+    input: TensorBinding<R>,
+    arg: InputScalar,
+    output: TensorBinding<R>,
+    num_selected: TensorBinding<R>,
+    dtype: StorageType,
+) -> Result<(), ScanError> {
+    validate_contiguous(&input)?;
+
+    let flags = launch_flags::<R, P>(client, input.clone(), arg, dtype);
+    launch_compact(client, input, flags, output, num_selected, dtype, true)
+}
+
+/// Compute the `u32` flags of the elements selected by `P`.
+fn launch_flags<R: Runtime, P: SelectPredicate>(
+    client: &ComputeClient<R>,
+    input: TensorBinding<R>,
+    arg: InputScalar,
+    dtype: StorageType,
+) -> TensorHandle<R> {
+    let len = input.size();
+    let flags = TensorHandle::<R>::empty(
+        client,
+        vec![len],
+        u32::as_type_native_unchecked().storage_type(),
+    );
+
+    let cube_dim = CubeDim::new(client, len);
+    let cube_count = calculate_cube_count_elemwise(client, len, cube_dim);
+    unsafe {
+        flag_kernel::launch_unchecked::<P, R>(
+            client,
+            cube_count,
+            cube_dim,
+            input.into_tensor_arg(),
+            flags.clone().binding().into_tensor_arg(),
+            arg,
+            dtype,
+        );
+    }
+
+    flags
+}
+
+fn launch_compact<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: TensorBinding<R>,
+    flags: TensorHandle<R>,
+    output: TensorBinding<R>,
+    num_selected: TensorBinding<R>,
+    dtype: StorageType,
+    partition: bool,
+) -> Result<(), ScanError> {
+    validate_contiguous(&input)?;
+    validate_contiguous(&output)?;
+    validate_size(&output, input.size())?;
+    validate_size(&num_selected, 1)?;
+
+    let len = input.size();
+    if len == 0 {
+        return Ok(());
+    }
+
+    let u32_dtype = u32::as_type_native_unchecked().storage_type();
+    let positions = TensorHandle::<R>::empty(client, vec![len], u32_dtype);
+    scan_contiguous::<R, AddOp>(
+        client,
+        flags.clone().binding(),
+        positions.clone().binding(),
+        ScanKind::Exclusive,
+        u32_dtype,
+        ScanStrategy::new(client),
+    );
+
+    let cube_dim = CubeDim::new(client, len);
+    let cube_count = calculate_cube_count_elemwise(client, len, cube_dim);
+    unsafe {
+        compact_kernel::launch_unchecked::<R>(
+            client,
+            cube_count,
+            cube_dim,
+            input.into_tensor_arg(),
+            flags.binding().into_tensor_arg(),
+            positions.binding().into_tensor_arg(),
+            output.into_tensor_arg(),
+            num_selected.into_tensor_arg(),
+            partition,
+            dtype,
+        );
+    }
+
+    Ok(())
+}
+
+fn validate_strategy<R: Runtime>(
+    client: &ComputeClient<R>,
+    strategy: ScanStrategy,
+) -> Result<(), ScanError> {
+    let max_units = client.properties().hardware.max_units_per_cube;
+    if strategy.cube_size == 0 || strategy.items_per_unit == 0 || strategy.cube_size > max_units {
+        return Err(ScanError::InvalidStrategy(strategy));
+    }
+
+    if strategy.use_planes && !client.features().plane.contains(Plane::Ops) {
+        return Err(ScanError::PlanesUnsupported);
+    }
+
+    Ok(())
+}
+
+fn validate_contiguous<R: Runtime>(tensor: &TensorBinding<R>) -> Result<(), ScanError> {
+    if !is_contiguous(&tensor.shape, &tensor.strides) {
+        return Err(ScanError::NonContiguous {
+            shape: tensor.shape.to_vec(),
+            strides: tensor.strides.to_vec(),
+        });
+    }
+    Ok(())
+}
+
+fn validate_size<R: Runtime>(tensor: &TensorBinding<R>, expected: usize) -> Result<(), ScanError> {
+    let actual = tensor.size();
+    if actual != expected {
+        return Err(ScanError::MismatchedSize { expected, actual });
+    }
+    Ok(())
+}
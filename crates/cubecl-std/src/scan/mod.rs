@@ -0,0 +1,24 @@
+//! Prefix scans and stream compaction for `CubeCL` kernels.
+//!
+//! Scans combine the elements in order with an associative [`ScanOperator`], which doesn't need
+//! to be commutative. [`AddOp`], [`MulOp`], [`MinOp`] and [`MaxOp`] are provided.
+//!
+//! - Cube-level functions scan one value per unit of a plane ([`plane_scan`]) or of a cube
+//!   ([`scan_cube`]).
+//! - Device-level functions scan a whole tensor with the reduce-then-scan algorithm, either with
+//!   the default [`ScanStrategy`] ([`scan`]) or a given one ([`launch_scan`]).
+//! - Compaction moves the elements selected by flags ([`compact`]) or by a [`SelectPredicate`]
+//!   ([`select_if`]) to the front of the output, and [`partition`] also keeps the other elements
+//!   after them. The positions are found with an exclusive scan of the flags.
+//!
+//! Plane operations are used when [`Plane::Ops`](cubecl_core::ir::features::Plane::Ops) is supported,
+//! and shared memory otherwise.
+
+mod cube;
+mod kernel;
+mod launch;
+mod operator;
+
+pub use cube::*;
+pub use launch::*;
+pub use operator::*;
@@ -0,0 +1,119 @@
+use cubecl::prelude::*;
+use cubecl_core as cubecl;
+
+/// An associative binary operator used by scans.
+///
+/// The operator doesn't need to be commutative: `combine` always receives the partial result of
+/// the earlier elements first.
+#[cube]
+pub trait ScanOperator: Send + Sync + 'static {
+    /// The value that leaves any other value unchanged when combined with it.
+    fn identity<T: Numeric>() -> T;
+
+    /// Combine the partial result of earlier elements with the one of later elements.
+    fn combine<T: Numeric>(lhs: T, rhs: T) -> T;
+}
+
+/// Prefix sum.
+pub struct AddOp;
+
+/// Prefix product.
+pub struct MulOp;
+
+/// Prefix minimum. The identity is the largest finite value of the type.
+pub struct MinOp;
+
+/// Prefix maximum. The identity is the smallest finite value of the type.
+pub struct MaxOp;
+
+#[cube]
+impl ScanOperator for AddOp {
+    fn identity<T: Numeric>() -> T {
+        T::from_int(0)
+    }
+
+    fn combine<T: Numeric>(lhs: T, rhs: T) -> T {
+        lhs + rhs
+    }
+}
+
+#[cube]
+impl ScanOperator for MulOp {
+    fn identity<T: Numeric>() -> T {
+        T::from_int(1)
+    }
+
+    fn combine<T: Numeric>(lhs: T, rhs: T) -> T {
+        lhs * rhs
+    }
+}
+
+#[cube]
+impl ScanOperator for MinOp {
+    fn identity<T: Numeric>() -> T {
+        T::max_value()
+    }
+
+    fn combine<T: Numeric>(lhs: T, rhs: T) -> T {
+        min(lhs, rhs)
+    }
+}
+
+#[cube]
+impl ScanOperator for MaxOp {
+    fn identity<T: Numeric>() -> T {
+        T::min_value()
+    }
+
+    fn combine<T: Numeric>(lhs: T, rhs: T) -> T {
+        max(lhs, rhs)
+    }
+}
+
+/// A predicate comparing each element to a runtime argument, used by
+/// [`select_if`](super::select_if) and [`partition`](super::partition).
+#[cube]
+pub trait SelectPredicate: Send + Sync + 'static {
+    /// Whether `value` is selected.
+    fn select<T: Numeric>(value: T, arg: T) -> bool;
+}
+
+/// Selects the elements greater than the argument.
+pub struct GreaterThan;
+
+/// Selects the elements less than the argument.
+pub struct LessThan;
+
+/// Selects the elements equal to the argument.
+pub struct Equal;
+
+/// Selects the elements not equal to the argument.
+pub struct NotEqual;
+
+#[cube]
+impl SelectPredicate for GreaterThan {
+    fn select<T: Numeric>(value: T, arg: T) -> bool {
+        value > arg
+    }
+}
+
+#[cube]
+impl SelectPredicate for LessThan {
+    fn select<T: Numeric>(value: T, arg: T) -> bool {
+        value < arg
+    }
+}
+
+#[cube]
+impl SelectPredicate for Equal {
+    fn select<T: Numeric>(value: T, arg: T) -> bool {
+        value == arg
+    }
+}
+
+#[cube]
+impl SelectPredicate for NotEqual {
+    fn select<T: Numeric>(value: T, arg: T) -> bool {
+        value != arg
+    }
+}
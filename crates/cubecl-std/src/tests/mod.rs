@@ -2,7 +2,10 @@
 pub use test_log;
 
 pub mod event;
+pub mod random;
+pub mod reduce;
 pub mod reinterpret_slice;
+pub mod scan;
 pub mod tensor;
 pub mod trigonometry;
 pub mod view;
@@ -17,6 +20,9 @@ macro_rules! testgen {
             cubecl_std::testgen_reinterpret_slice!();
             cubecl_std::testgen_trigonometry!();
             cubecl_std::testgen_event!();
+            cubecl_std::testgen_random!();
+            cubecl_std::testgen_reduce!();
+            cubecl_std::testgen_scan!();
         }
     };
 }
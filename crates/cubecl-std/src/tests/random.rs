@@ -0,0 +1,218 @@
+use cubecl::prelude::*;
+use cubecl_core as cubecl;
+
+use crate::random::{host, *};
+
+const SEED: u32 = 0x5eed;
+const OFFSET: u32 = 3;
+const NUM_UNITS: usize = 32;
+/// Each unit draws twice, to check that the offset is incremented.
+const NUM_DRAWS: usize = 2;
+
+/// A generator with its host reference.
+pub trait TestRng: CounterRng {
+    type Host: host::CounterRng;
+
+    fn host(seed: u32, offset: u32, subsequence: u32) -> Self::Host;
+}
+
+impl TestRng for Philox {
+    type Host = host::Philox;
+
+    fn host(seed: u32, offset: u32, subsequence: u32) -> Self::Host {
+        host::Philox::new(seed, offset, subsequence)
+    }
+}
+
+impl TestRng for Threefry {
+    type Host = host::Threefry;
+
+    fn host(seed: u32, offset: u32, subsequence: u32) -> Self::Host {
+        host::Threefry::new(seed, offset, subsequence)
+    }
+}
+
+#[cube(launch_unchecked)]
+fn kernel_random_bits<G: CounterRng, N: Size>(
+    output: &mut Array<Vector<u32, N>>,
+    seed: u32,
+    offset: u32,
+) {
+    let mut rng = G::with_subsequence(seed, offset, ABSOLUTE_POS as u32);
+    output[ABSOLUTE_POS * 2] = random_bits::<G, N>(&mut rng);
+    output[ABSOLUTE_POS * 2 + 1] = random_bits::<G, N>(&mut rng);
+}
+
+#[cube(launch_unchecked)]
+fn kernel_uniform<G: CounterRng, N: Size>(
+    output: &mut Array<Vector<f32, N>>,
+    seed: u32,
+    offset: u32,
+) {
+    let mut rng = G::with_subsequence(seed, offset, ABSOLUTE_POS as u32);
+    output[ABSOLUTE_POS * 2] = uniform::<G, f32, N>(&mut rng);
+    output[ABSOLUTE_POS * 2 + 1] = uniform::<G, f32, N>(&mut rng);
+}
+
+#[cube(launch_unchecked)]
+fn kernel_normal<G: CounterRng, N: Size>(
+    output: &mut Array<Vector<f32, N>>,
+    seed: u32,
+    offset: u32,
+) {
+    let mut rng = G::with_subsequence(seed, offset, ABSOLUTE_POS as u32);
+    output[ABSOLUTE_POS * 2] = normal::<G, f32, N>(&mut rng, 1.0, 2.0);
+    output[ABSOLUTE_POS * 2 + 1] = normal::<G, f32, N>(&mut rng, 1.0, 2.0);
+}
+
+#[cube(launch_unchecked)]
+fn kernel_bernoulli<G: CounterRng, N: Size>(
+    output: &mut Array<Vector<f32, N>>,
+    seed: u32,
+    offset: u32,
+) {
+    let mut rng = G::with_subsequence(seed, offset, ABSOLUTE_POS as u32);
+    output[ABSOLUTE_POS * 2] = bernoulli::<G, f32, N>(&mut rng, 0.3);
+    output[ABSOLUTE_POS * 2 + 1] = bernoulli::<G, f32, N>(&mut rng, 0.3);
+}
+
+/// The expected output of a kernel, drawing twice per unit with the host generator.
+fn expected<G: TestRng, T>(mut draw: impl FnMut(&mut G::Host) -> Vec<T>) -> Vec<T> {
+    (0..NUM_UNITS as u32)
+        .flat_map(|unit| {
+            let mut rng = G::host(SEED, OFFSET, unit);
+            (0..NUM_DRAWS)
+                .flat_map(|_| draw(&mut rng))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+pub fn test_random_bits<G: TestRng, R: Runtime>(client: ComputeClient<R>, vector_size: usize) {
+    let len = NUM_UNITS * NUM_DRAWS * vector_size;
+    let output = client.empty(len * size_of::<u32>());
+
+    unsafe {
+        kernel_random_bits::launch_unchecked::<G, R>(
+            &client,
+            CubeCount::new_single(),
+            CubeDim::new_1d(NUM_UNITS as u32),
+            vector_size,
+            ArrayArg::from_raw_parts(output.clone(), len / vector_size),
+            SEED,
+            OFFSET,
+        )
+    }
+
+    let actual = client.read_one_unchecked(output);
+    let actual = u32::from_bytes(&actual);
+    let expected = expected::<G, _>(|rng| host::random_bits(rng, vector_size));
+
+    assert_eq!(actual, expected);
+}
+
+pub fn test_uniform<G: TestRng, R: Runtime>(client: ComputeClient<R>, vector_size: usize) {
+    let len = NUM_UNITS * NUM_DRAWS * vector_size;
+    let output = client.empty(len * size_of::<f32>());
+
+    unsafe {
+        kernel_uniform::launch_unchecked::<G, R>(
+            &client,
+            CubeCount::new_single(),
+            CubeDim::new_1d(NUM_UNITS as u32),
+            vector_size,
+            ArrayArg::from_raw_parts(output.clone(), len / vector_size),
+            SEED,
+            OFFSET,
+        )
+    }
+
+    let actual = client.read_one_unchecked(output);
+    let actual = f32::from_bytes(&actual);
+    let expected = expected::<G, _>(|rng| host::uniform(rng, vector_size));
+
+    assert_eq!(actual, expected);
+}
+
+pub fn test_normal<G: TestRng, R: Runtime>(client: ComputeClient<R>, vector_size: usize) {
+    let len = NUM_UNITS * NUM_DRAWS * vector_size;
+    let output = client.empty(len * size_of::<f32>());
+
+    unsafe {
+        kernel_normal::launch_unchecked::<G, R>(
+            &client,
+            CubeCount::new_single(),
+            CubeDim::new_1d(NUM_UNITS as u32),
+            vector_size,
+            ArrayArg::from_raw_parts(output.clone(), len / vector_size),
+            SEED,
+            OFFSET,
+        )
+    }
+
+    let actual = client.read_one_unchecked(output);
+    let actual = f32::from_bytes(&actual);
+    let expected = expected::<G, _>(|rng| host::normal(rng, vector_size, 1.0, 2.0));
+
+    for (i, (expected, actual)) in expected.iter().zip(actual.iter()).enumerate() {
+        assert!(
+            (expected - actual).abs() < 1e-3,
+            "Value {i} failed: expected {expected}, got {actual}"
+        );
+    }
+}
+
+pub fn test_bernoulli<G: TestRng, R: Runtime>(client: ComputeClient<R>, vector_size: usize) {
+    let len = NUM_UNITS * NUM_DRAWS * vector_size;
+    let output = client.empty(len * size_of::<f32>());
+
+    unsafe {
+        kernel_bernoulli::launch_unchecked::<G, R>(
+            &client,
+            CubeCount::new_single(),
+            CubeDim::new_1d(NUM_UNITS as u32),
+            vector_size,
+            ArrayArg::from_raw_parts(output.clone(), len / vector_size),
+            SEED,
+            OFFSET,
+        )
+    }
+
+    let actual = client.read_one_unchecked(output);
+    let actual = f32::from_bytes(&actual);
+    let expected = expected::<G, _>(|rng| host::bernoulli(rng, vector_size, 0.3));
+
+    assert_eq!(actual, expected);
+}
+
+#[macro_export]
+macro_rules! testgen_random {
+    () => {
+        mod random {
+            use super::*;
+            use $crate::random::{Philox, Threefry};
+            use $crate::tests::random::*;
+
+            macro_rules! test_rng {
+                ($rng: ident, $name: ident, $test: ident) => {
+                    #[$crate::tests::test_log::test]
+                    fn $name() {
+                        let client = TestRuntime::client(&Default::default());
+                        for vector_size in [1, 2, 4] {
+                            $test::<$rng, TestRuntime>(client.clone(), vector_size);
+                        }
+                    }
+                };
+            }
+
+            test_rng!(Philox, test_philox_bits, test_random_bits);
+            test_rng!(Philox, test_philox_uniform, test_uniform);
+            test_rng!(Philox, test_philox_normal, test_normal);
+            test_rng!(Philox, test_philox_bernoulli, test_bernoulli);
+            test_rng!(Threefry, test_threefry_bits, test_random_bits);
+            test_rng!(Threefry, test_threefry_uniform, test_uniform);
+            test_rng!(Threefry, test_threefry_normal, test_normal);
+            test_rng!(Threefry, test_threefry_bernoulli, test_bernoulli);
+        }
+    };
+}
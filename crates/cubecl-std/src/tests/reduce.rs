@@ -0,0 +1,329 @@
+use cubecl::prelude::*;
+use cubecl_core::{self as cubecl, ir::features::Plane};
+
+use crate::{
+    reduce::{self, ReduceDtypes, ReduceOperation, ReduceStrategy},
+    tensor::TensorHandle,
+};
+
+const SHAPE: [usize; 3] = [3, 5, 24];
+
+/// Non-zero multiples of `0.125` in `[-1.125, 1.375]`, so sums are exact.
+fn input_values() -> Vec<f32> {
+    let len = SHAPE.iter().product::<usize>();
+    (0..len)
+        .map(|i| ((i * 7) % 11) as f32 / 4.0 - 1.125)
+        .collect()
+}
+
+/// Host reduction of the input along `axis`, with the arg reductions cast to `f32`.
+fn reduce_cpu(input: &[f32], axis: usize, operation: ReduceOperation) -> Vec<f32> {
+    let strides = [SHAPE[1] * SHAPE[2], SHAPE[2], 1];
+    let mut out_shape = SHAPE;
+    out_shape[axis] = 1;
+
+    let mut output = Vec::new();
+    for i in 0..out_shape[0] {
+        for j in 0..out_shape[1] {
+            for k in 0..out_shape[2] {
+                let offset = i * strides[0] + j * strides[1] + k * strides[2];
+                let values = (0..SHAPE[axis])
+                    .map(|n| input[offset + n * strides[axis]])
+                    .collect::<Vec<_>>();
+                output.push(reduce_values(&values, operation));
+            }
+        }
+    }
+    output
+}
+
+fn reduce_values(values: &[f32], operation: ReduceOperation) -> f32 {
+    let count = values.len() as f32;
+    let mean = values.iter().sum::<f32>() / count;
+    let position = |target: f32| values.iter().position(|v| *v == target).unwrap() as f32;
+
+    match operation {
+        ReduceOperation::Sum => values.iter().sum(),
+        ReduceOperation::Prod => values.iter().product(),
+        ReduceOperation::Min => values.iter().copied().fold(f32::MAX, f32::min),
+        ReduceOperation::Max => values.iter().copied().fold(f32::MIN, f32::max),
+        ReduceOperation::ArgMin => position(values.iter().copied().fold(f32::MAX, f32::min)),
+        ReduceOperation::ArgMax => position(values.iter().copied().fold(f32::MIN, f32::max)),
+        ReduceOperation::Mean => mean,
+        ReduceOperation::Variance => values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / count,
+    }
+}
+
+fn run_reduce<R: Runtime>(
+    client: &ComputeClient<R>,
+    input: &[f32],
+    axis: usize,
+    operation: ReduceOperation,
+    strategy: Option<ReduceStrategy>,
+) -> Vec<f32> {
+    let input = client.create_from_slice(f32::as_bytes(input));
+    let input = TensorHandle::<R>::new_contiguous(
+        SHAPE,
+        input,
+        f32::as_type_native_unchecked().storage_type(),
+    );
+
+    let mut out_shape = SHAPE;
+    out_shape[axis] = 1;
+    let out_dtype = match operation.is_arg() {
+        true => u32::as_type_native_unchecked().storage_type(),
+        false => f32::as_type_native_unchecked().storage_type(),
+    };
+    let output = TensorHandle::<R>::empty(client, out_shape.to_vec(), out_dtype);
+
+    let dtypes = ReduceDtypes {
+        output: out_dtype,
+        ..ReduceDtypes::new(f32::as_type_native_unchecked().storage_type())
+    };
+    let result = match strategy {
+        Some(strategy) => reduce::launch_reduce(
+            client,
+            input.binding(),
+            output.clone().binding(),
+            axis,
+            operation,
+            dtypes,
+            strategy,
+        ),
+        // The tests always run on the default device.
+        None => reduce::reduce(
+            client,
+            &Default::default(),
+            input.binding(),
+            output.clone().binding(),
+            axis,
+            operation,
+            dtypes,
+        ),
+    };
+    result.unwrap();
+
+    let actual = client.read_one_unchecked_tensor(output.handle.clone().copy_descriptor(
+        output.shape().clone(),
+        output.strides().clone(),
+        size_of::<f32>(),
+    ));
+    match operation.is_arg() {
+        true => u32::from_bytes(&actual).iter().map(|i| *i as f32).collect(),
+        false => f32::from_bytes(&actual).to_vec(),
+    }
+}
+
+fn assert_reduce<R: Runtime>(
+    client: &ComputeClient<R>,
+    operation: ReduceOperation,
+    strategy: Option<ReduceStrategy>,
+) {
+    for axis in 0..SHAPE.len() {
+        let expected = reduce_cpu(&input_values(), axis, operation);
+        let actual = run_reduce(client, &input_values(), axis, operation, strategy);
+        assert_close(&expected, &actual, 1e-4, || {
+            format!("{operation:?} with {strategy:?} on axis {axis}")
+        });
+    }
+}
+
+fn assert_close(expected: &[f32], actual: &[f32], tolerance: f32, context: impl Fn() -> String) {
+    for (i, (expected, actual)) in expected.iter().zip(actual.iter()).enumerate() {
+        assert!(
+            (expected - actual).abs() <= tolerance * expected.abs().max(1.0),
+            "{} failed at {i}: expected {expected}, got {actual}",
+            context()
+        );
+    }
+}
+
+pub fn test_reduce<R: Runtime>(
+    client: ComputeClient<R>,
+    operation: ReduceOperation,
+    strategy: ReduceStrategy,
+) {
+    if strategy.requires_planes() && !client.features().plane.contains(Plane::Ops) {
+        return;
+    }
+
+    assert_reduce(&client, operation, Some(strategy));
+}
+
+pub fn test_reduce_autotune<R: Runtime>(client: ComputeClient<R>, operation: ReduceOperation) {
+    assert_reduce(&client, operation, None);
+}
+
+/// The variance of values with a large offset, which loses all precision when computed from the
+/// sum of squares in `f32`.
+pub fn test_reduce_variance_offset<R: Runtime>(client: ComputeClient<R>, strategy: ReduceStrategy) {
+    if strategy.requires_planes() && !client.features().plane.contains(Plane::Ops) {
+        return;
+    }
+
+    const OFFSET: f32 = 1000.0;
+    let input = input_values()
+        .iter()
+        .map(|value| value + OFFSET)
+        .collect::<Vec<_>>();
+
+    for axis in 0..SHAPE.len() {
+        // The variance doesn't depend on the offset.
+        let expected = reduce_cpu(&input_values(), axis, ReduceOperation::Variance);
+        let actual = run_reduce(
+            &client,
+            &input,
+            axis,
+            ReduceOperation::Variance,
+            Some(strategy),
+        );
+        assert_close(&expected, &actual, 1e-3, || {
+            format!("Variance with offset with {strategy:?} on axis {axis}")
+        });
+    }
+}
+
+pub fn test_reduce_invalid_axis<R: Runtime>(client: ComputeClient<R>) {
+    let input = TensorHandle::<R>::empty(
+        &client,
+        SHAPE.to_vec(),
+        f32::as_type_native_unchecked().storage_type(),
+    );
+    let output = TensorHandle::<R>::empty(
+        &client,
+        SHAPE.to_vec(),
+        f32::as_type_native_unchecked().storage_type(),
+    );
+
+    let result = reduce::launch_reduce(
+        &client,
+        input.binding(),
+        output.binding(),
+        3,
+        ReduceOperation::Sum,
+        ReduceDtypes::new(f32::as_type_native_unchecked().storage_type()),
+        ReduceStrategy::Unit,
+    );
+
+    assert_eq!(
+        result,
+        Err(reduce::ReduceError::InvalidAxis { axis: 3, rank: 3 })
+    );
+}
+
+#[macro_export]
+macro_rules! testgen_reduce {
+    () => {
+        mod reduce {
+            use super::*;
+            use $crate::reduce::{ReduceOperation, ReduceStrategy};
+            use $crate::tests::reduce::*;
+
+            macro_rules! test_operation {
+                ($name: ident, $operation: ident) => {
+                    mod $name {
+                        use super::*;
+
+                        #[$crate::tests::test_log::test]
+                        fn unit() {
+                            let client = TestRuntime::client(&Default::default());
+                            test_reduce::<TestRuntime>(
+                                client,
+                                ReduceOperation::$operation,
+                                ReduceStrategy::Unit,
+                            );
+                        }
+
+                        #[$crate::tests::test_log::test]
+                        fn plane() {
+                            let client = TestRuntime::client(&Default::default());
+                            test_reduce::<TestRuntime>(
+                                client,
+                                ReduceOperation::$operation,
+                                ReduceStrategy::Plane,
+                            );
+                        }
+
+                        #[$crate::tests::test_log::test]
+                        fn cube_planes() {
+                            let client = TestRuntime::client(&Default::default());
+                            test_reduce::<TestRuntime>(
+                                client,
+                                ReduceOperation::$operation,
+                                ReduceStrategy::Cube { use_planes: true },
+                            );
+                        }
+
+                        #[$crate::tests::test_log::test]
+                        fn cube_shared() {
+                            let client = TestRuntime::client(&Default::default());
+                            test_reduce::<TestRuntime>(
+                                client,
+                                ReduceOperation::$operation,
+                                ReduceStrategy::Cube { use_planes: false },
+                            );
+                        }
+
+                        #[$crate::tests::test_log::test]
+                        fn autotune() {
+                            let client = TestRuntime::client(&Default::default());
+                            test_reduce_autotune::<TestRuntime>(
+                                client,
+                                ReduceOperation::$operation,
+                            );
+                        }
+                    }
+                };
+            }
+
+            test_operation!(sum, Sum);
+            test_operation!(prod, Prod);
+            test_operation!(min, Min);
+            test_operation!(max, Max);
+            test_operation!(argmin, ArgMin);
+            test_operation!(argmax, ArgMax);
+            test_operation!(mean, Mean);
+            test_operation!(variance, Variance);
+
+            mod variance_offset {
+                use super::*;
+
+                #[$crate::tests::test_log::test]
+                fn unit() {
+                    let client = TestRuntime::client(&Default::default());
+                    test_reduce_variance_offset::<TestRuntime>(client, ReduceStrategy::Unit);
+                }
+
+                #[$crate::tests::test_log::test]
+                fn plane() {
+                    let client = TestRuntime::client(&Default::default());
+                    test_reduce_variance_offset::<TestRuntime>(client, ReduceStrategy::Plane);
+                }
+
+                #[$crate::tests::test_log::test]
+                fn cube_planes() {
+                    let client = TestRuntime::client(&Default::default());
+                    test_reduce_variance_offset::<TestRuntime>(
+                        client,
+                        ReduceStrategy::Cube { use_planes: true },
+                    );
+                }
+
+                #[$crate::tests::test_log::test]
+                fn cube_shared() {
+                    let client = TestRuntime::client(&Default::default());
+                    test_reduce_variance_offset::<TestRuntime>(
+                        client,
+                        ReduceStrategy::Cube { use_planes: false },
+                    );
+                }
+            }
+
+            #[$crate::tests::test_log::test]
+            fn test_reduce_invalid_axis() {
+                let client = TestRuntime::client(&Default::default());
+                $crate::tests::reduce::test_reduce_invalid_axis::<TestRuntime>(client);
+            }
+        }
+    };
+}
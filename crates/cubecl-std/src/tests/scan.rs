@@ -0,0 +1,468 @@
+use cubecl::prelude::*;
+use cubecl_core::{self as cubecl, ir::features::Plane};
+
+use crate::{
+    scan::{
+        self, AddOp, GreaterThan, LessThan, MaxOp, MinOp, MulOp, ScanKind, ScanOperator,
+        ScanStrategy,
+    },
+    tensor::TensorHandle,
+};
+
+/// Long enough to need two levels of recursion with the small test strategy.
+pub const LEN: usize = 5000;
+
+/// An empty and a single element input, then lengths that aren't a multiple of the cube size of
+/// either the small test strategy or the default strategy, the last one needing a recursion.
+pub const EDGE_LENS: [usize; 4] = [0, 1, 100, 4097];
+
+fn input_values(len: usize) -> Vec<i32> {
+    (0..len).map(|i| ((i * 7) % 11) as i32 - 5).collect()
+}
+
+fn i32_dtype() -> StorageType {
+    i32::as_type_native_unchecked().storage_type()
+}
+
+fn u32_dtype() -> StorageType {
+    u32::as_type_native_unchecked().storage_type()
+}
+
+fn tensor_from<R: Runtime, E: CubeElement>(
+    client: &ComputeClient<R>,
+    values: &[E],
+    dtype: StorageType,
+) -> TensorHandle<R> {
+    let handle = client.create_from_slice(E::as_bytes(values));
+    TensorHandle::new_contiguous([values.len()], handle, dtype)
+}
+
+fn read_values<R: Runtime, E: CubeElement>(
+    client: &ComputeClient<R>,
+    tensor: &TensorHandle<R>,
+) -> Vec<E> {
+    E::from_bytes(&client.read_one_unchecked(tensor.handle.clone())).to_vec()
+}
+
+/// Host scan with the same order of operations as a sequential scan.
+fn scan_cpu(
+    input: &[i32],
+    kind: ScanKind,
+    identity: i32,
+    combine: fn(i32, i32) -> i32,
+) -> Vec<i32> {
+    let mut acc = identity;
+    input
+        .iter()
+        .map(|value| {
+            let before = acc;
+            acc = combine(acc, *value);
+            match kind {
+                ScanKind::Inclusive => acc,
+                ScanKind::Exclusive => before,
+            }
+        })
+        .collect()
+}
+
+fn assert_scan<R: Runtime, O: ScanOperator>(
+    client: &ComputeClient<R>,
+    input: &[i32],
+    kind: ScanKind,
+    strategy: Option<ScanStrategy>,
+    identity: i32,
+    combine: fn(i32, i32) -> i32,
+) {
+    let input_tensor = tensor_from(client, input, i32_dtype());
+    let output = TensorHandle::<R>::empty(client, vec![input.len()], i32_dtype());
+
+    let result = match strategy {
+        Some(strategy) => scan::launch_scan::<R, O>(
+            client,
+            input_tensor.binding(),
+            output.clone().binding(),
+            kind,
+            i32_dtype(),
+            strategy,
+        ),
+        None => scan::scan::<R, O>(
+            client,
+            input_tensor.binding(),
+            output.clone().binding(),
+            kind,
+            i32_dtype(),
+        ),
+    };
+    result.unwrap();
+
+    let expected = scan_cpu(input, kind, identity, combine);
+    let actual = read_values::<R, i32>(client, &output);
+    assert_eq!(
+        actual,
+        expected,
+        "{kind:?} scan of {} elements with {strategy:?} failed",
+        input.len()
+    );
+}
+
+/// A small strategy, so the test input spans many blocks.
+fn test_strategy(use_planes: bool) -> ScanStrategy {
+    ScanStrategy {
+        use_planes,
+        cube_size: 64,
+        items_per_unit: 1,
+    }
+}
+
+fn skip_planes<R: Runtime>(client: &ComputeClient<R>, use_planes: bool) -> bool {
+    use_planes && !client.features().plane.contains(Plane::Ops)
+}
+
+pub fn test_scan_add<R: Runtime>(client: ComputeClient<R>, kind: ScanKind, use_planes: bool) {
+    if skip_planes(&client, use_planes) {
+        return;
+    }
+
+    let strategy = Some(test_strategy(use_planes));
+    assert_scan::<R, AddOp>(&client, &input_values(LEN), kind, strategy, 0, |a, b| a + b);
+}
+
+pub fn test_scan_mul<R: Runtime>(client: ComputeClient<R>, kind: ScanKind, use_planes: bool) {
+    if skip_planes(&client, use_planes) {
+        return;
+    }
+
+    // Only signs, so the product doesn't overflow.
+    let input = input_values(LEN)
+        .iter()
+        .map(|value| if *value < 0 { -1 } else { 1 })
+        .collect::<Vec<_>>();
+    let strategy = Some(test_strategy(use_planes));
+    assert_scan::<R, MulOp>(&client, &input, kind, strategy, 1, |a, b| a * b);
+}
+
+pub fn test_scan_min_max<R: Runtime>(client: ComputeClient<R>, kind: ScanKind, use_planes: bool) {
+    if skip_planes(&client, use_planes) {
+        return;
+    }
+
+    // Trend downwards then upwards, so the running minimum and maximum keep changing.
+    let input = input_values(LEN)
+        .iter()
+        .enumerate()
+        .map(|(i, value)| value + (i as i32 - LEN as i32 / 2).abs() / 10)
+        .collect::<Vec<_>>();
+    let strategy = Some(test_strategy(use_planes));
+    assert_scan::<R, MinOp>(&client, &input, kind, strategy, i32::MAX, i32::min);
+    assert_scan::<R, MaxOp>(&client, &input, kind, strategy, i32::MIN, i32::max);
+}
+
+/// Keeps the last non-zero value, which is associative but not commutative, so any operand swapped
+/// by the plane shuffles or when combining the prefix of earlier blocks changes the result.
+struct LastNonZeroOp;
+
+#[cube]
+impl ScanOperator for LastNonZeroOp {
+    fn identity<T: Numeric>() -> T {
+        T::from_int(0)
+    }
+
+    fn combine<T: Numeric>(lhs: T, rhs: T) -> T {
+        select(rhs != T::from_int(0), rhs, lhs)
+    }
+}
+
+pub fn test_scan_non_commutative<R: Runtime>(
+    client: ComputeClient<R>,
+    kind: ScanKind,
+    use_planes: bool,
+) {
+    if skip_planes(&client, use_planes) {
+        return;
+    }
+
+    // Runs of a few distinct values within a plane, separated by gaps longer than a block.
+    let input = (0..LEN)
+        .map(|i| if i % 90 < 3 { i as i32 + 1 } else { 0 })
+        .collect::<Vec<_>>();
+    let strategy = Some(test_strategy(use_planes));
+    assert_scan::<R, LastNonZeroOp>(&client, &input, kind, strategy, 0, |a, b| {
+        if b != 0 { b } else { a }
+    });
+}
+
+pub fn test_scan_edge_lengths<R: Runtime>(
+    client: ComputeClient<R>,
+    kind: ScanKind,
+    use_planes: bool,
+) {
+    if skip_planes(&client, use_planes) {
+        return;
+    }
+
+    let strategy = Some(test_strategy(use_planes));
+    for len in EDGE_LENS {
+        assert_scan::<R, AddOp>(&client, &input_values(len), kind, strategy, 0, |a, b| a + b);
+    }
+}
+
+pub fn test_scan_default<R: Runtime>(client: ComputeClient<R>, kind: ScanKind) {
+    assert_scan::<R, AddOp>(&client, &input_values(LEN), kind, None, 0, |a, b| a + b);
+}
+
+pub fn test_scan_mismatched_size<R: Runtime>(client: ComputeClient<R>) {
+    let input = TensorHandle::<R>::empty(&client, vec![LEN], i32_dtype());
+    let output = TensorHandle::<R>::empty(&client, vec![LEN - 1], i32_dtype());
+
+    let result = scan::scan::<R, AddOp>(
+        &client,
+        input.binding(),
+        output.binding(),
+        ScanKind::Inclusive,
+        i32_dtype(),
+    );
+
+    assert_eq!(
+        result,
+        Err(scan::ScanError::MismatchedSize {
+            expected: LEN,
+            actual: LEN - 1
+        })
+    );
+}
+
+/// Run a compaction of `len` elements and return the output and the number of selected elements.
+fn run_compaction<R: Runtime>(
+    client: &ComputeClient<R>,
+    len: usize,
+    launch: impl FnOnce(TensorBinding<R>, TensorBinding<R>, TensorBinding<R>),
+) -> (Vec<i32>, usize) {
+    let input = tensor_from(client, &input_values(len), i32_dtype());
+    let output = tensor_from(client, &vec![i32::MIN; len], i32_dtype());
+    // Nothing is written for an empty input, so the count starts at zero.
+    let num_selected = tensor_from(client, &[0u32], u32_dtype());
+
+    launch(
+        input.binding(),
+        output.clone().binding(),
+        num_selected.clone().binding(),
+    );
+
+    let num_selected = read_values::<R, u32>(client, &num_selected)[0] as usize;
+    (read_values::<R, i32>(client, &output), num_selected)
+}
+
+pub fn test_compact<R: Runtime>(client: ComputeClient<R>, len: usize) {
+    // Any non-zero flag selects the element.
+    let flags = (0..len)
+        .map(|i| if i % 3 == 0 { (i % 5) as u32 + 1 } else { 0 })
+        .collect::<Vec<_>>();
+    let flags_tensor = tensor_from(&client, &flags, u32_dtype());
+
+    let (actual, num_selected) = run_compaction(&client, len, |input, output, num_selected| {
+        scan::compact(
+            &client,
+            input,
+            flags_tensor.binding(),
+            output,
+            num_selected,
+            i32_dtype(),
+        )
+        .unwrap()
+    });
+
+    let expected = input_values(len)
+        .into_iter()
+        .zip(flags)
+        .filter(|(_, flag)| *flag != 0)
+        .map(|(value, _)| value)
+        .collect::<Vec<_>>();
+    assert_eq!(num_selected, expected.len());
+    assert_eq!(&actual[..num_selected], expected.as_slice());
+    assert!(
+        actual[num_selected..]
+            .iter()
+            .all(|value| *value == i32::MIN)
+    );
+}
+
+pub fn test_select_if<R: Runtime>(client: ComputeClient<R>, len: usize) {
+    let (actual, num_selected) = run_compaction(&client, len, |input, output, num_selected| {
+        scan::select_if::<R, GreaterThan>(
+            &client,
+            input,
+            InputScalar::new(1, i32_dtype()),
+            output,
+            num_selected,
+            i32_dtype(),
+        )
+        .unwrap()
+    });
+
+    let expected = input_values(len)
+        .into_iter()
+        .filter(|value| *value > 1)
+        .collect::<Vec<_>>();
+    assert_eq!(num_selected, expected.len());
+    assert_eq!(&actual[..num_selected], expected.as_slice());
+}
+
+pub fn test_partition<R: Runtime>(client: ComputeClient<R>, len: usize) {
+    let (actual, num_selected) = run_compaction(&client, len, |input, output, num_selected| {
+        scan::partition::<R, LessThan>(
+            &client,
+            input,
+            InputScalar::new(0, i32_dtype()),
+            output,
+            num_selected,
+            i32_dtype(),
+        )
+        .unwrap()
+    });
+
+    let (mut expected, rejected): (Vec<_>, Vec<_>) =
+        input_values(len).into_iter().partition(|value| *value < 0);
+    assert_eq!(num_selected, expected.len());
+    expected.extend(rejected);
+    assert_eq!(actual, expected);
+}
+
+#[macro_export]
+macro_rules! testgen_scan {
+    () => {
+        mod scan {
+            use super::*;
+            use $crate::scan::ScanKind;
+            use $crate::tests::scan::*;
+
+            macro_rules! test_kind {
+                ($name: ident, $kind: ident) => {
+                    mod $name {
+                        use super::*;
+
+                        #[$crate::tests::test_log::test]
+                        fn add_planes() {
+                            let client = TestRuntime::client(&Default::default());
+                            test_scan_add::<TestRuntime>(client, ScanKind::$kind, true);
+                        }
+
+                        #[$crate::tests::test_log::test]
+                        fn add_shared() {
+                            let client = TestRuntime::client(&Default::default());
+                            test_scan_add::<TestRuntime>(client, ScanKind::$kind, false);
+                        }
+
+                        #[$crate::tests::test_log::test]
+                        fn mul_planes() {
+                            let client = TestRuntime::client(&Default::default());
+                            test_scan_mul::<TestRuntime>(client, ScanKind::$kind, true);
+                        }
+
+                        #[$crate::tests::test_log::test]
+                        fn mul_shared() {
+                            let client = TestRuntime::client(&Default::default());
+                            test_scan_mul::<TestRuntime>(client, ScanKind::$kind, false);
+                        }
+
+                        #[$crate::tests::test_log::test]
+                        fn min_max_planes() {
+                            let client = TestRuntime::client(&Default::default());
+                            test_scan_min_max::<TestRuntime>(client, ScanKind::$kind, true);
+                        }
+
+                        #[$crate::tests::test_log::test]
+                        fn min_max_shared() {
+                            let client = TestRuntime::client(&Default::default());
+                            test_scan_min_max::<TestRuntime>(client, ScanKind::$kind, false);
+                        }
+
+                        #[$crate::tests::test_log::test]
+                        fn non_commutative_planes() {
+                            let client = TestRuntime::client(&Default::default());
+                            test_scan_non_commutative::<TestRuntime>(client, ScanKind::$kind, true);
+                        }
+
+                        #[$crate::tests::test_log::test]
+                        fn non_commutative_shared() {
+                            let client = TestRuntime::client(&Default::default());
+                            test_scan_non_commutative::<TestRuntime>(
+                                client,
+                                ScanKind::$kind,
+                                false,
+                            );
+                        }
+
+                        #[$crate::tests::test_log::test]
+                        fn edge_lengths_planes() {
+                            let client = TestRuntime::client(&Default::default());
+                            test_scan_edge_lengths::<TestRuntime>(client, ScanKind::$kind, true);
+                        }
+
+                        #[$crate::tests::test_log::test]
+                        fn edge_lengths_shared() {
+                            let client = TestRuntime::client(&Default::default());
+                            test_scan_edge_lengths::<TestRuntime>(client, ScanKind::$kind, false);
+                        }
+
+                        #[$crate::tests::test_log::test]
+                        fn default_strategy() {
+                            let client = TestRuntime::client(&Default::default());
+                            test_scan_default::<TestRuntime>(client, ScanKind::$kind);
+                        }
+                    }
+                };
+            }
+
+            test_kind!(inclusive, Inclusive);
+            test_kind!(exclusive, Exclusive);
+
+            #[$crate::tests::test_log::test]
+            fn test_scan_mismatched_size() {
+                let client = TestRuntime::client(&Default::default());
+                $crate::tests::scan::test_scan_mismatched_size::<TestRuntime>(client);
+            }
+
+            #[$crate::tests::test_log::test]
+            fn test_compact() {
+                let client = TestRuntime::client(&Default::default());
+                $crate::tests::scan::test_compact::<TestRuntime>(client, LEN);
+            }
+
+            #[$crate::tests::test_log::test]
+            fn test_compact_edge_lengths() {
+                let client = TestRuntime::client(&Default::default());
+                for len in EDGE_LENS {
+                    $crate::tests::scan::test_compact::<TestRuntime>(client.clone(), len);
+                }
+            }
+
+            #[$crate::tests::test_log::test]
+            fn test_select_if() {
+                let client = TestRuntime::client(&Default::default());
+                $crate::tests::scan::test_select_if::<TestRuntime>(client, LEN);
+            }
+
+            #[$crate::tests::test_log::test]
+            fn test_select_if_edge_lengths() {
+                let client = TestRuntime::client(&Default::default());
+                for len in EDGE_LENS {
+                    $crate::tests::scan::test_select_if::<TestRuntime>(client.clone(), len);
+                }
+            }
+
+            #[$crate::tests::test_log::test]
+            fn test_partition() {
+                let client = TestRuntime::client(&Default::default());
+                $crate::tests::scan::test_partition::<TestRuntime>(client, LEN);
+            }
+
+            #[$crate::tests::test_log::test]
+            fn test_partition_edge_lengths() {
+                let client = TestRuntime::client(&Default::default());
+                for len in EDGE_LENS {
+                    $crate::tests::scan::test_partition::<TestRuntime>(client.clone(), len);
+                }
+            }
+        }
+    };
+}
@@ -6,6 +6,8 @@ fn main() {
     cfg_aliases! {
         exclusive_memory_only: { any(feature = "exclusive-memory-only", target_family = "wasm") },
         apple_silicon: { all(target_os = "macos", target_arch = "aarch64") },
+        // The kernel cache requires std with OS-level filesystem access.
+        std_io: { all(feature = "std", any(target_os = "windows", target_os = "linux", target_os = "macos", target_os = "android")) },
     }
 
     // Automatically enable spirv-dump if an output path is set
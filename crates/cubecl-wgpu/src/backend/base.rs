@@ -3,7 +3,8 @@ use crate::AutoRepresentationRef;
 use crate::WgpuServer;
 use cubecl_core::MemoryConfiguration;
 use cubecl_core::{
-    ExecutionMode, WgpuCompilationOptions, hash::StableHash, server::KernelArguments,
+    ExecutionMode, WgpuCompilationOptions, hash::StableHash, prelude::Visibility,
+    server::KernelArguments,
 };
 use cubecl_ir::DeviceProperties;
 use cubecl_runtime::{compiler::CompilationError, id::KernelId};
@@ -36,12 +37,40 @@ impl WgpuServer {
         kernel_id: &KernelId,
         bindings: &KernelArguments,
         mode: ExecutionMode,
-    ) -> Result<Option<Result<Arc<ComputePipeline>, (u64, StableHash)>>, CompilationError> {
+    ) -> Result<Option<Result<Arc<ComputePipeline>, StableHash>>, CompilationError> {
+        #[cfg(std_io)]
+        if let Some(cache) = &self.source_cache {
+            let key = cache.key(kernel_id);
+            let Some(entry) = cache.get(&key) else {
+                return Ok(Some(Err(key)));
+            };
+            log::trace!("Using the kernel source cache");
+
+            // Same layouts as `wgsl::bindings` and `cpp_metal::bindings`
+            let (module, uniform_info) = match self.backend {
+                #[cfg(all(feature = "msl", target_os = "macos"))]
+                wgpu::Backend::Metal => (
+                    self.create_msl_module(&entry.entrypoint_name, &entry.source, entry.cube_dim),
+                    bindings.info.dynamic_metadata_offset >= bindings.info.data.len(),
+                ),
+                _ => (self.create_wgsl_module(&entry.source, mode)?, false),
+            };
+            let info = (!bindings.info.data.is_empty()).then_some(Visibility::Read);
+            let layout = (entry.buffers.clone(), info, uniform_info);
+            let pipeline = self.create_pipeline_with_layout(
+                &entry.entrypoint_name,
+                Some(layout),
+                false,
+                module,
+            );
+            return Ok(Some(Ok(pipeline)));
+        }
+
         #[cfg(not(feature = "spirv"))]
         let res = Ok(None);
         #[cfg(feature = "spirv")]
         let res = if let Some(cache) = &self.spirv_cache {
-            let key = (self.utilities.properties_hash, kernel_id.stable_hash());
+            let key = cache.key(kernel_id);
             if let Some(entry) = cache.get(&key) {
                 log::trace!("Using SPIR-V cache");
 
@@ -67,10 +96,6 @@ impl WgpuServer {
         source: &str,
         mode: ExecutionMode,
     ) -> Result<ShaderModule, CompilationError> {
-        #[allow(unused_assignments)]
-        #[cfg(not(target_family = "wasm"))]
-        let mut error_scope = None;
-
         match repr {
             #[cfg(feature = "spirv")]
             Some(AutoRepresentationRef::SpirV(repr)) => unsafe {
@@ -83,60 +108,85 @@ impl WgpuServer {
                 ))
             },
             #[cfg(all(feature = "msl", target_os = "macos"))]
-            Some(AutoRepresentationRef::Msl(repr)) => unsafe {
-                Ok(self.device.create_shader_module_passthrough(
-                    wgpu::ShaderModuleDescriptorPassthrough {
-                        label: Some(entrypoint_name),
-                        msl: Some(Cow::Borrowed(source)),
-                        num_workgroups: (repr.cube_dim.x, repr.cube_dim.y, repr.cube_dim.z),
-                        ..Default::default()
-                    },
-                ))
-            },
+            Some(AutoRepresentationRef::Msl(repr)) => Ok(self.create_msl_module(
+                entrypoint_name,
+                source,
+                (repr.cube_dim.x, repr.cube_dim.y, repr.cube_dim.z),
+            )),
             _ => {
                 let _ = entrypoint_name; // otherwise unused
-                let checks = wgpu::ShaderRuntimeChecks {
-                    // Cube does not need wgpu bounds checks - OOB behaviour is instead
-                    // checked by cube (if enabled).
-                    // This is because the WebGPU specification only makes loose guarantees that Cube can't rely on.
-                    bounds_checks: false,
-                    // Loop bounds are only checked in checked mode.
-                    force_loop_bounding: mode == ExecutionMode::Checked,
-                    ..wgpu::ShaderRuntimeChecks::unchecked()
-                };
-
-                #[cfg(not(target_family = "wasm"))]
-                {
-                    error_scope = Some(self.device.push_error_scope(wgpu::ErrorFilter::Validation));
-                }
-
-                // SAFETY: Cube guarantees OOB safety when launching in checked mode. Launching in unchecked mode
-                // is only available through the use of unsafe code.
-                let module = unsafe {
-                    self.device.create_shader_module_trusted(
-                        ShaderModuleDescriptor {
-                            label: None,
-                            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(source)),
-                        },
-                        checks,
-                    )
-                };
-
-                #[cfg(not(target_family = "wasm"))]
-                if let Some(scope) = error_scope
-                    && let Some(err) = cubecl_common::future::block_on(scope.pop())
-                {
-                    return Err(CompilationError::Generic {
-                        reason: format!("{err}"),
-                        backtrace: cubecl_common::backtrace::BackTrace::capture(),
-                    });
-                }
-
-                Ok(module)
+                self.create_wgsl_module(source, mode)
             }
         }
     }
 
+    #[cfg(all(feature = "msl", target_os = "macos"))]
+    fn create_msl_module(
+        &self,
+        entrypoint_name: &str,
+        source: &str,
+        num_workgroups: (u32, u32, u32),
+    ) -> ShaderModule {
+        unsafe {
+            self.device
+                .create_shader_module_passthrough(wgpu::ShaderModuleDescriptorPassthrough {
+                    label: Some(entrypoint_name),
+                    msl: Some(Cow::Borrowed(source)),
+                    num_workgroups,
+                    ..Default::default()
+                })
+        }
+    }
+
+    fn create_wgsl_module(
+        &self,
+        source: &str,
+        mode: ExecutionMode,
+    ) -> Result<ShaderModule, CompilationError> {
+        #[allow(unused_assignments)]
+        #[cfg(not(target_family = "wasm"))]
+        let mut error_scope = None;
+
+        let checks = wgpu::ShaderRuntimeChecks {
+            // Cube does not need wgpu bounds checks - OOB behaviour is instead
+            // checked by cube (if enabled).
+            // This is because the WebGPU specification only makes loose guarantees that Cube can't rely on.
+            bounds_checks: false,
+            // Loop bounds are only checked in checked mode.
+            force_loop_bounding: mode == ExecutionMode::Checked,
+            ..wgpu::ShaderRuntimeChecks::unchecked()
+        };
+
+        #[cfg(not(target_family = "wasm"))]
+        {
+            error_scope = Some(self.device.push_error_scope(wgpu::ErrorFilter::Validation));
+        }
+
+        // SAFETY: Cube guarantees OOB safety when launching in checked mode. Launching in unchecked mode
+        // is only available through the use of unsafe code.
+        let module = unsafe {
+            self.device.create_shader_module_trusted(
+                ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(source)),
+                },
+                checks,
+            )
+        };
+
+        #[cfg(not(target_family = "wasm"))]
+        if let Some(scope) = error_scope
+            && let Some(err) = cubecl_common::future::block_on(scope.pop())
+        {
+            return Err(CompilationError::Generic {
+                reason: format!("{err}"),
+                backtrace: cubecl_common::backtrace::BackTrace::capture(),
+            });
+        }
+
+        Ok(module)
+    }
+
     #[allow(unused_variables)]
     pub fn create_pipeline(
         &self,
@@ -153,7 +203,22 @@ impl WgpuServer {
             Some(AutoRepresentationRef::SpirV(repr)) => Some(vulkan::bindings(repr, bindings)),
             _ => None,
         };
+        // The buffer WGSL kernels print to is bound last.
+        let printf =
+            matches!(repr, Some(AutoRepresentationRef::Wgsl(repr)) if !repr.prints.is_empty());
 
+        self.create_pipeline_with_layout(entrypoint_name, bindings_info, printf, module)
+    }
+
+    /// Creates a pipeline binding the buffers, the info and the printf buffer if `printf` is set.
+    fn create_pipeline_with_layout(
+        &self,
+        entrypoint_name: &str,
+        bindings_info: Option<(Vec<Visibility>, Option<Visibility>, bool)>,
+        printf: bool,
+        module: ShaderModule,
+    ) -> Arc<ComputePipeline> {
+        let printf = printf.then_some(BufferBindingType::Storage { read_only: false });
         let layout = bindings_info.map(|bindings| {
             let (mut bindings, info, uniform_info) = bindings;
             // When slices are shared, it needs to be read-write if ANY of the slices is read-write,
@@ -173,6 +238,7 @@ impl WgpuServer {
                     read_only: matches!(visibility, cubecl_runtime::kernel::Visibility::Read),
                 })
                 .chain(info)
+                .chain(printf)
                 .enumerate()
                 .map(|(i, ty)| BindGroupLayoutEntry {
                     binding: i as u32,
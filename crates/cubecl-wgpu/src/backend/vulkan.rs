@@ -340,6 +340,11 @@ fn register_types(props: &mut DeviceProperties, ext_feat: &ExtendedFeatures<'_>)
                 ElemType::UInt(UIntKind::U8),
                 TypeUsage::maybe_store(storage8),
             );
+            // Packed values are held as `u8` and unpacked for arithmetic
+            props.register_type_usage(
+                StorageType::Packed(ElemType::Float(FloatKind::E2M1), 2),
+                TypeUsage::maybe_store(storage8),
+            );
         }
     }
 
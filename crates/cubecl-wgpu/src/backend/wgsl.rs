@@ -87,6 +87,12 @@ pub fn register_types(props: &mut DeviceProperties, adapter: &wgpu::Adapter) {
         );
     }
 
+    // Packed values are unpacked for arithmetic, and stored as bytes of `u32` words
+    props.register_type_usage(
+        StorageType::Packed(ElemType::Float(FloatKind::E2M1), 2),
+        TypeUsage::Conversion | TypeUsage::Arithmetic | TypeUsage::Buffer,
+    );
+
     let feats = adapter.features();
 
     if feats.contains(wgpu::Features::SHADER_INT64) {
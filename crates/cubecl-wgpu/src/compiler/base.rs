@@ -3,13 +3,14 @@ use std::fmt::Display;
 use cubecl_core::{
     Compiler, ExecutionMode, WgpuCompilationOptions,
     ir::StorageType,
-    prelude::{CompiledKernel, KernelDefinition},
+    prelude::{CompiledKernel, KernelDefinition, Visibility},
     server::ComputeServer,
 };
 #[cfg(feature = "msl")]
 use cubecl_cpp::shared::MslComputeKernel;
 use cubecl_runtime::compiler::CompilationError;
 use derive_more::derive::From;
+use serde::{Deserialize, Serialize};
 
 use crate::{WgpuServer, WgslCompiler};
 
@@ -45,6 +46,51 @@ pub enum AutoRepresentationRef<'a> {
     Msl(&'a MslComputeKernel),
 }
 
+/// A WGSL or MSL kernel persisted in the kernel cache, its pipeline is created again from the
+/// source without compiling the kernel.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SourceCacheEntry {
+    pub entrypoint_name: String,
+    pub source: String,
+    /// The visibility of the buffers bound to the kernel.
+    pub buffers: Vec<Visibility>,
+    /// The size of the workgroups, required by MSL passthrough shaders.
+    pub cube_dim: (u32, u32, u32),
+}
+
+impl SourceCacheEntry {
+    /// Creates the entry of a compiled kernel, returns `None` for SPIR-V, which has its own
+    /// cache entry, and for WGSL kernels that print since their formats aren't persisted.
+    pub fn new(
+        entrypoint_name: &str,
+        source: &str,
+        repr: AutoRepresentationRef<'_>,
+    ) -> Option<Self> {
+        let (buffers, cube_dim) = match repr {
+            AutoRepresentationRef::Wgsl(repr) if repr.prints.is_empty() => (
+                repr.buffers.iter().map(|it| it.visibility).collect(),
+                (
+                    repr.workgroup_size.x,
+                    repr.workgroup_size.y,
+                    repr.workgroup_size.z,
+                ),
+            ),
+            #[cfg(feature = "msl")]
+            AutoRepresentationRef::Msl(repr) => (
+                repr.buffers.iter().map(|it| it.vis).collect(),
+                (repr.cube_dim.x, repr.cube_dim.y, repr.cube_dim.z),
+            ),
+            _ => return None,
+        };
+        Some(Self {
+            entrypoint_name: entrypoint_name.to_string(),
+            source: source.to_string(),
+            buffers,
+            cube_dim,
+        })
+    }
+}
+
 #[cfg(feature = "spirv")]
 impl AutoRepresentation {
     pub fn as_spirv(&self) -> Option<&cubecl_spirv::SpirvKernel> {
@@ -115,7 +161,11 @@ impl Compiler for AutoCompiler {
             AutoCompiler::Msl(msl_compiler) => {
                 // override compilation options with cpp compiler options for metal
                 use cubecl_cpp;
-                let compilation_options = cubecl_cpp::shared::CompilationOptions::default();
+                let compilation_options = cubecl_cpp::shared::CompilationOptions {
+                    optimize: compilation_options.optimize,
+                    unroll_loops: compilation_options.unroll_loops,
+                    ..Default::default()
+                };
                 Compiler::compile(msl_compiler, kernel, &compilation_options, mode, addr_type)?
                     .into()
             }
@@ -1,13 +1,16 @@
 use super::Subgroup;
 use super::{ConstantArray, shader::ComputeShader};
-use super::{Item, LocalArray, SharedArray};
+use super::{Item, LocalArray, PrintFormat, SharedArray};
 use crate::compiler::wgsl::{self, SharedValue};
 
 use cubecl_common::backtrace::BackTrace;
 use cubecl_core::prelude::*;
 use cubecl_core::{
     Info,
-    post_processing::{checked_io::CheckedIoProcessor, saturating::SaturatingArithmeticProcessor},
+    post_processing::{
+        checked_io::CheckedIoProcessor, packed::PackedArithmeticProcessor,
+        saturating::SaturatingArithmeticProcessor,
+    },
 };
 use cubecl_core::{
     Metadata, WgpuCompilationOptions,
@@ -18,8 +21,10 @@ use cubecl_core::{
     ir::{Processor, UIntKind},
     post_processing::unroll::UnrollProcessor,
 };
+use cubecl_opt::{Optimizer, UnrollOptions};
 use cubecl_runtime::compiler::CompilationError;
 use cubecl_runtime::kernel;
+use std::collections::HashMap;
 
 pub const MAX_VECTOR_SIZE: usize = 4;
 
@@ -46,11 +51,13 @@ pub struct WgslCompiler {
     shared_values: Vec<SharedValue>,
     const_arrays: Vec<ConstantArray>,
     local_arrays: Vec<LocalArray>,
-    #[allow(dead_code)]
+    prints: Vec<PrintFormat>,
     compilation_options: WgpuCompilationOptions,
     strategy: ExecutionMode,
     subgroup_instructions_used: bool,
     f16_used: bool,
+    /// Buffers of packed values, declared as `u32` words. Maps to whether they're atomic.
+    packed_buffers: HashMap<cube::Id, bool>,
 }
 
 impl core::fmt::Debug for WgslCompiler {
@@ -122,8 +129,29 @@ impl WgslCompiler {
         let metadata = Metadata::new(num_meta as u32, num_ext);
         self.info = Info::new(&value.scalars, metadata, address_type);
 
+        // Neighbouring units may write to the same word, so writable packed buffers are atomic
+        self.packed_buffers = value
+            .buffers
+            .iter()
+            .filter(|binding| matches!(binding.ty.storage_type(), StorageType::Packed(..)))
+            .map(|binding| (binding.id, binding.visibility == Visibility::ReadWrite))
+            .collect();
+
         let address_type = self.compile_storage_type(address_type);
-        let instructions = self.compile_scope(&mut value.body);
+        let instructions = if self.compilation_options.optimize {
+            let mut opt = Optimizer::with_unroll(
+                value.body.clone(),
+                value.cube_dim,
+                vec![],
+                self.processors(),
+                self.compilation_options
+                    .unroll_loops
+                    .then(UnrollOptions::default),
+            );
+            self.compile_scope(&mut opt.structured_scope())
+        } else {
+            self.compile_scope(&mut value.body)
+        };
         let extensions = register_extensions(&instructions);
         let body = wgsl::Body {
             instructions,
@@ -154,6 +182,7 @@ impl WgslCompiler {
             shared_values: self.shared_values.clone(),
             constant_arrays: self.const_arrays.clone(),
             local_arrays: self.local_arrays.clone(),
+            prints: self.prints.clone(),
             static_meta_len: self.info.metadata.static_len() as usize,
             info: self.info.clone(),
             workgroup_size: value.cube_dim,
@@ -213,8 +242,12 @@ impl WgslCompiler {
                 },
                 other => panic!("{other:?} is not a valid WgpuElement"),
             },
-            cube::StorageType::Packed(_, _) => {
-                unimplemented!("Packed types not yet supported in WGSL")
+            // Each packed value is held as its byte in a `u32`
+            cube::StorageType::Packed(cube::ElemType::Float(cube::FloatKind::E2M1), 2) => {
+                wgsl::Elem::U32
+            }
+            cube::StorageType::Packed(ty, factor) => {
+                unimplemented!("Packed type {ty}x{factor} not yet supported in WGSL")
             }
             cube::StorageType::Opaque(ty) => match ty {
                 cube::OpaqueType::Barrier(_) => {
@@ -284,9 +317,13 @@ impl WgslCompiler {
             cube::VariableKind::GlobalOutputArray(id) => {
                 wgsl::Variable::GlobalOutputArray(id, self.compile_type(item))
             }
-            cube::VariableKind::Constant(value) => {
-                wgsl::Variable::Constant(value, self.compile_type(item))
-            }
+            cube::VariableKind::Constant(value) => match item.storage_type() {
+                ty @ cube::StorageType::Packed(..) => wgsl::Variable::Constant(
+                    cube::ConstantValue::UInt(value.packed_bits(ty)),
+                    self.compile_type(item),
+                ),
+                _ => wgsl::Variable::Constant(value, self.compile_type(item)),
+            },
             cube::VariableKind::SharedArray {
                 id,
                 length,
@@ -442,6 +479,18 @@ impl WgslCompiler {
         self.compile_variable(var)
     }
 
+    fn processors(&self) -> Vec<Box<dyn Processor>> {
+        vec![
+            Box::new(PackedArithmeticProcessor::new()),
+            Box::new(UnrollProcessor::new(MAX_VECTOR_SIZE)),
+            Box::new(CheckedIoProcessor::new(
+                self.strategy,
+                self.kernel_name.clone(),
+            )),
+            Box::new(SaturatingArithmeticProcessor::new(true)),
+        ]
+    }
+
     fn compile_scope(&mut self, scope: &mut cube::Scope) -> Vec<wgsl::Instruction> {
         let mut instructions = Vec::new();
 
@@ -460,13 +509,12 @@ impl WgslCompiler {
             .collect::<Vec<_>>();
         self.const_arrays.extend(const_arrays);
 
-        let checked_io: Box<dyn Processor> = Box::new(CheckedIoProcessor::new(
-            self.strategy,
-            self.kernel_name.clone(),
-        ));
-        let unroll = Box::new(UnrollProcessor::new(MAX_VECTOR_SIZE));
-        let saturating = Box::new(SaturatingArithmeticProcessor::new(true));
-        let processing = scope.process([&*unroll, &*checked_io, &*saturating]);
+        // The optimizer already applied the processors
+        let processors = match self.compilation_options.optimize {
+            true => Vec::new(),
+            false => self.processors(),
+        };
+        let processing = scope.process(processors.iter().map(|it| &**it));
 
         for mut var in processing.variables {
             if var.ty.vector_size() > MAX_VECTOR_SIZE {
@@ -516,6 +564,10 @@ impl WgslCompiler {
             cube::Operation::NonSemantic(cube::NonSemantic::Comment { content }) => {
                 self.compile_comment(instructions, content)
             }
+            cube::Operation::NonSemantic(cube::NonSemantic::Print {
+                format_string,
+                args,
+            }) => self.compile_print(instructions, format_string, args),
             cube::Operation::NonSemantic(_) => {}
             cube::Operation::Barrier(_) => {
                 panic!("Barrier isn't supported on wgpu.")
@@ -641,6 +693,7 @@ impl WgslCompiler {
             // No unreachable hint in WGSL
             cube::Branch::Unreachable => instructions.push(wgsl::Instruction::Return),
             cube::Branch::Break => instructions.push(wgsl::Instruction::Break),
+            cube::Branch::Continue => instructions.push(wgsl::Instruction::Continue),
             cube::Branch::RangeLoop(mut range_loop) => {
                 instructions.push(wgsl::Instruction::RangeLoop {
                     i: self.compile_variable(range_loop.i),
@@ -680,6 +733,24 @@ impl WgslCompiler {
         instructions.push(wgsl::Instruction::Comment { content })
     }
 
+    fn compile_print(
+        &mut self,
+        instructions: &mut Vec<wgsl::Instruction>,
+        format_string: String,
+        args: Vec<cube::Variable>,
+    ) {
+        let args = args
+            .into_iter()
+            .map(|arg| self.compile_variable(arg))
+            .collect::<Vec<_>>();
+        let format = PrintFormat::new(format_string, &args);
+
+        if !self.prints.contains(&format) {
+            self.prints.push(format.clone());
+        }
+        instructions.push(wgsl::Instruction::Print { format, args })
+    }
+
     fn compile_metadata(
         &mut self,
         metadata: cube::Metadata,
@@ -1103,10 +1174,52 @@ impl WgslCompiler {
     ) {
         let out = out.unwrap();
         match value {
+            cube::Operator::Cast(op)
+                if op.input.storage_type() != out.storage_type()
+                    && op.input.ty.packing_factor() > 1 =>
+            {
+                assert_eq!(
+                    out.ty.packing_factor(),
+                    1,
+                    "Can't cast between packed types"
+                );
+                instructions.push(wgsl::Instruction::UnpackE2M1 {
+                    input: self.compile_variable(op.input),
+                    out: self.compile_variable(out),
+                })
+            }
+            cube::Operator::Cast(op)
+                if op.input.storage_type() != out.storage_type() && out.ty.packing_factor() > 1 =>
+            {
+                instructions.push(wgsl::Instruction::PackE2M1 {
+                    input: self.compile_variable(op.input),
+                    out: self.compile_variable(out),
+                })
+            }
             cube::Operator::Cast(op) => instructions.push(wgsl::Instruction::Assign {
                 input: self.compile_variable(op.input),
                 out: self.compile_variable(out),
             }),
+            cube::Operator::Index(op) | cube::Operator::UncheckedIndex(op)
+                if let Some(atomic) = self.packed_buffer(&op.list) =>
+            {
+                instructions.push(wgsl::Instruction::PackedLoad {
+                    buffer: self.compile_variable(op.list),
+                    index: self.compile_variable(op.index),
+                    out: self.compile_variable(out),
+                    atomic,
+                });
+            }
+            cube::Operator::IndexAssign(op) | cube::Operator::UncheckedIndexAssign(op)
+                if let Some(atomic) = self.packed_buffer(&out) =>
+            {
+                instructions.push(wgsl::Instruction::PackedStore {
+                    buffer: self.compile_variable(out),
+                    index: self.compile_variable(op.index),
+                    value: self.compile_variable(op.value),
+                    atomic,
+                });
+            }
             cube::Operator::Index(op) | cube::Operator::UncheckedIndex(op) => {
                 instructions.push(wgsl::Instruction::Index {
                     lhs: self.compile_variable(op.list),
@@ -1135,10 +1248,32 @@ impl WgslCompiler {
                 input: self.compile_variable(op.input),
                 out: self.compile_variable(out),
             }),
-            cube::Operator::Reinterpret(op) => instructions.push(wgsl::Instruction::Bitcast {
-                input: self.compile_variable(op.input),
-                out: self.compile_variable(out),
-            }),
+            cube::Operator::Reinterpret(op) => {
+                let input = self.compile_variable(op.input);
+                let out_var = self.compile_variable(out);
+                let instruction = match (
+                    op.input.ty.packing_factor() > 1,
+                    out.ty.packing_factor() > 1,
+                ) {
+                    (false, false) => wgsl::Instruction::Bitcast {
+                        input,
+                        out: out_var,
+                    },
+                    (true, false) => wgsl::Instruction::BytesToWord {
+                        input,
+                        out: out_var,
+                    },
+                    (false, true) => wgsl::Instruction::WordToBytes {
+                        input,
+                        out: out_var,
+                    },
+                    (true, true) => wgsl::Instruction::Assign {
+                        input,
+                        out: out_var,
+                    },
+                };
+                instructions.push(instruction);
+            }
             cube::Operator::InitVector(op) => instructions.push(wgsl::Instruction::VecInit {
                 inputs: op
                     .inputs
@@ -1147,6 +1282,34 @@ impl WgslCompiler {
                     .collect(),
                 out: self.compile_variable(out),
             }),
+            cube::Operator::CopyMemory(op)
+                if self.packed_buffer(&op.input).is_some()
+                    || self.packed_buffer(&out).is_some() =>
+            {
+                instructions.push(wgsl::Instruction::PackedCopy {
+                    in_atomic: self.packed_buffer(&op.input),
+                    out_atomic: self.packed_buffer(&out),
+                    input: self.compile_variable(op.input),
+                    in_index: self.compile_variable(op.in_index),
+                    out: self.compile_variable(out),
+                    out_index: self.compile_variable(op.out_index),
+                    len: 1,
+                });
+            }
+            cube::Operator::CopyMemoryBulk(op)
+                if self.packed_buffer(&op.input).is_some()
+                    || self.packed_buffer(&out).is_some() =>
+            {
+                instructions.push(wgsl::Instruction::PackedCopy {
+                    in_atomic: self.packed_buffer(&op.input),
+                    out_atomic: self.packed_buffer(&out),
+                    input: self.compile_variable(op.input),
+                    in_index: self.compile_variable(op.in_index),
+                    out: self.compile_variable(out),
+                    out_index: self.compile_variable(op.out_index),
+                    len: op.len as u32,
+                });
+            }
             cube::Operator::CopyMemory(op) => instructions.push(wgsl::Instruction::Copy {
                 input: self.compile_variable(op.input),
                 in_index: self.compile_variable(op.in_index),
@@ -1234,12 +1397,32 @@ impl WgslCompiler {
     }
 
     fn compile_binding(&mut self, value: kernel::KernelArg) -> wgsl::KernelArg {
+        let (item, size) = match self.packed_buffers.get(&value.id) {
+            Some(atomic) => {
+                let elem = match atomic {
+                    true => wgsl::Elem::AtomicU32,
+                    false => wgsl::Elem::U32,
+                };
+                let size = value.size.map(|size| (size * value.ty.size()).div_ceil(4));
+                (wgsl::Item::Scalar(elem), size)
+            }
+            None => (self.compile_type(value.ty), value.size),
+        };
         wgsl::KernelArg {
             id: value.id,
             visibility: value.visibility,
             location: wgsl::Location::Storage,
-            item: self.compile_type(value.ty),
-            size: value.size,
+            item,
+            size,
+        }
+    }
+
+    /// Whether `var` is a buffer of packed values, and if so whether it's atomic.
+    fn packed_buffer(&self, var: &cube::Variable) -> Option<bool> {
+        match var.kind {
+            cube::VariableKind::GlobalInputArray(id)
+            | cube::VariableKind::GlobalOutputArray(id) => self.packed_buffers.get(&id).copied(),
+            _ => None,
         }
     }
 }
@@ -1273,6 +1456,12 @@ fn register_extensions(instructions: &[wgsl::Instruction]) -> Vec<wgsl::Extensio
                 register_extension(wgsl::Extension::IsInfPrimitive(input.elem()));
                 register_extension(wgsl::Extension::IsInf(input.item(), out.item()));
             }
+            wgsl::Instruction::UnpackE2M1 { .. } => {
+                register_extension(wgsl::Extension::UnpackE2M1);
+            }
+            wgsl::Instruction::PackE2M1 { .. } => {
+                register_extension(wgsl::Extension::PackE2M1);
+            }
             wgsl::Instruction::If { instructions, .. } => {
                 for extension in register_extensions(instructions) {
                     register_extension(extension);
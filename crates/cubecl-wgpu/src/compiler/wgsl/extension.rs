@@ -15,6 +15,8 @@ pub enum Extension {
     IsNan(Item, Item),
     IsInfPrimitive(Elem),
     IsInf(Item, Item),
+    UnpackE2M1,
+    PackE2M1,
 }
 
 impl Display for Extension {
@@ -88,6 +90,8 @@ impl Display for Extension {
                 }],
                 *out_item,
             ),
+            Extension::UnpackE2M1 => super::format_e2m1_unpack(f),
+            Extension::PackE2M1 => super::format_e2m1_pack(f),
         }
     }
 }
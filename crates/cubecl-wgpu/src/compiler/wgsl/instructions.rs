@@ -1,6 +1,7 @@
 use super::{
-    Elem, Subgroup,
+    Elem, PrintFormat, Subgroup,
     base::{Item, Variable},
+    format_print,
 };
 use std::fmt::Display;
 
@@ -54,6 +55,7 @@ pub enum Instruction {
     },
     Return,
     Break,
+    Continue,
     Unreachable,
     WorkgroupBarrier,
     StorageBarrier,
@@ -349,6 +351,46 @@ pub enum Instruction {
         input: Variable,
         out: Variable,
     },
+    // Packed `e2m1x2` values are held as bytes in `u32` lanes.
+    UnpackE2M1 {
+        input: Variable,
+        out: Variable,
+    },
+    PackE2M1 {
+        input: Variable,
+        out: Variable,
+    },
+    BytesToWord {
+        input: Variable,
+        out: Variable,
+    },
+    WordToBytes {
+        input: Variable,
+        out: Variable,
+    },
+    PackedLoad {
+        buffer: Variable,
+        index: Variable,
+        out: Variable,
+        atomic: bool,
+    },
+    PackedStore {
+        buffer: Variable,
+        index: Variable,
+        value: Variable,
+        atomic: bool,
+    },
+    /// A copy where the input or the output is a buffer of packed values, with whether each of
+    /// them is a packed buffer and if so whether it's atomic.
+    PackedCopy {
+        input: Variable,
+        in_index: Variable,
+        in_atomic: Option<bool>,
+        out: Variable,
+        out_index: Variable,
+        out_atomic: Option<bool>,
+        len: u32,
+    },
     AtomicLoad {
         input: Variable,
         out: Variable,
@@ -449,6 +491,10 @@ pub enum Instruction {
     Comment {
         content: String,
     },
+    Print {
+        format: PrintFormat,
+        args: Vec<Variable>,
+    },
 }
 
 impl Display for Instruction {
@@ -878,6 +924,7 @@ for (var {i}: {i_ty} = {start}; {i} {cmp} {end}; {increment}) {{
             }
             Instruction::Return => f.write_str("return;\n"),
             Instruction::Break => f.write_str("break;\n"),
+            Instruction::Continue => f.write_str("continue;\n"),
             Instruction::WorkgroupBarrier => f.write_str("workgroupBarrier();\n"),
             Instruction::StorageBarrier => f.write_str("storageBarrier();\n"),
             Instruction::Length { var, out } => {
@@ -1030,6 +1077,40 @@ for (var {i}: {i_ty} = {start}; {i} {cmp} {end}; {increment}) {{
                 let out = out.fmt_left();
                 writeln!(f, "{out} = bitcast<{elem}>({input});")
             }
+            Instruction::UnpackE2M1 { input, out } => super::call_unpack_e2m1(f, input, out),
+            Instruction::PackE2M1 { input, out } => super::call_pack_e2m1(f, input, out),
+            Instruction::BytesToWord { input, out } => super::call_bytes_to_word(f, input, out),
+            Instruction::WordToBytes { input, out } => super::call_word_to_bytes(f, input, out),
+            Instruction::PackedLoad {
+                buffer,
+                index,
+                out,
+                atomic,
+            } => super::packed_load(f, buffer, index, out, *atomic),
+            Instruction::PackedStore {
+                buffer,
+                index,
+                value,
+                atomic,
+            } => super::packed_store(f, buffer, index, value, *atomic),
+            Instruction::PackedCopy {
+                input,
+                in_index,
+                in_atomic,
+                out,
+                out_index,
+                out_atomic,
+                len,
+            } => super::packed_copy(
+                f,
+                input,
+                in_index,
+                *in_atomic,
+                out,
+                out_index,
+                *out_atomic,
+                *len,
+            ),
             Instruction::AtomicLoad { input, out } => {
                 let out = out.fmt_left();
                 writeln!(f, "{out} = atomicLoad({input});")
@@ -1124,6 +1205,7 @@ for (var {i}: {i_ty} = {start}; {i} {cmp} {end}; {increment}) {{
                     writeln!(f, "// {content}")
                 }
             }
+            Instruction::Print { format, args } => format_print(f, format, args),
             // WGSL as usual has no lower level intrinsics
             Instruction::Unreachable => writeln!(f, "return;"),
         }
@@ -3,6 +3,8 @@ mod body;
 mod compiler;
 mod extension;
 mod instructions;
+mod packed;
+mod printf;
 pub(crate) mod shader;
 mod subgroup;
 
@@ -11,5 +13,7 @@ pub(crate) use body::*;
 pub use compiler::*;
 pub(crate) use extension::*;
 pub(crate) use instructions::*;
+pub(crate) use packed::*;
+pub(crate) use printf::*;
 pub(crate) use shader::*;
 pub(crate) use subgroup::*;
@@ -0,0 +1,249 @@
+//! WGSL has no packed types or bytes, so each packed value is held as the `u32` value of its byte
+//! in registers, and global buffers of packed values are declared as arrays of `u32` words that
+//! are accessed byte by byte. Buffers that can be written to are atomic, since neighbouring units
+//! may write to the same word.
+
+use super::{Elem, Item, Variable};
+use std::fmt::Display;
+
+const E2M1_UNPACK: &str = "unpack_e2m1";
+const E2M1_PACK: &str = "pack_e2m1";
+
+pub fn format_e2m1_unpack(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    // Magnitudes 0 and 1 are the subnormals 0.0 and 0.5, the others follow the regular
+    // exponent/mantissa layout shifted into the f32 position.
+    write!(
+        f,
+        "
+fn {E2M1_UNPACK}(nibble: u32) -> f32 {{
+    let mag = nibble & 7u;
+    let sign = (nibble & 8u) << 28u;
+    let bits = select((mag << 22u) + 0x3f000000u, mag * 0x3f000000u, mag < 2u);
+    return bitcast<f32>(bits | sign);
+}}
+"
+    )
+}
+
+pub fn format_e2m1_pack(f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    // Counts the midpoints between representable magnitudes that are exceeded. Ties round to the
+    // even code, so the comparison is inclusive for odd codes.
+    write!(
+        f,
+        "
+fn {E2M1_PACK}(value: f32) -> u32 {{
+    let sign = (bitcast<u32>(value) >> 31u) << 3u;
+    let x = abs(value);
+    let mag = u32(x > 0.25) + u32(x >= 0.75) + u32(x > 1.25) + u32(x >= 1.75)
+        + u32(x > 2.5) + u32(x >= 3.5) + u32(x > 5.0);
+    return mag | sign;
+}}
+"
+    )
+}
+
+/// Unpack each `e2m1x2` byte of `input` into two lanes of `out`.
+pub fn call_unpack_e2m1(
+    f: &mut std::fmt::Formatter<'_>,
+    input: &Variable,
+    out: &Variable,
+) -> std::fmt::Result {
+    let out_elem = out.elem();
+    let lanes = (0..input.item().vectorization_factor())
+        .flat_map(|i| {
+            let byte = input.index(i);
+            [
+                format!("{E2M1_UNPACK}({byte} & 15u)"),
+                format!("{E2M1_UNPACK}(({byte} >> 4u) & 15u)"),
+            ]
+        })
+        .map(|lane| cast_lane(lane, Elem::F32, out_elem))
+        .collect::<Vec<_>>();
+    write_lanes(f, out, &lanes)
+}
+
+/// Pack each pair of lanes of `input` into an `e2m1x2` byte of `out`. Scalars are broadcast.
+pub fn call_pack_e2m1(
+    f: &mut std::fmt::Formatter<'_>,
+    input: &Variable,
+    out: &Variable,
+) -> std::fmt::Result {
+    let in_elem = input.elem();
+    let lane = |i: usize| {
+        let value = match input.item() {
+            Item::Scalar(_) => format!("{input}"),
+            _ => format!("{}", input.index(i)),
+        };
+        format!("{E2M1_PACK}({})", cast_lane(value, in_elem, Elem::F32))
+    };
+    let bytes = (0..out.item().vectorization_factor())
+        .map(|i| format!("{} | ({} << 4u)", lane(2 * i), lane(2 * i + 1)))
+        .collect::<Vec<_>>();
+    write_lanes(f, out, &bytes)
+}
+
+/// Reinterpret four packed bytes as a 32-bit value.
+pub fn call_bytes_to_word(
+    f: &mut std::fmt::Formatter<'_>,
+    input: &Variable,
+    out: &Variable,
+) -> std::fmt::Result {
+    assert_word_sized(input.item(), out.item());
+    let item = out.item();
+    let out = out.fmt_left();
+    writeln!(f, "{out} = bitcast<{item}>(pack4xU8({input}));")
+}
+
+/// Reinterpret a 32-bit value as four packed bytes.
+pub fn call_word_to_bytes(
+    f: &mut std::fmt::Formatter<'_>,
+    input: &Variable,
+    out: &Variable,
+) -> std::fmt::Result {
+    assert_word_sized(out.item(), input.item());
+    let out = out.fmt_left();
+    writeln!(f, "{out} = unpack4xU8(bitcast<u32>({input}));")
+}
+
+/// Load the packed bytes at `index` from a buffer of `u32` words.
+pub fn packed_load(
+    f: &mut std::fmt::Formatter<'_>,
+    buffer: &Variable,
+    index: &Variable,
+    out: &Variable,
+    atomic: bool,
+) -> std::fmt::Result {
+    let load = |word: &dyn Display| match atomic {
+        true => format!("atomicLoad(&{buffer}[{word}])"),
+        false => format!("{buffer}[{word}]"),
+    };
+
+    let vector_size = buffer.item().vectorization_factor();
+    let lanes = match vector_size {
+        4 => {
+            let word = load(&format_args!("u32({index})"));
+            let out = out.fmt_left();
+            return writeln!(f, "{out} = unpack4xU8({word});");
+        }
+        _ => (0..vector_size)
+            .map(|i| {
+                let byte = format!("u32({index}) * {vector_size}u + {i}u");
+                let word = load(&format_args!("({byte}) / 4u"));
+                format!("({word} >> ((({byte}) % 4u) * 8u)) & 255u")
+            })
+            .collect::<Vec<_>>(),
+    };
+    write_lanes(f, out, &lanes)
+}
+
+/// Store the packed bytes of `value` at `index` into a buffer of `u32` words. Bytes that don't
+/// fill a whole word are merged into it, so neighbouring bytes are preserved.
+pub fn packed_store(
+    f: &mut std::fmt::Formatter<'_>,
+    buffer: &Variable,
+    index: &Variable,
+    value: &Variable,
+    atomic: bool,
+) -> std::fmt::Result {
+    let vector_size = buffer.item().vectorization_factor();
+    if vector_size == 4 {
+        let word = format!("pack4xU8({})", value.fmt_cast_to(Item::Vec4(Elem::U32)));
+        return match atomic {
+            true => writeln!(f, "atomicStore(&{buffer}[u32({index})], {word});"),
+            false => writeln!(f, "{buffer}[u32({index})] = {word};"),
+        };
+    }
+
+    for i in 0..vector_size {
+        let lane = match value.item() {
+            Item::Scalar(_) => format!("{value}"),
+            _ => format!("{}", value.index(i)),
+        };
+        let byte = format!("u32({index}) * {vector_size}u + {i}u");
+        let word = format!("{buffer}[({byte}) / 4u]");
+        let shift = format!("((({byte}) % 4u) * 8u)");
+        let mask = format!("(255u << {shift})");
+        let bits = format!("((u32({lane}) & 255u) << {shift})");
+        match atomic {
+            true => {
+                writeln!(f, "_ = atomicAnd(&{word}, ~{mask});")?;
+                writeln!(f, "_ = atomicOr(&{word}, {bits});")?;
+            }
+            false => writeln!(f, "{word} = ({word} & ~{mask}) | {bits};")?,
+        }
+    }
+    Ok(())
+}
+
+/// Copy `len` consecutive values between two buffers, where either may be a buffer of packed
+/// values. `in_atomic` and `out_atomic` are set for packed buffers, to whether they're atomic.
+#[allow(clippy::too_many_arguments)]
+pub fn packed_copy(
+    f: &mut std::fmt::Formatter<'_>,
+    input: &Variable,
+    in_index: &Variable,
+    in_atomic: Option<bool>,
+    out: &Variable,
+    out_index: &Variable,
+    out_atomic: Option<bool>,
+    len: u32,
+) -> std::fmt::Result {
+    // Values are copied through a register holding the byte of each packed value.
+    let item = match in_atomic {
+        Some(_) => input.item(),
+        None => out.item(),
+    };
+    let value = Variable::Named {
+        name: "packed_copy_value".to_string(),
+        item,
+        is_array: false,
+    };
+    let offset = |index: &Variable, i: u32| Variable::Named {
+        name: format!("(u32({index}) + {i}u)"),
+        item: Item::Scalar(Elem::U32),
+        is_array: false,
+    };
+
+    writeln!(f, "{{")?;
+    writeln!(f, "var {value}: {item};")?;
+    for i in 0..len {
+        let in_index = offset(in_index, i);
+        match in_atomic {
+            Some(atomic) => packed_load(f, input, &in_index, &value, atomic)?,
+            None => writeln!(f, "{value} = {input}[{in_index}];")?,
+        }
+        let out_index = offset(out_index, i);
+        match out_atomic {
+            Some(atomic) => packed_store(f, out, &out_index, &value, atomic)?,
+            None => writeln!(f, "{out}[{out_index}] = {value};")?,
+        }
+    }
+    writeln!(f, "}}")
+}
+
+fn cast_lane(lane: String, from: Elem, to: Elem) -> String {
+    match from == to {
+        true => lane,
+        false => format!("{to}({lane})"),
+    }
+}
+
+fn write_lanes(
+    f: &mut std::fmt::Formatter<'_>,
+    out: &Variable,
+    lanes: &[String],
+) -> std::fmt::Result {
+    let item = out.item();
+    let out = out.fmt_left();
+    match item {
+        Item::Scalar(_) => writeln!(f, "{out} = {};", lanes[0]),
+        _ => writeln!(f, "{out} = {item}({});", lanes.join(", ")),
+    }
+}
+
+fn assert_word_sized(packed: Item, other: Item) {
+    assert!(
+        packed.vectorization_factor() == 4 && other.size() == 4,
+        "Packed values can only be reinterpreted in groups of 4 bytes, as {other}"
+    );
+}
@@ -0,0 +1,143 @@
+use super::{Elem, Variable};
+use core::sync::atomic::{AtomicU32, Ordering};
+use std::fmt::Display;
+
+/// Name of the hidden storage buffer prints are appended to.
+///
+/// The first word is a cursor counting the words requested by all prints, followed by the
+/// records. Each record is the id of its [`PrintFormat`] followed by the encoded arguments.
+pub const PRINTF_BUFFER: &str = "printf_buffer";
+
+/// How an argument of a print is encoded in the printf buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrintArg {
+    F32,
+    I32,
+    U32,
+    I64,
+    U64,
+    Bool,
+}
+
+impl PrintArg {
+    fn from_elem(elem: Elem) -> Self {
+        match elem {
+            // Floats are always printed as `f32`, since the C++ convention promotes them to
+            // double anyway.
+            Elem::F16 | Elem::F32 | Elem::F64 | Elem::AtomicF32 => PrintArg::F32,
+            Elem::I32 | Elem::AtomicI32 => PrintArg::I32,
+            Elem::U32 | Elem::AtomicU32 => PrintArg::U32,
+            Elem::I64 => PrintArg::I64,
+            Elem::U64 => PrintArg::U64,
+            Elem::Bool => PrintArg::Bool,
+        }
+    }
+
+    /// The number of words used by a single value.
+    pub fn words(&self) -> usize {
+        match self {
+            PrintArg::I64 | PrintArg::U64 => 2,
+            _ => 1,
+        }
+    }
+
+    /// The expressions that encode a value into words, from least to most significant.
+    fn encode(&self, elem: Elem, value: impl Display) -> Vec<String> {
+        match (self, elem) {
+            (PrintArg::F32, Elem::F32) => vec![format!("bitcast<u32>({value})")],
+            (PrintArg::F32, _) => vec![format!("bitcast<u32>(f32({value}))")],
+            (PrintArg::I32, _) => vec![format!("bitcast<u32>({value})")],
+            (PrintArg::U32, _) => vec![format!("u32({value})")],
+            (PrintArg::I64 | PrintArg::U64, _) => {
+                vec![format!("u32({value})"), format!("u32({value} >> 32u)")]
+            }
+            (PrintArg::Bool, _) => vec![format!("select(0u, 1u, {value})")],
+        }
+    }
+}
+
+/// A print of a shader, used to decode its records on the host.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrintFormat {
+    /// Identifies the records of this print in the printf buffer.
+    pub id: u32,
+    /// The format string, following the C++ `printf` conventions.
+    pub format_string: String,
+    /// The encoding and vector size of each argument.
+    pub args: Vec<(PrintArg, usize)>,
+}
+
+impl PrintFormat {
+    /// Create the format of a print with the given arguments.
+    ///
+    /// Every print gets a new id, so the records of different prints sharing the printf buffer
+    /// can't be confused.
+    pub fn new(format_string: String, args: &[Variable]) -> Self {
+        let args = args
+            .iter()
+            .map(|arg| {
+                let item = arg.item();
+                (
+                    PrintArg::from_elem(*item.elem()),
+                    item.vectorization_factor(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            format_string,
+            args,
+        }
+    }
+
+    /// The number of words of a record, including the id.
+    pub fn len(&self) -> usize {
+        1 + self
+            .args
+            .iter()
+            .map(|(arg, vector_size)| arg.words() * vector_size)
+            .sum::<usize>()
+    }
+}
+
+/// Append a record to the printf buffer. Records that don't fit in the buffer are dropped, but
+/// still counted by the cursor so the host can report the truncation.
+pub fn format_print(
+    f: &mut std::fmt::Formatter<'_>,
+    format: &PrintFormat,
+    args: &[Variable],
+) -> std::fmt::Result {
+    let len = format.len();
+    let words = args.iter().flat_map(|arg| {
+        let item = arg.item();
+        let elem = *item.elem();
+        let kind = PrintArg::from_elem(elem);
+        (0..item.vectorization_factor()).flat_map(move |i| kind.encode(elem, arg.index(i)))
+    });
+
+    f.write_str("{\n")?;
+    writeln!(
+        f,
+        "let printf_offset = atomicAdd(&{PRINTF_BUFFER}[0], {len}u) + 1u;"
+    )?;
+    writeln!(
+        f,
+        "if printf_offset + {len}u <= arrayLength(&{PRINTF_BUFFER}) {{"
+    )?;
+    writeln!(
+        f,
+        "atomicStore(&{PRINTF_BUFFER}[printf_offset], {}u);",
+        format.id
+    )?;
+    for (i, word) in words.enumerate() {
+        writeln!(
+            f,
+            "atomicStore(&{PRINTF_BUFFER}[printf_offset + {}u], {word});",
+            i + 1
+        )?;
+    }
+    f.write_str("}\n}\n")
+}
@@ -1,4 +1,4 @@
-use super::{Body, Elem, Extension, Item, Variable};
+use super::{Body, Elem, Extension, Item, PRINTF_BUFFER, PrintFormat, Variable};
 use cubecl_core::{CubeDim, Info, ir::Id, prelude::Visibility};
 use std::fmt::Display;
 
@@ -84,6 +84,8 @@ pub struct ComputeShader {
     pub shared_values: Vec<SharedValue>,
     pub constant_arrays: Vec<ConstantArray>,
     pub local_arrays: Vec<LocalArray>,
+    /// The prints of the shader, which are appended to a hidden buffer bound after the info.
+    pub prints: Vec<PrintFormat>,
     pub info: Info,
     pub static_meta_len: usize,
     pub workgroup_size: CubeDim,
@@ -160,6 +162,17 @@ var<{location}, {visibility}> info: info_st;
             )?;
         }
 
+        if !self.prints.is_empty() {
+            let offset = offset + self.info.has_info() as usize;
+            write!(
+                f,
+                "@group(0)
+@binding({offset})
+var<storage, read_write> {PRINTF_BUFFER}: array<atomic<u32>>;
+\n",
+            )?;
+        }
+
         for array in self.shared_arrays.iter() {
             write!(
                 f,
@@ -1,7 +1,7 @@
-use core::mem::MaybeUninit;
+use core::{any::Any, mem::MaybeUninit, ptr::NonNull};
 use cubecl_common::bytes::{AllocationController, AllocationProperty};
 use cubecl_runtime::memory_management::ManagedMemoryBinding;
-use wgpu::BufferView;
+use wgpu::{BufferView, BufferViewMut};
 
 /// Controller for managing wgpu staging buffers managed by a memory pool.
 pub struct WgpuAllocController {
@@ -71,3 +71,95 @@ impl WgpuAllocController {
         }
     }
 }
+
+/// Controller for wgpu upload buffers, which are mapped so data can be written to them directly
+/// before being copied to a storage buffer on the device.
+pub struct WgpuUploadController {
+    view: Option<BufferViewMut>,
+    memory: NonNull<[u8]>,
+    buffer: wgpu::Buffer,
+}
+
+impl Drop for WgpuUploadController {
+    fn drop(&mut self) {
+        // The buffer is already unmapped when its content was copied to the device.
+        if self.view.take().is_some() {
+            self.buffer.unmap();
+        }
+    }
+}
+
+impl AllocationController for WgpuUploadController {
+    fn alloc_align(&self) -> usize {
+        wgpu::COPY_BUFFER_ALIGNMENT as usize
+    }
+
+    fn property(&self) -> AllocationProperty {
+        AllocationProperty::Pinned
+    }
+
+    unsafe fn memory_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        assert!(self.view.is_some(), "The upload buffer is no longer mapped");
+        // SAFETY:
+        // - The mapped range is valid while the view is alive.
+        // - MaybeUninit<u8> has the same layout as u8
+        // - Caller promises not to write uninitialized values.
+        unsafe {
+            std::slice::from_raw_parts_mut(
+                self.memory.as_ptr() as *mut MaybeUninit<u8>,
+                self.memory.len(),
+            )
+        }
+    }
+
+    fn memory(&self) -> &[MaybeUninit<u8>] {
+        assert!(self.view.is_some(), "The upload buffer is no longer mapped");
+        // SAFETY:
+        // - The mapped range is valid while the view is alive.
+        // - MaybeUninit<u8> has the same layout as u8
+        unsafe {
+            std::slice::from_raw_parts(
+                self.memory.as_ptr() as *const MaybeUninit<u8>,
+                self.memory.len(),
+            )
+        }
+    }
+
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        Some(self)
+    }
+}
+
+impl WgpuUploadController {
+    /// Creates a new allocation controller for an upload buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - The wgpu buffer, which must be mapped at creation.
+    ///
+    /// # Returns
+    ///
+    /// The controller.
+    pub fn init(buffer: wgpu::Buffer) -> Self {
+        let mut view = buffer.get_mapped_range_mut(..);
+        // Buffers mapped at creation are zero initialized, and the range stays at the same
+        // address until the view is dropped.
+        let memory = view.slice(..).as_raw_ptr();
+
+        Self {
+            view: Some(view),
+            memory,
+            buffer,
+        }
+    }
+
+    /// Unmaps the buffer so its content can be copied on the device.
+    ///
+    /// The memory of the controller can't be accessed anymore afterward.
+    pub fn unmap(&mut self) -> &wgpu::Buffer {
+        if self.view.take().is_some() {
+            self.buffer.unmap();
+        }
+        &self.buffer
+    }
+}
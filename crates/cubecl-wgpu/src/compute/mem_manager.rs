@@ -21,6 +21,7 @@ pub(crate) struct WgpuMemManager {
     memory_uniforms: MemoryManagement<WgpuStorage>,
     memory_pool_staging: MemoryManagement<WgpuStorage>,
     uniforms: Vec<ManagedMemoryHandle>,
+    temporaries: Vec<ManagedMemoryHandle>,
 }
 
 impl WgpuMemManager {
@@ -81,6 +82,7 @@ impl WgpuMemManager {
             memory_pool_staging: memory_staging,
             memory_uniforms,
             uniforms: vec![],
+            temporaries: vec![],
         }
     }
 
@@ -128,6 +130,26 @@ impl WgpuMemManager {
         self.memory_uniforms.storage().get(&handle)
     }
 
+    /// Reserve memory in the main pool for data that is only used by a single task.
+    ///
+    /// The returned handle must be given back with [`Self::hold_temporary`] once the task is
+    /// executed, so the memory is kept until the next flush.
+    pub(crate) fn reserve_temporary(
+        &mut self,
+        size: u64,
+    ) -> Result<(WgpuResource, ManagedMemoryHandle), IoError> {
+        let handle = self.memory_pool.reserve(size)?;
+        let resource =
+            self.memory_pool
+                .get_resource(MemoryHandle::binding(handle.clone()), None, None)?;
+        Ok((resource, handle))
+    }
+
+    /// Keep temporary memory alive until it is released with the uniforms.
+    pub(crate) fn hold_temporary(&mut self, handle: ManagedMemoryHandle) {
+        self.temporaries.push(handle);
+    }
+
     pub(crate) fn memory_usage(&self) -> cubecl_runtime::memory_management::MemoryUsage {
         self.memory_pool.memory_usage()
     }
@@ -136,11 +158,20 @@ impl WgpuMemManager {
         self.memory_pool.cleanup(explicit);
     }
 
+    pub(crate) fn memory_trace(&mut self) -> cubecl_runtime::memory_management::MemoryTrace {
+        self.memory_pool.take_trace()
+    }
+
+    pub(crate) fn trace_launch(&mut self, kernel: &str) {
+        self.memory_pool.trace_launch(kernel);
+    }
+
     pub(crate) fn mode(&mut self, mode: MemoryAllocationMode) {
         self.memory_pool.mode(mode);
     }
 
     pub(crate) fn release_uniforms(&mut self) {
         self.uniforms.clear();
+        self.temporaries.clear();
     }
 }
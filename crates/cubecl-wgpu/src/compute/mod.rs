@@ -4,6 +4,8 @@ mod storage;
 
 pub(super) mod mem_manager;
 pub(super) mod poll;
+pub(super) mod printf;
+pub(super) mod scatter;
 pub(super) mod schedule;
 mod server;
 pub(super) mod stream;
@@ -0,0 +1,222 @@
+use crate::{
+    WgpuResource,
+    compiler::wgsl::{PrintArg, PrintFormat},
+};
+use alloc::sync::Arc;
+use cubecl_common::printf::{PrintValue, format_printf};
+use hashbrown::HashMap;
+use wgpu::BufferUsages;
+
+/// The prints of a kernel.
+pub(crate) type KernelPrints = Arc<[PrintFormat]>;
+
+/// The buffer kernels append their prints to, decoded and printed on the host after each flush.
+#[derive(Debug)]
+pub(crate) struct PrintfBuffer {
+    buffer: wgpu::Buffer,
+    formats: Arc<HashMap<u32, PrintFormat>>,
+    /// Whether a kernel that prints was dispatched since the last flush.
+    pending: bool,
+}
+
+impl PrintfBuffer {
+    /// Create a new printf buffer of `size` bytes.
+    pub(crate) fn new(device: &wgpu::Device, size: u64) -> Self {
+        // The cursor and at least one word for records.
+        let size = size.next_multiple_of(4).max(8);
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("CubeCL Printf Buffer"),
+            size,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            buffer,
+            formats: Arc::new(HashMap::new()),
+            pending: false,
+        }
+    }
+
+    /// Register the prints of a kernel about to be dispatched and return the resource to bind.
+    pub(crate) fn bind(&mut self, formats: &[PrintFormat]) -> WgpuResource {
+        if formats.iter().any(|it| !self.formats.contains_key(&it.id)) {
+            let registered = Arc::make_mut(&mut self.formats);
+            for format in formats {
+                registered.insert(format.id, format.clone());
+            }
+        }
+        self.pending = true;
+
+        WgpuResource {
+            buffer: self.buffer.clone(),
+            offset: 0,
+            size: self.buffer.size(),
+        }
+    }
+
+    /// Copy the records to a staging buffer at the end of the encoder, if any kernel printed.
+    pub(crate) fn copy_records(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+    ) -> Option<wgpu::Buffer> {
+        if !core::mem::take(&mut self.pending) {
+            return None;
+        }
+
+        let staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("CubeCL Printf Staging Buffer"),
+            size: self.buffer.size(),
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        encoder.copy_buffer_to_buffer(&self.buffer, 0, &staging, 0, self.buffer.size());
+        // Records are only copied once, so the cursor is reset before the next submission.
+        encoder.clear_buffer(&self.buffer, 0, Some(4));
+
+        Some(staging)
+    }
+
+    /// Print the records of a staging buffer once the submission that filled it is done.
+    pub(crate) fn print_records(
+        &self,
+        device: &wgpu::Device,
+        staging: wgpu::Buffer,
+        submission: wgpu::SubmissionIndex,
+    ) {
+        let formats = self.formats.clone();
+        let buffer = staging.clone();
+
+        staging
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                if let Err(err) = result {
+                    log::warn!("Failed to read the printf buffer: {err}");
+                    return;
+                }
+
+                let words = {
+                    let data = buffer.slice(..).get_mapped_range();
+                    bytemuck::cast_slice::<u8, u32>(&data).to_vec()
+                };
+                buffer.unmap();
+
+                print_records(&words, &formats);
+            });
+
+        // Wait for the records, so prints show up in order with the host output.
+        #[cfg(not(target_family = "wasm"))]
+        if let Err(err) = device.poll(wgpu::PollType::Wait {
+            submission_index: Some(submission),
+            timeout: None,
+        }) {
+            log::warn!("wgpu: printf poll failed ({err})");
+        }
+        #[cfg(target_family = "wasm")]
+        let _ = (device, submission);
+    }
+}
+
+fn print_records(words: &[u32], formats: &HashMap<u32, PrintFormat>) {
+    let (messages, dropped) = decode_records(words, formats);
+    for message in messages {
+        print!("{message}");
+    }
+
+    if dropped > 0 {
+        log::warn!(
+            "The printf buffer overflowed and {dropped} bytes of prints were dropped, increase \
+             `RuntimeOptions::printf_buffer_size` to keep them"
+        );
+    }
+}
+
+/// Decode the records of a printf buffer into messages, also returning the number of bytes of
+/// records that didn't fit in the buffer.
+fn decode_records(words: &[u32], formats: &HashMap<u32, PrintFormat>) -> (Vec<String>, usize) {
+    let Some((cursor, records)) = words.split_first() else {
+        return (Vec::new(), 0);
+    };
+    let requested = *cursor as usize;
+    let end = requested.min(records.len());
+
+    let mut messages = Vec::new();
+    let mut pos = 0;
+    while pos < end {
+        let Some(format) = formats.get(&records[pos]) else {
+            log::warn!("Unknown print id {} in the printf buffer", records[pos]);
+            break;
+        };
+        let len = format.len();
+        // Records are written completely or not at all.
+        if pos + len > end {
+            break;
+        }
+
+        let values = decode_values(&records[pos + 1..pos + len], format);
+        messages.push(format_printf(&format.format_string, &values));
+        pos += len;
+    }
+
+    (messages, (requested - pos) * size_of::<u32>())
+}
+
+/// Decode the arguments of a record, with the components of vectors grouped together.
+fn decode_values(words: &[u32], format: &PrintFormat) -> Vec<Vec<PrintValue>> {
+    let mut words = words.iter().copied();
+    let mut next = || words.next().unwrap_or_default();
+
+    format
+        .args
+        .iter()
+        .map(|(arg, vector_size)| {
+            (0..*vector_size)
+                .map(|_| match arg {
+                    PrintArg::F32 => PrintValue::Float(f32::from_bits(next()) as f64),
+                    PrintArg::I32 => PrintValue::Int(next() as i32 as i64),
+                    PrintArg::U32 | PrintArg::Bool => PrintValue::UInt(next() as u64),
+                    PrintArg::I64 | PrintArg::U64 => {
+                        let low = next() as u64;
+                        let bits = low | ((next() as u64) << 32);
+                        match arg {
+                            PrintArg::I64 => PrintValue::Int(bits as i64),
+                            _ => PrintValue::UInt(bits),
+                        }
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_records() {
+        let format = PrintFormat {
+            id: 3,
+            format_string: "%d %lu %f\n".to_string(),
+            args: vec![(PrintArg::I32, 1), (PrintArg::U64, 1), (PrintArg::F32, 1)],
+        };
+        let formats = HashMap::from([(format.id, format)]);
+        let value = 1u64 << 40;
+        let words = [
+            // Two records were requested, but only one fits.
+            10,
+            3,
+            -2i32 as u32,
+            value as u32,
+            (value >> 32) as u32,
+            0.5f32.to_bits(),
+            3,
+            0,
+        ];
+
+        let (messages, dropped) = decode_records(&words, &formats);
+        assert_eq!(messages, vec![format!("-2 {value} 0.500000\n")]);
+        assert_eq!(dropped, 20);
+    }
+}
@@ -0,0 +1,121 @@
+use crate::WgpuServer;
+use alloc::sync::Arc;
+use cubecl_core::{
+    CubeCount, ExecutionMode,
+    server::{CopyLayout, KernelArguments},
+};
+use cubecl_runtime::compiler::CompilationError;
+use wgpu::ComputePipeline;
+
+/// Copies contiguous elements to their position in a strided layout.
+///
+/// Elements whose size is a multiple of 4 bytes are copied by words. Smaller elements are copied
+/// byte by byte with atomics, since other bytes of the same word may be written by other units
+/// or belong to other data.
+///
+/// The source is declared `read_write` since it may be a slice of the same buffer as the
+/// destination, which can't be bound with different usages.
+const SCATTER_SHADER: &str = r#"
+@group(0) @binding(0)
+var<storage, read_write> src: array<u32>;
+
+@group(0) @binding(1)
+var<storage, read_write> dst: array<atomic<u32>>;
+
+// [rank, elem_size, num_elems, shape..., strides...]
+@group(0) @binding(2)
+var<storage, read> info: array<u32>;
+
+fn src_byte(offset: u32) -> u32 {
+    return (src[offset / 4u] >> ((offset % 4u) * 8u)) & 0xffu;
+}
+
+@compute @workgroup_size(64, 1, 1)
+fn scatter(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(num_workgroups) num_workgroups: vec3<u32>,
+) {
+    let rank = info[0];
+    let elem_size = info[1];
+    let index = global_id.y * num_workgroups.x * 64u + global_id.x;
+    if index >= info[2] {
+        return;
+    }
+
+    var remainder = index;
+    var offset = 0u;
+    for (var i = 0u; i < rank; i++) {
+        let dim = rank - 1u - i;
+        offset += (remainder % info[3u + dim]) * info[3u + rank + dim];
+        remainder /= info[3u + dim];
+    }
+
+    let src_start = index * elem_size;
+    let dst_start = offset * elem_size;
+    if elem_size % 4u == 0u {
+        for (var i = 0u; i < elem_size / 4u; i++) {
+            atomicStore(&dst[dst_start / 4u + i], src[src_start / 4u + i]);
+        }
+    } else {
+        for (var i = 0u; i < elem_size; i++) {
+            let dst_offset = dst_start + i;
+            let shift = (dst_offset % 4u) * 8u;
+            atomicAnd(&dst[dst_offset / 4u], ~(0xffu << shift));
+            atomicOr(&dst[dst_offset / 4u], src_byte(src_start + i) << shift);
+        }
+    }
+}
+"#;
+
+const SCATTER_ENTRYPOINT: &str = "scatter";
+const SCATTER_WORKGROUP_SIZE: u32 = 64;
+const MAX_WORKGROUPS_PER_DIM: u32 = 65535;
+
+impl WgpuServer {
+    /// The pipeline used to write non-contiguous tensors, created on first use.
+    pub(crate) fn scatter_pipeline(&mut self) -> Result<Arc<ComputePipeline>, CompilationError> {
+        if let Some(pipeline) = &self.scatter_pipeline {
+            return Ok(pipeline.clone());
+        }
+
+        let module = self.create_module(
+            SCATTER_ENTRYPOINT,
+            None,
+            SCATTER_SHADER,
+            ExecutionMode::Unchecked,
+        )?;
+        let pipeline = self.create_pipeline(
+            SCATTER_ENTRYPOINT,
+            None,
+            module,
+            &KernelArguments::default(),
+        );
+        self.scatter_pipeline = Some(pipeline.clone());
+
+        Ok(pipeline)
+    }
+}
+
+/// The metadata of the scatter shader, or `None` if the layout can't be addressed with `u32`.
+pub(crate) fn scatter_info(layout: &CopyLayout) -> Option<Vec<u32>> {
+    if u32::try_from(layout.span().max(layout.size())).is_err() {
+        return None;
+    }
+
+    let rank = layout.shape.len();
+    let mut info = Vec::with_capacity(3 + 2 * rank);
+    info.extend([rank, layout.elem_size, layout.num_elems()]);
+    info.extend(layout.shape.iter());
+    info.extend(layout.strides.iter());
+
+    // Every value is bounded by the span or the size, except the strides of dimensions of size
+    // 1 which are never used.
+    Some(info.into_iter().map(|value| value as u32).collect())
+}
+
+/// The number of workgroups needed to scatter `num_elems` elements.
+pub(crate) fn scatter_count(num_elems: usize) -> CubeCount {
+    let workgroups = (num_elems as u32).div_ceil(SCATTER_WORKGROUP_SIZE);
+    let x = workgroups.min(MAX_WORKGROUPS_PER_DIM);
+    CubeCount::Static(x, workgroups.div_ceil(x.max(1)), 1)
+}
@@ -1,4 +1,4 @@
-use crate::{WgpuResource, stream::WgpuStream};
+use crate::{WgpuResource, printf::KernelPrints, stream::WgpuStream};
 use alloc::sync::Arc;
 use cubecl_common::{bytes::Bytes, profile::TimingMethod};
 use cubecl_core::{
@@ -8,6 +8,7 @@ use cubecl_core::{
 use cubecl_ir::MemoryDeviceProperties;
 use cubecl_runtime::{
     logging::ServerLogger,
+    memory_management::ManagedMemoryHandle,
     stream::{StreamFactory, scheduler::SchedulerStreamBackend},
 };
 
@@ -20,6 +21,23 @@ pub enum ScheduleTask {
         /// The target buffer resource.
         buffer: WgpuResource,
     },
+    /// Represents a task to write data to the elements of a non-contiguous tensor.
+    Scatter {
+        /// The contiguous data to be written.
+        data: Bytes,
+        /// The temporary resource the data is uploaded to.
+        src: WgpuResource,
+        /// The memory of the temporary resource, held until the task is flushed.
+        src_memory: ManagedMemoryHandle,
+        /// The target buffer resource.
+        buffer: WgpuResource,
+        /// The metadata of the scatter shader.
+        info: Vec<u32>,
+        /// The number of workgroups to dispatch.
+        count: CubeCount,
+        /// The scatter pipeline.
+        pipeline: Arc<wgpu::ComputePipeline>,
+    },
     /// Represents a task to execute a compute pipeline.
     Execute {
         /// The compute pipeline to execute.
@@ -28,6 +46,8 @@ pub enum ScheduleTask {
         count: CubeCount,
         /// The resources (bindings) required for execution.
         resources: BindingsResource,
+        /// The prints of the kernel, which binds the printf buffer after its resources.
+        prints: Option<KernelPrints>,
     },
 }
 
@@ -35,6 +55,9 @@ impl core::fmt::Debug for ScheduleTask {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Write { data, .. } => f.write_fmt(format_args!("Write(bytes={})", data.len())),
+            Self::Scatter { data, .. } => {
+                f.write_fmt(format_args!("Scatter(bytes={})", data.len()))
+            }
             Self::Execute {
                 count, resources, ..
             } => f.write_fmt(format_args!(
@@ -70,6 +93,7 @@ pub struct WgpuStreamFactory {
     memory_config: MemoryConfiguration,
     timing_method: TimingMethod,
     tasks_max: usize,
+    printf_buffer_size: u64,
     logger: Arc<ServerLogger>,
     count: u64,
 }
@@ -87,6 +111,7 @@ impl StreamFactory for WgpuStreamFactory {
             self.memory_config.clone(),
             self.timing_method,
             self.tasks_max,
+            self.printf_buffer_size,
             self.logger.clone(),
         )
     }
@@ -94,6 +119,7 @@ impl StreamFactory for WgpuStreamFactory {
 
 impl ScheduledWgpuBackend {
     /// Creates a new `ScheduledWgpuBackend` with the given WGPU device, queue, and configurations.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         device: wgpu::Device,
         queue: wgpu::Queue,
@@ -101,6 +127,7 @@ impl ScheduledWgpuBackend {
         memory_config: MemoryConfiguration,
         timing_method: TimingMethod,
         tasks_max: usize,
+        printf_buffer_size: u64,
         logger: Arc<ServerLogger>,
     ) -> Self {
         Self {
@@ -111,6 +138,7 @@ impl ScheduledWgpuBackend {
                 memory_config,
                 timing_method,
                 tasks_max,
+                printf_buffer_size,
                 logger,
                 count: 0,
             },
@@ -1,3 +1,5 @@
+use super::printf::KernelPrints;
+use super::scatter::{scatter_count, scatter_info};
 use super::storage::{WgpuResource, WgpuStorage};
 use crate::schedule::{BindingsResource, ScheduleTask, ScheduledWgpuBackend};
 use crate::{AutoCompiler, AutoRepresentation};
@@ -9,22 +11,20 @@ use cubecl_common::{
     stream_id::StreamId,
 };
 use cubecl_core::server::{Binding, StreamErrorMode};
-use cubecl_core::zspace::Shape;
 use cubecl_core::{
     MemoryConfiguration, WgpuCompilationOptions,
     future::DynFut,
     prelude::*,
     server::{
-        CopyDescriptor, IoError, KernelArguments, LaunchError, ProfileError, ProfilingToken,
-        ResourceLimitError, ServerCommunication, ServerError, ServerUtilities,
+        CopyDescriptor, CopyLayout, IoError, KernelArguments, LaunchError, ProfileError,
+        ProfilingToken, ResourceLimitError, ServerCommunication, ServerError, ServerUtilities,
     },
-    zspace::{Strides, strides},
 };
-#[cfg(feature = "spirv")]
-use cubecl_core::{cache::CacheOption, compilation_cache::CompilationCache, hash::StableHash};
 use cubecl_ir::MemoryDeviceProperties;
 use cubecl_runtime::allocator::ContiguousMemoryLayoutPolicy;
-use cubecl_runtime::memory_management::{ManagedMemoryHandle, MemoryUsage};
+#[cfg(any(std_io, feature = "spirv"))]
+use cubecl_runtime::kernel_cache::KernelCache;
+use cubecl_runtime::memory_management::{ManagedMemoryHandle, MemoryTrace, MemoryUsage};
 use cubecl_runtime::{
     compiler::CubeTask,
     config::GlobalConfig,
@@ -45,10 +45,16 @@ pub struct WgpuServer {
     // A buffer that can be used to store stream id without extra allocations.
     streams_pool: Vec<StreamId>,
     pipelines: HashMap<KernelId, Arc<ComputePipeline>>,
+    /// The prints of the compiled kernels that print.
+    prints: HashMap<KernelId, KernelPrints>,
+    /// Pipeline used to write non-contiguous tensors, created on first use.
+    pub(crate) scatter_pipeline: Option<Arc<ComputePipeline>>,
     scheduler: SchedulerMultiStream<ScheduledWgpuBackend>,
     #[cfg(feature = "spirv")]
-    pub(crate) spirv_cache:
-        Option<CompilationCache<(u64, StableHash), cubecl_spirv::SpirvCacheEntry>>,
+    pub(crate) spirv_cache: Option<KernelCache<cubecl_spirv::SpirvCacheEntry>>,
+    /// The cache of the kernels compiled to WGSL or MSL.
+    #[cfg(std_io)]
+    pub(crate) source_cache: Option<KernelCache<crate::SourceCacheEntry>>,
     pub compilation_options: WgpuCompilationOptions,
     pub(crate) backend: wgpu::Backend,
     pub(crate) utilities: Arc<ServerUtilities<Self>>,
@@ -68,6 +74,7 @@ impl WgpuServer {
         device: wgpu::Device,
         queue: wgpu::Queue,
         tasks_max: usize,
+        printf_buffer_size: u64,
         backend: wgpu::Backend,
         timing_method: TimingMethod,
         utilities: ServerUtilities<Self>,
@@ -79,6 +86,7 @@ impl WgpuServer {
             memory_config,
             timing_method,
             tasks_max,
+            printf_buffer_size,
             utilities.logger.clone(),
         );
 
@@ -90,6 +98,8 @@ impl WgpuServer {
             streams_pool: Vec::new(),
             device,
             pipelines: HashMap::new(),
+            prints: HashMap::new(),
+            scatter_pipeline: None,
             scheduler: SchedulerMultiStream::new(
                 utilities.logger.clone(),
                 backend_scheduler,
@@ -100,17 +110,29 @@ impl WgpuServer {
                 },
             ),
             #[cfg(feature = "spirv")]
-            spirv_cache: {
-                let config = cubecl_runtime::config::GlobalConfig::get();
-                if let Some(cache) = &config.compilation.cache {
-                    let root = cache.root();
-                    Some(CompilationCache::new(
-                        "spirv",
-                        CacheOption::default().name("vulkan").root(root),
-                    ))
-                } else {
-                    None
-                }
+            spirv_cache: KernelCache::new(
+                "vulkan",
+                "spirv",
+                &compilation_options,
+                utilities.properties_hash,
+            ),
+            #[cfg(std_io)]
+            source_cache: match compiler(backend, &compilation_options) {
+                AutoCompiler::Wgsl(_) => KernelCache::new(
+                    "wgpu",
+                    "wgsl",
+                    &compilation_options,
+                    utilities.properties_hash,
+                ),
+                #[cfg(feature = "msl")]
+                AutoCompiler::Msl(_) => KernelCache::new(
+                    "metal",
+                    "msl",
+                    &compilation_options,
+                    utilities.properties_hash,
+                ),
+                #[cfg(feature = "spirv")]
+                AutoCompiler::SpirV(_) => None,
             },
             backend,
             utilities: Arc::new(utilities),
@@ -134,24 +156,25 @@ impl WgpuServer {
         })
     }
 
+    /// Get the pipeline of a kernel, along with its prints if it has any.
     fn pipeline(
         &mut self,
         kernel: <Self as ComputeServer>::Kernel,
         bindings: &KernelArguments,
         mode: ExecutionMode,
-    ) -> Result<Arc<ComputePipeline>, LaunchError> {
+    ) -> Result<(Arc<ComputePipeline>, Option<KernelPrints>), LaunchError> {
         let mut kernel_id = kernel.id();
         kernel_id.mode(mode);
 
         if let Some(pipeline) = self.pipelines.get(&kernel_id) {
-            return Ok(pipeline.clone());
+            return Ok((pipeline.clone(), self.prints.get(&kernel_id).cloned()));
         }
 
         let cached = self.load_cached_pipeline(&kernel_id, bindings, mode)?;
 
         if let Some(Ok(pipeline)) = cached {
             self.pipelines.insert(kernel_id, pipeline.clone());
-            return Ok(pipeline);
+            return Ok((pipeline, None));
         }
 
         validate_cube_dim(&self.utilities.properties, &kernel_id)?;
@@ -198,21 +221,38 @@ impl WgpuServer {
         let pipeline = self.create_pipeline(&compiled.entrypoint_name, repr, module, bindings);
         self.pipelines.insert(kernel_id.clone(), pipeline.clone());
 
+        // Only WGSL kernels print to the printf buffer, SPIR-V uses the validation layers.
+        let prints = match &compiled.repr {
+            Some(AutoRepresentation::Wgsl(shader)) if !shader.prints.is_empty() => {
+                let prints: KernelPrints = shader.prints.clone().into();
+                self.prints.insert(kernel_id.clone(), prints.clone());
+                Some(prints)
+            }
+            _ => None,
+        };
+
+        #[cfg(std_io)]
+        if let Some(Err(key)) = cached
+            && let Some(cache) = self.source_cache.as_mut()
+            && let Some(entry) = repr.and_then(|repr| {
+                crate::SourceCacheEntry::new(&compiled.entrypoint_name, &compiled.source, repr)
+            })
+        {
+            cache.insert(key, entry);
+        }
+
         #[cfg(feature = "spirv")]
         if let Some(Err(key)) = cached
             && let Some(crate::AutoRepresentation::SpirV(kernel)) = compiled.repr
         {
             let cache = self.spirv_cache.as_mut().unwrap();
-            let result = cache.insert(
+            cache.insert(
                 key,
                 cubecl_spirv::SpirvCacheEntry::new(compiled.entrypoint_name, kernel),
             );
-            if let Err(err) = result {
-                log::warn!("Unable to save the SPIR-V {err:?}");
-            }
         }
 
-        Ok(pipeline)
+        Ok((pipeline, prints))
     }
 
     fn validate_shared(&self, repr: &Option<crate::AutoRepresentation>) -> Result<(), LaunchError> {
@@ -253,16 +293,9 @@ impl ComputeServer for WgpuServer {
         self.utilities.clone()
     }
 
-    fn staging(
-        &mut self,
-        _sizes: &[usize],
-        _stream_id: StreamId,
-    ) -> Result<Vec<Bytes>, ServerError> {
-        // TODO: Check if using a staging buffer is useful here.
-        Err(IoError::UnsupportedIoOperation {
-            backtrace: BackTrace::capture(),
-        }
-        .into())
+    fn staging(&mut self, sizes: &[usize], stream_id: StreamId) -> Result<Vec<Bytes>, ServerError> {
+        let stream = self.scheduler.stream(&stream_id);
+        Ok(sizes.iter().map(|size| stream.staging(*size)).collect())
     }
 
     fn initialize_memory(&mut self, memory: ManagedMemoryHandle, size: u64, stream_id: StreamId) {
@@ -279,14 +312,6 @@ impl ComputeServer for WgpuServer {
         let mut streams = vec![stream_id];
         let mut resources = Vec::with_capacity(descriptors.len());
         for desc in descriptors {
-            if contiguous_strides(&desc.shape) != desc.strides {
-                return Box::pin(async {
-                    Err(IoError::UnsupportedStrides {
-                        backtrace: BackTrace::capture(),
-                    }
-                    .into())
-                });
-            }
             if !streams.contains(&desc.handle.stream) {
                 streams.push(desc.handle.stream);
             }
@@ -295,7 +320,17 @@ impl ComputeServer for WgpuServer {
                 Ok(val) => val,
                 Err(err) => return Box::pin(async move { Err(err.into()) }),
             };
-            resources.push((resource, desc.shape, desc.elem_size));
+            // Non-contiguous tensors are gathered on the host after the transfer.
+            let layout = CopyLayout::new(desc.shape, desc.strides, desc.elem_size);
+            if layout.span() as u64 > resource.size {
+                return Box::pin(async {
+                    Err(IoError::UnsupportedStrides {
+                        backtrace: BackTrace::capture(),
+                    }
+                    .into())
+                });
+            }
+            resources.push((resource, layout));
         }
 
         self.scheduler.execute_streams(streams);
@@ -306,10 +341,13 @@ impl ComputeServer for WgpuServer {
 
     fn write(&mut self, descriptors: Vec<(CopyDescriptor, Bytes)>, stream_id: StreamId) {
         for (desc, data) in descriptors {
+            let layout = CopyLayout::new(desc.shape, desc.strides, desc.elem_size);
             let stream = self.scheduler.stream(&desc.handle.stream);
 
-            if contiguous_strides(&desc.shape) != desc.strides {
-                stream.error(ServerError::Io(IoError::UnsupportedStrides {
+            if data.len() != layout.size() {
+                stream.error(ServerError::Io(IoError::InvalidSize {
+                    expected: layout.size(),
+                    actual: data.len(),
                     backtrace: BackTrace::capture(),
                 }));
                 return;
@@ -322,9 +360,52 @@ impl ComputeServer for WgpuServer {
                     return;
                 }
             };
-            let task = ScheduleTask::Write {
-                data,
-                buffer: resource,
+
+            let task = if layout.is_contiguous() {
+                ScheduleTask::Write {
+                    data,
+                    buffer: resource,
+                }
+            } else {
+                // Non-contiguous tensors are scattered on the device, so the bytes between
+                // elements are left unchanged.
+                let info = match scatter_info(&layout) {
+                    Some(info) if layout.span() as u64 <= resource.size => info,
+                    _ => {
+                        stream.error(ServerError::Io(IoError::UnsupportedStrides {
+                            backtrace: BackTrace::capture(),
+                        }));
+                        return;
+                    }
+                };
+                let pipeline = match self.scatter_pipeline() {
+                    Ok(pipeline) => pipeline,
+                    Err(err) => {
+                        let stream = self.scheduler.stream(&stream_id);
+                        stream.error(ServerError::Launch(err.into()));
+                        return;
+                    }
+                };
+                // The upload memory is reserved now, so it can't alias memory that is freed
+                // before the task is executed.
+                let stream = self.scheduler.stream(&stream_id);
+                let (src, src_memory) = match stream.mem_manage.reserve_temporary(data.len() as u64)
+                {
+                    Ok(src) => src,
+                    Err(err) => {
+                        stream.error(ServerError::Io(err));
+                        return;
+                    }
+                };
+                ScheduleTask::Scatter {
+                    data,
+                    src,
+                    src_memory,
+                    buffer: resource,
+                    info,
+                    count: scatter_count(layout.num_elems()),
+                    pipeline,
+                }
             };
 
             self.scheduler.register(stream_id, task, &[]);
@@ -356,7 +437,8 @@ impl ComputeServer for WgpuServer {
         mode: ExecutionMode,
         stream_id: StreamId,
     ) {
-        let pipeline = match self.pipeline(kernel, &args, mode) {
+        let kernel_name = kernel.name();
+        let (pipeline, prints) = match self.pipeline(kernel, &args, mode) {
             Ok(val) => val,
             Err(err) => {
                 // We make the stream that would execute the kernel in error.
@@ -384,8 +466,11 @@ impl ComputeServer for WgpuServer {
             pipeline,
             count,
             resources,
+            prints,
         };
 
+        let stream = self.scheduler.stream(&stream_id);
+        stream.mem_manage.trace_launch(kernel_name);
         self.scheduler.register(stream_id, task, &self.streams_pool);
     }
 
@@ -437,6 +522,12 @@ impl ComputeServer for WgpuServer {
         stream.mem_manage.memory_cleanup(true);
     }
 
+    fn memory_trace(&mut self, stream_id: StreamId) -> Result<MemoryTrace, ServerError> {
+        self.scheduler.execute_streams(vec![stream_id]);
+        let stream = self.scheduler.stream(&stream_id);
+        Ok(stream.mem_manage.memory_trace())
+    }
+
     fn allocation_mode(&mut self, mode: MemoryAllocationMode, stream_id: StreamId) {
         self.scheduler.execute_streams(vec![stream_id]);
         let stream = self.scheduler.stream(&stream_id);
@@ -454,12 +545,3 @@ fn compiler(backend: wgpu::Backend, options: &WgpuCompilationOptions) -> AutoCom
         _ => AutoCompiler::Wgsl(Default::default()),
     }
 }
-
-pub(crate) fn contiguous_strides(shape: &Shape) -> Strides {
-    let rank = shape.len();
-    let mut strides = strides![1; rank];
-    for i in (0..rank - 1).rev() {
-        strides[i] = strides[i + 1] * shape[i + 1];
-    }
-    strides
-}
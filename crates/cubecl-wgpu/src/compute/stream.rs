@@ -1,5 +1,11 @@
-use super::{mem_manager::WgpuMemManager, poll::WgpuPoll, timings::QueryProfiler};
-use crate::{WgpuResource, controller::WgpuAllocController, schedule::ScheduleTask};
+use super::{
+    mem_manager::WgpuMemManager, poll::WgpuPoll, printf::PrintfBuffer, timings::QueryProfiler,
+};
+use crate::{
+    WgpuResource,
+    controller::{WgpuAllocController, WgpuUploadController},
+    schedule::ScheduleTask,
+};
 use cubecl_common::{
     backtrace::BackTrace,
     bytes::Bytes,
@@ -8,8 +14,7 @@ use cubecl_common::{
 use cubecl_core::{
     CubeCount, MemoryConfiguration,
     future::{self, DynFut},
-    server::{IoError, ProfileError, ProfilingToken, ServerError, StreamErrorMode},
-    zspace::Shape,
+    server::{CopyLayout, IoError, ProfileError, ProfilingToken, ServerError, StreamErrorMode},
 };
 use cubecl_ir::MemoryDeviceProperties;
 use cubecl_runtime::{
@@ -42,10 +47,14 @@ pub struct WgpuStream {
     /// Used to prevent wgpu staging buffer pool exhaustion during bulk writes
     /// (e.g. model loading with hundreds of tensors).
     pending_write_count: usize,
+    /// The buffer prints are appended to, created when the first kernel that prints is executed.
+    printf: Option<PrintfBuffer>,
+    printf_buffer_size: u64,
 }
 
 impl WgpuStream {
     /// Creates a new WGPU stream.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         device: wgpu::Device,
         queue: wgpu::Queue,
@@ -53,6 +62,7 @@ impl WgpuStream {
         memory_config: MemoryConfiguration,
         timing_method: TimingMethod,
         tasks_max: usize,
+        printf_buffer_size: u64,
         logger: Arc<ServerLogger>,
     ) -> Self {
         let timings = if timing_method == TimingMethod::Device {
@@ -91,6 +101,8 @@ impl WgpuStream {
             poll,
             submission_load: SubmissionLoad::default(),
             pending_write_count: 0,
+            printf: None,
+            printf_buffer_size,
         }
     }
 
@@ -101,7 +113,11 @@ impl WgpuStream {
     /// * `task` - The task to execute.
     pub fn enqueue_task(&mut self, task: ScheduleTask) {
         match task {
-            ScheduleTask::Write { data, buffer } => {
+            ScheduleTask::Write { mut data, buffer } => {
+                if let Some(upload) = data.controller_mut::<WgpuUploadController>() {
+                    self.copy_from_upload(upload, &buffer);
+                    return;
+                }
                 // It is important to flush before writing, as the write operation is inserted
                 // into the QUEUE not the encoder. We want to make sure all outstanding work
                 // happens _before_ the write operation.
@@ -113,12 +129,35 @@ impl WgpuStream {
                     .ok();
                 self.write_to_buffer(&buffer, &data);
             }
+            ScheduleTask::Scatter {
+                data,
+                src,
+                src_memory,
+                buffer,
+                info,
+                count,
+                pipeline,
+            } => {
+                // The data is uploaded to temporary memory, which is then scattered by a kernel
+                // ordered with the other tasks of the stream.
+                self.mem_manage.hold_temporary(src_memory);
+                self.write_to_buffer(&src, &data);
+                let info = self.create_uniform(bytemuck::cast_slice(&info));
+                self.register_pipeline(pipeline, [src, buffer, info].iter(), &count);
+            }
             ScheduleTask::Execute {
                 pipeline,
                 count,
                 resources,
+                prints,
             } => {
-                let resources = resources.into_resources(self);
+                let mut resources = resources.into_resources(self);
+                if let Some(prints) = prints {
+                    let printf = self.printf.get_or_insert_with(|| {
+                        PrintfBuffer::new(&self.device, self.printf_buffer_size)
+                    });
+                    resources.push(printf.bind(&prints));
+                }
                 self.register_pipeline(pipeline, resources.iter(), &count);
             }
         }
@@ -136,15 +175,13 @@ impl WgpuStream {
     /// A [Result] containing a vector of [Bytes] with the copied data, or an [`IoError`] if any copy fails.
     pub fn read_resources(
         &mut self,
-        descriptors: Vec<(WgpuResource, Shape, usize)>,
+        descriptors: Vec<(WgpuResource, CopyLayout)>,
     ) -> DynFut<Result<Vec<Bytes>, ServerError>> {
         self.compute_pass = None;
         let mut staging_info = Vec::with_capacity(descriptors.len());
         let mut callbacks = Vec::with_capacity(descriptors.len());
 
-        for (resource, shape, elem_size) in descriptors {
-            let size = shape.iter().product::<usize>() * elem_size;
-
+        for (resource, layout) in descriptors {
             // Zero-sized resources don't need a GPU copy.
             if resource.size == 0 {
                 staging_info.push(None);
@@ -165,7 +202,7 @@ impl WgpuStream {
                 0,
                 aligned_len,
             );
-            staging_info.push(Some((staging, binding, size)));
+            staging_info.push(Some((staging, binding, layout)));
         }
 
         // Flush all commands to the queue, so GPU gets started on copying to the staging buffer.
@@ -212,11 +249,18 @@ impl WgpuStream {
                 staging_info
                     .into_iter()
                     .map(|entry| {
-                        if let Some((staging, binding, size)) = entry {
+                        if let Some((staging, binding, layout)) = entry {
                             let controller =
                                 Box::new(WgpuAllocController::init(binding, staging.buffer));
-                            // SAFETY: The binding has initialized memory for at least `size` bytes.
-                            unsafe { Bytes::from_controller(controller, size) }
+                            if layout.is_contiguous() {
+                                // SAFETY: The binding has initialized memory for at least `size` bytes.
+                                unsafe { Bytes::from_controller(controller, layout.size()) }
+                            } else {
+                                // SAFETY: The binding has initialized memory for at least `span` bytes.
+                                let bytes =
+                                    unsafe { Bytes::from_controller(controller, layout.span()) };
+                                Bytes::from_bytes_vec(layout.gather(&bytes))
+                            }
                         } else {
                             Bytes::from_bytes_vec(vec![])
                         }
@@ -368,6 +412,25 @@ impl WgpuStream {
         self.mem_manage.reserve(size)
     }
 
+    /// Allocates an upload buffer of the given size, mapped so the returned [Bytes] can be
+    /// written to directly.
+    ///
+    /// Writing those bytes to a buffer copies them on the device instead of going through
+    /// `queue.write_buffer`.
+    pub fn staging(&mut self, size: usize) -> Bytes {
+        let align = wgpu::COPY_BUFFER_ALIGNMENT;
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("CubeCL Upload Buffer"),
+            size: (size as u64).next_multiple_of(align).max(align),
+            usage: wgpu::BufferUsages::MAP_WRITE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: true,
+        });
+        let controller = Box::new(WgpuUploadController::init(buffer));
+
+        // SAFETY: Buffers mapped at creation are zero initialized.
+        unsafe { Bytes::from_controller(controller, size) }
+    }
+
     /// Registers a new error into the error sink.
     pub fn error(&mut self, error: ServerError) {
         self.errors.push(error);
@@ -379,6 +442,29 @@ impl WgpuStream {
         resource
     }
 
+    /// Copy the content of an upload buffer to a resource.
+    ///
+    /// Unlike [`Self::write_to_buffer`], the copy is recorded in the encoder, so it is ordered
+    /// with the other tasks of the stream.
+    fn copy_from_upload(&mut self, upload: &mut WgpuUploadController, resource: &WgpuResource) {
+        // Nothing to write for zero-sized resources.
+        if resource.size == 0 {
+            return;
+        }
+
+        let src = upload.unmap();
+        let size = resource
+            .size
+            .next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT)
+            .min(src.size());
+
+        self.compute_pass = None;
+        self.tasks_count += 1;
+        self.encoder
+            .copy_buffer_to_buffer(src, 0, &resource.buffer, resource.offset, size);
+        self.flush_if_needed();
+    }
+
     // Nb: this function submits a command to the _queue_ not to the encoder,
     // so you have to be really careful about the ordering of operations here.
     // Any buffer which has outstanding (not yet flushed) compute work should
@@ -483,9 +569,20 @@ impl WgpuStream {
             })
         };
 
+        // The prints are copied once all tasks are done.
+        let mut tasks_encoder = tasks_encoder;
+        let printf_records = self
+            .printf
+            .as_mut()
+            .and_then(|printf| printf.copy_records(&self.device, &mut tasks_encoder));
+
         // This will _first_ fire off all pending write_buffer work.
         let index = self.queue.submit([tasks_encoder.finish()]);
 
+        if let (Some(printf), Some(records)) = (&self.printf, printf_records) {
+            printf.print_records(&self.device, records, index.clone());
+        }
+
         self.submission_load
             .regulate(&self.device, self.tasks_count, index);
 
@@ -1,19 +1,16 @@
-use crate::{
-    AutoCompiler, AutoGraphicsApi, GraphicsApi, WgpuDevice, backend, compute::WgpuServer,
-    contiguous_strides,
-};
+use crate::{AutoCompiler, AutoGraphicsApi, GraphicsApi, WgpuDevice, backend, compute::WgpuServer};
 use cubecl_common::device::{Device, DeviceService};
 use cubecl_common::{future, profile::TimingMethod};
 use cubecl_core::device::{DeviceId, ServerUtilitiesHandle};
 use cubecl_core::server::ServerUtilities;
 use cubecl_core::zspace::{Shape, Strides};
-use cubecl_core::{Runtime, ir::TargetProperties};
+use cubecl_core::{Runtime, WgpuCompilationOptions, ir::TargetProperties};
 use cubecl_ir::{DeviceProperties, HardwareProperties, MemoryDeviceProperties};
 use cubecl_runtime::allocator::ContiguousMemoryLayoutPolicy;
 #[cfg(not(feature = "vulkan-validate"))]
 use cubecl_runtime::logging::ProfileLevel;
 pub use cubecl_runtime::memory_management::MemoryConfiguration;
-use cubecl_runtime::{client::ComputeClient, logging::ServerLogger};
+use cubecl_runtime::{client::ComputeClient, config::GlobalConfig, logging::ServerLogger};
 use wgpu::{InstanceFlags, RequestAdapterOptions};
 
 /// Runtime that uses the [wgpu] crate with the wgsl compiler. This is used in the Wgpu backend.
@@ -68,17 +65,8 @@ impl Runtime for WgpuRuntime {
         (max_dim, max_dim, max_dim)
     }
 
-    fn can_read_tensor(shape: &Shape, strides: &Strides) -> bool {
-        if shape.is_empty() {
-            return true;
-        }
-
-        for (&expected, &stride) in contiguous_strides(shape).iter().zip(strides.iter()) {
-            if expected != stride {
-                return false;
-            }
-        }
-
+    fn can_read_tensor(_shape: &Shape, _strides: &Strides) -> bool {
+        // Non-contiguous tensors are gathered on the host after the transfer.
         true
     }
 
@@ -180,6 +168,9 @@ pub struct RuntimeOptions {
     pub tasks_max: usize,
     /// Configures the memory management.
     pub memory_config: MemoryConfiguration,
+    /// The size in bytes of the buffer WGSL kernels append their prints to. Prints that don't fit
+    /// are dropped with a warning.
+    pub printf_buffer_size: usize,
 }
 
 impl Default for RuntimeOptions {
@@ -188,6 +179,7 @@ impl Default for RuntimeOptions {
         const DEFAULT_MAX_TASKS: usize = 32;
         #[cfg(not(test))]
         const DEFAULT_MAX_TASKS: usize = 32;
+        const DEFAULT_PRINTF_BUFFER_SIZE: usize = 1024 * 1024;
 
         let tasks_max = match std::env::var("CUBECL_WGPU_MAX_TASKS") {
             Ok(value) => value
@@ -196,9 +188,17 @@ impl Default for RuntimeOptions {
             Err(_) => DEFAULT_MAX_TASKS,
         };
 
+        let printf_buffer_size = match std::env::var("CUBECL_WGPU_PRINTF_BUFFER_SIZE") {
+            Ok(value) => value
+                .parse::<usize>()
+                .expect("CUBECL_WGPU_PRINTF_BUFFER_SIZE should be a positive integer."),
+            Err(_) => DEFAULT_PRINTF_BUFFER_SIZE,
+        };
+
         Self {
             tasks_max,
             memory_config: MemoryConfiguration::default(),
+            printf_buffer_size,
         }
     }
 }
@@ -326,7 +326,11 @@ pub(crate) fn create_server(setup: WgpuSetup, options: RuntimeOptions) -> WgpuSe
         max_vector_size: 4,
     };
 
-    let mut compilation_options = Default::default();
+    let mut compilation_options = WgpuCompilationOptions {
+        optimize: GlobalConfig::get().compilation.optimize,
+        unroll_loops: GlobalConfig::get().compilation.unroll_loops,
+        ..Default::default()
+    };
 
     let features = setup.adapter.features();
 
@@ -377,6 +381,7 @@ pub(crate) fn create_server(setup: WgpuSetup, options: RuntimeOptions) -> WgpuSe
         setup.device.clone(),
         setup.queue,
         options.tasks_max,
+        options.printf_buffer_size as u64,
         setup.backend,
         time_measurement,
         ServerUtilities::new(device_props, logger, setup.backend, allocator),